@@ -0,0 +1,95 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::CurrentNetwork;
+use snarkos_node::bft::helpers::ConsensusSnapshot;
+
+use anyhow::{bail, Result};
+use clap::Parser;
+use std::path::PathBuf;
+
+/// Pretty-prints or dumps a consensus state snapshot, as returned by `GET /testnet3/debug/consensusState`.
+#[derive(Debug, Parser)]
+pub struct ConsensusState {
+    /// The path to the file containing the JSON-encoded consensus state snapshot.
+    #[clap(short, long, conflicts_with = "endpoint")]
+    pub file: Option<PathBuf>,
+    /// The REST endpoint of a live validator to fetch the snapshot from (e.g. "http://127.0.0.1:3033").
+    #[clap(short, long)]
+    pub endpoint: Option<String>,
+    /// The JWT to authenticate with the REST endpoint of the validator.
+    #[clap(long, requires = "endpoint")]
+    pub jwt: Option<String>,
+    /// Dumps the JSON-encoded snapshot, instead of pretty-printing it.
+    #[clap(long)]
+    pub json: bool,
+}
+
+impl ConsensusState {
+    pub fn parse(self) -> Result<String> {
+        // Retrieve the snapshot.
+        let snapshot = match (&self.file, &self.endpoint) {
+            // Read the snapshot from the file.
+            (Some(file), None) => std::fs::read_to_string(file)?,
+            // Fetch the snapshot from the validator.
+            (None, Some(endpoint)) => Self::fetch(endpoint, self.jwt.as_deref())?,
+            _ => bail!("Please specify either a '--file' or an '--endpoint' for the consensus state snapshot"),
+        };
+        // Dump or pretty-print the snapshot.
+        match self.json {
+            true => Ok(snapshot),
+            false => Self::pretty_print(&snapshot),
+        }
+    }
+
+    /// Fetches the JSON-encoded consensus state snapshot from the REST endpoint of a validator.
+    fn fetch(endpoint: &str, jwt: Option<&str>) -> Result<String> {
+        let mut request = ureq::get(&format!("{}/testnet3/debug/consensusState", endpoint.trim_end_matches('/')));
+        if let Some(jwt) = jwt {
+            request = request.set("Authorization", &format!("Bearer {jwt}"));
+        }
+        match request.call() {
+            Ok(response) => Ok(response.into_string()?),
+            Err(ureq::Error::Status(code, response)) => {
+                bail!("Failed to fetch the consensus state ({code}) - {}", response.into_string().unwrap_or_default())
+            }
+            Err(error) => bail!("Failed to fetch the consensus state - {error}"),
+        }
+    }
+
+    /// Parses the JSON-encoded consensus state snapshot, and returns it in a human-readable format.
+    fn pretty_print(snapshot: &str) -> Result<String> {
+        // Deserialize the snapshot.
+        let snapshot = serde_json::from_str::<ConsensusSnapshot<CurrentNetwork>>(snapshot)?;
+        // Return the pretty-printed snapshot.
+        Ok(snapshot.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_requires_a_source() {
+        let consensus_state = ConsensusState { file: None, endpoint: None, jwt: None, json: false };
+        assert!(consensus_state.parse().is_err());
+    }
+
+    #[test]
+    fn test_pretty_print_invalid_snapshot() {
+        assert!(ConsensusState::pretty_print("").is_err());
+        assert!(ConsensusState::pretty_print("{\"round\": 1}").is_err());
+    }
+}
@@ -0,0 +1,417 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node::bft::storage_service::RocksStorage;
+use snarkos_node_storage::ReplayPlan;
+use snarkvm::prelude::{block::Block, store::helpers::rocksdb::ConsensusDB, Address, FromBytes, Network};
+
+use anyhow::{bail, ensure, Result};
+use clap::Parser;
+use colored::Colorize;
+use std::{
+    ffi::OsString,
+    fs,
+    io,
+    path::{Path, PathBuf},
+    process::Command,
+    str::FromStr,
+};
+
+type CurrentNetwork = snarkvm::prelude::Testnet3;
+type CurrentLedger = snarkvm::ledger::Ledger<CurrentNetwork, ConsensusDB<CurrentNetwork>>;
+
+/// Commands to export, import, check, revert, and rebuild the ledger, and to read its address index.
+#[derive(Debug, Parser)]
+pub enum Ledger {
+    /// Exports a snapshot of the ledger from a running node, as returned by `GET /testnet3/snapshot`.
+    Export {
+        /// Specify the height of the trusted checkpoint of the snapshot
+        #[clap(long)]
+        height: u32,
+        /// Specify the path to write the snapshot archive to
+        #[clap(short, long)]
+        output: PathBuf,
+        /// Specify the REST endpoint of the running node
+        #[clap(short, long, default_value = "http://127.0.0.1:3030")]
+        endpoint: String,
+        /// Specify the JWT to authenticate with the REST endpoint of the node
+        #[clap(long)]
+        jwt: Option<String>,
+    },
+    /// Imports a snapshot of the ledger, before the node is started for the first time.
+    Import {
+        /// Specify the path to the snapshot archive
+        #[clap(short, long)]
+        input: PathBuf,
+        /// Specify the trusted checkpoint that the snapshot must match, as '<height>:<block hash>'
+        #[clap(long)]
+        checkpoint: Option<String>,
+        /// Enables development mode, specify the unique ID of the local node to import the snapshot for
+        #[clap(long)]
+        dev: Option<u16>,
+    },
+    /// Checks the integrity of the blocks and indexes of the ledger, while the node is stopped.
+    Check {
+        /// Specify the first block height to check (default: 0)
+        #[clap(long)]
+        start: Option<u32>,
+        /// Specify the block height to stop checking at, exclusive (default: the latest block height + 1)
+        #[clap(long)]
+        end: Option<u32>,
+        /// If the flag is set, the recoverable index corruption is repaired
+        #[clap(long)]
+        repair: bool,
+        /// Enables development mode, specify the unique ID of the local node to check
+        #[clap(long)]
+        dev: Option<u16>,
+    },
+    /// Reverts the ledger to the given block height, while the node is stopped, by replaying its blocks into a staged
+    /// ledger, from genesis or from the latest backup at or below the height, and swapping the staged ledger in.
+    Revert {
+        /// Specify the block height to revert the ledger to
+        #[clap(long)]
+        height: u32,
+        /// Specify the directory of the ledger backups to replay the blocks from
+        #[clap(long)]
+        backup_dir: Option<PathBuf>,
+        /// Enables development mode, specify the unique ID of the local node to revert
+        #[clap(long)]
+        dev: Option<u16>,
+    },
+    /// Rebuilds the block tree of the ledger from its stored block hashes, repairing them, while the node is stopped.
+    Rebuild {
+        /// If the flag is set, the ledger is rebuilt from its blocks instead, which rebuilds its state as well
+        #[clap(long)]
+        replay: bool,
+        /// Enables development mode, specify the unique ID of the local node to rebuild
+        #[clap(long)]
+        dev: Option<u16>,
+    },
+    /// Prints the history of an address from the address index, without locking it, so the node may be running.
+    History {
+        /// Specify the address to print the history of
+        address: Address<CurrentNetwork>,
+        /// Specify the number of the most recent transitions to skip
+        #[clap(long, default_value = "0")]
+        offset: usize,
+        /// Specify the maximum number of transitions to print
+        #[clap(long, default_value = "20")]
+        limit: usize,
+        /// Enables development mode, specify the unique ID of the local node to read the address index of
+        #[clap(long)]
+        dev: Option<u16>,
+    },
+    /// Writes the blocks of a staged revert or rebuild into a replay file (run by `revert` and `rebuild`).
+    #[clap(hide = true)]
+    StageExport {
+        #[clap(long)]
+        start: u32,
+        #[clap(long)]
+        height: Option<u32>,
+        #[clap(long)]
+        output: PathBuf,
+        #[clap(long)]
+        dev: Option<u16>,
+    },
+    /// Replays the blocks of a replay file into a staged ledger (run by `revert` and `rebuild`).
+    #[clap(hide = true)]
+    StageReplay {
+        #[clap(long)]
+        input: PathBuf,
+        #[clap(long)]
+        staging: PathBuf,
+    },
+}
+
+impl Ledger {
+    pub fn parse(self) -> Result<String> {
+        match self {
+            Self::Export { height, output, endpoint, jwt } => Self::export(height, output, &endpoint, jwt.as_deref()),
+            Self::Import { input, checkpoint, dev } => {
+                // Parse the trusted checkpoint, if one is given.
+                let checkpoint = checkpoint.as_deref().map(Self::parse_checkpoint::<CurrentNetwork>).transpose()?;
+                // Import the snapshot into the ledger directory.
+                let ledger_dir = aleo_std::aleo_ledger_dir(CurrentNetwork::ID, dev);
+                let manifest =
+                    snarkos_node_storage::import_snapshot::<CurrentNetwork>(&input, &ledger_dir, checkpoint)?;
+                Ok(format!(
+                    "✅ Imported the ledger snapshot at block {} ({}) {}",
+                    manifest.height,
+                    manifest.block_hash,
+                    format!("(in \"{}\")", ledger_dir.display()).dimmed()
+                ))
+            }
+            Self::Check { start, end, repair, dev } => Self::check(start, end, repair, dev),
+            Self::Revert { height, backup_dir, dev } => Self::revert(height, backup_dir, dev),
+            Self::Rebuild { replay, dev } => Self::rebuild(replay, dev),
+            Self::History { address, offset, limit, dev } => Self::history(address, offset, limit, dev),
+            Self::StageExport { start, height, output, dev } => {
+                let genesis = Block::from_bytes_le(CurrentNetwork::genesis_bytes())?;
+                snarkos_node_storage::export_replay(genesis, dev, start, height, &output)?;
+                Ok(String::new())
+            }
+            Self::StageReplay { input, staging } => {
+                let genesis = Block::from_bytes_le(CurrentNetwork::genesis_bytes())?;
+                snarkos_node_storage::replay_staged_ledger(genesis, &staging, &input)?;
+                // Record the schema version of the staged ledger, which the ledger is swapped to.
+                let staged_dev = Some(snarkos_node_storage::STAGED_LEDGER_DEV_ID);
+                snarkos_node::record_schema_version::<CurrentNetwork>(staged_dev)?;
+                Ok(String::new())
+            }
+        }
+    }
+
+    /// Prints a page of the history of the given address from the address index of the node.
+    fn history(address: Address<CurrentNetwork>, offset: usize, limit: usize, dev: Option<u16>) -> Result<String> {
+        let index_dir = aleo_std::aleo_ledger_dir(CurrentNetwork::ID, dev).with_extension("address-index");
+        ensure!(index_dir.exists(), "No address index was found (in \"{}\")", index_dir.display());
+        // Open the index as a secondary instance, which reads the latest writes of a running node without its lock.
+        let secondary_dir = std::env::temp_dir().join(format!("snarkos-address-index-{}", std::process::id()));
+        let storage = RocksStorage::open_secondary(&index_dir, &secondary_dir)?;
+        let result = storage.try_catch_up().and_then(|_| {
+            let index = snarkos_node_storage::AddressIndex::new(Box::new(storage));
+            Ok((index.indexed_height()?, index.history(&address, offset, limit)?))
+        });
+        // Remove the logs of the secondary instance, which are only used by this command.
+        let _ = fs::remove_dir_all(&secondary_dir);
+        let (indexed_height, history) = result?;
+
+        // Print the transitions, from the most recent to the oldest.
+        for activity in &history {
+            let transition_id = activity.transition_id.to_string();
+            println!("{} {} ({})", activity.height, activity.transaction_id, transition_id.dimmed());
+        }
+        match indexed_height {
+            Some(height) => {
+                Ok(format!("✅ Found {} transitions of {address} (indexed up to block {height})", history.len()))
+            }
+            None => Ok(format!("✅ The address index is empty (in \"{}\")", index_dir.display())),
+        }
+    }
+
+    /// Reverts the ledger to the given block height.
+    fn revert(height: u32, backup_dir: Option<PathBuf>, dev: Option<u16>) -> Result<String> {
+        let (plan, previous_dir) = Self::replay("revert", Some(height), backup_dir.as_deref(), dev)?;
+        // Remove the address index, as it indexes the removed blocks, and is rebuilt by the node.
+        let address_index_dir = aleo_std::aleo_ledger_dir(CurrentNetwork::ID, dev).with_extension("address-index");
+        if address_index_dir.exists() {
+            fs::remove_dir_all(address_index_dir)?;
+        }
+
+        // Print the removed blocks, from the latest to the oldest.
+        // Note: The node is stopped, so the removed blocks are reported here rather than as reorg events.
+        for (removed_height, hash) in &plan.removed {
+            println!("⏪ Removed block {removed_height} ({hash})");
+        }
+        Ok(format!(
+            "✅ Reverted the ledger to block {} ({}) {}",
+            plan.height,
+            plan.block_hash,
+            format!("(the previous ledger is in \"{}\")", previous_dir.display()).dimmed()
+        ))
+    }
+
+    /// Rebuilds the block tree of the ledger from its stored block hashes, or the ledger from its blocks.
+    fn rebuild(replay: bool, dev: Option<u16>) -> Result<String> {
+        if !replay {
+            // Ensure the ledger exists, as loading a missing ledger would initialize a new one.
+            let ledger_dir = aleo_std::aleo_ledger_dir(CurrentNetwork::ID, dev);
+            ensure!(ledger_dir.exists(), "No ledger was found (in \"{}\")", ledger_dir.display());
+            let genesis = Block::from_bytes_le(CurrentNetwork::genesis_bytes())?;
+            let ledger = CurrentLedger::load_unchecked(genesis, dev)?;
+
+            // Rebuild the block tree, repairing the stored block hashes.
+            let issues = snarkos_node_storage::rebuild_block_tree(&ledger, true)?;
+            let report = snarkos_node_storage::IntegrityReport { range: 0..ledger.latest_height() + 1, issues };
+            match report.is_ok() {
+                true => return Ok(format!("✅ Rebuilt the block tree from the stored block hashes - {report}")),
+                false => bail!("{report}\nRebuild the ledger from its blocks with 'snarkos ledger rebuild --replay'"),
+            }
+        }
+        let (plan, previous_dir) = Self::replay("rebuild", None, None, dev)?;
+        Ok(format!(
+            "✅ Rebuilt the ledger up to block {} {}",
+            plan.height,
+            format!("(the previous ledger is in \"{}\")", previous_dir.display()).dimmed()
+        ))
+    }
+
+    /// Replays the blocks of the ledger up to the given height, or up to its latest block height if no height is given,
+    /// into a staged ledger, and swaps the staged ledger in.
+    /// Returns the plan of the replay and the directory of the previous ledger.
+    ///
+    /// The ledger is left as it is if the replay fails, and the replay file and the staging directory are always
+    /// removed. Note: snarkVM opens one ledger storage per process, so each step that opens a ledger runs in a process
+    /// of its own, and this process never opens the ledger.
+    fn replay(
+        operation: &str,
+        height: Option<u32>,
+        backup_dir: Option<&Path>,
+        dev: Option<u16>,
+    ) -> Result<(ReplayPlan, PathBuf)> {
+        let ledger_dir = aleo_std::aleo_ledger_dir(CurrentNetwork::ID, dev);
+        ensure!(ledger_dir.exists(), "No ledger was found (in \"{}\")", ledger_dir.display());
+        let staging_dir = ledger_dir.with_extension(format!("{operation}-staging"));
+        let replay_path = ledger_dir.with_extension(format!("{operation}-blocks"));
+        for path in [&staging_dir, &replay_path] {
+            ensure!(!path.exists(), "'{}' is left from a previous {operation}, remove it to continue", path.display());
+        }
+
+        fs::create_dir_all(&staging_dir)?;
+        let result = Self::stage(operation, height, backup_dir, dev, &ledger_dir, &staging_dir, &replay_path);
+        // Remove the replay file and the staging directory, which is empty once the staged ledger is swapped in.
+        let _ = fs::remove_file(&replay_path);
+        let _ = fs::remove_dir_all(&staging_dir);
+        result
+    }
+
+    /// Stages the replay of the ledger in the given staging directory, and swaps the staged ledger in.
+    fn stage(
+        operation: &str,
+        height: Option<u32>,
+        backup_dir: Option<&Path>,
+        dev: Option<u16>,
+        ledger_dir: &Path,
+        staging_dir: &Path,
+        replay_path: &Path,
+    ) -> Result<(ReplayPlan, PathBuf)> {
+        // Start the staged ledger from the latest backup at or below the height, if there is one.
+        let mut start_height = 1;
+        if let (Some(backup_dir), Some(height)) = (backup_dir, height) {
+            if let Some((backup, backup_height)) = snarkos_node_storage::latest_backup(backup_dir, height)? {
+                snarkos_node_storage::stage_checkpoint(CurrentNetwork::ID, &backup, staging_dir)?;
+                println!("📦 Replaying the blocks from the backup at block {backup_height} ('{}')", backup.display());
+                start_height = backup_height + 1;
+            }
+        }
+
+        // Write the blocks to replay, and read the plan of the replay.
+        let mut args = vec![OsString::from("stage-export"), format!("--start={start_height}").into()];
+        args.extend([OsString::from("--output"), replay_path.into()]);
+        args.extend(height.map(|height| format!("--height={height}").into()));
+        args.extend(dev.map(|dev| format!("--dev={dev}").into()));
+        Self::run_stage(&args)?;
+        let plan = snarkos_node_storage::read_replay_plan(&mut io::BufReader::new(fs::File::open(replay_path)?))?;
+        let previous_dir = ledger_dir.with_extension(format!("pre-{operation}-{}", plan.latest_height));
+        ensure!(!previous_dir.exists(), "The pre-{operation} ledger '{}' already exists", previous_dir.display());
+
+        // Replay the blocks into the staged ledger.
+        let mut args = vec![OsString::from("stage-replay")];
+        args.extend([OsString::from("--input"), replay_path.into(), OsString::from("--staging"), staging_dir.into()]);
+        Self::run_stage(&args)?;
+
+        // Swap the staged ledger in.
+        let staged_dir = snarkos_node_storage::staged_ledger_dir(CurrentNetwork::ID, staging_dir)?;
+        snarkos_node_storage::swap_staged_ledger(&staged_dir, ledger_dir, &previous_dir)?;
+        Ok((plan, previous_dir))
+    }
+
+    /// Runs the given hidden ledger subcommand in a process of its own, and returns its output as the error on failure.
+    fn run_stage(args: &[OsString]) -> Result<()> {
+        let output = Command::new(std::env::current_exe()?).arg("ledger").args(args).output()?;
+        ensure!(output.status.success(), "{}", String::from_utf8_lossy(&output.stdout).trim());
+        Ok(())
+    }
+
+    /// Checks the integrity of the ledger over the given range of block heights, and repairs it if requested.
+    fn check(start: Option<u32>, end: Option<u32>, repair: bool, dev: Option<u16>) -> Result<String> {
+        // Ensure the ledger exists, as loading a missing ledger would initialize a new one.
+        let ledger_dir = aleo_std::aleo_ledger_dir(CurrentNetwork::ID, dev);
+        ensure!(ledger_dir.exists(), "No ledger was found (in \"{}\")", ledger_dir.display());
+        // Load the ledger, without spot-checking its blocks, as every block in the range is checked.
+        let genesis = Block::from_bytes_le(CurrentNetwork::genesis_bytes())?;
+        let ledger = CurrentLedger::load_unchecked(genesis, dev)?;
+
+        // Check the integrity of the ledger.
+        let range = start.unwrap_or(0)..end.unwrap_or(ledger.latest_height() + 1);
+        let report = snarkos_node_storage::verify_integrity(&ledger, range, repair)?;
+        match report.is_ok() {
+            true => Ok(format!("✅ {report}")),
+            false => bail!("{report}"),
+        }
+    }
+
+    /// Downloads the snapshot archive from the REST endpoint of a running node, into the given path.
+    fn export(height: u32, output: PathBuf, endpoint: &str, jwt: Option<&str>) -> Result<String> {
+        // Ensure an existing file is never overwritten.
+        ensure!(!output.exists(), "The output path '{}' already exists", output.display());
+
+        // Request the snapshot archive.
+        let url = format!("{}/testnet3/snapshot?height={height}", endpoint.trim_end_matches('/'));
+        let mut request = ureq::get(&url);
+        if let Some(jwt) = jwt {
+            request = request.set("Authorization", &format!("Bearer {jwt}"));
+        }
+        let response = match request.call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(code, response)) => {
+                bail!("Failed to export the ledger snapshot ({code}) - {}", response.into_string().unwrap_or_default())
+            }
+            Err(error) => bail!("Failed to export the ledger snapshot - {error}"),
+        };
+
+        // Stream the archive into a temporary file, and rename it, so the output is never partially written.
+        let temp_path = output.with_extension("partial");
+        let result = fs::File::create(&temp_path)
+            .and_then(|mut file| io::copy(&mut response.into_reader(), &mut file))
+            .and_then(|size| fs::rename(&temp_path, &output).map(|()| size));
+        let size = match result {
+            Ok(size) => size,
+            Err(error) => {
+                // Remove the partially written file, which may not exist if it could not be created.
+                let _ = fs::remove_file(&temp_path);
+                bail!("Failed to write the ledger snapshot to '{}' - {error}", output.display())
+            }
+        };
+        Ok(format!("✅ Exported the ledger snapshot at block {height} ({size} bytes) to '{}'", output.display()))
+    }
+
+    /// Parses the trusted checkpoint, as '<height>:<block hash>'.
+    fn parse_checkpoint<N: Network>(checkpoint: &str) -> Result<(u32, N::BlockHash)> {
+        let Some((height, hash)) = checkpoint.split_once(':') else {
+            bail!("The checkpoint must be specified as '<height>:<block hash>'")
+        };
+        match (height.parse(), N::BlockHash::from_str(hash)) {
+            (Ok(height), Ok(hash)) => Ok((height, hash)),
+            _ => bail!("The checkpoint '{checkpoint}' is invalid"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_checkpoint() {
+        let hash = "ab1fxetqjm0ppruay8vlg6gtt52d5fkeydmrk0talp04ymjm65acg9sh8d0r5";
+
+        // Ensure a well-formed checkpoint is parsed.
+        let (height, block_hash) = Ledger::parse_checkpoint::<CurrentNetwork>(&format!("1000:{hash}")).unwrap();
+        assert_eq!(height, 1000);
+        assert_eq!(block_hash.to_string(), hash);
+
+        // Ensure a malformed checkpoint is rejected.
+        for checkpoint in ["", "1000", format!("x:{hash}").as_str(), "1000:ab1"] {
+            assert!(Ledger::parse_checkpoint::<CurrentNetwork>(checkpoint).is_err());
+        }
+    }
+
+    #[test]
+    fn test_export_never_overwrites() {
+        // Ensure an existing output path is never overwritten.
+        let export = Ledger::Export { height: 0, output: std::env::temp_dir(), endpoint: String::new(), jwt: None };
+        assert!(export.parse().is_err());
+    }
+}
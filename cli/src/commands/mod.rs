@@ -21,9 +21,15 @@ pub use clean::*;
 mod developer;
 pub use developer::*;
 
+mod ledger;
+pub use ledger::*;
+
 mod start;
 pub use start::*;
 
+mod test_scenario;
+pub use test_scenario::*;
+
 mod update;
 pub use update::*;
 
@@ -57,8 +63,12 @@ pub enum Command {
     Clean(Clean),
     #[clap(subcommand)]
     Developer(Developer),
+    #[clap(subcommand)]
+    Ledger(Ledger),
     #[clap(name = "start")]
     Start(Box<Start>),
+    #[clap(name = "test-scenario")]
+    TestScenario(TestScenario),
     #[clap(name = "update")]
     Update(Update),
 }
@@ -70,7 +80,9 @@ impl Command {
             Self::Account(command) => command.parse(),
             Self::Clean(command) => command.parse(),
             Self::Developer(command) => command.parse(),
+            Self::Ledger(command) => command.parse(),
             Self::Start(command) => command.parse(),
+            Self::TestScenario(command) => command.parse(),
             Self::Update(command) => command.parse(),
         }
     }
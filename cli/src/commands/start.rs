@@ -14,7 +14,27 @@
 
 use snarkos_account::Account;
 use snarkos_display::Display;
-use snarkos_node::{bft::MEMORY_POOL_PORT, router::messages::NodeType, Node};
+use snarkos_node::{
+    bft::{
+        helpers::{BatchPolicy, WarmUp},
+        storage_service::{read_record, write_record, IndexStorage, MemoryStorage, RocksStorage},
+        PrimaryConfig,
+        MAX_GC_ROUNDS,
+        MEMORY_POOL_PORT,
+        STARTUP_WARM_UP_IN_SECS,
+    },
+    consensus::{MAX_MEMPOOL_SIZE_IN_BYTES, MAX_TRANSACTION_VALIDITY_IN_BLOCKS},
+    rest::{AdminConfig, ApiAccessConfig, HealthConfig, HttpConfig, LogVerbosityHandler, RestConfig},
+    router::{messages::NodeType, MemoryProfile, Whitelist},
+    storage::{AddressIndex, BackupConfig, LedgerCacheConfig, DEFAULT_MAX_BACKUPS},
+    sync::MAX_BLOCKS_BEHIND,
+    tcp::PeerDenyList,
+    MigrationOptions,
+    Node,
+    NodeConfig,
+    StorageMode,
+    LEDGER_SCHEMA_VERSION,
+};
 use snarkvm::{
     console::{
         account::{Address, PrivateKey},
@@ -24,20 +44,24 @@ use snarkvm::{
     ledger::{
         block::Block,
         committee::{Committee, MIN_VALIDATOR_STAKE},
-        store::{helpers::memory::ConsensusMemory, ConsensusStore},
+        store::{
+            helpers::{memory::ConsensusMemory, rocksdb::ConsensusDB},
+            ConsensusStore,
+        },
+        Ledger,
     },
     prelude::{FromBytes, ToBits, ToBytes},
     synthesizer::VM,
     utilities::to_bytes_le,
 };
 
-use anyhow::{bail, ensure, Result};
+use anyhow::{bail, ensure, Context, Result};
 use clap::Parser;
 use colored::Colorize;
 use core::str::FromStr;
 use rand::SeedableRng;
 use rand_chacha::ChaChaRng;
-use std::{net::SocketAddr, path::PathBuf};
+use std::{net::SocketAddr, path::PathBuf, sync::Arc, time::Duration};
 use tokio::runtime::{self, Runtime};
 
 /// The recommended minimum number of 'open files' limit for a validator.
@@ -46,9 +70,9 @@ use tokio::runtime::{self, Runtime};
 const RECOMMENDED_MIN_NOFILES_LIMIT: u64 = 2048;
 
 /// The development mode RNG seed.
-const DEVELOPMENT_MODE_RNG_SEED: u64 = 1234567890u64;
+pub(crate) const DEVELOPMENT_MODE_RNG_SEED: u64 = 1234567890u64;
 /// The development mode number of genesis committee members.
-const DEVELOPMENT_MODE_NUM_GENESIS_COMMITTEE_MEMBERS: u16 = 4;
+pub(crate) const DEVELOPMENT_MODE_NUM_GENESIS_COMMITTEE_MEMBERS: u16 = 4;
 
 /// Starts the snarkOS node.
 #[derive(Clone, Debug, Parser)]
@@ -86,6 +110,57 @@ pub struct Start {
     /// Specify the IP address and port of the validator(s) to connect to
     #[clap(default_value = "", long = "validators")]
     pub validators: String,
+    /// Specify the IP address and port, or the Aleo address, of the only peer(s) to connect to (enables whitelist mode)
+    #[clap(long = "whitelist")]
+    pub whitelist: Option<String>,
+    /// Specify the path to a file of peer IP addresses to refuse connections with, which is reloaded while running
+    #[clap(long = "peer-deny-list")]
+    pub peer_deny_list: Option<PathBuf>,
+
+    /// Specify the maximum number of blocks into the future that a transaction expiration height may be set to
+    #[clap(default_value_t = MAX_TRANSACTION_VALIDITY_IN_BLOCKS, long = "max-transaction-validity")]
+    pub max_transaction_validity: u32,
+    /// Specify the maximum total size (in bytes) of the unconfirmed transactions in the mempool
+    #[clap(default_value_t = MAX_MEMPOOL_SIZE_IN_BYTES, long = "max-mempool-size")]
+    pub max_mempool_size: usize,
+    /// Specify the number of seconds the validator must be connected to quorum stake before proposing batches
+    #[clap(default_value_t = STARTUP_WARM_UP_IN_SECS, long = "warm-up")]
+    pub warm_up: u64,
+    /// Specify the maximum number of blocks the validator may be behind the network tip before proposing batches
+    #[clap(default_value_t = MAX_BLOCKS_BEHIND, long = "warm-up-max-blocks-behind")]
+    pub warm_up_max_blocks_behind: u32,
+    /// If the flag is set, the node will adjust its clock by the median offset of the validators' timestamps
+    #[clap(long = "clock-sync")]
+    pub clock_sync: bool,
+    /// Specify the number of rounds the validator retains in memory behind the last committed round [default: 50]
+    #[clap(long = "committed-gc-depth")]
+    pub committed_gc_depth: Option<u64>,
+    /// Specify the number of workers that seal the transmissions of the validator into worker batches [default: 1]
+    #[clap(long = "workers")]
+    pub workers: Option<u8>,
+    /// Specify the maximum number of transmissions in a batch proposed by the validator [default: 250]
+    #[clap(long = "batch-max-transmissions")]
+    pub batch_max_transmissions: Option<usize>,
+    /// Specify the maximum number of bytes of transmissions in a batch proposed by the validator [default: 16 MiB]
+    #[clap(long = "batch-max-bytes")]
+    pub batch_max_bytes: Option<usize>,
+    /// Specify the maximum delay in milliseconds before the validator proposes a batch [default: 2500]
+    #[clap(long = "batch-max-delay")]
+    pub batch_max_delay: Option<u64>,
+    /// Specify the IP address and port on which the validator serves heartbeats to its standby.
+    /// The validator only signs while its standby renews the signing lease through the heartbeats
+    #[clap(long = "heartbeat")]
+    pub heartbeat: Option<SocketAddr>,
+    /// Specify the IP address and port of the heartbeats of the active validator, to start the validator in standby
+    #[clap(long = "standby")]
+    pub standby: Option<SocketAddr>,
+
+    /// If the flag is set, the node will relay new blocks to its peers in compact form, instead of announcing them
+    #[clap(long = "compact-relay")]
+    pub compact_relay: bool,
+    /// Specify the resource profile of the node [options: standard, low-memory]
+    #[clap(default_value = "standard", long = "profile")]
+    pub profile: MemoryProfile,
 
     /// Specify the IP address and port for the REST server
     #[clap(default_value = "0.0.0.0:3033", long = "rest")]
@@ -93,6 +168,42 @@ pub struct Start {
     /// If the flag is set, the node will not initialize the REST server
     #[clap(long)]
     pub norest: bool,
+    /// Specify the path of a Unix domain socket on which the REST server also listens
+    #[cfg(unix)]
+    #[clap(long = "rest-socket")]
+    pub rest_socket: Option<PathBuf>,
+    /// If the flag is set, the REST server only listens on the Unix domain socket, and not on the REST address
+    #[cfg(unix)]
+    #[clap(long = "rest-socket-only", requires = "rest_socket")]
+    pub rest_socket_only: bool,
+    /// Specify the origins allowed by CORS on the REST server, or every origin if empty (comma-separated)
+    #[clap(default_value = "", long = "rest-cors-origins")]
+    pub rest_cors_origins: String,
+    /// Specify the IPs of the reverse proxies whose 'X-Forwarded-For' header is trusted (comma-separated)
+    #[clap(default_value = "", long = "rest-trusted-proxies")]
+    pub rest_trusted_proxies: String,
+    /// Specify the maximum size of a request body on the REST server, in bytes
+    #[clap(default_value_t = snarkos_node::rest::DEFAULT_MAX_BODY_SIZE, long = "rest-max-body-size")]
+    pub rest_max_body_size: usize,
+    /// Specify the maximum number of blocks the node may be behind its peers to be reported as ready
+    #[clap(default_value_t = snarkos_node::rest::DEFAULT_HEALTH_MAX_BLOCKS_BEHIND, long = "health-max-blocks-behind")]
+    pub health_max_blocks_behind: u32,
+    /// Specify the minimum number of connected peers of the node to be reported as ready
+    #[clap(default_value_t = snarkos_node::rest::DEFAULT_HEALTH_MIN_PEERS, long = "health-min-peers")]
+    pub health_min_peers: usize,
+    /// Specify the path to the JSON file of the public methods and API keys of the REST, JSON-RPC, and gRPC servers
+    #[clap(long = "api-access")]
+    pub api_access: Option<PathBuf>,
+    /// Specify the IP address and port for the gRPC server, which is started alongside the REST server
+    #[cfg(feature = "grpc")]
+    #[clap(long = "grpc")]
+    pub grpc: Option<SocketAddr>,
+    /// If the flag is set, the node will initialize the admin server, which is authenticated with a separate admin JWT
+    #[clap(long)]
+    pub admin: bool,
+    /// Specify the IP address and port for the admin server
+    #[clap(default_value = snarkos_node::rest::DEFAULT_ADMIN_IP, long = "admin-ip")]
+    pub admin_ip: SocketAddr,
 
     /// If the flag is set, the node will not render the display
     #[clap(long)]
@@ -113,6 +224,48 @@ pub struct Start {
     /// If the flag is set, the node will not prefetch from a CDN
     #[clap(long)]
     pub nocdn: bool,
+    /// Enables the node to fast-sync from the ledger snapshot at the given URL, if the ledger is empty
+    #[clap(long = "snapshot")]
+    pub snapshot: Option<String>,
+    /// Specify the trusted checkpoint that the ledger snapshot must match, as '<height>:<block hash>'
+    #[clap(long = "snapshot-checkpoint")]
+    pub snapshot_checkpoint: Option<String>,
+    /// Enables pruned mode for a client, which only retains the bodies of the given number of recent blocks
+    #[clap(long = "prune-depth")]
+    pub prune_depth: Option<u32>,
+    /// If the flag is set, the node will hold its ledger in memory and write nothing to disk (for tests and devnets)
+    #[clap(long = "in-memory")]
+    pub in_memory: bool,
+    /// Specify the directory of the ledger backups, which are created through `POST /testnet3/node/backup`
+    #[clap(long = "backup-dir")]
+    pub backup_dir: Option<PathBuf>,
+    /// Specify the number of most recent ledger backups to retain
+    #[clap(default_value_t = DEFAULT_MAX_BACKUPS, long = "max-backups")]
+    pub max_backups: usize,
+    /// If the flag is set, the node will index the address activity, transactions, and records, for archival nodes
+    #[clap(long = "address-index")]
+    pub address_index: bool,
+    /// If the flag is set, the node will list the pending migrations of the ledger and exit, without applying them
+    #[clap(long = "migrate-dry-run")]
+    pub migrate_dry_run: bool,
+    /// If the flag is set, the node will not back up the ledger before applying the pending migrations
+    #[clap(long = "no-migration-backup")]
+    pub no_migration_backup: bool,
+    /// Specify the free disk space below which the node stops advancing the ledger, in MiB (default: 2048)
+    #[clap(long = "min-free-disk-mb")]
+    pub min_free_disk_mb: Option<u64>,
+    /// Specify the number of recent blocks to cache for the peers and the REST API, or 0 to disable the cache
+    /// [default: 128, or 16 in the low-memory profile]
+    #[clap(long = "ledger-cache-blocks")]
+    pub ledger_cache_blocks: Option<usize>,
+    /// Specify the number of recent block headers to cache for the peers and the REST API, or 0 to disable the cache
+    /// [default: 4096, or 512 in the low-memory profile]
+    #[clap(long = "ledger-cache-headers")]
+    pub ledger_cache_headers: Option<usize>,
+    /// Specify the number of recent transactions to cache for the REST API, or 0 to disable the cache
+    /// [default: 4096, or 512 in the low-memory profile]
+    #[clap(long = "ledger-cache-transactions")]
+    pub ledger_cache_transactions: Option<usize>,
 
     /// Enables development mode, specify a unique ID for this node
     #[clap(long)]
@@ -125,17 +278,27 @@ pub struct Start {
 impl Start {
     /// Starts the snarkOS node.
     pub fn parse(self) -> Result<String> {
+        // List the pending migrations of the ledger, if requested, without starting the node.
+        if self.migrate_dry_run {
+            // Ensure the ledger is stored on disk, as only a persistent ledger is migrated.
+            self.parse_storage_mode()?;
+            return match self.network {
+                3 => self.list_pending_migrations::<Testnet3>(),
+                _ => bail!("Invalid network ID specified"),
+            };
+        }
         // Initialize the logger.
-        let log_receiver = crate::helpers::initialize_logger(self.verbosity, self.nodisplay, self.logfile.clone());
+        let (log_receiver, log_verbosity) =
+            crate::helpers::initialize_logger(self.verbosity, self.nodisplay, self.logfile.clone());
         // Initialize the runtime.
-        Self::runtime().block_on(async move {
+        Self::runtime(self.profile).block_on(async move {
             // Clone the configurations.
             let mut cli = self.clone();
             // Parse the network.
             match cli.network {
                 3 => {
                     // Parse the node from the configurations.
-                    let node = cli.parse_node::<Testnet3>().await.expect("Failed to parse the node");
+                    let node = cli.parse_node::<Testnet3>(log_verbosity).await.expect("Failed to parse the node");
                     // If the display is enabled, render the display.
                     if !cli.nodisplay {
                         // Initialize the display.
@@ -172,6 +335,19 @@ impl Start {
         }
     }
 
+    /// Returns the HTTP settings of the REST server, from the given configurations.
+    fn parse_http_config(&self) -> Result<HttpConfig> {
+        let split = |list: &str| list.split(',').map(str::trim).filter(|item| !item.is_empty()).collect::<Vec<_>>();
+        let cors_origins = split(&self.rest_cors_origins).into_iter().map(ToString::to_string).collect();
+        let trusted_proxies = split(&self.rest_trusted_proxies)
+            .into_iter()
+            .map(|ip| {
+                ip.parse().with_context(|| format!("The IP supplied to --rest-trusted-proxies ('{ip}') is malformed"))
+            })
+            .collect::<Result<_>>()?;
+        Ok(HttpConfig { cors_origins, trusted_proxies, max_body_size: self.rest_max_body_size })
+    }
+
     /// Returns the initial validator(s) to connect to, from the given configurations.
     fn parse_trusted_validators(&self) -> Result<Vec<SocketAddr>> {
         match self.validators.is_empty() {
@@ -190,6 +366,73 @@ impl Start {
         }
     }
 
+    /// Returns the whitelist of peers, if whitelist mode is enabled in the given configurations.
+    fn parse_whitelist<N: Network>(&self) -> Result<Option<Whitelist<N>>> {
+        match &self.whitelist {
+            Some(whitelist) => {
+                // Parse the whitelist.
+                let whitelist = Whitelist::from_str(whitelist)?;
+                // Ensure the whitelist is not empty, as the node would be unable to connect to any peer.
+                ensure!(!whitelist.is_empty(), "The '--whitelist' argument must specify at least one peer");
+                Ok(Some(whitelist))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the storage of the ledger, from the given configurations.
+    fn parse_storage_mode(&self) -> Result<StorageMode> {
+        if !self.in_memory {
+            return Ok(StorageMode::Persistent);
+        }
+        // Ensure none of the features that write to disk are requested.
+        ensure!(self.snapshot.is_none(), "The '--in-memory' flag does not support '--snapshot'");
+        ensure!(self.prune_depth.is_none(), "The '--in-memory' flag does not support '--prune-depth'");
+        ensure!(self.backup_dir.is_none(), "The '--in-memory' flag does not support '--backup-dir'");
+        ensure!(!self.migrate_dry_run, "The '--in-memory' flag does not support '--migrate-dry-run'");
+        ensure!(
+            self.heartbeat.is_none() && self.standby.is_none(),
+            "The '--in-memory' flag does not support '--heartbeat' and '--standby', which require a signing journal"
+        );
+        Ok(StorageMode::InMemory)
+    }
+
+    /// Returns the sizes of the ledger cache, from the memory profile defaults and the given configurations.
+    fn parse_ledger_cache_config(&self) -> LedgerCacheConfig {
+        let mut config = match self.profile {
+            MemoryProfile::LowMemory => LedgerCacheConfig::low_memory(),
+            MemoryProfile::Standard => LedgerCacheConfig::default(),
+        };
+        config.blocks = self.ledger_cache_blocks.unwrap_or(config.blocks);
+        config.headers = self.ledger_cache_headers.unwrap_or(config.headers);
+        config.transactions = self.ledger_cache_transactions.unwrap_or(config.transactions);
+        config
+    }
+
+    /// Returns the list of the pending migrations of the ledger, without applying them.
+    fn list_pending_migrations<N: Network>(&self) -> Result<String> {
+        let options = MigrationOptions { dry_run: true, skip_backup: self.no_migration_backup };
+        let migrations = snarkos_node::migrate_ledger::<N>(self.dev, options)?;
+        if migrations.is_empty() {
+            return Ok(format!("📦 The ledger has no pending migrations (schema version {LEDGER_SCHEMA_VERSION})."));
+        }
+        let mut output = format!("📦 The ledger has {} pending migration(s):", migrations.len());
+        for migration in migrations {
+            output += &format!("\n  - Schema version {}: {}", migration.version, migration.description);
+        }
+        Ok(output)
+    }
+
+    /// Applies the pending migrations of the ledger, from the given configurations.
+    fn migrate_ledger<N: Network>(&self) -> Result<()> {
+        let options = MigrationOptions { dry_run: false, skip_backup: self.no_migration_backup };
+        let migrations = snarkos_node::migrate_ledger::<N>(self.dev, options)?;
+        if let Some(migration) = migrations.last() {
+            println!("📦 Migrated the ledger to schema version {}.\n", migration.version);
+        }
+        Ok(())
+    }
+
     /// Returns the CDN to prefetch initial blocks from, from the given configurations.
     fn parse_cdn(&self) -> Option<String> {
         // Determine if the node type is not declared.
@@ -209,6 +452,25 @@ impl Start {
         }
     }
 
+    /// Returns the ledger snapshot to fast-sync from, and its trusted checkpoint, from the given configurations.
+    fn parse_snapshot<N: Network>(&self) -> Result<Option<(String, (u32, N::BlockHash))>> {
+        let Some(snapshot) = &self.snapshot else {
+            ensure!(self.snapshot_checkpoint.is_none(), "The '--snapshot-checkpoint' argument requires '--snapshot'");
+            return Ok(None);
+        };
+        ensure!(
+            self.validator || self.client,
+            "The '--snapshot' argument is only supported for validators and clients"
+        );
+        // Parse the trusted checkpoint.
+        let Some((height, hash)) =
+            self.snapshot_checkpoint.as_deref().and_then(|checkpoint| checkpoint.split_once(':'))
+        else {
+            bail!("The '--snapshot' argument requires a '--snapshot-checkpoint' of the form '<height>:<block hash>'")
+        };
+        Ok(Some((snapshot.clone(), (height.trim().parse()?, N::BlockHash::from_str(hash.trim())?))))
+    }
+
     /// Read the private key directly from an argument or from a filesystem location,
     /// returning the Aleo account.
     fn parse_private_key<N: Network>(&self) -> Result<Account<N>> {
@@ -281,6 +543,8 @@ impl Start {
             if !self.norest && self.bft.is_none() {
                 self.rest = SocketAddr::from_str(&format!("0.0.0.0:{}", 3030 + dev))?;
             }
+            // Set the admin IP to `3130 + dev`.
+            self.admin_ip = SocketAddr::from_str(&format!("127.0.0.1:{}", 3130 + dev))?;
         }
         Ok(())
     }
@@ -371,7 +635,7 @@ impl Start {
 
     /// Returns the node type corresponding to the given configurations.
     #[rustfmt::skip]
-    async fn parse_node<N: Network>(&mut self) -> Result<Node<N>> {
+    async fn parse_node<N: Network>(&mut self, log_verbosity: LogVerbosityHandler) -> Result<Node<N>> {
         // Print the welcome.
         println!("{}", crate::helpers::welcome_message());
 
@@ -381,12 +645,66 @@ impl Start {
         let mut trusted_validators = self.parse_trusted_validators()?;
         // Parse the development configurations.
         self.parse_development(&mut trusted_peers, &mut trusted_validators)?;
+        // Parse the whitelist.
+        let whitelist = self.parse_whitelist::<N>()?;
+        // Ensure the whitelisted peer IPs are dialed and kept connected.
+        if let Some(whitelist) = &whitelist {
+            for peer_ip in whitelist.ips() {
+                if !trusted_peers.contains(peer_ip) {
+                    trusted_peers.push(*peer_ip);
+                }
+            }
+        }
+
+        // Ensure the low-memory profile is only used by clients and provers.
+        if self.profile == MemoryProfile::LowMemory {
+            ensure!(!self.validator, "The 'low-memory' profile is only supported for clients and provers");
+        }
+
+        // Ensure the number of workers is only specified for validators.
+        if self.workers.is_some() {
+            ensure!(self.validator, "The '--workers' argument is only supported for validators");
+        }
+        // Parse the batch sealing policy, starting from the default policy.
+        let mut batch_policy = BatchPolicy::default();
+        if self.batch_max_transmissions.is_some() || self.batch_max_bytes.is_some() || self.batch_max_delay.is_some() {
+            ensure!(self.validator, "The '--batch-max-*' arguments are only supported for validators");
+            batch_policy = BatchPolicy::new(
+                self.batch_max_transmissions.unwrap_or(batch_policy.max_transmissions()),
+                self.batch_max_bytes.unwrap_or(batch_policy.max_bytes()),
+                self.batch_max_delay.unwrap_or(batch_policy.max_delay().as_millis() as u64),
+            )?;
+        }
 
         // Parse the CDN.
         let cdn = self.parse_cdn();
 
+        // Parse the storage of the ledger.
+        let storage_mode = self.parse_storage_mode()?;
         // Parse the genesis block.
         let genesis = self.parse_genesis::<N>()?;
+        // Fast-sync the ledger from a snapshot, if requested and the ledger is empty.
+        if let Some((snapshot, checkpoint)) = self.parse_snapshot::<N>()? {
+            let ledger_dir = aleo_std::aleo_ledger_dir(N::ID, self.dev);
+            match ledger_dir.exists() {
+                true => println!("📦 Skipping the ledger snapshot, as the ledger already exists.\n"),
+                false => {
+                    println!("📦 Downloading the ledger snapshot at block {}.\n", checkpoint.0);
+                    let manifest = snarkos_node_storage::load_snapshot::<N>(&snapshot, &ledger_dir, checkpoint).await?;
+                    // Ensure the ledger loaded from the snapshot is valid and contains the checkpoint, or discard it.
+                    let ledger = Ledger::<N, ConsensusDB<N>>::load(genesis.clone(), self.dev)?;
+                    if let Err(error) = snarkos_node_storage::verify_snapshot(&ledger, &manifest, checkpoint) {
+                        drop(ledger);
+                        std::fs::remove_dir_all(&ledger_dir)?;
+                        return Err(error);
+                    }
+                }
+            }
+        }
+        // Apply the pending migrations of the ledger, before it is loaded.
+        if storage_mode == StorageMode::Persistent {
+            self.migrate_ledger::<N>()?;
+        }
         // Parse the private key of the node.
         let account = self.parse_private_key::<N>()?;
         // Parse the node type.
@@ -421,6 +739,15 @@ impl Start {
                     }
                 }
             }
+
+            // If the node is running an admin server, print the admin IP and JWT.
+            if self.admin {
+                println!("🛠️  Starting the admin server at {}.\n", self.admin_ip.to_string().bold());
+
+                if let Ok(jwt_token) = snarkos_node_rest::Claims::new(account.address()).to_admin_jwt_string() {
+                    println!("🔑 Your admin JWT token is {}\n", jwt_token.dimmed());
+                }
+            }
         }
 
         // If the node is a validator, check if the open files limit is lower than recommended.
@@ -430,27 +757,186 @@ impl Start {
         }
         // Check if the machine meets the minimum requirements for a validator.
         crate::helpers::check_validator_machine(node_type);
+        // If the node is a validator, persist the misbehavior reports of the other validators alongside the ledger.
+        let misbehavior_log = match node_type.is_validator() && storage_mode == StorageMode::Persistent {
+            true => Some(aleo_std::aleo_ledger_dir(N::ID, self.dev).with_extension("misbehavior-log")),
+            false => None,
+        };
+        // If the node is a validator, persist the mempool alongside the ledger, so it is restored after a restart.
+        let mempool_snapshot_path = match node_type.is_validator() && storage_mode == StorageMode::Persistent {
+            true => Some(aleo_std::aleo_ledger_dir(N::ID, self.dev).with_extension("mempool")),
+            false => None,
+        };
 
         // Initialize the metrics.
         if self.metrics {
             metrics::initialize_metrics();
         }
 
+        // Initialize the signing journal, to protect the validator from double-signing, if it has a standby.
+        let signing_journal = match self.heartbeat.is_some() || self.standby.is_some() {
+            true => {
+                ensure!(
+                    node_type.is_validator(),
+                    "The '--heartbeat' and '--standby' arguments are only supported for validators"
+                );
+                Some(aleo_std::aleo_ledger_dir(N::ID, self.dev).with_extension("signing-journal"))
+            }
+            false => None,
+        };
+        // If the validator is a standby, keep it in standby until the active validator fails.
+        if let Some(validator_ip) = self.standby {
+            println!("🕒 Standing by for the validator at {}.\n", validator_ip.to_string().bold());
+        }
+
+        // Enable the storage maintenance, which watches the free disk space and compacts the ledger.
+        let min_free_disk_space = if storage_mode == StorageMode::Persistent && !node_type.is_prover() {
+            match self.min_free_disk_mb {
+                Some(min_free_disk_mb) => Some(min_free_disk_mb.saturating_mul(1024 * 1024)),
+                None => Some(snarkos_node::DEFAULT_MIN_FREE_DISK_SPACE),
+            }
+        } else if self.min_free_disk_mb.is_some() {
+            bail!("The '--min-free-disk-mb' argument requires a validator or client with persistent storage");
+        } else {
+            None
+        };
+        // Initialize the peer deny list, if requested.
+        let peer_deny_list = self.peer_deny_list.clone().map(|path| Arc::new(PeerDenyList::new(path)));
+
+        // Configure the ledger backups, if requested.
+        let backups = match &self.backup_dir {
+            Some(backup_dir) => {
+                ensure!(
+                    !node_type.is_prover(),
+                    "The '--backup-dir' argument is only supported for validators and clients"
+                );
+                Some(BackupConfig::new(backup_dir.clone(), self.max_backups)?)
+            }
+            None => None,
+        };
+
+        // Open the address index, if requested.
+        let address_index = match self.address_index {
+            true => {
+                ensure!(
+                    !node_type.is_prover(),
+                    "The '--address-index' flag is only supported for validators and clients"
+                );
+                ensure!(self.prune_depth.is_none(), "The '--address-index' flag does not support '--prune-depth'");
+                let storage: Box<dyn IndexStorage> = match storage_mode {
+                    StorageMode::Persistent => {
+                        let path = aleo_std::aleo_ledger_dir(N::ID, self.dev).with_extension("address-index");
+                        Box::new(RocksStorage::open(&path)?)
+                    }
+                    StorageMode::InMemory => Box::new(MemoryStorage::new()),
+                };
+                Some(Arc::new(AddressIndex::new(storage)))
+            }
+            false => None,
+        };
+
+        // Parse the Unix domain socket of the REST server, if requested.
+        #[cfg(unix)]
+        let unix_socket = match &self.rest_socket {
+            Some(path) => {
+                ensure!(!self.norest, "The '--rest-socket' argument requires the REST server");
+                Some(snarkos_node::rest::UnixSocketConfig { path: path.clone(), exclusive: self.rest_socket_only })
+            }
+            None => None,
+        };
+
+        // Parse the access to the JSON-RPC server, if requested.
+        let api_access = match &self.api_access {
+            Some(path) => {
+                ensure!(!self.norest, "The '--api-access' argument requires the REST server");
+                let config = ApiAccessConfig::load(path)
+                    .with_context(|| format!("Failed to load the API access from '{}'", path.display()))?;
+                Some(config)
+            }
+            None => None,
+        };
+
+        // Ensure the gRPC server is only requested alongside the REST server.
+        #[cfg(feature = "grpc")]
+        if self.grpc.is_some() {
+            ensure!(!self.norest, "The '--grpc' argument requires the REST server");
+        }
+
+        // Parse the configuration of the admin server, if requested.
+        let admin = match self.admin {
+            true => Some(AdminConfig { ip: self.admin_ip, log_verbosity: Some(log_verbosity) }),
+            false => None,
+        };
+
+        // Ensure pruned mode is only enabled for clients, as validators must serve the full ledger.
+        if self.prune_depth.is_some() {
+            ensure!(node_type.is_client(), "The '--prune-depth' argument is only supported for clients");
+        }
+
+        // Initialize the configuration of the node.
+        let config = NodeConfig {
+            node_ip: self.node,
+            rest_ip,
+            rest: RestConfig {
+                http: self.parse_http_config()?,
+                health: HealthConfig {
+                    max_blocks_behind: self.health_max_blocks_behind,
+                    min_peers: self.health_min_peers,
+                },
+                api_access,
+                #[cfg(feature = "grpc")]
+                grpc_ip: self.grpc,
+                #[cfg(unix)]
+                unix_socket,
+            },
+            admin,
+            bft_ip: if self.dev.is_some() { self.bft } else { None },
+            trusted_peers,
+            whitelist,
+            trusted_validators,
+            peer_deny_list,
+            memory_profile: self.profile,
+            compact_relay: self.compact_relay,
+            max_transaction_validity: self.max_transaction_validity,
+            max_mempool_size: self.max_mempool_size,
+            mempool_snapshot_path,
+            primary: PrimaryConfig {
+                warm_up: WarmUp::new(Duration::from_secs(self.warm_up), self.warm_up_max_blocks_behind),
+                num_workers: self.workers.unwrap_or(1),
+                batch_policy,
+                committed_gc_depth: self.committed_gc_depth.unwrap_or(MAX_GC_ROUNDS),
+                signing_journal,
+                signing_lease: None,
+                misbehavior_log,
+                clock_sync: self.clock_sync,
+            },
+            standby: self.standby,
+            heartbeat_ip: self.heartbeat,
+            cdn,
+            prune_depth: self.prune_depth,
+            storage_mode,
+            ledger_cache: self.parse_ledger_cache_config(),
+            backups,
+            address_index,
+            min_free_disk_space,
+            dev: self.dev,
+        };
         // Initialize the node.
-        let bft_ip = if self.dev.is_some() { self.bft } else { None };
         match node_type {
-            NodeType::Validator => Node::new_validator(self.node, rest_ip, bft_ip, account, &trusted_peers, &trusted_validators, genesis, cdn, self.dev).await,
-            NodeType::Prover => Node::new_prover(self.node, account, &trusted_peers, genesis, self.dev).await,
-            NodeType::Client => Node::new_client(self.node, rest_ip, account, &trusted_peers, genesis, cdn, self.dev).await,
+            NodeType::Validator => Node::new_validator(account, genesis, config).await,
+            NodeType::Prover => Node::new_prover(account, genesis, config).await,
+            NodeType::Client => Node::new_client(account, genesis, config).await,
         }
     }
 
     /// Returns a runtime for the node.
-    fn runtime() -> Runtime {
+    fn runtime(profile: MemoryProfile) -> Runtime {
         // Retrieve the number of cores.
         let num_cores = num_cpus::get();
         // Determine the number of main cores.
         let main_cores = match num_cores {
+            // Low-memory mode
+            _ if profile == MemoryProfile::LowMemory => 1,
             // Insufficient
             0..=3 => {
                 eprintln!("The number of cores is insufficient, at least 4 are needed.");
@@ -464,8 +950,11 @@ impl Start {
             _ => 16,
         };
 
-        let (num_tokio_worker_threads, max_tokio_blocking_threads, num_rayon_cores_global) =
-            { (num_cores.min(main_cores), 512, num_cores.saturating_sub(main_cores).max(1)) };
+        let (num_tokio_worker_threads, max_tokio_blocking_threads, num_rayon_cores_global) = match profile {
+            // In low-memory mode, limit the verification parallelism, as each thread holds its own working memory.
+            MemoryProfile::LowMemory => (1, 16, num_cores.saturating_sub(main_cores).clamp(1, 2)),
+            MemoryProfile::Standard => (num_cores.min(main_cores), 512, num_cores.saturating_sub(main_cores).max(1)),
+        };
 
         // Initialize the parallelization parameters.
         rayon::ThreadPoolBuilder::new()
@@ -522,9 +1011,10 @@ fn load_or_compute_genesis<N: Network>(
     // A closure to load the block.
     let load_block = |file_path| -> Result<Block<N>> {
         // Attempts to load the genesis block file locally.
+        // Note: The genesis block files that predate the record headers are read in the unversioned encoding.
         let buffer = std::fs::read(file_path)?;
         // Return the genesis block.
-        Block::from_bytes_le(&buffer)
+        read_record(&buffer[..])
     };
 
     // Construct the file path.
@@ -543,8 +1033,10 @@ fn load_or_compute_genesis<N: Network>(
     let vm = VM::from(ConsensusStore::<N, ConsensusMemory<N>>::open(None)?)?;
     // Initialize the genesis block.
     let block = vm.genesis_quorum(&genesis_private_key, committee, public_balances, rng)?;
-    // Write the genesis block to the file.
-    std::fs::write(&file_path, block.to_bytes_le()?)?;
+    // Write the genesis block to the file, as a versioned record.
+    let mut buffer = Vec::new();
+    write_record(&block, &mut buffer)?;
+    std::fs::write(&file_path, buffer)?;
     // Return the genesis block.
     Ok(block)
 }
@@ -593,6 +1085,47 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn test_parse_whitelist() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert!(config.parse_whitelist::<CurrentNetwork>().unwrap().is_none());
+
+        let config = Start::try_parse_from(["snarkos", "--whitelist", ""].iter()).unwrap();
+        assert!(config.parse_whitelist::<CurrentNetwork>().is_err());
+
+        let config = Start::try_parse_from(["snarkos", "--whitelist", "1.2.3.4:5,6.7.8.9:0"].iter()).unwrap();
+        let whitelist = config.parse_whitelist::<CurrentNetwork>().unwrap().unwrap();
+        assert_eq!(whitelist.ips().iter().copied().collect::<Vec<_>>(), vec![
+            SocketAddr::from_str("1.2.3.4:5").unwrap(),
+            SocketAddr::from_str("6.7.8.9:0").unwrap()
+        ]);
+        assert!(whitelist.addresses().is_empty());
+
+        let config = Start::try_parse_from(["snarkos", "--whitelist", "1.2.3.4:5,aleo1xx"].iter()).unwrap();
+        assert!(config.parse_whitelist::<CurrentNetwork>().is_err());
+    }
+
+    #[test]
+    fn test_parse_profile() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.profile, MemoryProfile::Standard);
+
+        let config = Start::try_parse_from(["snarkos", "--profile", "low-memory"].iter()).unwrap();
+        assert_eq!(config.profile, MemoryProfile::LowMemory);
+
+        assert!(Start::try_parse_from(["snarkos", "--profile", "tiny"].iter()).is_err());
+
+        // Ensure the low-memory profile shrinks and compresses the ledger cache, unless the sizes are given.
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.parse_ledger_cache_config(), LedgerCacheConfig::default());
+        let config = Start::try_parse_from(["snarkos", "--profile", "low-memory"].iter()).unwrap();
+        assert_eq!(config.parse_ledger_cache_config(), LedgerCacheConfig::low_memory());
+        let args = ["snarkos", "--profile", "low-memory", "--ledger-cache-blocks", "0"];
+        let config = Start::try_parse_from(args.iter()).unwrap().parse_ledger_cache_config();
+        assert_eq!(config.blocks, 0);
+        assert!(config.compressed);
+    }
+
     #[test]
     fn test_parse_cdn() {
         // Validator (Prod)
@@ -688,6 +1221,58 @@ mod tests {
         assert!(config.parse_cdn().is_none());
     }
 
+    #[test]
+    fn test_parse_snapshot() {
+        let hash = "ab1fxetqjm0ppruay8vlg6gtt52d5fkeydmrk0talp04ymjm65acg9sh8d0r5";
+
+        // Ensure the snapshot is disabled by default.
+        let config = Start::try_parse_from(["snarkos", "--client"].iter()).unwrap();
+        assert!(config.parse_snapshot::<CurrentNetwork>().unwrap().is_none());
+
+        // Ensure the snapshot requires a trusted checkpoint.
+        let config = Start::try_parse_from(["snarkos", "--client", "--snapshot", "url"].iter()).unwrap();
+        assert!(config.parse_snapshot::<CurrentNetwork>().is_err());
+        let checkpoint = format!("1000:{hash}");
+        let args = ["snarkos", "--client", "--snapshot-checkpoint", checkpoint.as_str()];
+        let config = Start::try_parse_from(args.iter()).unwrap();
+        assert!(config.parse_snapshot::<CurrentNetwork>().is_err());
+
+        // Ensure the snapshot and its trusted checkpoint are parsed.
+        let args = ["snarkos", "--client", "--snapshot", "url", "--snapshot-checkpoint", checkpoint.as_str()];
+        let config = Start::try_parse_from(args.iter()).unwrap();
+        let (snapshot, (height, block_hash)) = config.parse_snapshot::<CurrentNetwork>().unwrap().unwrap();
+        assert_eq!(snapshot, "url");
+        assert_eq!(height, 1000);
+        assert_eq!(block_hash.to_string(), hash);
+
+        // Ensure the snapshot is not supported for provers.
+        let args = ["snarkos", "--prover", "--snapshot", "url", "--snapshot-checkpoint", checkpoint.as_str()];
+        let config = Start::try_parse_from(args.iter()).unwrap();
+        assert!(config.parse_snapshot::<CurrentNetwork>().is_err());
+    }
+
+    #[test]
+    fn test_parse_storage_mode() {
+        // Ensure the ledger is persisted by default.
+        let config = Start::try_parse_from(["snarkos", "--client"].iter()).unwrap();
+        assert_eq!(config.parse_storage_mode().unwrap(), StorageMode::Persistent);
+
+        // Ensure the ledger is held in memory, if requested.
+        let config = Start::try_parse_from(["snarkos", "--validator", "--dev", "0", "--in-memory"].iter()).unwrap();
+        assert_eq!(config.parse_storage_mode().unwrap(), StorageMode::InMemory);
+
+        // Ensure the features that write to disk are rejected.
+        let args = ["snarkos", "--client", "--in-memory", "--prune-depth", "1000"];
+        let config = Start::try_parse_from(args.iter()).unwrap();
+        assert!(config.parse_storage_mode().is_err());
+        let args = ["snarkos", "--validator", "--in-memory", "--heartbeat", "127.0.0.1:5000"];
+        let config = Start::try_parse_from(args.iter()).unwrap();
+        assert!(config.parse_storage_mode().is_err());
+        let args = ["snarkos", "--validator", "--in-memory", "--migrate-dry-run"];
+        let config = Start::try_parse_from(args.iter()).unwrap();
+        assert!(config.parse_storage_mode().is_err());
+    }
+
     #[test]
     fn test_parse_development_and_genesis() {
         let prod_genesis = Block::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
@@ -725,6 +1310,7 @@ mod tests {
         let genesis = config.parse_genesis::<CurrentNetwork>().unwrap();
         assert_eq!(config.node, SocketAddr::from_str("0.0.0.0:4131").unwrap());
         assert_eq!(config.rest, SocketAddr::from_str("0.0.0.0:3031").unwrap());
+        assert_eq!(config.admin_ip, SocketAddr::from_str("127.0.0.1:3131").unwrap());
         assert_eq!(trusted_peers.len(), 1);
         assert_eq!(trusted_validators.len(), 1);
         assert!(config.validator);
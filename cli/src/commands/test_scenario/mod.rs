@@ -0,0 +1,259 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod network;
+use network::*;
+
+mod scenario;
+pub use scenario::*;
+
+use super::DEVELOPMENT_MODE_RNG_SEED;
+use snarkvm::prelude::{
+    query::Query,
+    store::{helpers::memory::ConsensusMemory, ConsensusStore},
+    Address,
+    PrivateKey,
+    Value,
+    VM,
+};
+
+use anyhow::{anyhow, bail, Result};
+use clap::Parser;
+use colored::Colorize;
+use rand::SeedableRng;
+use rand_chacha::ChaChaRng;
+use std::{
+    fmt,
+    path::PathBuf,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+/// The interval at which the block heights of the validators are polled.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Runs a scripted scenario against a local development network, and reports whether it passed.
+#[derive(Debug, Parser)]
+pub struct TestScenario {
+    /// The path to the YAML file containing the scenario.
+    pub file: PathBuf,
+    /// Specify the directory where the logs of the validators will be stored
+    #[clap(default_value_os_t = std::env::temp_dir().join("snarkos-scenario"), long = "log-dir")]
+    pub log_dir: PathBuf,
+}
+
+impl TestScenario {
+    /// Runs the scenario, and returns the report if every step passed.
+    pub fn parse(self) -> Result<String> {
+        // Load the scenario.
+        let scenario = Scenario::load(&self.file)?;
+        println!("🧪 Running scenario '{}' with {} validators...\n", scenario.name.bold(), scenario.validators);
+
+        // Start the local network.
+        let mut network = LocalNetwork::start(scenario.validators, self.log_dir)?;
+        println!("📝 The validator logs are stored in {}\n", network.log_dir().display());
+
+        // Execute the steps in order, skipping the remaining steps after a failure.
+        let mut report = Report::new(scenario.name.clone());
+        for step in &scenario.steps {
+            let timer = Instant::now();
+            let outcome = match report.is_passing() {
+                true => Some(Self::run_step(&mut network, step)),
+                false => None,
+            };
+            report.push(step, timer.elapsed(), outcome);
+            println!("{}", report.outcomes.last().map(ToString::to_string).unwrap_or_default());
+        }
+
+        // Stop the local network.
+        network.shutdown();
+
+        // Return the report.
+        match report.is_passing() {
+            true => Ok(report.to_string()),
+            false => bail!("{report}"),
+        }
+    }
+
+    /// Executes the given step against the local network, and returns a summary of its outcome.
+    fn run_step(network: &mut LocalNetwork, step: &Step) -> Result<String> {
+        match step {
+            Step::Sleep(seconds) => {
+                std::thread::sleep(Duration::from_secs(*seconds));
+                Ok(format!("slept for {seconds}s"))
+            }
+            Step::WaitForHeight { height, timeout } => {
+                let is_reached = |network: &LocalNetwork| {
+                    let heights = network.heights();
+                    !heights.is_empty() && heights.iter().all(|(_, current)| current >= height)
+                };
+                match Self::poll(network, Duration::from_secs(*timeout), is_reached) {
+                    true => Ok(format!("every running validator reached height {height}")),
+                    false => bail!("the validators did not reach height {height} (heights: {:?})", network.heights()),
+                }
+            }
+            Step::Partition { groups, duration } => {
+                // Block the traffic between the groups, while every validator keeps running.
+                network.partition(groups)?;
+                std::thread::sleep(Duration::from_secs(*duration));
+                // Heal the partition.
+                network.heal()?;
+                Ok(format!("partitioned validators into {groups:?} for {duration}s"))
+            }
+            Step::Kill(id) => {
+                network.kill(*id)?;
+                Ok(format!("killed validator {id}"))
+            }
+            Step::KillLeader => {
+                let leader = network.leader()?;
+                network.kill(leader)?;
+                Ok(format!("killed validator {leader}"))
+            }
+            Step::Restart(id) => {
+                network.restart(*id)?;
+                Ok(format!("restarted validator {id}"))
+            }
+            Step::SubmitTransactions(num_transactions) => {
+                Self::submit_transactions(network, *num_transactions)?;
+                Ok(format!("submitted {num_transactions} transactions"))
+            }
+            Step::AssertHeightProgress { blocks, within } => {
+                // Retrieve the starting height of the network.
+                let start = network.max_height().ok_or_else(|| anyhow!("no running validator is reachable"))?;
+                let target = start.saturating_add(*blocks);
+                // Wait for the network to reach the target height.
+                let is_reached = |network: &LocalNetwork| network.max_height().unwrap_or_default() >= target;
+                match Self::poll(network, Duration::from_secs(*within), is_reached) {
+                    true => Ok(format!("the network advanced from height {start} to {target}")),
+                    false => bail!(
+                        "the network advanced from height {start} to {}, short of {target}",
+                        network.max_height().unwrap_or_default()
+                    ),
+                }
+            }
+        }
+    }
+
+    /// Polls the given condition until it holds or the timeout elapses, and returns `true` if it holds.
+    fn poll(network: &LocalNetwork, timeout: Duration, condition: impl Fn(&LocalNetwork) -> bool) -> bool {
+        let timer = Instant::now();
+        loop {
+            if condition(network) {
+                return true;
+            }
+            if timer.elapsed() >= timeout {
+                return false;
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    /// Submits the given number of `credits.aleo/transfer_public` transactions to a running validator.
+    ///
+    /// The transactions are signed with the development private key of the first validator,
+    /// which holds a public balance in the development genesis block.
+    fn submit_transactions(network: &LocalNetwork, num_transactions: u32) -> Result<()> {
+        // Select a running validator to query and broadcast to.
+        let id = (0..network.num_validators())
+            .find(|id| network.is_running(*id))
+            .ok_or_else(|| anyhow!("no validator is running"))?;
+        let endpoint = LocalNetwork::rest_endpoint(id);
+
+        // Retrieve the development private key of the first validator.
+        let private_key = PrivateKey::<CurrentNetwork>::new(&mut ChaChaRng::seed_from_u64(DEVELOPMENT_MODE_RNG_SEED))?;
+        // Prepare the inputs, transferring 1 microcredit back to the same account.
+        let address = Address::try_from(&private_key)?;
+        let inputs = [Value::<CurrentNetwork>::from_str(&address.to_string())?, Value::from_str("1u64")?];
+
+        // Initialize an RNG.
+        let rng = &mut rand::thread_rng();
+        // Initialize the VM.
+        let store = ConsensusStore::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::open(None)?;
+        let vm = VM::from(store)?;
+
+        for _ in 0..num_transactions {
+            // Create a new transaction.
+            let query = Some(Query::from(&endpoint));
+            let transaction =
+                vm.execute(&private_key, ("credits.aleo", "transfer_public"), inputs.iter(), None, 0, query, rng)?;
+            // Broadcast the transaction.
+            ureq::post(&format!("{endpoint}/testnet3/transaction/broadcast")).send_json(&transaction)?;
+        }
+        Ok(())
+    }
+}
+
+/// The outcome of a step of a scenario.
+struct StepOutcome {
+    /// The description of the step.
+    step: String,
+    /// The time taken by the step.
+    elapsed: Duration,
+    /// The summary of the step, or `None` if the step was skipped.
+    outcome: Option<Result<String>>,
+}
+
+impl fmt::Display for StepOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let elapsed = format!("({:.1}s)", self.elapsed.as_secs_f64()).dimmed();
+        match &self.outcome {
+            Some(Ok(summary)) => write!(f, "✅ {} - {summary} {elapsed}", self.step.bold()),
+            Some(Err(error)) => write!(f, "❌ {} - {error} {elapsed}", self.step.bold()),
+            None => write!(f, "⏭️  {} - skipped", self.step.bold()),
+        }
+    }
+}
+
+/// The pass/fail report of a scenario.
+struct Report {
+    /// The name of the scenario.
+    name: String,
+    /// The outcomes of the steps, in order.
+    outcomes: Vec<StepOutcome>,
+}
+
+impl Report {
+    /// Initializes a new report for the given scenario.
+    fn new(name: String) -> Self {
+        Self { name, outcomes: vec![] }
+    }
+
+    /// Appends the outcome of the given step.
+    fn push(&mut self, step: &Step, elapsed: Duration, outcome: Option<Result<String>>) {
+        self.outcomes.push(StepOutcome { step: step.to_string(), elapsed, outcome });
+    }
+
+    /// Returns `true` if no step has failed.
+    fn is_passing(&self) -> bool {
+        self.outcomes.iter().all(|outcome| matches!(outcome.outcome, Some(Ok(_))))
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Count the passed steps.
+        let num_passed = self.outcomes.iter().filter(|outcome| matches!(outcome.outcome, Some(Ok(_)))).count();
+        let status = match self.is_passing() {
+            true => "PASSED".green(),
+            false => "FAILED".red(),
+        };
+        writeln!(f, "Scenario '{}' {status} ({num_passed}/{} steps passed)\n", self.name.bold(), self.outcomes.len())?;
+        for outcome in &self.outcomes {
+            writeln!(f, "  {outcome}")?;
+        }
+        Ok(())
+    }
+}
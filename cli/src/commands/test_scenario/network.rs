@@ -0,0 +1,209 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::CurrentNetwork;
+use crate::commands::Clean;
+use snarkos_node::bft::{helpers::ConsensusSnapshot, MEMORY_POOL_PORT};
+use snarkvm::prelude::{Address, Network};
+
+use anyhow::{anyhow, bail, Result};
+use std::{
+    path::PathBuf,
+    process::{Child, Command, Stdio},
+};
+
+/// A local development network of validators, each running in a child process of this executable.
+pub struct LocalNetwork {
+    /// The directory containing the logs of the validators.
+    log_dir: PathBuf,
+    /// The processes of the validators, or `None` for stopped validators.
+    validators: Vec<Option<Child>>,
+}
+
+impl LocalNetwork {
+    /// Cleans the development ledgers, and starts a local network with the given number of validators.
+    pub fn start(num_validators: u16, log_dir: PathBuf) -> Result<Self> {
+        // Ensure the log directory exists.
+        std::fs::create_dir_all(&log_dir)?;
+        // Initialize the network, with all of the validators stopped.
+        let mut network = Self { log_dir, validators: (0..num_validators).map(|_| None).collect() };
+        for id in 0..num_validators {
+            // Remove the ledger of the validator, so the network starts from genesis.
+            Clean::remove_ledger(CurrentNetwork::ID, Some(id))?;
+            // Start the validator.
+            network.restart(id)?;
+        }
+        Ok(network)
+    }
+
+    /// Returns the number of validators in the network.
+    pub fn num_validators(&self) -> u16 {
+        self.validators.len() as u16
+    }
+
+    /// Returns the directory containing the logs of the validators.
+    pub const fn log_dir(&self) -> &PathBuf {
+        &self.log_dir
+    }
+
+    /// Returns `true` if the given validator is running.
+    pub fn is_running(&self, id: u16) -> bool {
+        matches!(self.validators.get(id as usize), Some(Some(_)))
+    }
+
+    /// Returns the REST endpoint of the given validator.
+    pub fn rest_endpoint(id: u16) -> String {
+        format!("http://127.0.0.1:{}", 3030 + id)
+    }
+
+    /// Returns the path to the peer deny list of the given validator.
+    fn deny_list_path(&self, id: u16) -> PathBuf {
+        self.log_dir.join(format!("deny-{id}"))
+    }
+
+    /// Starts the process of the given validator.
+    fn spawn(&self, id: u16) -> Result<Child> {
+        // Retrieve the path to this executable.
+        let executable = std::env::current_exe()?;
+        // Start the validator in development mode, logging to the log directory.
+        let child = Command::new(executable)
+            .arg("start")
+            .arg("--nodisplay")
+            .args(["--dev", &id.to_string()])
+            .args(["--dev-num-validators", &self.num_validators().to_string()])
+            .arg("--validator")
+            .arg("--peer-deny-list")
+            .arg(self.deny_list_path(id))
+            .arg("--logfile")
+            .arg(self.log_dir.join(format!("validator-{id}.log")))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+        Ok(child)
+    }
+
+    /// Stops the given validator.
+    pub fn kill(&mut self, id: u16) -> Result<()> {
+        // Retrieve the process of the validator.
+        let Some(mut child) = self.validators.get_mut(id as usize).and_then(Option::take) else {
+            bail!("Validator {id} is not running")
+        };
+        // Stop the validator.
+        child.kill()?;
+        child.wait()?;
+        Ok(())
+    }
+
+    /// Restarts the given (stopped) validator.
+    pub fn restart(&mut self, id: u16) -> Result<()> {
+        // Ensure the validator is stopped.
+        match self.validators.get(id as usize) {
+            Some(None) => (),
+            Some(Some(_)) => bail!("Validator {id} is already running"),
+            None => bail!("Validator {id} does not exist"),
+        }
+        // Start the validator.
+        let child = self.spawn(id)?;
+        self.validators[id as usize] = Some(child);
+        Ok(())
+    }
+
+    /// Partitions the validators into the given groups, by blocking the traffic between the groups.
+    ///
+    /// Each validator refuses the gateway and router addresses of the validators in the other groups,
+    /// so every group stays live, but only communicates within itself until the partition heals.
+    pub fn partition(&self, groups: &[Vec<u16>]) -> Result<()> {
+        for (index, group) in groups.iter().enumerate() {
+            // Collect the addresses of the validators in the other groups.
+            let denied = groups
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| *other != index)
+                .flat_map(|(_, other_group)| other_group.iter())
+                .flat_map(|id| [format!("127.0.0.1:{}", MEMORY_POOL_PORT + id), format!("127.0.0.1:{}", 4130 + id)])
+                .collect::<Vec<_>>();
+            // Write the addresses to the deny lists of the validators in this group.
+            for id in group {
+                std::fs::write(self.deny_list_path(*id), denied.join("\n"))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Heals the partition, by clearing the deny lists of all of the validators.
+    pub fn heal(&self) -> Result<()> {
+        for id in 0..self.num_validators() {
+            std::fs::write(self.deny_list_path(id), "")?;
+        }
+        Ok(())
+    }
+
+    /// Returns the latest block height of the given validator.
+    pub fn height(&self, id: u16) -> Result<u32> {
+        let endpoint = format!("{}/testnet3/latest/height", Self::rest_endpoint(id));
+        Ok(ureq::get(&endpoint).call()?.into_json()?)
+    }
+
+    /// Returns the latest block heights of the running validators that are reachable.
+    pub fn heights(&self) -> Vec<(u16, u32)> {
+        (0..self.num_validators())
+            .filter(|id| self.is_running(*id))
+            .filter_map(|id| self.height(id).ok().map(|height| (id, height)))
+            .collect()
+    }
+
+    /// Returns the maximum block height among the running validators, or `None` if none are reachable.
+    pub fn max_height(&self) -> Option<u32> {
+        self.heights().into_iter().map(|(_, height)| height).max()
+    }
+
+    /// Returns the validator that is the leader of the current round.
+    pub fn leader(&self) -> Result<u16> {
+        // Retrieve the running validators.
+        let running = (0..self.num_validators()).filter(|id| self.is_running(*id)).collect::<Vec<_>>();
+        // Retrieve the current leader from the first running validator that reports one.
+        let leader = running
+            .iter()
+            .find_map(|id| {
+                let endpoint = format!("{}/testnet3/debug/consensusState", Self::rest_endpoint(*id));
+                let snapshot: ConsensusSnapshot<CurrentNetwork> = ureq::get(&endpoint).call().ok()?.into_json().ok()?;
+                snapshot.leader
+            })
+            .ok_or_else(|| anyhow!("No running validator reported a leader"))?;
+        // Find the validator with the leader address.
+        for id in running {
+            let endpoint = format!("{}/testnet3/node/address", Self::rest_endpoint(id));
+            let address: Address<CurrentNetwork> = ureq::get(&endpoint).call()?.into_json()?;
+            if address == leader {
+                return Ok(id);
+            }
+        }
+        bail!("The leader {leader} is not a running validator")
+    }
+
+    /// Stops all of the validators.
+    pub fn shutdown(&mut self) {
+        // Stop the validators.
+        for id in 0..self.num_validators() {
+            let _ = self.kill(id);
+        }
+    }
+}
+
+impl Drop for LocalNetwork {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
@@ -0,0 +1,171 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::commands::DEVELOPMENT_MODE_NUM_GENESIS_COMMITTEE_MEMBERS;
+
+use anyhow::{ensure, Result};
+use indexmap::IndexSet;
+use serde::Deserialize;
+use std::{fmt, path::Path};
+
+/// A scripted scenario, executed against a local development network.
+///
+/// ```yaml
+/// name: partition-and-leader-failure
+/// validators: 4
+/// steps:
+///   - wait_for_height: { height: 5, timeout: 120 }
+///   - partition: { groups: [[0, 1], [2, 3]], duration: 30 }
+///   - kill_leader
+///   - submit_transactions: 1000
+///   - assert_height_progress: { blocks: 5, within: 120 }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    /// The name of the scenario.
+    pub name: String,
+    /// The number of validators in the local network.
+    pub validators: u16,
+    /// The steps of the scenario, executed in order.
+    pub steps: Vec<Step>,
+}
+
+/// A step of a scenario.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Step {
+    /// Pauses the scenario for the given number of seconds.
+    Sleep(u64),
+    /// Waits up to `timeout` seconds for every running validator to reach the given block height.
+    WaitForHeight { height: u32, timeout: u64 },
+    /// Partitions the validators into the given groups for the given number of seconds.
+    ///
+    /// The partition is simulated with per-node peer deny lists, so that every group stays live,
+    /// but the groups neither send nor receive messages from each other until the partition heals.
+    Partition { groups: Vec<Vec<u16>>, duration: u64 },
+    /// Stops the given validator.
+    Kill(u16),
+    /// Stops the validator that is the leader of the current round.
+    KillLeader,
+    /// Restarts the given (stopped) validator.
+    Restart(u16),
+    /// Submits the given number of `credits.aleo/transfer_public` transactions to the network.
+    SubmitTransactions(u32),
+    /// Asserts that the network advances by the given number of blocks within `within` seconds.
+    AssertHeightProgress { blocks: u32, within: u64 },
+}
+
+impl Scenario {
+    /// Loads the scenario from the given YAML file.
+    pub fn load(path: &Path) -> Result<Self> {
+        // Read the scenario from the file.
+        let scenario = std::fs::read_to_string(path)?;
+        // Parse the scenario.
+        Self::from_yaml(&scenario)
+    }
+
+    /// Parses and validates the given YAML-encoded scenario.
+    pub fn from_yaml(scenario: &str) -> Result<Self> {
+        // Deserialize the scenario.
+        let scenario: Self = serde_yaml::from_str(scenario)?;
+        // Ensure the network is large enough for the development genesis block.
+        ensure!(
+            scenario.validators >= DEVELOPMENT_MODE_NUM_GENESIS_COMMITTEE_MEMBERS,
+            "The scenario requires at least {DEVELOPMENT_MODE_NUM_GENESIS_COMMITTEE_MEMBERS} validators"
+        );
+        // Ensure the scenario has at least one step.
+        ensure!(!scenario.steps.is_empty(), "The scenario '{}' has no steps", scenario.name);
+        // Ensure each step refers to existing validators.
+        for step in &scenario.steps {
+            let validators = match step {
+                Step::Kill(id) | Step::Restart(id) => vec![*id],
+                Step::Partition { groups, .. } => {
+                    // Ensure the partition has at least two non-empty groups.
+                    ensure!(
+                        groups.len() >= 2 && groups.iter().all(|group| !group.is_empty()),
+                        "The partition '{step}' must have at least two non-empty groups"
+                    );
+                    // Ensure the groups are disjoint.
+                    let validators = groups.iter().flatten().copied().collect::<Vec<_>>();
+                    let num_unique = validators.iter().collect::<IndexSet<_>>().len();
+                    ensure!(num_unique == validators.len(), "The groups of the partition '{step}' overlap");
+                    validators
+                }
+                _ => vec![],
+            };
+            for id in validators {
+                ensure!(id < scenario.validators, "The step '{step}' refers to a nonexistent validator {id}");
+            }
+        }
+        Ok(scenario)
+    }
+}
+
+impl fmt::Display for Step {
+    /// Formats the step as a short human-readable description.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Sleep(seconds) => write!(f, "sleep {seconds}s"),
+            Self::WaitForHeight { height, timeout } => write!(f, "wait for height {height} (timeout {timeout}s)"),
+            Self::Partition { groups, duration } => write!(f, "partition {groups:?} for {duration}s"),
+            Self::Kill(id) => write!(f, "kill validator {id}"),
+            Self::KillLeader => write!(f, "kill leader"),
+            Self::Restart(id) => write!(f, "restart validator {id}"),
+            Self::SubmitTransactions(num_transactions) => write!(f, "submit {num_transactions} transactions"),
+            Self::AssertHeightProgress { blocks, within } => write!(f, "assert {blocks} blocks within {within}s"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scenario_from_yaml() {
+        let scenario = Scenario::from_yaml(
+            r"
+name: partition
+validators: 4
+steps:
+  - wait_for_height: { height: 5, timeout: 120 }
+  - partition: { groups: [[0, 1], [2, 3]], duration: 30 }
+  - kill_leader
+  - restart: 1
+  - submit_transactions: 1000
+  - assert_height_progress: { blocks: 5, within: 120 }
+",
+        )
+        .unwrap();
+        assert_eq!(scenario.name, "partition");
+        assert_eq!(scenario.validators, 4);
+        assert_eq!(scenario.steps, vec![
+            Step::WaitForHeight { height: 5, timeout: 120 },
+            Step::Partition { groups: vec![vec![0, 1], vec![2, 3]], duration: 30 },
+            Step::KillLeader,
+            Step::Restart(1),
+            Step::SubmitTransactions(1000),
+            Step::AssertHeightProgress { blocks: 5, within: 120 },
+        ]);
+
+        // Ensure invalid scenarios are rejected.
+        assert!(Scenario::from_yaml("name: small\nvalidators: 3\nsteps: [kill_leader]").is_err());
+        assert!(Scenario::from_yaml("name: empty\nvalidators: 4\nsteps: []").is_err());
+        assert!(Scenario::from_yaml("name: unknown\nvalidators: 4\nsteps: [kill: 4]").is_err());
+        let overlap = "name: overlap\nvalidators: 4\nsteps: [partition: { groups: [[0, 1], [1]], duration: 1 }]";
+        assert!(Scenario::from_yaml(overlap).is_err());
+        assert!(Scenario::from_yaml("name: typo\nvalidators: 4\nsteps: [kill_leaders]").is_err());
+    }
+}
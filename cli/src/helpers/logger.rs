@@ -13,17 +13,59 @@
 // limitations under the License.
 
 use crate::helpers::LogWriter;
+use snarkos_node_rest::LogVerbosityHandler;
 
 use crossterm::tty::IsTty;
-use std::{fs::File, io, path::Path};
+use std::{fs::File, io, path::Path, sync::Arc};
 use tokio::sync::mpsc;
 use tracing_subscriber::{
+    filter::LevelFilter,
     layer::{Layer, SubscriberExt},
+    reload,
     util::SubscriberInitExt,
     EnvFilter,
 };
 
-/// Initializes the logger.
+/// Returns the log filter for the given verbosity.
+///
+/// The filter is built from the `RUST_LOG` environment variable, with the level of the verbosity as its default,
+/// and the directives of the verbosity are layered on top of it, except for the targets that `RUST_LOG` sets.
+fn log_filter(verbosity: u8) -> EnvFilter {
+    let level = match verbosity {
+        0 => LevelFilter::INFO,
+        1 => LevelFilter::DEBUG,
+        2.. => LevelFilter::TRACE,
+    };
+    let env = std::env::var(EnvFilter::DEFAULT_ENV).unwrap_or_default();
+    let filter = EnvFilter::builder().with_default_directive(level.into()).parse_lossy(&env);
+
+    // Filter out undesirable logs.
+    let mut directives = vec!["mio=off", "tokio_util=off", "hyper=off", "reqwest=off", "want=off", "warp=off"];
+    directives.push(if verbosity >= 2 { "snarkos_node_sync=trace" } else { "snarkos_node_sync=debug" });
+    directives.push(if verbosity >= 3 { "snarkos_node_bft=trace" } else { "snarkos_node_bft=debug" });
+    directives.push(if verbosity >= 4 { "snarkos_node_bft::gateway=trace" } else { "snarkos_node_bft::gateway=debug" });
+    directives.push(if verbosity >= 5 { "snarkos_node_router=trace" } else { "snarkos_node_router=debug" });
+    directives.push(if verbosity >= 6 { "snarkos_node_tcp=trace" } else { "snarkos_node_tcp=off" });
+
+    // Note: The directives of `RUST_LOG` take precedence over the directives of the verbosity for the same target.
+    let env_targets = directive_targets(&env);
+    directives
+        .into_iter()
+        .filter(|directive| !directive_targets(directive).iter().any(|target| env_targets.contains(target)))
+        .fold(filter, |filter, directive| filter.add_directive(directive.parse().unwrap()))
+}
+
+/// Returns the targets of the given comma-separated log directives, e.g. `snarkos_node_sync` for
+/// `snarkos_node_sync=trace`.
+fn directive_targets(directives: &str) -> Vec<&str> {
+    directives
+        .split(',')
+        .filter_map(|directive| directive.trim().split(['=', '[']).next())
+        .filter(|target| !target.is_empty())
+        .collect()
+}
+
+/// Initializes the logger, and returns the receiver of the logs, and the function that sets the verbosity of the logs.
 ///
 /// ```ignore
 /// 0 => info
@@ -34,54 +76,19 @@ use tracing_subscriber::{
 /// 5 => info, debug, trace, snarkos_node_router=trace
 /// 6 => info, debug, trace, snarkos_node_tcp=trace
 /// ```
-pub fn initialize_logger<P: AsRef<Path>>(verbosity: u8, nodisplay: bool, logfile: P) -> mpsc::Receiver<Vec<u8>> {
-    match verbosity {
-        0 => std::env::set_var("RUST_LOG", "info"),
-        1 => std::env::set_var("RUST_LOG", "debug"),
-        2.. => std::env::set_var("RUST_LOG", "trace"),
-    };
-
+pub fn initialize_logger<P: AsRef<Path>>(
+    verbosity: u8,
+    nodisplay: bool,
+    logfile: P,
+) -> (mpsc::Receiver<Vec<u8>>, LogVerbosityHandler) {
     // Filter out undesirable logs. (unfortunately EnvFilter cannot be cloned)
-    let [filter, filter2] = std::array::from_fn(|_| {
-        let filter = EnvFilter::from_default_env()
-            .add_directive("mio=off".parse().unwrap())
-            .add_directive("tokio_util=off".parse().unwrap())
-            .add_directive("hyper=off".parse().unwrap())
-            .add_directive("reqwest=off".parse().unwrap())
-            .add_directive("want=off".parse().unwrap())
-            .add_directive("warp=off".parse().unwrap());
-
-        let filter = if verbosity >= 2 {
-            filter.add_directive("snarkos_node_sync=trace".parse().unwrap())
-        } else {
-            filter.add_directive("snarkos_node_sync=debug".parse().unwrap())
-        };
-
-        let filter = if verbosity >= 3 {
-            filter
-                .add_directive("snarkos_node_bft=trace".parse().unwrap())
-                .add_directive("snarkos_node_bft::gateway=debug".parse().unwrap())
-        } else {
-            filter.add_directive("snarkos_node_bft=debug".parse().unwrap())
-        };
-
-        let filter = if verbosity >= 4 {
-            filter.add_directive("snarkos_node_bft::gateway=trace".parse().unwrap())
-        } else {
-            filter.add_directive("snarkos_node_bft::gateway=debug".parse().unwrap())
-        };
-
-        let filter = if verbosity >= 5 {
-            filter.add_directive("snarkos_node_router=trace".parse().unwrap())
-        } else {
-            filter.add_directive("snarkos_node_router=debug".parse().unwrap())
-        };
-
-        if verbosity >= 6 {
-            filter.add_directive("snarkos_node_tcp=trace".parse().unwrap())
-        } else {
-            filter.add_directive("snarkos_node_tcp=off".parse().unwrap())
-        }
+    // Note: The filters are reloadable, so the verbosity can be changed through the admin server.
+    let (filter, filter_handle) = reload::Layer::new(log_filter(verbosity));
+    let (filter2, filter2_handle) = reload::Layer::new(log_filter(verbosity));
+    let log_verbosity: LogVerbosityHandler = Arc::new(move |verbosity| {
+        filter_handle.reload(log_filter(verbosity))?;
+        filter2_handle.reload(log_filter(verbosity))?;
+        Ok(())
     });
 
     // Create the directories tree for a logfile if it doesn't exist.
@@ -123,7 +130,7 @@ pub fn initialize_logger<P: AsRef<Path>>(verbosity: u8, nodisplay: bool, logfile
         )
         .try_init();
 
-    log_receiver
+    (log_receiver, log_verbosity)
 }
 
 /// Returns the welcome message as a string.
@@ -0,0 +1,74 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CatchUpRequest {
+    /// The last committed round of the requester.
+    pub round: u64,
+}
+
+impl CatchUpRequest {
+    /// Initializes a new catch-up request event.
+    pub const fn new(round: u64) -> Self {
+        Self { round }
+    }
+}
+
+impl EventTrait for CatchUpRequest {
+    /// Returns the event name.
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        "CatchUpRequest".into()
+    }
+}
+
+impl ToBytes for CatchUpRequest {
+    fn write_le<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        self.round.write_le(&mut writer)?;
+        Ok(())
+    }
+}
+
+impl FromBytes for CatchUpRequest {
+    fn read_le<R: Read>(mut reader: R) -> IoResult<Self> {
+        let round = u64::read_le(&mut reader)?;
+
+        Ok(Self { round })
+    }
+}
+
+#[cfg(test)]
+pub mod prop_tests {
+    use crate::CatchUpRequest;
+    use snarkvm::console::prelude::{FromBytes, ToBytes};
+
+    use bytes::{Buf, BufMut, BytesMut};
+    use proptest::prelude::{any, BoxedStrategy, Strategy};
+    use test_strategy::proptest;
+
+    pub fn any_catch_up_request() -> BoxedStrategy<CatchUpRequest> {
+        any::<u64>().prop_map(CatchUpRequest::new).boxed()
+    }
+
+    #[proptest]
+    fn serialize_deserialize(#[strategy(any_catch_up_request())] original: CatchUpRequest) {
+        let mut buf = BytesMut::default().writer();
+        CatchUpRequest::write_le(&original, &mut buf).unwrap();
+
+        let deserialized = CatchUpRequest::read_le(buf.into_inner().reader()).unwrap();
+        assert_eq!(original, deserialized);
+    }
+}
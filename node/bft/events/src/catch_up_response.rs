@@ -0,0 +1,91 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CatchUpResponse<N: Network> {
+    /// The round from the catch-up request.
+    pub round: u64,
+    /// The IDs of the certificates in storage after the requested round.
+    pub certificate_ids: IndexSet<Field<N>>,
+}
+
+impl<N: Network> CatchUpResponse<N> {
+    /// Initializes a new catch-up response event.
+    pub fn new(round: u64, certificate_ids: IndexSet<Field<N>>) -> Self {
+        Self { round, certificate_ids }
+    }
+}
+
+impl<N: Network> EventTrait for CatchUpResponse<N> {
+    /// Returns the event name.
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        "CatchUpResponse".into()
+    }
+}
+
+impl<N: Network> ToBytes for CatchUpResponse<N> {
+    fn write_le<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        self.round.write_le(&mut writer)?;
+        u16::try_from(self.certificate_ids.len()).map_err(error)?.write_le(&mut writer)?;
+        for certificate_id in &self.certificate_ids {
+            certificate_id.write_le(&mut writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<N: Network> FromBytes for CatchUpResponse<N> {
+    fn read_le<R: Read>(mut reader: R) -> IoResult<Self> {
+        let round = u64::read_le(&mut reader)?;
+        let num_certificates = u16::read_le(&mut reader)?;
+        let mut certificate_ids = IndexSet::with_capacity(num_certificates as usize);
+        for _ in 0..num_certificates {
+            certificate_ids.insert(Field::read_le(&mut reader)?);
+        }
+        Ok(Self { round, certificate_ids })
+    }
+}
+
+#[cfg(test)]
+pub mod prop_tests {
+    use crate::{certificate_request::prop_tests::any_field, CatchUpResponse};
+    use snarkvm::console::prelude::{FromBytes, ToBytes};
+
+    use bytes::{Buf, BufMut, BytesMut};
+    use proptest::{
+        collection::vec,
+        prelude::{any, BoxedStrategy, Strategy},
+    };
+    use test_strategy::proptest;
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    pub fn any_catch_up_response() -> BoxedStrategy<CatchUpResponse<CurrentNetwork>> {
+        (any::<u64>(), vec(any_field(), 0..16))
+            .prop_map(|(round, ids)| CatchUpResponse::new(round, ids.into_iter().collect()))
+            .boxed()
+    }
+
+    #[proptest]
+    fn serialize_deserialize(#[strategy(any_catch_up_response())] original: CatchUpResponse<CurrentNetwork>) {
+        let mut buf = BytesMut::default().writer();
+        CatchUpResponse::write_le(&original, &mut buf).unwrap();
+
+        let deserialized = CatchUpResponse::read_le(buf.into_inner().reader()).unwrap();
+        assert_eq!(original, deserialized);
+    }
+}
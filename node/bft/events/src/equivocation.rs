@@ -0,0 +1,91 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+/// The evidence that a validator equivocated, by signing two different batch headers for the same round.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Equivocation<N: Network> {
+    pub first: Data<BatchHeader<N>>,
+    pub second: Data<BatchHeader<N>>,
+}
+
+impl<N: Network> Equivocation<N> {
+    /// Initializes a new equivocation event.
+    pub fn new(first: Data<BatchHeader<N>>, second: Data<BatchHeader<N>>) -> Self {
+        Self { first, second }
+    }
+}
+
+impl<N: Network> EventTrait for Equivocation<N> {
+    /// Returns the event name.
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        "Equivocation".into()
+    }
+}
+
+impl<N: Network> ToBytes for Equivocation<N> {
+    fn write_le<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        self.first.write_le(&mut writer)?;
+        self.second.write_le(&mut writer)?;
+        Ok(())
+    }
+}
+
+impl<N: Network> FromBytes for Equivocation<N> {
+    fn read_le<R: Read>(mut reader: R) -> IoResult<Self> {
+        let first = Data::read_le(&mut reader)?;
+        let second = Data::read_le(&mut reader)?;
+
+        Ok(Self { first, second })
+    }
+}
+
+#[cfg(test)]
+pub mod prop_tests {
+    use crate::{certificate_response::prop_tests::any_batch_header, Equivocation};
+    use snarkvm::{
+        console::prelude::{FromBytes, ToBytes},
+        ledger::committee::prop_tests::CommitteeContext,
+        prelude::narwhal::Data,
+    };
+
+    use bytes::{Buf, BufMut, BytesMut};
+    use proptest::prelude::{any, BoxedStrategy, Strategy};
+    use test_strategy::proptest;
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    pub fn any_equivocation() -> BoxedStrategy<Equivocation<CurrentNetwork>> {
+        any::<CommitteeContext>()
+            .prop_flat_map(|committee| (any_batch_header(&committee), any_batch_header(&committee)))
+            .prop_map(|(first, second)| Equivocation::new(Data::Object(first), Data::Object(second)))
+            .boxed()
+    }
+
+    #[proptest]
+    fn serialize_deserialize(#[strategy(any_equivocation())] original: Equivocation<CurrentNetwork>) {
+        let mut buf = BytesMut::default().writer();
+        Equivocation::write_le(&original, &mut buf).unwrap();
+
+        let deserialized: Equivocation<CurrentNetwork> = Equivocation::read_le(buf.into_inner().reader()).unwrap();
+        // because of the Data enum, we cannot compare the structs directly even though it derives PartialEq
+        assert_eq!(original.first.deserialize_blocking().unwrap(), deserialized.first.deserialize_blocking().unwrap());
+        assert_eq!(
+            original.second.deserialize_blocking().unwrap(),
+            deserialized.second.deserialize_blocking().unwrap()
+        );
+    }
+}
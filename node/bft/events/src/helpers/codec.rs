@@ -12,10 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::Event;
+use crate::{Event, FrameCodec};
 use snarkvm::prelude::{FromBytes, Network, ToBytes};
 
-use bytes::{Buf, BufMut, Bytes, BytesMut};
+use bytes::{Buf, Bytes, BytesMut};
 use core::marker::PhantomData;
 use rayon::{
     iter::{IndexedParallelIterator, ParallelIterator},
@@ -29,14 +29,16 @@ use tracing::*;
 /// The maximum size of an event that can be transmitted during the handshake.
 const MAX_HANDSHAKE_SIZE: usize = 1024 * 1024; // 1 MiB
 /// The maximum size of an event that can be transmitted in the network.
-const MAX_EVENT_SIZE: usize = 128 * 1024 * 1024; // 128 MiB
+pub(crate) const MAX_EVENT_SIZE: usize = 128 * 1024 * 1024; // 128 MiB
+/// The magic that prefixes every event frame.
+const EVENT_FRAME_MAGIC: u32 = u32::from_le_bytes(*b"AEVT");
 
 /// The type of noise handshake to use for network encryption.
 pub const NOISE_HANDSHAKE_TYPE: &str = "Noise_XX_25519_ChaChaPoly_BLAKE2s";
 
 /// The codec used to decode and encode network `Event`s.
 pub struct EventCodec<N: Network> {
-    codec: LengthDelimitedCodec,
+    codec: FrameCodec,
     _phantom: PhantomData<N>,
 }
 
@@ -51,7 +53,7 @@ impl<N: Network> EventCodec<N> {
 impl<N: Network> Default for EventCodec<N> {
     fn default() -> Self {
         Self {
-            codec: LengthDelimitedCodec::builder().max_frame_length(MAX_EVENT_SIZE).little_endian().new_codec(),
+            codec: FrameCodec::new(EVENT_FRAME_MAGIC, MAX_EVENT_SIZE, Event::<N>::max_size),
             _phantom: Default::default(),
         }
     }
@@ -61,15 +63,8 @@ impl<N: Network> Encoder<Event<N>> for EventCodec<N> {
     type Error = std::io::Error;
 
     fn encode(&mut self, event: Event<N>, dst: &mut BytesMut) -> Result<(), Self::Error> {
-        // Serialize the payload directly into dst.
-        event
-            .write_le(&mut dst.writer())
-            // This error should never happen, the conversion is for greater compatibility.
-            .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "serialization error"))?;
-
-        let serialized_event = dst.split_to(dst.len()).freeze();
-
-        self.codec.encode(serialized_event, dst)
+        // Serialize the event into a frame.
+        self.codec.encode(&event, dst)
     }
 }
 
@@ -0,0 +1,290 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::ToBytes;
+
+use bytes::{Buf, BufMut, BytesMut};
+use std::io;
+use tokio_util::codec::Decoder;
+
+/// The size of the frame header: the magic (4 bytes), the payload length (4 bytes), and the payload checksum (4 bytes).
+pub const FRAME_HEADER_SIZE: usize = 12; // bytes
+/// The size of the ID that prefixes every payload.
+const PAYLOAD_ID_SIZE: usize = 2; // bytes
+
+/// The maximum size of a small payload (e.g. requests and control messages).
+pub const MAX_SMALL_PAYLOAD_SIZE: usize = 64 * 1024; // 64 KiB
+/// The maximum size of a medium payload (e.g. certificates, transactions, and solutions).
+pub const MAX_MEDIUM_PAYLOAD_SIZE: usize = 16 * 1024 * 1024; // 16 MiB
+
+/// The codec used to frame the payloads of network messages.
+///
+/// Each frame consists of a header with the magic, the payload length, and the CRC-32 checksum of the payload,
+/// followed by the payload, which begins with the (little-endian) ID of the message. The length is checked
+/// against the maximum size of the message type as soon as the header and ID are received, so that oversized
+/// frames are rejected before they are buffered.
+#[derive(Clone, Debug)]
+pub struct FrameCodec {
+    /// The magic that prefixes every frame.
+    magic: u32,
+    /// The maximum size of a payload, regardless of the message type.
+    max_frame_length: usize,
+    /// The function returning the maximum payload size for the given message ID, or `None` if the ID is unknown.
+    max_payload_size: fn(u16) -> Option<usize>,
+}
+
+impl FrameCodec {
+    /// Initializes a new frame codec.
+    pub fn new(magic: u32, max_frame_length: usize, max_payload_size: fn(u16) -> Option<usize>) -> Self {
+        Self { magic, max_frame_length, max_payload_size }
+    }
+
+    /// Sets the maximum size of a payload, regardless of the message type.
+    pub fn set_max_frame_length(&mut self, max_frame_length: usize) {
+        self.max_frame_length = max_frame_length;
+    }
+
+    /// Returns the maximum payload size for the given message ID.
+    fn max_payload_size(&self, id: u16) -> io::Result<usize> {
+        match (self.max_payload_size)(id) {
+            Some(max_size) => Ok(max_size.min(self.max_frame_length)),
+            None => Err(io::Error::new(io::ErrorKind::InvalidData, format!("Unknown message ID {id}"))),
+        }
+    }
+
+    /// Serializes the given payload into a frame, and appends it to the destination.
+    ///
+    /// If the payload cannot be framed, the destination is left as it was, so that it only holds complete frames.
+    pub fn encode<T: ToBytes>(&self, payload: &T, dst: &mut BytesMut) -> io::Result<()> {
+        let start = dst.len();
+        let result = self.encode_frame(payload, dst, start);
+        // Remove the partial frame on every error path.
+        if result.is_err() {
+            dst.truncate(start);
+        }
+        result
+    }
+
+    /// Serializes the given payload into a frame, which starts at the given offset of the destination.
+    fn encode_frame<T: ToBytes>(&self, payload: &T, dst: &mut BytesMut, start: usize) -> io::Result<()> {
+        // Reserve the space for the frame header.
+        dst.put_bytes(0, FRAME_HEADER_SIZE);
+        // Serialize the payload directly into dst.
+        payload
+            .write_le(&mut dst.writer())
+            // This error should never happen, the conversion is for greater compatibility.
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "serialization error"))?;
+
+        // Retrieve the serialized payload.
+        let payload = &dst[start + FRAME_HEADER_SIZE..];
+        if payload.len() < PAYLOAD_ID_SIZE {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "missing message ID"));
+        }
+        // Ensure the payload does not exceed the maximum size for its message type.
+        let id = u16::from_le_bytes([payload[0], payload[1]]);
+        let max_size = self.max_payload_size(id)?;
+        if payload.len() > max_size {
+            let error = format!("Message {id} is {} bytes, exceeding the limit of {max_size} bytes", payload.len());
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, error));
+        }
+        // Compute the payload length and checksum.
+        let length = payload.len() as u32;
+        let checksum = crc32fast::hash(payload);
+
+        // Write the frame header.
+        let mut header = &mut dst[start..start + FRAME_HEADER_SIZE];
+        header.put_u32_le(self.magic);
+        header.put_u32_le(length);
+        header.put_u32_le(checksum);
+        Ok(())
+    }
+}
+
+impl Decoder for FrameCodec {
+    type Error = io::Error;
+    type Item = BytesMut;
+
+    fn decode(&mut self, source: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        // Ensure the frame header and the message ID have been received.
+        if source.len() < FRAME_HEADER_SIZE + PAYLOAD_ID_SIZE {
+            return Ok(None);
+        }
+
+        // Read the frame header, without consuming it.
+        let mut header = &source[..FRAME_HEADER_SIZE + PAYLOAD_ID_SIZE];
+        let magic = header.get_u32_le();
+        let length = header.get_u32_le() as usize;
+        let checksum = header.get_u32_le();
+        let id = header.get_u16_le();
+
+        // Ensure the magic is correct.
+        if magic != self.magic {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("Invalid frame magic {magic:#010x}")));
+        }
+        // Ensure the frame does not exceed the maximum size for its message type, before buffering it.
+        let max_size = self.max_payload_size(id)?;
+        if length < PAYLOAD_ID_SIZE || length > max_size {
+            let error = format!("Frame for message {id} is {length} bytes, outside the limit of {max_size} bytes");
+            return Err(io::Error::new(io::ErrorKind::InvalidData, error));
+        }
+
+        // Ensure the entire frame has been received.
+        let frame_length = FRAME_HEADER_SIZE + length;
+        if source.len() < frame_length {
+            // Reserve the space for the remainder of the frame.
+            source.reserve(frame_length - source.len());
+            return Ok(None);
+        }
+
+        // Split off the payload.
+        source.advance(FRAME_HEADER_SIZE);
+        let payload = source.split_to(length);
+        // Ensure the checksum is correct.
+        if crc32fast::hash(&payload) != checksum {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("Invalid checksum for message {id}")));
+        }
+        Ok(Some(payload))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAGIC: u32 = 0x1234_5678;
+
+    /// A test payload, consisting of the given message ID and data.
+    struct Payload(u16, Vec<u8>);
+
+    impl ToBytes for Payload {
+        fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+            writer.write_all(&self.0.to_le_bytes())?;
+            writer.write_all(&self.1)
+        }
+    }
+
+    /// A test payload, whose serialization fails after the given message ID, or which is empty if there is no ID.
+    struct FaultyPayload(Option<u16>);
+
+    impl ToBytes for FaultyPayload {
+        fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+            match self.0 {
+                Some(id) => {
+                    writer.write_all(&id.to_le_bytes())?;
+                    Err(io::Error::new(io::ErrorKind::Other, "faulty payload"))
+                }
+                None => Ok(()),
+            }
+        }
+    }
+
+    fn max_payload_size(id: u16) -> Option<usize> {
+        match id {
+            0 => Some(16),
+            1 => Some(1024),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_frame_roundtrip() {
+        let mut codec = FrameCodec::new(MAGIC, 512, max_payload_size);
+
+        // Encode a frame.
+        let mut buffer = BytesMut::new();
+        codec.encode(&Payload(1, vec![7; 100]), &mut buffer).unwrap();
+        assert_eq!(buffer.len(), FRAME_HEADER_SIZE + 102);
+
+        // Ensure a partial frame is not decoded.
+        let mut partial = BytesMut::from(&buffer[..50]);
+        assert!(codec.decode(&mut partial).unwrap().is_none());
+
+        // Ensure the full frame is decoded.
+        let payload = codec.decode(&mut buffer).unwrap().unwrap();
+        assert_eq!(&payload[..2], &1u16.to_le_bytes());
+        assert_eq!(&payload[2..], &[7; 100]);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_frame_limits() {
+        let mut codec = FrameCodec::new(MAGIC, 512, max_payload_size);
+
+        // Ensure payloads exceeding the limit of their type, or the codec, are not encoded.
+        assert!(codec.encode(&Payload(0, vec![0; 15]), &mut BytesMut::new()).is_err());
+        assert!(codec.encode(&Payload(1, vec![0; 511]), &mut BytesMut::new()).is_err());
+        assert!(codec.encode(&Payload(2, vec![]), &mut BytesMut::new()).is_err());
+
+        // Ensure an oversized frame is rejected from its header alone.
+        let mut buffer = BytesMut::new();
+        buffer.put_u32_le(MAGIC);
+        buffer.put_u32_le(u32::MAX);
+        buffer.put_u32_le(0);
+        buffer.put_u16_le(1);
+        assert!(codec.decode(&mut buffer).is_err());
+        assert!(buffer.capacity() < 1024);
+
+        // Ensure a frame with an unknown message ID is rejected.
+        let mut buffer = BytesMut::new();
+        buffer.put_u32_le(MAGIC);
+        buffer.put_u32_le(2);
+        buffer.put_u32_le(0);
+        buffer.put_u16_le(2);
+        assert!(codec.decode(&mut buffer).is_err());
+    }
+
+    #[test]
+    fn test_frame_encode_error() {
+        let mut codec = FrameCodec::new(MAGIC, 512, max_payload_size);
+
+        // Encode a valid frame.
+        let mut buffer = BytesMut::new();
+        codec.encode(&Payload(1, vec![7; 100]), &mut buffer).unwrap();
+        let frame = buffer.clone();
+
+        // Ensure the partial frame is removed on every error path, leaving the valid frame intact.
+        // The errors are: a failed serialization, a missing message ID, an oversized payload, and an unknown ID.
+        assert!(codec.encode(&FaultyPayload(Some(1)), &mut buffer).is_err());
+        assert_eq!(buffer, frame);
+        assert!(codec.encode(&FaultyPayload(None), &mut buffer).is_err());
+        assert_eq!(buffer, frame);
+        assert!(codec.encode(&Payload(0, vec![0; 15]), &mut buffer).is_err());
+        assert_eq!(buffer, frame);
+        assert!(codec.encode(&Payload(2, vec![]), &mut buffer).is_err());
+        assert_eq!(buffer, frame);
+
+        // Ensure the valid frame is still decoded.
+        let payload = codec.decode(&mut buffer).unwrap().unwrap();
+        assert_eq!(&payload[2..], &[7; 100]);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_frame_corruption() {
+        let mut codec = FrameCodec::new(MAGIC, 512, max_payload_size);
+
+        let mut frame = BytesMut::new();
+        codec.encode(&Payload(1, vec![7; 100]), &mut frame).unwrap();
+
+        // Ensure a frame with an invalid magic is rejected.
+        let mut buffer = frame.clone();
+        buffer[0] ^= 1;
+        assert!(codec.decode(&mut buffer).is_err());
+
+        // Ensure a frame with a corrupted payload is rejected.
+        let mut buffer = frame.clone();
+        buffer[FRAME_HEADER_SIZE + 10] ^= 1;
+        assert!(codec.decode(&mut buffer).is_err());
+    }
+}
@@ -14,3 +14,6 @@
 
 mod codec;
 pub use codec::*;
+
+mod frame;
+pub use frame::*;
@@ -29,6 +29,12 @@ pub use block_request::BlockRequest;
 mod block_response;
 pub use block_response::{BlockResponse, DataBlocks};
 
+mod catch_up_request;
+pub use catch_up_request::CatchUpRequest;
+
+mod catch_up_response;
+pub use catch_up_response::CatchUpResponse;
+
 mod certificate_request;
 pub use certificate_request::CertificateRequest;
 
@@ -44,6 +50,9 @@ pub use challenge_response::ChallengeResponse;
 mod disconnect;
 pub use disconnect::{Disconnect, DisconnectReason};
 
+mod equivocation;
+pub use equivocation::Equivocation;
+
 mod helpers;
 pub use helpers::*;
 
@@ -62,6 +71,9 @@ pub use validators_request::ValidatorsRequest;
 mod validators_response;
 pub use validators_response::ValidatorsResponse;
 
+mod worker_batch;
+pub use worker_batch::WorkerBatch;
+
 mod worker_ping;
 pub use worker_ping::WorkerPing;
 
@@ -97,16 +109,20 @@ pub enum Event<N: Network> {
     BatchCertified(BatchCertified<N>),
     BlockRequest(BlockRequest),
     BlockResponse(BlockResponse<N>),
+    CatchUpRequest(CatchUpRequest),
+    CatchUpResponse(CatchUpResponse<N>),
     CertificateRequest(CertificateRequest<N>),
     CertificateResponse(CertificateResponse<N>),
     ChallengeRequest(ChallengeRequest<N>),
     ChallengeResponse(ChallengeResponse<N>),
     Disconnect(Disconnect),
+    Equivocation(Equivocation<N>),
     PrimaryPing(PrimaryPing<N>),
     TransmissionRequest(TransmissionRequest<N>),
     TransmissionResponse(TransmissionResponse<N>),
     ValidatorsRequest(ValidatorsRequest),
     ValidatorsResponse(ValidatorsResponse<N>),
+    WorkerBatch(WorkerBatch<N>),
     WorkerPing(WorkerPing<N>),
 }
 
@@ -118,7 +134,7 @@ impl<N: Network> From<DisconnectReason> for Event<N> {
 
 impl<N: Network> Event<N> {
     /// The version of the event protocol; it can be incremented in order to force users to update.
-    pub const VERSION: u32 = 5;
+    pub const VERSION: u32 = 10;
 
     /// Returns the event name.
     #[inline]
@@ -129,16 +145,20 @@ impl<N: Network> Event<N> {
             Self::BatchCertified(event) => event.name(),
             Self::BlockRequest(event) => event.name(),
             Self::BlockResponse(event) => event.name(),
+            Self::CatchUpRequest(event) => event.name(),
+            Self::CatchUpResponse(event) => event.name(),
             Self::CertificateRequest(event) => event.name(),
             Self::CertificateResponse(event) => event.name(),
             Self::ChallengeRequest(event) => event.name(),
             Self::ChallengeResponse(event) => event.name(),
             Self::Disconnect(event) => event.name(),
+            Self::Equivocation(event) => event.name(),
             Self::PrimaryPing(event) => event.name(),
             Self::TransmissionRequest(event) => event.name(),
             Self::TransmissionResponse(event) => event.name(),
             Self::ValidatorsRequest(event) => event.name(),
             Self::ValidatorsResponse(event) => event.name(),
+            Self::WorkerBatch(event) => event.name(),
             Self::WorkerPing(event) => event.name(),
         }
     }
@@ -163,6 +183,25 @@ impl<N: Network> Event<N> {
             Self::ValidatorsRequest(..) => 13,
             Self::ValidatorsResponse(..) => 14,
             Self::WorkerPing(..) => 15,
+            Self::Equivocation(..) => 16,
+            Self::CatchUpRequest(..) => 17,
+            Self::CatchUpResponse(..) => 18,
+            Self::WorkerBatch(..) => 19,
+        }
+    }
+
+    /// Returns the maximum size in bytes of an event with the given ID, or `None` if the ID is unknown.
+    pub const fn max_size(id: u16) -> Option<usize> {
+        match id {
+            // BatchSignature, BlockRequest, CertificateRequest, ChallengeRequest, ChallengeResponse,
+            // Disconnect, TransmissionRequest, ValidatorsRequest, CatchUpRequest
+            1 | 3 | 5 | 7 | 8 | 9 | 11 | 13 | 17 => Some(MAX_SMALL_PAYLOAD_SIZE),
+            // BatchPropose, BatchCertified, CertificateResponse, PrimaryPing, TransmissionResponse,
+            // ValidatorsResponse, WorkerPing, Equivocation, CatchUpResponse, WorkerBatch
+            0 | 2 | 6 | 10 | 12 | 14 | 15 | 16 | 18 | 19 => Some(MAX_MEDIUM_PAYLOAD_SIZE),
+            // BlockResponse
+            4 => Some(MAX_EVENT_SIZE),
+            20.. => None,
         }
     }
 }
@@ -177,16 +216,20 @@ impl<N: Network> ToBytes for Event<N> {
             Self::BatchCertified(event) => event.write_le(writer),
             Self::BlockRequest(event) => event.write_le(writer),
             Self::BlockResponse(event) => event.write_le(writer),
+            Self::CatchUpRequest(event) => event.write_le(writer),
+            Self::CatchUpResponse(event) => event.write_le(writer),
             Self::CertificateRequest(event) => event.write_le(writer),
             Self::CertificateResponse(event) => event.write_le(writer),
             Self::ChallengeRequest(event) => event.write_le(writer),
             Self::ChallengeResponse(event) => event.write_le(writer),
             Self::Disconnect(event) => event.write_le(writer),
+            Self::Equivocation(event) => event.write_le(writer),
             Self::PrimaryPing(event) => event.write_le(writer),
             Self::TransmissionRequest(event) => event.write_le(writer),
             Self::TransmissionResponse(event) => event.write_le(writer),
             Self::ValidatorsRequest(event) => event.write_le(writer),
             Self::ValidatorsResponse(event) => event.write_le(writer),
+            Self::WorkerBatch(event) => event.write_le(writer),
             Self::WorkerPing(event) => event.write_le(writer),
         }
     }
@@ -215,7 +258,11 @@ impl<N: Network> FromBytes for Event<N> {
             13 => Self::ValidatorsRequest(ValidatorsRequest::read_le(reader)?),
             14 => Self::ValidatorsResponse(ValidatorsResponse::read_le(reader)?),
             15 => Self::WorkerPing(WorkerPing::read_le(reader)?),
-            16.. => return Err(error("Unknown event ID {id}")),
+            16 => Self::Equivocation(Equivocation::read_le(reader)?),
+            17 => Self::CatchUpRequest(CatchUpRequest::read_le(reader)?),
+            18 => Self::CatchUpResponse(CatchUpResponse::read_le(reader)?),
+            19 => Self::WorkerBatch(WorkerBatch::read_le(reader)?),
+            20.. => return Err(error("Unknown event ID {id}")),
         };
 
         Ok(event)
@@ -249,10 +296,14 @@ pub mod prop_tests {
         batch_signature::prop_tests::any_batch_signature,
         certificate_request::prop_tests::any_certificate_request,
         certificate_response::prop_tests::any_certificate_response,
+        catch_up_request::prop_tests::any_catch_up_request,
+        catch_up_response::prop_tests::any_catch_up_response,
         challenge_request::prop_tests::any_challenge_request,
         challenge_response::prop_tests::any_challenge_response,
+        equivocation::prop_tests::any_equivocation,
         transmission_request::prop_tests::any_transmission_request,
         transmission_response::prop_tests::any_transmission_response,
+        worker_batch::prop_tests::any_worker_batch,
         worker_ping::prop_tests::any_worker_ping,
         Disconnect,
         DisconnectReason,
@@ -301,6 +352,8 @@ pub mod prop_tests {
             any_batch_certified().prop_map(Event::BatchCertified),
             any_batch_propose().prop_map(Event::BatchPropose),
             any_batch_signature().prop_map(Event::BatchSignature),
+            any_catch_up_request().prop_map(Event::CatchUpRequest),
+            any_catch_up_response().prop_map(Event::CatchUpResponse),
             any_certificate_request().prop_map(Event::CertificateRequest),
             any_certificate_response().prop_map(Event::CertificateResponse),
             any_challenge_request().prop_map(Event::ChallengeRequest),
@@ -315,8 +368,10 @@ pub mod prop_tests {
                 any::<Selector>()
             )
                 .prop_map(|(reasons, selector)| Event::Disconnect(Disconnect::from(selector.select(reasons)))),
+            any_equivocation().prop_map(Event::Equivocation),
             any_transmission_request().prop_map(Event::TransmissionRequest),
             any_transmission_response().prop_map(Event::TransmissionResponse),
+            any_worker_batch().prop_map(Event::WorkerBatch),
             any_worker_ping().prop_map(Event::WorkerPing)
         ]
         .boxed()
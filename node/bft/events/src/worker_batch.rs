@@ -0,0 +1,106 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use snarkvm::console::prelude::ToBits;
+
+/// The digest of a worker batch, which a worker broadcasts after sealing its ready transmissions into a batch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WorkerBatch<N: Network> {
+    pub digest: Field<N>,
+    pub transmission_ids: IndexSet<TransmissionID<N>>,
+}
+
+impl<N: Network> WorkerBatch<N> {
+    /// Initializes a new worker batch event.
+    pub fn new(digest: Field<N>, transmission_ids: IndexSet<TransmissionID<N>>) -> Self {
+        Self { digest, transmission_ids }
+    }
+
+    /// Returns the digest of the worker batch with the given transmission IDs.
+    pub fn compute_digest(transmission_ids: &IndexSet<TransmissionID<N>>) -> Result<Field<N>> {
+        let mut preimage = Vec::new();
+        for transmission_id in transmission_ids {
+            transmission_id.write_le(&mut preimage)?;
+        }
+        N::hash_bhp1024(&preimage.to_bits_le())
+    }
+}
+
+impl<N: Network> EventTrait for WorkerBatch<N> {
+    /// Returns the event name.
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        "WorkerBatch".into()
+    }
+}
+
+impl<N: Network> ToBytes for WorkerBatch<N> {
+    fn write_le<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        self.digest.write_le(&mut writer)?;
+        u16::try_from(self.transmission_ids.len()).map_err(error)?.write_le(&mut writer)?;
+        for transmission_id in &self.transmission_ids {
+            transmission_id.write_le(&mut writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<N: Network> FromBytes for WorkerBatch<N> {
+    fn read_le<R: Read>(mut reader: R) -> IoResult<Self> {
+        let digest = Field::read_le(&mut reader)?;
+        let num_transmissions = u16::read_le(&mut reader)?;
+        let mut transmission_ids = IndexSet::new();
+        for _ in 0..num_transmissions {
+            transmission_ids.insert(TransmissionID::read_le(&mut reader)?);
+        }
+        Ok(Self { digest, transmission_ids })
+    }
+}
+
+#[cfg(test)]
+pub mod prop_tests {
+    use crate::{prop_tests::any_transmission_id, WorkerBatch};
+    use snarkvm::console::prelude::{FromBytes, ToBytes};
+
+    use bytes::{Buf, BufMut, BytesMut};
+    use proptest::{
+        collection::hash_set,
+        prelude::{BoxedStrategy, Strategy},
+    };
+    use test_strategy::proptest;
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    pub fn any_worker_batch() -> BoxedStrategy<WorkerBatch<CurrentNetwork>> {
+        hash_set(any_transmission_id(), 1..16)
+            .prop_map(|ids| {
+                let transmission_ids = ids.into_iter().collect();
+                let digest = WorkerBatch::compute_digest(&transmission_ids).unwrap();
+                WorkerBatch::new(digest, transmission_ids)
+            })
+            .boxed()
+    }
+
+    #[proptest]
+    fn serialize_deserialize(#[strategy(any_worker_batch())] original: WorkerBatch<CurrentNetwork>) {
+        let mut buf = BytesMut::default().writer();
+        WorkerBatch::write_le(&original, &mut buf).unwrap();
+
+        let deserialized = WorkerBatch::read_le(buf.into_inner().reader()).unwrap();
+        assert_eq!(original, deserialized);
+        assert_eq!(WorkerBatch::compute_digest(&deserialized.transmission_ids).unwrap(), deserialized.digest);
+    }
+}
@@ -19,6 +19,7 @@ use snarkos_account::Account;
 use snarkos_node_bft::{
     helpers::{init_consensus_channels, init_primary_channels, ConsensusReceiver, PrimarySender, Storage},
     Primary,
+    PrimaryConfig,
     BFT,
     MAX_GC_ROUNDS,
     MEMORY_POOL_PORT,
@@ -129,7 +130,16 @@ pub async fn start_bft(
     // Initialize the consensus receiver handler.
     consensus_handler(consensus_receiver);
     // Initialize the BFT instance.
-    let mut bft = BFT::<CurrentNetwork>::new(account, storage, ledger, ip, &trusted_validators, dev)?;
+    let mut bft = BFT::<CurrentNetwork>::new(
+        account,
+        storage,
+        ledger,
+        ip,
+        &trusted_validators,
+        None,
+        PrimaryConfig::default(),
+        dev,
+    )?;
     // Run the BFT instance.
     bft.run(Some(consensus_sender), sender.clone(), receiver).await?;
     // Retrieve the BFT's primary.
@@ -162,7 +172,16 @@ pub async fn start_primary(
     // Initialize the trusted validators.
     let trusted_validators = trusted_validators(node_id, num_nodes, peers);
     // Initialize the primary instance.
-    let mut primary = Primary::<CurrentNetwork>::new(account, storage, ledger, ip, &trusted_validators, dev)?;
+    let mut primary = Primary::<CurrentNetwork>::new(
+        account,
+        storage,
+        ledger,
+        ip,
+        &trusted_validators,
+        None,
+        PrimaryConfig::default(),
+        dev,
+    )?;
     // Run the primary instance.
     primary.run(None, sender.clone(), receiver).await?;
     // Handle OS signals.
@@ -0,0 +1,64 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{ledger::block::Block, prelude::Network};
+
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+/// The maximum number of blocks buffered for each subscriber, before the oldest blocks are dropped.
+pub const MAX_BLOCK_EVENTS: usize = 64; // blocks
+
+/// The stream of the blocks added to the ledger, which any number of subscribers may receive.
+///
+/// Note: Emitting a block never blocks the ledger. A subscriber that falls behind by more than
+/// `MAX_BLOCK_EVENTS` blocks misses the oldest blocks, and is notified that it lagged.
+#[derive(Clone, Debug)]
+pub struct BlockEventStream<N: Network> {
+    /// The sender of the blocks.
+    sender: broadcast::Sender<Arc<Block<N>>>,
+}
+
+impl<N: Network> Default for BlockEventStream<N> {
+    /// Initializes a new instance of the block event stream.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Network> BlockEventStream<N> {
+    /// Initializes a new instance of the block event stream.
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(MAX_BLOCK_EVENTS);
+        Self { sender }
+    }
+
+    /// Returns a new receiver of the blocks added to the ledger from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<Block<N>>> {
+        self.sender.subscribe()
+    }
+
+    /// Returns the number of subscribers.
+    pub fn num_subscribers(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// Emits the given block to the subscribers, if there are any.
+    pub fn emit(&self, block: &Block<N>) {
+        // Note: The block is only cloned if there are subscribers.
+        if self.num_subscribers() > 0 {
+            let _ = self.sender.send(Arc::new(block.clone()));
+        }
+    }
+}
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{fmt_id, spawn_blocking, LedgerService};
+use crate::{fmt_id, is_storage_degraded, spawn_blocking, BlockEventStream, BlockIndex, LedgerService};
 use snarkvm::{
     ledger::{
         block::{Block, Transaction},
@@ -26,19 +26,54 @@ use snarkvm::{
 };
 
 use indexmap::IndexMap;
-use std::{fmt, ops::Range, sync::Arc};
+use parking_lot::Mutex;
+use std::{collections::HashMap, fmt, ops::Range, sync::Arc};
+
+/// The number of blocks for which the expiration height of an expired transaction is retained,
+/// so that the transaction is still rejected if it is received again.
+#[cfg(feature = "ledger-write")]
+const EXPIRED_TRANSACTION_RETENTION_IN_BLOCKS: u32 = 1000; // blocks
 
 /// A core ledger service.
 pub struct CoreLedgerService<N: Network, C: ConsensusStorage<N>> {
     ledger: Ledger<N, C>,
     coinbase_verifying_key: Arc<CoinbaseVerifyingKey<N>>,
+    block_events: BlockEventStream<N>,
+    /// The index of the blocks, which is updated as each block is added to the ledger, if it is enabled.
+    #[cfg_attr(not(feature = "ledger-write"), allow(dead_code))]
+    index: Option<Arc<dyn BlockIndex<N>>>,
+    /// The map of unconfirmed transaction IDs to their expiration height.
+    transaction_expirations: Mutex<HashMap<N::TransactionID, u32>>,
 }
 
 impl<N: Network, C: ConsensusStorage<N>> CoreLedgerService<N, C> {
-    /// Initializes a new core ledger service.
-    pub fn new(ledger: Ledger<N, C>) -> Self {
+    /// Initializes a new core ledger service, which updates the given block index as each block is added.
+    pub fn new(ledger: Ledger<N, C>, index: Option<Arc<dyn BlockIndex<N>>>) -> Self {
         let coinbase_verifying_key = Arc::new(ledger.coinbase_puzzle().coinbase_verifying_key().clone());
-        Self { ledger, coinbase_verifying_key }
+        Self {
+            ledger,
+            coinbase_verifying_key,
+            block_events: Default::default(),
+            index,
+            transaction_expirations: Default::default(),
+        }
+    }
+
+    /// Returns the stream of the blocks added to the ledger.
+    pub const fn block_events(&self) -> &BlockEventStream<N> {
+        &self.block_events
+    }
+
+    /// Indexes the given block, which was just added to the ledger, after any blocks the index is missing.
+    #[cfg(feature = "ledger-write")]
+    fn update_index(&self, index: &dyn BlockIndex<N>, block: &Block<N>) -> Result<()> {
+        // Note: The index misses blocks if indexing a previous block failed, as the index and the ledger
+        // are separate databases. The missing blocks are indexed before the new block, in order.
+        let start_height = index.indexed_height()?.map_or(0, |height| height + 1);
+        for height in start_height..block.height() {
+            index.index_block(&self.ledger.get_block(height)?)?;
+        }
+        index.index_block(block)
     }
 }
 
@@ -163,6 +198,37 @@ impl<N: Network, C: ConsensusStorage<N>> LedgerService<N> for CoreLedgerService<
         }
     }
 
+    /// Returns `true` if the serial number exists in the ledger.
+    fn contains_serial_number(&self, serial_number: &Field<N>) -> Result<bool> {
+        self.ledger.contains_serial_number(serial_number)
+    }
+
+    /// Returns `true` if the commitment exists in the ledger.
+    fn contains_commitment(&self, commitment: &Field<N>) -> Result<bool> {
+        self.ledger.contains_commitment(commitment)
+    }
+
+    /// Returns `true` if the global state root exists in the ledger.
+    fn contains_state_root(&self, state_root: &N::StateRoot) -> Result<bool> {
+        self.ledger.contains_state_root(state_root)
+    }
+
+    /// Records the expiration height of the given unconfirmed transaction,
+    /// at or after which this node no longer includes the transaction in its own batches.
+    fn insert_transaction_expiration(&self, transaction_id: N::TransactionID, expiration_height: u32) {
+        self.transaction_expirations.lock().insert(transaction_id, expiration_height);
+    }
+
+    /// Returns the expiration height of the given unconfirmed transaction, if it has one.
+    fn get_transaction_expiration(&self, transaction_id: &N::TransactionID) -> Option<u32> {
+        self.transaction_expirations.lock().get(transaction_id).copied()
+    }
+
+    /// Stops tracking the expiration height of the given unconfirmed transaction.
+    fn remove_transaction_expiration(&self, transaction_id: &N::TransactionID) {
+        self.transaction_expirations.lock().remove(transaction_id);
+    }
+
     /// Ensures the given transmission ID matches the given transmission.
     fn ensure_transmission_id_matches(
         &self,
@@ -250,6 +316,12 @@ impl<N: Network, C: ConsensusStorage<N>> LedgerService<N> for CoreLedgerService<
         transaction_id: N::TransactionID,
         transaction: Data<Transaction<N>>,
     ) -> Result<()> {
+        // Ensure the transaction has not expired.
+        if let Some(expiration_height) = self.get_transaction_expiration(&transaction_id) {
+            if expiration_height <= self.latest_block_height() {
+                bail!("Transaction '{}' expired at block {expiration_height}", fmt_id(transaction_id));
+            }
+        }
         // Deserialize the transaction.
         let transaction = spawn_blocking!(transaction.deserialize_blocking())?;
         // Ensure the transaction ID matches in the transaction.
@@ -283,7 +355,29 @@ impl<N: Network, C: ConsensusStorage<N>> LedgerService<N> for CoreLedgerService<
     /// Adds the given block as the next block in the ledger.
     #[cfg(feature = "ledger-write")]
     fn advance_to_next_block(&self, block: &Block<N>) -> Result<()> {
+        // Ensure the storage is not degraded, as the block may not be fully written.
+        if is_storage_degraded() {
+            bail!("Cannot advance to block {} - the storage is degraded", block.height())
+        }
         self.ledger.advance_to_next_block(block)?;
+        // Index the block, before it is emitted to the subscribers.
+        if let Some(index) = &self.index {
+            if let Err(error) = self.update_index(index.as_ref(), block) {
+                tracing::warn!("Failed to index block {} - {error}", block.height());
+            }
+        }
+        // Stop tracking the expiration heights of the confirmed transactions, and of the transactions
+        // that expired more than `EXPIRED_TRANSACTION_RETENTION_IN_BLOCKS` blocks ago.
+        {
+            let mut expirations = self.transaction_expirations.lock();
+            block.transaction_ids().for_each(|transaction_id| {
+                expirations.remove(transaction_id);
+            });
+            let retention_height = block.height().saturating_sub(EXPIRED_TRANSACTION_RETENTION_IN_BLOCKS);
+            expirations.retain(|_, expiration_height| *expiration_height > retention_height);
+        }
+        // Emit the block to the subscribers.
+        self.block_events.emit(block);
         tracing::info!("\n\nAdvanced to block {} at round {} - {}\n", block.height(), block.round(), block.hash());
         Ok(())
     }
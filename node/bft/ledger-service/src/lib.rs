@@ -17,6 +17,11 @@
 #[macro_use]
 extern crate async_trait;
 
+#[cfg(feature = "ledger")]
+pub mod block_events;
+#[cfg(feature = "ledger")]
+pub use block_events::*;
+
 #[cfg(feature = "ledger")]
 pub mod ledger;
 #[cfg(feature = "ledger")]
@@ -37,6 +42,9 @@ pub mod translucent;
 #[cfg(feature = "translucent")]
 pub use translucent::*;
 
+pub mod storage_health;
+pub use storage_health::*;
+
 pub mod traits;
 pub use traits::*;
 
@@ -33,12 +33,13 @@ use tracing::*;
 pub struct MockLedgerService<N: Network> {
     committee: Committee<N>,
     height_to_hash: Mutex<BTreeMap<u32, N::BlockHash>>,
+    transmissions: Mutex<IndexMap<TransmissionID<N>, Transmission<N>>>,
 }
 
 impl<N: Network> MockLedgerService<N> {
     /// Initializes a new mock ledger service.
     pub fn new(committee: Committee<N>) -> Self {
-        Self { committee, height_to_hash: Default::default() }
+        Self { committee, height_to_hash: Default::default(), transmissions: Default::default() }
     }
 
     /// Initializes a new mock ledger service at the specified height.
@@ -47,7 +48,12 @@ impl<N: Network> MockLedgerService<N> {
         for i in 0..=height {
             height_to_hash.insert(i, (Field::<N>::from_u32(i)).into());
         }
-        Self { committee, height_to_hash: Mutex::new(height_to_hash) }
+        Self { committee, height_to_hash: Mutex::new(height_to_hash), transmissions: Default::default() }
+    }
+
+    /// Persists the given transmission in the mock ledger, as if it was included in a block.
+    pub fn insert_transmission(&self, transmission_id: TransmissionID<N>, transmission: Transmission<N>) {
+        self.transmissions.lock().insert(transmission_id, transmission);
     }
 }
 
@@ -101,13 +107,19 @@ impl<N: Network> LedgerService<N> for MockLedgerService<N> {
     }
 
     /// Returns the solution for the given solution ID.
-    fn get_solution(&self, _solution_id: &PuzzleCommitment<N>) -> Result<ProverSolution<N>> {
-        unreachable!("MockLedgerService does not support get_solution")
+    fn get_solution(&self, solution_id: &PuzzleCommitment<N>) -> Result<ProverSolution<N>> {
+        match self.transmissions.lock().get(&TransmissionID::Solution(*solution_id)) {
+            Some(Transmission::Solution(solution)) => solution.clone().deserialize_blocking(),
+            _ => bail!("Missing solution {}", fmt_id(solution_id)),
+        }
     }
 
     /// Returns the unconfirmed transaction for the given transaction ID.
-    fn get_unconfirmed_transaction(&self, _transaction_id: N::TransactionID) -> Result<Transaction<N>> {
-        unreachable!("MockLedgerService does not support get_unconfirmed_transaction")
+    fn get_unconfirmed_transaction(&self, transaction_id: N::TransactionID) -> Result<Transaction<N>> {
+        match self.transmissions.lock().get(&TransmissionID::Transaction(transaction_id)) {
+            Some(Transmission::Transaction(transaction)) => transaction.clone().deserialize_blocking(),
+            _ => bail!("Missing transaction {}", fmt_id(transaction_id)),
+        }
     }
 
     /// Returns the batch certificate for the given batch certificate ID.
@@ -137,12 +149,31 @@ impl<N: Network> LedgerService<N> for MockLedgerService<N> {
         Ok(false)
     }
 
-    /// Returns `false` for all queries.
+    /// Returns `true` if the transmission was persisted with `insert_transmission`.
     fn contains_transmission(&self, transmission_id: &TransmissionID<N>) -> Result<bool> {
-        trace!("[MockLedgerService] Contains transmission ID {} - false", fmt_id(transmission_id));
+        let contains = self.transmissions.lock().contains_key(transmission_id);
+        trace!("[MockLedgerService] Contains transmission ID {} - {contains}", fmt_id(transmission_id));
+        Ok(contains)
+    }
+
+    /// Returns `false` for all queries.
+    fn contains_serial_number(&self, serial_number: &Field<N>) -> Result<bool> {
+        trace!("[MockLedgerService] Contains serial number {} - false", fmt_id(serial_number));
         Ok(false)
     }
 
+    /// Returns `false` for all queries.
+    fn contains_commitment(&self, commitment: &Field<N>) -> Result<bool> {
+        trace!("[MockLedgerService] Contains commitment {} - false", fmt_id(commitment));
+        Ok(false)
+    }
+
+    /// Returns `true` for all queries.
+    fn contains_state_root(&self, state_root: &N::StateRoot) -> Result<bool> {
+        trace!("[MockLedgerService] Contains state root {} - true", fmt_id(state_root));
+        Ok(true)
+    }
+
     /// Ensures the given transmission ID matches the given transmission.
     fn ensure_transmission_id_matches(
         &self,
@@ -124,6 +124,21 @@ impl<N: Network> LedgerService<N> for ProverLedgerService<N> {
         bail!("Transmission '{transmission_id}' does not exist in prover")
     }
 
+    /// Returns `true` if the serial number exists in the ledger.
+    fn contains_serial_number(&self, serial_number: &Field<N>) -> Result<bool> {
+        bail!("Serial number '{serial_number}' does not exist in prover")
+    }
+
+    /// Returns `true` if the commitment exists in the ledger.
+    fn contains_commitment(&self, commitment: &Field<N>) -> Result<bool> {
+        bail!("Commitment '{commitment}' does not exist in prover")
+    }
+
+    /// Returns `true` if the global state root exists in the ledger.
+    fn contains_state_root(&self, state_root: &N::StateRoot) -> Result<bool> {
+        bail!("State root '{state_root}' does not exist in prover")
+    }
+
     /// Ensures the given transmission ID matches the given transmission.
     fn ensure_transmission_id_matches(
         &self,
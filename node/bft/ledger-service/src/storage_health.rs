@@ -0,0 +1,31 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether the storage of the node is degraded, in which case the ledger is read-only.
+static STORAGE_DEGRADED: AtomicBool = AtomicBool::new(false);
+
+/// Sets whether the storage of the node is degraded.
+///
+/// While the storage is degraded, the ledger does not advance, and the validator neither proposes nor signs batches,
+/// so that the storage is not written to when it may be unable to complete the write (e.g. when the disk is full).
+pub fn set_storage_degraded(degraded: bool) {
+    STORAGE_DEGRADED.store(degraded, Ordering::Relaxed);
+}
+
+/// Returns `true` if the storage of the node is degraded.
+pub fn is_storage_degraded() -> bool {
+    STORAGE_DEGRADED.load(Ordering::Relaxed)
+}
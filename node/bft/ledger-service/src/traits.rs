@@ -78,6 +78,30 @@ pub trait LedgerService<N: Network>: Debug + Send + Sync {
     /// Returns `true` if the ledger contains the given transmission ID.
     fn contains_transmission(&self, transmission_id: &TransmissionID<N>) -> Result<bool>;
 
+    /// Returns `true` if the ledger contains the given serial number.
+    fn contains_serial_number(&self, serial_number: &Field<N>) -> Result<bool>;
+
+    /// Returns `true` if the ledger contains the given commitment.
+    fn contains_commitment(&self, commitment: &Field<N>) -> Result<bool>;
+
+    /// Returns `true` if the ledger contains the given global state root.
+    fn contains_state_root(&self, state_root: &N::StateRoot) -> Result<bool>;
+
+    /// Records the expiration height of the given unconfirmed transaction,
+    /// at or after which this node no longer includes the transaction in its own batches.
+    ///
+    /// Note: The expiration height is a local policy, which is not part of the transaction, so it is never
+    /// enforced on the batches of other validators, nor in the validation of blocks.
+    fn insert_transaction_expiration(&self, _transaction_id: N::TransactionID, _expiration_height: u32) {}
+
+    /// Returns the expiration height of the given unconfirmed transaction, if it has one.
+    fn get_transaction_expiration(&self, _transaction_id: &N::TransactionID) -> Option<u32> {
+        None
+    }
+
+    /// Stops tracking the expiration height of the given unconfirmed transaction.
+    fn remove_transaction_expiration(&self, _transaction_id: &N::TransactionID) {}
+
     /// Ensures the given transmission ID matches the given transmission.
     fn ensure_transmission_id_matches(
         &self,
@@ -111,6 +135,18 @@ pub trait LedgerService<N: Network>: Debug + Send + Sync {
     ) -> Result<Block<N>>;
 
     /// Adds the given block as the next block in the ledger.
+    ///
+    /// Note: The ledger writes the keys of the block as atomic batches, so this is a blocking call
+    /// that must be made from a blocking task, and never from the async executor.
     #[cfg(feature = "ledger-write")]
     fn advance_to_next_block(&self, block: &Block<N>) -> Result<()>;
 }
+
+/// An index of the blocks, which is kept alongside the ledger, and is updated as each block is added to the ledger.
+pub trait BlockIndex<N: Network>: Debug + Send + Sync {
+    /// Returns the latest indexed block height, if any block is indexed.
+    fn indexed_height(&self) -> Result<Option<u32>>;
+
+    /// Indexes the given block, which must be the next block after the latest indexed block.
+    fn index_block(&self, block: &Block<N>) -> Result<()>;
+}
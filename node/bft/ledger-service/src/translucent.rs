@@ -42,7 +42,7 @@ impl<N: Network, C: ConsensusStorage<N>> fmt::Debug for TranslucentLedgerService
 impl<N: Network, C: ConsensusStorage<N>> TranslucentLedgerService<N, C> {
     /// Initializes a new ledger service wrapper.
     pub fn new(ledger: Ledger<N, C>) -> Self {
-        Self { inner: CoreLedgerService::new(ledger) }
+        Self { inner: CoreLedgerService::new(ledger, None) }
     }
 }
 
@@ -129,6 +129,21 @@ impl<N: Network, C: ConsensusStorage<N>> LedgerService<N> for TranslucentLedgerS
         self.inner.contains_transmission(transmission_id)
     }
 
+    /// Returns `true` if the serial number exists in the ledger.
+    fn contains_serial_number(&self, serial_number: &Field<N>) -> Result<bool> {
+        self.inner.contains_serial_number(serial_number)
+    }
+
+    /// Returns `true` if the commitment exists in the ledger.
+    fn contains_commitment(&self, commitment: &Field<N>) -> Result<bool> {
+        self.inner.contains_commitment(commitment)
+    }
+
+    /// Returns `true` if the global state root exists in the ledger.
+    fn contains_state_root(&self, state_root: &N::StateRoot) -> Result<bool> {
+        self.inner.contains_state_root(state_root)
+    }
+
     /// Always succeeds.
     fn ensure_transmission_id_matches(
         &self,
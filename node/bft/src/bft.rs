@@ -16,19 +16,24 @@ use crate::{
     helpers::{
         fmt_id,
         init_bft_channels,
-        now,
+        now_in_millis,
         BFTReceiver,
+        ConsensusEvent,
         ConsensusSender,
+        ConsensusSnapshot,
         PrimaryReceiver,
         PrimarySender,
         Storage,
         DAG,
     },
     Primary,
+    PrimaryConfig,
+    LINKED_LEADERS_HEIGHT,
     MAX_LEADER_CERTIFICATE_DELAY_IN_SECS,
 };
 use snarkos_account::Account;
 use snarkos_node_bft_ledger_service::LedgerService;
+use snarkos_node_tcp::PeerDenyList;
 use snarkvm::{
     console::account::Address,
     ledger::{
@@ -65,10 +70,12 @@ pub struct BFT<N: Network> {
     dag: Arc<RwLock<DAG<N>>>,
     /// The batch certificate of the leader from the current even round, if one was present.
     leader_certificate: Arc<RwLock<Option<BatchCertificate<N>>>>,
-    /// The timer for the leader certificate to be received.
+    /// The timer for the leader certificate to be received, in milliseconds.
     leader_certificate_timer: Arc<AtomicI64>,
     /// The last election certificate IDs.
     last_election_certificate_ids: Arc<RwLock<IndexSet<Field<N>>>>,
+    /// The block height from which the linked previous leader certificates are committed before the leader.
+    linked_leaders_height: u32,
     /// The consensus sender.
     consensus_sender: Arc<OnceCell<ConsensusSender<N>>>,
     /// The spawned handles.
@@ -85,14 +92,17 @@ impl<N: Network> BFT<N> {
         ledger: Arc<dyn LedgerService<N>>,
         ip: Option<SocketAddr>,
         trusted_validators: &[SocketAddr],
+        peer_deny_list: Option<Arc<PeerDenyList>>,
+        config: PrimaryConfig,
         dev: Option<u16>,
     ) -> Result<Self> {
         Ok(Self {
-            primary: Primary::new(account, storage, ledger, ip, trusted_validators, dev)?,
+            primary: Primary::new(account, storage, ledger, ip, trusted_validators, peer_deny_list, config, dev)?,
             dag: Default::default(),
             leader_certificate: Default::default(),
             leader_certificate_timer: Default::default(),
             last_election_certificate_ids: Default::default(),
+            linked_leaders_height: LINKED_LEADERS_HEIGHT,
             consensus_sender: Default::default(),
             handles: Default::default(),
             lock: Default::default(),
@@ -136,6 +146,11 @@ impl<N: Network> BFT<N> {
         self.primary.ledger()
     }
 
+    /// Returns the round of the latest committed leader certificate.
+    pub fn last_committed_round(&self) -> u64 {
+        self.dag.read().last_committed_round()
+    }
+
     /// Returns the leader of the current even round, if one was present.
     pub fn leader(&self) -> Option<Address<N>> {
         self.leader_certificate.read().as_ref().map(|certificate| certificate.author())
@@ -152,6 +167,30 @@ impl<N: Network> BFT<N> {
     }
 }
 
+impl<N: Network> BFT<N> {
+    /// Returns a deterministic snapshot of the consensus state, for debugging purposes.
+    pub fn consensus_snapshot(&self) -> Result<ConsensusSnapshot<N>> {
+        // Retrieve the storage.
+        let storage = self.storage();
+        // Retrieve the current round.
+        let round = storage.current_round();
+        // Retrieve the previous committee for the current round.
+        let committee = self.ledger().get_previous_committee_for_round(round)?;
+        // Retrieve the map of connected peer IPs to addresses.
+        let peers = self.primary.gateway().resolver().peer_addresses().into_iter().collect();
+        // Construct the snapshot.
+        Ok(ConsensusSnapshot::new(
+            round,
+            storage.current_height(),
+            storage.gc_round(),
+            self.leader(),
+            &committee,
+            storage.num_certificates_per_round(),
+            peers,
+        ))
+    }
+}
+
 impl<N: Network> BFT<N> {
     /// Returns the number of unconfirmed transmissions.
     pub fn num_unconfirmed_transmissions(&self) -> usize {
@@ -217,8 +256,8 @@ impl<N: Network> BFT<N> {
             let start = self.leader_certificate_timer.load(Ordering::SeqCst);
             // Only log if the timer was set, otherwise we get a time difference since the EPOCH.
             if start > 0 {
-                let end = now();
-                let elapsed = std::time::Duration::from_secs((end - start) as u64);
+                let end = now_in_millis();
+                let elapsed = std::time::Duration::from_millis((end - start) as u64);
                 metrics::histogram(metrics::bft::COMMIT_ROUNDS_LATENCY, elapsed.as_secs_f64());
             }
         }
@@ -256,7 +295,9 @@ impl<N: Network> BFT<N> {
                 warn!("BFT failed to increment to the next round from round {current_round} - {e}");
             }
             // Update the timer for the leader certificate.
-            self.leader_certificate_timer.store(now(), Ordering::SeqCst);
+            self.leader_certificate_timer.store(now_in_millis(), Ordering::SeqCst);
+            // Update the round timer to the current round.
+            self.primary.round_timer().update(self.storage().current_round());
         }
 
         is_ready
@@ -343,8 +384,15 @@ impl<N: Network> BFT<N> {
     }
 
     /// Returns `true` if the timer for the leader certificate has expired.
+    ///
+    /// Note: The leader certificate delay is the round timeout, which adapts to the recent round latencies,
+    /// and is never shorter than `MAX_LEADER_CERTIFICATE_DELAY_IN_SECS`, so that a fast round does not
+    /// cut short the time given to the leader.
     fn is_timer_expired(&self) -> bool {
-        self.leader_certificate_timer.load(Ordering::SeqCst) + MAX_LEADER_CERTIFICATE_DELAY_IN_SECS <= now()
+        // Compute the leader certificate delay, in milliseconds.
+        let timeout = self.primary.round_timer().timeout().as_millis() as i64;
+        let delay = timeout.max(MAX_LEADER_CERTIFICATE_DELAY_IN_SECS * 1000);
+        self.leader_certificate_timer.load(Ordering::SeqCst) + delay <= now_in_millis()
     }
 
     /// Returns 'true' if any of the following conditions hold:
@@ -492,13 +540,128 @@ impl<N: Network> BFT<N> {
     }
 
     /// Commits the leader certificate, and all previous leader certificates since the last committed round.
+    ///
+    /// Per the Bullshark commit rule, a previous leader certificate is committed (before the leader certificate)
+    /// if there is a path to it from the next leader certificate to be committed. Each leader certificate is
+    /// committed as its own subdag, so that the leaders are ordered from the oldest to the newest.
     async fn commit_leader_certificate<const ALLOW_LEDGER_ACCESS: bool, const IS_SYNCING: bool>(
         &self,
         leader_certificate: BatchCertificate<N>,
         election_certificate_ids: IndexSet<Field<N>>,
     ) -> Result<()> {
-        // Retrieve the leader certificate round.
-        let leader_round = leader_certificate.round();
+        // Retrieve the previous leader certificates that are linked to the leader certificate.
+        // Note: When syncing, each block commits exactly one leader certificate, so the previous leaders are skipped.
+        // Note: Committing the linked leaders changes the resulting blocks, so every validator must run the
+        // same rule; it is therefore activated at a block height, which every validator reaches in the same order.
+        let previous_leader_certificates = match IS_SYNCING || !self.is_linked_leaders_active() {
+            true => vec![],
+            false => self.get_linked_leader_certificates(&leader_certificate)?,
+        };
+        // Commit the previous leader certificates, starting from the oldest leader certificate.
+        for previous_leader_certificate in previous_leader_certificates.into_iter().rev() {
+            // Retrieve the election certificate IDs, which are the votes for the previous leader certificate.
+            let previous_election_certificate_ids = self.get_election_certificate_ids(&previous_leader_certificate);
+            let previous_leader_round = previous_leader_certificate.round();
+            info!("Proceeding to commit the linked leader certificate from round {previous_leader_round}...");
+            // Commit the previous leader certificate.
+            self.commit_subdag::<ALLOW_LEDGER_ACCESS, IS_SYNCING>(
+                previous_leader_certificate,
+                previous_election_certificate_ids,
+            )
+            .await?;
+        }
+        // Commit the leader certificate.
+        self.commit_subdag::<ALLOW_LEDGER_ACCESS, IS_SYNCING>(leader_certificate, election_certificate_ids).await
+    }
+
+    /// Returns `true` if the linked previous leader certificates are committed before the leader certificate.
+    fn is_linked_leaders_active(&self) -> bool {
+        self.ledger().latest_block_height() >= self.linked_leaders_height
+    }
+
+    /// Returns the previous leader certificates since the last committed round, that are linked to the given
+    /// leader certificate, ordered from the newest to the oldest.
+    fn get_linked_leader_certificates(
+        &self,
+        leader_certificate: &BatchCertificate<N>,
+    ) -> Result<Vec<BatchCertificate<N>>> {
+        // Initialize a list for the linked leader certificates.
+        let mut linked_leader_certificates = Vec::new();
+        // Initialize the current leader certificate.
+        let mut current_certificate = leader_certificate.clone();
+        // Retrieve the last committed round.
+        let last_committed_round = self.dag.read().last_committed_round();
+        // Iterate over the previous even rounds since the last committed round, from the newest to the oldest.
+        for round in (last_committed_round + 2..=leader_certificate.round().saturating_sub(2)).rev().step_by(2) {
+            // Retrieve the previous committee for the round.
+            let previous_committee = match self.ledger().get_previous_committee_for_round(round) {
+                Ok(committee) => committee,
+                Err(e) => bail!("BFT failed to retrieve the previous committee for the even round {round} - {e}"),
+            };
+            // Compute the leader for the round.
+            let leader = match previous_committee.get_leader(round) {
+                Ok(leader) => leader,
+                Err(e) => bail!("BFT failed to compute the leader for the even round {round} - {e}"),
+            };
+            // Retrieve the leader certificate for the round, if the leader was present.
+            let Some(previous_certificate) = self.dag.read().get_certificate_for_round_with_author(round, leader)
+            else {
+                continue;
+            };
+            // If there is a path from the current leader certificate to the previous leader certificate, link it.
+            if self.is_linked(&previous_certificate, &current_certificate)? {
+                linked_leader_certificates.push(previous_certificate.clone());
+                // Update the current leader certificate.
+                current_certificate = previous_certificate;
+            }
+        }
+        Ok(linked_leader_certificates)
+    }
+
+    /// Returns the IDs of the certificates in the round after the given leader certificate, that reference it.
+    fn get_election_certificate_ids(&self, leader_certificate: &BatchCertificate<N>) -> IndexSet<Field<N>> {
+        match self.dag.read().get_certificates_for_round(leader_certificate.round() + 1) {
+            Some(certificates) => certificates
+                .values()
+                .filter(|c| c.previous_certificate_ids().contains(&leader_certificate.id()))
+                .map(|c| c.id())
+                .collect(),
+            None => IndexSet::new(),
+        }
+    }
+
+    /// Returns `true` if there is a path from the current certificate to the previous certificate in the DAG.
+    fn is_linked(
+        &self,
+        previous_certificate: &BatchCertificate<N>,
+        current_certificate: &BatchCertificate<N>,
+    ) -> Result<bool> {
+        // Initialize the traversal, starting from the current certificate.
+        let mut traversal = vec![current_certificate.clone()];
+        // Iterate over the rounds from the current certificate down to the previous certificate.
+        for round in (previous_certificate.round()..current_certificate.round()).rev() {
+            // Retrieve the certificates for the round.
+            let Some(certificates) = self.dag.read().get_certificates_for_round(round) else {
+                // Note: If this is hit, the DAG was garbage collected beyond the previous certificate.
+                bail!("BFT failed to retrieve the certificates for past round {round}");
+            };
+            // Retain the certificates that are referenced by the traversal.
+            traversal = certificates
+                .into_values()
+                .filter(|p| traversal.iter().any(|c| c.previous_certificate_ids().contains(&p.id())))
+                .collect();
+        }
+        Ok(traversal.contains(previous_certificate))
+    }
+
+    /// Commits the subdag of the given leader certificate.
+    async fn commit_subdag<const ALLOW_LEDGER_ACCESS: bool, const IS_SYNCING: bool>(
+        &self,
+        leader_certificate: BatchCertificate<N>,
+        election_certificate_ids: IndexSet<Field<N>>,
+    ) -> Result<()> {
+        // Retrieve the leader certificate round and author.
+        let (leader_round, leader) = (leader_certificate.round(), leader_certificate.author());
         // Compute the commit subdag.
         let commit_subdag = match self.order_dag_with_dfs::<ALLOW_LEDGER_ACCESS>(leader_certificate) {
             Ok(subdag) => subdag,
@@ -561,14 +724,9 @@ impl<N: Network> BFT<N> {
                 // Await the callback to continue.
                 match callback_receiver.await {
                     Ok(Ok(())) => (), // continue
-                    Ok(Err(e)) => {
-                        error!("BFT failed to advance the subdag for round {anchor_round} - {e}");
-                        return Ok(());
-                    }
-                    Err(e) => {
-                        error!("BFT failed to receive the callback for round {anchor_round} - {e}");
-                        return Ok(());
-                    }
+                    // Note: The error is returned, so that no later leader certificate is committed past this one.
+                    Ok(Err(e)) => bail!("BFT failed to advance the subdag for round {anchor_round} - {e}"),
+                    Err(e) => bail!("BFT failed to receive the callback for round {anchor_round} - {e}"),
                 }
             }
 
@@ -580,6 +738,20 @@ impl<N: Network> BFT<N> {
             for certificate in commit_subdag.values().flatten() {
                 dag_write.commit(certificate, self.storage().max_gc_rounds());
             }
+            // Prune the transmissions of the committed certificates, as they are now persisted in the ledger.
+            let num_pruned = self.storage().prune_committed(commit_subdag.values().flatten());
+            trace!("Pruned {num_pruned} committed transmissions from storage for round {anchor_round}");
+            // Garbage collect the rounds in storage that are sufficiently behind the committed leader round.
+            self.storage().garbage_collect_committed(leader_round);
+            // Reconcile the gateway with any change in the committee from the new block.
+            self.primary.gateway().handle_committee_change();
+            // Emit the committed subdag to the consensus event subscribers.
+            self.primary.gateway().consensus_events().emit(ConsensusEvent::SubdagCommitted {
+                round: anchor_round,
+                leader,
+                num_certificates: commit_subdag.values().map(IndexSet::len).sum(),
+                num_transmissions,
+            });
         }
         // Update the last election certificate IDs.
         {
@@ -753,8 +925,12 @@ impl<N: Network> BFT<N> {
                 Some((leader_certificate, election_certificate_ids)) => {
                     (past, leader_certificate.clone(), election_certificate_ids.clone())
                 }
-                // If there is no latest leader certificate, return early.
-                None => return,
+                // If there is no latest leader certificate, insert the certificates, and return early.
+                None => {
+                    let mut dag = self.dag.write();
+                    certificates.into_iter().for_each(|certificate| dag.insert(certificate));
+                    return;
+                }
             }
         };
         {
@@ -810,16 +986,28 @@ impl<N: Network> BFT<N> {
 #[cfg(test)]
 mod tests {
     use crate::{
-        helpers::{now, Storage},
+        helpers::{now, now_in_millis, ConsensusEvent, Storage},
+        PrimaryConfig,
         BFT,
+        MAX_BATCH_DELAY_IN_MS,
+        MAX_LEADER_CERTIFICATE_DELAY_IN_SECS,
     };
     use snarkos_account::Account;
     use snarkos_node_bft_ledger_service::MockLedgerService;
     use snarkos_node_bft_storage_service::BFTMemoryService;
     use snarkvm::{
+        console::account::{Address, PrivateKey},
         ledger::{
             committee::Committee,
-            narwhal::batch_certificate::test_helpers::{sample_batch_certificate, sample_batch_certificate_for_round},
+            narwhal::{
+                batch_certificate::test_helpers::{
+                    sample_batch_certificate,
+                    sample_batch_certificate_for_round,
+                    sample_batch_certificate_for_round_with_previous_certificate_ids,
+                },
+                BatchCertificate,
+                BatchHeader,
+            },
         },
         utilities::TestRng,
     };
@@ -853,6 +1041,61 @@ mod tests {
         (committee, account, ledger, storage)
     }
 
+    /// Samples a new BFT with a committee of four validators, and returns the BFT, the committee,
+    /// and the private keys of the committee members.
+    fn sample_bft_with_committee(
+        rng: &mut TestRng,
+    ) -> (BFT<CurrentNetwork>, Committee<CurrentNetwork>, Vec<PrivateKey<CurrentNetwork>>) {
+        let private_keys = (0..4).map(|_| PrivateKey::new(rng).unwrap()).collect::<Vec<_>>();
+        let members = private_keys.iter().map(|private_key| Address::try_from(private_key).unwrap()).collect();
+        let committee =
+            snarkvm::ledger::committee::test_helpers::sample_committee_for_round_and_members(1, members, rng);
+        let ledger = Arc::new(MockLedgerService::new(committee.clone()));
+        let storage = Storage::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 10);
+        let account = Account::try_from(&private_keys[0]).unwrap();
+        let bft = BFT::new(account, storage, ledger, None, &[], None, PrimaryConfig::default(), None).unwrap();
+        (bft, committee, private_keys)
+    }
+
+    /// Samples a certificate for the given round, authored by the given committee member, signed by the
+    /// other committee members, and referencing the given previous certificates.
+    fn sample_member_certificate(
+        round: u64,
+        author: usize,
+        private_keys: &[PrivateKey<CurrentNetwork>],
+        previous_certificates: &[&BatchCertificate<CurrentNetwork>],
+        rng: &mut TestRng,
+    ) -> BatchCertificate<CurrentNetwork> {
+        let previous_certificate_ids = previous_certificates.iter().map(|c| c.id()).collect();
+        let batch_header = BatchHeader::new(
+            &private_keys[author],
+            round,
+            now(),
+            Default::default(),
+            previous_certificate_ids,
+            Default::default(),
+            rng,
+        )
+        .unwrap();
+        let signatures = private_keys
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != author)
+            .map(|(_, private_key)| private_key.sign(&[batch_header.batch_id()], rng).unwrap())
+            .collect();
+        BatchCertificate::from(batch_header, signatures).unwrap()
+    }
+
+    /// Returns the index of the committee member that leads the given round.
+    fn leader_index(
+        committee: &Committee<CurrentNetwork>,
+        private_keys: &[PrivateKey<CurrentNetwork>],
+        round: u64,
+    ) -> usize {
+        let leader = committee.get_leader(round).unwrap();
+        private_keys.iter().position(|private_key| Address::try_from(private_key).unwrap() == leader).unwrap()
+    }
+
     #[test]
     #[tracing_test::traced_test]
     fn test_is_leader_quorum_odd() -> Result<()> {
@@ -863,7 +1106,7 @@ mod tests {
         assert_eq!(storage.max_gc_rounds(), 10);
 
         // Initialize the BFT.
-        let bft = BFT::new(account, storage, ledger, None, &[], None)?;
+        let bft = BFT::new(account, storage, ledger, None, &[], None, PrimaryConfig::default(), None)?;
         assert!(bft.is_timer_expired()); // 0 + 5 < now()
 
         // Ensure this call succeeds on an odd round.
@@ -879,7 +1122,7 @@ mod tests {
         assert!(result); // should now fall through to the end of function
 
         // Set the timer to now().
-        bft.leader_certificate_timer.store(now(), Ordering::SeqCst);
+        bft.leader_certificate_timer.store(now_in_millis(), Ordering::SeqCst);
         assert!(!bft.is_timer_expired());
 
         // Ensure this call succeeds on an odd round.
@@ -889,6 +1132,36 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    #[tracing_test::traced_test]
+    fn test_is_timer_expired_floor() -> Result<()> {
+        let rng = &mut TestRng::default();
+
+        // Sample the test instance.
+        let (_, account, ledger, storage) = sample_test_instance(None, 10, rng);
+
+        // Initialize the BFT.
+        let bft = BFT::new(account, storage, ledger, None, &[], None, PrimaryConfig::default(), None)?;
+
+        // Observe fast rounds, so that the round timeout drops to its minimum.
+        for round in 1..=5 {
+            bft.primary.round_timer().update(round);
+        }
+        assert_eq!(bft.primary.round_timer().timeout().as_millis(), MAX_BATCH_DELAY_IN_MS as u128);
+
+        // Ensure the timer does not expire before the maximum leader certificate delay.
+        let delay = MAX_LEADER_CERTIFICATE_DELAY_IN_SECS * 1000;
+        bft.leader_certificate_timer.store(now_in_millis() - MAX_BATCH_DELAY_IN_MS as i64 - 1, Ordering::SeqCst);
+        assert!(!bft.is_timer_expired());
+        bft.leader_certificate_timer.store(now_in_millis() - delay + 1000, Ordering::SeqCst);
+        assert!(!bft.is_timer_expired());
+
+        // Ensure the timer expires once the maximum leader certificate delay has elapsed.
+        bft.leader_certificate_timer.store(now_in_millis() - delay, Ordering::SeqCst);
+        assert!(bft.is_timer_expired());
+        Ok(())
+    }
+
     #[test]
     #[tracing_test::traced_test]
     fn test_is_leader_quorum_even_out_of_sync() -> Result<()> {
@@ -901,7 +1174,7 @@ mod tests {
         assert_eq!(storage.max_gc_rounds(), 10);
 
         // Initialize the BFT.
-        let bft = BFT::new(account, storage, ledger, None, &[], None)?;
+        let bft = BFT::new(account, storage, ledger, None, &[], None, PrimaryConfig::default(), None)?;
         assert!(bft.is_timer_expired()); // 0 + 5 < now()
 
         // Store is at round 1, and we are checking for round 2.
@@ -923,7 +1196,7 @@ mod tests {
         assert_eq!(storage.max_gc_rounds(), 10);
 
         // Initialize the BFT.
-        let bft = BFT::new(account, storage, ledger, None, &[], None)?;
+        let bft = BFT::new(account, storage, ledger, None, &[], None, PrimaryConfig::default(), None)?;
         assert!(bft.is_timer_expired()); // 0 + 5 < now()
 
         // Ensure this call fails on an even round.
@@ -944,7 +1217,7 @@ mod tests {
         assert_eq!(storage.max_gc_rounds(), 10);
 
         // Initialize the BFT.
-        let bft = BFT::new(account, storage, ledger, None, &[], None)?;
+        let bft = BFT::new(account, storage, ledger, None, &[], None, PrimaryConfig::default(), None)?;
 
         let result = bft.is_even_round_ready_for_next_round(IndexSet::new(), committee.clone(), 2);
         assert!(!result);
@@ -969,7 +1242,7 @@ mod tests {
         assert_eq!(storage.max_gc_rounds(), 10);
 
         // Initialize the BFT.
-        let bft = BFT::new(account, storage, ledger, None, &[], None)?;
+        let bft = BFT::new(account, storage, ledger, None, &[], None, PrimaryConfig::default(), None)?;
 
         // Ensure this call fails on an odd round.
         let result = bft.update_leader_certificate_to_even_round(1);
@@ -987,7 +1260,7 @@ mod tests {
         assert_eq!(storage.max_gc_rounds(), 10);
 
         // Initialize the BFT.
-        let bft = BFT::new(account, storage, ledger, None, &[], None)?;
+        let bft = BFT::new(account, storage, ledger, None, &[], None, PrimaryConfig::default(), None)?;
 
         // Ensure this call succeeds on an even round.
         let result = bft.update_leader_certificate_to_even_round(6);
@@ -1039,7 +1312,7 @@ mod tests {
 
         // Initialize the BFT.
         let account = Account::new(rng)?;
-        let bft = BFT::new(account, storage.clone(), ledger, None, &[], None)?;
+        let bft = BFT::new(account, storage.clone(), ledger, None, &[], None, PrimaryConfig::default(), None)?;
 
         // Set the leader certificate.
         *bft.leader_certificate.write() = Some(leader_certificate);
@@ -1077,7 +1350,8 @@ mod tests {
             // Initialize the storage.
             let storage = Storage::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
             // Initialize the BFT.
-            let bft = BFT::new(account.clone(), storage, ledger.clone(), None, &[], None)?;
+            let bft =
+                BFT::new(account.clone(), storage, ledger.clone(), None, &[], None, PrimaryConfig::default(), None)?;
 
             // Insert a mock DAG in the BFT.
             *bft.dag.write() = crate::helpers::dag::test_helpers::mock_dag_with_modified_last_committed_round(3);
@@ -1107,7 +1381,7 @@ mod tests {
             // Initialize the storage.
             let storage = Storage::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
             // Initialize the BFT.
-            let bft = BFT::new(account, storage, ledger, None, &[], None)?;
+            let bft = BFT::new(account, storage, ledger, None, &[], None, PrimaryConfig::default(), None)?;
 
             // Insert a mock DAG in the BFT.
             *bft.dag.write() = crate::helpers::dag::test_helpers::mock_dag_with_modified_last_committed_round(2);
@@ -1165,7 +1439,7 @@ mod tests {
         /* Test missing previous certificate. */
 
         // Initialize the BFT.
-        let bft = BFT::new(account, storage, ledger, None, &[], None)?;
+        let bft = BFT::new(account, storage, ledger, None, &[], None, PrimaryConfig::default(), None)?;
 
         // The expected error message.
         let error_msg = format!(
@@ -1179,4 +1453,188 @@ mod tests {
         assert_eq!(result.unwrap_err().to_string(), error_msg);
         Ok(())
     }
+
+    #[test]
+    #[tracing_test::traced_test]
+    fn test_is_linked() -> Result<()> {
+        let rng = &mut TestRng::default();
+
+        // Sample the test instance.
+        let (_, account, ledger, storage) = sample_test_instance(Some(1), 10, rng);
+        // Initialize the BFT.
+        let bft = BFT::new(account, storage, ledger, None, &[], None, PrimaryConfig::default(), None)?;
+
+        // Sample the certificates for round 2.
+        let round_2 = (0..4).map(|_| sample_batch_certificate_for_round(2, rng)).collect::<Vec<_>>();
+        // Sample a certificate for round 3, that references the first two certificates of round 2.
+        let round_3 = sample_batch_certificate_for_round_with_previous_certificate_ids(
+            3,
+            round_2[..2].iter().map(|c| c.id()).collect(),
+            rng,
+        );
+        // Sample a certificate for round 4, that references the certificate of round 3.
+        let round_4 = sample_batch_certificate_for_round_with_previous_certificate_ids(
+            4,
+            [round_3.id()].into_iter().collect(),
+            rng,
+        );
+        // Insert the certificates into the DAG.
+        for certificate in round_2.iter().chain([&round_3, &round_4]) {
+            bft.dag.write().insert(certificate.clone());
+        }
+
+        // Ensure the referenced certificates are linked, and the others are not.
+        assert!(bft.is_linked(&round_3, &round_4)?);
+        assert!(bft.is_linked(&round_2[0], &round_4)?);
+        assert!(bft.is_linked(&round_2[1], &round_4)?);
+        assert!(!bft.is_linked(&round_2[2], &round_4)?);
+        assert!(!bft.is_linked(&round_2[3], &round_3)?);
+        // Ensure the traversal fails if the DAG is missing a round.
+        assert!(bft.is_linked(&sample_batch_certificate_for_round(1, rng), &round_4).is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_commit_skips_unlinked_leader() -> Result<()> {
+        let rng = &mut TestRng::default();
+
+        // Sample the BFT with a committee of four validators, in which the linked leaders are active.
+        let (mut bft, committee, private_keys) = sample_bft_with_committee(rng);
+        bft.linked_leaders_height = 0;
+        let leader_2 = leader_index(&committee, &private_keys, 2);
+        let leader_4 = leader_index(&committee, &private_keys, 4);
+
+        // Sample the DAG, in which no certificate of round 3 references the leader certificate of round 2.
+        let round_1 = (0..4).map(|i| sample_member_certificate(1, i, &private_keys, &[], rng)).collect::<Vec<_>>();
+        let round_2 = (0..4)
+            .map(|i| sample_member_certificate(2, i, &private_keys, &round_1.iter().collect::<Vec<_>>(), rng))
+            .collect::<Vec<_>>();
+        let round_2_without_leader =
+            round_2.iter().enumerate().filter(|(i, _)| *i != leader_2).map(|(_, c)| c).collect::<Vec<_>>();
+        let round_3 = (0..4)
+            .map(|i| sample_member_certificate(3, i, &private_keys, &round_2_without_leader, rng))
+            .collect::<Vec<_>>();
+        let round_4 = (0..4)
+            .map(|i| sample_member_certificate(4, i, &private_keys, &round_3.iter().collect::<Vec<_>>(), rng))
+            .collect::<Vec<_>>();
+        let round_5 = (0..4)
+            .map(|i| sample_member_certificate(5, i, &private_keys, &round_4.iter().collect::<Vec<_>>(), rng))
+            .collect::<Vec<_>>();
+
+        // Subscribe to the consensus events.
+        let mut events = bft.primary.gateway().consensus_events().subscribe();
+        // Insert the certificates into the DAG, round by round.
+        for certificate in round_1.iter().chain(&round_2).chain(&round_3).chain(&round_4).chain(&round_5) {
+            bft.update_dag::<false>(certificate.clone()).await?;
+        }
+
+        // Ensure only the leader of round 4 was committed, and the unlinked leader of round 2 was skipped.
+        let mut committed_rounds = vec![];
+        while let Ok(event) = events.try_recv() {
+            if let ConsensusEvent::SubdagCommitted { round, .. } = event {
+                committed_rounds.push(round);
+            }
+        }
+        assert_eq!(committed_rounds, vec![4]);
+        assert_eq!(bft.dag.read().last_committed_round(), 4);
+        assert!(bft.dag.read().is_recently_committed(4, round_4[leader_4].id()));
+        assert!(!bft.dag.read().is_recently_committed(2, round_2[leader_2].id()));
+        Ok(())
+    }
+
+    /// Samples a DAG in which a single certificate of round 3 references the leader certificate of round 2,
+    /// which is below the availability threshold, but links the leader certificate of round 2 to round 4.
+    /// The DAG is inserted into a BFT with the given linked leaders height, and the BFT is returned with
+    /// the leader certificates of rounds 2 and 4, the certificate that voted for the leader of round 2,
+    /// and the committed rounds.
+    async fn commit_linked_leader_dag(
+        linked_leaders_height: u32,
+        rng: &mut TestRng,
+    ) -> Result<(BFT<CurrentNetwork>, [BatchCertificate<CurrentNetwork>; 3], Vec<u64>)> {
+        // Sample the BFT with a committee of four validators.
+        let (mut bft, committee, private_keys) = sample_bft_with_committee(rng);
+        bft.linked_leaders_height = linked_leaders_height;
+        let leader_2 = leader_index(&committee, &private_keys, 2);
+        let leader_4 = leader_index(&committee, &private_keys, 4);
+
+        // Sample the DAG.
+        let round_1 = (0..4).map(|i| sample_member_certificate(1, i, &private_keys, &[], rng)).collect::<Vec<_>>();
+        let round_2 = (0..4)
+            .map(|i| sample_member_certificate(2, i, &private_keys, &round_1.iter().collect::<Vec<_>>(), rng))
+            .collect::<Vec<_>>();
+        let round_2_without_leader =
+            round_2.iter().enumerate().filter(|(i, _)| *i != leader_2).map(|(_, c)| c).collect::<Vec<_>>();
+        let voter = (leader_2 + 1) % 4;
+        let round_3 = (0..4)
+            .map(|i| match i == voter {
+                true => sample_member_certificate(3, i, &private_keys, &round_2.iter().collect::<Vec<_>>(), rng),
+                false => sample_member_certificate(3, i, &private_keys, &round_2_without_leader, rng),
+            })
+            .collect::<Vec<_>>();
+        let round_4 = (0..4)
+            .map(|i| sample_member_certificate(4, i, &private_keys, &round_3.iter().collect::<Vec<_>>(), rng))
+            .collect::<Vec<_>>();
+        let round_5 = (0..4)
+            .map(|i| sample_member_certificate(5, i, &private_keys, &round_4.iter().collect::<Vec<_>>(), rng))
+            .collect::<Vec<_>>();
+
+        // Subscribe to the consensus events.
+        let mut events = bft.primary.gateway().consensus_events().subscribe();
+        // Insert the certificates into the DAG, round by round.
+        for certificate in round_1.iter().chain(&round_2).chain(&round_3) {
+            bft.update_dag::<false>(certificate.clone()).await?;
+        }
+        // Ensure the leader of round 2 was not committed on its own, as it lacks the votes.
+        assert_eq!(bft.dag.read().last_committed_round(), 0);
+        for certificate in round_4.iter().chain(&round_5) {
+            bft.update_dag::<false>(certificate.clone()).await?;
+        }
+
+        // Retrieve the committed rounds.
+        let mut committed_rounds = vec![];
+        while let Ok(event) = events.try_recv() {
+            if let ConsensusEvent::SubdagCommitted { round, .. } = event {
+                committed_rounds.push(round);
+            }
+        }
+        let certificates = [round_2[leader_2].clone(), round_4[leader_4].clone(), round_3[voter].clone()];
+        Ok((bft, certificates, committed_rounds))
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_commit_linked_leader_before_leader() -> Result<()> {
+        let rng = &mut TestRng::default();
+
+        // Sample the DAG, and commit it once the linked leaders are active.
+        let (bft, [leader_2, leader_4, voter], committed_rounds) = commit_linked_leader_dag(0, rng).await?;
+
+        // Ensure the linked leader of round 2 was committed before the leader of round 4.
+        assert_eq!(committed_rounds, vec![2, 4]);
+        assert_eq!(bft.dag.read().last_committed_round(), 4);
+        assert!(bft.dag.read().is_recently_committed(2, leader_2.id()));
+        assert!(bft.dag.read().is_recently_committed(4, leader_4.id()));
+        // Ensure the election certificates of the linked leader are only the certificates that voted for it.
+        let election_certificate_ids = bft.get_election_certificate_ids(&leader_2);
+        assert_eq!(election_certificate_ids, [voter.id()].into_iter().collect());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_commit_linked_leader_below_activation_height() -> Result<()> {
+        let rng = &mut TestRng::default();
+
+        // Sample the DAG, and commit it before the linked leaders are active.
+        let (bft, [leader_2, leader_4, _], committed_rounds) = commit_linked_leader_dag(1, rng).await?;
+        assert!(!bft.is_linked_leaders_active());
+
+        // Ensure only the leader of round 4 was committed, as the linked leader of round 2 is not committed yet.
+        assert_eq!(committed_rounds, vec![4]);
+        assert_eq!(bft.dag.read().last_committed_round(), 4);
+        assert!(!bft.dag.read().is_recently_committed(2, leader_2.id()));
+        assert!(bft.dag.read().is_recently_committed(4, leader_4.id()));
+        Ok(())
+    }
 }
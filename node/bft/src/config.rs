@@ -0,0 +1,62 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+use crate::{
+    helpers::{BatchPolicy, SigningLease, WarmUp},
+    MAX_GC_ROUNDS,
+};
+
+use std::{path::PathBuf, sync::Arc};
+
+/// The configuration of a primary, which is passed to the primary on initialization.
+#[derive(Debug)]
+pub struct PrimaryConfig {
+    /// The startup warm-up, which must complete before the primary proposes a batch.
+    pub warm_up: WarmUp,
+    /// The number of workers spawned by the primary.
+    ///
+    /// Note: Transmissions are assigned to workers locally, so validators may run a different number of workers.
+    pub num_workers: u8,
+    /// The policy for sealing the batches proposed by the primary.
+    pub batch_policy: BatchPolicy,
+    /// The number of rounds that the storage of the primary retains behind the last committed round.
+    pub committed_gc_depth: u64,
+    /// The path to the signing journal, which protects the primary from double-signing, if it is enabled.
+    pub signing_journal: Option<PathBuf>,
+    /// The signing lease the primary must hold to sign, if the validator serves heartbeats to a standby.
+    pub signing_lease: Option<Arc<SigningLease>>,
+    /// The path to persist the misbehavior reports of the other validators to, if the misbehavior log is persisted.
+    pub misbehavior_log: Option<PathBuf>,
+    /// The boolean flag for whether the system clock is adjusted by the median offset of the timestamps
+    /// observed from validators.
+    pub clock_sync: bool,
+}
+
+impl Default for PrimaryConfig {
+    /// Initializes a new configuration with a disabled warm-up, one worker, the default batch policy,
+    /// the maximum committed GC depth, no signing journal or misbehavior log, and clock sync disabled.
+    fn default() -> Self {
+        Self {
+            warm_up: WarmUp::disabled(),
+            num_workers: 1,
+            batch_policy: BatchPolicy::default(),
+            committed_gc_depth: MAX_GC_ROUNDS,
+            signing_journal: None,
+            signing_lease: None,
+            misbehavior_log: None,
+            clock_sync: false,
+        }
+    }
+}
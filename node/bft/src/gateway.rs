@@ -14,12 +14,26 @@
 
 use crate::{
     events::{EventCodec, PrimaryPing},
-    helpers::{assign_to_worker, Cache, PrimarySender, Resolver, SyncSender, WorkerSender},
+    helpers::{
+        assign_to_worker,
+        Cache,
+        CommitteeChange,
+        CommitteeManager,
+        ConsensusEvent,
+        ConsensusEventStream,
+        MisbehaviorLog,
+        MisbehaviorReport,
+        PrimarySender,
+        Resolver,
+        SyncSender,
+        WorkerSender,
+    },
     spawn_blocking,
     CONTEXT,
     MAX_BATCH_DELAY_IN_MS,
     MAX_GC_ROUNDS,
     MAX_TRANSMISSIONS_PER_BATCH,
+    MAX_TRANSMISSIONS_PER_WORKER_BATCH,
     MAX_TRANSMISSIONS_PER_WORKER_PING,
     MEMORY_POOL_PORT,
 };
@@ -39,22 +53,27 @@ use snarkos_node_bft_events::{
     TransmissionResponse,
     ValidatorsRequest,
     ValidatorsResponse,
+    WorkerBatch,
 };
 use snarkos_node_bft_ledger_service::LedgerService;
 use snarkos_node_sync::communication_service::CommunicationService;
 use snarkos_node_tcp::{
     is_bogon_ip,
     is_unspecified_or_broadcast_ip,
-    protocols::{Disconnect, Handshake, OnConnect, Reading, Writing},
+    protocols::{Disconnect, Handshake, OnConnect, Priority, Reading, Writing},
     Config,
     Connection,
     ConnectionSide,
+    PeerDenyList,
     Tcp,
     P2P,
 };
 use snarkvm::{
     console::prelude::*,
-    ledger::{committee::Committee, narwhal::Data},
+    ledger::{
+        committee::Committee,
+        narwhal::{Data, TransmissionID},
+    },
     prelude::Address,
 };
 
@@ -63,7 +82,7 @@ use futures::SinkExt;
 use indexmap::{IndexMap, IndexSet};
 use parking_lot::{Mutex, RwLock};
 use rand::seq::{IteratorRandom, SliceRandom};
-use std::{collections::HashSet, future::Future, io, net::SocketAddr, sync::Arc, time::Duration};
+use std::{collections::HashSet, future::Future, io, net::SocketAddr, path::PathBuf, sync::Arc, time::Duration};
 use tokio::{
     net::TcpStream,
     sync::{oneshot, OnceCell},
@@ -82,6 +101,9 @@ const MAX_CONNECTION_ATTEMPTS: usize = 10;
 /// The maximum interval to restrict a peer.
 const RESTRICTED_INTERVAL: i64 = (MAX_CONNECTION_ATTEMPTS as u64 * MAX_BATCH_DELAY_IN_MS / 1000) as i64; // seconds
 
+/// The duration after which the resolver mappings of a disconnected validator are removed.
+const RESOLVER_TTL_IN_SECS: u64 = 3600; // seconds
+
 /// The minimum number of validators to maintain a connection to.
 const MIN_CONNECTED_VALIDATORS: usize = 175;
 /// The maximum number of validators to send in a validators response event.
@@ -107,8 +129,16 @@ pub struct Gateway<N: Network> {
     cache: Arc<Cache<N>>,
     /// The resolver.
     resolver: Arc<Resolver<N>>,
+    /// The committee manager.
+    committee: Arc<CommitteeManager<N>>,
+    /// The log of the misbehavior reports, which determines the validators that are banned.
+    misbehavior: Arc<MisbehaviorLog<N>>,
+    /// The stream of consensus events, shared by the primary and the BFT.
+    consensus_events: ConsensusEventStream<N>,
     /// The set of trusted validators.
     trusted_validators: IndexSet<SocketAddr>,
+    /// The peer deny list, if it is enabled.
+    peer_deny_list: Option<Arc<PeerDenyList>>,
     /// The map of connected peer IPs to their peer handlers.
     connected_peers: Arc<RwLock<IndexSet<SocketAddr>>>,
     /// The set of handshaking peers. While `Tcp` already recognizes the connecting IP addresses
@@ -133,6 +163,8 @@ impl<N: Network> Gateway<N> {
         ledger: Arc<dyn LedgerService<N>>,
         ip: Option<SocketAddr>,
         trusted_validators: &[SocketAddr],
+        peer_deny_list: Option<Arc<PeerDenyList>>,
+        misbehavior_log: Option<PathBuf>,
         dev: Option<u16>,
     ) -> Result<Self> {
         // Initialize the gateway IP.
@@ -150,7 +182,11 @@ impl<N: Network> Gateway<N> {
             tcp,
             cache: Default::default(),
             resolver: Default::default(),
+            committee: Default::default(),
+            misbehavior: Arc::new(MisbehaviorLog::load(misbehavior_log)?),
+            consensus_events: Default::default(),
             trusted_validators: trusted_validators.iter().copied().collect(),
+            peer_deny_list,
             connected_peers: Default::default(),
             connecting_peers: Default::default(),
             primary_sender: Default::default(),
@@ -191,6 +227,8 @@ impl<N: Network> Gateway<N> {
 
         // Initialize the heartbeat.
         self.initialize_heartbeat();
+        // Initialize the committee listener.
+        self.initialize_committee_listener();
 
         info!("Started the gateway for the memory pool at '{}'", self.local_ip());
     }
@@ -274,6 +312,21 @@ impl<N: Network> Gateway<N> {
         &self.resolver
     }
 
+    /// Returns the committee manager.
+    pub fn committee(&self) -> &CommitteeManager<N> {
+        &self.committee
+    }
+
+    /// Returns the misbehavior log.
+    pub fn misbehavior(&self) -> &MisbehaviorLog<N> {
+        &self.misbehavior
+    }
+
+    /// Returns the stream of consensus events.
+    pub const fn consensus_events(&self) -> &ConsensusEventStream<N> {
+        &self.consensus_events
+    }
+
     /// Returns the primary sender.
     pub fn primary_sender(&self) -> &PrimarySender<N> {
         self.primary_sender.get().expect("Primary sender not set in gateway")
@@ -310,6 +363,11 @@ impl<N: Network> Gateway<N> {
         self.connecting_peers.lock().contains(&ip)
     }
 
+    /// Returns `true` if the given listener IP of a peer is in the peer deny list.
+    pub fn is_denied_peer(&self, peer_ip: &SocketAddr) -> bool {
+        self.peer_deny_list.as_ref().map_or(false, |deny_list| deny_list.contains(peer_ip))
+    }
+
     /// Returns `true` if the given peer IP is an authorized validator.
     pub fn is_authorized_validator_ip(&self, ip: SocketAddr) -> bool {
         // If the peer IP is in the trusted validators, return early.
@@ -331,6 +389,12 @@ impl<N: Network> Gateway<N> {
         //  1. New validators should be able to connect immediately once bonded as a committee member.
         //  2. Existing validators must remain connected until they are no longer bonded as a committee member.
         //     (i.e. meaning they must stay online until the next block has been produced)
+        // Additionally, the members of the next epoch's committee are honored once it is locked in,
+        // and the members of the previous epoch's committee are honored during the epoch handover window.
+        // The validators that the operator removed from the current committee are never authorized.
+        if self.committee.is_removed(validator_address) {
+            return false;
+        }
         self.ledger
             .get_previous_committee_for_round(self.ledger.latest_round())
             .map_or(false, |committee| committee.is_committee_member(validator_address))
@@ -338,6 +402,7 @@ impl<N: Network> Gateway<N> {
                 .ledger
                 .current_committee()
                 .map_or(false, |committee| committee.is_committee_member(validator_address))
+            || self.committee.epochs().is_honored_member(self.ledger.latest_round(), validator_address)
     }
 
     /// Returns the maximum number of connected peers.
@@ -397,6 +462,10 @@ impl<N: Network> Gateway<N> {
         if self.is_connecting_ip(peer_ip) {
             bail!("{CONTEXT} Dropping connection attempt to '{peer_ip}' (already connecting)")
         }
+        // Ensure the peer is not in the peer deny list.
+        if self.is_denied_peer(&peer_ip) {
+            bail!("{CONTEXT} Dropping connection attempt to '{peer_ip}' (denied)")
+        }
         Ok(())
     }
 
@@ -607,6 +676,22 @@ impl<N: Network> Gateway<N> {
                 }
                 Ok(())
             }
+            Event::CatchUpRequest(catch_up_request) => {
+                // If a sync sender was provided, send the catch-up request to the sync module.
+                if let Some(sync_sender) = self.sync_sender.get() {
+                    // Send the catch-up request to the sync module.
+                    let _ = sync_sender.tx_catch_up_request.send((peer_ip, catch_up_request)).await;
+                }
+                Ok(())
+            }
+            Event::CatchUpResponse(catch_up_response) => {
+                // If a sync sender was provided, send the catch-up response to the sync module.
+                if let Some(sync_sender) = self.sync_sender.get() {
+                    // Send the catch-up response to the sync module.
+                    let _ = sync_sender.tx_catch_up_response.send((peer_ip, catch_up_response)).await;
+                }
+                Ok(())
+            }
             Event::CertificateRequest(certificate_request) => {
                 // If a sync sender was provided, send the certificate request to the sync module.
                 if let Some(sync_sender) = self.sync_sender.get() {
@@ -630,6 +715,11 @@ impl<N: Network> Gateway<N> {
             Event::Disconnect(disconnect) => {
                 bail!("{CONTEXT} {:?}", disconnect.reason)
             }
+            Event::Equivocation(equivocation) => {
+                // Send the evidence of equivocation to the primary.
+                let _ = self.primary_sender().tx_equivocation.send((peer_ip, equivocation)).await;
+                Ok(())
+            }
             Event::PrimaryPing(ping) => {
                 let PrimaryPing { version, block_locators, primary_certificate, batch_certificates } = ping;
 
@@ -750,32 +840,82 @@ impl<N: Network> Gateway<N> {
                 }
                 Ok(())
             }
+            Event::WorkerBatch(worker_batch) => {
+                // Ensure the number of transmissions is not too large.
+                ensure!(
+                    worker_batch.transmission_ids.len() <= MAX_TRANSMISSIONS_PER_WORKER_BATCH,
+                    "{CONTEXT} Received too many transmissions"
+                );
+                // Ensure the digest matches the transmission IDs.
+                if WorkerBatch::compute_digest(&worker_batch.transmission_ids)? != worker_batch.digest {
+                    bail!("{CONTEXT} Received a worker batch with an invalid digest")
+                }
+                // Send the transmission IDs to the workers, which fetch the missing transmissions.
+                self.send_transmission_ids_to_workers(peer_ip, worker_batch.transmission_ids).await;
+                Ok(())
+            }
             Event::WorkerPing(ping) => {
                 // Ensure the number of transmissions is not too large.
                 ensure!(
                     ping.transmission_ids.len() <= MAX_TRANSMISSIONS_PER_WORKER_PING,
                     "{CONTEXT} Received too many transmissions"
                 );
-                // Retrieve the number of workers.
-                let num_workers = self.num_workers();
-                // Iterate over the transmission IDs.
-                for transmission_id in ping.transmission_ids.into_iter() {
-                    // Determine the worker ID.
-                    let Ok(worker_id) = assign_to_worker(transmission_id, num_workers) else {
-                        warn!("{CONTEXT} Unable to assign transmission ID '{transmission_id}' to a worker");
-                        continue;
-                    };
-                    // Send the transmission ID to the worker.
-                    if let Some(sender) = self.get_worker_sender(worker_id) {
-                        // Send the transmission ID to the worker.
-                        let _ = sender.tx_worker_ping.send((peer_ip, transmission_id)).await;
-                    }
-                }
+                // Send the transmission IDs to the workers, which fetch the missing transmissions.
+                self.send_transmission_ids_to_workers(peer_ip, ping.transmission_ids).await;
                 Ok(())
             }
         }
     }
 
+    /// Sends the given transmission IDs from the peer to the workers they are assigned to.
+    async fn send_transmission_ids_to_workers(
+        &self,
+        peer_ip: SocketAddr,
+        transmission_ids: IndexSet<TransmissionID<N>>,
+    ) {
+        // Retrieve the number of workers.
+        let num_workers = self.num_workers();
+        // Iterate over the transmission IDs.
+        for transmission_id in transmission_ids.into_iter() {
+            // Determine the worker ID.
+            let Ok(worker_id) = assign_to_worker(transmission_id, num_workers) else {
+                warn!("{CONTEXT} Unable to assign transmission ID '{transmission_id}' to a worker");
+                continue;
+            };
+            // Send the transmission ID to the worker.
+            if let Some(sender) = self.get_worker_sender(worker_id) {
+                // Send the transmission ID to the worker.
+                let _ = sender.tx_worker_ping.send((peer_ip, transmission_id)).await;
+            }
+        }
+    }
+
+    /// Records the given misbehavior report, and if the validator is now banned, disconnects from it.
+    pub fn report_misbehavior(&self, report: MisbehaviorReport<N>) {
+        let (address, kind, round) = (report.address, report.kind, report.round);
+        warn!("{CONTEXT} Validator '{address}' misbehaved in round {round} ({kind}) - {}", report.details);
+        // Record the report, and determine if the validator is now banned.
+        let peer_ip = report.peer_ip.or_else(|| self.resolver.get_peer_ip_for_address(address));
+        // Emit the misbehavior to the consensus event subscribers.
+        self.consensus_events.emit(ConsensusEvent::ValidatorMisbehaved(report.clone()));
+        if self.misbehavior.record(report) {
+            // Disconnect from the banned validator, if it is connected.
+            if let Some(peer_ip) = peer_ip.filter(|peer_ip| self.is_connected_ip(*peer_ip)) {
+                warn!("{CONTEXT} Disconnecting from '{peer_ip}' - Validator '{address}' is banned for misbehavior");
+                self.disconnect(peer_ip);
+            }
+        }
+    }
+
+    /// Disconnects from the connected peers that are in the peer deny list.
+    pub fn disconnect_denied_peers(&self) {
+        let connected_peers = self.connected_peers.read().iter().copied().collect::<Vec<_>>();
+        for peer_ip in connected_peers.into_iter().filter(|peer_ip| self.is_denied_peer(peer_ip)) {
+            debug!("{CONTEXT} Disconnecting from '{peer_ip}' (denied)");
+            self.disconnect(peer_ip);
+        }
+    }
+
     /// Disconnects from the given peer IP, if the peer is connected.
     pub fn disconnect(&self, peer_ip: SocketAddr) -> JoinHandle<()> {
         let gateway = self.clone();
@@ -804,6 +944,26 @@ impl<N: Network> Gateway<N> {
         });
     }
 
+    /// Initialize a listener that reconciles the connections with every change in the committee,
+    /// whether it was derived from the ledger or made by the operator.
+    fn initialize_committee_listener(&self) {
+        let self_clone = self.clone();
+        let mut receiver = self.committee.subscribe();
+        self.spawn(async move {
+            // Retrieve the view of the committee, if it was already derived.
+            let mut previous = receiver.borrow_and_update().clone();
+            while receiver.changed().await.is_ok() {
+                let current = receiver.borrow_and_update().clone();
+                if let Some(view) = &current {
+                    // Reconcile the change from the previous view, which covers any swaps in between.
+                    let change = CommitteeChange::new(previous.as_ref().map(|view| view.committee()), view.committee());
+                    self_clone.reconcile_committee_change(&change);
+                }
+                previous = current;
+            }
+        });
+    }
+
     /// Spawns a task with the given future; it should only be used for long-running tasks.
     #[allow(dead_code)]
     fn spawn<T: Future<Output = ()> + Send + 'static>(&self, future: T) {
@@ -826,10 +986,14 @@ impl<N: Network> Gateway<N> {
         self.log_connected_validators();
         // Keep the trusted validators connected.
         self.handle_trusted_validators();
+        // Reconciles the connections and resolver mappings with any change in the committee.
+        self.handle_committee_change();
         // Removes any validators that not in the current committee.
         self.handle_unauthorized_validators();
         // If the number of connected validators is less than the minimum, send a `ValidatorsRequest`.
         self.handle_min_connected_validators();
+        // Removes the resolver mappings of any expired or departed validators.
+        self.handle_stale_resolver_mappings();
     }
 
     /// Logs the connected validators.
@@ -887,6 +1051,64 @@ impl<N: Network> Gateway<N> {
         });
     }
 
+    /// This function derives the current committee from the ledger, and if it changed, removes the resolver mappings
+    /// of the validators that departed the committee.
+    pub(crate) fn handle_committee_change(&self) {
+        // Retrieve the current committee from the ledger.
+        let committee = match self.ledger.current_committee() {
+            Ok(committee) => committee,
+            Err(error) => return warn!("{CONTEXT} Failed to retrieve the current committee - {error}"),
+        };
+        // Lock in the ledger's committees for the current and next epoch.
+        if let Err(error) = self.committee.lock_in_epochs(self.ledger.latest_round(), &*self.ledger) {
+            warn!("{CONTEXT} Failed to lock in the committee for the next epoch - {error}");
+        }
+        // Swap in the committee, returning early if it is unchanged.
+        let Some(change) = self.committee.update(committee.clone()) else { return };
+        // Remove the mappings of the validators that departed the committee.
+        let starting_round = change.starting_round;
+        let is_member = |address: &Address<N>| committee.is_committee_member(*address);
+        if self.resolver.invalidate_for_committee(starting_round, is_member) {
+            debug!("{CONTEXT} Invalidated the resolver mappings for the committee at round {starting_round}");
+        }
+        // Note: The committee listener reconciles the connections with the change in the committee.
+    }
+
+    /// Logs the given change in the committee, and if the membership changed,
+    /// disconnects any validators that are no longer authorized.
+    fn reconcile_committee_change(&self, change: &CommitteeChange<N>) {
+        let starting_round = change.starting_round;
+        // Log the change in the committee.
+        for (address, stake) in &change.joined {
+            debug!("{CONTEXT} Validator '{address}' joined the committee at round {starting_round} with {stake} stake");
+        }
+        for (address, stake) in &change.departed {
+            debug!("{CONTEXT} Validator '{address}' departed the committee at round {starting_round} ({stake} stake)");
+        }
+        for (address, previous_stake, stake) in &change.restaked {
+            trace!("{CONTEXT} Validator '{address}' changed its stake from {previous_stake} to {stake}");
+        }
+        // If the membership changed, disconnect any validators that are no longer authorized.
+        if change.is_membership_changed() {
+            self.handle_unauthorized_validators();
+        }
+    }
+
+    /// This function removes the resolver mappings of any disconnected validators that have expired,
+    /// and checks the consistency of the resolver.
+    fn handle_stale_resolver_mappings(&self) {
+        // Remove the mappings of the disconnected validators that have expired.
+        let connected_peers = self.connected_peers().read().clone();
+        let ttl = Duration::from_secs(RESOLVER_TTL_IN_SECS);
+        for peer_ip in self.resolver.remove_expired_peers(ttl, |peer_ip| connected_peers.contains(peer_ip)) {
+            debug!("{CONTEXT} Removed the expired resolver mappings of '{peer_ip}'");
+        }
+        // Ensure the resolver mappings are consistent.
+        if let Err(error) = self.resolver.check_consistency() {
+            warn!("{CONTEXT} {error}");
+        }
+    }
+
     /// This function sends a `ValidatorsRequest` to a random validator,
     /// if the number of connected validators is less than the minimum.
     fn handle_min_connected_validators(&self) {
@@ -1028,6 +1250,33 @@ impl<N: Network> Writing for Gateway<N> {
     fn codec(&self, _peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
         Default::default()
     }
+
+    /// Returns the priority lane that the given outbound event is queued in.
+    ///
+    /// Batch proposals, signatures, and certificates are sent ahead of sync traffic, while gossip
+    /// (worker pings and validator lists) is dropped when its lane is full.
+    fn priority(&self, event: &Self::Message) -> Priority {
+        match event {
+            Event::BatchPropose(..)
+            | Event::BatchSignature(..)
+            | Event::BatchCertified(..)
+            | Event::CertificateRequest(..)
+            | Event::CertificateResponse(..)
+            | Event::ChallengeRequest(..)
+            | Event::ChallengeResponse(..)
+            | Event::Disconnect(..)
+            | Event::PrimaryPing(..) => Priority::High,
+            Event::BlockRequest(..)
+            | Event::BlockResponse(..)
+            | Event::CatchUpRequest(..)
+            | Event::CatchUpResponse(..)
+            | Event::Equivocation(..)
+            | Event::TransmissionRequest(..)
+            | Event::TransmissionResponse(..)
+            | Event::WorkerBatch(..) => Priority::Normal,
+            Event::ValidatorsRequest(..) | Event::ValidatorsResponse(..) | Event::WorkerPing(..) => Priority::Low,
+        }
+    }
 }
 
 #[async_trait]
@@ -1262,17 +1511,27 @@ impl<N: Network> Gateway<N> {
     /// Verifies the given challenge request. Returns a disconnect reason if the request is invalid.
     fn verify_challenge_request(&self, peer_addr: SocketAddr, event: &ChallengeRequest<N>) -> Option<DisconnectReason> {
         // Retrieve the components of the challenge request.
-        let &ChallengeRequest { version, listener_port: _, address, nonce: _ } = event;
+        let &ChallengeRequest { version, listener_port, address, nonce: _ } = event;
         // Ensure the event protocol version is not outdated.
         if version < Event::<N>::VERSION {
             warn!("{CONTEXT} Gateway is dropping '{peer_addr}' on version {version} (outdated)");
             return Some(DisconnectReason::OutdatedClientVersion);
         }
+        // Ensure the listener IP of the peer is not in the peer deny list.
+        if self.is_denied_peer(&SocketAddr::new(peer_addr.ip(), listener_port)) {
+            debug!("{CONTEXT} Gateway is dropping '{peer_addr}' (denied)");
+            return Some(DisconnectReason::NoReasonGiven);
+        }
         // Ensure the address is a current committee member.
         if !self.is_authorized_validator_address(address) {
             warn!("{CONTEXT} Gateway is dropping '{peer_addr}' for being an unauthorized validator ({address})");
             return Some(DisconnectReason::ProtocolViolation);
         }
+        // Ensure the address is not banned for misbehavior.
+        if self.misbehavior.is_banned(address) {
+            warn!("{CONTEXT} Gateway is dropping '{peer_addr}' for being banned for misbehavior ({address})");
+            return Some(DisconnectReason::ProtocolViolation);
+        }
         // Ensure the address is not already connected.
         if self.is_connected_address(address) {
             warn!("{CONTEXT} Gateway is dropping '{peer_addr}' for being already connected ({address})");
@@ -1309,7 +1568,7 @@ impl<N: Network> Gateway<N> {
 mod prop_tests {
     use crate::{
         gateway::prop_tests::GatewayAddress::{Dev, Prod},
-        helpers::{init_primary_channels, init_worker_channels, Storage},
+        helpers::{init_primary_channels, init_worker_batch_channels, init_worker_channels, Storage},
         Gateway,
         Worker,
         MAX_WORKERS,
@@ -1381,6 +1640,8 @@ mod prop_tests {
                         storage.ledger().clone(),
                         address.ip(),
                         &[],
+                        None,
+                        None,
                         address.port(),
                     )
                     .unwrap()
@@ -1426,7 +1687,8 @@ mod prop_tests {
         let (storage, _, private_key, dev) = input;
         let account = Account::try_from(private_key).unwrap();
 
-        let gateway = Gateway::new(account.clone(), storage.ledger().clone(), dev.ip(), &[], dev.port()).unwrap();
+        let gateway =
+            Gateway::new(account.clone(), storage.ledger().clone(), dev.ip(), &[], None, None, dev.port()).unwrap();
         let tcp_config = gateway.tcp().config();
         assert_eq!(tcp_config.listener_ip, Some(IpAddr::V4(Ipv4Addr::LOCALHOST)));
         assert_eq!(tcp_config.desired_listening_port, Some(MEMORY_POOL_PORT + dev.port().unwrap()));
@@ -1441,7 +1703,8 @@ mod prop_tests {
         let (storage, _, private_key, dev) = input;
         let account = Account::try_from(private_key).unwrap();
 
-        let gateway = Gateway::new(account.clone(), storage.ledger().clone(), dev.ip(), &[], dev.port()).unwrap();
+        let gateway =
+            Gateway::new(account.clone(), storage.ledger().clone(), dev.ip(), &[], None, None, dev.port()).unwrap();
         let tcp_config = gateway.tcp().config();
         if let Some(socket_addr) = dev.ip() {
             assert_eq!(tcp_config.listener_ip, Some(socket_addr.ip()));
@@ -1466,9 +1729,10 @@ mod prop_tests {
         let worker_storage = storage.clone();
         let account = Account::try_from(private_key).unwrap();
 
-        let gateway = Gateway::new(account, storage.ledger().clone(), dev.ip(), &[], dev.port()).unwrap();
+        let gateway = Gateway::new(account, storage.ledger().clone(), dev.ip(), &[], None, None, dev.port()).unwrap();
 
         let (primary_sender, _) = init_primary_channels();
+        let (tx_worker_batch, _) = init_worker_batch_channels();
 
         let (workers, worker_senders) = {
             // Construct a map of the worker senders.
@@ -1481,11 +1745,17 @@ mod prop_tests {
                 let (tx_worker, rx_worker) = init_worker_channels();
                 // Construct the worker instance.
                 let ledger = Arc::new(MockLedgerService::new(committee.clone()));
-                let worker =
-                    Worker::new(id, Arc::new(gateway.clone()), worker_storage.clone(), ledger, Default::default())
-                        .unwrap();
+                let worker = Worker::new(
+                    id,
+                    workers_count,
+                    Arc::new(gateway.clone()),
+                    worker_storage.clone(),
+                    ledger,
+                    Default::default(),
+                )
+                .unwrap();
                 // Run the worker instance.
-                worker.run(rx_worker);
+                worker.run(rx_worker, tx_worker_batch.clone());
 
                 // Add the worker and the worker sender to maps
                 workers.insert(id, worker);
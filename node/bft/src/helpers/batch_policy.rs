@@ -0,0 +1,134 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{MAX_BATCH_DELAY_IN_MS, MAX_BATCH_SIZE_IN_BYTES, MAX_TRANSMISSIONS_PER_BATCH};
+
+use anyhow::{bail, ensure, Result};
+use std::time::Duration;
+
+/// Ensures a batch with the given number of transmissions and size is within the protocol limits.
+pub fn check_batch_limits(num_transmissions: usize, num_bytes: usize) -> Result<()> {
+    if num_transmissions > MAX_TRANSMISSIONS_PER_BATCH {
+        bail!("Batch contains {num_transmissions} transmissions (max = {MAX_TRANSMISSIONS_PER_BATCH})")
+    }
+    if num_bytes > MAX_BATCH_SIZE_IN_BYTES {
+        bail!("Batch contains {num_bytes} bytes of transmissions (max = {MAX_BATCH_SIZE_IN_BYTES})")
+    }
+    Ok(())
+}
+
+/// The policy for sealing a batch: a batch is proposed once it is full, or once the maximum delay has elapsed.
+///
+/// Note: The policy only governs the batches proposed by this node. Batches received from peers
+/// are checked against the protocol limits, so validators may run different policies.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BatchPolicy {
+    /// The maximum number of transmissions in a batch.
+    max_transmissions: usize,
+    /// The maximum number of bytes of transmissions in a batch.
+    max_bytes: usize,
+    /// The maximum number of milliseconds to wait before proposing a batch.
+    max_delay_in_ms: u64,
+}
+
+impl Default for BatchPolicy {
+    /// Initializes a new instance of the batch policy, using the protocol limits.
+    fn default() -> Self {
+        Self {
+            max_transmissions: MAX_TRANSMISSIONS_PER_BATCH,
+            max_bytes: MAX_BATCH_SIZE_IN_BYTES,
+            max_delay_in_ms: MAX_BATCH_DELAY_IN_MS,
+        }
+    }
+}
+
+impl BatchPolicy {
+    /// Initializes a new batch policy, ensuring it is within the protocol limits.
+    pub fn new(max_transmissions: usize, max_bytes: usize, max_delay_in_ms: u64) -> Result<Self> {
+        ensure!(
+            (1..=MAX_TRANSMISSIONS_PER_BATCH).contains(&max_transmissions),
+            "The maximum transmissions per batch must be between 1 and {MAX_TRANSMISSIONS_PER_BATCH}"
+        );
+        ensure!(
+            (1..=MAX_BATCH_SIZE_IN_BYTES).contains(&max_bytes),
+            "The maximum batch size must be between 1 and {MAX_BATCH_SIZE_IN_BYTES} bytes"
+        );
+        ensure!(
+            (1..=MAX_BATCH_DELAY_IN_MS).contains(&max_delay_in_ms),
+            "The maximum batch delay must be between 1 and {MAX_BATCH_DELAY_IN_MS} ms"
+        );
+        Ok(Self { max_transmissions, max_bytes, max_delay_in_ms })
+    }
+
+    /// Returns the maximum number of transmissions in a batch.
+    pub const fn max_transmissions(&self) -> usize {
+        self.max_transmissions
+    }
+
+    /// Returns the maximum number of bytes of transmissions in a batch.
+    pub const fn max_bytes(&self) -> usize {
+        self.max_bytes
+    }
+
+    /// Returns the maximum delay before proposing a batch.
+    pub const fn max_delay(&self) -> Duration {
+        Duration::from_millis(self.max_delay_in_ms)
+    }
+
+    /// Returns `true` if a batch with the given number of transmissions should be sealed.
+    pub const fn is_full(&self, num_transmissions: usize) -> bool {
+        num_transmissions >= self.max_transmissions
+    }
+
+    /// Returns `true` if a transmission of the given size fits in a batch of `num_transmissions` and `num_bytes`.
+    pub const fn fits(&self, num_transmissions: usize, num_bytes: usize, transmission_size: usize) -> bool {
+        num_transmissions < self.max_transmissions && num_bytes.saturating_add(transmission_size) <= self.max_bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_policy() {
+        // Ensure the default policy uses the protocol limits.
+        let policy = BatchPolicy::default();
+        assert_eq!(policy.max_transmissions(), MAX_TRANSMISSIONS_PER_BATCH);
+        assert_eq!(policy.max_bytes(), MAX_BATCH_SIZE_IN_BYTES);
+        assert_eq!(policy.max_delay(), Duration::from_millis(MAX_BATCH_DELAY_IN_MS));
+
+        // Ensure the policy must be within the protocol limits.
+        assert!(BatchPolicy::new(0, 1024, 100).is_err());
+        assert!(BatchPolicy::new(MAX_TRANSMISSIONS_PER_BATCH + 1, 1024, 100).is_err());
+        assert!(BatchPolicy::new(10, MAX_BATCH_SIZE_IN_BYTES + 1, 100).is_err());
+        assert!(BatchPolicy::new(10, 1024, 0).is_err());
+        assert!(BatchPolicy::new(10, 1024, MAX_BATCH_DELAY_IN_MS + 1).is_err());
+
+        // Ensure a batch is sealed when full, by number of transmissions or bytes.
+        let policy = BatchPolicy::new(2, 1024, 100).unwrap();
+        assert!(!policy.is_full(1));
+        assert!(policy.is_full(2));
+        assert!(policy.fits(1, 512, 512));
+        assert!(!policy.fits(1, 513, 512));
+        assert!(!policy.fits(2, 0, 1));
+    }
+
+    #[test]
+    fn test_check_batch_limits() {
+        assert!(check_batch_limits(MAX_TRANSMISSIONS_PER_BATCH, MAX_BATCH_SIZE_IN_BYTES).is_ok());
+        assert!(check_batch_limits(MAX_TRANSMISSIONS_PER_BATCH + 1, 0).is_err());
+        assert!(check_batch_limits(0, MAX_BATCH_SIZE_IN_BYTES + 1).is_err());
+    }
+}
@@ -15,8 +15,11 @@
 use crate::events::{
     BatchPropose,
     BatchSignature,
+    CatchUpRequest,
+    CatchUpResponse,
     CertificateRequest,
     CertificateResponse,
+    Equivocation,
     TransmissionRequest,
     TransmissionResponse,
 };
@@ -154,6 +157,7 @@ pub struct PrimarySender<N: Network> {
     pub tx_batch_certified: mpsc::Sender<(SocketAddr, Data<BatchCertificate<N>>)>,
     pub tx_primary_ping:
         mpsc::Sender<(SocketAddr, Data<BatchCertificate<N>>, IndexMap<Field<N>, Data<BatchCertificate<N>>>)>,
+    pub tx_equivocation: mpsc::Sender<(SocketAddr, Equivocation<N>)>,
     pub tx_unconfirmed_solution:
         mpsc::Sender<(PuzzleCommitment<N>, Data<ProverSolution<N>>, oneshot::Sender<Result<()>>)>,
     pub tx_unconfirmed_transaction: mpsc::Sender<(N::TransactionID, Data<Transaction<N>>, oneshot::Sender<Result<()>>)>,
@@ -196,6 +200,7 @@ pub struct PrimaryReceiver<N: Network> {
     pub rx_batch_certified: mpsc::Receiver<(SocketAddr, Data<BatchCertificate<N>>)>,
     pub rx_primary_ping:
         mpsc::Receiver<(SocketAddr, Data<BatchCertificate<N>>, IndexMap<Field<N>, Data<BatchCertificate<N>>>)>,
+    pub rx_equivocation: mpsc::Receiver<(SocketAddr, Equivocation<N>)>,
     pub rx_unconfirmed_solution:
         mpsc::Receiver<(PuzzleCommitment<N>, Data<ProverSolution<N>>, oneshot::Sender<Result<()>>)>,
     pub rx_unconfirmed_transaction:
@@ -208,6 +213,7 @@ pub fn init_primary_channels<N: Network>() -> (PrimarySender<N>, PrimaryReceiver
     let (tx_batch_signature, rx_batch_signature) = mpsc::channel(MAX_CHANNEL_SIZE);
     let (tx_batch_certified, rx_batch_certified) = mpsc::channel(MAX_CHANNEL_SIZE);
     let (tx_primary_ping, rx_primary_ping) = mpsc::channel(MAX_CHANNEL_SIZE);
+    let (tx_equivocation, rx_equivocation) = mpsc::channel(MAX_CHANNEL_SIZE);
     let (tx_unconfirmed_solution, rx_unconfirmed_solution) = mpsc::channel(MAX_CHANNEL_SIZE);
     let (tx_unconfirmed_transaction, rx_unconfirmed_transaction) = mpsc::channel(MAX_CHANNEL_SIZE);
 
@@ -216,6 +222,7 @@ pub fn init_primary_channels<N: Network>() -> (PrimarySender<N>, PrimaryReceiver
         tx_batch_signature,
         tx_batch_certified,
         tx_primary_ping,
+        tx_equivocation,
         tx_unconfirmed_solution,
         tx_unconfirmed_transaction,
     };
@@ -224,6 +231,7 @@ pub fn init_primary_channels<N: Network>() -> (PrimarySender<N>, PrimaryReceiver
         rx_batch_signature,
         rx_batch_certified,
         rx_primary_ping,
+        rx_equivocation,
         rx_unconfirmed_solution,
         rx_unconfirmed_transaction,
     };
@@ -245,6 +253,11 @@ pub struct WorkerReceiver<N: Network> {
     pub rx_transmission_response: mpsc::Receiver<(SocketAddr, TransmissionResponse<N>)>,
 }
 
+/// Initializes the channels over which the workers send the digests of their sealed worker batches to the primary.
+pub fn init_worker_batch_channels<N: Network>() -> (mpsc::Sender<(u8, Field<N>)>, mpsc::Receiver<(u8, Field<N>)>) {
+    mpsc::channel(MAX_CHANNEL_SIZE)
+}
+
 /// Initializes the worker channels.
 pub fn init_worker_channels<N: Network>() -> (WorkerSender<N>, WorkerReceiver<N>) {
     let (tx_worker_ping, rx_worker_ping) = mpsc::channel(MAX_CHANNEL_SIZE);
@@ -264,6 +277,8 @@ pub struct SyncSender<N: Network> {
     pub tx_block_sync_update_peer_locators: mpsc::Sender<(SocketAddr, BlockLocators<N>, oneshot::Sender<Result<()>>)>,
     pub tx_certificate_request: mpsc::Sender<(SocketAddr, CertificateRequest<N>)>,
     pub tx_certificate_response: mpsc::Sender<(SocketAddr, CertificateResponse<N>)>,
+    pub tx_catch_up_request: mpsc::Sender<(SocketAddr, CatchUpRequest)>,
+    pub tx_catch_up_response: mpsc::Sender<(SocketAddr, CatchUpResponse<N>)>,
 }
 
 impl<N: Network> SyncSender<N> {
@@ -296,6 +311,8 @@ pub struct SyncReceiver<N: Network> {
     pub rx_block_sync_update_peer_locators: mpsc::Receiver<(SocketAddr, BlockLocators<N>, oneshot::Sender<Result<()>>)>,
     pub rx_certificate_request: mpsc::Receiver<(SocketAddr, CertificateRequest<N>)>,
     pub rx_certificate_response: mpsc::Receiver<(SocketAddr, CertificateResponse<N>)>,
+    pub rx_catch_up_request: mpsc::Receiver<(SocketAddr, CatchUpRequest)>,
+    pub rx_catch_up_response: mpsc::Receiver<(SocketAddr, CatchUpResponse<N>)>,
 }
 
 /// Initializes the sync channels.
@@ -306,6 +323,8 @@ pub fn init_sync_channels<N: Network>() -> (SyncSender<N>, SyncReceiver<N>) {
     let (tx_block_sync_update_peer_locators, rx_block_sync_update_peer_locators) = mpsc::channel(MAX_CHANNEL_SIZE);
     let (tx_certificate_request, rx_certificate_request) = mpsc::channel(MAX_CHANNEL_SIZE);
     let (tx_certificate_response, rx_certificate_response) = mpsc::channel(MAX_CHANNEL_SIZE);
+    let (tx_catch_up_request, rx_catch_up_request) = mpsc::channel(MAX_CHANNEL_SIZE);
+    let (tx_catch_up_response, rx_catch_up_response) = mpsc::channel(MAX_CHANNEL_SIZE);
 
     let sender = SyncSender {
         tx_block_sync_advance_with_sync_blocks,
@@ -313,6 +332,8 @@ pub fn init_sync_channels<N: Network>() -> (SyncSender<N>, SyncReceiver<N>) {
         tx_block_sync_update_peer_locators,
         tx_certificate_request,
         tx_certificate_response,
+        tx_catch_up_request,
+        tx_catch_up_response,
     };
     let receiver = SyncReceiver {
         rx_block_sync_advance_with_sync_blocks,
@@ -320,6 +341,8 @@ pub fn init_sync_channels<N: Network>() -> (SyncSender<N>, SyncReceiver<N>) {
         rx_block_sync_update_peer_locators,
         rx_certificate_request,
         rx_certificate_response,
+        rx_catch_up_request,
+        rx_catch_up_response,
     };
 
     (sender, receiver)
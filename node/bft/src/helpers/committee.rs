@@ -0,0 +1,608 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::helpers::Epochs;
+use snarkos_node_bft_ledger_service::LedgerService;
+use snarkvm::{
+    ledger::committee::{Committee, MIN_VALIDATOR_STAKE},
+    prelude::{Address, Network},
+};
+
+use anyhow::Result;
+use indexmap::{IndexMap, IndexSet};
+use parking_lot::RwLock;
+use std::{collections::HashSet, sync::Arc};
+use thiserror::Error;
+use tokio::sync::watch;
+
+/// The minimum number of members in a committee.
+pub const MIN_COMMITTEE_SIZE: usize = 3;
+
+/// The reason the committee could not be modified.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum CommitteeError<N: Network> {
+    #[error("The committee has not been derived yet")]
+    NotDerived,
+    #[error("Validator '{0}' is already a member of the committee")]
+    AlreadyMember(Address<N>),
+    #[error("Validator '{0}' is not a member of the committee")]
+    NotMember(Address<N>),
+    #[error("Validator '{0}' has zero stake")]
+    ZeroStake(Address<N>),
+    #[error("The stake of '{0}' ({1}) is below the minimum of {MIN_VALIDATOR_STAKE}")]
+    InsufficientStake(Address<N>, u64),
+    #[error("Cannot add validator '{0}' - the committee already has the maximum of {1} members")]
+    CommitteeFull(Address<N>, usize),
+    #[error("Cannot remove validator '{0}' - the committee must have at least {MIN_COMMITTEE_SIZE} members")]
+    BelowMinimumSize(Address<N>),
+    #[error("Cannot stake {1} for '{0}' - it overflows the total stake of {2}")]
+    StakeOverflow(Address<N>, u64, u64),
+    #[error("Invalid committee - {0}")]
+    InvalidCommittee(String),
+}
+
+/// The change in membership and stake between two committees.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitteeChange<N: Network> {
+    /// The starting round of the new committee.
+    pub starting_round: u64,
+    /// The addresses that joined the committee, with their stake.
+    pub joined: Vec<(Address<N>, u64)>,
+    /// The addresses that departed the committee, with their previous stake.
+    pub departed: Vec<(Address<N>, u64)>,
+    /// The addresses that remained in the committee with a different stake, with their previous and new stake.
+    pub restaked: Vec<(Address<N>, u64, u64)>,
+}
+
+impl<N: Network> CommitteeChange<N> {
+    /// Returns the change from the given previous committee (if any) to the given committee.
+    pub fn new(previous: Option<&Committee<N>>, committee: &Committee<N>) -> Self {
+        // Retrieve the members of the previous committee.
+        let previous = previous.map(|committee| committee.members().clone()).unwrap_or_default();
+        let members = committee.members();
+
+        // Determine the change in membership and stake.
+        let joined = members
+            .iter()
+            .filter(|(address, _)| !previous.contains_key(*address))
+            .map(|(address, (stake, _))| (*address, *stake))
+            .collect();
+        let departed = previous
+            .iter()
+            .filter(|(address, _)| !members.contains_key(*address))
+            .map(|(address, (stake, _))| (*address, *stake))
+            .collect();
+        let restaked = members
+            .iter()
+            .filter_map(|(address, (stake, _))| match previous.get(address) {
+                Some((previous_stake, _)) if previous_stake != stake => Some((*address, *previous_stake, *stake)),
+                _ => None,
+            })
+            .collect();
+        Self { starting_round: committee.starting_round(), joined, departed, restaked }
+    }
+
+    /// Returns `true` if the membership of the committee changed.
+    pub fn is_membership_changed(&self) -> bool {
+        !self.joined.is_empty() || !self.departed.is_empty()
+    }
+}
+
+/// An immutable snapshot of the committee, with its quorum and availability thresholds precomputed.
+///
+/// The committee manager swaps in a new view on every committee change, so holders of a view never observe
+/// a partially-updated committee, and never need to acquire a lock to read it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitteeView<N: Network> {
+    /// The committee.
+    committee: Committee<N>,
+    /// The validators of the ledger's committee that were removed by this node.
+    removed: IndexSet<Address<N>>,
+    /// The quorum threshold of the committee.
+    quorum_threshold: u64,
+    /// The availability threshold of the committee.
+    availability_threshold: u64,
+}
+
+impl<N: Network> CommitteeView<N> {
+    /// Initializes a new view of the given committee.
+    pub fn new(committee: Committee<N>) -> Self {
+        Self::new_with_removed(committee, Default::default())
+    }
+
+    /// Initializes a new view of the given committee, from which the given validators were removed.
+    fn new_with_removed(committee: Committee<N>, removed: IndexSet<Address<N>>) -> Self {
+        let quorum_threshold = committee.quorum_threshold();
+        let availability_threshold = committee.availability_threshold();
+        Self { committee, removed, quorum_threshold, availability_threshold }
+    }
+
+    /// Returns the committee.
+    pub const fn committee(&self) -> &Committee<N> {
+        &self.committee
+    }
+
+    /// Returns the starting round of the committee.
+    pub const fn starting_round(&self) -> u64 {
+        self.committee.starting_round()
+    }
+
+    /// Returns the committee members alongside their stake.
+    pub const fn members(&self) -> &IndexMap<Address<N>, (u64, bool)> {
+        self.committee.members()
+    }
+
+    /// Returns the number of validators in the committee.
+    pub fn num_members(&self) -> usize {
+        self.committee.num_members()
+    }
+
+    /// Returns `true` if the given address is in the committee.
+    pub fn is_committee_member(&self, address: Address<N>) -> bool {
+        self.committee.is_committee_member(address)
+    }
+
+    /// Returns `true` if the given address was removed from the committee by this node.
+    pub fn is_removed(&self, address: Address<N>) -> bool {
+        self.removed.contains(&address)
+    }
+
+    /// Returns the amount of stake for the given address.
+    pub fn get_stake(&self, address: Address<N>) -> u64 {
+        self.committee.get_stake(address)
+    }
+
+    /// Returns the total stake of the committee.
+    pub const fn total_stake(&self) -> u64 {
+        self.committee.total_stake()
+    }
+
+    /// Returns the quorum threshold of the committee.
+    pub const fn quorum_threshold(&self) -> u64 {
+        self.quorum_threshold
+    }
+
+    /// Returns the availability threshold of the committee.
+    pub const fn availability_threshold(&self) -> u64 {
+        self.availability_threshold
+    }
+
+    /// Returns `true` if the combined stake for the given addresses reaches the quorum threshold.
+    pub fn is_quorum_threshold_reached(&self, addresses: &HashSet<Address<N>>) -> bool {
+        self.combined_stake(addresses).map_or(false, |stake| stake >= self.quorum_threshold)
+    }
+
+    /// Returns `true` if the combined stake for the given addresses reaches the availability threshold.
+    pub fn is_availability_threshold_reached(&self, addresses: &HashSet<Address<N>>) -> bool {
+        self.combined_stake(addresses).map_or(false, |stake| stake >= self.availability_threshold)
+    }
+
+    /// Returns the combined stake for the given addresses, or `None` if it overflows.
+    fn combined_stake(&self, addresses: &HashSet<Address<N>>) -> Option<u64> {
+        addresses.iter().try_fold(0u64, |stake, address| stake.checked_add(self.get_stake(*address)))
+    }
+}
+
+/// The manager of the committee, which tracks the committee derived from the ledger's staking state.
+///
+/// The ledger recomputes the committee from the bonded stake whenever a block is added, and the manager
+/// swaps in the new committee when it changes, so that the membership changes can be reconciled.
+/// The operator may also add and remove validators, or update their stake, until the ledger's next committee
+/// is swapped in. These local changes only govern which validators this node connects to, as every validator
+/// must derive the same committee from the ledger in order to agree on the quorum and availability thresholds.
+/// Every swap is broadcast to the subscribers as a new view, with the quorum and availability thresholds precomputed.
+/// Callers only ever receive snapshots of the committee, and never the lock guarding it.
+#[derive(Debug)]
+pub struct CommitteeManager<N: Network> {
+    /// The view of the current committee, if it has been derived.
+    committee: RwLock<Option<Arc<CommitteeView<N>>>>,
+    /// The sender notifying the subscribers of the view of the current committee.
+    notifier: watch::Sender<Option<Arc<CommitteeView<N>>>>,
+    /// The schedule of the committees for each epoch.
+    epochs: Epochs<N>,
+}
+
+impl<N: Network> Default for CommitteeManager<N> {
+    /// Initializes a new instance of the committee manager.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Network> CommitteeManager<N> {
+    /// Initializes a new instance of the committee manager.
+    pub fn new() -> Self {
+        Self { committee: Default::default(), notifier: watch::channel(None).0, epochs: Default::default() }
+    }
+
+    /// Returns the schedule of the committees for each epoch.
+    pub const fn epochs(&self) -> &Epochs<N> {
+        &self.epochs
+    }
+
+    /// Returns a receiver that is notified with the new view whenever the committee is swapped.
+    pub fn subscribe(&self) -> watch::Receiver<Option<Arc<CommitteeView<N>>>> {
+        self.notifier.subscribe()
+    }
+
+    /// Returns the view of the current committee, if it has been derived.
+    pub fn view(&self) -> Option<Arc<CommitteeView<N>>> {
+        self.committee.read().clone()
+    }
+
+    /// Returns a snapshot of the current committee, if it has been derived.
+    pub fn current(&self) -> Option<Committee<N>> {
+        self.view().map(|view| view.committee().clone())
+    }
+
+    /// Returns `true` if the given validator was removed from the current committee by this node.
+    pub fn is_removed(&self, address: Address<N>) -> bool {
+        self.committee.read().as_ref().map_or(false, |view| view.is_removed(address))
+    }
+
+    /// Swaps in the given committee, and returns the change from the previous committee.
+    /// If a committee with the same starting round was already swapped in, this method returns `None`.
+    ///
+    /// Note: The first committee is reported as a change in which every member joined.
+    /// The local changes to the previous committee are discarded.
+    pub fn update(&self, committee: Committee<N>) -> Option<CommitteeChange<N>> {
+        // Acquire the write lock, so that the comparison and the swap are atomic.
+        let mut current = self.committee.write();
+        // If the committee for this starting round was already swapped in, return early.
+        if current.as_ref().map(|current| current.starting_round()) == Some(committee.starting_round()) {
+            return None;
+        }
+        // Determine the change from the previous committee.
+        let change = CommitteeChange::new(current.as_ref().map(|view| view.committee()), &committee);
+        // Swap in the view of the new committee, and notify the subscribers.
+        self.swap(&mut current, CommitteeView::new(committee));
+        Some(change)
+    }
+
+    /// Adds the given validator with the given stake to the current committee, and returns the change.
+    pub fn add_validator(&self, address: Address<N>, stake: u64) -> Result<CommitteeChange<N>, CommitteeError<N>> {
+        // Ensure the stake is nonzero, and meets the minimum.
+        Self::check_stake(address, stake)?;
+        self.modify(|members, total_stake| {
+            // Ensure the validator is new.
+            if members.contains_key(&address) {
+                return Err(CommitteeError::AlreadyMember(address));
+            }
+            // Ensure the committee is not full.
+            let max_committee_size = Committee::<N>::MAX_COMMITTEE_SIZE as usize;
+            if members.len() >= max_committee_size {
+                return Err(CommitteeError::CommitteeFull(address, max_committee_size));
+            }
+            // Ensure the stake does not overflow the total stake.
+            if total_stake.checked_add(stake).is_none() {
+                return Err(CommitteeError::StakeOverflow(address, stake, total_stake));
+            }
+            members.insert(address, (stake, false));
+            Ok(())
+        })
+    }
+
+    /// Removes the given validator from the current committee, and returns the change.
+    pub fn remove_validator(&self, address: Address<N>) -> Result<CommitteeChange<N>, CommitteeError<N>> {
+        self.modify(|members, _| {
+            // Ensure the validator is a member of the committee.
+            if !members.contains_key(&address) {
+                return Err(CommitteeError::NotMember(address));
+            }
+            // Ensure the committee does not drop below the minimum size.
+            if members.len() <= MIN_COMMITTEE_SIZE {
+                return Err(CommitteeError::BelowMinimumSize(address));
+            }
+            members.shift_remove(&address);
+            Ok(())
+        })
+    }
+
+    /// Updates the stake of the given validator in the current committee, and returns the change.
+    pub fn update_stake(&self, address: Address<N>, stake: u64) -> Result<CommitteeChange<N>, CommitteeError<N>> {
+        // Ensure the stake is nonzero, and meets the minimum.
+        Self::check_stake(address, stake)?;
+        self.modify(|members, total_stake| {
+            // Retrieve the current stake of the validator.
+            let Some((current_stake, _)) = members.get_mut(&address) else {
+                return Err(CommitteeError::NotMember(address));
+            };
+            // Ensure the new stake does not overflow the total stake.
+            if (total_stake - *current_stake).checked_add(stake).is_none() {
+                return Err(CommitteeError::StakeOverflow(address, stake, total_stake - *current_stake));
+            }
+            *current_stake = stake;
+            Ok(())
+        })
+    }
+
+    /// Ensures the given stake is nonzero, and meets the minimum stake of a validator.
+    fn check_stake(address: Address<N>, stake: u64) -> Result<(), CommitteeError<N>> {
+        match stake {
+            0 => Err(CommitteeError::ZeroStake(address)),
+            stake if stake < MIN_VALIDATOR_STAKE => Err(CommitteeError::InsufficientStake(address, stake)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Applies the given modification to the members of the current committee, given its total stake,
+    /// and swaps in the new committee.
+    fn modify(
+        &self,
+        f: impl FnOnce(&mut IndexMap<Address<N>, (u64, bool)>, u64) -> Result<(), CommitteeError<N>>,
+    ) -> Result<CommitteeChange<N>, CommitteeError<N>> {
+        // Acquire the write lock, so that the checks, the modification, and the swap are atomic.
+        let mut current = self.committee.write();
+        // Retrieve the current committee.
+        let Some(previous) = current.as_ref() else { return Err(CommitteeError::NotDerived) };
+        // Modify the members of the committee.
+        let mut members = previous.members().clone();
+        f(&mut members, previous.total_stake())?;
+        // Construct the new committee, which recomputes the total stake.
+        let committee = Committee::new(previous.starting_round(), members)
+            .map_err(|error| CommitteeError::InvalidCommittee(error.to_string()))?;
+        // Determine the change from the previous committee.
+        let change = CommitteeChange::new(Some(previous.committee()), &committee);
+        // Track the validators that were removed, and forget those that were added back.
+        let mut removed = previous.removed.clone();
+        removed.extend(change.departed.iter().map(|(address, _)| *address));
+        removed.retain(|address| !committee.is_committee_member(*address));
+        // Swap in the view of the new committee, and notify the subscribers.
+        self.swap(&mut current, CommitteeView::new_with_removed(committee, removed));
+        Ok(change)
+    }
+
+    /// Swaps in the given view, and notifies the subscribers.
+    fn swap(&self, current: &mut Option<Arc<CommitteeView<N>>>, view: CommitteeView<N>) {
+        let view = Arc::new(view);
+        *current = Some(view.clone());
+        self.notifier.send_replace(Some(view));
+    }
+
+    /// Locks in the ledger's committee for the epoch of the given round, and for the next epoch
+    /// once its lock-in round is reached, and removes the committees of the epochs that are no longer honored.
+    pub fn lock_in_epochs(&self, round: u64, ledger: &dyn LedgerService<N>) -> Result<()> {
+        let epoch = self.epochs.epoch_for_round(round);
+        for epoch in [epoch, epoch + 1] {
+            // Skip the epoch if it is already locked in, or if its lock-in round has not been reached.
+            let lock_in_round = self.epochs.lock_in_round(epoch);
+            if self.epochs.latest_epoch().map_or(false, |latest| latest >= epoch) || lock_in_round > round {
+                continue;
+            }
+            // Lock in the ledger's committee at the lock-in round.
+            self.epochs.lock_in(epoch, ledger.get_committee_for_round(lock_in_round)?)?;
+        }
+        // Remove the committees of the epochs that are no longer honored.
+        self.epochs.garbage_collect(round);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::EPOCH_LENGTH;
+    use snarkos_node_bft_ledger_service::MockLedgerService;
+    use snarkvm::{
+        ledger::committee::test_helpers::sample_committee_for_round_and_members,
+        prelude::Rng,
+        utilities::TestRng,
+    };
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_committee_manager() {
+        let rng = &mut TestRng::default();
+        let addresses = (0..5).map(|_| Address::<CurrentNetwork>::new(rng.gen())).collect::<Vec<_>>();
+
+        // Ensure the first committee is reported as every member joining.
+        let manager = CommitteeManager::<CurrentNetwork>::new();
+        let committee = sample_committee_for_round_and_members(1, addresses[..4].to_vec(), rng);
+        let change = manager.update(committee.clone()).unwrap();
+        assert_eq!(change.joined.len(), 4);
+        assert!(change.departed.is_empty());
+        assert_eq!(manager.current(), Some(committee.clone()));
+
+        // Ensure a committee for the same starting round is not reported.
+        assert!(manager.update(committee).is_none());
+        assert!(manager.update(sample_committee_for_round_and_members(1, addresses[1..].to_vec(), rng)).is_none());
+
+        // Ensure a membership change is reported.
+        let committee = sample_committee_for_round_and_members(10, addresses[1..].to_vec(), rng);
+        let change = manager.update(committee).unwrap();
+        assert!(change.is_membership_changed());
+        assert_eq!(change.starting_round, 10);
+        assert_eq!(change.joined, vec![(addresses[4], 2 * MIN_VALIDATOR_STAKE)]);
+        assert_eq!(change.departed, vec![(addresses[0], 2 * MIN_VALIDATOR_STAKE)]);
+        assert!(change.restaked.is_empty());
+
+        // Ensure a stake change is reported.
+        let mut members = IndexMap::new();
+        for address in &addresses[1..] {
+            members.insert(*address, (2 * MIN_VALIDATOR_STAKE, false));
+        }
+        members.insert(addresses[1], (3 * MIN_VALIDATOR_STAKE, false));
+        let change = manager.update(Committee::new(20, members).unwrap()).unwrap();
+        assert!(!change.is_membership_changed());
+        assert_eq!(change.restaked, vec![(addresses[1], 2 * MIN_VALIDATOR_STAKE, 3 * MIN_VALIDATOR_STAKE)]);
+    }
+
+    #[test]
+    fn test_committee_view() {
+        let rng = &mut TestRng::default();
+        let addresses = (0..4).map(|_| Address::<CurrentNetwork>::new(rng.gen())).collect::<Vec<_>>();
+        let committee = sample_committee_for_round_and_members(1, addresses.clone(), rng);
+
+        // Ensure the thresholds match those of the committee.
+        let view = CommitteeView::new(committee.clone());
+        assert_eq!(view.quorum_threshold(), committee.quorum_threshold());
+        assert_eq!(view.availability_threshold(), committee.availability_threshold());
+        for num_signers in 0..=addresses.len() {
+            let signers = addresses[..num_signers].iter().copied().collect::<HashSet<_>>();
+            assert_eq!(view.is_quorum_threshold_reached(&signers), committee.is_quorum_threshold_reached(&signers));
+            assert_eq!(
+                view.is_availability_threshold_reached(&signers),
+                committee.is_availability_threshold_reached(&signers)
+            );
+        }
+
+        // Ensure a view held across a swap remains unchanged.
+        let manager = CommitteeManager::<CurrentNetwork>::new();
+        assert!(manager.view().is_none());
+        manager.update(committee).unwrap();
+        let view = manager.view().unwrap();
+        manager.update(sample_committee_for_round_and_members(2, addresses[1..].to_vec(), rng)).unwrap();
+        assert_eq!(view.num_members(), 4);
+        assert_eq!(manager.view().unwrap().num_members(), 3);
+        assert_eq!(manager.view().unwrap().quorum_threshold(), manager.current().unwrap().quorum_threshold());
+    }
+
+    #[test]
+    fn test_remove_validator_and_update_stake() {
+        let rng = &mut TestRng::default();
+        let addresses = (0..4).map(|_| Address::<CurrentNetwork>::new(rng.gen())).collect::<Vec<_>>();
+
+        // Ensure the committee cannot be modified before it is derived.
+        let manager = CommitteeManager::<CurrentNetwork>::new();
+        assert_eq!(manager.remove_validator(addresses[0]), Err(CommitteeError::NotDerived));
+        let mut receiver = manager.subscribe();
+        manager.update(sample_committee_for_round_and_members(5, addresses.clone(), rng)).unwrap();
+        assert!(receiver.has_changed().unwrap());
+        let view = receiver.borrow_and_update().clone().unwrap();
+        let (total_stake, quorum_threshold) = (view.total_stake(), view.quorum_threshold());
+
+        // Ensure a validator is removed, and the subscribers are notified with the recalculated thresholds.
+        let change = manager.remove_validator(addresses[0]).unwrap();
+        assert_eq!(change.departed, vec![(addresses[0], 2 * MIN_VALIDATOR_STAKE)]);
+        assert!(receiver.has_changed().unwrap());
+        let view = receiver.borrow_and_update().clone().unwrap();
+        assert_eq!(view.starting_round(), 5);
+        assert_eq!(view.total_stake(), total_stake - 2 * MIN_VALIDATOR_STAKE);
+        assert!(view.quorum_threshold() < quorum_threshold);
+        assert!(!view.is_committee_member(addresses[0]));
+        assert!(manager.is_removed(addresses[0]));
+
+        // Ensure unknown validators, and removals below the minimum size, are rejected.
+        assert_eq!(manager.remove_validator(addresses[0]), Err(CommitteeError::NotMember(addresses[0])));
+        assert_eq!(manager.remove_validator(addresses[1]), Err(CommitteeError::BelowMinimumSize(addresses[1])));
+        assert_eq!(manager.current().unwrap().num_members(), MIN_COMMITTEE_SIZE);
+        assert!(!receiver.has_changed().unwrap());
+
+        // Ensure the stake is updated, and invalid stakes are rejected.
+        let change = manager.update_stake(addresses[1], 5 * MIN_VALIDATOR_STAKE).unwrap();
+        assert_eq!(change.restaked, vec![(addresses[1], 2 * MIN_VALIDATOR_STAKE, 5 * MIN_VALIDATOR_STAKE)]);
+        assert_eq!(manager.current().unwrap().total_stake(), 9 * MIN_VALIDATOR_STAKE);
+        assert!(receiver.has_changed().unwrap());
+        assert_eq!(manager.update_stake(addresses[1], 0), Err(CommitteeError::ZeroStake(addresses[1])));
+        assert_eq!(
+            manager.update_stake(addresses[1], MIN_VALIDATOR_STAKE - 1),
+            Err(CommitteeError::InsufficientStake(addresses[1], MIN_VALIDATOR_STAKE - 1))
+        );
+        assert_eq!(
+            manager.update_stake(addresses[0], MIN_VALIDATOR_STAKE),
+            Err(CommitteeError::NotMember(addresses[0]))
+        );
+        assert_eq!(
+            manager.update_stake(addresses[1], u64::MAX),
+            Err(CommitteeError::StakeOverflow(addresses[1], u64::MAX, 4 * MIN_VALIDATOR_STAKE))
+        );
+
+        // Ensure the ledger's next committee discards the local changes.
+        manager.update(sample_committee_for_round_and_members(10, addresses.clone(), rng)).unwrap();
+        assert!(!manager.is_removed(addresses[0]));
+        assert_eq!(manager.current().unwrap().num_members(), 4);
+    }
+
+    #[test]
+    fn test_add_validator() {
+        let rng = &mut TestRng::default();
+        let addresses = (0..5).map(|_| Address::<CurrentNetwork>::new(rng.gen())).collect::<Vec<_>>();
+
+        // Ensure a validator cannot be added before the committee is derived.
+        let manager = CommitteeManager::<CurrentNetwork>::new();
+        assert_eq!(manager.add_validator(addresses[4], MIN_VALIDATOR_STAKE), Err(CommitteeError::NotDerived));
+        manager.update(sample_committee_for_round_and_members(5, addresses[..4].to_vec(), rng)).unwrap();
+        let mut receiver = manager.subscribe();
+
+        // Ensure zero stake, insufficient stake, and existing members are rejected.
+        assert_eq!(manager.add_validator(addresses[4], 0), Err(CommitteeError::ZeroStake(addresses[4])));
+        assert_eq!(
+            manager.add_validator(addresses[4], MIN_VALIDATOR_STAKE - 1),
+            Err(CommitteeError::InsufficientStake(addresses[4], MIN_VALIDATOR_STAKE - 1))
+        );
+        assert_eq!(
+            manager.add_validator(addresses[0], MIN_VALIDATOR_STAKE),
+            Err(CommitteeError::AlreadyMember(addresses[0]))
+        );
+        assert!(!receiver.has_changed().unwrap());
+
+        // Ensure a validator is added, and the subscribers are notified with the recomputed stake.
+        let change = manager.add_validator(addresses[4], MIN_VALIDATOR_STAKE).unwrap();
+        assert_eq!(change.joined, vec![(addresses[4], MIN_VALIDATOR_STAKE)]);
+        assert!(receiver.has_changed().unwrap());
+        let view = receiver.borrow_and_update().clone().unwrap();
+        assert_eq!(view.starting_round(), 5);
+        assert_eq!(view.total_stake(), 9 * MIN_VALIDATOR_STAKE);
+
+        // Ensure a removed validator that is added back is no longer reported as removed.
+        manager.remove_validator(addresses[0]).unwrap();
+        assert!(manager.is_removed(addresses[0]));
+        manager.add_validator(addresses[0], MIN_VALIDATOR_STAKE).unwrap();
+        assert!(!manager.is_removed(addresses[0]));
+
+        // Ensure a stake that overflows the total stake is rejected.
+        let address = Address::<CurrentNetwork>::new(rng.gen());
+        let total_stake = manager.current().unwrap().total_stake();
+        assert_eq!(
+            manager.add_validator(address, u64::MAX),
+            Err(CommitteeError::StakeOverflow(address, u64::MAX, total_stake))
+        );
+
+        // Ensure a validator cannot be added to a full committee.
+        let max_committee_size = Committee::<CurrentNetwork>::MAX_COMMITTEE_SIZE as usize;
+        let members = (0..max_committee_size).map(|_| Address::<CurrentNetwork>::new(rng.gen())).collect::<Vec<_>>();
+        manager.update(sample_committee_for_round_and_members(10, members, rng)).unwrap();
+        assert_eq!(
+            manager.add_validator(address, MIN_VALIDATOR_STAKE),
+            Err(CommitteeError::CommitteeFull(address, max_committee_size))
+        );
+    }
+
+    #[test]
+    fn test_lock_in_epochs() {
+        let rng = &mut TestRng::default();
+        let addresses = (0..4).map(|_| Address::<CurrentNetwork>::new(rng.gen())).collect::<Vec<_>>();
+        let committee = sample_committee_for_round_and_members(0, addresses, rng);
+        let ledger = MockLedgerService::<CurrentNetwork>::new(committee.clone());
+        let manager = CommitteeManager::<CurrentNetwork>::new();
+        let epochs = manager.epochs();
+
+        // Ensure only the current epoch is locked in before the lock-in round of the next epoch.
+        let lock_in_round = epochs.lock_in_round(1);
+        manager.lock_in_epochs(lock_in_round - 1, &ledger).unwrap();
+        assert_eq!(epochs.latest_epoch(), Some(0));
+        assert_eq!(epochs.get_committee_for_round(0), Some(committee.clone()));
+
+        // Ensure the next epoch is locked in with the ledger's committee once its lock-in round is reached.
+        manager.lock_in_epochs(lock_in_round, &ledger).unwrap();
+        assert_eq!(epochs.latest_epoch(), Some(1));
+        assert_eq!(epochs.get_committee_for_round(EPOCH_LENGTH), Some(committee));
+
+        // Ensure the committees that are no longer honored are garbage collected.
+        manager.lock_in_epochs(2 * EPOCH_LENGTH, &ledger).unwrap();
+        assert!(!epochs.is_locked_in(0));
+        assert!(epochs.is_locked_in(2));
+    }
+}
@@ -0,0 +1,151 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{console::prelude::*, ledger::narwhal::TransmissionID};
+
+use parking_lot::RwLock;
+use std::collections::HashMap;
+
+/// The number of rounds for which the transmissions of a proposed batch are retained, if it is not certified.
+pub const MAX_DEDUP_PROPOSED_ROUNDS: u64 = 2; // rounds
+/// The number of rounds for which the transmissions of a certified batch are retained.
+pub const MAX_DEDUP_CERTIFIED_ROUNDS: u64 = 10; // rounds
+
+/// The index of the transmissions included in recent batches, used to avoid proposing duplicate transmissions.
+///
+/// The index tracks the transmissions of the batches proposed by peers (which may still fail to be certified),
+/// and of the certified batches (which are eventually committed), for a window of recent rounds.
+#[derive(Debug)]
+pub struct DedupIndex<N: Network> {
+    /// The map of `transmission ID` to the earliest round of a proposed batch that includes it.
+    proposed: RwLock<HashMap<TransmissionID<N>, u64>>,
+    /// The map of `transmission ID` to the earliest round of a certified batch that includes it.
+    certified: RwLock<HashMap<TransmissionID<N>, u64>>,
+}
+
+impl<N: Network> Default for DedupIndex<N> {
+    /// Initializes a new instance of the dedup index.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Network> DedupIndex<N> {
+    /// Initializes a new instance of the dedup index.
+    pub fn new() -> Self {
+        Self { proposed: Default::default(), certified: Default::default() }
+    }
+
+    /// Returns the number of transmissions in the index.
+    pub fn len(&self) -> usize {
+        self.proposed.read().len() + self.certified.read().len()
+    }
+
+    /// Returns `true` if the index is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the given transmission ID is included in a recently proposed batch.
+    pub fn is_proposed(&self, transmission_id: &TransmissionID<N>) -> bool {
+        self.proposed.read().contains_key(transmission_id)
+    }
+
+    /// Returns `true` if the given transmission ID is included in a recently certified batch.
+    pub fn is_certified(&self, transmission_id: &TransmissionID<N>) -> bool {
+        self.certified.read().contains_key(transmission_id)
+    }
+
+    /// Returns the number of the given transmission IDs that are included in a certified batch before the given round.
+    pub fn num_certified_before<'a>(
+        &self,
+        transmission_ids: impl IntoIterator<Item = &'a TransmissionID<N>>,
+        round: u64,
+    ) -> usize {
+        let certified = self.certified.read();
+        transmission_ids.into_iter().filter(|id| certified.get(*id).map_or(false, |r| *r < round)).count()
+    }
+
+    /// Inserts the transmission IDs of a batch proposed in the given round.
+    pub fn insert_proposed<'a>(&self, round: u64, transmission_ids: impl IntoIterator<Item = &'a TransmissionID<N>>) {
+        Self::insert(&mut self.proposed.write(), round, transmission_ids);
+    }
+
+    /// Inserts the transmission IDs of a batch certified in the given round.
+    pub fn insert_certified<'a>(&self, round: u64, transmission_ids: impl IntoIterator<Item = &'a TransmissionID<N>>) {
+        Self::insert(&mut self.certified.write(), round, transmission_ids);
+    }
+
+    /// Removes the transmission IDs of the batches that fall outside the window of the given current round.
+    pub fn garbage_collect(&self, current_round: u64) {
+        let proposed_gc_round = current_round.saturating_sub(MAX_DEDUP_PROPOSED_ROUNDS);
+        self.proposed.write().retain(|_, round| *round > proposed_gc_round);
+        let certified_gc_round = current_round.saturating_sub(MAX_DEDUP_CERTIFIED_ROUNDS);
+        self.certified.write().retain(|_, round| *round > certified_gc_round);
+    }
+
+    /// Inserts the transmission IDs into the given map, retaining the earliest round of each transmission.
+    fn insert<'a>(
+        map: &mut HashMap<TransmissionID<N>, u64>,
+        round: u64,
+        transmission_ids: impl IntoIterator<Item = &'a TransmissionID<N>>,
+    ) {
+        for transmission_id in transmission_ids {
+            let entry = map.entry(*transmission_id).or_insert(round);
+            *entry = (*entry).min(round);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::ledger::coinbase::PuzzleCommitment;
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_dedup_index() {
+        let rng = &mut TestRng::default();
+
+        // Initialize the commitments.
+        let a = TransmissionID::Solution(PuzzleCommitment::from_g1_affine(rng.gen()));
+        let b = TransmissionID::Solution(PuzzleCommitment::from_g1_affine(rng.gen()));
+        let c = TransmissionID::Solution(PuzzleCommitment::from_g1_affine(rng.gen()));
+
+        let index = DedupIndex::<CurrentNetwork>::new();
+        assert!(index.is_empty());
+
+        // Insert a proposed batch, and a certified batch.
+        index.insert_proposed(3, [&a]);
+        index.insert_certified(2, [&b]);
+        index.insert_certified(4, [&b, &c]);
+        assert_eq!(index.len(), 3);
+        assert!(index.is_proposed(&a));
+        assert!(!index.is_certified(&a));
+        assert!(index.is_certified(&b));
+
+        // Ensure the earliest round of each certified transmission is retained.
+        assert_eq!(index.num_certified_before([&a, &b, &c], 3), 1);
+        assert_eq!(index.num_certified_before([&a, &b, &c], 5), 2);
+
+        // Ensure garbage collection retains the proposed transmissions for fewer rounds than the certified ones.
+        index.garbage_collect(MAX_DEDUP_PROPOSED_ROUNDS + 3);
+        assert!(!index.is_proposed(&a));
+        assert!(index.is_certified(&b));
+        index.garbage_collect(MAX_DEDUP_CERTIFIED_ROUNDS + 2);
+        assert!(!index.is_certified(&b));
+        assert!(index.is_certified(&c));
+    }
+}
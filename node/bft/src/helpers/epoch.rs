@@ -0,0 +1,199 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::committee::Committee,
+    prelude::{Address, Network},
+};
+
+use anyhow::{ensure, Result};
+use parking_lot::RwLock;
+use std::collections::BTreeMap;
+
+/// The number of rounds in an epoch.
+pub const EPOCH_LENGTH: u64 = 1000; // rounds
+/// The number of rounds at the start of an epoch, during which the committee of the previous epoch is also honored.
+pub const EPOCH_HANDOVER_ROUNDS: u64 = 50; // rounds
+
+/// The schedule of the committees for each epoch.
+///
+/// The committee for epoch `E + 1` is the ledger's committee at its lock-in round, which is `handover_rounds`
+/// before the epoch boundary, so every validator locks in the same committee during epoch `E`, and once locked in,
+/// it can no longer be changed. The members of the locked-in committee for epoch `E + 1` are honored ahead of the
+/// boundary, and during the handover window at the start of epoch `E + 1`, the members of the committee for
+/// epoch `E` are honored alongside the new committee.
+#[derive(Debug)]
+pub struct Epochs<N: Network> {
+    /// The number of rounds in an epoch.
+    epoch_length: u64,
+    /// The number of rounds in the handover window.
+    handover_rounds: u64,
+    /// The map of `epoch` to the locked-in committee.
+    committees: RwLock<BTreeMap<u64, Committee<N>>>,
+}
+
+impl<N: Network> Default for Epochs<N> {
+    /// Initializes a new epoch schedule with the default epoch length and handover window.
+    fn default() -> Self {
+        Self { epoch_length: EPOCH_LENGTH, handover_rounds: EPOCH_HANDOVER_ROUNDS, committees: Default::default() }
+    }
+}
+
+impl<N: Network> Epochs<N> {
+    /// Initializes a new epoch schedule with the given epoch length and handover window.
+    pub fn new(epoch_length: u64, handover_rounds: u64) -> Result<Self> {
+        // Ensure the epoch length is nonzero.
+        ensure!(epoch_length > 0, "The epoch length must be nonzero");
+        // Ensure the handover window is shorter than an epoch.
+        ensure!(handover_rounds < epoch_length, "The handover window must be shorter than the epoch length");
+        Ok(Self { epoch_length, handover_rounds, committees: Default::default() })
+    }
+
+    /// Returns the number of rounds in an epoch.
+    pub const fn epoch_length(&self) -> u64 {
+        self.epoch_length
+    }
+
+    /// Returns the number of rounds in the handover window.
+    pub const fn handover_rounds(&self) -> u64 {
+        self.handover_rounds
+    }
+
+    /// Returns the epoch of the given round.
+    pub const fn epoch_for_round(&self, round: u64) -> u64 {
+        round / self.epoch_length
+    }
+
+    /// Returns the starting round of the given epoch.
+    pub const fn starting_round(&self, epoch: u64) -> u64 {
+        epoch.saturating_mul(self.epoch_length)
+    }
+
+    /// Returns the round at which the ledger's committee is locked in for the given epoch.
+    pub const fn lock_in_round(&self, epoch: u64) -> u64 {
+        self.starting_round(epoch).saturating_sub(self.handover_rounds)
+    }
+
+    /// Returns `true` if the given round is in the handover window at the start of its epoch.
+    pub const fn is_handover(&self, round: u64) -> bool {
+        round >= self.epoch_length && round % self.epoch_length < self.handover_rounds
+    }
+
+    /// Returns the latest epoch with a locked-in committee, if any.
+    pub fn latest_epoch(&self) -> Option<u64> {
+        self.committees.read().keys().next_back().copied()
+    }
+
+    /// Returns `true` if a committee is locked in for the given epoch.
+    pub fn is_locked_in(&self, epoch: u64) -> bool {
+        self.committees.read().contains_key(&epoch)
+    }
+
+    /// Locks in the given committee for the given epoch, which must be after the latest locked-in epoch.
+    pub fn lock_in(&self, epoch: u64, committee: Committee<N>) -> Result<()> {
+        let mut committees = self.committees.write();
+        // Ensure the committees of the epoch and any earlier epochs can no longer be changed.
+        if let Some(latest) = committees.keys().next_back() {
+            ensure!(epoch > *latest, "Cannot lock in epoch {epoch}, as epoch {latest} is already locked in");
+        }
+        committees.insert(epoch, committee);
+        Ok(())
+    }
+
+    /// Returns the committee for the epoch of the given round, if it is locked in.
+    pub fn get_committee_for_round(&self, round: u64) -> Option<Committee<N>> {
+        self.committees.read().get(&self.epoch_for_round(round)).cloned()
+    }
+
+    /// Returns the committees honored in the given round, which includes the committee of the next epoch
+    /// if it is locked in, and the committee of the previous epoch if the round is in the handover window.
+    pub fn get_honored_committees_for_round(&self, round: u64) -> Vec<Committee<N>> {
+        let committees = self.committees.read();
+        let epoch = self.epoch_for_round(round);
+        // Retrieve the committees for the epoch of the round, and for the next epoch.
+        let mut honored = [epoch, epoch + 1].iter().filter_map(|e| committees.get(e).cloned()).collect::<Vec<_>>();
+        // If the round is in the handover window, retrieve the committee for the previous epoch.
+        if self.is_handover(round) {
+            honored.extend(committees.get(&(epoch - 1)).cloned());
+        }
+        honored
+    }
+
+    /// Returns `true` if the given address is a member of any committee honored in the given round.
+    pub fn is_honored_member(&self, round: u64, address: Address<N>) -> bool {
+        self.get_honored_committees_for_round(round).iter().any(|committee| committee.is_committee_member(address))
+    }
+
+    /// Removes the committees of the epochs that are no longer honored in the given round.
+    pub fn garbage_collect(&self, round: u64) {
+        // Retain the committee of the previous epoch, as it is honored during the handover window.
+        let epoch = self.epoch_for_round(round).saturating_sub(1);
+        self.committees.write().retain(|e, _| *e >= epoch);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{
+        ledger::committee::test_helpers::sample_committee_for_round_and_members,
+        prelude::Rng,
+        utilities::TestRng,
+    };
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_epochs() {
+        let rng = &mut TestRng::default();
+        let addresses = (0..5).map(|_| Address::<CurrentNetwork>::new(rng.gen())).collect::<Vec<_>>();
+        let committee_0 = sample_committee_for_round_and_members(0, addresses[..4].to_vec(), rng);
+        let committee_1 = sample_committee_for_round_and_members(10, addresses[1..].to_vec(), rng);
+
+        // Ensure the epoch schedule is validated.
+        assert!(Epochs::<CurrentNetwork>::new(0, 0).is_err());
+        assert!(Epochs::<CurrentNetwork>::new(10, 10).is_err());
+        let epochs = Epochs::<CurrentNetwork>::new(10, 2).unwrap();
+        assert_eq!(epochs.epoch_for_round(19), 1);
+        assert_eq!(epochs.starting_round(2), 20);
+        assert!(!epochs.is_handover(1));
+        assert!(epochs.is_handover(11));
+        assert!(!epochs.is_handover(12));
+        assert_eq!(epochs.lock_in_round(2), 18);
+        assert_eq!(epochs.lock_in_round(0), 0);
+
+        // Ensure the committees cannot be changed once locked in.
+        epochs.lock_in(0, committee_0.clone()).unwrap();
+        assert!(epochs.lock_in(0, committee_1.clone()).is_err());
+        epochs.lock_in(1, committee_1.clone()).unwrap();
+        assert!(epochs.lock_in(1, committee_0.clone()).is_err());
+        assert_eq!(epochs.latest_epoch(), Some(1));
+
+        // Ensure the locked-in committee of the next epoch is honored ahead of the boundary,
+        // and both committees are honored during the handover window.
+        assert!(epochs.is_honored_member(9, addresses[4]));
+        assert!(epochs.is_honored_member(10, addresses[0]));
+        assert!(epochs.is_honored_member(11, addresses[4]));
+        assert!(!epochs.is_honored_member(12, addresses[0]));
+        assert_eq!(epochs.get_committee_for_round(9), Some(committee_0.clone()));
+        assert_eq!(epochs.get_committee_for_round(10), Some(committee_1.clone()));
+
+        // Ensure the committees that are no longer honored are garbage collected.
+        epochs.garbage_collect(15);
+        assert!(epochs.is_locked_in(0));
+        epochs.garbage_collect(25);
+        assert!(!epochs.is_locked_in(0));
+        assert!(epochs.is_locked_in(1));
+    }
+}
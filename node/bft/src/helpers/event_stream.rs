@@ -0,0 +1,121 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::helpers::MisbehaviorReport;
+use snarkvm::console::{account::Address, network::Network, types::Field};
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+/// The maximum number of consensus events buffered for each subscriber, before the oldest events are dropped.
+pub const MAX_CONSENSUS_EVENTS: usize = 1024; // events
+
+/// A structured event emitted by consensus, for the RPC layer, metrics, and external monitoring.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case", bound = "")]
+pub enum ConsensusEvent<N: Network> {
+    /// This node proposed a batch.
+    BatchProposed { round: u64, batch_id: Field<N>, num_transmissions: usize },
+    /// A batch certificate was formed by this node, or received from a peer, and stored.
+    CertificateFormed { round: u64, certificate_id: Field<N>, author: Address<N> },
+    /// This node advanced to the given round.
+    RoundAdvanced { round: u64 },
+    /// A subdag was committed for the leader of the given round.
+    SubdagCommitted { round: u64, leader: Address<N>, num_certificates: usize, num_transmissions: usize },
+    /// A validator misbehaved.
+    ValidatorMisbehaved(MisbehaviorReport<N>),
+}
+
+impl<N: Network> ConsensusEvent<N> {
+    /// Returns the name of the consensus event.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::BatchProposed { .. } => "batch_proposed",
+            Self::CertificateFormed { .. } => "certificate_formed",
+            Self::RoundAdvanced { .. } => "round_advanced",
+            Self::SubdagCommitted { .. } => "subdag_committed",
+            Self::ValidatorMisbehaved(..) => "validator_misbehaved",
+        }
+    }
+}
+
+/// The stream of consensus events, which any number of subscribers may receive without touching consensus internals.
+///
+/// Note: Emitting an event never blocks consensus. A subscriber that falls behind by more than
+/// `MAX_CONSENSUS_EVENTS` events misses the oldest events, and is notified that it lagged.
+#[derive(Clone, Debug)]
+pub struct ConsensusEventStream<N: Network> {
+    /// The sender of the consensus events.
+    sender: broadcast::Sender<ConsensusEvent<N>>,
+}
+
+impl<N: Network> Default for ConsensusEventStream<N> {
+    /// Initializes a new instance of the consensus event stream.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Network> ConsensusEventStream<N> {
+    /// Initializes a new instance of the consensus event stream.
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(MAX_CONSENSUS_EVENTS);
+        Self { sender }
+    }
+
+    /// Returns a new receiver of the consensus events emitted from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<ConsensusEvent<N>> {
+        self.sender.subscribe()
+    }
+
+    /// Returns the number of subscribers.
+    pub fn num_subscribers(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// Emits the given consensus event to the subscribers, if there are any.
+    pub fn emit(&self, event: ConsensusEvent<N>) {
+        trace!("Emitting a '{}' consensus event", event.name());
+        // Note: Sending fails only if there are no subscribers, in which case the event is dropped.
+        let _ = self.sender.send(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use anyhow::Result;
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[tokio::test]
+    async fn test_consensus_event_stream() -> Result<()> {
+        let stream = ConsensusEventStream::<CurrentNetwork>::new();
+        // Ensure events without subscribers are dropped.
+        stream.emit(ConsensusEvent::RoundAdvanced { round: 1 });
+        assert_eq!(stream.num_subscribers(), 0);
+
+        // Ensure every subscriber receives the events emitted after it subscribed.
+        let (mut first, mut second) = (stream.subscribe(), stream.subscribe());
+        stream.emit(ConsensusEvent::RoundAdvanced { round: 2 });
+        assert_eq!(first.recv().await?, ConsensusEvent::RoundAdvanced { round: 2 });
+        assert_eq!(second.recv().await?, ConsensusEvent::RoundAdvanced { round: 2 });
+
+        // Ensure the events serialize with their type.
+        let json = serde_json::to_string(&ConsensusEvent::<CurrentNetwork>::RoundAdvanced { round: 3 })?;
+        assert_eq!(json, r#"{"type":"round_advanced","round":3}"#);
+        Ok(())
+    }
+}
@@ -0,0 +1,224 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::narwhal::BatchHeader,
+    prelude::{Address, Network},
+};
+
+use anyhow::{bail, ensure, Result};
+use indexmap::IndexMap;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The maximum number of equivocations retained in the evidence store.
+pub const MAX_EVIDENCE: usize = 1024; // equivocations
+
+/// The evidence that a validator equivocated, by signing two different batch headers for the same round.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Evidence<N: Network> {
+    /// The first signed batch header.
+    pub first: BatchHeader<N>,
+    /// The second signed batch header.
+    pub second: BatchHeader<N>,
+}
+
+impl<N: Network> Evidence<N> {
+    /// Initializes a new evidence of equivocation, after ensuring the batch headers conflict.
+    pub fn new(first: BatchHeader<N>, second: BatchHeader<N>) -> Result<Self> {
+        // Ensure the batch headers are from the same author.
+        ensure!(first.author() == second.author(), "The batch headers are from different authors");
+        // Ensure the batch headers are for the same round.
+        ensure!(first.round() == second.round(), "The batch headers are for different rounds");
+        // Ensure the batch headers are different.
+        ensure!(first.batch_id() != second.batch_id(), "The batch headers are identical");
+        // Ensure the batch headers are signed by the author.
+        for header in [&first, &second] {
+            if !header.signature().verify(&header.author(), &[header.batch_id()]) {
+                bail!("The batch header for round {} is not signed by its author", header.round())
+            }
+        }
+        Ok(Self { first, second })
+    }
+
+    /// Returns the address of the equivocating validator.
+    pub fn author(&self) -> Address<N> {
+        self.first.author()
+    }
+
+    /// Returns the round in which the validator equivocated.
+    pub fn round(&self) -> u64 {
+        self.first.round()
+    }
+}
+
+/// The store of the evidence of equivocation, keyed by the validator address and round.
+///
+/// The store detects an equivocation when it observes a batch header that conflicts with the
+/// latest batch header observed from the same author, and retains the most recent evidence.
+#[derive(Debug)]
+pub struct EvidenceStore<N: Network> {
+    /// The map of `author` to the latest batch header observed from the author.
+    headers: RwLock<HashMap<Address<N>, BatchHeader<N>>>,
+    /// The map of `(author, round)` to the evidence of equivocation, in insertion order.
+    evidence: RwLock<IndexMap<(Address<N>, u64), Evidence<N>>>,
+}
+
+impl<N: Network> Default for EvidenceStore<N> {
+    /// Initializes a new instance of the evidence store.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Network> EvidenceStore<N> {
+    /// Initializes a new instance of the evidence store.
+    pub fn new() -> Self {
+        Self { headers: Default::default(), evidence: Default::default() }
+    }
+
+    /// Returns the number of equivocations in the store.
+    pub fn len(&self) -> usize {
+        self.evidence.read().len()
+    }
+
+    /// Returns `true` if the store contains no evidence.
+    pub fn is_empty(&self) -> bool {
+        self.evidence.read().is_empty()
+    }
+
+    /// Returns `true` if the store contains evidence that the given author equivocated in the given round.
+    pub fn contains(&self, author: Address<N>, round: u64) -> bool {
+        self.evidence.read().contains_key(&(author, round))
+    }
+
+    /// Returns the evidence of equivocation by the given author.
+    pub fn get_evidence_for_author(&self, author: Address<N>) -> Vec<Evidence<N>> {
+        self.evidence.read().values().filter(|evidence| evidence.author() == author).cloned().collect()
+    }
+
+    /// Returns all of the evidence in the store.
+    pub fn evidence(&self) -> Vec<Evidence<N>> {
+        self.evidence.read().values().cloned().collect()
+    }
+
+    /// Observes the given (signed) batch header, and returns the evidence if it conflicts with
+    /// the latest batch header observed from the same author, and the evidence is new.
+    pub fn observe(&self, header: &BatchHeader<N>) -> Option<Evidence<N>> {
+        let mut headers = self.headers.write();
+        match headers.get(&header.author()) {
+            // If the batch header conflicts with the latest batch header, record the evidence.
+            Some(latest) if latest.round() == header.round() && latest.batch_id() != header.batch_id() => {
+                let evidence = Evidence::new(latest.clone(), header.clone()).ok()?;
+                self.insert(evidence.clone()).then_some(evidence)
+            }
+            // If the batch header is not newer than the latest batch header, skip it.
+            Some(latest) if latest.round() >= header.round() => None,
+            // Otherwise, record the batch header as the latest from the author.
+            _ => {
+                headers.insert(header.author(), header.clone());
+                None
+            }
+        }
+    }
+
+    /// Inserts the given evidence into the store, and returns `true` if it is new.
+    ///
+    /// Note: If the store is full, the oldest evidence is evicted.
+    pub fn insert(&self, evidence: Evidence<N>) -> bool {
+        let mut store = self.evidence.write();
+        // Ensure the evidence is new.
+        let key = (evidence.author(), evidence.round());
+        if store.contains_key(&key) {
+            return false;
+        }
+        store.insert(key, evidence);
+        // Evict the oldest evidence, if the store is full.
+        if store.len() > MAX_EVIDENCE {
+            store.shift_remove_index(0);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{
+        ledger::narwhal::batch_header::test_helpers::sample_batch_header_for_round,
+        prelude::PrivateKey,
+        utilities::TestRng,
+    };
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    /// Returns a batch header signed by the given private key, for the given round and timestamp.
+    fn sample_header(
+        private_key: &PrivateKey<CurrentNetwork>,
+        round: u64,
+        timestamp: i64,
+    ) -> BatchHeader<CurrentNetwork> {
+        let rng = &mut TestRng::default();
+        BatchHeader::new(private_key, round, timestamp, Default::default(), Default::default(), Default::default(), rng)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_evidence() {
+        let rng = &mut TestRng::default();
+        let private_key = PrivateKey::new(rng).unwrap();
+        let first = sample_header(&private_key, 1, 1);
+
+        // Ensure the evidence requires conflicting batch headers from the same author.
+        assert!(Evidence::new(first.clone(), first.clone()).is_err());
+        assert!(Evidence::new(first.clone(), sample_header(&private_key, 0, 2)).is_err());
+        assert!(Evidence::new(first.clone(), sample_batch_header_for_round(1, rng)).is_err());
+
+        let evidence = Evidence::new(first.clone(), sample_header(&private_key, 1, 2)).unwrap();
+        assert_eq!(evidence.author(), first.author());
+        assert_eq!(evidence.round(), 1);
+    }
+
+    #[test]
+    fn test_evidence_store() {
+        let rng = &mut TestRng::default();
+        let private_key = PrivateKey::new(rng).unwrap();
+        let store = EvidenceStore::<CurrentNetwork>::new();
+
+        // Ensure consistent batch headers are not reported.
+        let first = sample_header(&private_key, 1, 1);
+        assert!(store.observe(&first).is_none());
+        assert!(store.observe(&first).is_none());
+        assert!(store.observe(&sample_header(&private_key, 0, 2)).is_none());
+
+        // Ensure a conflicting batch header is reported once.
+        let second = sample_header(&private_key, 1, 2);
+        let evidence = store.observe(&second).unwrap();
+        assert_eq!(evidence, Evidence::new(first.clone(), second.clone()).unwrap());
+        assert!(store.observe(&second).is_none());
+        assert!(store.contains(first.author(), 1));
+        assert_eq!(store.get_evidence_for_author(first.author()), vec![evidence.clone()]);
+
+        // Ensure gossiped evidence is only inserted once.
+        assert!(!store.insert(evidence));
+        assert_eq!(store.len(), 1);
+
+        // Ensure a newer batch header replaces the latest batch header.
+        assert!(store.observe(&sample_header(&private_key, 2, 3)).is_none());
+        assert!(store.observe(&sample_header(&private_key, 2, 4)).is_some());
+        assert_eq!(store.evidence().len(), 2);
+    }
+}
@@ -0,0 +1,218 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{Address, Network};
+
+use indexmap::IndexMap;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
+
+/// The duration a transaction may wait in the memory pool, before its inclusion in a batch is forced.
+pub const MAX_TRANSACTION_WAIT_IN_SECS: u64 = 30; // seconds
+/// The number of batches with room for a transaction that may omit it, before its inclusion is forced.
+pub const MAX_TRANSACTION_OMISSIONS: u32 = 3; // batches
+/// The maximum number of overdue transactions whose inclusion is forced in a single batch.
+pub const MAX_FORCED_TRANSACTIONS_PER_BATCH: usize = 50; // transactions
+
+/// The wait of a transaction in the memory pool.
+#[derive(Copy, Clone, Debug)]
+struct Wait {
+    /// The time at which the transaction was first observed.
+    since: Instant,
+    /// The number of local batches with room for the transaction that omitted it.
+    num_omissions: u32,
+}
+
+/// The censorship statistics of a validator, from the batches it authored.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CensorshipStats {
+    /// The number of batches with room for more transmissions that the validator authored.
+    pub num_batches: u64,
+    /// The number of overdue transactions that were omitted from those batches.
+    pub num_omissions: u64,
+}
+
+/// The tracker of the inclusion of the transactions in the memory pool.
+///
+/// The tracker records how long each transaction has waited, and how many of the local batches with room for
+/// more transmissions omitted it. Transactions that waited too long, or were omitted too often, are overdue,
+/// and their inclusion in the next local batch is forced, from the oldest. The tracker also counts the overdue
+/// transactions omitted by the batches of each validator, as a (local, and therefore heuristic) measure of censorship.
+#[derive(Debug)]
+pub struct InclusionTracker<N: Network> {
+    /// The wait of each transaction in the memory pool, in the order they were observed.
+    waits: RwLock<IndexMap<N::TransactionID, Wait>>,
+    /// The censorship statistics of each validator.
+    censorship: RwLock<IndexMap<Address<N>, CensorshipStats>>,
+}
+
+impl<N: Network> Default for InclusionTracker<N> {
+    /// Initializes a new instance of the inclusion tracker.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Network> InclusionTracker<N> {
+    /// Initializes a new instance of the inclusion tracker.
+    pub fn new() -> Self {
+        Self { waits: Default::default(), censorship: Default::default() }
+    }
+
+    /// Returns the number of transactions being tracked.
+    pub fn len(&self) -> usize {
+        self.waits.read().len()
+    }
+
+    /// Returns `true` if no transactions are being tracked.
+    pub fn is_empty(&self) -> bool {
+        self.waits.read().is_empty()
+    }
+
+    /// Returns the duration the given transaction has waited, if it is being tracked.
+    pub fn wait(&self, transaction_id: &N::TransactionID) -> Option<Duration> {
+        self.waits.read().get(transaction_id).map(|wait| wait.since.elapsed())
+    }
+
+    /// Returns the number of local batches with room for the given transaction that omitted it.
+    pub fn num_omissions(&self, transaction_id: &N::TransactionID) -> u32 {
+        self.waits.read().get(transaction_id).map_or(0, |wait| wait.num_omissions)
+    }
+
+    /// Returns the censorship statistics of each validator.
+    pub fn censorship_stats(&self) -> IndexMap<Address<N>, CensorshipStats> {
+        self.censorship.read().clone()
+    }
+
+    /// Updates the tracker with the transactions that are currently pending in the memory pool.
+    ///
+    /// The new transactions start waiting, and the transactions that are no longer pending are no longer tracked.
+    pub fn observe(&self, pending: impl IntoIterator<Item = N::TransactionID>) {
+        let now = Instant::now();
+        let pending = pending.into_iter().collect::<HashSet<_>>();
+        let mut waits = self.waits.write();
+        // Remove the transactions that are no longer pending.
+        waits.retain(|transaction_id, _| pending.contains(transaction_id));
+        // Insert the new transactions.
+        for transaction_id in pending {
+            waits.entry(transaction_id).or_insert(Wait { since: now, num_omissions: 0 });
+        }
+    }
+
+    /// Returns the overdue transactions, from the oldest, up to the maximum that may be forced in a batch.
+    pub fn overdue(&self) -> Vec<N::TransactionID> {
+        self.waits
+            .read()
+            .iter()
+            .filter(|(_, wait)| Self::is_overdue(wait))
+            .map(|(transaction_id, _)| *transaction_id)
+            .take(MAX_FORCED_TRANSACTIONS_PER_BATCH)
+            .collect()
+    }
+
+    /// Records a local batch with the given transactions, and whether it had room for more transmissions.
+    ///
+    /// The included transactions are no longer tracked. If the batch had room, every other pending transaction
+    /// is counted as omitted.
+    pub fn record_local_batch(&self, included: &HashSet<N::TransactionID>, has_room: bool) {
+        let mut waits = self.waits.write();
+        waits.retain(|transaction_id, _| !included.contains(transaction_id));
+        if has_room {
+            waits.values_mut().for_each(|wait| wait.num_omissions = wait.num_omissions.saturating_add(1));
+        }
+    }
+
+    /// Records a batch from the given validator with the given transactions, if it had room for more transmissions.
+    ///
+    /// The transactions that were overdue and omitted from the batch are counted against the validator.
+    pub fn record_peer_batch(&self, author: Address<N>, included: &HashSet<N::TransactionID>, has_room: bool) {
+        // Only batches with room for more transmissions can be considered to omit transactions.
+        if !has_room {
+            return;
+        }
+        // Count the overdue transactions that were omitted.
+        let num_omissions = self
+            .waits
+            .read()
+            .iter()
+            .filter(|(transaction_id, wait)| Self::is_overdue(wait) && !included.contains(*transaction_id))
+            .count() as u64;
+        // Update the censorship statistics of the validator.
+        let mut censorship = self.censorship.write();
+        let stats = censorship.entry(author).or_default();
+        stats.num_batches += 1;
+        stats.num_omissions += num_omissions;
+    }
+
+    /// Returns `true` if the given wait is overdue.
+    fn is_overdue(wait: &Wait) -> bool {
+        wait.num_omissions >= MAX_TRANSACTION_OMISSIONS
+            || wait.since.elapsed() >= Duration::from_secs(MAX_TRANSACTION_WAIT_IN_SECS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{
+        prelude::{Field, Rng, Uniform},
+        utilities::TestRng,
+    };
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_inclusion_tracker() {
+        let rng = &mut TestRng::default();
+        let transaction_ids: Vec<<CurrentNetwork as Network>::TransactionID> =
+            (0..3).map(|_| Field::<CurrentNetwork>::rand(rng).into()).collect();
+        let validator = Address::<CurrentNetwork>::new(rng.gen());
+
+        // Ensure the pending transactions are tracked, and are not initially overdue.
+        let tracker = InclusionTracker::<CurrentNetwork>::new();
+        tracker.observe(transaction_ids.clone());
+        assert_eq!(tracker.len(), 3);
+        assert!(tracker.overdue().is_empty());
+
+        // Ensure the included transactions are no longer tracked, and the others are counted as omitted.
+        let included = [transaction_ids[0]].into_iter().collect();
+        tracker.record_local_batch(&included, true);
+        assert_eq!(tracker.len(), 2);
+        assert_eq!(tracker.num_omissions(&transaction_ids[1]), 1);
+        // Ensure a full batch does not count as an omission.
+        tracker.record_local_batch(&HashSet::new(), false);
+        assert_eq!(tracker.num_omissions(&transaction_ids[1]), 1);
+
+        // Ensure the transactions that are omitted too often are overdue, from the oldest.
+        for _ in 1..MAX_TRANSACTION_OMISSIONS {
+            tracker.record_local_batch(&HashSet::new(), true);
+        }
+        assert_eq!(tracker.overdue(), transaction_ids[1..].to_vec());
+
+        // Ensure the overdue transactions omitted by a validator are counted against it.
+        let included = [transaction_ids[1]].into_iter().collect();
+        tracker.record_peer_batch(validator, &included, true);
+        tracker.record_peer_batch(validator, &HashSet::new(), false);
+        assert_eq!(tracker.censorship_stats()[&validator], CensorshipStats { num_batches: 1, num_omissions: 1 });
+
+        // Ensure the transactions that are no longer pending are no longer tracked.
+        tracker.observe([transaction_ids[2]]);
+        assert_eq!(tracker.len(), 1);
+        assert!(tracker.wait(&transaction_ids[1]).is_none());
+    }
+}
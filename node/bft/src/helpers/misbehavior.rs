@@ -0,0 +1,355 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::helpers::now;
+use snarkvm::prelude::{Address, Network};
+
+use anyhow::Result;
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fmt,
+    fs::{self, File, OpenOptions},
+    io::Write,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+};
+
+/// The penalty at or above which a validator is banned.
+pub const MAX_MISBEHAVIOR_PENALTY: u32 = 100;
+/// The duration for which a misbehavior report counts towards the penalty of a validator.
+pub const MISBEHAVIOR_WINDOW_IN_SECS: i64 = 3600; // seconds
+/// The maximum number of misbehavior reports retained in memory for each validator.
+const MAX_REPORTS_PER_VALIDATOR: usize = 128;
+/// The number of reports appended to the log file, after which the log file is compacted to the retained reports.
+const MAX_APPENDS_BEFORE_COMPACTION: usize = 10_000;
+
+/// The kind of misbehavior observed by consensus.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Misbehavior {
+    /// The validator sent a batch certificate that failed verification.
+    InvalidCertificate,
+    /// The validator sent a batch certificate that fails verification, and references parents it could not provide.
+    UnknownParents,
+    /// The validator sent a batch or signature that is signed by a different address than claimed.
+    SignatureMismatch,
+    /// The validator signed two different batches for the same round.
+    Equivocation,
+    /// The validator proposed a batch that mostly duplicates transmissions certified in earlier rounds.
+    DuplicateTransmissions,
+}
+
+impl Misbehavior {
+    /// Returns the penalty of the misbehavior.
+    pub const fn penalty(&self) -> u32 {
+        match self {
+            Self::DuplicateTransmissions => 5,
+            Self::UnknownParents => 10,
+            Self::InvalidCertificate => 25,
+            Self::SignatureMismatch | Self::Equivocation => MAX_MISBEHAVIOR_PENALTY,
+        }
+    }
+}
+
+impl fmt::Display for Misbehavior {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidCertificate => write!(f, "invalid certificate"),
+            Self::UnknownParents => write!(f, "unknown parents"),
+            Self::SignatureMismatch => write!(f, "signature mismatch"),
+            Self::Equivocation => write!(f, "equivocation"),
+            Self::DuplicateTransmissions => write!(f, "duplicate transmissions"),
+        }
+    }
+}
+
+/// A structured report of misbehavior by a validator.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct MisbehaviorReport<N: Network> {
+    /// The address of the misbehaving validator.
+    pub address: Address<N>,
+    /// The IP of the peer that the misbehavior was observed from, if any.
+    pub peer_ip: Option<SocketAddr>,
+    /// The kind of misbehavior.
+    pub kind: Misbehavior,
+    /// The round in which the misbehavior was observed.
+    pub round: u64,
+    /// The UTC timestamp at which the misbehavior was observed.
+    pub timestamp: i64,
+    /// The details of the misbehavior.
+    pub details: String,
+}
+
+impl<N: Network> MisbehaviorReport<N> {
+    /// Initializes a new misbehavior report, observed at the current time.
+    pub fn new(
+        address: Address<N>,
+        peer_ip: Option<SocketAddr>,
+        kind: Misbehavior,
+        round: u64,
+        details: String,
+    ) -> Self {
+        Self { address, peer_ip, kind, round, timestamp: now(), details }
+    }
+}
+
+/// The log of the misbehavior reports emitted by consensus, keyed by validator address.
+///
+/// The log feeds the reputation of each validator: the penalties of its reports within the misbehavior window
+/// are summed, and the validator is banned while its penalty is at or above the maximum. If the log is persisted,
+/// each report is appended to the log file as a line of JSON by a dedicated writer thread, so that recording a
+/// report never blocks the caller on disk IO, and the log is restored from the file on startup. The log file is
+/// compacted to the reports retained in memory on startup, and after every `MAX_APPENDS_BEFORE_COMPACTION` reports,
+/// so that it does not grow without bound.
+#[derive(Debug)]
+pub struct MisbehaviorLog<N: Network> {
+    /// The writer of the log file, if the log is persisted.
+    writer: Option<LogWriter>,
+    /// The map of `address` to its most recent misbehavior reports.
+    reports: RwLock<HashMap<Address<N>, Vec<MisbehaviorReport<N>>>>,
+    /// The number of reports appended to the log file since it was last compacted.
+    num_appends: AtomicUsize,
+}
+
+impl<N: Network> Default for MisbehaviorLog<N> {
+    /// Initializes a new misbehavior log that is not persisted.
+    fn default() -> Self {
+        Self { writer: None, reports: Default::default(), num_appends: Default::default() }
+    }
+}
+
+/// A command to the thread that writes the log file.
+#[derive(Debug)]
+enum LogCommand {
+    /// Appends the given serialized report to the log file.
+    Append(String),
+    /// Replaces the log file with the given serialized reports.
+    Compact(Vec<String>),
+}
+
+/// The handle to the thread that appends the serialized reports to the log file.
+#[derive(Debug)]
+struct LogWriter {
+    /// The sender of the commands to the writer thread.
+    sender: Mutex<Option<mpsc::Sender<LogCommand>>>,
+    /// The handle of the writer thread.
+    handle: Mutex<Option<thread::JoinHandle<()>>>,
+}
+
+impl LogWriter {
+    /// Spawns the writer thread for the log file at the given path.
+    fn spawn(path: PathBuf) -> Result<Self> {
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let (sender, receiver) = mpsc::channel::<LogCommand>();
+        let handle = thread::Builder::new().name("misbehavior-log".into()).spawn(move || {
+            while let Ok(command) = receiver.recv() {
+                match command {
+                    LogCommand::Append(line) => {
+                        if let Err(error) = Self::append(&mut file, &line) {
+                            warn!("Failed to persist a misbehavior report - {error}");
+                        }
+                    }
+                    LogCommand::Compact(lines) => match Self::compact(&path, &lines) {
+                        Ok(compacted) => file = compacted,
+                        Err(error) => warn!("Failed to compact the misbehavior log - {error}"),
+                    },
+                }
+            }
+        })?;
+        Ok(Self { sender: Mutex::new(Some(sender)), handle: Mutex::new(Some(handle)) })
+    }
+
+    /// Queues the given command to the writer thread.
+    fn send(&self, command: LogCommand) {
+        if let Some(sender) = &*self.sender.lock() {
+            let _ = sender.send(command);
+        }
+    }
+
+    /// Appends the given line to the log file, in a single write.
+    fn append(file: &mut File, line: &str) -> std::io::Result<()> {
+        file.write_all(format!("{line}\n").as_bytes())
+    }
+
+    /// Writes the given lines to a temporary file, which atomically replaces the log file at the given path,
+    /// and returns the replaced log file, opened for appending.
+    fn compact(path: &Path, lines: &[String]) -> std::io::Result<File> {
+        let temp_path = path.with_extension("compact");
+        let mut temp_file = File::create(&temp_path)?;
+        for line in lines {
+            Self::append(&mut temp_file, line)?;
+        }
+        temp_file.sync_all()?;
+        fs::rename(&temp_path, path)?;
+        OpenOptions::new().append(true).open(path)
+    }
+}
+
+impl Drop for LogWriter {
+    /// Closes the channel, and waits for the writer thread to flush the queued reports.
+    fn drop(&mut self) {
+        self.sender.lock().take();
+        if let Some(handle) = self.handle.lock().take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl<N: Network> MisbehaviorLog<N> {
+    /// Loads the misbehavior log at the given path, if it is persisted.
+    pub fn load(path: Option<PathBuf>) -> Result<Self> {
+        match path {
+            Some(path) => Self::open(path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Opens the misbehavior log at the given path, restoring its reports, and compacting the log file.
+    pub fn open(path: PathBuf) -> Result<Self> {
+        let mut reports = HashMap::new();
+        if path.exists() {
+            for (index, line) in fs::read_to_string(&path)?.lines().enumerate() {
+                // Skip any malformed lines, which may result from a partially written report.
+                match serde_json::from_str(line) {
+                    Ok(report) => Self::insert(&mut reports, report),
+                    Err(error) => warn!("Skipping line {} of the misbehavior log - {error}", index + 1),
+                }
+            }
+        }
+        let writer = LogWriter::spawn(path)?;
+        // Compact the log file to the retained reports.
+        writer.send(LogCommand::Compact(Self::serialize(&reports)));
+        Ok(Self { writer: Some(writer), reports: RwLock::new(reports), num_appends: Default::default() })
+    }
+
+    /// Records the given misbehavior report, and returns `true` if the validator is now banned.
+    pub fn record(&self, report: MisbehaviorReport<N>) -> bool {
+        let address = report.address;
+        // Note: The lock is held while the report is queued, so that a compaction includes every queued report.
+        let mut reports = self.reports.write();
+        // Persist the report.
+        if let Some(writer) = &self.writer {
+            match serde_json::to_string(&report) {
+                Ok(line) => writer.send(LogCommand::Append(line)),
+                Err(error) => warn!("Failed to serialize the misbehavior report for '{address}' - {error}"),
+            }
+        }
+        Self::insert(&mut reports, report);
+        // Compact the log file to the retained reports, if enough reports were appended since the last compaction.
+        if let Some(writer) = &self.writer {
+            if self.num_appends.fetch_add(1, Ordering::Relaxed) + 1 >= MAX_APPENDS_BEFORE_COMPACTION {
+                self.num_appends.store(0, Ordering::Relaxed);
+                writer.send(LogCommand::Compact(Self::serialize(&reports)));
+            }
+        }
+        drop(reports);
+        self.is_banned(address)
+    }
+
+    /// Returns the misbehavior reports for the given address.
+    pub fn get_reports(&self, address: Address<N>) -> Vec<MisbehaviorReport<N>> {
+        self.reports.read().get(&address).cloned().unwrap_or_default()
+    }
+
+    /// Returns the penalty of the given address, from its reports within the misbehavior window.
+    pub fn penalty(&self, address: Address<N>) -> u32 {
+        let cutoff = now().saturating_sub(MISBEHAVIOR_WINDOW_IN_SECS);
+        self.reports.read().get(&address).map_or(0, |reports| {
+            reports.iter().filter(|report| report.timestamp >= cutoff).map(|report| report.kind.penalty()).sum()
+        })
+    }
+
+    /// Returns `true` if the given address is banned.
+    pub fn is_banned(&self, address: Address<N>) -> bool {
+        self.penalty(address) >= MAX_MISBEHAVIOR_PENALTY
+    }
+
+    /// Inserts the given report into the given reports, evicting the oldest report of the validator if necessary.
+    fn insert(reports: &mut HashMap<Address<N>, Vec<MisbehaviorReport<N>>>, report: MisbehaviorReport<N>) {
+        let entry = reports.entry(report.address).or_default();
+        entry.push(report);
+        if entry.len() > MAX_REPORTS_PER_VALIDATOR {
+            entry.remove(0);
+        }
+    }
+
+    /// Returns the given reports, serialized as the lines of the log file.
+    fn serialize(reports: &HashMap<Address<N>, Vec<MisbehaviorReport<N>>>) -> Vec<String> {
+        reports.values().flatten().filter_map(|report| serde_json::to_string(report).ok()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{prelude::Rng, utilities::TestRng};
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_misbehavior_log() {
+        let rng = &mut TestRng::default();
+        let path = std::env::temp_dir().join(format!("misbehavior-log-{}", rand::random::<u64>()));
+        let address = Address::<CurrentNetwork>::new(rng.gen());
+        let report = |kind| MisbehaviorReport::new(address, None, kind, 1, String::new());
+
+        // Ensure minor misbehavior accumulates a penalty, until the validator is banned.
+        let log = MisbehaviorLog::<CurrentNetwork>::open(path.clone()).unwrap();
+        assert!(!log.record(report(Misbehavior::UnknownParents)));
+        assert!(!log.record(report(Misbehavior::InvalidCertificate)));
+        assert_eq!(log.penalty(address), 35);
+        for _ in 0..2 {
+            assert!(!log.record(report(Misbehavior::InvalidCertificate)));
+        }
+        assert!(log.record(report(Misbehavior::UnknownParents)));
+        assert_eq!(log.get_reports(address).len(), 5);
+        // Drop the log, which flushes the queued reports to the log file.
+        drop(log);
+
+        // Ensure the reports are restored from the log file.
+        let log = MisbehaviorLog::<CurrentNetwork>::open(path.clone()).unwrap();
+        assert_eq!(log.get_reports(address).len(), 5);
+        assert!(log.is_banned(address));
+
+        // Ensure the log file is compacted to the retained reports when it is opened.
+        for _ in 0..MAX_REPORTS_PER_VALIDATOR {
+            log.record(report(Misbehavior::DuplicateTransmissions));
+        }
+        drop(log);
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), MAX_REPORTS_PER_VALIDATOR + 5);
+        drop(MisbehaviorLog::<CurrentNetwork>::open(path.clone()).unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), MAX_REPORTS_PER_VALIDATOR);
+
+        // Ensure severe misbehavior bans the validator immediately.
+        let log = MisbehaviorLog::<CurrentNetwork>::default();
+        assert!(log.record(report(Misbehavior::SignatureMismatch)));
+
+        // Ensure reports outside the misbehavior window do not count towards the penalty.
+        let log = MisbehaviorLog::<CurrentNetwork>::default();
+        let mut stale = report(Misbehavior::Equivocation);
+        stale.timestamp -= MISBEHAVIOR_WINDOW_IN_SECS + 1;
+        assert!(!log.record(stale));
+        assert_eq!(log.penalty(address), 0);
+
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -12,15 +12,42 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod batch_policy;
+pub use batch_policy::*;
+
 pub mod cache;
 pub use cache::*;
 
 pub mod channels;
 pub use channels::*;
 
+pub mod committee;
+pub use committee::*;
+
 pub mod dag;
 pub use dag::*;
 
+pub mod dedup;
+pub use dedup::*;
+
+pub mod epoch;
+pub use epoch::*;
+
+pub mod event_stream;
+pub use event_stream::*;
+
+pub mod evidence;
+pub use evidence::*;
+
+pub mod inclusion;
+pub use inclusion::*;
+
+pub mod misbehavior;
+pub use misbehavior::*;
+
+pub mod participation;
+pub use participation::*;
+
 pub mod partition;
 pub use partition::*;
 
@@ -33,15 +60,39 @@ pub use proposal::*;
 pub mod ready;
 pub use ready::*;
 
+pub mod replay;
+pub use replay::*;
+
 pub mod resolver;
 pub use resolver::*;
 
+pub mod round_timer;
+pub use round_timer::*;
+
+pub mod signature_requests;
+pub use signature_requests::*;
+
+pub mod signing_journal;
+pub use signing_journal::*;
+
+pub mod snapshot;
+pub use snapshot::*;
+
+pub mod standby;
+pub use standby::*;
+
 pub mod storage;
 pub use storage::*;
 
 pub mod timestamp;
 pub use timestamp::*;
 
+pub mod verifier;
+pub use verifier::*;
+
+pub mod warm_up;
+pub use warm_up::*;
+
 /// Formats an ID into a truncated identifier (for logging purposes).
 pub fn fmt_id(id: impl ToString) -> String {
     let id = id.to_string();
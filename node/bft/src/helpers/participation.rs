@@ -0,0 +1,107 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{Address, Network};
+
+use parking_lot::RwLock;
+use std::collections::{BTreeMap, HashSet};
+
+/// The number of recent rounds over which the participation of each validator is measured.
+pub const PARTICIPATION_WINDOW_IN_ROUNDS: u64 = 50; // rounds
+
+/// The participation of the validators in the recent rounds.
+///
+/// A validator participates in a round if a certificate it authored for the round is stored.
+/// Its participation rate is the fraction of the recent rounds with certificates in which it participated.
+#[derive(Debug)]
+pub struct Participation<N: Network> {
+    /// The authors of the stored certificates, for each of the recent rounds.
+    rounds: RwLock<BTreeMap<u64, HashSet<Address<N>>>>,
+}
+
+impl<N: Network> Default for Participation<N> {
+    /// Initializes a new instance of the participation tracker.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Network> Participation<N> {
+    /// Initializes a new instance of the participation tracker.
+    pub fn new() -> Self {
+        Self { rounds: Default::default() }
+    }
+
+    /// Returns the number of rounds in the window.
+    pub fn num_rounds(&self) -> usize {
+        self.rounds.read().len()
+    }
+
+    /// Records that the given author participated in the given round.
+    pub fn insert(&self, round: u64, author: Address<N>) {
+        let mut rounds = self.rounds.write();
+        // Insert the author for the round.
+        rounds.entry(round).or_default().insert(author);
+        // Remove the rounds that are outside the window.
+        let latest_round = rounds.keys().next_back().copied().unwrap_or(round);
+        rounds.retain(|round, _| round + PARTICIPATION_WINDOW_IN_ROUNDS > latest_round);
+    }
+
+    /// Returns the participation rate of the given validator, between 0 and 1.
+    pub fn rate(&self, address: Address<N>) -> f64 {
+        let rounds = self.rounds.read();
+        // If no round has been recorded, the participation rate is zero.
+        if rounds.is_empty() {
+            return 0.0;
+        }
+        let num_participated = rounds.values().filter(|authors| authors.contains(&address)).count();
+        num_participated as f64 / rounds.len() as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{prelude::Rng, utilities::TestRng};
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_participation() {
+        let rng = &mut TestRng::default();
+        let (alice, bob) = (Address::<CurrentNetwork>::new(rng.gen()), Address::<CurrentNetwork>::new(rng.gen()));
+
+        let participation = Participation::<CurrentNetwork>::new();
+        assert_eq!(participation.rate(alice), 0.0);
+
+        // Ensure the participation rate is the fraction of the rounds in which the validator participated.
+        for round in 1..=PARTICIPATION_WINDOW_IN_ROUNDS {
+            participation.insert(round, alice);
+            if round % 2 == 0 {
+                participation.insert(round, bob);
+            }
+        }
+        assert_eq!(participation.rate(alice), 1.0);
+        assert_eq!(participation.rate(bob), 0.5);
+
+        // Ensure the window slides, and a recorded round does not count twice.
+        for round in PARTICIPATION_WINDOW_IN_ROUNDS + 1..=2 * PARTICIPATION_WINDOW_IN_ROUNDS {
+            participation.insert(round, bob);
+            participation.insert(round, bob);
+        }
+        assert_eq!(participation.num_rounds(), PARTICIPATION_WINDOW_IN_ROUNDS as usize);
+        assert_eq!(participation.rate(alice), 0.0);
+        assert_eq!(participation.rate(bob), 1.0);
+    }
+}
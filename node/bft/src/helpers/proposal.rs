@@ -136,6 +136,21 @@ impl<N: Network> Proposal<N> {
         signer: Address<N>,
         signature: Signature<N>,
         committee: &Committee<N>,
+    ) -> Result<()> {
+        // Verify the signature. If the signature is not valid, return an error.
+        // Note: This check ensures the peer's address matches the address of the signature.
+        if !signature.verify(&signer, &[self.batch_id()]) {
+            bail!("Signature verification failed")
+        }
+        self.add_verified_signature(signer, signature, committee)
+    }
+
+    /// Adds a signature to the proposal, which the caller has verified against the batch ID of the proposal.
+    pub fn add_verified_signature(
+        &mut self,
+        signer: Address<N>,
+        signature: Signature<N>,
+        committee: &Committee<N>,
     ) -> Result<()> {
         // Ensure the signer is in the committee.
         if !committee.is_committee_member(signer) {
@@ -145,11 +160,6 @@ impl<N: Network> Proposal<N> {
         if self.signers().contains(&signer) {
             bail!("Duplicate signature from '{signer}'")
         }
-        // Verify the signature. If the signature is not valid, return an error.
-        // Note: This check ensures the peer's address matches the address of the signature.
-        if !signature.verify(&signer, &[self.batch_id()]) {
-            bail!("Signature verification failed")
-        }
         // Insert the signature.
         self.signatures.insert(signature);
         Ok(())
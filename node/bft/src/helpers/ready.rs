@@ -117,6 +117,11 @@ impl<N: Network> Ready<N> {
         is_new
     }
 
+    /// Removes the specified `transmission ID` from the ready queue, and returns its transmission.
+    pub fn remove(&self, transmission_id: impl Into<TransmissionID<N>>) -> Option<Transmission<N>> {
+        self.transmissions.write().shift_remove(&transmission_id.into())
+    }
+
     /// Removes up to the specified number of transmissions and returns them.
     pub fn drain(&self, num_transmissions: usize) -> IndexMap<TransmissionID<N>, Transmission<N>> {
         // Acquire the write lock.
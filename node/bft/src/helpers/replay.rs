@@ -0,0 +1,233 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{helpers::EPOCH_LENGTH, MAX_GC_ROUNDS};
+use snarkvm::{
+    console::prelude::*,
+    ledger::narwhal::{BatchCertificate, Data},
+};
+
+use parking_lot::RwLock;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+
+/// The maximum number of rounds ahead of the current round, for which consensus messages are accepted.
+pub const MAX_REPLAY_FUTURE_ROUNDS: u64 = 2 * MAX_GC_ROUNDS; // rounds
+
+/// The guard against replayed and out-of-bounds consensus messages.
+///
+/// The round of a batch proposal or certificate is checked to be after the GC round, within
+/// `MAX_REPLAY_FUTURE_ROUNDS` of the current round, and at most one epoch ahead of the current round.
+/// The digest of every message is recorded for its round, so that a replayed message is rejected
+/// before its signatures are verified. The digests are garbage collected along with the rounds.
+///
+/// Note: A batch signature is only recorded once it is verified to be from the sending validator,
+/// so that a copy relayed by another peer never blocks the signature from its signer.
+#[derive(Debug)]
+pub struct ReplayGuard {
+    /// The maximum number of rounds ahead of the current round.
+    max_future_rounds: u64,
+    /// The number of rounds in an epoch.
+    epoch_length: u64,
+    /// The map of `round` to the digests of the messages seen for the round.
+    seen: RwLock<BTreeMap<u64, HashSet<[u8; 32]>>>,
+}
+
+impl Default for ReplayGuard {
+    /// Initializes a new instance of the replay guard.
+    fn default() -> Self {
+        Self::new(MAX_REPLAY_FUTURE_ROUNDS, EPOCH_LENGTH)
+    }
+}
+
+impl ReplayGuard {
+    /// Initializes a new replay guard with the given maximum number of future rounds and epoch length.
+    pub fn new(max_future_rounds: u64, epoch_length: u64) -> Self {
+        Self { max_future_rounds, epoch_length: epoch_length.max(1), seen: Default::default() }
+    }
+
+    /// Returns the number of messages seen.
+    pub fn len(&self) -> usize {
+        self.seen.read().values().map(HashSet::len).sum()
+    }
+
+    /// Returns `true` if no messages have been seen.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Ensures the given message round is within the bounds of the given current round and GC round.
+    pub fn check_round(&self, round: u64, current_round: u64, gc_round: u64) -> Result<()> {
+        // Ensure the round is not too far in the past.
+        if round <= gc_round {
+            bail!("Round {round} is too far in the past (GC round {gc_round})")
+        }
+        // Ensure the round is not too far in the future.
+        if round > current_round.saturating_add(self.max_future_rounds) {
+            bail!("Round {round} is too far in the future (current round {current_round})")
+        }
+        // Ensure the round is at most one epoch ahead of the current round.
+        let (epoch, current_epoch) = (round / self.epoch_length, current_round / self.epoch_length);
+        if epoch > current_epoch + 1 {
+            bail!("Round {round} is in epoch {epoch}, ahead of the next epoch (current epoch {current_epoch})")
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the given message digest was already seen for the given round.
+    pub fn contains(&self, round: u64, digest: &[u8; 32]) -> bool {
+        self.seen.read().get(&round).map_or(false, |digests| digests.contains(digest))
+    }
+
+    /// Records the given message digest for the given round, returning `false` if it was already seen.
+    pub fn insert(&self, round: u64, digest: [u8; 32]) -> bool {
+        self.seen.write().entry(round).or_default().insert(digest)
+    }
+
+    /// Removes the given message digest for the given round, so that the message may be processed again.
+    pub fn remove(&self, round: u64, digest: &[u8; 32]) {
+        let mut seen = self.seen.write();
+        if let Some(digests) = seen.get_mut(&round) {
+            digests.remove(digest);
+            if digests.is_empty() {
+                seen.remove(&round);
+            }
+        }
+    }
+
+    /// Removes the message digests for the rounds at or below the given GC round.
+    pub fn garbage_collect(&self, gc_round: u64) {
+        let mut seen = self.seen.write();
+        *seen = seen.split_off(&gc_round.saturating_add(1));
+    }
+
+    /// Returns the digest of the given message bytes, which are prefixed with the given message tag.
+    pub fn digest(tag: &str, bytes: &[u8]) -> [u8; 32] {
+        Sha256::new().chain_update(tag.as_bytes()).chain_update(bytes).finalize().into()
+    }
+
+    /// Returns the digest of the given message data, which is prefixed with the given message tag.
+    pub fn digest_data<T: FromBytes + ToBytes + Send + 'static>(tag: &str, data: &Data<T>) -> Result<[u8; 32]> {
+        match data {
+            Data::Object(object) => Ok(Self::digest(tag, &object.to_bytes_le()?)),
+            Data::Buffer(bytes) => Ok(Self::digest(tag, bytes)),
+        }
+    }
+
+    /// Returns the round of the given batch certificate, without deserializing it.
+    pub fn certificate_round<N: Network>(certificate: &Data<BatchCertificate<N>>) -> Option<u64> {
+        match certificate {
+            Data::Object(certificate) => Some(certificate.round()),
+            Data::Buffer(bytes) => Self::peek_certificate_round::<N>(bytes),
+        }
+    }
+
+    /// Returns the round of the given serialized batch certificate, without deserializing it.
+    ///
+    /// Note: Only the leading fields of the certificate and its batch header are read, in the order
+    /// of `BatchCertificate::read_le` and `BatchHeader::read_le`, so the signatures are not verified.
+    pub fn peek_certificate_round<N: Network>(bytes: &[u8]) -> Option<u64> {
+        let mut reader = bytes;
+        // Read the certificate version, and the certificate ID of a version 1 certificate.
+        match u8::read_le(&mut reader).ok()? {
+            1 => {
+                Field::<N>::read_le(&mut reader).ok()?;
+            }
+            2 => (),
+            _ => return None,
+        }
+        // Read the batch header version, batch ID, and author, and then the round.
+        if !matches!(u8::read_le(&mut reader).ok()?, 1 | 2) {
+            return None;
+        }
+        Field::<N>::read_le(&mut reader).ok()?;
+        Address::<N>::read_le(&mut reader).ok()?;
+        u64::read_le(&mut reader).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::ledger::narwhal::batch_certificate::test_helpers::sample_batch_certificate_for_round;
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_replay_guard() {
+        let guard = ReplayGuard::new(10, 100);
+
+        // Ensure the rounds are bounded by the GC round, the future rounds, and the next epoch.
+        assert!(guard.check_round(5, 20, 5).is_err());
+        assert!(guard.check_round(6, 20, 5).is_ok());
+        assert!(guard.check_round(30, 20, 5).is_ok());
+        assert!(guard.check_round(31, 20, 5).is_err());
+        assert!(guard.check_round(199, 195, 5).is_ok());
+        assert!(guard.check_round(200, 195, 5).is_err());
+
+        // Ensure a replayed message is detected.
+        let (a, b) = (ReplayGuard::digest("a", b"message"), ReplayGuard::digest("b", b"message"));
+        assert_ne!(a, b);
+        assert!(guard.insert(6, a));
+        assert!(!guard.insert(6, a));
+        assert!(guard.insert(6, b));
+        assert!(guard.insert(7, a));
+        assert_eq!(guard.len(), 3);
+        assert!(guard.contains(6, &b));
+        assert!(!guard.contains(8, &a));
+
+        // Ensure a removed message may be processed again.
+        guard.remove(6, &b);
+        assert!(guard.insert(6, b));
+
+        // Ensure the digests of the garbage collected rounds are removed.
+        guard.garbage_collect(6);
+        assert_eq!(guard.len(), 1);
+        assert!(!guard.insert(7, a));
+    }
+
+    #[test]
+    fn test_peek_certificate_round() {
+        let rng = &mut TestRng::default();
+
+        let certificate = sample_batch_certificate_for_round(7, rng);
+        let bytes = certificate.to_bytes_le().unwrap();
+        assert_eq!(ReplayGuard::peek_certificate_round::<CurrentNetwork>(&bytes), Some(7));
+        assert_eq!(ReplayGuard::certificate_round::<CurrentNetwork>(&Data::Buffer(bytes.clone().into())), Some(7));
+        assert_eq!(ReplayGuard::certificate_round(&Data::Object(certificate)), Some(7));
+        assert_eq!(ReplayGuard::peek_certificate_round::<CurrentNetwork>(&bytes[..10]), None);
+        assert_eq!(ReplayGuard::peek_certificate_round::<CurrentNetwork>(&[]), None);
+
+        // Ensure the round is read from the serialized (deprecated) version 1 certificate, at every round.
+        for round in [1, 255, 256, u32::MAX as u64 + 1] {
+            let batch_header = sample_batch_certificate_for_round(round, rng).batch_header().clone();
+            let preimage = batch_header.batch_id().to_bytes_le().unwrap().to_bits_le();
+            let certificate_id = CurrentNetwork::hash_bhp1024(&preimage).unwrap();
+            let certificate = BatchCertificate::from_v1_deprecated(certificate_id, batch_header, Default::default());
+            let bytes = certificate.unwrap().to_bytes_le().unwrap();
+            assert_eq!(bytes[0], 1);
+            assert_eq!(ReplayGuard::peek_certificate_round::<CurrentNetwork>(&bytes), Some(round));
+
+            // Ensure the round is read from the serialized version 2 certificate.
+            let bytes = sample_batch_certificate_for_round(round, rng).to_bytes_le().unwrap();
+            assert_eq!(bytes[0], 2);
+            assert_eq!(ReplayGuard::peek_certificate_round::<CurrentNetwork>(&bytes), Some(round));
+        }
+
+        // Ensure an unknown certificate version is rejected.
+        let mut bytes = sample_batch_certificate_for_round(7, rng).to_bytes_le().unwrap();
+        bytes[0] = 3;
+        assert_eq!(ReplayGuard::peek_certificate_round::<CurrentNetwork>(&bytes), None);
+    }
+}
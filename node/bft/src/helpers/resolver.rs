@@ -14,8 +14,13 @@
 
 use snarkvm::prelude::{Address, Network};
 
+use anyhow::{bail, Result};
 use parking_lot::RwLock;
-use std::{collections::HashMap, net::SocketAddr};
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
 
 #[derive(Debug)]
 pub struct Resolver<N: Network> {
@@ -27,6 +32,10 @@ pub struct Resolver<N: Network> {
     peer_addresses: RwLock<HashMap<SocketAddr, Address<N>>>,
     /// A map of `address` to `peer IP`.
     address_peers: RwLock<HashMap<Address<N>, SocketAddr>>,
+    /// A map of `peer IP` to the time its mappings were last inserted.
+    timestamps: RwLock<HashMap<SocketAddr, Instant>>,
+    /// The starting round of the committee that the `address` mappings were last invalidated for.
+    committee_round: RwLock<Option<u64>>,
 }
 
 impl<N: Network> Default for Resolver<N> {
@@ -44,6 +53,8 @@ impl<N: Network> Resolver<N> {
             to_listener: Default::default(),
             peer_addresses: Default::default(),
             address_peers: Default::default(),
+            timestamps: Default::default(),
+            committee_round: Default::default(),
         }
     }
 }
@@ -69,13 +80,38 @@ impl<N: Network> Resolver<N> {
         self.address_peers.read().get(&address).copied()
     }
 
+    /// Returns the map of `peer IP` to `address`.
+    pub fn peer_addresses(&self) -> HashMap<SocketAddr, Address<N>> {
+        self.peer_addresses.read().clone()
+    }
+
     /// Inserts a bidirectional mapping of the listener address and the (ambiguous) peer address,
     /// alongside a bidirectional mapping of the listener address and the Aleo address.
+    ///
+    /// If the listener address or the Aleo address was previously mapped to another peer,
+    /// the stale mapping is removed, so that the `address` mappings remain consistent.
     pub fn insert_peer(&self, listener_ip: SocketAddr, peer_addr: SocketAddr, address: Address<N>) {
         self.from_listener.write().insert(listener_ip, peer_addr);
         self.to_listener.write().insert(peer_addr, listener_ip);
-        self.peer_addresses.write().insert(listener_ip, address);
-        self.address_peers.write().insert(address, listener_ip);
+
+        let mut peer_addresses = self.peer_addresses.write();
+        let mut address_peers = self.address_peers.write();
+        // Remove the stale mapping, if the listener address was previously mapped to another address.
+        if let Some(previous_address) = peer_addresses.insert(listener_ip, address) {
+            if previous_address != address && address_peers.get(&previous_address) == Some(&listener_ip) {
+                address_peers.remove(&previous_address);
+            }
+        }
+        // Remove the stale mapping, if the address was previously mapped to another listener address.
+        if let Some(previous_ip) = address_peers.insert(address, listener_ip) {
+            if previous_ip != listener_ip && peer_addresses.get(&previous_ip) == Some(&address) {
+                peer_addresses.remove(&previous_ip);
+            }
+        }
+        drop(peer_addresses);
+        drop(address_peers);
+
+        self.timestamps.write().insert(listener_ip, Instant::now());
     }
 
     /// Removes the bidirectional mapping of the listener address and the (ambiguous) peer address,
@@ -84,9 +120,74 @@ impl<N: Network> Resolver<N> {
         if let Some(peer_addr) = self.from_listener.write().remove(&listener_ip) {
             self.to_listener.write().remove(&peer_addr);
         }
+        self.remove_address(listener_ip);
+        self.timestamps.write().remove(&listener_ip);
+    }
+
+    /// Removes the bidirectional mapping of the listener address and the Aleo address.
+    fn remove_address(&self, listener_ip: SocketAddr) {
         if let Some(address) = self.peer_addresses.write().remove(&listener_ip) {
-            self.address_peers.write().remove(&address);
+            let mut address_peers = self.address_peers.write();
+            if address_peers.get(&address) == Some(&listener_ip) {
+                address_peers.remove(&address);
+            }
+        }
+    }
+
+    /// Removes the mappings of every peer that were inserted longer than the given TTL ago,
+    /// and for which the given predicate (e.g. whether the peer is connected) returns `false`.
+    /// Returns the listener addresses of the removed peers.
+    pub fn remove_expired_peers(&self, ttl: Duration, retain: impl Fn(&SocketAddr) -> bool) -> Vec<SocketAddr> {
+        // Determine the expired peers.
+        let expired = self
+            .timestamps
+            .read()
+            .iter()
+            .filter(|(listener_ip, timestamp)| timestamp.elapsed() > ttl && !retain(listener_ip))
+            .map(|(listener_ip, _)| *listener_ip)
+            .collect::<Vec<_>>();
+        // Remove the expired peers.
+        for listener_ip in &expired {
+            self.remove_peer(*listener_ip);
+        }
+        expired
+    }
+
+    /// Removes the `address` mappings of every address that is not a member of the committee,
+    /// if the committee starting at the given round has not already been accounted for.
+    /// Returns `true` if the mappings were invalidated.
+    pub fn invalidate_for_committee(&self, starting_round: u64, is_member: impl Fn(&Address<N>) -> bool) -> bool {
+        // Ensure the committee has changed since the last invalidation.
+        if self.committee_round.write().replace(starting_round) == Some(starting_round) {
+            return false;
         }
+        // Remove the mappings of the departed addresses.
+        let departed = self
+            .peer_addresses
+            .read()
+            .iter()
+            .filter(|(_, address)| !is_member(address))
+            .map(|(listener_ip, _)| *listener_ip)
+            .collect::<Vec<_>>();
+        for listener_ip in departed {
+            self.remove_address(listener_ip);
+        }
+        true
+    }
+
+    /// Ensures the `peer IP` to `address` map and the `address` to `peer IP` map are inverses of each other.
+    pub fn check_consistency(&self) -> Result<()> {
+        let peer_addresses = self.peer_addresses.read();
+        let address_peers = self.address_peers.read();
+        if peer_addresses.len() != address_peers.len() {
+            bail!("The resolver maps {} peer IPs, but {} addresses", peer_addresses.len(), address_peers.len())
+        }
+        for (listener_ip, address) in peer_addresses.iter() {
+            if address_peers.get(address) != Some(listener_ip) {
+                bail!("The resolver maps '{listener_ip}' to '{address}', but not vice versa")
+            }
+        }
+        Ok(())
     }
 }
 
@@ -116,6 +217,7 @@ mod tests {
         assert_eq!(resolver.get_address(listener_ip).unwrap(), address);
         assert_eq!(resolver.get_ambiguous(listener_ip).unwrap(), peer_addr);
         assert_eq!(resolver.get_peer_ip_for_address(address).unwrap(), listener_ip);
+        assert_eq!(resolver.peer_addresses(), HashMap::from([(listener_ip, address)]));
 
         resolver.remove_peer(listener_ip);
 
@@ -123,5 +225,46 @@ mod tests {
         assert!(resolver.get_address(listener_ip).is_none());
         assert!(resolver.get_ambiguous(listener_ip).is_none());
         assert!(resolver.get_peer_ip_for_address(address).is_none());
+        assert!(resolver.peer_addresses().is_empty());
+    }
+
+    #[test]
+    fn test_resolver_stale_mappings() {
+        let resolver = Resolver::<CurrentNetwork>::new();
+        let ip_1 = SocketAddr::from(([127, 0, 0, 1], 1234));
+        let ip_2 = SocketAddr::from(([127, 0, 0, 1], 5678));
+        let mut rng = TestRng::default();
+        let address_1 = Address::<CurrentNetwork>::new(rng.gen());
+        let address_2 = Address::<CurrentNetwork>::new(rng.gen());
+
+        // Ensure a validator that changes its IP does not leave a stale mapping.
+        resolver.insert_peer(ip_1, ip_1, address_1);
+        resolver.insert_peer(ip_2, ip_2, address_1);
+        assert!(resolver.get_address(ip_1).is_none());
+        assert_eq!(resolver.get_peer_ip_for_address(address_1).unwrap(), ip_2);
+        resolver.check_consistency().unwrap();
+
+        // Ensure an IP that is reused by another validator does not leave a stale mapping.
+        resolver.insert_peer(ip_2, ip_2, address_2);
+        assert!(resolver.get_peer_ip_for_address(address_1).is_none());
+        assert_eq!(resolver.get_address(ip_2).unwrap(), address_2);
+        resolver.check_consistency().unwrap();
+
+        // Ensure the mappings of departed validators are invalidated once per committee.
+        resolver.insert_peer(ip_1, ip_1, address_1);
+        assert!(resolver.invalidate_for_committee(1, |address| *address == address_2));
+        assert!(resolver.get_address(ip_1).is_none());
+        assert!(resolver.get_peer_ip_for_address(address_1).is_none());
+        assert_eq!(resolver.get_address(ip_2).unwrap(), address_2);
+        assert!(resolver.get_ambiguous(ip_1).is_some());
+        assert!(!resolver.invalidate_for_committee(1, |_| false));
+        resolver.check_consistency().unwrap();
+
+        // Ensure the expired mappings are removed, unless retained.
+        assert!(resolver.remove_expired_peers(Duration::from_secs(60), |_| false).is_empty());
+        assert_eq!(resolver.remove_expired_peers(Duration::ZERO, |ip| *ip == ip_2), vec![ip_1]);
+        assert!(resolver.get_ambiguous(ip_1).is_none());
+        assert_eq!(resolver.get_address(ip_2).unwrap(), address_2);
+        resolver.check_consistency().unwrap();
     }
 }
@@ -0,0 +1,179 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{MAX_BATCH_DELAY_IN_MS, MAX_LEADER_CERTIFICATE_DELAY_IN_SECS};
+
+use parking_lot::Mutex;
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// The minimum round timeout.
+pub const MIN_ROUND_TIMEOUT_IN_MS: u64 = MAX_BATCH_DELAY_IN_MS; // ms
+/// The maximum round timeout.
+pub const MAX_ROUND_TIMEOUT_IN_MS: u64 = MAX_LEADER_CERTIFICATE_DELAY_IN_SECS as u64 * 1000; // ms
+/// The multiple of the average observed round latency that is allowed before a round times out.
+pub const ROUND_TIMEOUT_MULTIPLIER: u64 = 2;
+/// The number of recent round latencies used to compute the round timeout.
+const NUM_ROUND_LATENCY_SAMPLES: usize = 10;
+
+/// The timer for the current round of a primary.
+///
+/// The round timeout adapts to the latencies of recently-observed rounds: it is a multiple of their average,
+/// bounded between `MIN_ROUND_TIMEOUT_IN_MS` and `MAX_ROUND_TIMEOUT_IN_MS`. Until a latency is observed,
+/// the round timeout is the maximum.
+#[derive(Debug)]
+pub struct RoundTimer {
+    /// The current round, and the time at which it started.
+    current: Mutex<(u64, Instant)>,
+    /// The latencies of the most recent rounds, in milliseconds.
+    latencies: Mutex<VecDeque<u64>>,
+}
+
+impl Default for RoundTimer {
+    /// Initializes a new instance of the round timer.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RoundTimer {
+    /// Initializes a new instance of the round timer.
+    pub fn new() -> Self {
+        Self { current: Mutex::new((0, Instant::now())), latencies: Default::default() }
+    }
+
+    /// Returns the round that is being timed.
+    pub fn round(&self) -> u64 {
+        self.current.lock().0
+    }
+
+    /// Returns the time elapsed since the current round started.
+    pub fn elapsed(&self) -> Duration {
+        self.current.lock().1.elapsed()
+    }
+
+    /// Returns the average latency of the recent rounds, in milliseconds, if any latency has been observed.
+    pub fn average_latency(&self) -> Option<u64> {
+        // Retrieve the recent round latencies.
+        let latencies = self.latencies.lock();
+        match latencies.is_empty() {
+            true => None,
+            false => Some(latencies.iter().sum::<u64>() / latencies.len() as u64),
+        }
+    }
+
+    /// Returns the number of rounds per second, from the average latency of the recent rounds.
+    pub fn rounds_per_second(&self) -> f64 {
+        self.average_latency().map_or(0.0, |average| 1000.0 / average.max(1) as f64)
+    }
+
+    /// Returns the timeout for the current round.
+    pub fn timeout(&self) -> Duration {
+        // If no latency has been observed, use the maximum round timeout.
+        let Some(average) = self.average_latency() else {
+            return Duration::from_millis(MAX_ROUND_TIMEOUT_IN_MS);
+        };
+        // Compute the round timeout.
+        let timeout = average.saturating_mul(ROUND_TIMEOUT_MULTIPLIER);
+        Duration::from_millis(timeout.clamp(MIN_ROUND_TIMEOUT_IN_MS, MAX_ROUND_TIMEOUT_IN_MS))
+    }
+
+    /// Returns the time remaining until the current round times out.
+    pub fn remaining(&self) -> Duration {
+        self.timeout().saturating_sub(self.elapsed())
+    }
+
+    /// Returns `true` if the current round has exceeded its timeout.
+    pub fn is_expired(&self) -> bool {
+        self.elapsed() >= self.timeout()
+    }
+
+    /// Updates the timer to the given round, recording the latency of the previous round if it was the prior round.
+    ///
+    /// Note: If the given round skips ahead (e.g. when syncing), the latency is not recorded,
+    /// as it does not reflect the latency of a single round.
+    pub fn update(&self, round: u64) {
+        let mut current = self.current.lock();
+        // If the round has not advanced, return early.
+        if round <= current.0 {
+            return;
+        }
+        // Record the latency of the previous round, if the timer is advancing by one round.
+        if current.0 > 0 && round == current.0 + 1 {
+            // Note: As a round advances once its certificates reach quorum, this is the time-to-quorum of the round.
+            let latency = current.1.elapsed();
+            self.record(latency.as_millis() as u64);
+
+            #[cfg(feature = "metrics")]
+            {
+                metrics::histogram(metrics::bft::ROUND_LATENCY, latency.as_secs_f64());
+                metrics::gauge(metrics::bft::ROUNDS_PER_SECOND, self.rounds_per_second());
+            }
+        }
+        // Start the timer for the given round.
+        *current = (round, Instant::now());
+    }
+
+    /// Records the given round latency, in milliseconds.
+    fn record(&self, latency: u64) {
+        let mut latencies = self.latencies.lock();
+        // Ensure the number of samples is bounded.
+        if latencies.len() >= NUM_ROUND_LATENCY_SAMPLES {
+            latencies.pop_front();
+        }
+        latencies.push_back(latency);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_timer() {
+        let timer = RoundTimer::new();
+        // Ensure the timeout is the maximum until a latency is observed.
+        assert_eq!(timer.timeout(), Duration::from_millis(MAX_ROUND_TIMEOUT_IN_MS));
+        assert_eq!(timer.rounds_per_second(), 0.0);
+        assert!(!timer.is_expired());
+
+        // Ensure the timer tracks the round, and skipped rounds are not recorded.
+        timer.update(5);
+        assert_eq!(timer.round(), 5);
+        timer.update(3);
+        assert_eq!(timer.round(), 5);
+        timer.update(6);
+        assert_eq!(timer.latencies.lock().len(), 1);
+        timer.update(10);
+        assert_eq!(timer.latencies.lock().len(), 1);
+
+        // Ensure the timeout adapts to the observed latencies, within the bounds.
+        let timer = RoundTimer::new();
+        timer.record(MAX_ROUND_TIMEOUT_IN_MS);
+        assert_eq!(timer.timeout(), Duration::from_millis(MAX_ROUND_TIMEOUT_IN_MS));
+        for _ in 0..NUM_ROUND_LATENCY_SAMPLES {
+            timer.record(1);
+        }
+        assert_eq!(timer.latencies.lock().len(), NUM_ROUND_LATENCY_SAMPLES);
+        assert_eq!(timer.timeout(), Duration::from_millis(MIN_ROUND_TIMEOUT_IN_MS));
+        assert_eq!(timer.rounds_per_second(), 1000.0);
+        for _ in 0..NUM_ROUND_LATENCY_SAMPLES {
+            timer.record(MIN_ROUND_TIMEOUT_IN_MS);
+        }
+        let expected = (ROUND_TIMEOUT_MULTIPLIER * MIN_ROUND_TIMEOUT_IN_MS).min(MAX_ROUND_TIMEOUT_IN_MS);
+        assert_eq!(timer.timeout(), Duration::from_millis(expected));
+    }
+}
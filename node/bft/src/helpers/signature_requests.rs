@@ -0,0 +1,177 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{Address, Field, Network};
+
+use parking_lot::Mutex;
+use std::{
+    collections::{BTreeSet, HashMap},
+    time::{Duration, Instant},
+};
+
+/// The delay before a batch proposal is first resent to a validator that has not signed it.
+pub const SIGNATURE_REQUEST_BASE_DELAY_IN_MS: u64 = 500; // ms
+/// The maximum delay between resending a batch proposal to a validator that has not signed it.
+pub const SIGNATURE_REQUEST_MAX_DELAY_IN_MS: u64 = 8_000; // ms
+/// The maximum number of batch proposals from prior rounds that may be outstanding, before proposing is paused.
+pub const MAX_OUTSTANDING_PROPOSALS: usize = 2;
+/// The number of recent rounds in which the outstanding batch proposals are counted.
+pub const OUTSTANDING_PROPOSALS_WINDOW_IN_ROUNDS: u64 = 4; // rounds
+
+/// The state of the signature requests for the current batch proposal.
+#[derive(Debug)]
+struct Requests<N: Network> {
+    /// The batch ID of the batch proposal.
+    batch_id: Field<N>,
+    /// The time at which the batch proposal was broadcast.
+    proposed_at: Instant,
+    /// The number of times the batch proposal was resent to each validator, and when it was last resent.
+    retries: HashMap<Address<N>, (u32, Instant)>,
+}
+
+impl<N: Network> Requests<N> {
+    /// Initializes the signature requests for the batch proposal with the given batch ID, broadcast now.
+    fn new(batch_id: Field<N>) -> Self {
+        Self { batch_id, proposed_at: Instant::now(), retries: Default::default() }
+    }
+}
+
+/// The tracker of the signature requests for the batch proposals of the primary.
+///
+/// A batch proposal is resent to each validator that has not signed it with an exponential backoff, starting from
+/// `SIGNATURE_REQUEST_BASE_DELAY_IN_MS` and capped at `SIGNATURE_REQUEST_MAX_DELAY_IN_MS`. A batch proposal is
+/// outstanding until its certificate is stored. Once `MAX_OUTSTANDING_PROPOSALS` from the prior rounds in the window
+/// are outstanding, the primary applies backpressure by pausing its batch proposals, until they age out of the window.
+#[derive(Debug)]
+pub struct SignatureRequests<N: Network> {
+    /// The signature requests for the current batch proposal, if any.
+    requests: Mutex<Option<Requests<N>>>,
+    /// The rounds of the batch proposals that have not been certified.
+    outstanding: Mutex<BTreeSet<u64>>,
+}
+
+impl<N: Network> Default for SignatureRequests<N> {
+    /// Initializes a new instance of the signature requests tracker.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Network> SignatureRequests<N> {
+    /// Initializes a new instance of the signature requests tracker.
+    pub fn new() -> Self {
+        Self { requests: Default::default(), outstanding: Default::default() }
+    }
+
+    /// Returns the delay before resending a batch proposal, after it was resent the given number of times.
+    pub fn backoff(num_retries: u32) -> Duration {
+        let delay = SIGNATURE_REQUEST_BASE_DELAY_IN_MS.saturating_mul(1u64 << num_retries.min(16));
+        Duration::from_millis(delay.min(SIGNATURE_REQUEST_MAX_DELAY_IN_MS))
+    }
+
+    /// Returns the number of outstanding batch proposals.
+    pub fn num_outstanding(&self) -> usize {
+        self.outstanding.lock().len()
+    }
+
+    /// Starts tracking the signature requests for the given batch proposal, which is now outstanding.
+    pub fn start(&self, round: u64, batch_id: Field<N>) {
+        *self.requests.lock() = Some(Requests::new(batch_id));
+        self.outstanding.lock().insert(round);
+    }
+
+    /// Returns `true` if the given batch proposal should be resent to the given validator,
+    /// and if so, records the retry.
+    pub fn should_resend(&self, batch_id: Field<N>, validator: Address<N>) -> bool {
+        let mut requests = self.requests.lock();
+        // If the batch proposal is not tracked (e.g. it was restored after a restart), start tracking it.
+        if requests.as_ref().map_or(false, |requests| requests.batch_id != batch_id) {
+            *requests = None;
+        }
+        let requests = requests.get_or_insert_with(|| Requests::new(batch_id));
+        // Retrieve the number of retries, and the time of the last request.
+        let (num_retries, last_sent) = requests.retries.get(&validator).copied().unwrap_or((0, requests.proposed_at));
+        // Ensure the backoff has elapsed.
+        if last_sent.elapsed() < Self::backoff(num_retries) {
+            return false;
+        }
+        // Record the retry.
+        requests.retries.insert(validator, (num_retries.saturating_add(1), Instant::now()));
+        true
+    }
+
+    /// Marks the batch proposal for the given round as certified.
+    pub fn certified(&self, round: u64) {
+        self.outstanding.lock().remove(&round);
+    }
+
+    /// Returns `true` if too many batch proposals from the rounds prior to the given round are outstanding.
+    pub fn is_backpressured(&self, current_round: u64) -> bool {
+        let mut outstanding = self.outstanding.lock();
+        // Remove the batch proposals that are outside the window.
+        let start_round = current_round.saturating_sub(OUTSTANDING_PROPOSALS_WINDOW_IN_ROUNDS);
+        outstanding.retain(|round| *round >= start_round);
+        // Count the outstanding batch proposals from the prior rounds.
+        outstanding.range(..current_round).count() >= MAX_OUTSTANDING_PROPOSALS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{
+        prelude::{Rng, Uniform},
+        utilities::TestRng,
+    };
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_backoff() {
+        assert_eq!(SignatureRequests::<CurrentNetwork>::backoff(0), Duration::from_millis(500));
+        assert_eq!(SignatureRequests::<CurrentNetwork>::backoff(2), Duration::from_millis(2_000));
+        assert_eq!(SignatureRequests::<CurrentNetwork>::backoff(u32::MAX), Duration::from_millis(8_000));
+    }
+
+    #[test]
+    fn test_signature_requests() {
+        let rng = &mut TestRng::default();
+        let validator = Address::<CurrentNetwork>::new(rng.gen());
+        let requests = SignatureRequests::<CurrentNetwork>::new();
+
+        // Ensure a batch proposal is not resent before the backoff elapses.
+        let batch_id = Field::rand(rng);
+        requests.start(1, batch_id);
+        assert!(!requests.should_resend(batch_id, validator));
+        // Ensure the backoff elapses for a batch proposal that was broadcast earlier.
+        requests.requests.lock().as_mut().unwrap().proposed_at -= Duration::from_millis(500);
+        assert!(requests.should_resend(batch_id, validator));
+        assert!(!requests.should_resend(batch_id, validator));
+
+        // Ensure the outstanding batch proposals from prior rounds apply backpressure.
+        assert!(!requests.is_backpressured(1));
+        requests.start(2, Field::rand(rng));
+        assert!(!requests.is_backpressured(2));
+        assert!(requests.is_backpressured(3));
+        // Ensure a certified batch proposal is no longer outstanding.
+        requests.certified(2);
+        assert!(!requests.is_backpressured(3));
+        assert_eq!(requests.num_outstanding(), 1);
+        // Ensure the outstanding batch proposals age out of the window.
+        requests.start(3, Field::rand(rng));
+        assert!(requests.is_backpressured(4));
+        assert!(!requests.is_backpressured(1 + OUTSTANDING_PROPOSALS_WINDOW_IN_ROUNDS + 1));
+        assert_eq!(requests.num_outstanding(), 1);
+    }
+}
@@ -0,0 +1,202 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{helpers::SigningLease, spawn_blocking};
+
+use anyhow::{bail, Result};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+use tokio::sync::Mutex;
+
+/// The signing journal of a primary, which protects the primary from double-signing.
+///
+/// The journal persists the highest round in which the primary has signed a batch. On startup, the persisted
+/// round becomes the floor of the journal, and the primary refuses to sign in any round at or below the floor.
+/// This ensures a validator that is restarted, or a standby that is promoted with the journal handed off
+/// from the failed validator, never signs a second batch in a round that was already signed.
+///
+/// If the validator serves heartbeats to a standby, the primary additionally refuses to sign
+/// while it does not hold the signing lease renewed by the standby.
+#[derive(Debug)]
+pub struct SigningJournal {
+    /// The path to the journal file, if the journal is enabled.
+    path: Option<PathBuf>,
+    /// The round at or below which the primary must not sign.
+    floor: u64,
+    /// The highest round in which the primary has signed.
+    last_signed_round: AtomicU64,
+    /// The signing lease the primary must hold to sign, if the validator has a standby.
+    lease: Option<Arc<SigningLease>>,
+    /// The lock that serializes the writes to the journal file.
+    write_lock: Mutex<()>,
+}
+
+impl SigningJournal {
+    /// Loads the signing journal at the given path, if it is enabled, which requires the given signing lease to sign.
+    pub fn load(path: Option<PathBuf>, lease: Option<Arc<SigningLease>>) -> Result<Self> {
+        let journal = match path {
+            Some(path) => Self::open(path)?,
+            None => Self::disabled(),
+        };
+        Ok(match lease {
+            Some(lease) => journal.with_lease(lease),
+            None => journal,
+        })
+    }
+
+    /// Opens the signing journal at the given path.
+    pub fn open(path: PathBuf) -> Result<Self> {
+        let floor = Self::read_round(&path)?;
+        Ok(Self {
+            path: Some(path),
+            floor,
+            last_signed_round: AtomicU64::new(floor),
+            lease: None,
+            write_lock: Default::default(),
+        })
+    }
+
+    /// Initializes a signing journal that is not persisted, and permits signing in any round.
+    pub fn disabled() -> Self {
+        Self {
+            path: None,
+            floor: 0,
+            last_signed_round: Default::default(),
+            lease: None,
+            write_lock: Default::default(),
+        }
+    }
+
+    /// Requires the primary to hold the given signing lease to sign.
+    pub fn with_lease(mut self, lease: Arc<SigningLease>) -> Self {
+        self.lease = Some(lease);
+        self
+    }
+
+    /// Returns the round at or below which the primary must not sign.
+    pub const fn floor(&self) -> u64 {
+        self.floor
+    }
+
+    /// Returns the highest round in which the primary has signed.
+    pub fn last_signed_round(&self) -> u64 {
+        self.last_signed_round.load(Ordering::SeqCst)
+    }
+
+    /// Ensures the primary may sign in the given round, without recording the round in the journal.
+    pub fn check(&self, round: u64) -> Result<()> {
+        // Ensure the signing lease is held, if the validator has a standby.
+        if let Some(lease) = &self.lease {
+            if !lease.is_held() {
+                bail!("Refusing to sign in round {round}, as the signing lease from the standby has expired")
+            }
+        }
+        // Ensure the round is above the floor.
+        if self.path.is_some() && round <= self.floor {
+            bail!("Refusing to sign in round {round}, as the signing journal was handed off at round {}", self.floor)
+        }
+        Ok(())
+    }
+
+    /// Ensures the primary may sign in the given round, and records the round in the journal.
+    /// The round must be recorded **before** the signature is released.
+    pub async fn record(&self, round: u64) -> Result<()> {
+        // Ensure the primary may sign in the round.
+        self.check(round)?;
+        // Update the highest signed round, and persist it if it increased.
+        if self.last_signed_round.fetch_max(round, Ordering::SeqCst) < round {
+            if let Some(path) = self.path.clone() {
+                // Serialize the writes, so a lower round never overwrites a higher round.
+                let _write_lock = self.write_lock.lock().await;
+                spawn_blocking!(Self::write_round(&path, round))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the round persisted in the journal file at the given path, or `0` if the file does not exist.
+    pub fn read_round(path: &Path) -> Result<u64> {
+        match path.exists() {
+            true => match fs::read_to_string(path)?.trim().parse() {
+                Ok(round) => Ok(round),
+                Err(error) => bail!("The signing journal at '{}' is corrupted - {error}", path.display()),
+            },
+            false => Ok(0),
+        }
+    }
+
+    /// Persists the given round to the journal file at the given path, if it is above the persisted round.
+    pub fn write_round(path: &Path, round: u64) -> Result<()> {
+        if round <= Self::read_round(path)? {
+            return Ok(());
+        }
+        // Write the round to a temporary file, and rename it, so the journal is never partially written.
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, round.to_string())?;
+        fs::rename(temp_path, path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_signing_journal() {
+        let path = std::env::temp_dir().join(format!("signing-journal-{}", rand::random::<u64>()));
+
+        // Ensure a new journal permits signing, and persists the highest round.
+        let journal = SigningJournal::open(path.clone()).unwrap();
+        assert_eq!(journal.floor(), 0);
+        journal.record(5).await.unwrap();
+        journal.record(4).await.unwrap();
+        assert_eq!(journal.last_signed_round(), 5);
+        assert_eq!(SigningJournal::read_round(&path).unwrap(), 5);
+
+        // Ensure a reopened journal refuses to sign at or below the persisted round.
+        let journal = SigningJournal::open(path.clone()).unwrap();
+        assert_eq!(journal.floor(), 5);
+        assert!(journal.check(5).is_err());
+        assert!(journal.record(5).await.is_err());
+        journal.check(7).unwrap();
+        assert_eq!(journal.last_signed_round(), 5);
+        journal.record(6).await.unwrap();
+
+        // Ensure a handed off round only raises the journal.
+        SigningJournal::write_round(&path, 3).unwrap();
+        assert_eq!(SigningJournal::read_round(&path).unwrap(), 6);
+        SigningJournal::write_round(&path, 10).unwrap();
+        assert_eq!(SigningJournal::open(path.clone()).unwrap().floor(), 10);
+
+        // Ensure a disabled journal permits signing in any round.
+        assert!(SigningJournal::disabled().record(0).await.is_ok());
+
+        // Ensure a journal with a signing lease only permits signing while the lease is held.
+        let lease = Arc::new(SigningLease::default());
+        let journal = SigningJournal::disabled().with_lease(lease.clone());
+        assert!(journal.check(1).is_err());
+        assert!(journal.record(1).await.is_err());
+        lease.renew();
+        journal.record(1).await.unwrap();
+
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,148 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::committee::Committee,
+    prelude::{Address, Network},
+};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt, net::SocketAddr};
+
+/// A deterministic snapshot of the consensus state, intended to be attached to liveness bug reports.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct ConsensusSnapshot<N: Network> {
+    /// The current round in storage.
+    pub round: u64,
+    /// The current block height in storage.
+    pub height: u32,
+    /// The current garbage collection round in storage.
+    pub gc_round: u64,
+    /// The current leader, if one has been elected.
+    pub leader: Option<Address<N>>,
+    /// The starting round of the committee.
+    pub committee_round: u64,
+    /// The total stake of the committee.
+    pub total_stake: u64,
+    /// The committee members and their stakes, sorted by address.
+    pub committee: IndexMap<Address<N>, u64>,
+    /// The number of batch certificates in storage for each round.
+    pub batches_per_round: BTreeMap<u64, usize>,
+    /// The map of connected peer IPs to their Aleo address.
+    pub peers: BTreeMap<SocketAddr, Address<N>>,
+}
+
+impl<N: Network> ConsensusSnapshot<N> {
+    /// Initializes a new consensus snapshot.
+    ///
+    /// Note: The committee members are sorted by address, so that two snapshots of the same state are identical.
+    pub fn new(
+        round: u64,
+        height: u32,
+        gc_round: u64,
+        leader: Option<Address<N>>,
+        committee: &Committee<N>,
+        batches_per_round: BTreeMap<u64, usize>,
+        peers: BTreeMap<SocketAddr, Address<N>>,
+    ) -> Self {
+        // Collect the committee members and their stakes.
+        let mut members =
+            committee.members().iter().map(|(address, (stake, _))| (*address, *stake)).collect::<IndexMap<_, _>>();
+        // Sort the committee members by address.
+        members.sort_by_cached_key(|address, _| address.to_string());
+
+        Self {
+            round,
+            height,
+            gc_round,
+            leader,
+            committee_round: committee.starting_round(),
+            total_stake: committee.total_stake(),
+            committee: members,
+            batches_per_round,
+            peers,
+        }
+    }
+}
+
+impl<N: Network> fmt::Display for ConsensusSnapshot<N> {
+    /// Pretty-prints the consensus snapshot.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Round:    {}", self.round)?;
+        writeln!(f, "Height:   {}", self.height)?;
+        writeln!(f, "GC round: {}", self.gc_round)?;
+        match &self.leader {
+            Some(leader) => writeln!(f, "Leader:   {leader}")?,
+            None => writeln!(f, "Leader:   (none)")?,
+        }
+
+        writeln!(f, "\nCommittee (round {}, total stake {}):", self.committee_round, self.total_stake)?;
+        for (address, stake) in &self.committee {
+            // Determine if the member is connected.
+            let is_connected = self.peers.values().any(|peer| peer == address);
+            writeln!(f, "  {address}  {stake:>20}  {}", if is_connected { "connected" } else { "-" })?;
+        }
+
+        writeln!(f, "\nBatches per round:")?;
+        for (round, num_batches) in &self.batches_per_round {
+            writeln!(f, "  {round:>10}  {num_batches}")?;
+        }
+
+        writeln!(f, "\nPeers:")?;
+        for (peer_ip, address) in &self.peers {
+            writeln!(f, "  {peer_ip:<21}  {address}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::utilities::TestRng;
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_snapshot_serde_roundtrip() {
+        let rng = &mut TestRng::default();
+
+        // Sample a committee.
+        let committee = snarkvm::ledger::committee::test_helpers::sample_committee(rng);
+        let addresses = committee.members().keys().copied().collect::<Vec<_>>();
+
+        // Construct the snapshot.
+        let batches_per_round = [(1, 4), (2, 3)].into_iter().collect();
+        let peers = [(SocketAddr::from(([127, 0, 0, 1], 5001)), addresses[1])].into_iter().collect();
+        let snapshot =
+            ConsensusSnapshot::<CurrentNetwork>::new(2, 1, 0, Some(addresses[0]), &committee, batches_per_round, peers);
+
+        // Ensure the committee is sorted by address.
+        let sorted = snapshot.committee.keys().map(|address| address.to_string()).collect::<Vec<_>>();
+        assert!(sorted.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(snapshot.total_stake, committee.total_stake());
+
+        // Ensure the snapshot survives a JSON roundtrip.
+        let json = serde_json::to_string_pretty(&snapshot).unwrap();
+        let candidate = serde_json::from_str::<ConsensusSnapshot<CurrentNetwork>>(&json).unwrap();
+        assert_eq!(snapshot, candidate);
+
+        // Ensure the pretty-printer includes the state.
+        let output = snapshot.to_string();
+        assert!(output.contains("Round:    2"));
+        assert!(output.contains(&addresses[1].to_string()));
+    }
+}
@@ -0,0 +1,368 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{helpers::SigningJournal, spawn_blocking};
+use snarkos_account::Account;
+use snarkos_node_bft_ledger_service::LedgerService;
+use snarkvm::{
+    ledger::block::Block,
+    prelude::{FromBytes, Network, Signature, ToBytes},
+};
+
+use anyhow::{anyhow, ensure, Result};
+use parking_lot::Mutex;
+use std::{
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+    time::timeout,
+};
+
+/// The interval at which a standby requests a heartbeat from the active validator.
+pub const HEARTBEAT_INTERVAL_IN_MS: u64 = 2000; // ms
+/// The number of consecutive missed heartbeats after which a standby promotes itself.
+pub const MAX_MISSED_HEARTBEATS: u32 = 5;
+/// The duration for which a heartbeat request from the standby renews the signing lease of the active validator.
+pub const SIGNING_LEASE_IN_MS: u64 = 3 * HEARTBEAT_INTERVAL_IN_MS; // ms
+/// The maximum number of blocks sent to the standby in a heartbeat.
+const MAX_BLOCKS_PER_HEARTBEAT: u32 = 10;
+/// The maximum combined size of the blocks sent to the standby in a heartbeat.
+const MAX_HEARTBEAT_BLOCKS_SIZE: usize = 16 * 1024 * 1024; // 16 MiB
+/// The domain separator of the heartbeat request.
+const HEARTBEAT_REQUEST_DOMAIN: &[u8] = b"snarkos-heartbeat-request";
+/// The domain separator of the heartbeat.
+const HEARTBEAT_DOMAIN: &[u8] = b"snarkos-heartbeat";
+/// The maximum size of a heartbeat signature.
+const MAX_HEARTBEAT_SIGNATURE_SIZE: usize = 1024; // bytes
+
+/// The lease under which a validator with a standby may sign.
+///
+/// Every authenticated heartbeat request from the standby renews the lease, and the standby does not promote itself
+/// until the lease granted by its last request has expired. As such, a validator that is cut off from its standby
+/// stops signing before the standby is promoted, and the validator and the standby never sign at the same time.
+/// This assumes a heartbeat request is delivered within the heartbeat interval.
+#[derive(Debug, Default)]
+pub struct SigningLease {
+    /// The instant at which the lease expires, if the lease was ever granted.
+    expires_at: Mutex<Option<Instant>>,
+}
+
+impl SigningLease {
+    /// Renews the lease for the lease duration.
+    pub fn renew(&self) {
+        *self.expires_at.lock() = Some(Instant::now() + Duration::from_millis(SIGNING_LEASE_IN_MS));
+    }
+
+    /// Returns `true` if the lease has not expired.
+    pub fn is_held(&self) -> bool {
+        self.expires_at.lock().map_or(false, |expires_at| Instant::now() < expires_at)
+    }
+}
+
+/// Returns the message that is signed in a heartbeat request, for the given challenge, nonce, and height.
+fn heartbeat_request_message(challenge: u64, nonce: u64, height: u32) -> Vec<u8> {
+    [HEARTBEAT_REQUEST_DOMAIN, &challenge.to_le_bytes(), &nonce.to_le_bytes(), &height.to_le_bytes()].concat()
+}
+
+/// Returns the message that is signed in a heartbeat, for the given nonce, round, and next challenge.
+fn heartbeat_message(nonce: u64, round: u64, challenge: u64) -> Vec<u8> {
+    [HEARTBEAT_DOMAIN, &nonce.to_le_bytes(), &round.to_le_bytes(), &challenge.to_le_bytes()].concat()
+}
+
+/// Writes the given signature to the stream, prefixed by its length.
+async fn write_signature<N: Network>(stream: &mut TcpStream, signature: Signature<N>) -> Result<()> {
+    let signature = signature.to_bytes_le()?;
+    stream.write_u16_le(signature.len() as u16).await?;
+    stream.write_all(&signature).await?;
+    Ok(())
+}
+
+/// Reads a signature from the stream, prefixed by its length.
+async fn read_signature<N: Network>(stream: &mut TcpStream) -> Result<Signature<N>> {
+    let length = stream.read_u16_le().await? as usize;
+    ensure!(length <= MAX_HEARTBEAT_SIGNATURE_SIZE, "The heartbeat signature is {length} bytes");
+    let mut signature = vec![0u8; length];
+    stream.read_exact(&mut signature).await?;
+    Signature::read_le(&signature[..])
+}
+
+/// Serves heartbeats to standbys on the given listener IP.
+///
+/// Each heartbeat answers an authenticated request from the standby with the highest round in the signing journal
+/// of this validator, signed by the validator, so the standby can authenticate the heartbeat with its copy of the
+/// validator account. The heartbeat carries the blocks the standby is missing, so the ledger of the standby is in sync
+/// once it is promoted, and each request renews the signing lease of this validator.
+pub async fn serve_heartbeats<N: Network>(
+    listener_ip: SocketAddr,
+    account: Account<N>,
+    journal_path: PathBuf,
+    lease: Arc<SigningLease>,
+    ledger: Arc<dyn LedgerService<N>>,
+) -> Result<JoinHandle<()>> {
+    let listener = TcpListener::bind(listener_ip).await?;
+    info!("Serving heartbeats to standbys on '{listener_ip}'");
+    Ok(spawn_heartbeat_server(listener, account, journal_path, lease, ledger))
+}
+
+/// Spawns the server that accepts the heartbeat connections from standbys on the given listener.
+fn spawn_heartbeat_server<N: Network>(
+    listener: TcpListener,
+    account: Account<N>,
+    journal_path: PathBuf,
+    lease: Arc<SigningLease>,
+    ledger: Arc<dyn LedgerService<N>>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, standby_addr)) => {
+                    let (account, journal_path) = (account.clone(), journal_path.clone());
+                    let (lease, ledger) = (lease.clone(), ledger.clone());
+                    tokio::spawn(async move {
+                        if let Err(error) = respond_to_heartbeats(stream, &account, journal_path, &lease, ledger).await
+                        {
+                            debug!("Closed the heartbeat connection to '{standby_addr}' - {error}");
+                        }
+                    });
+                }
+                Err(error) => warn!("Failed to accept a heartbeat connection - {error}"),
+            }
+        }
+    })
+}
+
+/// Responds to the heartbeat requests on the given stream, until the stream is closed.
+async fn respond_to_heartbeats<N: Network>(
+    mut stream: TcpStream,
+    account: &Account<N>,
+    journal_path: PathBuf,
+    lease: &SigningLease,
+    ledger: Arc<dyn LedgerService<N>>,
+) -> Result<()> {
+    // Send the first challenge to the standby.
+    let mut challenge = rand::random::<u64>();
+    stream.write_u64_le(challenge).await?;
+    loop {
+        // Read the request from the standby.
+        let nonce = stream.read_u64_le().await?;
+        let height = stream.read_u32_le().await?;
+        let request_signature = read_signature::<N>(&mut stream).await?;
+        // Ensure the request is signed by the validator account, over the last challenge.
+        let message = heartbeat_request_message(challenge, nonce, height);
+        ensure!(account.verify_bytes(&message, &request_signature), "The heartbeat request signature is invalid");
+        // Renew the signing lease, as the standby does not promote itself until the lease has expired.
+        lease.renew();
+
+        // Sign the nonce, the highest signed round, and the next challenge.
+        let path = journal_path.clone();
+        let round = spawn_blocking!(SigningJournal::read_round(&path))?;
+        challenge = rand::random::<u64>();
+        let signature = account.sign_bytes(&heartbeat_message(nonce, round, challenge), &mut rand::thread_rng())?;
+        // Retrieve the blocks the standby is missing.
+        let ledger_ = ledger.clone();
+        let blocks = spawn_blocking!({
+            let start = height.saturating_add(1);
+            let end = ledger_.latest_block_height().saturating_add(1);
+            let end = end.min(start.saturating_add(MAX_BLOCKS_PER_HEARTBEAT));
+            // Serialize the blocks, until their combined size reaches the maximum.
+            // Note: A standby that falls behind by a larger block catches up through the block sync once promoted.
+            let mut blocks = Vec::new();
+            let mut num_bytes = 0;
+            for height in start..end {
+                let block = ledger_.get_block(height)?.to_bytes_le()?;
+                num_bytes += block.len();
+                if num_bytes > MAX_HEARTBEAT_BLOCKS_SIZE {
+                    break;
+                }
+                blocks.push(block);
+            }
+            Ok::<_, anyhow::Error>(blocks)
+        })?;
+
+        // Send the heartbeat to the standby.
+        stream.write_u64_le(round).await?;
+        stream.write_u64_le(challenge).await?;
+        write_signature(&mut stream, signature).await?;
+        stream.write_u16_le(blocks.len() as u16).await?;
+        for block in blocks {
+            stream.write_u32_le(block.len() as u32).await?;
+            stream.write_all(&block).await?;
+        }
+    }
+}
+
+/// A heartbeat connection from the standby to the active validator.
+struct HeartbeatConnection {
+    /// The stream to the active validator.
+    stream: TcpStream,
+    /// The challenge to sign in the next heartbeat request.
+    challenge: u64,
+}
+
+/// Requests a heartbeat from the active validator, and returns the connection, the highest round signed
+/// by the validator, and the blocks following the given height.
+async fn request_heartbeat<N: Network>(
+    connection: Option<HeartbeatConnection>,
+    validator_ip: SocketAddr,
+    account: &Account<N>,
+    height: u32,
+) -> Result<(HeartbeatConnection, u64, Vec<Block<N>>)> {
+    // Connect to the active validator, if the connection is not open.
+    let HeartbeatConnection { mut stream, challenge } = match connection {
+        Some(connection) => connection,
+        None => {
+            let mut stream = TcpStream::connect(validator_ip).await?;
+            let challenge = stream.read_u64_le().await?;
+            HeartbeatConnection { stream, challenge }
+        }
+    };
+    // Send the request to the validator, signed over the challenge.
+    let nonce = rand::random::<u64>();
+    let message = heartbeat_request_message(challenge, nonce, height);
+    let signature = account.sign_bytes(&message, &mut rand::thread_rng())?;
+    stream.write_u64_le(nonce).await?;
+    stream.write_u32_le(height).await?;
+    write_signature(&mut stream, signature).await?;
+
+    // Read the heartbeat from the validator.
+    let round = stream.read_u64_le().await?;
+    let challenge = stream.read_u64_le().await?;
+    let signature = read_signature::<N>(&mut stream).await?;
+    // Ensure the heartbeat is signed by the validator account.
+    ensure!(account.verify_bytes(&heartbeat_message(nonce, round, challenge), &signature), "The heartbeat is invalid");
+    // Read the blocks following the given height.
+    let num_blocks = stream.read_u16_le().await? as u32;
+    ensure!(num_blocks <= MAX_BLOCKS_PER_HEARTBEAT, "The heartbeat contains {num_blocks} blocks");
+    let mut blocks = Vec::with_capacity(num_blocks as usize);
+    let mut num_bytes = 0;
+    for _ in 0..num_blocks {
+        let length = stream.read_u32_le().await? as usize;
+        num_bytes += length;
+        ensure!(num_bytes <= MAX_HEARTBEAT_BLOCKS_SIZE, "The heartbeat blocks exceed {MAX_HEARTBEAT_BLOCKS_SIZE} B");
+        let mut bytes = vec![0u8; length];
+        stream.read_exact(&mut bytes).await?;
+        blocks.push(spawn_blocking!(Block::read_le(&bytes[..]))?);
+    }
+    Ok((HeartbeatConnection { stream, challenge }, round, blocks))
+}
+
+/// Waits in standby until the active validator on the given IP fails, and returns once the standby may promote itself.
+///
+/// The standby requests a heartbeat from the active validator at a fixed interval, and hands off the highest round
+/// signed by the validator to its own signing journal, so that it never signs in a round the validator has signed.
+/// The standby advances its ledger with the blocks in each heartbeat, so it is in sync once promoted.
+/// Once the validator has missed the maximum number of consecutive heartbeats, the standby waits for
+/// the signing lease granted by its last request to expire, and is promoted.
+///
+/// Note: The standby is only promoted after receiving at least one heartbeat, as it cannot otherwise
+/// distinguish a failed validator from a validator that it is unable to reach.
+pub async fn await_failover<N: Network>(
+    validator_ip: SocketAddr,
+    account: &Account<N>,
+    journal_path: &Path,
+    ledger: Arc<dyn LedgerService<N>>,
+) -> Result<()> {
+    let interval = Duration::from_millis(HEARTBEAT_INTERVAL_IN_MS);
+    let mut connection = None;
+    let mut is_handed_off = false;
+    let mut num_missed = 0;
+    loop {
+        // Request a heartbeat from the active validator.
+        let last_request = Instant::now();
+        let height = ledger.latest_block_height();
+        let result = timeout(interval, request_heartbeat(connection.take(), validator_ip, account, height))
+            .await
+            .unwrap_or_else(|_| Err(anyhow!("The heartbeat timed out")));
+        match result {
+            Ok((connection_, round, blocks)) => {
+                connection = Some(connection_);
+                // Hand off the signing journal.
+                let path = journal_path.to_path_buf();
+                spawn_blocking!(SigningJournal::write_round(&path, round))?;
+                // Advance the ledger with the blocks from the validator.
+                let ledger_ = ledger.clone();
+                spawn_blocking!(blocks.iter().try_for_each(|block| {
+                    ledger_.check_next_block(block)?;
+                    ledger_.advance_to_next_block(block)
+                }))?;
+                if !is_handed_off {
+                    info!("Standing by for the validator on '{validator_ip}' (round {round})");
+                }
+                is_handed_off = true;
+                num_missed = 0;
+            }
+            Err(error) => {
+                num_missed += 1;
+                warn!("Missed a heartbeat from '{validator_ip}' ({num_missed}/{MAX_MISSED_HEARTBEATS}) - {error}");
+            }
+        }
+        // If the validator has failed, promote the standby once the signing lease of the validator has expired.
+        if is_handed_off && num_missed >= MAX_MISSED_HEARTBEATS {
+            warn!("The validator on '{validator_ip}' has failed, promoting the standby");
+            let lease = Duration::from_millis(SIGNING_LEASE_IN_MS);
+            tokio::time::sleep_until((last_request + lease + interval).into()).await;
+            return Ok(());
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_node_bft_ledger_service::MockLedgerService;
+    use snarkvm::{ledger::committee::test_helpers::sample_committee, prelude::TestRng};
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[tokio::test]
+    async fn test_heartbeat() {
+        let mut rng = TestRng::default();
+        let account = Account::<CurrentNetwork>::new(&mut rng).unwrap();
+        let journal_path = std::env::temp_dir().join(format!("heartbeat-journal-{}", rand::random::<u64>()));
+        SigningJournal::write_round(&journal_path, 7).unwrap();
+        let ledger = Arc::new(MockLedgerService::new(sample_committee(&mut rng)));
+
+        // Serve heartbeats from the validator, on the listener bound to a free port.
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let validator_ip = listener.local_addr().unwrap();
+        let lease = Arc::new(SigningLease::default());
+        let handle = spawn_heartbeat_server(listener, account.clone(), journal_path.clone(), lease.clone(), ledger);
+        assert!(!lease.is_held());
+
+        // Ensure the standby authenticates the heartbeat, receives the highest signed round, and renews the lease.
+        let (connection, round, blocks) = request_heartbeat(None, validator_ip, &account, 0).await.unwrap();
+        assert_eq!(round, 7);
+        assert!(blocks.is_empty());
+        assert!(lease.is_held());
+        // Ensure the connection is reused for the next heartbeat.
+        let (_, round, _) = request_heartbeat(Some(connection), validator_ip, &account, 0).await.unwrap();
+        assert_eq!(round, 7);
+
+        // Ensure a heartbeat request signed by another account is rejected, and does not renew the lease.
+        let other = Account::<CurrentNetwork>::new(&mut rng).unwrap();
+        *lease.expires_at.lock() = None;
+        assert!(request_heartbeat(None, validator_ip, &other, 0).await.is_err());
+        assert!(!lease.is_held());
+
+        handle.abort();
+        std::fs::remove_file(journal_path).unwrap();
+    }
+}
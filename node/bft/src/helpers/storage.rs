@@ -12,7 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::helpers::{check_timestamp_for_liveness, fmt_id};
+use crate::{
+    helpers::{check_timestamp_for_liveness, fmt_id, Proposal},
+    MAX_GC_ROUNDS,
+};
 use snarkos_node_bft_ledger_service::LedgerService;
 use snarkos_node_bft_storage_service::StorageService;
 use snarkvm::{
@@ -27,12 +30,22 @@ use indexmap::{IndexMap, IndexSet};
 use parking_lot::RwLock;
 use rayon::prelude::*;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     sync::{
         atomic::{AtomicU32, AtomicU64, Ordering},
         Arc,
     },
 };
+use tokio::sync::watch;
+
+/// The minimum number of rounds that storage may retain behind the last committed round.
+pub const MIN_COMMITTED_GC_DEPTH: u64 = 2; // rounds
+
+/// The error of a batch header or certificate that fails verification, which is attributable to its sender,
+/// as opposed to an error from checking it against the local state (e.g. a missing previous certificate).
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct InvalidCertificate(pub String);
 
 #[derive(Clone, Debug)]
 pub struct Storage<N: Network>(Arc<StorageInner<N>>);
@@ -64,6 +77,9 @@ impl<N: Network> std::ops::Deref for Storage<N> {
 ///   - The certificate ID is inserted into the `transmissions` map.
 /// 3. After a `round` reaches quorum threshold:
 ///  - The next round is inserted into the `current_round`.
+/// 4. After a subdag is committed into a block:
+///  - The transmissions persisted in the ledger are pruned from the `transmissions` map.
+///  - The certificates are retained as parents, until they are garbage collected.
 #[derive(Debug)]
 pub struct StorageInner<N: Network> {
     /// The ledger service.
@@ -76,8 +92,14 @@ pub struct StorageInner<N: Network> {
     current_round: AtomicU64,
     /// The `round` for which garbage collection has occurred **up to** (inclusive).
     gc_round: AtomicU64,
+    /// The last committed round, below which storage may never be rolled back.
+    committed_round: AtomicU64,
+    /// The sender notifying the subscribers of the round that storage was last rolled back to.
+    rollbacks: watch::Sender<u64>,
     /// The maximum number of rounds to keep in storage.
     max_gc_rounds: u64,
+    /// The maximum number of rounds to keep in storage behind the last committed round.
+    committed_gc_depth: u64,
     /* Once per batch */
     /// The map of `round` to a list of `(certificate ID, batch ID, author)` entries.
     rounds: RwLock<IndexMap<u64, IndexSet<(Field<N>, Field<N>, Address<N>)>>>,
@@ -90,11 +112,35 @@ pub struct StorageInner<N: Network> {
 }
 
 impl<N: Network> Storage<N> {
-    /// Initializes a new instance of storage.
+    /// Initializes a new instance of storage, which retains up to `max_gc_rounds` behind the last committed round.
     pub fn new(
         ledger: Arc<dyn LedgerService<N>>,
         transmissions: Arc<dyn StorageService<N>>,
         max_gc_rounds: u64,
+    ) -> Self {
+        Self::initialize(ledger, transmissions, max_gc_rounds, max_gc_rounds)
+    }
+
+    /// Initializes a new instance of storage, which retains the given number of rounds behind the last committed round.
+    pub fn with_committed_gc_depth(
+        ledger: Arc<dyn LedgerService<N>>,
+        transmissions: Arc<dyn StorageService<N>>,
+        max_gc_rounds: u64,
+        committed_gc_depth: u64,
+    ) -> Result<Self> {
+        ensure!(
+            (MIN_COMMITTED_GC_DEPTH..=MAX_GC_ROUNDS).contains(&committed_gc_depth),
+            "The committed GC depth must be between {MIN_COMMITTED_GC_DEPTH} and {MAX_GC_ROUNDS} rounds"
+        );
+        Ok(Self::initialize(ledger, transmissions, max_gc_rounds, committed_gc_depth.min(max_gc_rounds)))
+    }
+
+    /// Initializes a new instance of storage, with the given number of rounds to keep behind the last committed round.
+    fn initialize(
+        ledger: Arc<dyn LedgerService<N>>,
+        transmissions: Arc<dyn StorageService<N>>,
+        max_gc_rounds: u64,
+        committed_gc_depth: u64,
     ) -> Self {
         // Retrieve the current committee.
         let committee = ledger.current_committee().expect("Ledger is missing a committee.");
@@ -107,7 +153,10 @@ impl<N: Network> Storage<N> {
             current_height: Default::default(),
             current_round: Default::default(),
             gc_round: Default::default(),
+            committed_round: Default::default(),
+            rollbacks: watch::channel(0).0,
             max_gc_rounds,
+            committed_gc_depth,
             rounds: Default::default(),
             certificates: Default::default(),
             batch_ids: Default::default(),
@@ -115,6 +164,8 @@ impl<N: Network> Storage<N> {
         }));
         // Update the storage to the current round.
         storage.update_current_round(current_round);
+        // Restore the certificates from the storage service.
+        storage.restore_certificates();
         // Return the storage.
         storage
     }
@@ -141,11 +192,21 @@ impl<N: Network> Storage<N> {
         self.gc_round.load(Ordering::SeqCst)
     }
 
+    /// Returns the last committed round.
+    pub fn committed_round(&self) -> u64 {
+        self.committed_round.load(Ordering::SeqCst)
+    }
+
     /// Returns the maximum number of rounds to keep in storage.
     pub fn max_gc_rounds(&self) -> u64 {
         self.max_gc_rounds
     }
 
+    /// Returns the maximum number of rounds to keep in storage behind the last committed round.
+    pub fn committed_gc_depth(&self) -> u64 {
+        self.committed_gc_depth
+    }
+
     /// Increments storage to the next round, updating the current round.
     /// Note: This method is only called once per round, upon certification of the primary's batch.
     pub fn increment_to_next_round(&self, current_round: u64) -> Result<u64> {
@@ -197,10 +258,100 @@ impl<N: Network> Storage<N> {
         // Update the current round.
         self.current_round.store(next_round, Ordering::SeqCst);
 
-        // Fetch the current GC round.
-        let current_gc_round = self.gc_round();
         // Compute the next GC round.
         let next_gc_round = next_round.saturating_sub(self.max_gc_rounds);
+        // Garbage collect the storage.
+        self.garbage_collect(next_gc_round);
+    }
+
+    /// Garbage collects the rounds that are more than the committed GC depth behind the given committed round.
+    ///
+    /// Note: This method is called once the certificates up to the given round are committed,
+    /// so that storage does not retain the full `max_gc_rounds` of certificates that are already in the ledger.
+    pub fn garbage_collect_committed(&self, committed_round: u64) {
+        // Update the last committed round, ensuring it never moves backwards.
+        self.committed_round.fetch_max(committed_round, Ordering::SeqCst);
+        // Compute the next GC round, ensuring it never passes the current round.
+        let next_gc_round = committed_round.min(self.current_round()).saturating_sub(self.committed_gc_depth);
+        // Garbage collect the storage.
+        self.garbage_collect(next_gc_round);
+    }
+
+    /// Returns a receiver that is notified with the target round whenever storage is rolled back.
+    pub fn subscribe_rollbacks(&self) -> watch::Receiver<u64> {
+        self.rollbacks.subscribe()
+    }
+
+    /// Rolls back storage to the given round, and returns the number of certificates discarded.
+    ///
+    /// This resets the current round to the given round, and discards the certificates (and the persisted proposal)
+    /// for the rounds beyond it, so that crash recovery can rebuild those rounds from the network.
+    /// Storage can never be rolled back below the last committed round, or to a garbage-collected round.
+    pub fn rollback_to_round(&self, round: u64) -> Result<usize> {
+        // Ensure the round is at or after the last committed round.
+        let committed_round = self.committed_round();
+        ensure!(round >= committed_round, "Cannot roll back to round {round}, below committed round {committed_round}");
+        // Ensure the round is above the GC round.
+        let gc_round = self.gc_round();
+        ensure!(round > gc_round, "Cannot roll back to round {round}, at or below the GC round {gc_round}");
+        // Reset the current round, ensuring it is at or after the given round.
+        self.current_round
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |current_round| (round <= current_round).then_some(round))
+            .map_err(|current_round| anyhow!("Cannot roll back to round {round}, ahead of round {current_round}"))?;
+
+        // Retrieve the certificates for the rounds beyond the given round.
+        let certificate_ids = self
+            .certificates
+            .read()
+            .iter()
+            .filter(|(_, certificate)| certificate.round() > round)
+            .map(|(certificate_id, _)| *certificate_id)
+            .collect::<Vec<_>>();
+        // Discard the certificates.
+        for certificate_id in &certificate_ids {
+            self.remove_certificate(*certificate_id);
+        }
+        // Discard the persisted proposal, if it is beyond the given round.
+        if self.get_proposal().map_or(false, |(batch_header, _)| batch_header.round() > round) {
+            self.remove_proposal();
+        }
+
+        #[cfg(feature = "metrics")]
+        metrics::gauge(metrics::bft::LAST_STORED_ROUND, round as f64);
+
+        // Notify the subscribers of the rollback.
+        self.rollbacks.send_replace(round);
+        warn!("Rolled back to round {round}, discarding {} certificates", certificate_ids.len());
+        Ok(certificate_ids.len())
+    }
+
+    /// Prunes the transmissions of the given committed certificates that are persisted in the ledger,
+    /// and returns the number of transmissions pruned.
+    ///
+    /// Note: This method is called once the block for the committed subdag is persisted, so that storage only
+    /// retains the certificates themselves (as the parents of the next rounds) until they are garbage collected.
+    pub fn prune_committed<'a>(&self, certificates: impl IntoIterator<Item = &'a BatchCertificate<N>>) -> usize {
+        let mut num_pruned = 0;
+        for certificate in certificates {
+            // Retrieve the transmission IDs that are persisted in the ledger.
+            // Note: On failure to read from the ledger, the transmission is retained, out of safety.
+            let transmission_ids = certificate
+                .transmission_ids()
+                .iter()
+                .filter(|transmission_id| self.ledger.contains_transmission(transmission_id).unwrap_or(false))
+                .copied()
+                .collect::<IndexSet<_>>();
+            // Remove the certificate ID for the transmissions, removing the transmissions without other certificates.
+            self.transmissions.remove_transmissions(&certificate.id(), &transmission_ids);
+            num_pruned += transmission_ids.len();
+        }
+        num_pruned
+    }
+
+    /// Removes the certificates for the rounds up to the given GC round (inclusive) from storage.
+    fn garbage_collect(&self, next_gc_round: u64) {
+        // Fetch the current GC round.
+        let current_gc_round = self.gc_round();
         // Check if storage needs to be garbage collected.
         if next_gc_round > current_gc_round {
             // Remove the GC round(s) from storage.
@@ -211,9 +362,12 @@ impl<N: Network> Storage<N> {
                     self.remove_certificate(certificate.id());
                 }
             }
-            // Update the GC round.
-            self.gc_round.store(next_gc_round, Ordering::SeqCst);
+            // Update the GC round, ensuring it never moves backwards.
+            self.gc_round.fetch_max(next_gc_round, Ordering::SeqCst);
         }
+
+        #[cfg(feature = "metrics")]
+        metrics::gauge(metrics::bft::RETAINED_ROUNDS, self.rounds.read().len() as f64);
     }
 }
 
@@ -247,9 +401,26 @@ impl<N: Network> Storage<N> {
     }
 
     /// Returns the transmission for the given `transmission ID`.
-    /// If the transmission ID does not exist in storage, `None` is returned.
+    /// If the transmission ID does not exist in storage, or in the ledger once pruned, `None` is returned.
     pub fn get_transmission(&self, transmission_id: impl Into<TransmissionID<N>>) -> Option<Transmission<N>> {
-        self.transmissions.get_transmission(transmission_id.into())
+        let transmission_id = transmission_id.into();
+        // Retrieve the transmission from the storage service.
+        if let Some(transmission) = self.transmissions.get_transmission(transmission_id) {
+            return Some(transmission);
+        }
+        // Otherwise, retrieve the transmission from the ledger, as it is pruned from storage once committed.
+        if !self.ledger.contains_transmission(&transmission_id).unwrap_or(false) {
+            return None;
+        }
+        match transmission_id {
+            TransmissionID::Ratification => None,
+            TransmissionID::Solution(puzzle_commitment) => {
+                self.ledger.get_solution(&puzzle_commitment).ok().map(Into::into)
+            }
+            TransmissionID::Transaction(transaction_id) => {
+                self.ledger.get_unconfirmed_transaction(transaction_id).ok().map(Into::into)
+            }
+        }
     }
 
     /// Returns the round for the given `certificate ID`.
@@ -295,6 +466,11 @@ impl<N: Network> Storage<N> {
         }
     }
 
+    /// Returns the number of certificates in storage for each round, in ascending order of rounds.
+    pub fn num_certificates_per_round(&self) -> BTreeMap<u64, usize> {
+        self.rounds.read().iter().map(|(round, entries)| (*round, entries.len())).collect()
+    }
+
     /// Returns the certificates for the given `round`.
     /// If the round does not exist in storage, `None` is returned.
     pub fn get_certificates_for_round(&self, round: u64) -> IndexSet<BatchCertificate<N>> {
@@ -346,11 +522,14 @@ impl<N: Network> Storage<N> {
         };
         // Ensure the author is in the committee.
         if !previous_committee.is_committee_member(batch_header.author()) {
-            bail!("Author {} is not in the committee for round {round} {gc_log}", batch_header.author())
+            bail!(InvalidCertificate(format!(
+                "Author {} is not in the committee for round {round} {gc_log}",
+                batch_header.author()
+            )))
         }
 
         // Check the timestamp for liveness.
-        check_timestamp_for_liveness(batch_header.timestamp())?;
+        check_timestamp_for_liveness(batch_header.timestamp()).map_err(|e| InvalidCertificate(e.to_string()))?;
 
         // Retrieve the missing transmissions in storage from the given transmissions.
         let missing_transmissions = self
@@ -372,7 +551,7 @@ impl<N: Network> Storage<N> {
             }
             // Ensure the number of previous certificate IDs is at or below the number of committee members.
             if batch_header.previous_certificate_ids().len() > previous_committee.num_members() {
-                bail!("Too many previous certificates for round {round} {gc_log}")
+                bail!(InvalidCertificate(format!("Too many previous certificates for round {round} {gc_log}")))
             }
             // Initialize a set of the previous authors.
             let mut previous_authors = HashSet::with_capacity(batch_header.previous_certificate_ids().len());
@@ -387,18 +566,22 @@ impl<N: Network> Storage<N> {
                 };
                 // Ensure the previous certificate is for the previous round.
                 if previous_certificate.round() != previous_round {
-                    bail!("Round {round} certificate contains a round {previous_round} certificate {gc_log}")
+                    bail!(InvalidCertificate(format!(
+                        "Round {round} certificate contains a round {previous_round} certificate {gc_log}"
+                    )))
                 }
                 // Ensure the previous author is new.
                 if previous_authors.contains(&previous_certificate.author()) {
-                    bail!("Round {round} certificate contains a duplicate author {gc_log}")
+                    bail!(InvalidCertificate(format!("Round {round} certificate contains a duplicate author {gc_log}")))
                 }
                 // Insert the author of the previous certificate.
                 previous_authors.insert(previous_certificate.author());
             }
             // Ensure the previous certificates have reached the quorum threshold.
             if !previous_committee.is_quorum_threshold_reached(&previous_authors) {
-                bail!("Previous certificates for a batch in round {round} did not reach quorum threshold {gc_log}")
+                bail!(InvalidCertificate(format!(
+                    "Previous certificates for a batch in round {round} did not reach quorum threshold {gc_log}"
+                )))
             }
         }
         Ok(missing_transmissions)
@@ -445,7 +628,23 @@ impl<N: Network> Storage<N> {
         let missing_transmissions = self.check_batch_header(certificate.batch_header(), transmissions)?;
 
         // Check the timestamp for liveness.
-        check_timestamp_for_liveness(certificate.timestamp())?;
+        check_timestamp_for_liveness(certificate.timestamp()).map_err(|e| InvalidCertificate(e.to_string()))?;
+
+        // Ensure the signers are in the committee, and have reached the quorum threshold.
+        self.check_certificate_signers(certificate)?;
+        Ok(missing_transmissions)
+    }
+
+    /// Checks the signers of the given `certificate`, which does not depend on its previous certificates.
+    ///
+    /// This method ensures the following invariants:
+    /// - The signers are members of the committee for the batch round.
+    /// - The signers have reached the quorum threshold (2f+1).
+    pub fn check_certificate_signers(&self, certificate: &BatchCertificate<N>) -> Result<()> {
+        // Retrieve the round.
+        let round = certificate.round();
+        // Construct a GC log message.
+        let gc_log = format!("(gc = {})", self.gc_round());
 
         // Retrieve the previous committee for the batch round.
         let Ok(previous_committee) = self.ledger.get_previous_committee_for_round(round) else {
@@ -463,7 +662,7 @@ impl<N: Network> Storage<N> {
             let signer = signature.to_address();
             // Ensure the signer is in the committee.
             if !previous_committee.is_committee_member(signer) {
-                bail!("Signer {signer} is not in the committee for round {round} {gc_log}")
+                bail!(InvalidCertificate(format!("Signer {signer} is not in the committee for round {round} {gc_log}")))
             }
             // Append the signer.
             signers.insert(signer);
@@ -471,9 +670,11 @@ impl<N: Network> Storage<N> {
 
         // Ensure the signatures have reached the quorum threshold.
         if !previous_committee.is_quorum_threshold_reached(&signers) {
-            bail!("Signatures for a batch in round {round} did not reach quorum threshold {gc_log}")
+            bail!(InvalidCertificate(format!(
+                "Signatures for a batch in round {round} did not reach quorum threshold {gc_log}"
+            )))
         }
-        Ok(missing_transmissions)
+        Ok(())
     }
 
     /// Inserts the given `certificate` into storage.
@@ -522,6 +723,8 @@ impl<N: Network> Storage<N> {
 
         // Insert the round to certificate ID entry.
         self.rounds.write().entry(round).or_default().insert((certificate_id, batch_id, author));
+        // Persist the certificate in the storage service.
+        self.transmissions.insert_certificate(&certificate);
         // Obtain the certificate's transmission ids.
         let transmission_ids = certificate.transmission_ids().clone();
         // Insert the certificate.
@@ -530,6 +733,9 @@ impl<N: Network> Storage<N> {
         self.batch_ids.write().insert(batch_id, round);
         // Insert the certificate ID for each of the transmissions into storage.
         self.transmissions.insert_transmissions(certificate_id, transmission_ids, missing_transmissions);
+
+        #[cfg(feature = "metrics")]
+        metrics::gauge(metrics::bft::RETAINED_ROUNDS, self.rounds.read().len() as f64);
     }
 
     /// Removes the given `certificate ID` from storage.
@@ -568,9 +774,75 @@ impl<N: Network> Storage<N> {
         self.batch_ids.write().remove(&batch_id);
         // Remove the transmission entries in the certificate from storage.
         self.transmissions.remove_transmissions(&certificate_id, certificate.transmission_ids());
+        // Remove the certificate from the storage service.
+        self.transmissions.remove_certificate(&certificate_id);
         // Return successfully.
         true
     }
+
+    /// Restores the certificates from the storage service, and advances to the last restored round.
+    ///
+    /// This ensures a restarted validator resumes from the round it reached, with the certificates it produced
+    /// and received, instead of resuming from the round of the latest block and risking equivocation.
+    fn restore_certificates(&self) {
+        // Retrieve the certificates, ordered by round.
+        let mut certificates = self.transmissions.certificates();
+        if certificates.is_empty() {
+            return;
+        }
+        certificates.sort_unstable_by_key(|certificate| certificate.round());
+
+        // Advance to the last restored round.
+        let last_round = certificates.last().map_or(0, |certificate| certificate.round());
+        if last_round > self.current_round() {
+            self.update_current_round(last_round);
+        }
+
+        // Retrieve the GC round.
+        let gc_round = self.gc_round();
+        // Restore the certificates above the GC round.
+        let mut num_restored = 0;
+        for certificate in certificates {
+            // Retrieve the round.
+            let round = certificate.round();
+            // Retrieve the certificate ID.
+            let certificate_id = certificate.id();
+            // Retrieve the batch ID.
+            let batch_id = certificate.batch_id();
+            // Retrieve the author of the batch.
+            let author = certificate.author();
+
+            // If the certificate is at or below the GC round, remove it from the storage service.
+            if round <= gc_round {
+                self.transmissions.remove_transmissions(&certificate_id, certificate.transmission_ids());
+                self.transmissions.remove_certificate(&certificate_id);
+                continue;
+            }
+            // Insert the certificate, as its transmissions are already in the storage service.
+            self.rounds.write().entry(round).or_default().insert((certificate_id, batch_id, author));
+            self.certificates.write().insert(certificate_id, certificate);
+            self.batch_ids.write().insert(batch_id, round);
+            num_restored += 1;
+        }
+        info!("Restored {num_restored} certificates from storage, up to round {last_round}");
+    }
+}
+
+impl<N: Network> Storage<N> {
+    /// Persists the given batch proposal of this node, so that it is restored after a restart.
+    pub fn insert_proposal(&self, proposal: &Proposal<N>) {
+        self.transmissions.insert_proposal(proposal.batch_header(), proposal.transmissions());
+    }
+
+    /// Removes the persisted batch proposal of this node.
+    pub fn remove_proposal(&self) {
+        self.transmissions.remove_proposal();
+    }
+
+    /// Returns the persisted batch proposal of this node, as its batch header and transmissions.
+    pub fn get_proposal(&self) -> Option<(BatchHeader<N>, IndexMap<TransmissionID<N>, Transmission<N>>)> {
+        self.transmissions.proposal()
+    }
 }
 
 impl<N: Network> Storage<N> {
@@ -933,6 +1205,135 @@ mod tests {
         // Check that the underlying storage representation remains unchanged.
         assert_storage(&storage, &rounds, &certificates, &batch_ids, &transmissions);
     }
+
+    #[test]
+    fn test_garbage_collect_committed() {
+        use snarkvm::ledger::{
+            ledger_test_helpers::sample_fee_public_transaction,
+            narwhal::batch_certificate::test_helpers::sample_batch_certificate_for_round,
+        };
+
+        let rng = &mut TestRng::default();
+
+        // Sample a committee.
+        let committee = snarkvm::ledger::committee::test_helpers::sample_committee(rng);
+        // Initialize the ledger.
+        let ledger = Arc::new(MockLedgerService::new(committee));
+        // Initialize the storage.
+        let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 4);
+        // Ensure the committed GC depth defaults to the maximum number of GC rounds.
+        assert_eq!(storage.committed_gc_depth(), 4);
+        // Ensure the committed GC depth must be within bounds, and is capped at the maximum number of GC rounds.
+        let with_depth = |depth| {
+            let transmissions = Arc::new(BFTMemoryService::new());
+            Storage::<CurrentNetwork>::with_committed_gc_depth(ledger.clone(), transmissions, 4, depth)
+        };
+        assert!(with_depth(MIN_COMMITTED_GC_DEPTH - 1).is_err());
+        assert!(with_depth(MAX_GC_ROUNDS + 1).is_err());
+        assert_eq!(with_depth(MIN_COMMITTED_GC_DEPTH).unwrap().committed_gc_depth(), MIN_COMMITTED_GC_DEPTH);
+        assert_eq!(with_depth(MAX_GC_ROUNDS).unwrap().committed_gc_depth(), 4);
+
+        // Insert a certificate for each of the rounds 1 to 10.
+        for round in 1..=10 {
+            let certificate = sample_batch_certificate_for_round(round, rng);
+            let (missing_transmissions, _) = sample_transmissions(&certificate, rng);
+            storage.insert_certificate_atomic(certificate, missing_transmissions);
+        }
+        // Set the current round, without garbage collecting.
+        storage.current_round.store(10, Ordering::SeqCst);
+
+        // Commit up to round 8, and ensure the rounds up to 4 are garbage collected.
+        storage.garbage_collect_committed(8);
+        assert_eq!(storage.gc_round(), 4);
+        assert!((1..=4).all(|round| !storage.contains_certificates_for_round(round)));
+        assert!((5..=10).all(|round| storage.contains_certificates_for_round(round)));
+
+        // Ensure pruning retains the transmissions that are not persisted in the ledger.
+        let num_transmissions = storage.transmissions_iter().count();
+        assert_eq!(storage.prune_committed(storage.get_certificates_for_round(8).iter()), 0);
+        assert_eq!(storage.transmissions_iter().count(), num_transmissions);
+        assert!(storage.contains_certificates_for_round(8));
+
+        // Persist the transactions of a committed certificate in the ledger.
+        let certificate = storage.get_certificates_for_round(8).into_iter().next().unwrap();
+        let transaction = sample_fee_public_transaction(rng);
+        let transaction_ids = certificate
+            .transmission_ids()
+            .iter()
+            .filter(|transmission_id| matches!(transmission_id, TransmissionID::Transaction(_)))
+            .copied()
+            .collect::<Vec<_>>();
+        for transaction_id in &transaction_ids {
+            ledger.insert_transmission(*transaction_id, transaction.clone().into());
+        }
+        // Ensure pruning removes the persisted transmissions, and retains the others.
+        assert_eq!(storage.prune_committed([&certificate]), transaction_ids.len());
+        assert_eq!(storage.transmissions_iter().count(), num_transmissions - transaction_ids.len());
+        for transmission_id in certificate.transmission_ids() {
+            let is_pruned = transaction_ids.contains(transmission_id);
+            assert_eq!(storage.transmissions_iter().any(|(id, _)| id == *transmission_id), !is_pruned);
+            // Ensure the pruned transmissions are still returned, from the ledger.
+            assert!(storage.get_transmission(*transmission_id).is_some());
+        }
+        for transaction_id in &transaction_ids {
+            assert_eq!(storage.get_transmission(*transaction_id), Some(transaction.clone().into()));
+        }
+        assert!(storage.contains_certificate(certificate.id()));
+
+        // Ensure an older commit does not move the GC round backwards.
+        storage.garbage_collect_committed(6);
+        assert_eq!(storage.gc_round(), 4);
+
+        // Ensure a commit beyond the current round does not collect past the current round.
+        storage.garbage_collect_committed(20);
+        assert_eq!(storage.gc_round(), 6);
+        assert!((1..=6).all(|round| !storage.contains_certificates_for_round(round)));
+        assert!((7..=10).all(|round| storage.contains_certificates_for_round(round)));
+    }
+
+    #[test]
+    fn test_rollback_to_round() {
+        use snarkvm::ledger::narwhal::batch_certificate::test_helpers::sample_batch_certificate_for_round;
+
+        let rng = &mut TestRng::default();
+
+        // Sample a committee.
+        let committee = snarkvm::ledger::committee::test_helpers::sample_committee(rng);
+        // Initialize the ledger.
+        let ledger = Arc::new(MockLedgerService::new(committee));
+        // Initialize the storage.
+        let storage = Storage::<CurrentNetwork>::new(ledger, Arc::new(BFTMemoryService::new()), 10);
+        let mut receiver = storage.subscribe_rollbacks();
+
+        // Insert a certificate for each of the rounds 1 to 10.
+        for round in 1..=10 {
+            let certificate = sample_batch_certificate_for_round(round, rng);
+            let (missing_transmissions, _) = sample_transmissions(&certificate, rng);
+            storage.insert_certificate_atomic(certificate, missing_transmissions);
+        }
+        // Set the current round, and commit up to round 5.
+        storage.current_round.store(10, Ordering::SeqCst);
+        storage.garbage_collect_committed(5);
+        assert_eq!(storage.committed_round(), 5);
+
+        // Ensure storage cannot be rolled back below the committed round, or ahead of the current round.
+        assert!(storage.rollback_to_round(4).is_err());
+        assert!(storage.rollback_to_round(11).is_err());
+        assert_eq!(storage.current_round(), 10);
+        assert!(!receiver.has_changed().unwrap());
+
+        // Ensure the rollback resets the current round, discards the rounds beyond it, and notifies the subscribers.
+        assert_eq!(storage.rollback_to_round(7).unwrap(), 3);
+        assert_eq!(storage.current_round(), 7);
+        assert!((1..=7).all(|round| storage.contains_certificates_for_round(round)));
+        assert!((8..=10).all(|round| !storage.contains_certificates_for_round(round)));
+        assert!(storage.certificates_iter().all(|(_, certificate)| certificate.round() <= 7));
+        assert_eq!(*receiver.borrow_and_update(), 7);
+
+        // Ensure a rollback to the committed round succeeds.
+        assert_eq!(storage.rollback_to_round(5).unwrap(), 2);
+        assert_eq!(storage.current_round(), 5);
+    }
 }
 
 #[cfg(test)]
@@ -13,15 +13,46 @@
 // limitations under the License.
 
 use crate::MAX_TIMESTAMP_DELTA_IN_SECS;
-use snarkvm::prelude::{bail, Result};
+use snarkvm::{
+    ledger::committee::Committee,
+    prelude::{bail, Address, Network, Result},
+};
 
+use indexmap::IndexMap;
+use parking_lot::RwLock;
+use std::sync::atomic::{AtomicI64, Ordering};
 use time::OffsetDateTime;
 
-/// Returns the current UTC epoch timestamp.
+/// The minimum number of validators required to estimate the clock offset.
+const MIN_CLOCK_OFFSET_SAMPLES: usize = 3;
+/// The minimum clock offset in seconds for the system clock to be considered skewed.
+const MIN_CLOCK_SKEW_IN_SECS: i64 = 2; // seconds
+/// The maximum clock offset in seconds that may be applied to the system clock.
+const MAX_CLOCK_OFFSET_IN_SECS: i64 = 60; // seconds
+
+/// The offset in seconds that is applied to the system clock.
+static CLOCK_OFFSET: AtomicI64 = AtomicI64::new(0);
+
+/// Returns the current UTC epoch timestamp, adjusted by the clock offset.
 pub fn now() -> i64 {
+    system_now() + CLOCK_OFFSET.load(Ordering::SeqCst)
+}
+
+/// Returns the current UTC epoch timestamp in milliseconds, adjusted by the clock offset.
+pub fn now_in_millis() -> i64 {
+    (OffsetDateTime::now_utc().unix_timestamp_nanos() / 1_000_000) as i64 + CLOCK_OFFSET.load(Ordering::SeqCst) * 1000
+}
+
+/// Returns the current UTC epoch timestamp of the system clock.
+fn system_now() -> i64 {
     OffsetDateTime::now_utc().unix_timestamp()
 }
 
+/// Returns the offset in seconds that is applied to the system clock.
+pub fn clock_offset() -> i64 {
+    CLOCK_OFFSET.load(Ordering::SeqCst)
+}
+
 /// Sanity checks the timestamp for liveness.
 pub fn check_timestamp_for_liveness(timestamp: i64) -> Result<()> {
     // Ensure the timestamp is within range.
@@ -36,6 +67,121 @@ pub fn check_timestamp_for_liveness(timestamp: i64) -> Result<()> {
     Ok(())
 }
 
+/// An estimator of the offset of the system clock, from the timestamps of the batches proposed by other validators.
+#[derive(Debug)]
+pub struct ClockOffset<N: Network> {
+    /// The map of validator addresses to the offset of their latest proposed timestamp from the system clock.
+    offsets: RwLock<IndexMap<Address<N>, i64>>,
+    /// The boolean flag for whether the system clock is adjusted by the estimated clock offset.
+    is_clock_sync_enabled: bool,
+}
+
+impl<N: Network> Default for ClockOffset<N> {
+    /// Initializes a new clock offset estimator, which does not adjust the system clock.
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+impl<N: Network> ClockOffset<N> {
+    /// Initializes a new clock offset estimator, which adjusts the system clock by the median offset
+    /// of the timestamps observed from validators, if clock sync is enabled.
+    pub fn new(is_clock_sync_enabled: bool) -> Self {
+        Self { offsets: Default::default(), is_clock_sync_enabled }
+    }
+
+    /// Records the timestamp of a batch that was just proposed by the given validator,
+    /// and adjusts the clock offset, if clock sync is enabled.
+    pub fn insert_timestamp(&self, author: Address<N>, timestamp: i64) {
+        // Record the offset of the timestamp from the system clock.
+        self.insert_offset(author, timestamp.saturating_sub(system_now()));
+        // If clock sync is enabled, apply the estimated clock offset.
+        if self.is_clock_sync_enabled {
+            let offset = self.estimate();
+            if CLOCK_OFFSET.swap(offset, Ordering::SeqCst) != offset {
+                info!("Adjusting the system clock by {offset} seconds");
+            }
+            #[cfg(feature = "metrics")]
+            metrics::gauge(metrics::bft::CLOCK_OFFSET, offset as f64);
+        }
+    }
+
+    /// Returns the clock offset in seconds, as the median offset of the validators.
+    /// The offset is zero if there are too few validators, or if the system clock is not skewed.
+    pub fn estimate(&self) -> i64 {
+        // Retrieve the sorted offsets.
+        let mut offsets = self.offsets.read().values().copied().collect::<Vec<_>>();
+        // Ensure there are enough validators to estimate the offset.
+        if offsets.len() < MIN_CLOCK_OFFSET_SAMPLES {
+            return 0;
+        }
+        offsets.sort_unstable();
+        // Compute the median offset.
+        let middle = offsets.len() / 2;
+        let median = match offsets.len() % 2 {
+            0 => (offsets[middle - 1] + offsets[middle]) / 2,
+            _ => offsets[middle],
+        };
+        // Ensure the system clock is skewed, and bound the offset.
+        match median.abs() < MIN_CLOCK_SKEW_IN_SECS {
+            true => 0,
+            false => median.clamp(-MAX_CLOCK_OFFSET_IN_SECS, MAX_CLOCK_OFFSET_IN_SECS),
+        }
+    }
+
+    /// Records the offset for the given validator, keeping at most one offset per committee member.
+    fn insert_offset(&self, author: Address<N>, offset: i64) {
+        let mut offsets = self.offsets.write();
+        // Move the validator to the back, so that the stalest offsets are evicted first.
+        offsets.shift_remove(&author);
+        offsets.insert(author, offset);
+        if offsets.len() > usize::from(Committee::<N>::MAX_COMMITTEE_SIZE) {
+            offsets.shift_remove_index(0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{prelude::Rng, utilities::TestRng};
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_clock_offset_estimate() {
+        let mut rng = TestRng::default();
+        let clock_offset = ClockOffset::<CurrentNetwork>::default();
+
+        // Ensure there is no offset with too few validators.
+        clock_offset.insert_offset(Address::new(rng.gen()), 30);
+        clock_offset.insert_offset(Address::new(rng.gen()), 30);
+        assert_eq!(clock_offset.estimate(), 0);
+
+        // Ensure the median offset is applied.
+        clock_offset.insert_offset(Address::new(rng.gen()), -5);
+        assert_eq!(clock_offset.estimate(), 30);
+        clock_offset.insert_offset(Address::new(rng.gen()), -5);
+        assert_eq!(clock_offset.estimate(), 12);
+
+        // Ensure a small offset is ignored.
+        let clock_offset = ClockOffset::<CurrentNetwork>::default();
+        for offset in [1, -1, 1] {
+            clock_offset.insert_offset(Address::new(rng.gen()), offset);
+        }
+        assert_eq!(clock_offset.estimate(), 0);
+
+        // Ensure the offset is bounded, and each validator contributes one offset.
+        let author = Address::new(rng.gen());
+        for offset in [1000, 1000, 1000] {
+            clock_offset.insert_offset(author, offset);
+        }
+        clock_offset.insert_offset(Address::new(rng.gen()), 1000);
+        clock_offset.insert_offset(Address::new(rng.gen()), 1000);
+        assert_eq!(clock_offset.estimate(), MAX_CLOCK_OFFSET_IN_SECS);
+    }
+}
+
 #[cfg(test)]
 mod prop_tests {
     use super::*;
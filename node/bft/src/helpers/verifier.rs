@@ -0,0 +1,199 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    console::{
+        account::{Address, Signature},
+        prelude::*,
+        types::Field,
+    },
+    ledger::narwhal::{BatchCertificate, Data},
+};
+
+use indexmap::IndexMap;
+use parking_lot::Mutex;
+use rayon::prelude::*;
+use std::{collections::hash_map::RandomState, hash::BuildHasher};
+
+/// The maximum number of verified certificates retained in the verification cache.
+pub const MAX_VERIFIED_CERTIFICATES: usize = 1024; // certificates
+
+/// The verifier of the certificates and signatures received from peers.
+///
+/// Certificates are deserialized (which verifies their signatures) and signatures are verified on the blocking
+/// thread pool, so that the event handlers never block on cryptography. The verified certificates are cached,
+/// keyed by the digest of their serialized form, as the same certificate is received from many peers.
+#[derive(Debug)]
+pub struct CertificateVerifier<N: Network> {
+    /// The hasher used to compute the digest of a serialized certificate.
+    hasher: RandomState,
+    /// The cache of verified certificates, keyed by the digest of their serialized form.
+    cache: Mutex<IndexMap<u64, BatchCertificate<N>>>,
+}
+
+impl<N: Network> Default for CertificateVerifier<N> {
+    /// Initializes a new instance of the certificate verifier.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Network> CertificateVerifier<N> {
+    /// Initializes a new instance of the certificate verifier.
+    pub fn new() -> Self {
+        Self { hasher: RandomState::new(), cache: Default::default() }
+    }
+
+    /// Returns the number of certificates in the verification cache.
+    pub fn num_cached(&self) -> usize {
+        self.cache.lock().len()
+    }
+
+    /// Deserializes and verifies the given certificate on the blocking thread pool, unless it was verified before.
+    pub async fn verify_certificate(&self, certificate: Data<BatchCertificate<N>>) -> Result<BatchCertificate<N>> {
+        self.verify_certificates(vec![certificate]).await.pop().unwrap_or_else(|| bail!("Missing certificate"))
+    }
+
+    /// Deserializes and verifies the given certificates in parallel on the blocking thread pool,
+    /// unless they were verified before, and returns the results in the given order.
+    pub async fn verify_certificates(
+        &self,
+        certificates: Vec<Data<BatchCertificate<N>>>,
+    ) -> Vec<Result<BatchCertificate<N>>> {
+        // Look up the serialized certificates in the cache.
+        let lookups = certificates
+            .into_iter()
+            .map(|certificate| match certificate {
+                // Note: A certificate object was constructed by this node, and requires no verification.
+                Data::Object(certificate) => Ok(Ok(certificate)),
+                Data::Buffer(bytes) => {
+                    let digest = self.hasher.hash_one(&bytes);
+                    match self.cache.lock().get(&digest) {
+                        Some(certificate) => Ok(Ok(certificate.clone())),
+                        None => Err((digest, bytes)),
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // Retrieve the serialized certificates that are not cached.
+        let misses = lookups.iter().filter_map(|lookup| lookup.as_ref().err().cloned()).collect::<Vec<_>>();
+        // Deserialize and verify the certificates in parallel.
+        let mut verified = match tokio::task::spawn_blocking(move || {
+            misses
+                .into_par_iter()
+                .map(|(digest, bytes)| (digest, BatchCertificate::<N>::from_bytes_le(&bytes)))
+                .collect::<Vec<_>>()
+        })
+        .await
+        {
+            Ok(verified) => verified.into_iter(),
+            Err(error) => return lookups.iter().map(|_| Err(anyhow!("[tokio::spawn_blocking] {error}"))).collect(),
+        };
+
+        // Cache the verified certificates, and return the results in order.
+        lookups
+            .into_iter()
+            .map(|lookup| match lookup {
+                Ok(result) => result,
+                Err(_) => {
+                    let (digest, result) = verified.next().ok_or_else(|| anyhow!("Missing verification result"))?;
+                    if let Ok(certificate) = &result {
+                        self.insert(digest, certificate.clone());
+                    }
+                    result
+                }
+            })
+            .collect()
+    }
+
+    /// Verifies the given signature for the given batch ID on the blocking thread pool, returning the signer.
+    pub async fn verify_batch_signature(signature: Signature<N>, batch_id: Field<N>) -> Result<Address<N>> {
+        match tokio::task::spawn_blocking(move || {
+            // Retrieve the signer.
+            let signer = signature.to_address();
+            // Verify the signature.
+            match signature.verify(&signer, &[batch_id]) {
+                true => Ok(signer),
+                false => bail!("Invalid signature from '{signer}' for batch '{batch_id}'"),
+            }
+        })
+        .await
+        {
+            Ok(result) => result,
+            Err(error) => bail!("[tokio::spawn_blocking] {error}"),
+        }
+    }
+
+    /// Inserts the given verified certificate into the cache, evicting the oldest certificate if necessary.
+    fn insert(&self, digest: u64, certificate: BatchCertificate<N>) {
+        let mut cache = self.cache.lock();
+        cache.insert(digest, certificate);
+        if cache.len() > MAX_VERIFIED_CERTIFICATES {
+            cache.shift_remove_index(0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{
+        console::account::PrivateKey,
+        ledger::narwhal::batch_certificate::test_helpers::sample_batch_certificate,
+    };
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[tokio::test]
+    async fn test_verify_certificates() {
+        let rng = &mut TestRng::default();
+
+        // Sample a certificate, and serialize it.
+        let certificate = sample_batch_certificate(rng);
+        let bytes = certificate.to_bytes_le().unwrap();
+        let data = || Data::<BatchCertificate<CurrentNetwork>>::Buffer(bytes.clone().into());
+
+        let verifier = CertificateVerifier::<CurrentNetwork>::new();
+        // Ensure the certificate is verified and cached.
+        assert_eq!(verifier.verify_certificate(data()).await.unwrap(), certificate);
+        assert_eq!(verifier.num_cached(), 1);
+        // Ensure the cached certificate is returned.
+        let results = verifier.verify_certificates(vec![data(), Data::Object(certificate.clone())]).await;
+        assert!(results.iter().all(|result| result.as_ref().unwrap() == &certificate));
+        assert_eq!(verifier.num_cached(), 1);
+
+        // Ensure a corrupted certificate is rejected, and not cached.
+        let mut corrupted = bytes.clone();
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 1;
+        assert!(verifier.verify_certificate(Data::Buffer(corrupted.into())).await.is_err());
+        assert_eq!(verifier.num_cached(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_verify_batch_signature() {
+        let rng = &mut TestRng::default();
+
+        // Sign a batch ID.
+        let private_key = PrivateKey::<CurrentNetwork>::new(rng).unwrap();
+        let batch_id = Field::rand(rng);
+        let signature = private_key.sign(&[batch_id], rng).unwrap();
+
+        // Ensure the signature is verified for the batch ID.
+        let signer = CertificateVerifier::verify_batch_signature(signature, batch_id).await.unwrap();
+        assert_eq!(signer, Address::try_from(private_key).unwrap());
+        assert!(CertificateVerifier::verify_batch_signature(signature, Field::rand(rng)).await.is_err());
+    }
+}
@@ -0,0 +1,145 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use parking_lot::Mutex;
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+/// The startup warm-up of a primary, which must complete before the primary proposes its first batch.
+///
+/// The warm-up completes once the primary has been connected to quorum stake for the warm-up duration,
+/// is within the maximum number of blocks behind the network tip, and has caught up on the recent DAG.
+#[derive(Debug)]
+pub struct WarmUp {
+    /// The duration for which the primary must be connected to quorum stake.
+    duration: Duration,
+    /// The maximum number of blocks that the primary may be behind the network tip.
+    max_blocks_behind: u32,
+    /// The time since which the primary has been continuously connected to quorum stake.
+    quorum_connected_since: Mutex<Option<Instant>>,
+    /// The boolean flag for whether the primary has caught up on the recent DAG.
+    is_caught_up: AtomicBool,
+    /// The boolean flag for whether the warm-up has completed.
+    is_complete: AtomicBool,
+}
+
+impl WarmUp {
+    /// Initializes a new warm-up with the given duration and maximum number of blocks behind.
+    pub fn new(duration: Duration, max_blocks_behind: u32) -> Self {
+        Self {
+            duration,
+            max_blocks_behind,
+            quorum_connected_since: Default::default(),
+            is_caught_up: Default::default(),
+            is_complete: Default::default(),
+        }
+    }
+
+    /// Initializes a warm-up that is already complete.
+    pub fn disabled() -> Self {
+        let warm_up = Self::new(Duration::ZERO, u32::MAX);
+        warm_up.is_caught_up.store(true, Ordering::SeqCst);
+        warm_up.is_complete.store(true, Ordering::SeqCst);
+        warm_up
+    }
+
+    /// Returns the duration for which the primary must be connected to quorum stake.
+    pub const fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Returns the maximum number of blocks that the primary may be behind the network tip.
+    pub const fn max_blocks_behind(&self) -> u32 {
+        self.max_blocks_behind
+    }
+
+    /// Returns `true` if the primary has caught up on the recent DAG.
+    pub fn is_caught_up(&self) -> bool {
+        self.is_caught_up.load(Ordering::SeqCst)
+    }
+
+    /// Marks the primary as caught up on the recent DAG.
+    pub fn set_caught_up(&self) {
+        self.is_caught_up.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns `true` if the warm-up has completed.
+    pub fn is_complete(&self) -> bool {
+        self.is_complete.load(Ordering::SeqCst)
+    }
+
+    /// Updates the warm-up with the current connectivity and sync status of the primary,
+    /// and returns `true` if the warm-up has completed.
+    ///
+    /// Note: If the primary loses quorum connectivity before the warm-up completes, the warm-up restarts.
+    pub fn update(&self, is_quorum_connected: bool, num_blocks_behind: u32) -> bool {
+        // If the warm-up has completed, return early.
+        if self.is_complete() {
+            return true;
+        }
+        let mut quorum_connected_since = self.quorum_connected_since.lock();
+        // If the primary is not connected to quorum stake, restart the warm-up.
+        if !is_quorum_connected {
+            *quorum_connected_since = None;
+            return false;
+        }
+        // Ensure the primary has been connected to quorum stake for the warm-up duration.
+        if quorum_connected_since.get_or_insert_with(Instant::now).elapsed() < self.duration {
+            return false;
+        }
+        // Ensure the primary is not too far behind the network tip.
+        if num_blocks_behind > self.max_blocks_behind {
+            return false;
+        }
+        // Ensure the primary has caught up on the recent DAG.
+        if !self.is_caught_up() {
+            return false;
+        }
+        // Mark the warm-up as complete.
+        self.is_complete.store(true, Ordering::SeqCst);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_warm_up() {
+        // Ensure a disabled warm-up is complete.
+        assert!(WarmUp::disabled().update(false, u32::MAX));
+
+        // Ensure the warm-up requires quorum connectivity, and the node to be synced.
+        let warm_up = WarmUp::new(Duration::ZERO, 2);
+        assert!(!warm_up.update(false, 0));
+        assert!(!warm_up.update(true, 3));
+        // Ensure the warm-up requires the primary to catch up on the recent DAG.
+        assert!(!warm_up.update(true, 2));
+        warm_up.set_caught_up();
+        assert!(warm_up.update(true, 2));
+        // Ensure the warm-up remains complete.
+        assert!(warm_up.update(false, 10));
+
+        // Ensure the warm-up requires quorum connectivity for the full duration.
+        let warm_up = WarmUp::new(Duration::from_secs(60), 2);
+        assert!(!warm_up.update(true, 0));
+        assert!(warm_up.quorum_connected_since.lock().is_some());
+        assert!(!warm_up.update(false, 0));
+        assert!(warm_up.quorum_connected_since.lock().is_none());
+        assert!(!warm_up.is_complete());
+    }
+}
@@ -29,6 +29,9 @@ pub mod helpers;
 mod bft;
 pub use bft::*;
 
+mod config;
+pub use config::*;
+
 mod gateway;
 pub use gateway::*;
 
@@ -48,6 +51,10 @@ pub const MEMORY_POOL_PORT: u16 = 5000; // port
 
 /// The maximum number of milliseconds to wait before proposing a batch.
 pub const MAX_BATCH_DELAY_IN_MS: u64 = 2500; // ms
+/// The maximum number of bytes of transmissions allowed in a batch.
+pub const MAX_BATCH_SIZE_IN_BYTES: usize = 16 * 1024 * 1024; // bytes
+/// The maximum number of other validators to request a missing certificate from, if the referencing peer fails to.
+pub const MAX_CERTIFICATE_FETCH_FALLBACKS: usize = 3; // validators
 /// The maximum number of rounds to store before garbage collecting.
 pub const MAX_GC_ROUNDS: u64 = 50; // rounds
 /// The maximum number of seconds allowed for the leader to send their certificate.
@@ -56,15 +63,28 @@ pub const MAX_LEADER_CERTIFICATE_DELAY_IN_SECS: i64 = 2 * MAX_BATCH_DELAY_IN_MS
 pub const MAX_TIMESTAMP_DELTA_IN_SECS: i64 = 10; // seconds
 /// The maximum number of transmissions allowed in a batch.
 pub const MAX_TRANSMISSIONS_PER_BATCH: usize = 250; // transmissions
+/// The maximum number of transmissions a worker seals into a worker batch.
+pub const MAX_TRANSMISSIONS_PER_WORKER_BATCH: usize = MAX_TRANSMISSIONS_PER_BATCH / 10; // transmissions
 /// The maximum number of transmissions allowed in a worker ping.
 pub const MAX_TRANSMISSIONS_PER_WORKER_PING: usize = MAX_TRANSMISSIONS_PER_BATCH / 10; // transmissions
 /// The maximum number of workers that can be spawned.
-pub const MAX_WORKERS: u8 = 1; // workers
+pub const MAX_WORKERS: u8 = 4; // workers
+
+/// The block height from which the linked previous leader certificates are committed before the leader.
+/// Note: The linked leaders change the resulting blocks, so every validator must switch at the same height.
+pub const LINKED_LEADERS_HEIGHT: u32 = 250_000; // blocks
+
+/// The number of seconds that a primary must be connected to quorum stake at startup, before proposing a batch.
+pub const STARTUP_WARM_UP_IN_SECS: u64 = 10; // seconds
 
+/// The frequency at which a primary checks whether its ready transmissions fill a batch.
+pub const BATCH_POLL_IN_MS: u64 = 100; // ms
 /// The frequency at which each primary broadcasts a ping to every other node.
 pub const PRIMARY_PING_IN_MS: u64 = 4 * MAX_BATCH_DELAY_IN_MS; // ms
 /// The frequency at which each worker broadcasts a ping to every other node.
 pub const WORKER_PING_IN_MS: u64 = 4 * MAX_BATCH_DELAY_IN_MS; // ms
+/// The frequency at which each worker seals its ready transmissions into worker batches.
+pub const WORKER_BATCH_IN_MS: u64 = MAX_BATCH_DELAY_IN_MS / 5; // ms
 
 /// A helper macro to spawn a blocking task.
 #[macro_export]
@@ -13,34 +13,56 @@
 // limitations under the License.
 
 use crate::{
-    events::{BatchPropose, BatchSignature, Event},
+    events::{BatchPropose, BatchSignature, Equivocation, Event},
     helpers::{
         assign_to_worker,
         assign_to_workers,
+        check_batch_limits,
         fmt_id,
         init_sync_channels,
+        init_worker_batch_channels,
         init_worker_channels,
         now,
         BFTSender,
+        BatchPolicy,
+        CertificateVerifier,
+        ClockOffset,
+        ConsensusEvent,
+        DedupIndex,
+        Evidence,
+        EvidenceStore,
+        InclusionTracker,
+        InvalidCertificate,
+        Misbehavior,
+        MisbehaviorReport,
+        Participation,
         PrimaryReceiver,
         PrimarySender,
         Proposal,
+        ReplayGuard,
+        RoundTimer,
+        SignatureRequests,
+        SigningJournal,
         Storage,
+        WarmUp,
     },
+    check_num_workers,
     spawn_blocking,
     Gateway,
+    PrimaryConfig,
     Sync,
     Transport,
     Worker,
+    BATCH_POLL_IN_MS,
     MAX_BATCH_DELAY_IN_MS,
-    MAX_TRANSMISSIONS_PER_BATCH,
-    MAX_WORKERS,
+    MAX_CERTIFICATE_FETCH_FALLBACKS,
     PRIMARY_PING_IN_MS,
     WORKER_PING_IN_MS,
 };
 use snarkos_account::Account;
 use snarkos_node_bft_events::PrimaryPing;
-use snarkos_node_bft_ledger_service::LedgerService;
+use snarkos_node_bft_ledger_service::{is_storage_degraded, LedgerService};
+use snarkos_node_tcp::PeerDenyList;
 use snarkvm::{
     console::{
         account::Signature,
@@ -60,11 +82,11 @@ use futures::stream::{FuturesUnordered, StreamExt};
 use indexmap::{IndexMap, IndexSet};
 use parking_lot::{Mutex, RwLock};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{HashMap, HashSet, VecDeque},
     future::Future,
     net::SocketAddr,
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 use tokio::{
     sync::{Mutex as TMutex, OnceCell},
@@ -84,14 +106,42 @@ pub struct Primary<N: Network> {
     storage: Storage<N>,
     /// The ledger service.
     ledger: Arc<dyn LedgerService<N>>,
+    /// The number of workers spawned by the primary.
+    num_workers: u8,
     /// The workers.
     workers: Arc<[Worker<N>]>,
+    /// The digests of the sealed worker batches that are not yet proposed, with their worker IDs, in sealing order.
+    worker_batches: Arc<Mutex<VecDeque<(u8, Field<N>)>>>,
     /// The BFT sender.
     bft_sender: Arc<OnceCell<BFTSender<N>>>,
     /// The batch proposal, if the primary is currently proposing a batch.
     proposed_batch: Arc<ProposedBatch<N>>,
     /// The recently-signed batch proposals (a map from the address to the round, batch ID, and signature).
     signed_proposals: Arc<RwLock<HashMap<Address<N>, (u64, Field<N>, Signature<N>)>>>,
+    /// The estimator of the clock offset, from the timestamps of the batch proposals.
+    clock_offset: Arc<ClockOffset<N>>,
+    /// The startup warm-up, which must complete before the primary proposes a batch.
+    warm_up: Arc<WarmUp>,
+    /// The policy for sealing the batches proposed by the primary.
+    batch_policy: BatchPolicy,
+    /// The signing journal, which protects the primary from double-signing.
+    signing_journal: Arc<SigningJournal>,
+    /// The store of the evidence of equivocation by other validators.
+    evidence: Arc<EvidenceStore<N>>,
+    /// The timer for the current round, with a timeout that adapts to the recent round latencies.
+    round_timer: Arc<RoundTimer>,
+    /// The index of the transmissions in recent batches, used to avoid proposing duplicate transmissions.
+    dedup: Arc<DedupIndex<N>>,
+    /// The verifier of the certificates and signatures received from peers.
+    verifier: Arc<CertificateVerifier<N>>,
+    /// The participation of the validators in the recent rounds.
+    participation: Arc<Participation<N>>,
+    /// The tracker of the inclusion of the pending transactions, used to force the inclusion of overdue transactions.
+    inclusion: Arc<InclusionTracker<N>>,
+    /// The tracker of the signature requests for the batch proposals, with their retries and backpressure.
+    signature_requests: Arc<SignatureRequests<N>>,
+    /// The guard against replayed and out-of-bounds batch proposals, signatures, and certificates from peers.
+    replay_guard: Arc<ReplayGuard>,
     /// The spawned handles.
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
     /// The lock for propose_batch.
@@ -106,22 +156,43 @@ impl<N: Network> Primary<N> {
         ledger: Arc<dyn LedgerService<N>>,
         ip: Option<SocketAddr>,
         trusted_validators: &[SocketAddr],
+        peer_deny_list: Option<Arc<PeerDenyList>>,
+        config: PrimaryConfig,
         dev: Option<u16>,
     ) -> Result<Self> {
+        // Ensure the number of workers is valid.
+        check_num_workers(config.num_workers)?;
         // Initialize the gateway.
-        let gateway = Gateway::new(account, ledger.clone(), ip, trusted_validators, dev)?;
+        let gateway =
+            Gateway::new(account, ledger.clone(), ip, trusted_validators, peer_deny_list, config.misbehavior_log, dev)?;
         // Initialize the sync module.
         let sync = Sync::new(gateway.clone(), storage.clone(), ledger.clone());
+        // Load the signing journal.
+        let signing_journal = SigningJournal::load(config.signing_journal, config.signing_lease)?;
         // Initialize the primary instance.
         Ok(Self {
             sync,
             gateway,
             storage,
             ledger,
+            num_workers: config.num_workers,
             workers: Arc::from(vec![]),
+            worker_batches: Default::default(),
             bft_sender: Default::default(),
             proposed_batch: Default::default(),
             signed_proposals: Default::default(),
+            clock_offset: Arc::new(ClockOffset::new(config.clock_sync)),
+            warm_up: Arc::new(config.warm_up),
+            batch_policy: config.batch_policy,
+            signing_journal: Arc::new(signing_journal),
+            evidence: Default::default(),
+            round_timer: Default::default(),
+            dedup: Default::default(),
+            verifier: Default::default(),
+            participation: Default::default(),
+            inclusion: Default::default(),
+            signature_requests: Default::default(),
+            replay_guard: Default::default(),
             handles: Default::default(),
             propose_lock: Default::default(),
         })
@@ -146,20 +217,23 @@ impl<N: Network> Primary<N> {
         let mut worker_senders = IndexMap::new();
         // Construct a map for the workers.
         let mut workers = Vec::new();
+        // Construct the channels over which the workers send the digests of their sealed worker batches.
+        let (tx_worker_batch, mut rx_worker_batch) = init_worker_batch_channels();
         // Initialize the workers.
-        for id in 0..MAX_WORKERS {
+        for id in 0..self.num_workers {
             // Construct the worker channels.
             let (tx_worker, rx_worker) = init_worker_channels();
             // Construct the worker instance.
             let worker = Worker::new(
                 id,
+                self.num_workers,
                 Arc::new(self.gateway.clone()),
                 self.storage.clone(),
                 self.ledger.clone(),
                 self.proposed_batch.clone(),
             )?;
             // Run the worker instance.
-            worker.run(rx_worker);
+            worker.run(rx_worker, tx_worker_batch.clone());
             // Add the worker to the list of workers.
             workers.push(worker);
             // Add the worker sender to the map.
@@ -167,11 +241,20 @@ impl<N: Network> Primary<N> {
         }
         // Set the workers.
         self.workers = Arc::from(workers);
+        // Collect the digests of the worker batches, which the primary proposes in the order they were sealed.
+        let worker_batches = self.worker_batches.clone();
+        self.spawn(async move {
+            while let Some(worker_batch) = rx_worker_batch.recv().await {
+                worker_batches.lock().push_back(worker_batch);
+            }
+        });
 
         // First, initialize the sync channels.
         let (sync_sender, sync_receiver) = init_sync_channels();
         // Next, initialize the sync module.
         self.sync.run(bft_sender, sync_receiver).await?;
+        // Next, restore the batch proposal that was pending before a restart, if any.
+        self.restore_proposed_batch().await;
         // Next, initialize the gateway.
         self.gateway.run(primary_sender, worker_senders, Some(sync_sender)).await;
         // Lastly, start the primary handlers.
@@ -201,6 +284,11 @@ impl<N: Network> Primary<N> {
         &self.ledger
     }
 
+    /// Returns the sync module.
+    pub const fn sync(&self) -> &Sync<N> {
+        &self.sync
+    }
+
     /// Returns the number of workers.
     pub fn num_workers(&self) -> u8 {
         u8::try_from(self.workers.len()).expect("Too many workers")
@@ -215,6 +303,31 @@ impl<N: Network> Primary<N> {
     pub fn proposed_batch(&self) -> &Arc<ProposedBatch<N>> {
         &self.proposed_batch
     }
+
+    /// Returns the store of the evidence of equivocation.
+    pub const fn evidence(&self) -> &Arc<EvidenceStore<N>> {
+        &self.evidence
+    }
+
+    /// Returns the timer for the current round.
+    pub const fn round_timer(&self) -> &Arc<RoundTimer> {
+        &self.round_timer
+    }
+
+    /// Returns the index of the transmissions in recent batches.
+    pub const fn dedup(&self) -> &Arc<DedupIndex<N>> {
+        &self.dedup
+    }
+
+    /// Returns the participation of the validators in the recent rounds.
+    pub const fn participation(&self) -> &Arc<Participation<N>> {
+        &self.participation
+    }
+
+    /// Returns the tracker of the inclusion of the pending transactions.
+    pub const fn inclusion(&self) -> &Arc<InclusionTracker<N>> {
+        &self.inclusion
+    }
 }
 
 impl<N: Network> Primary<N> {
@@ -270,6 +383,12 @@ impl<N: Network> Primary<N> {
     /// 3. Set the batch proposal in the primary.
     /// 4. Broadcast the batch header to all validators for signing.
     pub async fn propose_batch(&self) -> Result<()> {
+        // Do not propose a batch while the storage is degraded, as the certificate could not be stored.
+        if is_storage_degraded() {
+            debug!("Skipping the batch proposal - the storage is degraded");
+            return Ok(());
+        }
+
         // This function isn't re-entrant.
         let mut lock_guard = self.propose_lock.lock().await;
 
@@ -287,6 +406,10 @@ impl<N: Network> Primary<N> {
             let event = Event::BatchPropose(proposal.batch_header().clone().into());
             // Iterate through the non-signers.
             for address in proposal.nonsigners(&self.ledger.get_previous_committee_for_round(proposal.round())?) {
+                // Ensure the backoff for the validator has elapsed.
+                if !self.signature_requests.should_resend(proposal.batch_id(), address) {
+                    continue;
+                }
                 // Resolve the address to the peer IP.
                 match self.gateway.resolver().get_peer_ip_for_address(address) {
                     // Resend the batch proposal to the validator for signing.
@@ -340,8 +463,12 @@ impl<N: Network> Primary<N> {
             let mut connected_validators = self.gateway.connected_addresses();
             // Append the primary to the set.
             connected_validators.insert(self.gateway.account().address());
+            // Determine if the quorum threshold is reached.
+            let is_quorum_reached = committee.is_quorum_threshold_reached(&connected_validators);
+            // Update the startup warm-up.
+            let is_warmed_up = self.warm_up.update(is_quorum_reached, self.sync.num_blocks_behind());
             // If quorum threshold is not reached, return early.
-            if !committee.is_quorum_threshold_reached(&connected_validators) {
+            if !is_quorum_reached {
                 debug!(
                     "Primary is safely skipping a batch proposal {}",
                     "(please connect to more validators)".dimmed()
@@ -349,6 +476,12 @@ impl<N: Network> Primary<N> {
                 trace!("Primary is connected to {} validators", connected_validators.len() - 1);
                 return Ok(());
             }
+            // If the startup warm-up has not completed, return early.
+            if !is_warmed_up {
+                debug!("Primary is safely skipping a batch proposal {}", "(warming up)".dimmed());
+                trace!("Primary is {} blocks behind its peers", self.sync.num_blocks_behind());
+                return Ok(());
+            }
         }
 
         // Compute the previous round.
@@ -381,47 +514,120 @@ impl<N: Network> Primary<N> {
             return Ok(());
         }
 
-        // Determined the required number of transmissions per worker.
-        let num_transmissions_per_worker = MAX_TRANSMISSIONS_PER_BATCH / self.num_workers() as usize;
+        // If too many of the prior batch proposals are uncertified, pause proposing, to apply backpressure.
+        if self.signature_requests.is_backpressured(round) {
+            debug!(
+                "Primary is safely skipping a batch proposal {}",
+                format!("({} prior batch proposals are uncertified)", self.signature_requests.num_outstanding())
+                    .dimmed()
+            );
+            return Ok(());
+        }
+
+        // Ensure the batch was not already proposed, and that the round may be signed, before taking
+        // any transmissions from the workers, so that they are not lost if the batch cannot be proposed.
+        ensure!(round > 0, "Round 0 cannot have transaction batches");
+        if *lock_guard == round {
+            warn!("Primary is safely skipping a batch proposal - round {round} already proposed");
+            return Ok(());
+        }
+        if let Err(e) = self.signing_journal.check(round) {
+            warn!("Primary is safely skipping a batch proposal - {e}");
+            return Ok(());
+        }
+
+        // Retrieve the batch sealing policy.
+        let policy = self.batch_policy;
         // Initialize the map of transmissions.
         let mut transmissions: IndexMap<_, _> = Default::default();
         // Initialize a tracker for the number of transactions.
         let mut num_transactions = 0;
-        // Take the transmissions from the workers.
-        for worker in self.workers.iter() {
-            for (id, transmission) in worker.drain(num_transmissions_per_worker) {
-                // Check if the ledger already contains the transmission.
-                if self.ledger.contains_transmission(&id).unwrap_or(true) {
-                    trace!("Proposing - Skipping transmission '{}' - Already in ledger", fmt_id(id));
+        // Initialize a tracker for the number of bytes of transmissions.
+        let mut num_bytes = 0;
+        // Update the inclusion tracker with the pending transactions.
+        let pending = self.workers.iter().flat_map(|worker| worker.transmission_ids()).filter_map(|id| match id {
+            TransmissionID::Transaction(transaction_id) => Some(transaction_id),
+            _ => None,
+        });
+        self.inclusion.observe(pending);
+        // Take the overdue transactions from the workers first, so that their inclusion is forced, from the oldest.
+        let mut candidates = Vec::new();
+        for transaction_id in self.inclusion.overdue() {
+            let id = TransmissionID::Transaction(transaction_id);
+            if let Some((worker, transmission)) =
+                self.workers.iter().find_map(|worker| worker.take(id).map(|transmission| (worker, transmission)))
+            {
+                candidates.push((worker, id, transmission));
+            }
+        }
+        // Take the sealed worker batches, in the order they were sealed, until they fill the batch.
+        while candidates.len() < policy.max_transmissions() {
+            let Some((worker_id, digest)) = self.worker_batches.lock().pop_front() else {
+                break;
+            };
+            if let Some(worker) = self.workers.get(worker_id as usize) {
+                candidates.extend(worker.take_batch(digest).map(|(id, transmission)| (worker, id, transmission)));
+            }
+        }
+        for (worker, id, transmission) in candidates {
+            // Check if the ledger already contains the transmission.
+            if self.ledger.contains_transmission(&id).unwrap_or(true) {
+                trace!("Proposing - Skipping transmission '{}' - Already in ledger", fmt_id(id));
+                continue;
+            }
+            // Check if a recently certified batch already contains the transmission.
+            if self.dedup.is_certified(&id) {
+                trace!("Proposing - Skipping transmission '{}' - Already certified", fmt_id(id));
+                continue;
+            }
+            // Check if a batch proposed by a peer already contains the transmission.
+            // Note: The transmission is reinserted into the worker, in case the peer's batch is not certified.
+            if self.dedup.is_proposed(&id) {
+                trace!("Proposing - Deferring transmission '{}' - Already proposed by a peer", fmt_id(id));
+                worker.reinsert(id, transmission);
+                continue;
+            }
+            // Retrieve the size of the transmission.
+            let transmission_size = match transmission.to_bytes_le() {
+                Ok(bytes) => bytes.len(),
+                Err(e) => {
+                    trace!("Proposing - Skipping transmission '{}' - {e}", fmt_id(id));
                     continue;
                 }
-                // Check the transmission is still valid.
-                match (id, transmission.clone()) {
-                    (TransmissionID::Solution(solution_id), Transmission::Solution(solution)) => {
-                        // Check if the solution is still valid.
-                        if let Err(e) = self.ledger.check_solution_basic(solution_id, solution).await {
-                            trace!("Proposing - Skipping solution '{}' - {e}", fmt_id(solution_id));
-                            continue;
-                        }
+            };
+            // If the transmission does not fit in the batch, reinsert it into the worker for the next batch.
+            if !policy.fits(transmissions.len(), num_bytes, transmission_size) {
+                worker.reinsert(id, transmission);
+                continue;
+            }
+            // Check the transmission is still valid.
+            match (id, transmission.clone()) {
+                (TransmissionID::Solution(solution_id), Transmission::Solution(solution)) => {
+                    // Check if the solution is still valid.
+                    if let Err(e) = self.ledger.check_solution_basic(solution_id, solution).await {
+                        trace!("Proposing - Skipping solution '{}' - {e}", fmt_id(solution_id));
+                        continue;
                     }
-                    (TransmissionID::Transaction(transaction_id), Transmission::Transaction(transaction)) => {
-                        // Check if the transaction is still valid.
-                        if let Err(e) = self.ledger.check_transaction_basic(transaction_id, transaction).await {
-                            trace!("Proposing - Skipping transaction '{}' - {e}", fmt_id(transaction_id));
-                            continue;
-                        }
-                        // Increment the number of transactions.
-                        num_transactions += 1;
+                }
+                (TransmissionID::Transaction(transaction_id), Transmission::Transaction(transaction)) => {
+                    // Check if the transaction is still valid.
+                    if let Err(e) = self.ledger.check_transaction_basic(transaction_id, transaction).await {
+                        trace!("Proposing - Skipping transaction '{}' - {e}", fmt_id(transaction_id));
+                        continue;
                     }
-                    // Note: We explicitly forbid including ratifications,
-                    // as the protocol currently does not support ratifications.
-                    (TransmissionID::Ratification, Transmission::Ratification) => continue,
-                    // All other combinations are clearly invalid.
-                    _ => continue,
+                    // Increment the number of transactions.
+                    num_transactions += 1;
                 }
-                // Insert the transmission into the map.
-                transmissions.insert(id, transmission);
+                // Note: We explicitly forbid including ratifications,
+                // as the protocol currently does not support ratifications.
+                (TransmissionID::Ratification, Transmission::Ratification) => continue,
+                // All other combinations are clearly invalid.
+                _ => continue,
             }
+            // Insert the transmission into the map.
+            transmissions.insert(id, transmission);
+            // Increment the number of bytes.
+            num_bytes += transmission_size;
         }
         // If there are no unconfirmed transmissions to propose, return early.
         if transmissions.is_empty() {
@@ -433,17 +639,29 @@ impl<N: Network> Primary<N> {
             debug!("Primary is safely skipping a batch proposal {}", "(no unconfirmed transactions)".dimmed());
             return Ok(());
         }
-        // Ditto if the batch had already been proposed.
-        ensure!(round > 0, "Round 0 cannot have transaction batches");
-        if *lock_guard == round {
-            warn!("Primary is safely skipping a batch proposal - round {round} already proposed");
+
+        // Record the round in the signing journal.
+        // Note: The transmissions are reinserted into the workers if the round can no longer be signed
+        // (e.g. the signing lease expired while the batch was assembled).
+        if let Err(e) = self.signing_journal.record(round).await {
+            warn!("Primary is safely skipping a batch proposal - {e}");
+            assign_to_workers(&self.workers, transmissions.into_iter(), |worker, transmission_id, transmission| {
+                worker.reinsert(transmission_id, transmission);
+            })?;
             return Ok(());
         }
 
         *lock_guard = round;
 
+        // Record the included transactions, and count the omission of the others if the batch has room.
+        let included = transmissions.keys().filter_map(|id| match id {
+            TransmissionID::Transaction(transaction_id) => Some(*transaction_id),
+            _ => None,
+        });
+        self.inclusion.record_local_batch(&included.collect(), !policy.is_full(transmissions.len()));
+
         /* Proceeding to sign & propose the batch. */
-        info!("Proposing a batch with {} transmissions for round {round}...", transmissions.len());
+        info!("Proposing a batch with {} transmissions ({num_bytes} bytes) for round {round}...", transmissions.len());
 
         // Retrieve the private key.
         let private_key = *self.gateway.account().private_key();
@@ -469,6 +687,16 @@ impl<N: Network> Primary<N> {
         // Construct the proposal.
         let proposal =
             Proposal::new(self.ledger.get_previous_committee_for_round(round)?, batch_header.clone(), transmissions)?;
+        // Persist the proposal, so that the same batch is proposed after a restart.
+        self.storage.insert_proposal(&proposal);
+        // Emit the proposed batch to the consensus event subscribers.
+        self.gateway.consensus_events().emit(ConsensusEvent::BatchProposed {
+            round,
+            batch_id: batch_header.batch_id(),
+            num_transmissions: batch_header.transmission_ids().len(),
+        });
+        // Track the signature requests for the batch.
+        self.signature_requests.start(round, batch_header.batch_id());
         // Broadcast the batch to all validators for signing.
         self.gateway.broadcast(Event::BatchPropose(batch_header.into()));
         // Set the proposed batch.
@@ -476,6 +704,52 @@ impl<N: Network> Primary<N> {
         Ok(())
     }
 
+    /// Waits until the ready transmissions fill a batch, or until the given delay has elapsed.
+    async fn wait_for_batch(&self, delay: Duration) {
+        // Retrieve the batch sealing policy.
+        let policy = self.batch_policy;
+        // Compute the deadline.
+        let deadline = Instant::now() + delay;
+        loop {
+            // Sleep briefly, but no longer than until the deadline.
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            tokio::time::sleep(remaining.min(Duration::from_millis(BATCH_POLL_IN_MS))).await;
+            // If the deadline has passed, the batch is sealed by the timer.
+            if Instant::now() >= deadline {
+                return;
+            }
+            // If there is no proposed batch, and the sealed worker batches fill a batch, the batch is sealed early.
+            let num_sealed_transmissions = self.workers.iter().map(Worker::num_sealed_transmissions).sum();
+            if self.proposed_batch.read().is_none() && policy.is_full(num_sealed_transmissions) {
+                return;
+            }
+        }
+    }
+
+    /// Ensures the transmissions of the given batch header are within the protocol limits.
+    fn check_batch_size(
+        &self,
+        batch_header: &BatchHeader<N>,
+        transmissions: &HashMap<TransmissionID<N>, Transmission<N>>,
+    ) -> Result<()> {
+        // Initialize a tracker for the number of bytes of transmissions.
+        let mut num_bytes = 0usize;
+        for transmission_id in batch_header.transmission_ids() {
+            // Retrieve the transmission.
+            // Note: Missing transmissions are rejected when checking the batch header.
+            let transmission = match transmissions.get(transmission_id) {
+                Some(transmission) => transmission.clone(),
+                None => match self.storage.get_transmission(*transmission_id) {
+                    Some(transmission) => transmission,
+                    None => continue,
+                },
+            };
+            // Increment the number of bytes.
+            num_bytes = num_bytes.saturating_add(transmission.to_bytes_le()?.len());
+        }
+        check_batch_limits(batch_header.transmission_ids().len(), num_bytes)
+    }
+
     /// Processes a batch propose from a peer.
     ///
     /// This method performs the following steps:
@@ -488,6 +762,11 @@ impl<N: Network> Primary<N> {
     async fn process_batch_propose_from_peer(&self, peer_ip: SocketAddr, batch_propose: BatchPropose<N>) -> Result<()> {
         let BatchPropose { round: batch_round, batch_header } = batch_propose;
 
+        // Do not sign a batch while the storage is degraded, as the certificate could not be stored.
+        if is_storage_degraded() {
+            bail!("Cannot sign the batch proposal from '{peer_ip}' - the storage is degraded");
+        }
+
         // Deserialize the batch header.
         let batch_header = spawn_blocking!(batch_header.deserialize_blocking())?;
         // Ensure the round matches in the batch header.
@@ -505,6 +784,8 @@ impl<N: Network> Primary<N> {
             // If the peer is a validator, then ensure the batch proposal is from the validator.
             Some(address) => {
                 if address != batch_author {
+                    let details = format!("Proposed a batch signed by a different validator ({batch_author})");
+                    self.report_misbehavior(peer_ip, Misbehavior::SignatureMismatch, batch_round, details);
                     // Proceed to disconnect the validator.
                     self.gateway.disconnect(peer_ip);
                     bail!("Malicious peer - proposed batch from a different validator ({batch_author})");
@@ -522,6 +803,14 @@ impl<N: Network> Primary<N> {
         if self.gateway.account().address() == batch_author {
             bail!("Invalid peer - proposed batch from myself ({batch_author})");
         }
+        // Ensure the batch does not exceed the maximum number of transmissions, before fetching them.
+        if let Err(e) = check_batch_limits(batch_header.transmission_ids().len(), 0) {
+            // Proceed to disconnect the validator.
+            self.gateway.disconnect(peer_ip);
+            bail!("Malicious peer - proposed an oversized batch - {e}");
+        }
+        // Check the batch header for equivocation by the batch author.
+        self.check_for_equivocation(&batch_header);
 
         // Retrieve the cached round and batch ID for this validator.
         if let Some((signed_round, signed_batch_id, signature)) =
@@ -536,22 +825,24 @@ impl<N: Network> Primary<N> {
             // If the round and batch ID matches, then skip signing the batch a second time.
             // Instead, rebroadcast the cached signature to the peer.
             if signed_round == batch_header.round() && signed_batch_id == batch_header.batch_id() {
-                let gateway = self.gateway.clone();
-                tokio::spawn(async move {
-                    debug!("Resending a signature for a batch in round {batch_round} from '{peer_ip}'");
-                    let event = Event::BatchSignature(BatchSignature::new(batch_header.batch_id(), signature));
-                    // Resend the batch signature to the peer.
-                    if gateway.send(peer_ip, event).await.is_none() {
-                        warn!("Failed to resend a signature for a batch in round {batch_round} to '{peer_ip}'");
-                    }
-                });
+                self.resend_signature(peer_ip, batch_round, signed_batch_id, signature);
                 // Return early.
                 return Ok(());
             }
         }
 
+        // Record the timestamp of the batch proposal, to estimate the offset of the system clock.
+        // Note: This must be called prior to checking the timestamp of the batch header (below).
+        self.clock_offset.insert_timestamp(batch_author, batch_header.timestamp());
+
         // If the peer is ahead, use the batch header to sync up to the peer.
         let transmissions = self.sync_with_batch_header_from_peer(peer_ip, &batch_header).await?;
+        // Ensure the batch does not exceed the maximum batch size.
+        if let Err(e) = self.check_batch_size(&batch_header, &transmissions) {
+            // Proceed to disconnect the validator.
+            self.gateway.disconnect(peer_ip);
+            bail!("Malicious peer - proposed an oversized batch - {e}");
+        }
 
         // Ensure the batch is for the current round.
         // This method must be called after fetching previous certificates (above),
@@ -562,9 +853,13 @@ impl<N: Network> Primary<N> {
         let missing_transmissions = self.storage.check_batch_header(&batch_header, transmissions)?;
         // Inserts the missing transmissions into the workers.
         self.insert_missing_transmissions_into_workers(peer_ip, missing_transmissions.into_iter())?;
+        // Record the transmissions of the proposed batch, so they are not proposed again by this primary.
+        self.dedup.insert_proposed(batch_round, batch_header.transmission_ids());
 
         /* Proceeding to sign the batch. */
 
+        // Ensure the round may be signed, and record it in the signing journal.
+        self.signing_journal.record(batch_round).await?;
         // Retrieve the batch ID.
         let batch_id = batch_header.batch_id();
         // Sign the batch ID.
@@ -595,6 +890,15 @@ impl<N: Network> Primary<N> {
             }
         };
 
+        // Check if the batch mostly duplicates transmissions that were certified before the previous round.
+        let previous_round = batch_round.saturating_sub(1);
+        let num_transmissions = batch_header.transmission_ids().len();
+        let num_duplicates = self.dedup.num_certified_before(batch_header.transmission_ids(), previous_round);
+        if num_duplicates > 0 && 2 * num_duplicates > num_transmissions {
+            let details = format!("Proposed a batch with {num_duplicates}/{num_transmissions} duplicate transmissions");
+            self.report_misbehavior(peer_ip, Misbehavior::DuplicateTransmissions, batch_round, details);
+        }
+
         // Broadcast the signature back to the validator.
         let self_ = self.clone();
         tokio::spawn(async move {
@@ -607,6 +911,51 @@ impl<N: Network> Primary<N> {
         Ok(())
     }
 
+    /// Resends the cached signature for the batch proposal of the given round to the peer, if it was signed.
+    fn resend_cached_signature(&self, peer_ip: SocketAddr, batch_round: u64) {
+        // Retrieve the address of the validator.
+        let Some(address) = self.gateway.resolver().get_address(peer_ip) else {
+            return;
+        };
+        // Retrieve the cached round, batch ID, and signature for the validator.
+        if let Some((signed_round, batch_id, signature)) = self.signed_proposals.read().get(&address).copied() {
+            // If the round matches, then resend the cached signature to the peer.
+            if signed_round == batch_round {
+                self.resend_signature(peer_ip, batch_round, batch_id, signature);
+            }
+        }
+    }
+
+    /// Resends the given signature for the batch proposal of the given round to the peer.
+    fn resend_signature(&self, peer_ip: SocketAddr, batch_round: u64, batch_id: Field<N>, signature: Signature<N>) {
+        let gateway = self.gateway.clone();
+        tokio::spawn(async move {
+            debug!("Resending a signature for a batch in round {batch_round} from '{peer_ip}'");
+            let event = Event::BatchSignature(BatchSignature::new(batch_id, signature));
+            // Resend the batch signature to the peer.
+            if gateway.send(peer_ip, event).await.is_none() {
+                warn!("Failed to resend a signature for a batch in round {batch_round} to '{peer_ip}'");
+            }
+        });
+    }
+
+    /// Ensures the given message from a peer is for a round within bounds, and is not a replay,
+    /// returning the digest of the message, or `None` if the message was already seen.
+    ///
+    /// Note: This method must be called prior to deserializing (and verifying) the message.
+    fn check_for_replay<T: FromBytes + ToBytes + Send + 'static>(
+        &self,
+        tag: &str,
+        round: u64,
+        data: &Data<T>,
+    ) -> Result<Option<[u8; 32]>> {
+        // Ensure the round is within bounds.
+        self.replay_guard.check_round(round, self.current_round(), self.storage.gc_round())?;
+        // Record the digest of the message, and return it if the message was not already seen.
+        let digest = ReplayGuard::digest_data(tag, data)?;
+        Ok(self.replay_guard.insert(round, digest).then_some(digest))
+    }
+
     /// Processes a batch signature from a peer.
     ///
     /// This method performs the following steps:
@@ -626,11 +975,22 @@ impl<N: Network> Primary<N> {
         // Retrieve the signature and timestamp.
         let BatchSignature { batch_id, signature } = batch_signature;
 
-        // Retrieve the signer.
-        let signer = spawn_blocking!(Ok(signature.to_address()))?;
+        // Ensure the batch signature is not a replay, before verifying it.
+        let round = self.proposed_batch.read().as_ref().map(|proposal| proposal.round());
+        let round = round.unwrap_or_else(|| self.current_round());
+        let bytes = [batch_id.to_bytes_le()?, signature.to_bytes_le()?].concat();
+        let digest = ReplayGuard::digest("BatchSignature", &bytes);
+        if self.replay_guard.contains(round, &digest) {
+            return Ok(());
+        }
+
+        // Verify the signature on the blocking thread pool, and retrieve the signer.
+        let signer = CertificateVerifier::verify_batch_signature(signature, batch_id).await?;
 
         // Ensure the batch signature is signed by the validator.
         if self.gateway.resolver().get_address(peer_ip).map_or(true, |address| address != signer) {
+            let details = format!("Sent a batch signature signed by a different validator ({signer})");
+            self.report_misbehavior(peer_ip, Misbehavior::SignatureMismatch, self.current_round(), details);
             // Proceed to disconnect the validator.
             self.gateway.disconnect(peer_ip);
             bail!("Malicious peer - batch signature is from a different validator ({signer})");
@@ -639,6 +999,10 @@ impl<N: Network> Primary<N> {
         if self.gateway.account().address() == signer {
             bail!("Invalid peer - received a batch signature from myself ({signer})");
         }
+        // Record the batch signature, now that it is verified to be from the validator.
+        // Note: The signature is not recorded before it is verified, as a copy of the signature
+        // relayed by another peer would otherwise cause the signature from the validator to be dropped.
+        self.replay_guard.insert(round, digest);
 
         let proposal = {
             // Acquire the write lock.
@@ -659,12 +1023,13 @@ impl<N: Network> Primary<N> {
                     }
                     // Retrieve the previous committee for the round.
                     let previous_committee = self.ledger.get_previous_committee_for_round(proposal.round())?;
-                    // Retrieve the address of the validator.
-                    let Some(signer) = self.gateway.resolver().get_address(peer_ip) else {
+                    // Ensure the validator is still connected.
+                    if self.gateway.resolver().get_address(peer_ip) != Some(signer) {
                         bail!("Signature is from a disconnected validator");
-                    };
+                    }
                     // Add the signature to the batch.
-                    proposal.add_signature(signer, signature, &previous_committee)?;
+                    // Note: The signature was verified against the batch ID of the proposal (above).
+                    proposal.add_verified_signature(signer, signature, &previous_committee)?;
                     info!("Received a batch signature for round {} from '{peer_ip}'", proposal.round());
                     // Check if the batch is ready to be certified.
                     if !proposal.is_quorum_threshold_reached(&previous_committee) {
@@ -698,6 +1063,108 @@ impl<N: Network> Primary<N> {
         Ok(())
     }
 
+    /// Checks the given (signed) batch header for equivocation by its author,
+    /// and if the author equivocated, records the evidence and gossips it to the validators.
+    fn check_for_equivocation(&self, batch_header: &BatchHeader<N>) {
+        if let Some(evidence) = self.evidence.observe(batch_header) {
+            let (author, round) = (evidence.author(), evidence.round());
+            warn!("Validator '{author}' equivocated in round {round} - recorded the evidence");
+            let details = "Signed two different batches for the same round".to_string();
+            let report = MisbehaviorReport::new(author, None, Misbehavior::Equivocation, round, details);
+            self.gateway.report_misbehavior(report);
+            // Gossip the evidence to the validators.
+            self.broadcast_evidence(evidence);
+        }
+    }
+
+    /// Processes the evidence of equivocation from a peer.
+    ///
+    /// This method performs the following steps:
+    /// 1. Ensures the round of the evidence is within the GC window.
+    /// 2. Ensures the author of the evidence is a member of the committee for the round.
+    /// 3. Ensures the evidence consists of two conflicting batch headers signed by the same author.
+    /// 4. Records the evidence, and if it is new, gossips it to the validators.
+    async fn process_equivocation_from_peer(&self, equivocation: Equivocation<N>) -> Result<()> {
+        let Equivocation { first, second } = equivocation;
+        // Deserialize the batch headers.
+        let first = spawn_blocking!(first.deserialize_blocking())?;
+        let second = spawn_blocking!(second.deserialize_blocking())?;
+        // Ensure the round is within the GC window.
+        let (author, round) = (first.author(), first.round());
+        let (gc_round, current_round) = (self.storage.gc_round(), self.current_round());
+        if round <= gc_round || round > current_round.saturating_add(self.storage.max_gc_rounds()) {
+            bail!("The evidence for round {round} is outside the GC window (GC round {gc_round}, at {current_round})")
+        }
+        // Ensure the author is a member of the committee for the round.
+        let committee = self.ledger.get_previous_committee_for_round(round)?;
+        if !committee.is_committee_member(author) {
+            bail!("The evidence for round {round} is from '{author}', who is not in the committee")
+        }
+        // Ensure the batch headers are conflicting, and signed by the same author.
+        let evidence = spawn_blocking!(Evidence::new(first, second))?;
+        // Record the evidence, and if it is new, gossip it to the validators.
+        if self.evidence.insert(evidence.clone()) {
+            let (author, round) = (evidence.author(), evidence.round());
+            warn!("Validator '{author}' equivocated in round {round} - received the evidence");
+            let details = "Signed two different batches for the same round (gossiped)".to_string();
+            let report = MisbehaviorReport::new(author, None, Misbehavior::Equivocation, round, details);
+            self.gateway.report_misbehavior(report);
+            self.broadcast_evidence(evidence);
+        }
+        Ok(())
+    }
+
+    /// Reports the given misbehavior by the validator at the given peer IP, if its address is known.
+    fn report_misbehavior(&self, peer_ip: SocketAddr, kind: Misbehavior, round: u64, details: String) {
+        if let Some(address) = self.gateway.resolver().get_address(peer_ip) {
+            self.gateway.report_misbehavior(MisbehaviorReport::new(address, Some(peer_ip), kind, round, details));
+        }
+    }
+
+    /// Records the participation of the author of the given stored certificate, and either the certification
+    /// of the primary's batch proposal or the author's included transactions, and emits the certificate
+    /// to the consensus event subscribers.
+    fn emit_certificate_formed(&self, certificate: &BatchCertificate<N>) {
+        // Record the participation of the author in the round.
+        self.participation.insert(certificate.round(), certificate.author());
+        // If the certificate is for the batch proposal of this primary, it is no longer outstanding.
+        if certificate.author() == self.gateway.account().address() {
+            self.signature_requests.certified(certificate.round());
+        }
+        // Otherwise, record the transactions included by the author, to track the omission of overdue transactions.
+        else {
+            let transmission_ids = certificate.transmission_ids();
+            let included = transmission_ids.iter().filter_map(|id| match id {
+                TransmissionID::Transaction(transaction_id) => Some(*transaction_id),
+                _ => None,
+            });
+            let has_room = !self.batch_policy.is_full(transmission_ids.len());
+            self.inclusion.record_peer_batch(certificate.author(), &included.collect(), has_room);
+        }
+
+        #[cfg(feature = "metrics")]
+        match certificate.author() == self.gateway.account().address() {
+            true => metrics::increment_counter(metrics::bft::CERTIFICATES_PRODUCED),
+            false => metrics::increment_counter_with_label(
+                metrics::bft::CERTIFICATES_RECEIVED,
+                "validator",
+                certificate.author().to_string(),
+            ),
+        }
+
+        self.gateway.consensus_events().emit(ConsensusEvent::CertificateFormed {
+            round: certificate.round(),
+            certificate_id: certificate.id(),
+            author: certificate.author(),
+        });
+    }
+
+    /// Broadcasts the given evidence of equivocation to the validators.
+    fn broadcast_evidence(&self, evidence: Evidence<N>) {
+        let Evidence { first, second } = evidence;
+        self.gateway.broadcast(Event::Equivocation(Equivocation::new(Data::Object(first), Data::Object(second))));
+    }
+
     /// Processes a batch certificate from a peer.
     ///
     /// This method performs the following steps:
@@ -722,6 +1189,8 @@ impl<N: Network> Primary<N> {
             // If the peer is a validator, then ensure the batch certificate is from the validator.
             Some(address) => {
                 if address != author {
+                    let details = format!("Sent a batch certificate from a different validator ({author})");
+                    self.report_misbehavior(peer_ip, Misbehavior::SignatureMismatch, certificate.round(), details);
                     // Proceed to disconnect the validator.
                     self.gateway.disconnect(peer_ip);
                     bail!("Malicious peer - batch certificate from a different validator ({author})");
@@ -739,6 +1208,8 @@ impl<N: Network> Primary<N> {
         if self.gateway.account().address() == author {
             bail!("Received a batch certificate for myself ({author})");
         }
+        // Check the batch header for equivocation by the batch author.
+        self.check_for_equivocation(certificate.batch_header());
 
         // Store the certificate, after ensuring it is valid.
         self.sync_with_certificate_from_peer(peer_ip, certificate).await?;
@@ -810,6 +1281,7 @@ impl<N: Network> Primary<N> {
             mut rx_batch_signature,
             mut rx_batch_certified,
             mut rx_primary_ping,
+            mut rx_equivocation,
             mut rx_unconfirmed_solution,
             mut rx_unconfirmed_transaction,
         } = primary_receiver;
@@ -907,8 +1379,8 @@ impl<N: Network> Primary<N> {
                 {
                     let self_ = self_.clone();
                     tokio::spawn(async move {
-                        // Deserialize the primary certificate in the primary ping.
-                        let Ok(primary_certificate) = spawn_blocking!(primary_certificate.deserialize_blocking())
+                        // Deserialize and verify the primary certificate in the primary ping.
+                        let Ok(primary_certificate) = self_.verifier.verify_certificate(primary_certificate).await
                         else {
                             warn!("Failed to deserialize primary certificate in 'PrimaryPing' from '{peer_ip}'");
                             return;
@@ -920,19 +1392,23 @@ impl<N: Network> Primary<N> {
                     });
                 }
 
-                // Iterate through the batch certificates.
-                for (certificate_id, certificate) in batch_certificates {
-                    // Ensure storage does not already contain the certificate.
-                    if self_.storage.contains_certificate(certificate_id) {
-                        continue;
-                    }
-                    // Spawn a task to process the batch certificate.
-                    let self_ = self_.clone();
-                    tokio::spawn(async move {
-                        // Deserialize the batch certificate in the primary ping.
-                        let Ok(batch_certificate) = spawn_blocking!(certificate.deserialize_blocking()) else {
+                // Retrieve the batch certificates that are not in storage.
+                let (certificate_ids, certificates): (Vec<_>, Vec<_>) = batch_certificates
+                    .into_iter()
+                    .filter(|(certificate_id, _)| !self_.storage.contains_certificate(*certificate_id))
+                    .unzip();
+                if certificates.is_empty() {
+                    continue;
+                }
+                // Spawn a task to process the batch certificates.
+                let self_ = self_.clone();
+                tokio::spawn(async move {
+                    // Deserialize and verify the batch certificates in the primary ping, in parallel.
+                    let results = self_.verifier.verify_certificates(certificates).await;
+                    for (certificate_id, result) in certificate_ids.into_iter().zip(results) {
+                        let Ok(batch_certificate) = result else {
                             warn!("Failed to deserialize batch certificate in a 'PrimaryPing' from '{peer_ip}'");
-                            return;
+                            continue;
                         };
                         // Ensure the batch certificate ID matches.
                         if batch_certificate.id() != certificate_id {
@@ -941,12 +1417,17 @@ impl<N: Network> Primary<N> {
                             self_.gateway.disconnect(peer_ip);
                             return;
                         }
-                        // Process the batch certificate.
-                        if let Err(e) = self_.process_batch_certificate_from_ping(peer_ip, batch_certificate).await {
-                            warn!("Cannot process a batch certificate in a 'PrimaryPing' from '{peer_ip}' - {e}");
-                        }
-                    });
-                }
+                        // Spawn a task to process the batch certificate.
+                        let self_ = self_.clone();
+                        tokio::spawn(async move {
+                            // Process the batch certificate.
+                            let result = self_.process_batch_certificate_from_ping(peer_ip, batch_certificate).await;
+                            if let Err(e) = result {
+                                warn!("Cannot process a batch certificate in a 'PrimaryPing' from '{peer_ip}' - {e}");
+                            }
+                        });
+                    }
+                });
             }
         });
 
@@ -969,17 +1450,46 @@ impl<N: Network> Primary<N> {
             });
         }
 
+        // Catch up on the recent DAG, which is required to complete the warm-up.
+        let self_ = self.clone();
+        self.spawn(async move {
+            while !self_.warm_up.is_caught_up() {
+                // Sleep briefly.
+                tokio::time::sleep(Duration::from_millis(MAX_BATCH_DELAY_IN_MS)).await;
+                // If the primary is not synced, then do not catch up yet.
+                if !self_.sync.is_synced() {
+                    trace!("Skipping catch-up {}", "(node is syncing)".dimmed());
+                    continue;
+                }
+                // Catch up on the recent DAG from the connected validators.
+                match self_.catch_up().await {
+                    Ok(()) => self_.warm_up.set_caught_up(),
+                    Err(e) => debug!("Primary has not caught up on the recent DAG yet - {e}"),
+                }
+            }
+        });
+
         // Start the batch proposer.
         let self_ = self.clone();
         self.spawn(async move {
             loop {
-                // Sleep briefly, but longer than if there were no batch.
-                tokio::time::sleep(Duration::from_millis(MAX_BATCH_DELAY_IN_MS)).await;
+                // Retrieve the maximum batch delay.
+                let max_delay = self_.batch_policy.max_delay();
+                // Wait for the batch to be sealed, but no longer than until the current round times out.
+                let delay = match self_.round_timer.remaining() {
+                    Duration::ZERO => max_delay,
+                    remaining => remaining.min(max_delay),
+                };
+                self_.wait_for_batch(delay).await;
                 // If the primary is not synced, then do not propose a batch.
                 if !self_.sync.is_synced() {
                     debug!("Skipping batch proposal {}", "(node is syncing)".dimmed());
                     continue;
                 }
+                // If the current round timed out, attempt to advance to the next round.
+                if let Err(e) = self_.check_round_timeout().await {
+                    warn!("Cannot advance from a timed out round - {e}");
+                }
                 // If there is no proposed batch, attempt to propose a batch.
                 // Note: Do NOT spawn a task around this function call. Proposing a batch is a critical path,
                 // and only one batch needs be proposed at a time.
@@ -998,11 +1508,27 @@ impl<N: Network> Primary<N> {
                     trace!("Skipping a batch proposal from '{peer_ip}' {}", "(node is syncing)".dimmed());
                     continue;
                 }
+                // Ensure the batch proposal is within bounds and is not a replay, before deserializing it.
+                let batch_round = batch_propose.round;
+                let digest = match self_.check_for_replay("BatchPropose", batch_round, &batch_propose.batch_header) {
+                    Ok(Some(digest)) => digest,
+                    // If the batch proposal is a replay, resend the cached signature to the peer, if it was signed.
+                    Ok(None) => {
+                        self_.resend_cached_signature(peer_ip, batch_round);
+                        continue;
+                    }
+                    Err(e) => {
+                        debug!("Dropping a batch proposal from '{peer_ip}' - {e}");
+                        continue;
+                    }
+                };
                 // Spawn a task to process the proposed batch.
                 let self_ = self_.clone();
                 tokio::spawn(async move {
                     // Process the batch proposal.
                     if let Err(e) = self_.process_batch_propose_from_peer(peer_ip, batch_propose).await {
+                        // Allow the batch proposal to be processed again, if the peer resends it.
+                        self_.replay_guard.remove(batch_round, &digest);
                         warn!("Cannot sign a batch from '{peer_ip}' - {e}");
                     }
                 });
@@ -1038,22 +1564,52 @@ impl<N: Network> Primary<N> {
                     trace!("Skipping a certified batch from '{peer_ip}' {}", "(node is syncing)".dimmed());
                     continue;
                 }
+                // Ensure the batch certificate is within bounds and is not a replay, before deserializing it.
+                let Some(batch_round) = ReplayGuard::certificate_round(&batch_certificate) else {
+                    debug!("Dropping a malformed batch certificate from '{peer_ip}'");
+                    continue;
+                };
+                let digest = match self_.check_for_replay("BatchCertified", batch_round, &batch_certificate) {
+                    Ok(Some(digest)) => digest,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        debug!("Dropping a batch certificate from '{peer_ip}' - {e}");
+                        continue;
+                    }
+                };
                 // Spawn a task to process the batch certificate.
                 let self_ = self_.clone();
                 tokio::spawn(async move {
-                    // Deserialize the batch certificate.
-                    let Ok(batch_certificate) = spawn_blocking!(batch_certificate.deserialize_blocking()) else {
+                    // Deserialize and verify the batch certificate.
+                    let Ok(batch_certificate) = self_.verifier.verify_certificate(batch_certificate).await else {
                         warn!("Failed to deserialize the batch certificate from '{peer_ip}'");
                         return;
                     };
                     // Process the batch certificate.
                     if let Err(e) = self_.process_batch_certificate_from_peer(peer_ip, batch_certificate).await {
+                        // Allow the batch certificate to be processed again, if the peer resends it.
+                        self_.replay_guard.remove(batch_round, &digest);
                         warn!("Cannot store a certificate from '{peer_ip}' - {e}");
                     }
                 });
             }
         });
 
+        // Process the evidence of equivocation.
+        let self_ = self.clone();
+        self.spawn(async move {
+            while let Some((peer_ip, equivocation)) = rx_equivocation.recv().await {
+                // Spawn a task to process the evidence.
+                let self_ = self_.clone();
+                tokio::spawn(async move {
+                    // Process the evidence of equivocation.
+                    if let Err(e) = self_.process_equivocation_from_peer(equivocation).await {
+                        warn!("Cannot record the evidence of equivocation from '{peer_ip}' - {e}");
+                    }
+                });
+            }
+        });
+
         // Process the unconfirmed solutions.
         let self_ = self.clone();
         self.spawn(async move {
@@ -1098,6 +1654,45 @@ impl<N: Network> Primary<N> {
         });
     }
 
+    /// Restores the persisted batch proposal, if it is still pending in the current round.
+    ///
+    /// This ensures a restarted primary rebroadcasts the batch it already proposed, instead of proposing
+    /// a conflicting batch for the same round.
+    async fn restore_proposed_batch(&self) {
+        // Retrieve the persisted batch proposal.
+        let Some((batch_header, transmissions)) = self.storage.get_proposal() else {
+            return;
+        };
+        // Retrieve the round.
+        let round = batch_header.round();
+        // Ensure the proposal is from this primary, is for the current round, and has not been certified.
+        let author = self.gateway.account().address();
+        if batch_header.author() != author
+            || round != self.current_round()
+            || self.storage.contains_certificate_in_round_from(round, author)
+        {
+            self.storage.remove_proposal();
+            return;
+        }
+        // Reconstruct the proposal.
+        let proposal = self
+            .ledger
+            .get_previous_committee_for_round(round)
+            .and_then(|committee| Proposal::new(committee, batch_header, transmissions));
+        match proposal {
+            Ok(proposal) => {
+                // Ensure the round is not proposed again.
+                *self.propose_lock.lock().await = round;
+                // Track the signature requests for the batch.
+                self.signature_requests.start(round, proposal.batch_id());
+                // Set the proposed batch.
+                *self.proposed_batch.write() = Some(proposal);
+                info!("Restored the batch proposal for round {round}");
+            }
+            Err(e) => warn!("Failed to restore the batch proposal for round {round} - {e}"),
+        }
+    }
+
     /// Checks if the proposed batch is expired, and clears the proposed batch if it has expired.
     async fn check_proposed_batch_for_expiration(&self) -> Result<()> {
         // Check if the proposed batch is timed out or stale.
@@ -1116,6 +1711,124 @@ impl<N: Network> Primary<N> {
         Ok(())
     }
 
+    /// Rolls back the primary to the given round, and returns the number of certificates discarded.
+    ///
+    /// This rolls back storage, and clears the proposed batch if it is beyond the given round, reinserting its
+    /// transmissions into the workers. The primary never proposes again in a round it already proposed in,
+    /// so a rollback does not cause it to equivocate.
+    pub fn rollback_to_round(&self, round: u64) -> Result<usize> {
+        // Roll back storage.
+        let num_discarded = self.storage.rollback_to_round(round)?;
+        // Clear the proposed batch, if it is beyond the given round.
+        let proposal = {
+            let mut proposed_batch = self.proposed_batch.write();
+            match proposed_batch.as_ref().map_or(false, |proposal| proposal.round() > round) {
+                true => proposed_batch.take(),
+                false => None,
+            }
+        };
+        if let Some(proposal) = proposal {
+            self.reinsert_transmissions_into_workers(proposal)?;
+        }
+        Ok(num_discarded)
+    }
+
+    /// Catches up on the recent DAG from the connected validators.
+    ///
+    /// The primary requests the IDs of the certificates after its last committed round from the connected
+    /// validators, and fetches any that it is missing. Each certificate is verified and stored (along with any
+    /// missing previous certificates), from the oldest round. The catch-up succeeds once validators with quorum
+    /// stake (including the primary) have responded.
+    async fn catch_up(&self) -> Result<()> {
+        // Retrieve the last committed round.
+        let committed_round = self.ledger.latest_round();
+        // Retrieve the committee to check against.
+        let committee = self.ledger.get_previous_committee_for_round(self.current_round())?;
+
+        // Request the certificate IDs from the connected validators.
+        let peer_ips = self.gateway.connected_peers().read().iter().copied().collect::<Vec<_>>();
+        let mut catch_up_requests = peer_ips
+            .into_iter()
+            .map(|peer_ip| async move { (peer_ip, self.sync.send_catch_up_request(peer_ip, committed_round).await) })
+            .collect::<FuturesUnordered<_>>();
+
+        // Initialize a set for the validators that responded, starting with the primary.
+        let mut responders = HashSet::from([self.gateway.account().address()]);
+        // Process the responses.
+        while let Some((peer_ip, result)) = catch_up_requests.next().await {
+            let certificate_ids = match result {
+                Ok(certificate_ids) => certificate_ids,
+                Err(e) => {
+                    debug!("{e}");
+                    continue;
+                }
+            };
+            // Fetch the missing certificates from the peer.
+            let mut fetch_certificates = certificate_ids
+                .into_iter()
+                .filter(|id| !self.storage.contains_certificate(*id))
+                .filter(|id| !self.ledger.contains_certificate(id).unwrap_or(true))
+                .map(|id| self.sync.send_certificate_request(peer_ip, id))
+                .collect::<FuturesUnordered<_>>();
+            let mut certificates = Vec::with_capacity(fetch_certificates.len());
+            while let Some(result) = fetch_certificates.next().await {
+                match result {
+                    Ok(certificate) => certificates.push(certificate),
+                    Err(e) => trace!("Primary failed to fetch a certificate from '{peer_ip}' during catch-up - {e}"),
+                }
+            }
+            // Store the certificates, starting from the oldest round.
+            certificates.sort_by_key(|certificate| certificate.round());
+            let num_certificates = certificates.len();
+            for certificate in certificates {
+                if let Err(e) = self.sync_with_certificate_from_peer(peer_ip, certificate).await {
+                    warn!("Primary failed to store a certificate from '{peer_ip}' during catch-up - {e}");
+                }
+            }
+            debug!("Caught up on {num_certificates} certificates after round {committed_round} from '{peer_ip}'");
+            // Insert the peer into the set of responders.
+            if let Some(address) = self.gateway.resolver().get_address(peer_ip) {
+                responders.insert(address);
+            }
+        }
+
+        // Ensure validators with quorum stake have responded.
+        if !committee.is_quorum_threshold_reached(&responders) {
+            bail!("Catch-up did not reach quorum threshold ({} validators responded)", responders.len())
+        }
+        info!("Caught up on the recent DAG at round {}", self.current_round());
+        Ok(())
+    }
+
+    /// Checks if the current round timed out, and if so, attempts to advance to the next round.
+    ///
+    /// If the round timeout elapses before the primary advances, the primary advances as soon as the DAG permits,
+    /// i.e. once a quorum of certificates for the current round is stored, without waiting on the remaining
+    /// certificates (or, in the BFT, on the leader certificate, whose timer shares the round timeout).
+    async fn check_round_timeout(&self) -> Result<()> {
+        // Retrieve the current round.
+        let round = self.current_round();
+        // Update the round timer to the current round.
+        self.round_timer.update(round);
+        // If the current round has not timed out, return early.
+        if !self.round_timer.is_expired() {
+            return Ok(());
+        }
+        // Retrieve the committee for the current round.
+        let committee = self.ledger.get_previous_committee_for_round(round)?;
+        // Retrieve the authors of the certificates for the current round.
+        let authors = self.storage.get_certificates_for_round(round).into_iter().map(|c| c.author()).collect();
+        // If the quorum threshold is not reached, the primary cannot advance yet.
+        if !committee.is_quorum_threshold_reached(&authors) {
+            let elapsed = self.round_timer.elapsed().as_millis();
+            debug!("Round {round} timed out after {elapsed}ms {}", "(waiting for a quorum of certificates)".dimmed());
+            return Ok(());
+        }
+        debug!("Round {round} timed out - attempting to advance to round {}", round + 1);
+        // Attempt to advance to the next round.
+        self.try_increment_to_the_next_round(round + 1).await
+    }
+
     /// Increments to the next round.
     async fn try_increment_to_the_next_round(&self, next_round: u64) -> Result<()> {
         // If the next round is within GC range, then iterate to the penultimate round.
@@ -1151,6 +1864,25 @@ impl<N: Network> Primary<N> {
                 // Set 'is_ready' to 'true'.
                 true
             };
+            // Update the round timer to the current round.
+            self.round_timer.update(self.current_round());
+            // Remove the transmissions of the batches outside the dedup window.
+            self.dedup.garbage_collect(self.current_round());
+            // Remove the digests of the messages at or below the GC round.
+            self.replay_guard.garbage_collect(self.storage.gc_round());
+            // If the round advanced, emit the new round to the consensus event subscribers.
+            if self.current_round() > current_round {
+                self.gateway.consensus_events().emit(ConsensusEvent::RoundAdvanced { round: self.current_round() });
+            }
+
+            // Update the participation rate of each validator in the committee.
+            #[cfg(feature = "metrics")]
+            if let Ok(committee) = self.ledger.current_committee() {
+                for address in committee.members().keys() {
+                    let (name, rate) = (metrics::bft::VALIDATOR_PARTICIPATION, self.participation.rate(*address));
+                    metrics::gauge_with_label(name, "validator", address.to_string(), rate);
+                }
+            }
 
             // Log whether the next round is ready.
             match is_ready {
@@ -1200,7 +1932,11 @@ impl<N: Network> Primary<N> {
         let transmissions = transmissions.into_iter().collect::<HashMap<_, _>>();
         // Store the certified batch.
         self.storage.insert_certificate(certificate.clone(), transmissions)?;
+        // Record the transmissions of the certified batch.
+        self.dedup.insert_certified(certificate.round(), certificate.transmission_ids());
         debug!("Stored a batch certificate for round {}", certificate.round());
+        // Emit the certificate to the consensus event subscribers.
+        self.emit_certificate_formed(&certificate);
         // If a BFT sender was provided, send the certificate to the BFT.
         if let Some(bft_sender) = self.bft_sender.get() {
             // Await the callback to continue.
@@ -1273,13 +2009,38 @@ impl<N: Network> Primary<N> {
         }
 
         // If the peer is ahead, use the batch header to sync up to the peer.
-        let missing_transmissions = self.sync_with_batch_header_from_peer(peer_ip, batch_header).await?;
+        let missing_transmissions = match self.sync_with_batch_header_from_peer(peer_ip, batch_header).await {
+            Ok(missing_transmissions) => missing_transmissions,
+            Err(error) => {
+                // Report the peer for referencing parents it could not provide, only if the certificate also fails
+                // verification, as a certificate signed by a quorum attests that its parents exist, in which case
+                // the fetch failed on our side (e.g. a timeout).
+                if let Err(invalid) = self.storage.check_certificate_signers(&certificate) {
+                    if invalid.downcast_ref::<InvalidCertificate>().is_some() {
+                        let details = format!("Referenced parents it could not provide - {error} - {invalid}");
+                        self.report_misbehavior(peer_ip, Misbehavior::UnknownParents, batch_round, details);
+                    }
+                }
+                return Err(error);
+            }
+        };
 
         // Check if the certificate needs to be stored.
         if !self.storage.contains_certificate(certificate.id()) {
-            // Store the batch certificate.
-            self.storage.insert_certificate(certificate.clone(), missing_transmissions)?;
+            // Store the batch certificate, reporting the peer only if the certificate fails verification,
+            // as the other errors (e.g. a race with garbage collection) are not attributable to the peer.
+            if let Err(error) = self.storage.insert_certificate(certificate.clone(), missing_transmissions) {
+                if error.downcast_ref::<InvalidCertificate>().is_some() {
+                    let details = format!("Sent an invalid batch certificate - {error}");
+                    self.report_misbehavior(peer_ip, Misbehavior::InvalidCertificate, batch_round, details);
+                }
+                return Err(error);
+            }
+            // Record the transmissions of the certified batch.
+            self.dedup.insert_certified(batch_round, certificate.transmission_ids());
             debug!("Stored a batch certificate for round {batch_round} from '{peer_ip}'");
+            // Emit the certificate to the consensus event subscribers.
+            self.emit_certificate_formed(&certificate);
             // If a BFT sender was provided, send the round and certificate to the BFT.
             if let Some(bft_sender) = self.bft_sender.get() {
                 // Send the certificate to the BFT.
@@ -1486,8 +2247,8 @@ impl<N: Network> Primary<N> {
             if !self.storage.contains_certificate(*certificate_id) {
                 trace!("Primary - Found a new certificate ID for round {round} from '{peer_ip}'");
                 // TODO (howardwu): Limit the number of open requests we send to a peer.
-                // Send an certificate request to the peer.
-                fetch_certificates.push(self.sync.send_certificate_request(peer_ip, *certificate_id));
+                // Send an certificate request to the peer, falling back to the other validators.
+                fetch_certificates.push(self.fetch_certificate(peer_ip, round, *certificate_id));
             }
         }
 
@@ -1510,6 +2271,46 @@ impl<N: Network> Primary<N> {
         // Return the missing certificates.
         Ok(missing_certificates)
     }
+
+    /// Fetches the certificate with the given ID from the specified peer.
+    /// If the peer fails to provide the certificate, it is requested from the other connected validators
+    /// in the committee, up to `MAX_CERTIFICATE_FETCH_FALLBACKS` validators.
+    async fn fetch_certificate(
+        &self,
+        peer_ip: SocketAddr,
+        round: u64,
+        certificate_id: Field<N>,
+    ) -> Result<BatchCertificate<N>> {
+        // Request the certificate from the specified peer.
+        let error = match self.sync.send_certificate_request(peer_ip, certificate_id).await {
+            Ok(certificate) => return Ok(certificate),
+            Err(error) => error,
+        };
+        // Retrieve the committee for the round.
+        let committee = self.ledger.get_previous_committee_for_round(round)?;
+        // Retrieve the connected validators in the committee, other than the specified peer.
+        let fallback_peer_ips = committee
+            .members()
+            .keys()
+            .filter(|address| **address != self.gateway.account().address())
+            .filter_map(|address| self.gateway.resolver().get_peer_ip_for_address(*address))
+            .filter(|fallback_ip| *fallback_ip != peer_ip && self.gateway.is_connected_ip(*fallback_ip))
+            .take(MAX_CERTIFICATE_FETCH_FALLBACKS)
+            .collect::<Vec<_>>();
+        // Request the certificate from the fallback validators, one at a time.
+        let id = fmt_id(certificate_id);
+        for fallback_ip in fallback_peer_ips {
+            trace!("Requesting certificate {id} from '{fallback_ip}' (fallback for '{peer_ip}')");
+            match self.sync.send_certificate_request(fallback_ip, certificate_id).await {
+                Ok(certificate) => {
+                    debug!("Fetched certificate {id} from '{fallback_ip}' (fallback for '{peer_ip}')");
+                    return Ok(certificate);
+                }
+                Err(e) => trace!("Failed to fetch certificate {id} from '{fallback_ip}' - {e}"),
+            }
+        }
+        Err(error)
+    }
 }
 
 impl<N: Network> Primary<N> {
@@ -1533,6 +2334,7 @@ impl<N: Network> Primary<N> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::helpers::MAX_REPLAY_FUTURE_ROUNDS;
     use snarkos_node_bft_ledger_service::MockLedgerService;
     use snarkos_node_bft_storage_service::BFTMemoryService;
     use snarkvm::{
@@ -1571,11 +2373,13 @@ mod tests {
         let storage = Storage::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 10);
 
         // Initialize the primary.
-        let mut primary = Primary::new(account, storage, ledger, None, &[], None).unwrap();
+        let mut primary =
+            Primary::new(account, storage, ledger, None, &[], None, PrimaryConfig::default(), None).unwrap();
 
         // Construct a worker instance.
         primary.workers = Arc::from([Worker::new(
             0, // id
+            1, // num_workers
             Arc::new(primary.gateway.clone()),
             primary.storage.clone(),
             primary.ledger.clone(),
@@ -1774,6 +2578,15 @@ mod tests {
         }
     }
 
+    // Seals the ready transmissions of the workers, and hands the digests to the primary.
+    fn seal_worker_batches(primary: &Primary<CurrentNetwork>) {
+        for worker in primary.workers.iter() {
+            while let Some(digest) = worker.seal_batch() {
+                primary.worker_batches.lock().push_back((worker.id(), digest));
+            }
+        }
+    }
+
     #[tokio::test]
     async fn test_propose_batch() {
         let mut rng = TestRng::default();
@@ -1795,6 +2608,11 @@ mod tests {
         primary.workers[0].process_unconfirmed_solution(solution_commitment, solution).await.unwrap();
         primary.workers[0].process_unconfirmed_transaction(transaction_id, transaction).await.unwrap();
 
+        // Ensure the primary does not propose the transmissions before the worker seals them.
+        assert!(primary.propose_batch().await.is_ok());
+        assert!(primary.proposed_batch.read().is_none());
+        seal_worker_batches(&primary);
+
         // Try to propose a batch again. This time, it should succeed.
         assert!(primary.propose_batch().await.is_ok());
         assert!(primary.proposed_batch.read().is_some());
@@ -1818,9 +2636,10 @@ mod tests {
         let (solution_commitment, solution) = sample_unconfirmed_solution(&mut rng);
         let (transaction_id, transaction) = sample_unconfirmed_transaction(&mut rng);
 
-        // Store it on one of the workers.
+        // Store it on one of the workers, and seal it into a worker batch.
         primary.workers[0].process_unconfirmed_solution(solution_commitment, solution).await.unwrap();
         primary.workers[0].process_unconfirmed_transaction(transaction_id, transaction).await.unwrap();
+        seal_worker_batches(&primary);
 
         // Propose a batch again. This time, it should succeed.
         assert!(primary.propose_batch().await.is_ok());
@@ -1860,6 +2679,31 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_batch_propose_replay() {
+        let mut rng = TestRng::default();
+        let (primary, accounts) = primary_without_handlers(&mut rng).await;
+
+        // Create a valid proposal with an author that isn't the primary.
+        let round = 1;
+        let proposal = create_test_proposal(
+            &accounts[1].1,
+            primary.ledger.current_committee().unwrap(),
+            round,
+            Default::default(),
+            now(),
+            &mut rng,
+        );
+        let batch_header = Data::Object(proposal.batch_header().clone());
+
+        // Ensure the batch proposal is accepted once, and then rejected as a replay.
+        assert!(primary.check_for_replay("BatchPropose", round, &batch_header).unwrap().is_some());
+        assert!(primary.check_for_replay("BatchPropose", round, &batch_header).unwrap().is_none());
+        // Ensure a batch proposal for a round far in the future is rejected.
+        let far_round = primary.current_round() + MAX_REPLAY_FUTURE_ROUNDS + 1;
+        assert!(primary.check_for_replay("BatchPropose", far_round, &batch_header).is_err());
+    }
+
     #[tokio::test]
     async fn test_batch_propose_from_peer_in_round() {
         let round = 2;
@@ -2118,4 +2962,42 @@ mod tests {
         // Check the round was incremented.
         assert_eq!(primary.current_round(), round);
     }
+
+    // Creates an equivocation, in which the given author signed two different batch headers for the given round.
+    fn sample_equivocation(
+        author: &Account<CurrentNetwork>,
+        round: u64,
+        rng: &mut TestRng,
+    ) -> Equivocation<CurrentNetwork> {
+        let private_key = author.private_key();
+        let mut sample_header = |timestamp| {
+            let (transmission_ids, previous_ids, election_ids) = Default::default();
+            BatchHeader::new(private_key, round, timestamp, transmission_ids, previous_ids, election_ids, rng).unwrap()
+        };
+        let (first, second) = (sample_header(now()), sample_header(now() + 1));
+        Equivocation::new(Data::Object(first), Data::Object(second))
+    }
+
+    #[tokio::test]
+    async fn test_equivocation_from_peer() {
+        let mut rng = TestRng::default();
+        let (primary, accounts) = primary_without_handlers(&mut rng).await;
+        let author = &accounts[1].1;
+
+        // Ensure the evidence from a committee member within the GC window is recorded.
+        let round = primary.current_round();
+        primary.process_equivocation_from_peer(sample_equivocation(author, round, &mut rng)).await.unwrap();
+        assert!(primary.evidence.contains(author.address(), round));
+
+        // Ensure the evidence from a validator that is not in the committee is rejected.
+        let outsider = Account::new(&mut rng).unwrap();
+        assert!(primary.process_equivocation_from_peer(sample_equivocation(&outsider, round, &mut rng)).await.is_err());
+        assert!(!primary.evidence.contains(outsider.address(), round));
+
+        // Ensure the evidence for a round beyond the GC window is rejected.
+        let round = round + primary.storage.max_gc_rounds() + 1;
+        assert!(primary.process_equivocation_from_peer(sample_equivocation(author, round, &mut rng)).await.is_err());
+        assert!(!primary.evidence.contains(author.address(), round));
+        assert_eq!(primary.evidence.len(), 1);
+    }
 }
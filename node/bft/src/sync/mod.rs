@@ -14,12 +14,13 @@
 
 use crate::{
     helpers::{BFTSender, Pending, Storage, SyncReceiver},
+    spawn_blocking,
     Gateway,
     Transport,
     MAX_BATCH_DELAY_IN_MS,
     PRIMARY_PING_IN_MS,
 };
-use snarkos_node_bft_events::{CertificateRequest, CertificateResponse, Event};
+use snarkos_node_bft_events::{CatchUpRequest, CatchUpResponse, CertificateRequest, CertificateResponse, Event};
 use snarkos_node_bft_ledger_service::LedgerService;
 use snarkos_node_sync::{locators::BlockLocators, BlockSync, BlockSyncMode};
 use snarkvm::{
@@ -28,8 +29,9 @@ use snarkvm::{
 };
 
 use anyhow::{bail, Result};
+use indexmap::IndexSet;
 use parking_lot::Mutex;
-use std::{future::Future, net::SocketAddr, sync::Arc};
+use std::{collections::HashMap, future::Future, net::SocketAddr, sync::Arc, time::Instant};
 use tokio::{
     sync::{oneshot, Mutex as TMutex, OnceCell},
     task::JoinHandle,
@@ -47,6 +49,8 @@ pub struct Sync<N: Network> {
     block_sync: BlockSync<N>,
     /// The pending certificates queue.
     pending: Arc<Pending<Field<N>, BatchCertificate<N>>>,
+    /// The pending catch-up requests (a map from the peer IP and round to the callback).
+    pending_catch_ups: Arc<Mutex<HashMap<(SocketAddr, u64), oneshot::Sender<IndexSet<Field<N>>>>>>,
     /// The BFT sender.
     bft_sender: Arc<OnceCell<BFTSender<N>>>,
     /// The spawned handles.
@@ -67,6 +71,7 @@ impl<N: Network> Sync<N> {
             ledger,
             block_sync,
             pending: Default::default(),
+            pending_catch_ups: Default::default(),
             bft_sender: Default::default(),
             handles: Default::default(),
             lock: Default::default(),
@@ -97,6 +102,10 @@ impl<N: Network> Sync<N> {
                 let communication = &self_.gateway;
                 // let communication = &node.router;
                 self_.block_sync.try_block_sync(communication).await;
+                // Log a summary of the sync status, if the node is behind.
+                if let Some(status) = self_.block_sync.status_summary() {
+                    info!("{status}");
+                }
             }
         }));
 
@@ -107,6 +116,8 @@ impl<N: Network> Sync<N> {
             mut rx_block_sync_update_peer_locators,
             mut rx_certificate_request,
             mut rx_certificate_response,
+            mut rx_catch_up_request,
+            mut rx_catch_up_response,
         } = sync_receiver;
 
         // Process the block sync request to advance with sync blocks.
@@ -170,6 +181,22 @@ impl<N: Network> Sync<N> {
             }
         });
 
+        // Process the catch-up request.
+        let self_ = self.clone();
+        self.spawn(async move {
+            while let Some((peer_ip, catch_up_request)) = rx_catch_up_request.recv().await {
+                self_.send_catch_up_response(peer_ip, catch_up_request);
+            }
+        });
+
+        // Process the catch-up response.
+        let self_ = self.clone();
+        self.spawn(async move {
+            while let Some((peer_ip, catch_up_response)) = rx_catch_up_response.recv().await {
+                self_.finish_catch_up_request(peer_ip, catch_up_response)
+            }
+        });
+
         Ok(())
     }
 }
@@ -228,7 +255,12 @@ impl<N: Network> Sync<N> {
                 }
             })
             .collect::<Vec<_>>();
-        if leader_certificates.is_empty() {
+        // Retrieve the certificates in storage that are not in the ledger, such as those restored after a restart.
+        let uncommitted_certificates = (self.storage.gc_round().saturating_add(1)..=self.storage.current_round())
+            .flat_map(|round| self.storage.get_certificates_for_round(round))
+            .filter(|certificate| !self.ledger.contains_certificate(&certificate.id()).unwrap_or(true))
+            .collect::<Vec<_>>();
+        if leader_certificates.is_empty() && uncommitted_certificates.is_empty() {
             return Ok(());
         }
 
@@ -244,6 +276,7 @@ impl<N: Network> Sync<N> {
                 }
             })
             .flatten()
+            .chain(uncommitted_certificates)
             .collect::<Vec<_>>();
 
         // If a BFT sender was provided, send the certificate to the BFT.
@@ -262,7 +295,19 @@ impl<N: Network> Sync<N> {
         // Retrieve the latest block height.
         let mut current_height = self.ledger.latest_block_height() + 1;
         // Try to advance the ledger with sync blocks.
-        while let Some(block) = self.block_sync.process_next_block(current_height) {
+        while let Some((block, sync_ips)) = self.block_sync.process_next_block(current_height) {
+            // If the ledger is at the previous height, ensure the block is valid before syncing the storage with it.
+            // Note: The ledger only advances once the BFT commits the synced certificates, so later blocks in the
+            // sync pool are validated by the ledger when they are committed.
+            // Note: The check runs on the blocking pool, as it verifies the block and would stall the executor.
+            if self.ledger.latest_block_height() + 1 == current_height {
+                let (ledger, block_) = (self.ledger.clone(), block.clone());
+                if let Err(error) = spawn_blocking!(ledger.check_next_block(&block_)) {
+                    // Ban the peers that served the block, and request the block from other peers.
+                    self.block_sync.handle_invalid_block(current_height, &sync_ips);
+                    bail!("The next block ({current_height}) is invalid - {error}");
+                }
+            }
             info!("Syncing the BFT to block {}...", block.height());
             // Sync the storage with the block.
             self.sync_storage_with_block(block).await?;
@@ -312,6 +357,16 @@ impl<N: Network> Sync<N> {
         self.block_sync.is_block_synced()
     }
 
+    /// Returns the number of blocks that the node is behind its peers.
+    pub fn num_blocks_behind(&self) -> u32 {
+        self.block_sync.num_blocks_behind()
+    }
+
+    /// Returns the block sync module.
+    pub const fn block_sync(&self) -> &BlockSync<N> {
+        &self.block_sync
+    }
+
     /// Returns `true` if the node is in gateway mode.
     pub const fn is_gateway_mode(&self) -> bool {
         self.block_sync.mode().is_gateway()
@@ -333,8 +388,11 @@ impl<N: Network> Sync<N> {
     ) -> Result<BatchCertificate<N>> {
         // Initialize a oneshot channel.
         let (callback_sender, callback_receiver) = oneshot::channel();
+        // Initialize the timestamp of the request.
+        let start = Instant::now();
         // Insert the certificate ID into the pending queue.
-        if self.pending.insert(certificate_id, peer_ip, Some(callback_sender)) {
+        let is_new_request = self.pending.insert(certificate_id, peer_ip, Some(callback_sender));
+        if is_new_request {
             // Send the certificate request to the peer.
             if self.gateway.send(peer_ip, Event::CertificateRequest(certificate_id.into())).await.is_none() {
                 bail!("Unable to fetch batch certificate {certificate_id} - failed to send request")
@@ -343,7 +401,14 @@ impl<N: Network> Sync<N> {
         // Wait for the certificate to be fetched.
         match tokio::time::timeout(core::time::Duration::from_millis(MAX_BATCH_DELAY_IN_MS), callback_receiver).await {
             // If the certificate was fetched, return it.
-            Ok(result) => Ok(result?),
+            Ok(result) => {
+                let certificate = result?;
+                // If this request was sent to the peer, update the round-trip time to the peer.
+                if is_new_request {
+                    self.block_sync.update_peer_latency(peer_ip, start.elapsed());
+                }
+                Ok(certificate)
+            }
             // If the certificate was not fetched, return an error.
             Err(e) => bail!("Unable to fetch batch certificate {certificate_id} - (timeout) {e}"),
         }
@@ -368,14 +433,67 @@ impl<N: Network> Sync<N> {
         // Check if the peer IP exists in the pending queue for the given certificate ID.
         let exists = self.pending.get(certificate.id()).unwrap_or_default().contains(&peer_ip);
         // If the peer IP exists, finish the pending request.
+        // Note: The certificate ID commits to the batch header and signatures, so a certificate that matches
+        // the pending ID is the requested certificate. Its signatures are verified when it is stored.
         if exists {
-            // TODO: Validate the certificate.
             // Remove the certificate ID from the pending queue.
             self.pending.remove(certificate.id(), Some(certificate));
         }
     }
 }
 
+// Methods to assist with catching up on the recent DAG from peers.
+impl<N: Network> Sync<N> {
+    /// Sends a catch-up request to the specified peer, returning the IDs of the certificates
+    /// that the peer has in storage after the given round.
+    pub async fn send_catch_up_request(&self, peer_ip: SocketAddr, round: u64) -> Result<IndexSet<Field<N>>> {
+        // Initialize a oneshot channel.
+        let (callback_sender, callback_receiver) = oneshot::channel();
+        // Insert the callback into the pending catch-up requests.
+        self.pending_catch_ups.lock().insert((peer_ip, round), callback_sender);
+        // Send the catch-up request to the peer.
+        if self.gateway.send(peer_ip, Event::CatchUpRequest(CatchUpRequest::new(round))).await.is_none() {
+            self.pending_catch_ups.lock().remove(&(peer_ip, round));
+            bail!("Unable to catch up from '{peer_ip}' - failed to send request")
+        }
+        // Wait for the certificate IDs to be received.
+        match tokio::time::timeout(core::time::Duration::from_millis(MAX_BATCH_DELAY_IN_MS), callback_receiver).await {
+            // If the certificate IDs were received, return them.
+            Ok(result) => Ok(result?),
+            // If the certificate IDs were not received, return an error.
+            Err(e) => {
+                self.pending_catch_ups.lock().remove(&(peer_ip, round));
+                bail!("Unable to catch up from '{peer_ip}' - (timeout) {e}")
+            }
+        }
+    }
+
+    /// Handles the incoming catch-up request.
+    fn send_catch_up_response(&self, peer_ip: SocketAddr, request: CatchUpRequest) {
+        // Retrieve the IDs of the certificates in storage after the requested round (and above the GC round).
+        let start_round = request.round.max(self.storage.gc_round()).saturating_add(1);
+        let certificate_ids = (start_round..=self.storage.current_round())
+            .flat_map(|round| self.storage.get_certificates_for_round(round))
+            .map(|certificate| certificate.id())
+            .take(u16::MAX as usize)
+            .collect();
+        // Send the catch-up response to the peer.
+        let self_ = self.clone();
+        tokio::spawn(async move {
+            let event = Event::CatchUpResponse(CatchUpResponse::new(request.round, certificate_ids));
+            let _ = self_.gateway.send(peer_ip, event).await;
+        });
+    }
+
+    /// Handles the incoming catch-up response.
+    fn finish_catch_up_request(&self, peer_ip: SocketAddr, response: CatchUpResponse<N>) {
+        // If the catch-up request is pending, send the certificate IDs to the callback.
+        if let Some(callback) = self.pending_catch_ups.lock().remove(&(peer_ip, response.round)) {
+            callback.send(response.certificate_ids).ok();
+        }
+    }
+}
+
 impl<N: Network> Sync<N> {
     /// Spawns a task with the given future; it should only be used for long-running tasks.
     fn spawn<T: Future<Output = ()> + Send + 'static>(&self, future: T) {
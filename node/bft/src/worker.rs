@@ -13,14 +13,16 @@
 // limitations under the License.
 
 use crate::{
-    events::{Event, TransmissionRequest, TransmissionResponse},
+    events::{Event, TransmissionRequest, TransmissionResponse, WorkerBatch},
     helpers::{fmt_id, Pending, Ready, Storage, WorkerReceiver},
     ProposedBatch,
     Transport,
     MAX_BATCH_DELAY_IN_MS,
     MAX_TRANSMISSIONS_PER_BATCH,
+    MAX_TRANSMISSIONS_PER_WORKER_BATCH,
     MAX_TRANSMISSIONS_PER_WORKER_PING,
     MAX_WORKERS,
+    WORKER_BATCH_IN_MS,
 };
 use snarkos_node_bft_ledger_service::LedgerService;
 use snarkvm::{
@@ -29,20 +31,42 @@ use snarkvm::{
     prelude::{
         block::Transaction,
         coinbase::{ProverSolution, PuzzleCommitment},
+        Field,
     },
 };
 
 use indexmap::{IndexMap, IndexSet};
 use parking_lot::Mutex;
-use std::{future::Future, net::SocketAddr, sync::Arc, time::Duration};
-use tokio::{sync::oneshot, task::JoinHandle, time::timeout};
+use std::{
+    collections::HashSet,
+    future::Future,
+    net::SocketAddr,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{
+    sync::{mpsc, oneshot},
+    task::JoinHandle,
+    time::timeout,
+};
 
-const MAX_TRANSMISSIONS_PER_WORKER: usize = MAX_TRANSMISSIONS_PER_BATCH / MAX_WORKERS as usize;
+/// Ensures the given number of workers is within bounds.
+pub(crate) fn check_num_workers(num_workers: u8) -> Result<()> {
+    ensure!((1..=MAX_WORKERS).contains(&num_workers), "The number of workers must be between 1 and {MAX_WORKERS}");
+    Ok(())
+}
+
+/// Returns the maximum number of transmissions in the ready queue of each of the given number of workers.
+fn max_transmissions_per_worker(num_workers: u8) -> usize {
+    MAX_TRANSMISSIONS_PER_BATCH / num_workers.max(1) as usize
+}
 
 #[derive(Clone)]
 pub struct Worker<N: Network> {
     /// The worker ID.
     id: u8,
+    /// The number of workers spawned by the primary.
+    num_workers: u8,
     /// The gateway.
     gateway: Arc<dyn Transport<N>>,
     /// The storage.
@@ -53,6 +77,8 @@ pub struct Worker<N: Network> {
     proposed_batch: Arc<ProposedBatch<N>>,
     /// The ready queue.
     ready: Ready<N>,
+    /// The worker batches sealed from the ready queue, which are not yet proposed by the primary.
+    batches: Arc<Mutex<IndexMap<Field<N>, IndexSet<TransmissionID<N>>>>>,
     /// The pending transmissions queue.
     pending: Arc<Pending<TransmissionID<N>, Transmission<N>>>,
     /// The spawned handles.
@@ -63,31 +89,34 @@ impl<N: Network> Worker<N> {
     /// Initializes a new worker instance.
     pub fn new(
         id: u8,
+        num_workers: u8,
         gateway: Arc<dyn Transport<N>>,
         storage: Storage<N>,
         ledger: Arc<dyn LedgerService<N>>,
         proposed_batch: Arc<ProposedBatch<N>>,
     ) -> Result<Self> {
         // Ensure the worker ID is valid.
-        ensure!(id < MAX_WORKERS, "Invalid worker ID '{id}'");
+        ensure!(id < MAX_WORKERS && id < num_workers, "Invalid worker ID '{id}'");
         // Return the worker.
         Ok(Self {
             id,
+            num_workers,
             gateway,
             storage,
             ledger,
             proposed_batch,
             ready: Default::default(),
+            batches: Default::default(),
             pending: Default::default(),
             handles: Default::default(),
         })
     }
 
-    /// Run the worker instance.
-    pub fn run(&self, receiver: WorkerReceiver<N>) {
+    /// Run the worker instance, which sends the digests of its sealed worker batches to the primary.
+    pub fn run(&self, receiver: WorkerReceiver<N>, tx_worker_batch: mpsc::Sender<(u8, Field<N>)>) {
         info!("Starting worker instance {} of the memory pool...", self.id);
         // Start the worker handlers.
-        self.start_handlers(receiver);
+        self.start_handlers(receiver, tx_worker_batch);
     }
 
     /// Returns the worker ID.
@@ -191,9 +220,54 @@ impl<N: Network> Worker<N> {
         Ok((transmission_id, transmission))
     }
 
-    /// Removes up to the specified number of transmissions from the ready queue, and returns them.
-    pub(crate) fn drain(&self, num_transmissions: usize) -> impl Iterator<Item = (TransmissionID<N>, Transmission<N>)> {
-        self.ready.drain(num_transmissions).into_iter()
+    /// Returns the number of transmissions in the sealed worker batches.
+    pub fn num_sealed_transmissions(&self) -> usize {
+        self.batches.lock().values().map(IndexSet::len).sum()
+    }
+
+    /// Seals the ready transmissions that are not in a worker batch yet into a new worker batch,
+    /// broadcasts its digest to the peers, and returns the digest, if there are any such transmissions.
+    pub(crate) fn seal_batch(&self) -> Option<Field<N>> {
+        let mut batches = self.batches.lock();
+        // Select the oldest ready transmissions that are not sealed yet.
+        let sealed = batches.values().flatten().copied().collect::<HashSet<_>>();
+        let transmission_ids = self
+            .ready
+            .transmission_ids()
+            .into_iter()
+            .filter(|transmission_id| !sealed.contains(transmission_id))
+            .take(MAX_TRANSMISSIONS_PER_WORKER_BATCH)
+            .collect::<IndexSet<_>>();
+        if transmission_ids.is_empty() {
+            return None;
+        }
+        // Compute the digest of the worker batch.
+        let digest = match WorkerBatch::compute_digest(&transmission_ids) {
+            Ok(digest) => digest,
+            Err(e) => {
+                warn!("Worker {} - Failed to compute the digest of a worker batch - {e}", self.id);
+                return None;
+            }
+        };
+        batches.insert(digest, transmission_ids.clone());
+        drop(batches);
+        let num_transmissions = transmission_ids.len();
+        trace!("Worker {} - Sealed worker batch '{}' ({num_transmissions} transmissions)", self.id, fmt_id(digest));
+        // Broadcast the digest, so the peers fetch the missing transmissions ahead of the batch proposal.
+        self.gateway.broadcast(Event::WorkerBatch(WorkerBatch::new(digest, transmission_ids)));
+        Some(digest)
+    }
+
+    /// Removes the worker batch with the given digest, and returns its transmissions that are still in the ready queue.
+    pub(crate) fn take_batch(&self, digest: Field<N>) -> impl Iterator<Item = (TransmissionID<N>, Transmission<N>)> {
+        let transmission_ids = self.batches.lock().shift_remove(&digest).unwrap_or_default();
+        let transmissions = transmission_ids.into_iter().filter_map(|id| self.ready.remove(id).map(|t| (id, t)));
+        transmissions.collect::<Vec<_>>().into_iter()
+    }
+
+    /// Removes the specified transmission from the ready queue, and returns it.
+    pub(crate) fn take(&self, transmission_id: TransmissionID<N>) -> Option<Transmission<N>> {
+        self.ready.remove(transmission_id)
     }
 
     /// Reinserts the specified transmission into the ready queue.
@@ -228,7 +302,7 @@ impl<N: Network> Worker<N> {
         }
         // If the ready queue is full, then skip this transmission.
         // Note: We must prioritize the unconfirmed solutions and unconfirmed transactions, not transmissions.
-        if self.ready.num_transmissions() > MAX_TRANSMISSIONS_PER_WORKER {
+        if self.ready.num_transmissions() > max_transmissions_per_worker(self.num_workers) {
             return;
         }
         // Attempt to fetch the transmission from the peer.
@@ -339,9 +413,22 @@ impl<N: Network> Worker<N> {
 
 impl<N: Network> Worker<N> {
     /// Starts the worker handlers.
-    fn start_handlers(&self, receiver: WorkerReceiver<N>) {
+    fn start_handlers(&self, receiver: WorkerReceiver<N>, tx_worker_batch: mpsc::Sender<(u8, Field<N>)>) {
         let WorkerReceiver { mut rx_worker_ping, mut rx_transmission_request, mut rx_transmission_response } = receiver;
 
+        // Seal the ready transmissions into worker batches, and send their digests to the primary.
+        let self_ = self.clone();
+        self.spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_millis(WORKER_BATCH_IN_MS)).await;
+                while let Some(digest) = self_.seal_batch() {
+                    if tx_worker_batch.send((self_.id, digest)).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+
         // Process the ping events.
         let self_ = self.clone();
         self.spawn(async move {
@@ -487,6 +574,9 @@ mod tests {
             fn get_previous_committee_for_round(&self, round: u64) -> Result<Committee<N>>;
             fn contains_certificate(&self, certificate_id: &Field<N>) -> Result<bool>;
             fn contains_transmission(&self, transmission_id: &TransmissionID<N>) -> Result<bool>;
+            fn contains_serial_number(&self, serial_number: &Field<N>) -> Result<bool>;
+            fn contains_commitment(&self, commitment: &Field<N>) -> Result<bool>;
+            fn contains_state_root(&self, state_root: &N::StateRoot) -> Result<bool>;
             fn ensure_transmission_id_matches(
                 &self,
                 transmission_id: TransmissionID<N>,
@@ -528,7 +618,7 @@ mod tests {
         let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
 
         // Create the Worker.
-        let worker = Worker::new(0, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
+        let worker = Worker::new(0, 1, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
         let data = |rng: &mut TestRng| Data::Buffer(Bytes::from((0..512).map(|_| rng.gen::<u8>()).collect::<Vec<_>>()));
         let transmission_id = TransmissionID::Solution(PuzzleCommitment::from_g1_affine(rng.gen()));
         let peer_ip = SocketAddr::from(([127, 0, 0, 1], 1234));
@@ -540,11 +630,62 @@ mod tests {
         assert!(worker.ready.contains(transmission_id));
         assert_eq!(worker.get_transmission(transmission_id), Some(transmission));
         // Take the transmission from the ready set.
-        let transmission: Vec<_> = worker.drain(1).collect();
-        assert_eq!(transmission.len(), 1);
+        assert!(worker.take(transmission_id).is_some());
         assert!(!worker.ready.contains(transmission_id));
     }
 
+    #[tokio::test]
+    async fn test_seal_batch() {
+        let rng = &mut TestRng::default();
+        // Sample a committee.
+        let committee = snarkvm::ledger::committee::test_helpers::sample_committee(rng);
+        // Setup the mock gateway and ledger.
+        let mut gateway = MockGateway::default();
+        gateway.expect_broadcast().times(2).return_const(());
+        let mut mock_ledger = MockLedger::default();
+        mock_ledger.expect_current_committee().returning(move || Ok(committee.clone()));
+        mock_ledger.expect_contains_transmission().returning(|_| Ok(false));
+        let ledger: Arc<dyn LedgerService<CurrentNetwork>> = Arc::new(mock_ledger);
+        // Initialize the storage.
+        let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
+
+        // Create the Worker.
+        let worker = Worker::new(0, 1, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
+        let data = |rng: &mut TestRng| Data::Buffer(Bytes::from((0..512).map(|_| rng.gen::<u8>()).collect::<Vec<_>>()));
+        let peer_ip = SocketAddr::from(([127, 0, 0, 1], 1234));
+        // Ensure there is no worker batch to seal without ready transmissions.
+        assert!(worker.seal_batch().is_none());
+
+        // Insert more ready transmissions than fit in a worker batch.
+        let transmission_ids = (0..MAX_TRANSMISSIONS_PER_WORKER_BATCH + 1)
+            .map(|_| {
+                let transmission_id = TransmissionID::Solution(PuzzleCommitment::from_g1_affine(rng.gen()));
+                worker.process_transmission_from_peer(peer_ip, transmission_id, Transmission::Solution(data(rng)));
+                transmission_id
+            })
+            .collect::<Vec<_>>();
+
+        // Seal the ready transmissions, and ensure the digests cover the oldest transmissions first.
+        let first = worker.seal_batch().unwrap();
+        let expected = transmission_ids[..MAX_TRANSMISSIONS_PER_WORKER_BATCH].iter().copied().collect();
+        assert_eq!(first, WorkerBatch::compute_digest(&expected).unwrap());
+        let second = worker.seal_batch().unwrap();
+        let remaining = IndexSet::from([transmission_ids[MAX_TRANSMISSIONS_PER_WORKER_BATCH]]);
+        assert_eq!(second, WorkerBatch::compute_digest(&remaining).unwrap());
+        // Ensure the sealed transmissions are not sealed again, but remain available until they are taken.
+        assert!(worker.seal_batch().is_none());
+        assert_eq!(worker.num_sealed_transmissions(), transmission_ids.len());
+        assert!(transmission_ids.iter().all(|transmission_id| worker.contains_transmission(*transmission_id)));
+
+        // Take the first worker batch, and ensure its transmissions leave the ready queue.
+        let taken = worker.take_batch(first).map(|(transmission_id, _)| transmission_id).collect::<IndexSet<_>>();
+        assert_eq!(taken, expected);
+        assert_eq!(worker.num_transmissions(), 1);
+        assert_eq!(worker.num_sealed_transmissions(), 1);
+        // Ensure a worker batch is taken only once.
+        assert_eq!(worker.take_batch(first).count(), 0);
+    }
+
     #[tokio::test]
     async fn test_send_transmission() {
         let rng = &mut TestRng::default();
@@ -564,7 +705,7 @@ mod tests {
         let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
 
         // Create the Worker.
-        let worker = Worker::new(0, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
+        let worker = Worker::new(0, 1, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
         let transmission_id = TransmissionID::Solution(PuzzleCommitment::from_g1_affine(rng.gen()));
         let worker_ = worker.clone();
         let peer_ip = SocketAddr::from(([127, 0, 0, 1], 1234));
@@ -600,7 +741,7 @@ mod tests {
         let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
 
         // Create the Worker.
-        let worker = Worker::new(0, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
+        let worker = Worker::new(0, 1, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
         let puzzle = PuzzleCommitment::from_g1_affine(rng.gen());
         let transmission_id = TransmissionID::Solution(puzzle);
         let worker_ = worker.clone();
@@ -638,7 +779,7 @@ mod tests {
         let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
 
         // Create the Worker.
-        let worker = Worker::new(0, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
+        let worker = Worker::new(0, 1, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
         let puzzle = PuzzleCommitment::from_g1_affine(rng.gen());
         let transmission_id = TransmissionID::Solution(puzzle);
         let worker_ = worker.clone();
@@ -676,7 +817,7 @@ mod tests {
         let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
 
         // Create the Worker.
-        let worker = Worker::new(0, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
+        let worker = Worker::new(0, 1, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
         let transaction_id: <CurrentNetwork as Network>::TransactionID = Field::<CurrentNetwork>::rand(&mut rng).into();
         let transmission_id = TransmissionID::Transaction(transaction_id);
         let worker_ = worker.clone();
@@ -714,7 +855,7 @@ mod tests {
         let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
 
         // Create the Worker.
-        let worker = Worker::new(0, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
+        let worker = Worker::new(0, 1, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
         let transaction_id: <CurrentNetwork as Network>::TransactionID = Field::<CurrentNetwork>::rand(&mut rng).into();
         let transmission_id = TransmissionID::Transaction(transaction_id);
         let worker_ = worker.clone();
@@ -731,6 +872,17 @@ mod tests {
         assert!(!worker.pending.contains(transmission_id));
         assert!(!worker.ready.contains(transmission_id));
     }
+
+    #[test]
+    fn test_num_workers() {
+        // Ensure the number of workers must be within bounds.
+        assert!(check_num_workers(0).is_err());
+        assert!(check_num_workers(MAX_WORKERS + 1).is_err());
+        assert!((1..=MAX_WORKERS).all(|num_workers| check_num_workers(num_workers).is_ok()));
+        // Ensure the ready queues are split evenly among the workers.
+        assert_eq!(max_transmissions_per_worker(1), MAX_TRANSMISSIONS_PER_BATCH);
+        assert_eq!(max_transmissions_per_worker(MAX_WORKERS), MAX_TRANSMISSIONS_PER_BATCH / MAX_WORKERS as usize);
+    }
 }
 
 #[cfg(test)]
@@ -769,7 +921,7 @@ mod prop_tests {
     ) {
         let committee = new_test_committee(4);
         let ledger: Arc<dyn LedgerService<CurrentNetwork>> = Arc::new(MockLedgerService::new(committee));
-        let worker = Worker::new(id, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
+        let worker = Worker::new(id, MAX_WORKERS, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
         assert_eq!(worker.id(), id);
     }
 
@@ -781,7 +933,7 @@ mod prop_tests {
     ) {
         let committee = new_test_committee(4);
         let ledger: Arc<dyn LedgerService<CurrentNetwork>> = Arc::new(MockLedgerService::new(committee));
-        let worker = Worker::new(id, Arc::new(gateway), storage, ledger, Default::default());
+        let worker = Worker::new(id, MAX_WORKERS, Arc::new(gateway), storage, ledger, Default::default());
         // TODO once Worker implements Debug, simplify this with `unwrap_err`
         if let Err(error) = worker {
             assert_eq!(error.to_string(), format!("Invalid worker ID '{}'", id));
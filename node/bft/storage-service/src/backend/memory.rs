@@ -0,0 +1,159 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{BatchOperation, IndexStorage, Keyspace};
+use snarkvm::prelude::{bail, ensure, Result};
+
+use parking_lot::RwLock;
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{BufReader, Read},
+    path::Path,
+};
+
+/// An in-memory storage backend.
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    /// The map of `key` to `value` entries.
+    entries: RwLock<BTreeMap<Vec<u8>, Vec<u8>>>,
+}
+
+impl MemoryStorage {
+    /// Initializes a new in-memory storage backend.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Initializes a new in-memory storage backend from the checkpoint at the given path.
+    pub fn open_checkpoint(path: &Path) -> Result<Self> {
+        let mut reader = BufReader::new(fs::File::open(path)?);
+        let mut entries = BTreeMap::new();
+        // Read the entries, until the end of the checkpoint.
+        while let Some(key) = read_chunk(&mut reader)? {
+            let Some(value) = read_chunk(&mut reader)? else {
+                bail!("The storage checkpoint at '{}' is truncated", path.display())
+            };
+            entries.insert(key, value);
+        }
+        Ok(Self { entries: RwLock::new(entries) })
+    }
+}
+
+impl IndexStorage for MemoryStorage {
+    /// Returns the value for the given key, if one exists.
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Keyspace::of(key)?;
+        Ok(self.entries.read().get(key).cloned())
+    }
+
+    /// Inserts the value for the given key, replacing any previous value.
+    fn put(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        Keyspace::of(key)?;
+        self.entries.write().insert(key.to_vec(), value.to_vec());
+        Ok(())
+    }
+
+    /// Removes the value for the given key, if one exists.
+    fn delete(&self, key: &[u8]) -> Result<()> {
+        Keyspace::of(key)?;
+        self.entries.write().remove(key);
+        Ok(())
+    }
+
+    /// Applies the given operations atomically, in order.
+    fn batch(&self, operations: Vec<BatchOperation>) -> Result<()> {
+        // Ensure every key belongs to a keyspace, before any operation is applied.
+        for operation in &operations {
+            match operation {
+                BatchOperation::Put(key, _) | BatchOperation::Delete(key) => Keyspace::of(key)?,
+            };
+        }
+        // Hold the write lock for the whole batch, so that readers never observe a partial batch.
+        let mut entries = self.entries.write();
+        for operation in operations {
+            match operation {
+                BatchOperation::Put(key, value) => entries.insert(key, value),
+                BatchOperation::Delete(key) => entries.remove(&key),
+            };
+        }
+        Ok(())
+    }
+
+    /// Returns the `(key, value)` entries whose key starts with the given prefix, in key order.
+    fn iterate(&self, prefix: &[u8]) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        Ok(self
+            .entries
+            .read()
+            .range(prefix.to_vec()..)
+            .take_while(|(key, _)| key.starts_with(prefix))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect())
+    }
+
+    /// Writes a consistent copy of the storage to the given path, which must not exist yet.
+    ///
+    /// Each entry is written as the length-prefixed key, followed by the length-prefixed value.
+    fn checkpoint(&self, path: &Path) -> Result<()> {
+        ensure!(!path.exists(), "The storage checkpoint path '{}' already exists", path.display());
+        let mut buffer = Vec::new();
+        for (key, value) in self.entries.read().iter() {
+            for chunk in [key, value] {
+                buffer.extend_from_slice(&u32::try_from(chunk.len())?.to_le_bytes());
+                buffer.extend_from_slice(chunk);
+            }
+        }
+        fs::write(path, buffer)?;
+        Ok(())
+    }
+}
+
+/// Reads a length-prefixed chunk from the given reader, or returns `None` if the reader is at its end.
+fn read_chunk<R: Read>(reader: &mut R) -> Result<Option<Vec<u8>>> {
+    let mut length = [0u8; 4];
+    match reader.read_exact(&mut length) {
+        Ok(()) => (),
+        Err(error) if error.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(error) => return Err(error.into()),
+    }
+    let mut chunk = vec![0u8; u32::from_le_bytes(length) as usize];
+    reader.read_exact(&mut chunk)?;
+    Ok(Some(chunk))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::test_helpers::check_storage;
+
+    #[test]
+    fn test_memory_storage() {
+        let path = std::env::temp_dir().join(format!("memory-storage-{}", std::process::id()));
+
+        // Check the storage backend.
+        let storage = MemoryStorage::new();
+        check_storage(&storage, &path);
+
+        // Ensure the checkpoint restores the entries at the time it was written.
+        let restored = MemoryStorage::open_checkpoint(&path).unwrap();
+        assert_eq!(restored.iterate(b"").unwrap().len(), 3);
+        assert_eq!(restored.get(b"\x01a/3").unwrap(), Some(b"three".to_vec()));
+        assert_eq!(restored.get(b"\x02d/1").unwrap(), None);
+
+        // Ensure a truncated checkpoint is rejected.
+        fs::write(&path, [1, 0, 0, 0, 7]).unwrap();
+        assert!(MemoryStorage::open_checkpoint(&path).is_err());
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,187 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(feature = "memory")]
+mod memory;
+#[cfg(feature = "memory")]
+pub use memory::*;
+
+#[cfg(feature = "persistent")]
+mod rocksdb;
+#[cfg(feature = "persistent")]
+pub use self::rocksdb::*;
+
+use snarkvm::prelude::{bail, Result};
+
+use std::{fmt::Debug, path::Path};
+
+/// The keyspaces of the indexes, each of which is identified by the first byte of its keys.
+///
+/// Note: A backend may store each keyspace apart, e.g. the RocksDB backend stores each one in its own column family.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Keyspace {
+    /// The metadata of the indexes, such as the latest indexed block height.
+    Metadata,
+    /// The activity of the addresses, which is read in ranges by address.
+    Addresses,
+    /// The location of each transaction, which is read by transaction ID.
+    Transactions,
+    /// The transaction of each record commitment, which is read by commitment.
+    Commitments,
+    /// The transaction of each record serial number, which is read by serial number.
+    SerialNumbers,
+}
+
+impl Keyspace {
+    /// The keyspaces, in order of their prefix.
+    pub const ALL: [Self; 5] =
+        [Self::Metadata, Self::Addresses, Self::Transactions, Self::Commitments, Self::SerialNumbers];
+
+    /// Returns the prefix of the keys of the keyspace.
+    pub const fn prefix(&self) -> u8 {
+        match self {
+            Self::Metadata => 0,
+            Self::Addresses => 1,
+            Self::Transactions => 2,
+            Self::Commitments => 3,
+            Self::SerialNumbers => 4,
+        }
+    }
+
+    /// Returns the keyspace of the given key, which is identified by its first byte.
+    pub fn of(key: &[u8]) -> Result<Self> {
+        match key.first() {
+            Some(prefix) => match Self::ALL.into_iter().find(|keyspace| keyspace.prefix() == *prefix) {
+                Some(keyspace) => Ok(keyspace),
+                None => bail!("The key prefix '{prefix}' does not belong to a keyspace"),
+            },
+            None => bail!("The key is empty, and does not belong to a keyspace"),
+        }
+    }
+}
+
+/// A write operation in a storage batch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BatchOperation {
+    /// Inserts the value for the key, replacing any previous value.
+    Put(Vec<u8>, Vec<u8>),
+    /// Removes the value for the key, if one exists.
+    Delete(Vec<u8>),
+}
+
+/// A key-value storage backend of the indexes that the node maintains alongside the ledger (e.g. the address index).
+///
+/// Keys are ordered lexicographically, so that related entries can be grouped under a common key prefix.
+/// The first byte of each key is the prefix of its `Keyspace`, and a key outside of every keyspace is rejected.
+///
+/// Note: The ledger itself is stored through snarkVM's `ConsensusStorage`, which opens its own RocksDB instance
+/// at a fixed path with fixed options, so it is not backed by this trait.
+pub trait IndexStorage: Debug + Send + Sync {
+    /// Returns the value for the given key, if one exists.
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>>;
+
+    /// Inserts the value for the given key, replacing any previous value.
+    fn put(&self, key: &[u8], value: &[u8]) -> Result<()>;
+
+    /// Removes the value for the given key, if one exists.
+    fn delete(&self, key: &[u8]) -> Result<()>;
+
+    /// Applies the given operations atomically, in order.
+    fn batch(&self, operations: Vec<BatchOperation>) -> Result<()>;
+
+    /// Returns the `(key, value)` entries whose key starts with the given prefix, in key order.
+    fn iterate(&self, prefix: &[u8]) -> Result<Vec<(Vec<u8>, Vec<u8>)>>;
+
+    /// Writes a consistent copy of the storage to the given path, which must not exist yet.
+    fn checkpoint(&self, path: &Path) -> Result<()>;
+
+    /// Returns `true` if the storage contains a value for the given key.
+    fn contains(&self, key: &[u8]) -> Result<bool> {
+        Ok(self.get(key)?.is_some())
+    }
+}
+
+#[cfg(all(test, any(feature = "memory", feature = "persistent")))]
+pub(crate) mod test_helpers {
+    use super::*;
+
+    /// Checks the given storage backend against the expected behavior of the `IndexStorage` trait,
+    /// writing a checkpoint to the given path along the way.
+    ///
+    /// Note: The keys start with the prefix of their keyspace, e.g. `\x01` for the addresses.
+    pub(crate) fn check_storage<S: IndexStorage>(storage: &S, checkpoint_path: &Path) {
+        // Ensure the storage starts empty.
+        assert_eq!(storage.get(b"\x01a/1").unwrap(), None);
+        assert!(storage.iterate(b"").unwrap().is_empty());
+
+        // Insert, replace, and remove values.
+        storage.put(b"\x01a/1", b"one").unwrap();
+        storage.put(b"\x01a/2", b"two").unwrap();
+        storage.put(b"\x01a/2", b"deux").unwrap();
+        storage.put(b"\x01b/1", b"uno").unwrap();
+        assert_eq!(storage.get(b"\x01a/2").unwrap(), Some(b"deux".to_vec()));
+        storage.delete(b"\x01b/1").unwrap();
+        assert!(!storage.contains(b"\x01b/1").unwrap());
+
+        // Apply a batch across keyspaces, in order.
+        storage
+            .batch(vec![
+                BatchOperation::Put(b"\x01a/3".to_vec(), b"three".to_vec()),
+                BatchOperation::Delete(b"\x01a/1".to_vec()),
+                BatchOperation::Put(b"\x01b/2".to_vec(), b"dos".to_vec()),
+                BatchOperation::Delete(b"\x01b/2".to_vec()),
+                BatchOperation::Put(b"\x00c/1".to_vec(), b"eins".to_vec()),
+            ])
+            .unwrap();
+
+        // Ensure the keys outside of every keyspace are rejected, and a rejected batch is not applied.
+        assert!(storage.put(b"", b"none").is_err());
+        assert!(storage.put(b"\xffa/1", b"none").is_err());
+        let operations = vec![
+            BatchOperation::Put(b"\x02a/1".to_vec(), b"none".to_vec()),
+            BatchOperation::Put(b"\xffa/1".to_vec(), b"none".to_vec()),
+        ];
+        assert!(storage.batch(operations).is_err());
+        assert_eq!(storage.get(b"\x02a/1").unwrap(), None);
+
+        // Ensure the entries are iterated by prefix, in key order, across keyspaces.
+        let expected = vec![(b"\x01a/2".to_vec(), b"deux".to_vec()), (b"\x01a/3".to_vec(), b"three".to_vec())];
+        assert_eq!(storage.iterate(b"\x01a/").unwrap(), expected);
+        assert!(storage.iterate(b"\x01b/").unwrap().is_empty());
+        let keys = storage.iterate(b"").unwrap().into_iter().map(|(key, _)| key).collect::<Vec<_>>();
+        assert_eq!(keys, vec![b"\x00c/1".to_vec(), b"\x01a/2".to_vec(), b"\x01a/3".to_vec()]);
+
+        // Write a checkpoint, and ensure later writes do not change it.
+        storage.checkpoint(checkpoint_path).unwrap();
+        storage.put(b"\x02d/1", b"un").unwrap();
+        assert!(storage.checkpoint(checkpoint_path).is_err());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keyspace() {
+        // Ensure each keyspace is identified by its prefix, in order.
+        for (i, keyspace) in Keyspace::ALL.into_iter().enumerate() {
+            assert_eq!(keyspace.prefix() as usize, i);
+            assert_eq!(Keyspace::of(&[keyspace.prefix(), 7]).unwrap(), keyspace);
+        }
+        // Ensure the keys outside of every keyspace are rejected.
+        assert!(Keyspace::of(&[]).is_err());
+        assert!(Keyspace::of(&[Keyspace::ALL.len() as u8]).is_err());
+    }
+}
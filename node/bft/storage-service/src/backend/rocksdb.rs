@@ -0,0 +1,348 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{BatchOperation, IndexStorage, Keyspace};
+use snarkvm::prelude::{anyhow, ensure, Result};
+
+use rocksdb::{
+    checkpoint::Checkpoint,
+    BlockBasedOptions,
+    Cache,
+    ColumnFamily,
+    ColumnFamilyDescriptor,
+    DBCompactionStyle,
+    DBCompressionType,
+    Direction,
+    IteratorMode,
+    Options,
+    WriteBatch,
+    DB,
+};
+use std::{fmt, path::Path, sync::Arc};
+
+/// The size of the block cache, which is shared by the column families (in bytes).
+const BLOCK_CACHE_SIZE: usize = 128 * 1024 * 1024;
+/// The size of the write buffer of each column family of the indexes (in bytes).
+const WRITE_BUFFER_SIZE: usize = 32 * 1024 * 1024;
+/// The size of the write buffer of the metadata column family (in bytes).
+const METADATA_WRITE_BUFFER_SIZE: usize = 1024 * 1024;
+/// The maximum total size of the write-ahead log (in bytes).
+const MAX_TOTAL_WAL_SIZE: u64 = 64 * 1024 * 1024;
+/// The maximum number of background compaction and flush jobs.
+const MAX_BACKGROUND_JOBS: i32 = 2;
+
+/// Returns the name of the column family of the given keyspace.
+const fn column_family_name(keyspace: Keyspace) -> &'static str {
+    match keyspace {
+        Keyspace::Metadata => "metadata",
+        Keyspace::Addresses => "addresses",
+        Keyspace::Transactions => "transactions",
+        Keyspace::Commitments => "commitments",
+        Keyspace::SerialNumbers => "serial_numbers",
+    }
+}
+
+/// Returns the options of the column family of the given keyspace, which shares the given block cache.
+fn column_family_options(keyspace: Keyspace, cache: &Cache) -> Options {
+    let mut table_options = BlockBasedOptions::default();
+    table_options.set_block_cache(cache);
+    // Keep the index and filter blocks in the cache, so they are bounded by the cache size.
+    table_options.set_cache_index_and_filter_blocks(true);
+    table_options.set_pin_l0_filter_and_index_blocks_in_cache(true);
+
+    let mut options = Options::default();
+    // Note: Every keyspace is read by key, so level compaction bounds the tables per lookup.
+    options.set_compaction_style(DBCompactionStyle::Level);
+    options.set_level_compaction_dynamic_level_bytes(true);
+    match keyspace {
+        // The metadata is a handful of keys that are rewritten on every block, and fit in a small write buffer.
+        Keyspace::Metadata => {
+            options.set_write_buffer_size(METADATA_WRITE_BUFFER_SIZE);
+            options.set_compression_type(DBCompressionType::None);
+        }
+        // The history of an address is read in ranges, which larger data blocks serve in fewer reads.
+        Keyspace::Addresses => {
+            table_options.set_block_size(16 * 1024);
+            options.set_write_buffer_size(WRITE_BUFFER_SIZE);
+            options.set_compression_type(DBCompressionType::Lz4);
+        }
+        // The transactions and records are read by a single hashed key, which smaller data blocks serve with
+        // less read amplification, and a bloom filter answers without a read if the key is missing.
+        Keyspace::Transactions | Keyspace::Commitments | Keyspace::SerialNumbers => {
+            table_options.set_block_size(4 * 1024);
+            table_options.set_bloom_filter(10.0, false);
+            options.set_write_buffer_size(WRITE_BUFFER_SIZE);
+            options.set_compression_type(DBCompressionType::Lz4);
+        }
+    }
+    options.set_block_based_table_factory(&table_options);
+    options
+}
+
+/// Returns the descriptors of the column families of every keyspace, which share one block cache.
+fn column_family_descriptors() -> Vec<ColumnFamilyDescriptor> {
+    let cache = Cache::new_lru_cache(BLOCK_CACHE_SIZE);
+    Keyspace::ALL
+        .into_iter()
+        .map(|keyspace| {
+            ColumnFamilyDescriptor::new(column_family_name(keyspace), column_family_options(keyspace, &cache))
+        })
+        .collect()
+}
+
+/// The mode in which the RocksDB storage backend is opened.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AccessMode {
+    /// The storage is the primary instance, which takes the lock of the database, and reads and writes.
+    ReadWrite,
+    /// The storage reads the database as it was when it was opened, without taking the lock.
+    ReadOnly,
+    /// The storage follows a primary instance, without taking the lock, and reads its writes after a catch-up.
+    Secondary,
+}
+
+/// A RocksDB storage backend, which stores each keyspace in its own column family of the database.
+#[derive(Clone)]
+pub struct RocksStorage {
+    /// The RocksDB instance.
+    database: Arc<DB>,
+    /// The mode in which the database is opened.
+    mode: AccessMode,
+}
+
+impl fmt::Debug for RocksStorage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RocksStorage").field("path", &self.database.path()).field("mode", &self.mode).finish()
+    }
+}
+
+impl RocksStorage {
+    /// Opens the RocksDB storage backend at the given path, creating it if it does not exist.
+    pub fn open(path: &Path) -> Result<Self> {
+        let mut options = Options::default();
+        options.create_if_missing(true);
+        options.create_missing_column_families(true);
+        options.set_max_background_jobs(MAX_BACKGROUND_JOBS);
+        // Note: The log is only recycled once every column family has flushed its writes, so its size is capped
+        // to flush the rarely written column families (e.g. the metadata) in time.
+        options.set_max_total_wal_size(MAX_TOTAL_WAL_SIZE);
+        // Open the database with every column family, each with its own options.
+        let database = DB::open_cf_descriptors(&options, path, column_family_descriptors())?;
+        Ok(Self { database: Arc::new(database), mode: AccessMode::ReadWrite })
+    }
+
+    /// Opens the RocksDB storage backend at the given path in read-only mode.
+    ///
+    /// Note: The storage does not take the lock of the database, so it may be opened while a node is running,
+    /// but it only reads the entries that were flushed when it was opened. To follow a running node,
+    /// use `RocksStorage::open_secondary` instead.
+    pub fn open_read_only(path: &Path) -> Result<Self> {
+        ensure!(path.exists(), "The storage path '{}' does not exist", path.display());
+        let database =
+            DB::open_cf_descriptors_read_only(&Options::default(), path, column_family_descriptors(), false)?;
+        Ok(Self { database: Arc::new(database), mode: AccessMode::ReadOnly })
+    }
+
+    /// Opens the RocksDB storage backend at the given path as a secondary instance, which keeps its own logs
+    /// at the given secondary path.
+    ///
+    /// Note: The storage does not take the lock of the database, so it may be opened while a node is running,
+    /// and it reads the writes of the node after each call to `RocksStorage::try_catch_up`.
+    pub fn open_secondary(path: &Path, secondary_path: &Path) -> Result<Self> {
+        ensure!(path.exists(), "The storage path '{}' does not exist", path.display());
+        let mut options = Options::default();
+        // Note: A secondary instance must keep every table file open, as the primary may delete them at any time.
+        options.set_max_open_files(-1);
+        let database =
+            DB::open_cf_descriptors_as_secondary(&options, path, secondary_path, column_family_descriptors())?;
+        Ok(Self { database: Arc::new(database), mode: AccessMode::Secondary })
+    }
+
+    /// Returns the mode in which the storage is opened.
+    pub const fn mode(&self) -> AccessMode {
+        self.mode
+    }
+
+    /// Reads the latest writes of the primary instance, if the storage is a secondary instance.
+    pub fn try_catch_up(&self) -> Result<()> {
+        ensure!(self.mode == AccessMode::Secondary, "Only a secondary storage can catch up with the primary");
+        Ok(self.database.try_catch_up_with_primary()?)
+    }
+
+    /// Ensures the storage is opened in read-write mode.
+    fn ensure_writable(&self) -> Result<()> {
+        ensure!(
+            self.mode == AccessMode::ReadWrite,
+            "The storage is opened in {:?} mode, and cannot be written",
+            self.mode
+        );
+        Ok(())
+    }
+
+    /// Returns the handle of the column family of the given keyspace.
+    fn handle(&self, keyspace: Keyspace) -> Result<&ColumnFamily> {
+        let name = column_family_name(keyspace);
+        self.database.cf_handle(name).ok_or_else(|| anyhow!("The column family '{name}' is missing"))
+    }
+
+    /// Returns the handle of the column family of the keyspace of the given key.
+    fn handle_of(&self, key: &[u8]) -> Result<&ColumnFamily> {
+        self.handle(Keyspace::of(key)?)
+    }
+
+    /// Appends the entries of the given column family whose key starts with the given prefix, in key order.
+    fn iterate_column_family(
+        &self,
+        handle: &ColumnFamily,
+        prefix: &[u8],
+        entries: &mut Vec<(Vec<u8>, Vec<u8>)>,
+    ) -> Result<()> {
+        for entry in self.database.iterator_cf(handle, IteratorMode::From(prefix, Direction::Forward)) {
+            let (key, value) = entry?;
+            // Stop at the first key past the prefix.
+            if !key.starts_with(prefix) {
+                break;
+            }
+            entries.push((key.into_vec(), value.into_vec()));
+        }
+        Ok(())
+    }
+}
+
+impl IndexStorage for RocksStorage {
+    /// Returns the value for the given key, if one exists.
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self.database.get_cf(self.handle_of(key)?, key)?)
+    }
+
+    /// Inserts the value for the given key, replacing any previous value.
+    fn put(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.ensure_writable()?;
+        Ok(self.database.put_cf(self.handle_of(key)?, key, value)?)
+    }
+
+    /// Removes the value for the given key, if one exists.
+    fn delete(&self, key: &[u8]) -> Result<()> {
+        self.ensure_writable()?;
+        Ok(self.database.delete_cf(self.handle_of(key)?, key)?)
+    }
+
+    /// Applies the given operations atomically, in order.
+    ///
+    /// Note: The operations on every column family are written in one batch, which RocksDB applies atomically.
+    fn batch(&self, operations: Vec<BatchOperation>) -> Result<()> {
+        self.ensure_writable()?;
+        let mut batch = WriteBatch::default();
+        for operation in operations {
+            match operation {
+                BatchOperation::Put(key, value) => batch.put_cf(self.handle_of(&key)?, key, value),
+                BatchOperation::Delete(key) => batch.delete_cf(self.handle_of(&key)?, key),
+            }
+        }
+        Ok(self.database.write(batch)?)
+    }
+
+    /// Returns the `(key, value)` entries whose key starts with the given prefix, in key order.
+    fn iterate(&self, prefix: &[u8]) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        let mut entries = Vec::new();
+        if prefix.is_empty() {
+            // Iterate every column family, in key order, as the keyspaces are in order of their prefix.
+            for keyspace in Keyspace::ALL {
+                self.iterate_column_family(self.handle(keyspace)?, prefix, &mut entries)?;
+            }
+        } else {
+            self.iterate_column_family(self.handle_of(prefix)?, prefix, &mut entries)?;
+        }
+        Ok(entries)
+    }
+
+    /// Writes a consistent copy of the storage to the given path, which must not exist yet.
+    ///
+    /// Note: The checkpoint contains every column family, and hard-links the immutable table files,
+    /// so it is cheap to write on the same filesystem.
+    fn checkpoint(&self, path: &Path) -> Result<()> {
+        ensure!(!path.exists(), "The storage checkpoint path '{}' already exists", path.display());
+        // Note: A checkpoint flushes the memtables, which only the primary instance can do.
+        self.ensure_writable()?;
+        Ok(Checkpoint::new(&self.database)?.create_checkpoint(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::test_helpers::check_storage;
+
+    #[test]
+    fn test_rocks_storage() {
+        let directory = std::env::temp_dir().join(format!("rocks-storage-{}", std::process::id()));
+        let checkpoint_path = directory.join("checkpoint");
+
+        // Check the storage backend.
+        let storage = RocksStorage::open(&directory.join("storage")).unwrap();
+        check_storage(&storage, &checkpoint_path);
+
+        // Ensure the checkpoint restores the entries at the time it was written.
+        let restored = RocksStorage::open(&checkpoint_path).unwrap();
+        assert_eq!(restored.iterate(b"").unwrap().len(), 3);
+        assert_eq!(restored.get(b"\x01a/3").unwrap(), Some(b"three".to_vec()));
+        assert_eq!(restored.get(b"\x02d/1").unwrap(), None);
+
+        drop((storage, restored));
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_rocks_storage_read_only() {
+        let directory = std::env::temp_dir().join(format!("rocks-storage-read-only-{}", std::process::id()));
+        let path = directory.join("storage");
+
+        // Ensure a missing storage is not created.
+        assert!(RocksStorage::open_read_only(&path).is_err());
+        assert!(RocksStorage::open_secondary(&path, &directory.join("secondary")).is_err());
+        assert!(!path.exists());
+
+        // Open the primary, and write an entry.
+        let primary = RocksStorage::open(&path).unwrap();
+        primary.put(b"\x01a/1", b"one").unwrap();
+        primary.database.flush_cf(primary.handle(Keyspace::Addresses).unwrap()).unwrap();
+
+        // Ensure the storage opens in read-only mode while the primary is open.
+        let read_only = RocksStorage::open_read_only(&path).unwrap();
+        assert_eq!(read_only.mode(), AccessMode::ReadOnly);
+        assert_eq!(read_only.get(b"\x01a/1").unwrap(), Some(b"one".to_vec()));
+        assert!(read_only.try_catch_up().is_err());
+
+        // Ensure the secondary reads the writes of the primary after a catch-up.
+        let secondary = RocksStorage::open_secondary(&path, &directory.join("secondary")).unwrap();
+        assert_eq!(secondary.mode(), AccessMode::Secondary);
+        primary.put(b"\x01a/2", b"two").unwrap();
+        secondary.try_catch_up().unwrap();
+        assert_eq!(secondary.get(b"\x01a/2").unwrap(), Some(b"two".to_vec()));
+        assert_eq!(secondary.iterate(b"\x01a/").unwrap().len(), 2);
+
+        // Ensure neither mode writes to the storage.
+        for storage in [&read_only, &secondary] {
+            assert!(storage.put(b"\x01a/3", b"three").is_err());
+            assert!(storage.delete(b"\x01a/1").is_err());
+            assert!(storage.batch(vec![BatchOperation::Delete(b"\x01a/1".to_vec())]).is_err());
+            assert!(storage.checkpoint(&directory.join("checkpoint")).is_err());
+        }
+        assert_eq!(primary.get(b"\x01a/1").unwrap(), Some(b"one".to_vec()));
+        assert_eq!(primary.get(b"\x01a/3").unwrap(), None);
+
+        drop((primary, read_only, secondary));
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -25,5 +25,11 @@ pub mod persistent;
 #[cfg(feature = "persistent")]
 pub use persistent::*;
 
+pub mod backend;
+pub use backend::*;
+
+pub mod record;
+pub use record::*;
+
 pub mod traits;
 pub use traits::*;
@@ -15,7 +15,7 @@
 use crate::StorageService;
 use snarkvm::{
     ledger::{
-        narwhal::{BatchHeader, Transmission, TransmissionID},
+        narwhal::{BatchCertificate, BatchHeader, Transmission, TransmissionID},
         store::helpers::{
             rocksdb::{
                 internal::{self, BFTMap, Database, MapID},
@@ -28,29 +28,62 @@ use snarkvm::{
     prelude::{bail, Field, Network, Result},
 };
 
-use indexmap::{indexset, IndexSet};
+use indexmap::{indexset, IndexMap, IndexSet};
 use snarkvm::ledger::store::cow_to_cloned;
 use std::{borrow::Cow, collections::HashMap};
 use tracing::error;
 
+/// The key of the batch proposal of this node.
+const PROPOSAL_KEY: u8 = 0;
+
+/// The RocksDB map IDs of the DAG.
+///
+/// Note: These IDs are reserved far above the map IDs of the ledger, so that the two never collide.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u16)]
+enum DAGMap {
+    Certificates = 0xD000,
+    Proposal = 0xD001,
+}
+
+impl From<DAGMap> for u16 {
+    fn from(map_id: DAGMap) -> Self {
+        map_id as u16
+    }
+}
+
 /// A BFT persistent storage service.
 #[derive(Debug)]
 pub struct BFTPersistentStorage<N: Network> {
     /// The map of `transmission ID` to `(transmission, certificate IDs)` entries.
     transmissions: DataMap<TransmissionID<N>, (Transmission<N>, IndexSet<Field<N>>)>,
+    /// The map of `certificate ID` to `certificate` entries.
+    certificates: DataMap<Field<N>, BatchCertificate<N>>,
+    /// The batch proposal of this node, as its batch header and transmissions.
+    proposal: DataMap<u8, (BatchHeader<N>, IndexMap<TransmissionID<N>, Transmission<N>>)>,
 }
 
 impl<N: Network> BFTPersistentStorage<N> {
     /// Initializes a new BFT persistent storage service.
     pub fn open(dev: Option<u16>) -> Result<Self> {
-        Ok(Self { transmissions: internal::RocksDB::open_map(N::ID, dev, MapID::BFT(BFTMap::Transmissions))? })
+        Ok(Self {
+            transmissions: internal::RocksDB::open_map(N::ID, dev, MapID::BFT(BFTMap::Transmissions))?,
+            certificates: internal::RocksDB::open_map(N::ID, dev, DAGMap::Certificates)?,
+            proposal: internal::RocksDB::open_map(N::ID, dev, DAGMap::Proposal)?,
+        })
     }
 
     /// Initializes a new BFT persistent storage service.
     #[cfg(any(test, feature = "test"))]
     pub fn open_testing(temp_dir: std::path::PathBuf, dev: Option<u16>) -> Result<Self> {
         Ok(Self {
-            transmissions: internal::RocksDB::open_map_testing(temp_dir, dev, MapID::BFT(BFTMap::Transmissions))?,
+            transmissions: internal::RocksDB::open_map_testing(
+                temp_dir.clone(),
+                dev,
+                MapID::BFT(BFTMap::Transmissions),
+            )?,
+            certificates: internal::RocksDB::open_map_testing(temp_dir.clone(), dev, DAGMap::Certificates)?,
+            proposal: internal::RocksDB::open_map_testing(temp_dir, dev, DAGMap::Proposal)?,
         })
     }
 }
@@ -191,6 +224,55 @@ impl<N: Network> StorageService<N> for BFTPersistentStorage<N> {
         }
     }
 
+    /// Inserts the given certificate into storage, so that it is restored after a restart.
+    fn insert_certificate(&self, certificate: &BatchCertificate<N>) {
+        let certificate_id = certificate.id();
+        if let Err(e) = self.certificates.insert(certificate_id, certificate.clone()) {
+            error!("Failed to insert certificate {certificate_id} into storage - {e}");
+        }
+    }
+
+    /// Removes the certificate for the given `certificate ID` from storage.
+    fn remove_certificate(&self, certificate_id: &Field<N>) {
+        if let Err(e) = self.certificates.remove(certificate_id) {
+            error!("Failed to remove certificate {certificate_id} from storage - {e}");
+        }
+    }
+
+    /// Returns the certificates to restore after a restart.
+    fn certificates(&self) -> Vec<BatchCertificate<N>> {
+        self.certificates.values_confirmed().map(|certificate| cow_to_cloned!(certificate)).collect()
+    }
+
+    /// Inserts the batch proposal of this node into storage, replacing any previous proposal.
+    fn insert_proposal(
+        &self,
+        batch_header: &BatchHeader<N>,
+        transmissions: &IndexMap<TransmissionID<N>, Transmission<N>>,
+    ) {
+        if let Err(e) = self.proposal.insert(PROPOSAL_KEY, (batch_header.clone(), transmissions.clone())) {
+            error!("Failed to insert the batch proposal for round {} into storage - {e}", batch_header.round());
+        }
+    }
+
+    /// Removes the batch proposal of this node from storage.
+    fn remove_proposal(&self) {
+        if let Err(e) = self.proposal.remove(&PROPOSAL_KEY) {
+            error!("Failed to remove the batch proposal from storage - {e}");
+        }
+    }
+
+    /// Returns the batch proposal of this node to restore after a restart, if one exists.
+    fn proposal(&self) -> Option<(BatchHeader<N>, IndexMap<TransmissionID<N>, Transmission<N>>)> {
+        match self.proposal.get_confirmed(&PROPOSAL_KEY) {
+            Ok(proposal) => proposal.map(|proposal| cow_to_cloned!(proposal)),
+            Err(e) => {
+                error!("Failed to get the batch proposal from storage - {e}");
+                None
+            }
+        }
+    }
+
     /// Returns a HashMap over the `(transmission ID, (transmission, certificate IDs))` entries.
     #[cfg(any(test, feature = "test"))]
     fn as_hashmap(&self) -> HashMap<TransmissionID<N>, (Transmission<N>, IndexSet<Field<N>>)> {
@@ -198,3 +280,35 @@ impl<N: Network> StorageService<N> for BFTPersistentStorage<N> {
         self.transmissions.iter_confirmed().map(|(k, v)| (cow_to_copied!(k), cow_to_cloned!(v))).collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{ledger::narwhal::batch_certificate::test_helpers::sample_batch_certificate, prelude::TestRng};
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_certificates_and_proposal() {
+        let rng = &mut TestRng::default();
+
+        // Initialize the storage.
+        let storage = BFTPersistentStorage::<CurrentNetwork>::open_testing(std::env::temp_dir(), None).unwrap();
+        assert!(storage.certificates().is_empty());
+        assert!(storage.proposal().is_none());
+
+        // Insert and remove a certificate.
+        let certificate = sample_batch_certificate(rng);
+        storage.insert_certificate(&certificate);
+        assert_eq!(storage.certificates(), vec![certificate.clone()]);
+        storage.remove_certificate(&certificate.id());
+        assert!(storage.certificates().is_empty());
+
+        // Insert and remove a batch proposal.
+        let batch_header = certificate.batch_header().clone();
+        storage.insert_proposal(&batch_header, &IndexMap::new());
+        assert_eq!(storage.proposal(), Some((batch_header, IndexMap::new())));
+        storage.remove_proposal();
+        assert!(storage.proposal().is_none());
+    }
+}
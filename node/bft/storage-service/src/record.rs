@@ -0,0 +1,269 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::{
+        block::{Block, Transaction},
+        narwhal::Data,
+    },
+    prelude::{bail, ensure, FromBytes, Network, Result, ToBytes},
+};
+
+use std::io::{Read, Write};
+
+/// The magic bytes at the start of the header of a versioned record.
+///
+/// Note: The unversioned records start with the snarkVM version byte `1`, so they never start with these bytes.
+const RECORD_MAGIC: [u8; 4] = *b"SREC";
+/// The latest encoding version of the records, which is the version they are written in.
+pub const LATEST_RECORD_VERSION: u16 = 1;
+/// The maximum size of the body of a record (in bytes).
+const MAX_RECORD_SIZE: u32 = 256 * 1024 * 1024; // 256 MiB
+
+/// The kind of a record.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum RecordKind {
+    /// A block.
+    Block = 0,
+    /// A transaction.
+    Transaction = 1,
+}
+
+impl RecordKind {
+    /// The kinds of records.
+    pub const ALL: [Self; 2] = [Self::Block, Self::Transaction];
+}
+
+impl TryFrom<u8> for RecordKind {
+    type Error = snarkvm::prelude::Error;
+
+    fn try_from(kind: u8) -> Result<Self> {
+        match kind {
+            0 => Ok(Self::Block),
+            1 => Ok(Self::Transaction),
+            _ => bail!("Invalid record kind '{kind}'"),
+        }
+    }
+}
+
+/// A record that is written to disk in a versioned encoding.
+///
+/// Note: The ledger storage holds the blocks and transactions as bodies without headers, as it is written by snarkVM.
+/// The encoding version of each kind of record in the ledger is recorded in the metadata of the ledger storage
+/// instead, and the ledger is migrated to each new encoding version, by reading it in the previous version.
+///
+/// A record is written as a header of the magic bytes, the kind (`u8`), the encoding version (`u16`),
+/// and the size of the body (`u32`), followed by the body. A new encoding version is added by writing
+/// the body in the new version, and reading each older version in `VersionedRecord::from_body`.
+pub trait VersionedRecord: Sized {
+    /// The kind of the record.
+    const KIND: RecordKind;
+
+    /// Returns the body of the record, in the latest encoding version.
+    fn to_body(&self) -> Result<Vec<u8>>;
+
+    /// Returns the record from its body, in the given encoding version.
+    fn from_body(version: u16, body: &[u8]) -> Result<Self>;
+
+    /// Reads the record in the unversioned encoding, which predates the record headers.
+    fn read_unversioned<R: Read>(reader: R) -> Result<Self>;
+}
+
+/// Writes the given record with its header, in the latest encoding version.
+pub fn write_record<T: VersionedRecord, W: Write>(record: &T, mut writer: W) -> Result<()> {
+    let body = record.to_body()?;
+    let size = u32::try_from(body.len())?;
+    ensure!(size <= MAX_RECORD_SIZE, "The {:?} record is too large ({size} bytes)", T::KIND);
+    writer.write_all(&RECORD_MAGIC)?;
+    (T::KIND as u8).write_le(&mut writer)?;
+    LATEST_RECORD_VERSION.write_le(&mut writer)?;
+    size.write_le(&mut writer)?;
+    writer.write_all(&body)?;
+    Ok(())
+}
+
+/// Reads a record, in the encoding version of its header, or in the unversioned encoding if it has no header.
+pub fn read_record<T: VersionedRecord, R: Read>(mut reader: R) -> Result<T> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    // If the record has no header, read it in the unversioned encoding.
+    if magic != RECORD_MAGIC {
+        return T::read_unversioned((&magic[..]).chain(reader));
+    }
+    // Read the header.
+    let kind = RecordKind::try_from(u8::read_le(&mut reader)?)?;
+    let version = u16::read_le(&mut reader)?;
+    let size = u32::read_le(&mut reader)?;
+    ensure!(kind == T::KIND, "Expected a {:?} record, found a {kind:?} record", T::KIND);
+    ensure!(
+        (1..=LATEST_RECORD_VERSION).contains(&version),
+        "Unsupported version {version} of the {kind:?} record (the latest supported version is {LATEST_RECORD_VERSION})"
+    );
+    ensure!(size <= MAX_RECORD_SIZE, "The {kind:?} record is too large ({size} bytes)");
+    // Read the body.
+    let mut body = vec![0u8; size as usize];
+    reader.read_exact(&mut body)?;
+    T::from_body(version, &body)
+}
+
+/// Returns the value in the given bytes, ensuring no bytes remain.
+fn from_bytes_exact<T: FromBytes>(mut bytes: &[u8]) -> Result<T> {
+    let value = T::read_le(&mut bytes)?;
+    ensure!(bytes.is_empty(), "The record has {} trailing bytes", bytes.len());
+    Ok(value)
+}
+
+impl<N: Network> VersionedRecord for Block<N> {
+    const KIND: RecordKind = RecordKind::Block;
+
+    fn to_body(&self) -> Result<Vec<u8>> {
+        self.to_bytes_le()
+    }
+
+    fn from_body(version: u16, body: &[u8]) -> Result<Self> {
+        match version {
+            1 => from_bytes_exact(body),
+            _ => bail!("Unsupported version {version} of the block record"),
+        }
+    }
+
+    fn read_unversioned<R: Read>(reader: R) -> Result<Self> {
+        Ok(Self::read_le(reader)?)
+    }
+}
+
+impl<N: Network> VersionedRecord for Transaction<N> {
+    const KIND: RecordKind = RecordKind::Transaction;
+
+    fn to_body(&self) -> Result<Vec<u8>> {
+        self.to_bytes_le()
+    }
+
+    fn from_body(version: u16, body: &[u8]) -> Result<Self> {
+        match version {
+            1 => from_bytes_exact(body),
+            _ => bail!("Unsupported version {version} of the transaction record"),
+        }
+    }
+
+    fn read_unversioned<R: Read>(reader: R) -> Result<Self> {
+        Ok(Self::read_le(reader)?)
+    }
+}
+
+/// Note: The transaction is not deserialized when it is read, so it must be validated before it is used.
+impl<N: Network> VersionedRecord for Data<Transaction<N>> {
+    const KIND: RecordKind = RecordKind::Transaction;
+
+    fn to_body(&self) -> Result<Vec<u8>> {
+        match self {
+            Self::Object(transaction) => transaction.to_bytes_le(),
+            Self::Buffer(bytes) => Ok(bytes.to_vec()),
+        }
+    }
+
+    fn from_body(version: u16, body: &[u8]) -> Result<Self> {
+        match version {
+            1 => Ok(Self::Buffer(body.to_vec().into())),
+            _ => bail!("Unsupported version {version} of the transaction record"),
+        }
+    }
+
+    fn read_unversioned<R: Read>(reader: R) -> Result<Self> {
+        Ok(Self::read_le(reader)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    /// Returns the given record, written in the given encoding version.
+    fn record_bytes(kind: RecordKind, version: u16, body: &[u8]) -> Vec<u8> {
+        let mut bytes = RECORD_MAGIC.to_vec();
+        bytes.push(kind as u8);
+        bytes.extend(version.to_le_bytes());
+        bytes.extend((body.len() as u32).to_le_bytes());
+        bytes.extend(body);
+        bytes
+    }
+
+    #[test]
+    fn test_block_record() {
+        let block = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+
+        // Ensure the block round-trips in the latest version.
+        let mut bytes = Vec::new();
+        write_record(&block, &mut bytes).unwrap();
+        assert_eq!(bytes[..4], RECORD_MAGIC);
+        assert_eq!(read_record::<Block<CurrentNetwork>, _>(&bytes[..]).unwrap(), block);
+        // Ensure the block is read from every version.
+        let body = block.to_bytes_le().unwrap();
+        let bytes = record_bytes(RecordKind::Block, 1, &body);
+        assert_eq!(read_record::<Block<CurrentNetwork>, _>(&bytes[..]).unwrap(), block);
+        // Ensure the block is read from the unversioned encoding.
+        assert_eq!(read_record::<Block<CurrentNetwork>, _>(&body[..]).unwrap(), block);
+
+        // Ensure the records are read in sequence.
+        let mut bytes = Vec::new();
+        write_record(&block, &mut bytes).unwrap();
+        bytes.extend(&body);
+        write_record(&block, &mut bytes).unwrap();
+        let mut reader = &bytes[..];
+        for _ in 0..3 {
+            assert_eq!(read_record::<Block<CurrentNetwork>, _>(&mut reader).unwrap(), block);
+        }
+        assert!(reader.is_empty());
+
+        // Ensure an unsupported version, a mismatching kind, and trailing bytes are rejected.
+        let future = record_bytes(RecordKind::Block, LATEST_RECORD_VERSION + 1, &body);
+        assert!(read_record::<Block<CurrentNetwork>, _>(&future[..]).is_err());
+        let mismatching = record_bytes(RecordKind::Transaction, 1, &body);
+        assert!(read_record::<Block<CurrentNetwork>, _>(&mismatching[..]).is_err());
+        let trailing = record_bytes(RecordKind::Block, 1, &[body.as_slice(), &[0]].concat());
+        assert!(read_record::<Block<CurrentNetwork>, _>(&trailing[..]).is_err());
+    }
+
+    #[test]
+    fn test_transaction_record() {
+        let block = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let transaction = block.transactions().iter().next().unwrap().transaction().clone();
+        let body = transaction.to_bytes_le().unwrap();
+
+        // Ensure the transaction round-trips in the latest version, and from the unversioned encoding.
+        let mut bytes = Vec::new();
+        write_record(&transaction, &mut bytes).unwrap();
+        assert_eq!(read_record::<Transaction<CurrentNetwork>, _>(&bytes[..]).unwrap(), transaction);
+        assert_eq!(read_record::<Transaction<CurrentNetwork>, _>(&body[..]).unwrap(), transaction);
+
+        // Ensure the transaction data round-trips, and is read from the unversioned encoding.
+        let data = Data::Object(transaction.clone());
+        let mut bytes = Vec::new();
+        write_record(&data, &mut bytes).unwrap();
+        let read = read_record::<Data<Transaction<CurrentNetwork>>, _>(&bytes[..]).unwrap();
+        assert_eq!(read.deserialize_blocking().unwrap(), transaction);
+        let unversioned = data.to_bytes_le().unwrap();
+        let read = read_record::<Data<Transaction<CurrentNetwork>>, _>(&unversioned[..]).unwrap();
+        assert_eq!(read.deserialize_blocking().unwrap(), transaction);
+
+        // Ensure the transaction is read from every version, as a transaction and as transaction data.
+        let bytes = record_bytes(RecordKind::Transaction, 1, &body);
+        assert_eq!(read_record::<Transaction<CurrentNetwork>, _>(&bytes[..]).unwrap(), transaction);
+        let read = read_record::<Data<Transaction<CurrentNetwork>>, _>(&bytes[..]).unwrap();
+        assert_eq!(read.deserialize_blocking().unwrap(), transaction);
+    }
+}
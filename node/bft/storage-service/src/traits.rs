@@ -13,11 +13,11 @@
 // limitations under the License.
 
 use snarkvm::{
-    ledger::narwhal::{BatchHeader, Transmission, TransmissionID},
+    ledger::narwhal::{BatchCertificate, BatchHeader, Transmission, TransmissionID},
     prelude::{Field, Network, Result},
 };
 
-use indexmap::IndexSet;
+use indexmap::{IndexMap, IndexSet};
 use std::{collections::HashMap, fmt::Debug};
 
 pub trait StorageService<N: Network>: Debug + Send + Sync {
@@ -48,6 +48,35 @@ pub trait StorageService<N: Network>: Debug + Send + Sync {
     /// If the transmission no longer references any certificate IDs, the entry is removed from storage.
     fn remove_transmissions(&self, certificate_id: &Field<N>, transmission_ids: &IndexSet<TransmissionID<N>>);
 
+    /// Inserts the given certificate into storage, so that it is restored after a restart.
+    ///
+    /// Note: Storage services that do not outlive the node do not need to retain the certificate.
+    fn insert_certificate(&self, _certificate: &BatchCertificate<N>) {}
+
+    /// Removes the certificate for the given `certificate ID` from storage.
+    fn remove_certificate(&self, _certificate_id: &Field<N>) {}
+
+    /// Returns the certificates to restore after a restart.
+    fn certificates(&self) -> Vec<BatchCertificate<N>> {
+        Vec::new()
+    }
+
+    /// Inserts the batch proposal of this node into storage, replacing any previous proposal.
+    fn insert_proposal(
+        &self,
+        _batch_header: &BatchHeader<N>,
+        _transmissions: &IndexMap<TransmissionID<N>, Transmission<N>>,
+    ) {
+    }
+
+    /// Removes the batch proposal of this node from storage.
+    fn remove_proposal(&self) {}
+
+    /// Returns the batch proposal of this node to restore after a restart, if one exists.
+    fn proposal(&self) -> Option<(BatchHeader<N>, IndexMap<TransmissionID<N>, Transmission<N>>)> {
+        None
+    }
+
     /// Returns a HashMap over the `(transmission ID, (transmission, certificate IDs))` entries.
     #[cfg(any(test, feature = "test"))]
     fn as_hashmap(&self) -> HashMap<TransmissionID<N>, (Transmission<N>, IndexSet<Field<N>>)>;
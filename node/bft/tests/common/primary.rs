@@ -21,6 +21,7 @@ use snarkos_account::Account;
 use snarkos_node_bft::{
     helpers::{init_primary_channels, PrimarySender, Storage},
     Primary,
+    PrimaryConfig,
     BFT,
     MAX_BATCH_DELAY_IN_MS,
     MAX_GC_ROUNDS,
@@ -155,11 +156,30 @@ impl TestNetwork {
             let storage = Storage::new(ledger.clone(), Arc::new(BFTMemoryService::new()), MAX_GC_ROUNDS);
 
             let (primary, bft) = if config.bft {
-                let bft = BFT::<CurrentNetwork>::new(account, storage, ledger, None, &[], Some(id as u16)).unwrap();
+                let bft = BFT::<CurrentNetwork>::new(
+                    account,
+                    storage,
+                    ledger,
+                    None,
+                    &[],
+                    None,
+                    PrimaryConfig::default(),
+                    Some(id as u16),
+                )
+                .unwrap();
                 (bft.primary().clone(), Some(bft))
             } else {
-                let primary =
-                    Primary::<CurrentNetwork>::new(account, storage, ledger, None, &[], Some(id as u16)).unwrap();
+                let primary = Primary::<CurrentNetwork>::new(
+                    account,
+                    storage,
+                    ledger,
+                    None,
+                    &[],
+                    None,
+                    PrimaryConfig::default(),
+                    Some(id as u16),
+                )
+                .unwrap();
                 (primary, None)
             };
 
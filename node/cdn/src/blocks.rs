@@ -356,7 +356,7 @@ fn log_progress<const OBJECTS_PER_FILE: u32>(
 }
 
 /// Executes the given closure, with a backoff policy, and returns the result.
-pub(crate) async fn handle_dispatch_error<'a, T, F>(func: impl Fn() -> F + 'a) -> anyhow::Result<T>
+pub async fn handle_dispatch_error<'a, T, F>(func: impl Fn() -> F + 'a) -> anyhow::Result<T>
 where
     F: Future<Output = Result<T, anyhow::Error>>,
 {
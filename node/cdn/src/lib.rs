@@ -18,4 +18,4 @@
 extern crate tracing;
 
 mod blocks;
-pub use blocks::{load_blocks, sync_ledger_with_cdn};
+pub use blocks::{handle_dispatch_error, load_blocks, sync_ledger_with_cdn};
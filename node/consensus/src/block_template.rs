@@ -0,0 +1,196 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{ledger::block::Transaction, prelude::*};
+
+use std::{collections::HashSet, hash::Hash};
+
+/// The maximum number of transitions in a block template, which bounds the verification cost of the template.
+pub const MAX_TRANSITIONS_PER_TEMPLATE: usize = 1024; // transitions
+/// The number of candidate transactions considered for each slot in a block template.
+pub const TEMPLATE_CANDIDATES_PER_SLOT: usize = 4;
+
+/// The limits of a block template.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TemplateLimits {
+    /// The maximum number of transactions.
+    pub max_transactions: usize,
+    /// The maximum total size of the transactions in bytes.
+    pub max_size_in_bytes: usize,
+    /// The maximum total number of transitions of the transactions.
+    pub max_transitions: usize,
+}
+
+/// A candidate transaction for a block template.
+#[derive(Debug)]
+struct Candidate<S> {
+    /// The fee of the candidate.
+    fee: u64,
+    /// The size of the candidate in bytes.
+    size: usize,
+    /// The number of transitions of the candidate.
+    num_transitions: usize,
+    /// The serial numbers and commitments of the candidate.
+    spends: Vec<S>,
+}
+
+/// Returns the indices of the candidates to include in a template, in order of fee density (fee per byte),
+/// such that the template is within the given limits, and no two candidates share a serial number or commitment.
+/// Among candidates with equal fee density, the earlier candidate is preferred.
+fn select_by_fee_density<S: Copy + Eq + Hash>(limits: &TemplateLimits, candidates: &[Candidate<S>]) -> Vec<usize> {
+    // Order the candidates by fee density, from the highest to the lowest.
+    let mut order = (0..candidates.len()).collect::<Vec<_>>();
+    order.sort_by(|a, b| {
+        let (a, b) = (&candidates[*a], &candidates[*b]);
+        // Compare `a.fee / a.size` to `b.fee / b.size`, without division.
+        let density_a = a.fee as u128 * b.size.max(1) as u128;
+        let density_b = b.fee as u128 * a.size.max(1) as u128;
+        density_b.cmp(&density_a)
+    });
+
+    let mut selected = Vec::new();
+    let mut spends = HashSet::new();
+    let (mut size_in_bytes, mut num_transitions) = (0usize, 0usize);
+    for index in order {
+        // Stop once the template is full.
+        if selected.len() >= limits.max_transactions {
+            break;
+        }
+        let candidate = &candidates[index];
+        // Skip the candidate if it does not fit in the remaining space.
+        if size_in_bytes + candidate.size > limits.max_size_in_bytes
+            || num_transitions + candidate.num_transitions > limits.max_transitions
+        {
+            continue;
+        }
+        // Skip the candidate if it conflicts with a selected candidate.
+        if candidate.spends.iter().any(|spend| spends.contains(spend)) {
+            continue;
+        }
+        spends.extend(candidate.spends.iter().copied());
+        size_in_bytes += candidate.size;
+        num_transitions += candidate.num_transitions;
+        selected.push(index);
+    }
+    selected
+}
+
+/// A block template, which is the set of unconfirmed transactions that are handed to the workers
+/// for inclusion in the next batch, and thereby the next block.
+///
+/// The template selects the candidate transactions by fee density, within the size and transition limits,
+/// and is validated as a set, such that no two of its transactions spend the same serial number
+/// or produce the same commitment.
+#[derive(Debug)]
+pub struct BlockTemplate<N: Network> {
+    /// The selected transactions, in order of fee density.
+    transactions: Vec<(N::TransactionID, Transaction<N>)>,
+    /// The candidate transactions that were not selected.
+    excluded: Vec<(N::TransactionID, Transaction<N>)>,
+    /// The total size of the selected transactions in bytes.
+    size_in_bytes: usize,
+    /// The total fee of the selected transactions.
+    total_fee: u64,
+}
+
+impl<N: Network> BlockTemplate<N> {
+    /// Builds a block template from the given candidate transactions, in order of priority, within the given limits.
+    pub fn build(limits: TemplateLimits, candidates: Vec<(N::TransactionID, Transaction<N>)>) -> Self {
+        // Compute the fee, size, transitions, and spends of each candidate.
+        let infos = candidates
+            .iter()
+            .map(|(_, transaction)| Candidate {
+                fee: transaction.fee_amount().map_or(0, |fee| *fee),
+                size: transaction.to_bytes_le().map_or(usize::MAX, |bytes| bytes.len()),
+                num_transitions: transaction.transitions().count(),
+                spends: transaction.serial_numbers().chain(transaction.commitments()).copied().collect(),
+            })
+            .collect::<Vec<_>>();
+        // Select the candidates.
+        let selected = select_by_fee_density(&limits, &infos);
+        let size_in_bytes = selected.iter().map(|index| infos[*index].size).sum();
+        let total_fee = selected.iter().fold(0u64, |total, index| total.saturating_add(infos[*index].fee));
+        // Split the candidates into the selected and excluded transactions.
+        let mut candidates = candidates.into_iter().map(Some).collect::<Vec<_>>();
+        let transactions = selected.iter().filter_map(|index| candidates[*index].take()).collect();
+        let excluded = candidates.into_iter().flatten().collect();
+        Self { transactions, excluded, size_in_bytes, total_fee }
+    }
+
+    /// Returns the number of selected transactions.
+    pub fn len(&self) -> usize {
+        self.transactions.len()
+    }
+
+    /// Returns `true` if no transactions were selected.
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+
+    /// Returns the total size of the selected transactions in bytes.
+    pub const fn size_in_bytes(&self) -> usize {
+        self.size_in_bytes
+    }
+
+    /// Returns the total fee of the selected transactions.
+    pub const fn total_fee(&self) -> u64 {
+        self.total_fee
+    }
+
+    /// Returns the candidate transactions that were not selected.
+    pub fn excluded(&self) -> &[(N::TransactionID, Transaction<N>)] {
+        &self.excluded
+    }
+
+    /// Returns the selected transactions, in order of fee density.
+    pub fn into_transactions(self) -> Vec<(N::TransactionID, Transaction<N>)> {
+        self.transactions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(fee: u64, size: usize, num_transitions: usize, spends: &[u8]) -> Candidate<u8> {
+        Candidate { fee, size, num_transitions, spends: spends.to_vec() }
+    }
+
+    #[test]
+    fn test_select_by_fee_density() {
+        let candidates = [
+            candidate(200, 50, 1, &[1]),
+            candidate(60, 20, 1, &[2]),
+            candidate(30, 10, 1, &[3]),
+            candidate(90, 30, 1, &[1]),
+            candidate(10, 5, 4, &[4]),
+        ];
+        // Ensure the candidates are selected by fee density, skipping the conflicting candidate,
+        // and the candidate that exceeds the transition limit.
+        let limits = TemplateLimits { max_transactions: 5, max_size_in_bytes: 1000, max_transitions: 5 };
+        assert_eq!(select_by_fee_density(&limits, &candidates), vec![0, 1, 2]);
+
+        // Ensure the size limit is enforced, while smaller candidates still fill the remaining space.
+        let limits = TemplateLimits { max_transactions: 5, max_size_in_bytes: 65, max_transitions: 10 };
+        assert_eq!(select_by_fee_density(&limits, &candidates), vec![0, 2, 4]);
+
+        // Ensure the transaction limit is enforced.
+        let limits = TemplateLimits { max_transactions: 2, max_size_in_bytes: 1000, max_transitions: 10 };
+        assert_eq!(select_by_fee_density(&limits, &candidates), vec![0, 1]);
+
+        // Ensure candidates with equal fee density keep their order.
+        let candidates = [candidate(10, 10, 1, &[1]), candidate(20, 20, 1, &[2])];
+        assert_eq!(select_by_fee_density(&limits, &candidates), vec![0, 1]);
+    }
+}
@@ -0,0 +1,157 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+
+/// The number of recent blocks that the fee estimator analyzes.
+pub const FEE_ESTIMATOR_WINDOW_IN_BLOCKS: usize = 100; // blocks
+
+/// The priority fees of the accepted transactions in a block.
+#[derive(Debug)]
+struct BlockFees {
+    /// The number of transactions in the block.
+    num_transactions: usize,
+    /// The priority fees of the accepted transactions, in ascending order.
+    priority_fees: Vec<u64>,
+}
+
+/// An estimate of the priority fee for a transaction to be included within a target number of blocks.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FeeEstimate {
+    /// The target number of blocks.
+    pub target_blocks: u32,
+    /// The estimated priority fee in microcredits.
+    pub priority_fee: u64,
+    /// The median priority fee of the accepted transactions in the recent blocks.
+    pub median_priority_fee: u64,
+    /// The number of unconfirmed transactions waiting to be included.
+    pub backlog: usize,
+    /// The number of recent blocks that were analyzed.
+    pub num_blocks: usize,
+}
+
+/// The fee estimator, which estimates the priority fee for a transaction to be included within a target number
+/// of blocks, from the priority fees of the transactions in the recent blocks, and the current mempool backlog.
+///
+/// The capacity of a block is approximated by the average number of transactions in the recent blocks.
+/// If the backlog ahead of a new transaction exceeds the capacity of the target number of blocks, the estimate
+/// outbids the last transaction of the backlog that fits. Otherwise, the estimate is the median priority fee
+/// of the recently accepted transactions.
+#[derive(Debug)]
+pub struct FeeEstimator {
+    /// The maximum number of recent blocks to analyze.
+    window: usize,
+    /// The priority fees of the recent blocks, from the oldest block to the latest block.
+    blocks: Mutex<VecDeque<BlockFees>>,
+}
+
+impl Default for FeeEstimator {
+    /// Initializes a new instance of the fee estimator.
+    fn default() -> Self {
+        Self::new(FEE_ESTIMATOR_WINDOW_IN_BLOCKS)
+    }
+}
+
+impl FeeEstimator {
+    /// Initializes a new fee estimator, which analyzes the given number of recent blocks.
+    pub fn new(window: usize) -> Self {
+        Self { window, blocks: Default::default() }
+    }
+
+    /// Returns the number of recent blocks that are analyzed.
+    pub fn num_blocks(&self) -> usize {
+        self.blocks.lock().len()
+    }
+
+    /// Records the given number of transactions, and the priority fees of the accepted transactions, of a new block.
+    pub fn record_block(&self, num_transactions: usize, priority_fees: impl IntoIterator<Item = u64>) {
+        let mut priority_fees = priority_fees.into_iter().collect::<Vec<_>>();
+        priority_fees.sort_unstable();
+        let mut blocks = self.blocks.lock();
+        blocks.push_back(BlockFees { num_transactions, priority_fees });
+        // Forget the blocks beyond the window.
+        while blocks.len() > self.window {
+            blocks.pop_front();
+        }
+    }
+
+    /// Returns the estimated priority fee for a transaction to be included within the given number of blocks,
+    /// given the number of unconfirmed transactions that are already scheduled for inclusion, and the priority fees
+    /// of the unconfirmed transactions that are waiting in the mempool.
+    pub fn estimate_fee(&self, target_blocks: u32, num_scheduled: usize, mempool_fees: &[u64]) -> FeeEstimate {
+        let target_blocks = target_blocks.max(1);
+        let blocks = self.blocks.lock();
+
+        // Approximate the capacity of a block by the average number of transactions in the recent blocks.
+        let num_transactions = blocks.iter().map(|block| block.num_transactions).sum::<usize>();
+        let capacity = num_transactions.checked_div(blocks.len()).unwrap_or(0).max(1);
+        // Compute the median priority fee of the accepted transactions in the recent blocks.
+        let mut recent_fees = blocks.iter().flat_map(|block| block.priority_fees.iter().copied()).collect::<Vec<_>>();
+        recent_fees.sort_unstable();
+        let median_priority_fee = recent_fees.get(recent_fees.len() / 2).copied().unwrap_or(0);
+
+        // Determine the number of mempool transactions that are included within the target number of blocks.
+        let num_slots = capacity.saturating_mul(target_blocks as usize).saturating_sub(num_scheduled);
+        // If the mempool does not fit, outbid the last mempool transaction that fits.
+        let backlog_fee = match mempool_fees.len() > num_slots {
+            true => {
+                let mut mempool_fees = mempool_fees.to_vec();
+                mempool_fees.sort_unstable_by(|a, b| b.cmp(a));
+                mempool_fees[num_slots.saturating_sub(1)].saturating_add(1)
+            }
+            false => 0,
+        };
+
+        FeeEstimate {
+            target_blocks,
+            priority_fee: backlog_fee.max(median_priority_fee),
+            median_priority_fee,
+            backlog: num_scheduled + mempool_fees.len(),
+            num_blocks: blocks.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fee_estimator() {
+        let estimator = FeeEstimator::new(2);
+
+        // Ensure the estimate is zero without any history or backlog.
+        assert_eq!(estimator.estimate_fee(1, 0, &[]).priority_fee, 0);
+
+        // Ensure the estimate is the median priority fee of the recent blocks, if the backlog fits.
+        estimator.record_block(3, [1, 5, 3]);
+        estimator.record_block(5, [2, 4, 100]);
+        let estimate = estimator.estimate_fee(1, 0, &[10, 20]);
+        assert_eq!(estimate.median_priority_fee, 4);
+        assert_eq!(estimate.priority_fee, 4);
+        assert_eq!(estimate.backlog, 2);
+
+        // Ensure the estimate outbids the backlog, if it exceeds the capacity of the target number of blocks.
+        let mempool_fees = [10, 50, 20, 30, 40];
+        assert_eq!(estimator.estimate_fee(1, 0, &mempool_fees).priority_fee, 21);
+        assert_eq!(estimator.estimate_fee(1, 3, &mempool_fees).priority_fee, 51);
+        assert_eq!(estimator.estimate_fee(2, 3, &mempool_fees).priority_fee, 4);
+
+        // Ensure the oldest blocks are forgotten.
+        estimator.record_block(1, [7]);
+        assert_eq!(estimator.num_blocks(), 2);
+        assert_eq!(estimator.estimate_fee(1, 0, &[]).median_priority_fee, 7);
+    }
+}
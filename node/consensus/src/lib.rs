@@ -17,6 +17,21 @@
 #[macro_use]
 extern crate tracing;
 
+mod block_template;
+pub use block_template::*;
+
+mod fee_estimator;
+pub use fee_estimator::*;
+
+mod mempool;
+pub use mempool::*;
+
+mod orphans;
+pub use orphans::*;
+
+mod persistence;
+pub use persistence::*;
+
 use snarkos_account::Account;
 use snarkos_node_bft::{
     helpers::{
@@ -28,15 +43,18 @@ use snarkos_node_bft::{
         Storage as NarwhalStorage,
     },
     spawn_blocking,
+    PrimaryConfig,
     BFT,
+    MAX_BATCH_SIZE_IN_BYTES,
     MAX_GC_ROUNDS,
     MAX_TRANSMISSIONS_PER_BATCH,
 };
 use snarkos_node_bft_ledger_service::LedgerService;
-use snarkos_node_bft_storage_service::BFTPersistentStorage;
+use snarkos_node_bft_storage_service::StorageService;
+use snarkos_node_tcp::PeerDenyList;
 use snarkvm::{
     ledger::{
-        block::Transaction,
+        block::{Block, Transaction},
         coinbase::{ProverSolution, PuzzleCommitment},
         narwhal::{Data, Subdag, Transmission, TransmissionID},
     },
@@ -48,12 +66,38 @@ use colored::Colorize;
 use indexmap::IndexMap;
 use lru::LruCache;
 use parking_lot::Mutex;
-use std::{future::Future, net::SocketAddr, num::NonZeroUsize, sync::Arc};
+use std::{
+    future::Future,
+    net::SocketAddr,
+    num::NonZeroUsize,
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
 use tokio::{
-    sync::{oneshot, OnceCell},
+    sync::{broadcast, oneshot, OnceCell},
     task::JoinHandle,
 };
 
+/// The default maximum number of blocks beyond the latest block that a transaction expiration height may be set to.
+pub const MAX_TRANSACTION_VALIDITY_IN_BLOCKS: u32 = 8640; // blocks
+
+/// Ensures the given transaction expiration height is valid, with respect to the latest block height.
+///
+/// A transaction may only be included in a block at or below its expiration height, and may not set
+/// its expiration height more than `max_validity` blocks beyond the latest block height.
+pub fn check_transaction_expiration(latest_height: u32, expiration_height: u32, max_validity: u32) -> Result<()> {
+    // Ensure the transaction has not expired.
+    if expiration_height <= latest_height {
+        bail!("Transaction expired at block {expiration_height} (the latest block is {latest_height})")
+    }
+    // Ensure the transaction is not dated too far into the future.
+    if expiration_height - latest_height > max_validity {
+        bail!("Transaction expiration height {expiration_height} is more than {max_validity} blocks in the future")
+    }
+    Ok(())
+}
+
 #[derive(Clone)]
 pub struct Consensus<N: Network> {
     /// The ledger.
@@ -64,12 +108,22 @@ pub struct Consensus<N: Network> {
     primary_sender: Arc<OnceCell<PrimarySender<N>>>,
     /// The unconfirmed solutions queue.
     solutions_queue: Arc<Mutex<IndexMap<PuzzleCommitment<N>, ProverSolution<N>>>>,
-    /// The unconfirmed transactions queue.
-    transactions_queue: Arc<Mutex<IndexMap<N::TransactionID, Transaction<N>>>>,
+    /// The mempool of unconfirmed transactions, ordered by fee.
+    mempool: Arc<Mempool<N>>,
+    /// The pool of orphan transactions, which reference a global state root that is not yet in the ledger.
+    orphans: Arc<OrphanPool<N>>,
+    /// The fee estimator, which analyzes the priority fees of the recent blocks.
+    fee_estimator: Arc<FeeEstimator>,
     /// The recently-seen unconfirmed solutions.
     seen_solutions: Arc<Mutex<LruCache<PuzzleCommitment<N>, ()>>>,
     /// The recently-seen unconfirmed transactions.
     seen_transactions: Arc<Mutex<LruCache<N::TransactionID, ()>>>,
+    /// The maximum number of blocks beyond the latest block that a transaction expiration height may be set to.
+    max_transaction_validity: u32,
+    /// The path to the mempool snapshot, if the mempool persistence is enabled.
+    snapshot_path: Option<PathBuf>,
+    /// The sender of the mempool events.
+    mempool_events: broadcast::Sender<MempoolEvent<N>>,
     /// The spawned handles.
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
 }
@@ -79,27 +133,50 @@ impl<N: Network> Consensus<N> {
     pub fn new(
         account: Account<N>,
         ledger: Arc<dyn LedgerService<N>>,
+        transmissions: Arc<dyn StorageService<N>>,
         ip: Option<SocketAddr>,
         trusted_validators: &[SocketAddr],
+        peer_deny_list: Option<Arc<PeerDenyList>>,
+        max_transaction_validity: u32,
+        max_mempool_size: usize,
+        snapshot_path: Option<PathBuf>,
+        primary_config: PrimaryConfig,
         dev: Option<u16>,
     ) -> Result<Self> {
-        // Initialize the Narwhal transmissions.
-        let transmissions = Arc::new(BFTPersistentStorage::open(dev)?);
         // Initialize the Narwhal storage.
-        let storage = NarwhalStorage::new(ledger.clone(), transmissions, MAX_GC_ROUNDS);
+        let storage = NarwhalStorage::with_committed_gc_depth(
+            ledger.clone(),
+            transmissions,
+            MAX_GC_ROUNDS,
+            primary_config.committed_gc_depth,
+        )?;
         // Initialize the BFT.
-        let bft = BFT::new(account, storage, ledger.clone(), ip, trusted_validators, dev)?;
-        // Return the consensus.
-        Ok(Self {
+        let bft =
+            BFT::new(account, storage, ledger.clone(), ip, trusted_validators, peer_deny_list, primary_config, dev)?;
+        // Initialize the consensus.
+        let consensus = Self {
             ledger,
             bft,
             primary_sender: Default::default(),
             solutions_queue: Default::default(),
-            transactions_queue: Default::default(),
+            mempool: Arc::new(Mempool::new(
+                max_mempool_size,
+                Some(MIN_REPLACEMENT_FEE_INCREASE_PERCENT),
+                Duration::from_secs(MEMPOOL_TRANSACTION_TTL_IN_SECS),
+            )),
+            orphans: Default::default(),
+            fee_estimator: Default::default(),
             seen_solutions: Arc::new(Mutex::new(LruCache::new(NonZeroUsize::new(1 << 16).unwrap()))),
             seen_transactions: Arc::new(Mutex::new(LruCache::new(NonZeroUsize::new(1 << 16).unwrap()))),
+            max_transaction_validity,
+            snapshot_path,
+            mempool_events: broadcast::channel(MEMPOOL_EVENT_CAPACITY).0,
             handles: Default::default(),
-        })
+        };
+        // Warm up the fee estimator with the recent blocks.
+        consensus.seed_fee_estimator();
+        // Return the consensus.
+        Ok(consensus)
     }
 
     /// Run the consensus instance.
@@ -114,6 +191,25 @@ impl<N: Network> Consensus<N> {
         self.start_handlers(consensus_receiver);
         // Lastly, the consensus.
         self.bft.run(Some(consensus_sender), primary_sender, primary_receiver).await?;
+
+        // If the mempool persistence is enabled, restore the mempool snapshot, and then persist it periodically.
+        if self.snapshot_path.is_some() {
+            let self_ = self.clone();
+            self.spawn(async move {
+                // Restore the unconfirmed transactions from the mempool snapshot.
+                if let Err(e) = self_.restore_mempool().await {
+                    warn!("Failed to restore the mempool snapshot - {e}");
+                }
+                loop {
+                    tokio::time::sleep(Duration::from_secs(MEMPOOL_PERSIST_INTERVAL_IN_SECS)).await;
+                    // Persist the unconfirmed transactions to the mempool snapshot.
+                    let self__ = self_.clone();
+                    if let Err(e) = spawn_blocking!(self__.persist_mempool()) {
+                        warn!("Failed to persist the mempool snapshot - {e}");
+                    }
+                }
+            });
+        }
         Ok(())
     }
 
@@ -127,10 +223,33 @@ impl<N: Network> Consensus<N> {
         &self.bft
     }
 
+    /// Returns the mempool of unconfirmed transactions.
+    pub const fn mempool(&self) -> &Arc<Mempool<N>> {
+        &self.mempool
+    }
+
+    /// Returns the estimated priority fee for a transaction to be included within the given number of blocks.
+    pub fn estimate_fee(&self, target_blocks: u32) -> FeeEstimate {
+        // Retrieve the priority fees of the transactions in the mempool.
+        let mempool_fees = self.mempool.entries().into_iter().map(|entry| entry.priority_fee).collect::<Vec<_>>();
+        // Note: The transactions held by the workers are already scheduled for inclusion, ahead of the mempool.
+        self.fee_estimator.estimate_fee(target_blocks, self.num_unconfirmed_transactions(), &mempool_fees)
+    }
+
+    /// Returns a receiver of the mempool events, which are published as transactions are added, removed, and confirmed.
+    pub fn subscribe_mempool(&self) -> broadcast::Receiver<MempoolEvent<N>> {
+        self.mempool_events.subscribe()
+    }
+
     /// Returns the primary sender.
     pub fn primary_sender(&self) -> &PrimarySender<N> {
         self.primary_sender.get().expect("Primary sender not set")
     }
+
+    /// Returns the maximum number of blocks beyond the latest block that a transaction expiration height may be set to.
+    pub const fn max_transaction_validity(&self) -> u32 {
+        self.max_transaction_validity
+    }
 }
 
 impl<N: Network> Consensus<N> {
@@ -247,42 +366,260 @@ impl<N: Network> Consensus<N> {
             if self.ledger.contains_transmission(&TransmissionID::from(&transaction_id))? {
                 bail!("Transaction '{}' exists in the ledger {}", fmt_id(transaction_id), "(skipping)".dimmed());
             }
-            // Add the transaction to the memory pool.
-            trace!("Received unconfirmed transaction '{}' in the queue", fmt_id(transaction_id));
-            if self.transactions_queue.lock().insert(transaction_id, transaction).is_some() {
+            // Check if the transaction already exists in the mempool, or was handed to the workers.
+            if self.mempool.contains(&transaction_id) || self.mempool.is_in_flight(&transaction_id) {
                 bail!("Transaction '{}' exists in the memory pool", fmt_id(transaction_id));
             }
+            // Check if the serial numbers of the transaction were already spent in the ledger.
+            for serial_number in transaction.serial_numbers() {
+                if self.ledger.contains_serial_number(serial_number)? {
+                    bail!("Transaction '{}' spends an existing serial number", fmt_id(transaction_id));
+                }
+            }
+            // Check if the commitments of the transaction already exist in the ledger.
+            for commitment in transaction.commitments() {
+                if self.ledger.contains_commitment(commitment)? {
+                    bail!("Transaction '{}' produces an existing commitment", fmt_id(transaction_id));
+                }
+            }
+            // If the transaction references a global state root that is not yet in the ledger,
+            // park it as an orphan, to be re-evaluated once the ledger advances.
+            let missing_state_roots = self.find_missing_state_roots(&transaction)?;
+            if !missing_state_roots.is_empty() {
+                trace!("Parking orphan transaction '{}' until its state root is known", fmt_id(transaction_id));
+                if let Some(evicted_id) = self.orphans.insert(transaction, missing_state_roots) {
+                    debug!("Dropping orphan transaction '{}' {}", fmt_id(evicted_id), "(evicted)".dimmed());
+                }
+                return Ok(());
+            }
+            // Add the transaction to the mempool.
+            trace!("Received unconfirmed transaction '{}' in the queue", fmt_id(transaction_id));
+            let removed = self.mempool.insert(transaction)?;
+            self.publish_mempool_event(MempoolEvent::Added { transaction_id });
+            // Stop tracking the expiration heights of the replaced and evicted transactions.
+            for removed_id in removed {
+                debug!("Removed transaction '{}' from the mempool {}", fmt_id(removed_id), "(outbid)".dimmed());
+                self.ledger.remove_transaction_expiration(&removed_id);
+                self.publish_mempool_event(MempoolEvent::Removed { transaction_id: removed_id, reason: "outbid" });
+            }
         }
 
+        // Send the highest-priority transactions to the primary.
+        self.send_mempool_transactions_to_primary().await;
+        Ok(())
+    }
+
+    /// Records the priority fees of the recent blocks in the ledger, to warm up the fee estimator.
+    fn seed_fee_estimator(&self) {
+        let latest_height = self.ledger.latest_block_height();
+        let start_height = latest_height.saturating_sub(FEE_ESTIMATOR_WINDOW_IN_BLOCKS as u32 - 1);
+        for height in start_height..=latest_height {
+            match self.ledger.get_block(height) {
+                Ok(block) => self.record_block_fees(&block),
+                Err(e) => warn!("Failed to seed the fee estimator with block {height} - {e}"),
+            }
+        }
+    }
+
+    /// Records the number of transactions, and the priority fees of the accepted transactions, of the given block.
+    fn record_block_fees(&self, block: &Block<N>) {
+        let priority_fees = block
+            .transactions()
+            .iter()
+            .filter(|transaction| transaction.is_accepted())
+            .filter_map(|transaction| transaction.transaction().priority_fee_amount().ok())
+            .map(|priority_fee| *priority_fee);
+        self.fee_estimator.record_block(block.transactions().len(), priority_fees);
+    }
+
+    /// Returns the global state roots referenced by the given transaction that are not yet in the ledger.
+    fn find_missing_state_roots(&self, transaction: &Transaction<N>) -> Result<Vec<N::StateRoot>> {
+        // Retrieve the global state roots of the execution and the fee.
+        let state_roots = match transaction {
+            Transaction::Deploy(_, _, _, fee) => vec![fee.global_state_root()],
+            Transaction::Execute(_, execution, fee) => {
+                let fee_state_root = fee.as_ref().map(|fee| fee.global_state_root());
+                std::iter::once(execution.global_state_root()).chain(fee_state_root).collect()
+            }
+            Transaction::Fee(_, fee) => vec![fee.global_state_root()],
+        };
+        // Retain the global state roots that are not in the ledger.
+        let mut missing_state_roots = Vec::new();
+        for state_root in state_roots {
+            if !missing_state_roots.contains(&state_root) && !self.ledger.contains_state_root(&state_root)? {
+                missing_state_roots.push(state_root);
+            }
+        }
+        Ok(missing_state_roots)
+    }
+
+    /// Re-evaluates the orphan transactions whose global state roots are now in the ledger,
+    /// and drops the orphan transactions that have expired.
+    async fn reevaluate_orphans(&self) {
+        // If there are no orphan transactions, return early.
+        if self.orphans.is_empty() {
+            return;
+        }
+        // Retrieve the orphan transactions that are ready, and those that have expired.
+        let (ready, expired) =
+            self.orphans.take_ready(|state_root| self.ledger.contains_state_root(state_root).unwrap_or(false));
+        for transaction_id in expired {
+            debug!("Dropping orphan transaction '{}' {}", fmt_id(transaction_id), "(TTL expired)".dimmed());
+        }
+        // Add the ready transactions to the mempool.
+        for transaction in ready {
+            let transaction_id = transaction.id();
+            // Forget the transaction, so that it is not skipped as a recently-seen transaction.
+            self.seen_transactions.lock().pop(&transaction_id);
+            if let Err(e) = self.add_unconfirmed_transaction(transaction).await {
+                debug!("Dropping orphan transaction '{}' - {e}", fmt_id(transaction_id));
+            }
+        }
+    }
+
+    /// Sends the highest-priority transactions in the mempool to the primary, up to the available capacity.
+    async fn send_mempool_transactions_to_primary(&self) {
+        // Drop the transactions that have been in the mempool for longer than their time-to-live.
+        for transaction_id in self.mempool.remove_expired() {
+            debug!("Dropping transaction '{}' from the mempool {}", fmt_id(transaction_id), "(TTL expired)".dimmed());
+            self.ledger.remove_transaction_expiration(&transaction_id);
+            self.publish_mempool_event(MempoolEvent::Removed { transaction_id, reason: "ttl_expired" });
+        }
         // If the memory pool of this node is full, return early.
         let num_unconfirmed = self.num_unconfirmed_transmissions();
-        if num_unconfirmed > MAX_TRANSMISSIONS_PER_BATCH {
-            return Ok(());
+        if num_unconfirmed >= MAX_TRANSMISSIONS_PER_BATCH {
+            return;
         }
-        // Retrieve the transactions.
-        let transactions = {
-            // Determine the available capacity.
-            let capacity = MAX_TRANSMISSIONS_PER_BATCH.saturating_sub(num_unconfirmed);
-            // Acquire the lock on the queue.
-            let mut queue = self.transactions_queue.lock();
-            // Determine the number of transactions to send.
-            let num_transactions = queue.len().min(capacity);
-            // Drain the solutions from the queue.
-            queue.drain(..num_transactions).collect::<Vec<_>>()
+        // Determine the available capacity.
+        let capacity = MAX_TRANSMISSIONS_PER_BATCH.saturating_sub(num_unconfirmed);
+        // Retrieve the candidate transactions with the highest priority.
+        let mut candidates = self.mempool.peek(capacity.saturating_mul(TEMPLATE_CANDIDATES_PER_SLOT));
+        // Drop the candidate transactions that have expired.
+        candidates.retain(|(transaction_id, _)| {
+            let transaction_id = *transaction_id;
+            if !self.is_transaction_expired(&transaction_id) {
+                return true;
+            }
+            debug!("Dropping expired transaction '{}' from the memory pool", fmt_id(transaction_id));
+            self.mempool.remove(&transaction_id);
+            self.ledger.remove_transaction_expiration(&transaction_id);
+            self.publish_mempool_event(MempoolEvent::Removed { transaction_id, reason: "height_expired" });
+            false
+        });
+        // Build the block template from the candidate transactions, by fee density.
+        let limits = TemplateLimits {
+            max_transactions: capacity,
+            max_size_in_bytes: MAX_BATCH_SIZE_IN_BYTES,
+            max_transitions: MAX_TRANSITIONS_PER_TEMPLATE,
         };
-        // Iterate over the transactions.
-        for (_, transaction) in transactions.into_iter() {
-            let transaction_id = transaction.id();
+        let template = BlockTemplate::build(limits, candidates);
+        trace!("Built a block template with {} transactions ({} bytes)", template.len(), template.size_in_bytes());
+        // Iterate over the transactions of the template.
+        for (transaction_id, transaction) in template.into_transactions() {
+            // Hand the transaction from the mempool to the workers, keeping it in the conflict index.
+            self.mempool.dispatch(&transaction_id);
             trace!("Adding unconfirmed transaction '{}' to the memory pool...", fmt_id(transaction_id));
             // Send the unconfirmed transaction to the primary.
             if let Err(e) =
                 self.primary_sender().send_unconfirmed_transaction(transaction_id, Data::Object(transaction)).await
             {
                 warn!("Failed to add unconfirmed transaction '{}' to the memory pool - {e}", fmt_id(transaction_id));
+                // Release the transaction from the conflict index, as the workers do not hold it.
+                self.mempool.remove(&transaction_id);
             }
         }
+    }
+
+    /// Adds the given unconfirmed transaction to the memory pool,
+    /// to be dropped if it is not included in a block at or below the given expiration height.
+    pub async fn add_unconfirmed_transaction_with_expiration(
+        &self,
+        transaction: Transaction<N>,
+        expiration_height: u32,
+    ) -> Result<()> {
+        // Ensure the expiration height is valid.
+        let latest_height = self.ledger.latest_block_height();
+        check_transaction_expiration(latest_height, expiration_height, self.max_transaction_validity)?;
+        // Track the expiration height of the transaction, so that the primary enforces it.
+        self.ledger.insert_transaction_expiration(transaction.id(), expiration_height);
+        // Add the transaction to the memory pool.
+        self.add_unconfirmed_transaction(transaction).await
+    }
+
+    /// Publishes the given mempool event to the subscribers, if there are any.
+    fn publish_mempool_event(&self, event: MempoolEvent<N>) {
+        // Note: Sending fails only if there are no subscribers, in which case the event is dropped.
+        let _ = self.mempool_events.send(event);
+    }
+
+    /// Persists the unconfirmed transactions of the workers and the mempool to the mempool snapshot, if enabled.
+    fn persist_mempool(&self) -> Result<()> {
+        let Some(path) = &self.snapshot_path else {
+            return Ok(());
+        };
+        // Retrieve the unconfirmed transactions of the workers, and then those queued in the mempool.
+        let mut transactions = self.unconfirmed_transactions().collect::<Vec<_>>();
+        let queued = self.mempool.transactions().into_iter().map(|(id, transaction)| (id, Data::Object(transaction)));
+        transactions.extend(queued);
+        // Attach the expiration heights of the transactions.
+        let entries = transactions
+            .into_iter()
+            .map(|(id, transaction)| (transaction, self.ledger.get_transaction_expiration(&id)))
+            .collect::<Vec<_>>();
+        // Write the mempool snapshot.
+        write_mempool_snapshot(path, &entries)?;
+        debug!("Persisted {} unconfirmed transactions to the mempool snapshot", entries.len());
         Ok(())
     }
+
+    /// Restores the unconfirmed transactions from the mempool snapshot, if enabled, after validating them.
+    async fn restore_mempool(&self) -> Result<()> {
+        let Some(path) = self.snapshot_path.clone() else {
+            return Ok(());
+        };
+        // Read the mempool snapshot.
+        let entries = spawn_blocking!(read_mempool_snapshot::<N>(&path))?;
+        let num_entries = entries.len();
+        let mut num_restored = 0;
+        for (transaction, expiration_height) in entries {
+            // Deserialize the transaction.
+            let transaction = match spawn_blocking!(transaction.deserialize_blocking()) {
+                Ok(transaction) => transaction,
+                Err(e) => {
+                    warn!("Failed to deserialize a transaction from the mempool snapshot - {e}");
+                    continue;
+                }
+            };
+            let transaction_id = transaction.id();
+            // Ensure the transaction is still valid, with respect to the latest ledger.
+            let data = Data::Object(transaction.clone());
+            if let Err(e) = self.ledger.check_transaction_basic(transaction_id, data).await {
+                debug!("Dropping transaction '{}' from the mempool snapshot - {e}", fmt_id(transaction_id));
+                continue;
+            }
+            // Add the transaction to the mempool.
+            let result = match expiration_height {
+                Some(expiration_height) => {
+                    self.add_unconfirmed_transaction_with_expiration(transaction, expiration_height).await
+                }
+                None => self.add_unconfirmed_transaction(transaction).await,
+            };
+            match result {
+                Ok(()) => num_restored += 1,
+                Err(e) => debug!("Dropping transaction '{}' from the mempool snapshot - {e}", fmt_id(transaction_id)),
+            }
+        }
+        info!("Restored {num_restored} of {num_entries} unconfirmed transactions from the mempool snapshot");
+        Ok(())
+    }
+
+    /// Returns `true` if the given transaction has an expiration height, and the latest block has reached it.
+    fn is_transaction_expired(&self, transaction_id: &N::TransactionID) -> bool {
+        match self.ledger.get_transaction_expiration(transaction_id) {
+            Some(expiration_height) => expiration_height <= self.ledger.latest_block_height(),
+            None => false,
+        }
+    }
 }
 
 impl<N: Network> Consensus<N> {
@@ -319,7 +656,14 @@ impl<N: Network> Consensus<N> {
         }
         // Send the callback **after** advancing to the next block.
         // Note: We must await the block to be advanced before sending the callback.
+        let is_advanced = result.is_ok();
         callback.send(result).ok();
+        // If the block advanced, re-evaluate the orphan transactions, and send the queued transactions
+        // to the primary, as the workers have capacity again.
+        if is_advanced {
+            self.reevaluate_orphans().await;
+            self.send_mempool_transactions_to_primary().await;
+        }
     }
 
     /// Attempts to advance to the next block.
@@ -340,6 +684,23 @@ impl<N: Network> Consensus<N> {
         // Advance to the next block.
         self.ledger.advance_to_next_block(&next_block)?;
 
+        // Record the priority fees of the block.
+        self.record_block_fees(&next_block);
+
+        // Remove the confirmed transactions, and the transactions that conflict with them, from the mempool.
+        // Note: The ledger service stops tracking the expiration heights of the confirmed transactions.
+        next_block.transaction_ids().for_each(|transaction_id| {
+            self.mempool.remove(transaction_id);
+            let height = next_block.height();
+            self.publish_mempool_event(MempoolEvent::Confirmed { transaction_id: *transaction_id, height });
+        });
+        let spends = next_block.serial_numbers().chain(next_block.commitments());
+        for transaction_id in self.mempool.remove_conflicts(spends) {
+            debug!("Removed transaction '{}' from the mempool {}", fmt_id(transaction_id), "(conflict)".dimmed());
+            self.ledger.remove_transaction_expiration(&transaction_id);
+            self.publish_mempool_event(MempoolEvent::Removed { transaction_id, reason: "conflict" });
+        }
+
         #[cfg(feature = "metrics")]
         {
             let elapsed = std::time::Duration::from_secs((snarkos_node_bft::helpers::now() - start) as u64);
@@ -381,6 +742,10 @@ impl<N: Network> Consensus<N> {
                 self.primary_sender().tx_unconfirmed_solution.send((commitment, solution, callback)).await?;
             }
             (TransmissionID::Transaction(transaction_id), Transmission::Transaction(transaction)) => {
+                // Ensure the transaction has not expired.
+                if self.is_transaction_expired(&transaction_id) {
+                    bail!("Transaction '{}' has expired {}", fmt_id(transaction_id), "(skipping)".dimmed());
+                }
                 // Send the transaction to the primary.
                 self.primary_sender().tx_unconfirmed_transaction.send((transaction_id, transaction, callback)).await?;
             }
@@ -398,9 +763,31 @@ impl<N: Network> Consensus<N> {
     /// Shuts down the BFT.
     pub async fn shut_down(&self) {
         info!("Shutting down consensus...");
+        // Persist the mempool, before the workers are shut down.
+        if let Err(e) = self.persist_mempool() {
+            warn!("Failed to persist the mempool snapshot - {e}");
+        }
         // Shut down the BFT.
         self.bft.shut_down().await;
         // Abort the tasks.
         self.handles.lock().iter().for_each(|handle| handle.abort());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_transaction_expiration() {
+        // Ensure a transaction within the validity window is accepted.
+        assert!(check_transaction_expiration(10, 11, 5).is_ok());
+        assert!(check_transaction_expiration(10, 15, 5).is_ok());
+        // Ensure an expired transaction is rejected.
+        assert!(check_transaction_expiration(10, 10, 5).is_err());
+        assert!(check_transaction_expiration(10, 0, 5).is_err());
+        // Ensure a transaction dated too far into the future is rejected.
+        assert!(check_transaction_expiration(10, 16, 5).is_err());
+        assert!(check_transaction_expiration(0, u32::MAX, MAX_TRANSACTION_VALIDITY_IN_BLOCKS).is_err());
+    }
+}
@@ -0,0 +1,632 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{ledger::block::Transaction, prelude::*};
+
+use anyhow::Result;
+use parking_lot::Mutex;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+    time::{Duration, Instant},
+};
+
+/// The default maximum size of the unconfirmed transactions in the mempool.
+pub const MAX_MEMPOOL_SIZE_IN_BYTES: usize = 128 * 1024 * 1024; // bytes
+/// The default minimum fee increase, over the total fee of the conflicting transactions, to replace them.
+pub const MIN_REPLACEMENT_FEE_INCREASE_PERCENT: u64 = 10; // percent
+/// The default time-to-live of an unconfirmed transaction in the mempool, after which it is dropped.
+pub const MEMPOOL_TRANSACTION_TTL_IN_SECS: u64 = 60 * 60; // 1 hour
+/// The maximum number of mempool events that are buffered for a lagging subscriber.
+pub const MEMPOOL_EVENT_CAPACITY: usize = 1024;
+
+/// A summary of an unconfirmed transaction in the mempool.
+#[derive(Clone, Debug)]
+pub struct MempoolEntry<N: Network> {
+    /// The ID of the transaction.
+    pub transaction_id: N::TransactionID,
+    /// The fee of the transaction.
+    pub fee: u64,
+    /// The priority fee of the transaction.
+    pub priority_fee: u64,
+    /// The size of the transaction in bytes.
+    pub size_in_bytes: usize,
+    /// The time since the transaction was inserted into the mempool.
+    pub age: Duration,
+}
+
+/// An event in the lifecycle of an unconfirmed transaction, which is published to the mempool subscribers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MempoolEvent<N: Network> {
+    /// The transaction was admitted to the mempool.
+    Added { transaction_id: N::TransactionID },
+    /// The transaction was dropped from the mempool without being confirmed, for the given reason.
+    Removed { transaction_id: N::TransactionID, reason: &'static str },
+    /// The transaction was confirmed in the block at the given height.
+    Confirmed { transaction_id: N::TransactionID, height: u32 },
+}
+
+/// An entry in the fee queue.
+#[derive(Debug)]
+struct Entry<V> {
+    /// The value of the entry.
+    value: V,
+    /// The fee of the entry.
+    fee: u64,
+    /// The size of the entry in bytes.
+    size: usize,
+    /// The sequence number of the entry, which orders the entries by age.
+    sequence: u64,
+    /// The time at which the entry was inserted.
+    inserted_at: Instant,
+}
+
+/// The queue of entries ordered by fee, and then by age, with a cap on the total size of the entries.
+#[derive(Debug)]
+struct FeeQueue<K, V> {
+    /// The maximum total size of the entries in bytes.
+    max_size_in_bytes: usize,
+    /// The total size of the entries in bytes.
+    size_in_bytes: usize,
+    /// The sequence number of the next entry.
+    next_sequence: u64,
+    /// The map of keys to their entries.
+    entries: HashMap<K, Entry<V>>,
+    /// The map of `(fee, age)` to the key, from the lowest priority to the highest priority.
+    priorities: BTreeMap<(u64, Reverse<u64>), K>,
+}
+
+impl<K: Copy + Eq + Hash, V> FeeQueue<K, V> {
+    /// Initializes a new fee queue with the given maximum total size in bytes.
+    fn new(max_size_in_bytes: usize) -> Self {
+        Self {
+            max_size_in_bytes,
+            size_in_bytes: 0,
+            next_sequence: 0,
+            entries: Default::default(),
+            priorities: Default::default(),
+        }
+    }
+
+    /// Inserts the given entry in place of the given replaced entries, evicting the lowest-fee entries
+    /// if the queue is full, and returns the replaced and evicted entries.
+    ///
+    /// If the entry does not fit, even after evicting all of the entries with a lower fee, it is rejected,
+    /// and the replaced entries are retained.
+    fn insert(&mut self, key: K, value: V, fee: u64, size: usize, replaced: &[K]) -> Result<Vec<(K, V)>> {
+        // Ensure the entry is not already in the queue.
+        if self.entries.contains_key(&key) {
+            bail!("The entry already exists in the queue")
+        }
+        // Determine the number of bytes to free, for the entry to fit, once the replaced entries are removed.
+        let num_bytes_replaced = replaced.iter().filter_map(|key| self.entries.get(key)).map(|entry| entry.size).sum();
+        let num_bytes_to_free =
+            (self.size_in_bytes + size).saturating_sub(self.max_size_in_bytes).saturating_sub(num_bytes_replaced);
+        // Select the lowest-fee entries to evict, until enough bytes are freed.
+        let mut evicted = Vec::new();
+        let mut num_bytes_freed = 0;
+        for ((entry_fee, _), entry_key) in self.priorities.iter().filter(|(_, key)| !replaced.contains(*key)) {
+            if num_bytes_freed >= num_bytes_to_free || *entry_fee >= fee {
+                break;
+            }
+            num_bytes_freed += self.entries.get(entry_key).map_or(0, |entry| entry.size);
+            evicted.push(*entry_key);
+        }
+        // Ensure enough bytes are freed.
+        if num_bytes_freed < num_bytes_to_free {
+            bail!("The queue is full, and the fee ({fee}) is too low to evict other entries")
+        }
+        // Remove the replaced and evicted entries.
+        let removed = replaced
+            .iter()
+            .chain(evicted.iter())
+            .filter_map(|removed_key| self.remove(removed_key).map(|value| (*removed_key, value)))
+            .collect();
+        // Insert the entry.
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        self.size_in_bytes += size;
+        self.priorities.insert((fee, Reverse(sequence)), key);
+        self.entries.insert(key, Entry { value, fee, size, sequence, inserted_at: Instant::now() });
+        Ok(removed)
+    }
+
+    /// Removes the entry for the given key, returning its value.
+    fn remove(&mut self, key: &K) -> Option<V> {
+        let entry = self.entries.remove(key)?;
+        self.size_in_bytes -= entry.size;
+        self.priorities.remove(&(entry.fee, Reverse(entry.sequence)));
+        Some(entry.value)
+    }
+
+    /// Removes the entries that were inserted more than the given time-to-live before the given time,
+    /// returning their keys and values.
+    fn remove_expired(&mut self, now: Instant, ttl: Duration) -> Vec<(K, V)> {
+        let is_expired = |entry: &Entry<V>| now.saturating_duration_since(entry.inserted_at) > ttl;
+        let expired =
+            self.entries.iter().filter_map(|(key, entry)| is_expired(entry).then_some(*key)).collect::<Vec<_>>();
+        expired.into_iter().filter_map(|key| self.remove(&key).map(|value| (key, value))).collect()
+    }
+
+    /// Removes the entry with the highest fee (and the oldest, among equal fees), returning its key and value.
+    fn pop(&mut self) -> Option<(K, V)> {
+        let (_, key) = self.priorities.pop_last()?;
+        let entry = self.entries.remove(&key)?;
+        self.size_in_bytes -= entry.size;
+        Some((key, entry.value))
+    }
+}
+
+/// Returns the minimum fee to replace conflicting transactions with the given total fee,
+/// given the minimum fee increase in percent.
+fn min_replacement_fee(conflicting_fee: u64, increase_percent: u64) -> u64 {
+    let increase = (conflicting_fee as u128 * increase_percent as u128 + 99) / 100;
+    u64::try_from(conflicting_fee as u128 + increase).unwrap_or(u64::MAX)
+}
+
+/// A transaction that was handed to the workers, and is not yet committed.
+#[derive(Debug)]
+struct InFlight<N: Network> {
+    /// The serial numbers and commitments of the transaction.
+    spends: Vec<Field<N>>,
+    /// The time at which the transaction was handed to the workers.
+    dispatched_at: Instant,
+}
+
+/// The transactions in the mempool, along with the index of their serial numbers and commitments.
+#[derive(Debug)]
+struct Pool<N: Network> {
+    /// The queue of unconfirmed transactions.
+    queue: FeeQueue<N::TransactionID, Transaction<N>>,
+    /// The transactions handed to the workers, which remain in the index until they are committed or expire.
+    in_flight: HashMap<N::TransactionID, InFlight<N>>,
+    /// The map of the serial numbers and commitments of the transactions to their transaction ID.
+    spends: HashMap<Field<N>, N::TransactionID>,
+}
+
+impl<N: Network> Pool<N> {
+    /// Removes the serial numbers and commitments of the given transaction from the index.
+    fn unindex(&mut self, transaction_id: &N::TransactionID, transaction: &Transaction<N>) {
+        self.unindex_spends(transaction_id, transaction.serial_numbers().chain(transaction.commitments()));
+    }
+
+    /// Removes the given serial numbers and commitments of the given transaction from the index.
+    fn unindex_spends<'a>(&mut self, transaction_id: &N::TransactionID, spends: impl Iterator<Item = &'a Field<N>>) {
+        for id in spends {
+            if self.spends.get(id) == Some(transaction_id) {
+                self.spends.remove(id);
+            }
+        }
+    }
+
+    /// Removes the given transaction from the in-flight transactions and from the index,
+    /// returning `true` if it was in flight.
+    fn remove_in_flight(&mut self, transaction_id: &N::TransactionID) -> bool {
+        match self.in_flight.remove(transaction_id) {
+            Some(in_flight) => {
+                self.unindex_spends(transaction_id, in_flight.spends.iter());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes the given transaction from the queue, and tracks it as in flight, keeping it in the index.
+    fn dispatch(&mut self, transaction_id: N::TransactionID, transaction: &Transaction<N>) {
+        let spends = transaction.serial_numbers().chain(transaction.commitments()).copied().collect();
+        self.in_flight.insert(transaction_id, InFlight { spends, dispatched_at: Instant::now() });
+    }
+}
+
+/// The mempool of verified unconfirmed transactions, which are ordered by fee, and then by age.
+///
+/// The total size of the transactions is capped, and once the cap is reached, the transactions with the lowest
+/// fee are evicted to make room for a transaction with a higher fee. The transactions with the highest priority
+/// are the candidates for the `BlockTemplate`, which is sent to the workers for inclusion in a batch.
+///
+/// A transaction that spends a serial number, or produces a commitment, of another transaction in the mempool
+/// is a conflict. A conflicting transaction is rejected, unless replacement is enabled and its fee exceeds the
+/// total fee of the conflicting transactions by the minimum fee increase, in which case it replaces them.
+/// The transactions handed to the workers remain in the conflict index until they are committed or expire,
+/// and a transaction that conflicts with them is always rejected, as they can no longer be replaced.
+///
+/// Each transaction has a time-to-live, counted from its arrival in the mempool, after which it is dropped,
+/// so that transactions whose fee is too low to be drained from the mempool do not linger indefinitely.
+#[derive(Debug)]
+pub struct Mempool<N: Network> {
+    /// The transactions in the mempool.
+    pool: Mutex<Pool<N>>,
+    /// The minimum fee increase (in percent) to replace conflicting transactions, or `None` if replacement is disabled.
+    min_replacement_fee_increase: Option<u64>,
+    /// The time-to-live of a transaction in the mempool.
+    ttl: Duration,
+}
+
+impl<N: Network> Default for Mempool<N> {
+    /// Initializes a new instance of the mempool.
+    fn default() -> Self {
+        let ttl = Duration::from_secs(MEMPOOL_TRANSACTION_TTL_IN_SECS);
+        Self::new(MAX_MEMPOOL_SIZE_IN_BYTES, Some(MIN_REPLACEMENT_FEE_INCREASE_PERCENT), ttl)
+    }
+}
+
+impl<N: Network> Mempool<N> {
+    /// Initializes a new mempool with the given maximum total size of the transactions in bytes,
+    /// the minimum fee increase (in percent) to replace conflicting transactions, if replacement is enabled,
+    /// and the time-to-live of a transaction.
+    pub fn new(max_size_in_bytes: usize, min_replacement_fee_increase: Option<u64>, ttl: Duration) -> Self {
+        let pool =
+            Pool { queue: FeeQueue::new(max_size_in_bytes), in_flight: Default::default(), spends: Default::default() };
+        Self { pool: Mutex::new(pool), min_replacement_fee_increase, ttl }
+    }
+
+    /// Returns the time-to-live of a transaction in the mempool.
+    pub const fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Returns the number of transactions in the mempool.
+    pub fn len(&self) -> usize {
+        self.pool.lock().queue.entries.len()
+    }
+
+    /// Returns `true` if the mempool is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the total size of the transactions in the mempool in bytes.
+    pub fn size_in_bytes(&self) -> usize {
+        self.pool.lock().queue.size_in_bytes
+    }
+
+    /// Returns the maximum total size of the transactions in the mempool in bytes.
+    pub fn max_size_in_bytes(&self) -> usize {
+        self.pool.lock().queue.max_size_in_bytes
+    }
+
+    /// Returns `true` if the mempool contains the given transaction ID.
+    pub fn contains(&self, transaction_id: &N::TransactionID) -> bool {
+        self.pool.lock().queue.entries.contains_key(transaction_id)
+    }
+
+    /// Returns the ID of the transaction in the mempool that spends the given serial number, or produces
+    /// the given commitment, if any.
+    pub fn find_spender(&self, id: &Field<N>) -> Option<N::TransactionID> {
+        self.pool.lock().spends.get(id).copied()
+    }
+
+    /// Returns the transaction IDs in the mempool, from the highest priority to the lowest priority.
+    pub fn transaction_ids(&self) -> Vec<N::TransactionID> {
+        self.pool.lock().queue.priorities.values().rev().copied().collect()
+    }
+
+    /// Returns the transactions in the mempool, from the highest priority to the lowest priority.
+    pub fn transactions(&self) -> Vec<(N::TransactionID, Transaction<N>)> {
+        let pool = self.pool.lock();
+        let transaction_ids = pool.queue.priorities.values().rev();
+        transaction_ids.filter_map(|id| pool.queue.entries.get(id).map(|entry| (*id, entry.value.clone()))).collect()
+    }
+
+    /// Returns the summaries of the transactions in the mempool, from the highest priority to the lowest priority.
+    pub fn entries(&self) -> Vec<MempoolEntry<N>> {
+        let pool = self.pool.lock();
+        let transaction_ids = pool.queue.priorities.values().rev();
+        transaction_ids
+            .filter_map(|transaction_id| {
+                let entry = pool.queue.entries.get(transaction_id)?;
+                Some(MempoolEntry {
+                    transaction_id: *transaction_id,
+                    fee: entry.fee,
+                    priority_fee: entry.value.priority_fee_amount().map_or(0, |priority_fee| *priority_fee),
+                    size_in_bytes: entry.size,
+                    age: entry.inserted_at.elapsed(),
+                })
+            })
+            .collect()
+    }
+
+    /// Inserts the given transaction into the mempool, replacing the conflicting transactions if permitted,
+    /// and evicting the transactions with the lowest fee if it is full.
+    /// Returns the IDs of the replaced and evicted transactions.
+    pub fn insert(&self, transaction: Transaction<N>) -> Result<Vec<N::TransactionID>> {
+        let transaction_id = transaction.id();
+        // Retrieve the fee and size of the transaction.
+        let fee = *transaction.fee_amount()?;
+        let size = transaction.to_bytes_le()?.len();
+
+        let mut pool = self.pool.lock();
+        // Retrieve the transactions that conflict with the transaction.
+        let mut conflicts = Vec::new();
+        for id in transaction.serial_numbers().chain(transaction.commitments()) {
+            if let Some(conflict_id) = pool.spends.get(id) {
+                if !conflicts.contains(conflict_id) {
+                    conflicts.push(*conflict_id);
+                }
+            }
+        }
+        // Ensure the transaction does not conflict with a transaction that was handed to the workers.
+        if let Some(conflict_id) = conflicts.iter().find(|id| pool.in_flight.contains_key(id)) {
+            bail!("Transaction '{transaction_id}' conflicts with in-flight transaction '{conflict_id}'")
+        }
+        // Ensure the transaction may replace the conflicting transactions.
+        if let Some(conflict_id) = conflicts.first() {
+            let Some(increase_percent) = self.min_replacement_fee_increase else {
+                bail!("Transaction '{transaction_id}' conflicts with transaction '{conflict_id}' in the mempool")
+            };
+            let conflicting_fee = conflicts
+                .iter()
+                .filter_map(|id| pool.queue.entries.get(id))
+                .fold(0u64, |total, entry| total.saturating_add(entry.fee));
+            let min_fee = min_replacement_fee(conflicting_fee, increase_percent);
+            if fee < min_fee {
+                bail!(
+                    "Transaction '{transaction_id}' conflicts with {} transaction(s) in the mempool, \
+                     and its fee ({fee}) is below the replacement fee ({min_fee})",
+                    conflicts.len()
+                )
+            }
+        }
+
+        // Insert the transaction into the queue.
+        let removed = pool
+            .queue
+            .insert(transaction_id, transaction, fee, size, &conflicts)
+            .map_err(|e| anyhow!("Cannot add transaction '{transaction_id}' to the mempool - {e}"))?;
+        // Remove the serial numbers and commitments of the replaced and evicted transactions from the index.
+        for (removed_id, removed_transaction) in &removed {
+            pool.unindex(removed_id, removed_transaction);
+        }
+        // Index the serial numbers and commitments of the transaction.
+        if let Some(entry) = pool.queue.entries.get(&transaction_id) {
+            let ids = entry.value.serial_numbers().chain(entry.value.commitments()).copied().collect::<Vec<_>>();
+            pool.spends.extend(ids.into_iter().map(|id| (id, transaction_id)));
+        }
+        Ok(removed.into_iter().map(|(removed_id, _)| removed_id).collect())
+    }
+
+    /// Returns `true` if the given transaction was handed to the workers, and is not yet committed.
+    pub fn is_in_flight(&self, transaction_id: &N::TransactionID) -> bool {
+        self.pool.lock().in_flight.contains_key(transaction_id)
+    }
+
+    /// Removes the given transaction from the mempool, or from the in-flight transactions,
+    /// returning the transaction if it was in the mempool.
+    pub fn remove(&self, transaction_id: &N::TransactionID) -> Option<Transaction<N>> {
+        let mut pool = self.pool.lock();
+        if pool.remove_in_flight(transaction_id) {
+            return None;
+        }
+        let transaction = pool.queue.remove(transaction_id)?;
+        pool.unindex(transaction_id, &transaction);
+        Some(transaction)
+    }
+
+    /// Removes the transactions that spend any of the given serial numbers, or produce any of the given commitments,
+    /// from the mempool and the in-flight transactions, and returns their IDs.
+    pub fn remove_conflicts<'a>(&self, ids: impl IntoIterator<Item = &'a Field<N>>) -> Vec<N::TransactionID> {
+        let mut pool = self.pool.lock();
+        let mut removed = Vec::new();
+        for id in ids {
+            if let Some(transaction_id) = pool.spends.get(id).copied() {
+                if pool.remove_in_flight(&transaction_id) {
+                    removed.push(transaction_id);
+                } else if let Some(transaction) = pool.queue.remove(&transaction_id) {
+                    pool.unindex(&transaction_id, &transaction);
+                    removed.push(transaction_id);
+                }
+            }
+        }
+        removed
+    }
+
+    /// Removes the transactions that have been in the mempool, or in flight, for longer than the time-to-live,
+    /// and returns their IDs.
+    pub fn remove_expired(&self) -> Vec<N::TransactionID> {
+        let now = Instant::now();
+        let mut pool = self.pool.lock();
+        let expired = pool.queue.remove_expired(now, self.ttl);
+        for (transaction_id, transaction) in &expired {
+            pool.unindex(transaction_id, transaction);
+        }
+        let mut expired = expired.into_iter().map(|(transaction_id, _)| transaction_id).collect::<Vec<_>>();
+        // Remove the in-flight transactions that were not committed within the time-to-live.
+        let is_expired = |in_flight: &InFlight<N>| now.saturating_duration_since(in_flight.dispatched_at) > self.ttl;
+        let expired_in_flight = pool
+            .in_flight
+            .iter()
+            .filter_map(|(transaction_id, in_flight)| is_expired(in_flight).then_some(*transaction_id))
+            .collect::<Vec<_>>();
+        for transaction_id in expired_in_flight {
+            pool.remove_in_flight(&transaction_id);
+            expired.push(transaction_id);
+        }
+        expired
+    }
+
+    /// Removes the given transaction from the mempool, to hand it to the workers, and returns the transaction.
+    /// The transaction remains in the conflict index, until it is committed or expires.
+    pub fn dispatch(&self, transaction_id: &N::TransactionID) -> Option<Transaction<N>> {
+        let mut pool = self.pool.lock();
+        let transaction = pool.queue.remove(transaction_id)?;
+        pool.dispatch(*transaction_id, &transaction);
+        Some(transaction)
+    }
+
+    /// Returns up to the given number of transactions with the highest priority, without removing them.
+    pub fn peek(&self, num_transactions: usize) -> Vec<(N::TransactionID, Transaction<N>)> {
+        let pool = self.pool.lock();
+        let transaction_ids = pool.queue.priorities.values().rev().take(num_transactions);
+        transaction_ids.filter_map(|id| pool.queue.entries.get(id).map(|entry| (*id, entry.value.clone()))).collect()
+    }
+
+    /// Removes up to the given number of transactions with the highest priority from the mempool, to hand them
+    /// to the workers, and returns them. The transactions remain in the conflict index, until they are committed
+    /// or expire.
+    pub fn pop(&self, num_transactions: usize) -> Vec<(N::TransactionID, Transaction<N>)> {
+        let mut pool = self.pool.lock();
+        let mut transactions = Vec::with_capacity(num_transactions.min(pool.queue.entries.len()));
+        while transactions.len() < num_transactions {
+            let Some((transaction_id, transaction)) = pool.queue.pop() else {
+                break;
+            };
+            pool.dispatch(transaction_id, &transaction);
+            transactions.push((transaction_id, transaction));
+        }
+        transactions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::ledger::ledger_test_helpers::sample_fee_public_transaction;
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_fee_queue_priority() {
+        let mut queue = FeeQueue::<u32, &str>::new(100);
+
+        // Insert entries with different fees, and equal fees of different ages.
+        assert!(queue.insert(1, "low", 1, 10, &[]).unwrap().is_empty());
+        assert!(queue.insert(2, "high", 5, 10, &[]).unwrap().is_empty());
+        assert!(queue.insert(3, "old", 3, 10, &[]).unwrap().is_empty());
+        assert!(queue.insert(4, "new", 3, 10, &[]).unwrap().is_empty());
+        assert!(queue.insert(4, "duplicate", 3, 10, &[]).is_err());
+        assert_eq!(queue.size_in_bytes, 40);
+
+        // Ensure the entries are removed by fee, and then by age.
+        assert_eq!(queue.pop(), Some((2, "high")));
+        assert_eq!(queue.pop(), Some((3, "old")));
+        assert_eq!(queue.remove(&1), Some("low"));
+        assert_eq!(queue.pop(), Some((4, "new")));
+        assert_eq!(queue.pop(), None);
+        assert_eq!(queue.size_in_bytes, 0);
+        assert!(queue.priorities.is_empty());
+    }
+
+    #[test]
+    fn test_fee_queue_eviction() {
+        let mut queue = FeeQueue::<u32, ()>::new(30);
+        queue.insert(1, (), 2, 10, &[]).unwrap();
+        queue.insert(2, (), 1, 10, &[]).unwrap();
+        queue.insert(3, (), 3, 10, &[]).unwrap();
+
+        // Ensure an entry that only fits by evicting entries with an equal or higher fee is rejected.
+        assert!(queue.insert(4, (), 1, 10, &[]).is_err());
+        // Ensure an entry that exceeds the cap is rejected.
+        assert!(queue.insert(5, (), 10, 31, &[]).is_err());
+        assert_eq!(queue.entries.len(), 3);
+
+        // Ensure the lowest-fee entries are evicted to make room for an entry with a higher fee.
+        assert_eq!(queue.insert(6, (), 4, 15, &[]).unwrap(), vec![(2, ()), (1, ())]);
+        assert_eq!(queue.size_in_bytes, 25);
+        assert_eq!(queue.pop(), Some((6, ())));
+        assert_eq!(queue.pop(), Some((3, ())));
+    }
+
+    #[test]
+    fn test_fee_queue_replacement() {
+        let mut queue = FeeQueue::<u32, ()>::new(30);
+        queue.insert(1, (), 5, 10, &[]).unwrap();
+        queue.insert(2, (), 1, 10, &[]).unwrap();
+        queue.insert(3, (), 2, 10, &[]).unwrap();
+
+        // Ensure the replaced entries are retained, if the entry does not fit.
+        assert!(queue.insert(4, (), 3, 25, &[3]).is_err());
+        assert_eq!(queue.entries.len(), 3);
+
+        // Ensure the space of the replaced entries is reused, before evicting other entries.
+        assert_eq!(queue.insert(4, (), 3, 15, &[3]).unwrap(), vec![(3, ()), (2, ())]);
+        assert_eq!(queue.size_in_bytes, 25);
+        assert_eq!(queue.entries.len(), 2);
+    }
+
+    #[test]
+    fn test_fee_queue_expiry() {
+        let mut queue = FeeQueue::<u32, ()>::new(30);
+        queue.insert(1, (), 1, 10, &[]).unwrap();
+        queue.insert(2, (), 2, 10, &[]).unwrap();
+        let ttl = Duration::from_secs(60);
+
+        // Ensure no entries expire within the time-to-live.
+        assert!(queue.remove_expired(Instant::now(), ttl).is_empty());
+        // Ensure each entry expires once its own time-to-live has elapsed.
+        queue.entries.get_mut(&2).unwrap().inserted_at += ttl;
+        let now = Instant::now() + ttl + Duration::from_secs(1);
+        assert_eq!(queue.remove_expired(now, ttl), vec![(1, ())]);
+        assert_eq!(queue.size_in_bytes, 10);
+        assert!(queue.remove_expired(now, ttl).is_empty());
+        assert_eq!(queue.remove_expired(now + ttl, ttl), vec![(2, ())]);
+        assert!(queue.priorities.is_empty());
+    }
+
+    #[test]
+    fn test_in_flight_conflicts() {
+        let rng = &mut TestRng::default();
+        let mempool = Mempool::<CurrentNetwork>::default();
+        let transaction = sample_fee_public_transaction(rng);
+        let transaction_id = transaction.id();
+        mempool.insert(transaction).unwrap();
+
+        // Hand the transaction to the workers, and index a serial number that it spends.
+        let spend = Field::rand(rng);
+        assert!(mempool.dispatch(&transaction_id).is_some());
+        {
+            let mut pool = mempool.pool.lock();
+            pool.in_flight.get_mut(&transaction_id).unwrap().spends.push(spend);
+            pool.spends.insert(spend, transaction_id);
+        }
+        // Ensure the in-flight transaction leaves the queue, but remains in the conflict index.
+        assert!(mempool.is_empty());
+        assert!(mempool.is_in_flight(&transaction_id));
+        assert_eq!(mempool.find_spender(&spend), Some(transaction_id));
+
+        // Ensure the in-flight transaction is removed from the index once a conflicting transaction is committed.
+        assert_eq!(mempool.remove_conflicts([&spend]), vec![transaction_id]);
+        assert!(!mempool.is_in_flight(&transaction_id));
+        assert_eq!(mempool.find_spender(&spend), None);
+
+        // Ensure the in-flight transaction is removed from the index once it is committed.
+        let transaction = sample_fee_public_transaction(rng);
+        let transaction_id = transaction.id();
+        mempool.insert(transaction).unwrap();
+        assert_eq!(mempool.pop(1).len(), 1);
+        assert!(mempool.is_in_flight(&transaction_id));
+        assert!(mempool.remove(&transaction_id).is_none());
+        assert!(!mempool.is_in_flight(&transaction_id));
+
+        // Ensure the in-flight transaction expires once the time-to-live has elapsed.
+        let mempool = Mempool::<CurrentNetwork>::new(MAX_MEMPOOL_SIZE_IN_BYTES, None, Duration::from_millis(500));
+        let transaction = sample_fee_public_transaction(rng);
+        let transaction_id = transaction.id();
+        mempool.insert(transaction).unwrap();
+        assert!(mempool.dispatch(&transaction_id).is_some());
+        assert!(mempool.remove_expired().is_empty());
+        std::thread::sleep(Duration::from_secs(1));
+        assert_eq!(mempool.remove_expired(), vec![transaction_id]);
+        assert!(!mempool.is_in_flight(&transaction_id));
+    }
+
+    #[test]
+    fn test_min_replacement_fee() {
+        assert_eq!(min_replacement_fee(0, 10), 0);
+        assert_eq!(min_replacement_fee(100, 10), 110);
+        assert_eq!(min_replacement_fee(101, 10), 112);
+        assert_eq!(min_replacement_fee(100, 0), 100);
+        assert_eq!(min_replacement_fee(u64::MAX, 10), u64::MAX);
+    }
+}
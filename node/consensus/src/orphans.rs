@@ -0,0 +1,186 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{ledger::block::Transaction, prelude::*};
+
+use indexmap::IndexMap;
+use parking_lot::Mutex;
+use std::{
+    hash::Hash,
+    time::{Duration, Instant},
+};
+
+/// The maximum number of orphan transactions that are parked at once.
+pub const MAX_ORPHAN_TRANSACTIONS: usize = 1024;
+/// The time-to-live of an orphan transaction, after which it is dropped if its dependencies are still missing.
+pub const ORPHAN_TTL_IN_SECS: u64 = 10 * 60; // 10 minutes
+
+/// An entry in the orphan queue.
+#[derive(Debug)]
+struct Orphan<V, D> {
+    /// The value of the entry.
+    value: V,
+    /// The dependencies of the entry that are missing.
+    missing: Vec<D>,
+    /// The time at which the entry was parked.
+    parked_at: Instant,
+}
+
+/// The queue of entries with missing dependencies, in the order in which they were parked.
+#[derive(Debug)]
+struct OrphanQueue<K, V, D> {
+    /// The maximum number of entries.
+    max_orphans: usize,
+    /// The map of keys to their entries.
+    orphans: IndexMap<K, Orphan<V, D>>,
+}
+
+impl<K: Copy + Eq + Hash, V, D> OrphanQueue<K, V, D> {
+    /// Initializes a new orphan queue with the given maximum number of entries.
+    fn new(max_orphans: usize) -> Self {
+        Self { max_orphans, orphans: Default::default() }
+    }
+
+    /// Parks the given entry with the given missing dependencies, evicting the oldest entry if the queue is full.
+    /// Returns the key of the evicted entry, if any.
+    fn insert(&mut self, key: K, value: V, missing: Vec<D>) -> Option<K> {
+        // If the entry is already parked, return early.
+        if self.orphans.contains_key(&key) {
+            return None;
+        }
+        // If the queue is full, evict the oldest entry.
+        let evicted = match self.orphans.len() >= self.max_orphans {
+            true => self.orphans.shift_remove_index(0).map(|(evicted_key, _)| evicted_key),
+            false => None,
+        };
+        self.orphans.insert(key, Orphan { value, missing, parked_at: Instant::now() });
+        evicted
+    }
+
+    /// Removes the entries whose dependencies are all available, according to the given function,
+    /// along with the entries that were parked more than the given time-to-live before the given time.
+    /// Returns the values of the ready entries, and the keys of the expired entries.
+    fn take_ready(&mut self, now: Instant, ttl: Duration, is_available: impl Fn(&D) -> bool) -> (Vec<V>, Vec<K>) {
+        let mut ready = Vec::new();
+        let mut expired = Vec::new();
+        for (key, orphan) in self.orphans.iter_mut() {
+            // Forget the dependencies that have become available.
+            orphan.missing.retain(|dependency| !is_available(dependency));
+            if orphan.missing.is_empty() {
+                ready.push(*key);
+            } else if now.saturating_duration_since(orphan.parked_at) > ttl {
+                expired.push(*key);
+            }
+        }
+        // Remove the ready and expired entries.
+        let ready = ready.iter().filter_map(|key| self.orphans.shift_remove(key).map(|orphan| orphan.value)).collect();
+        for key in &expired {
+            self.orphans.shift_remove(key);
+        }
+        (ready, expired)
+    }
+}
+
+/// The pool of orphan transactions, which reference a global state root that is not yet in the ledger.
+///
+/// A transaction is proven against a recent global state root, which includes the records that it spends.
+/// If this node has not yet advanced to the block with that state root, e.g. because the transaction spends
+/// a record created by a transaction that was only just confirmed, it cannot be verified yet. Instead of rejecting
+/// such a transaction, it is parked in this bounded pool, and re-evaluated once the ledger advances.
+#[derive(Debug)]
+pub struct OrphanPool<N: Network> {
+    /// The queue of orphan transactions, along with their missing global state roots.
+    queue: Mutex<OrphanQueue<N::TransactionID, Transaction<N>, N::StateRoot>>,
+    /// The time-to-live of an orphan transaction.
+    ttl: Duration,
+}
+
+impl<N: Network> Default for OrphanPool<N> {
+    /// Initializes a new instance of the orphan pool.
+    fn default() -> Self {
+        Self::new(MAX_ORPHAN_TRANSACTIONS, Duration::from_secs(ORPHAN_TTL_IN_SECS))
+    }
+}
+
+impl<N: Network> OrphanPool<N> {
+    /// Initializes a new orphan pool with the given maximum number of transactions, and time-to-live.
+    pub fn new(max_orphans: usize, ttl: Duration) -> Self {
+        Self { queue: Mutex::new(OrphanQueue::new(max_orphans)), ttl }
+    }
+
+    /// Returns the number of orphan transactions.
+    pub fn len(&self) -> usize {
+        self.queue.lock().orphans.len()
+    }
+
+    /// Returns `true` if there are no orphan transactions.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the given transaction is parked as an orphan.
+    pub fn contains(&self, transaction_id: &N::TransactionID) -> bool {
+        self.queue.lock().orphans.contains_key(transaction_id)
+    }
+
+    /// Parks the given transaction, until the given global state roots are in the ledger.
+    /// Returns the ID of the oldest orphan transaction, if it was evicted to make room.
+    pub fn insert(
+        &self,
+        transaction: Transaction<N>,
+        missing_state_roots: Vec<N::StateRoot>,
+    ) -> Option<N::TransactionID> {
+        self.queue.lock().insert(transaction.id(), transaction, missing_state_roots)
+    }
+
+    /// Removes the orphan transactions whose global state roots are all in the ledger, according to the given
+    /// function, along with the orphan transactions that have expired. Returns the ready transactions,
+    /// and the IDs of the expired transactions.
+    pub fn take_ready(
+        &self,
+        contains_state_root: impl Fn(&N::StateRoot) -> bool,
+    ) -> (Vec<Transaction<N>>, Vec<N::TransactionID>) {
+        self.queue.lock().take_ready(Instant::now(), self.ttl, contains_state_root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orphan_queue() {
+        let mut queue = OrphanQueue::<u32, &str, u8>::new(2);
+        let ttl = Duration::from_secs(60);
+
+        // Ensure the oldest entry is evicted once the queue is full.
+        assert_eq!(queue.insert(1, "first", vec![1]), None);
+        assert_eq!(queue.insert(1, "duplicate", vec![1]), None);
+        assert_eq!(queue.insert(2, "second", vec![1, 2]), None);
+        assert_eq!(queue.insert(3, "third", vec![3]), Some(1));
+
+        // Ensure an entry is only ready once all of its dependencies are available.
+        let (ready, expired) = queue.take_ready(Instant::now(), ttl, |dependency| *dependency == 1);
+        assert!(ready.is_empty() && expired.is_empty());
+        let (ready, expired) = queue.take_ready(Instant::now(), ttl, |dependency| *dependency == 2);
+        assert_eq!(ready, vec!["second"]);
+        assert!(expired.is_empty());
+
+        // Ensure an entry with missing dependencies expires after the time-to-live.
+        let (ready, expired) = queue.take_ready(Instant::now() + ttl * 2, ttl, |_| false);
+        assert!(ready.is_empty());
+        assert_eq!(expired, vec![3]);
+        assert!(queue.orphans.is_empty());
+    }
+}
@@ -0,0 +1,108 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_bft_storage_service::{read_record, write_record};
+use snarkvm::{
+    ledger::{block::Transaction, narwhal::Data},
+    prelude::*,
+};
+
+use anyhow::Result;
+use std::{fs, io::BufReader, path::Path};
+
+/// The interval at which the mempool is persisted.
+pub const MEMPOOL_PERSIST_INTERVAL_IN_SECS: u64 = 60; // seconds
+/// The maximum number of transactions in a mempool snapshot.
+const MAX_SNAPSHOT_TRANSACTIONS: u32 = 1 << 16;
+
+/// An unconfirmed transaction in a mempool snapshot, along with its expiration height, if any.
+pub type SnapshotEntry<N> = (Data<Transaction<N>>, Option<u32>);
+
+/// Persists the given unconfirmed transactions to the mempool snapshot at the given path.
+///
+/// Each transaction is written as its expiration height (or `0` if it has none), followed by the transaction
+/// as a versioned record. The snapshot is written to a temporary file and renamed, so it is never partially written.
+pub fn write_mempool_snapshot<N: Network>(path: &Path, entries: &[SnapshotEntry<N>]) -> Result<()> {
+    let mut buffer = Vec::new();
+    // Write the number of transactions.
+    u32::try_from(entries.len())?.min(MAX_SNAPSHOT_TRANSACTIONS).write_le(&mut buffer)?;
+    // Write the transactions.
+    for (transaction, expiration_height) in entries.iter().take(MAX_SNAPSHOT_TRANSACTIONS as usize) {
+        expiration_height.unwrap_or(0).write_le(&mut buffer)?;
+        write_record(transaction, &mut buffer)?;
+    }
+    // Write the snapshot to a temporary file, and rename it.
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, buffer)?;
+    fs::rename(temp_path, path)?;
+    Ok(())
+}
+
+/// Returns the unconfirmed transactions in the mempool snapshot at the given path,
+/// or no transactions if the snapshot does not exist.
+///
+/// Note: The transactions are not deserialized, and must be validated before they are admitted to the mempool.
+/// The snapshots written before the transactions were versioned records are read as well.
+pub fn read_mempool_snapshot<N: Network>(path: &Path) -> Result<Vec<SnapshotEntry<N>>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let mut reader = BufReader::new(fs::File::open(path)?);
+    // Read the number of transactions.
+    let num_transactions = u32::read_le(&mut reader)?;
+    if num_transactions > MAX_SNAPSHOT_TRANSACTIONS {
+        bail!("The mempool snapshot at '{}' has too many transactions ({num_transactions})", path.display())
+    }
+    // Read the transactions.
+    let mut entries = Vec::with_capacity(num_transactions as usize);
+    for _ in 0..num_transactions {
+        let expiration_height = u32::read_le(&mut reader)?;
+        let transaction = read_record(&mut reader)?;
+        entries.push((transaction, (expiration_height > 0).then_some(expiration_height)));
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_mempool_snapshot() {
+        let path = std::env::temp_dir().join(format!("mempool-{}", rand::random::<u64>()));
+
+        // Ensure a missing snapshot has no transactions.
+        assert!(read_mempool_snapshot::<CurrentNetwork>(&path).unwrap().is_empty());
+
+        // Ensure the transactions and their expiration heights are restored from the snapshot.
+        let entries: Vec<SnapshotEntry<CurrentNetwork>> =
+            vec![(Data::Buffer(vec![1, 2, 3].into()), Some(10)), (Data::Buffer(vec![4, 5].into()), None)];
+        write_mempool_snapshot(&path, &entries).unwrap();
+        assert_eq!(read_mempool_snapshot::<CurrentNetwork>(&path).unwrap(), entries);
+
+        // Ensure a snapshot of unversioned transactions is read.
+        let mut buffer = 1u32.to_bytes_le().unwrap();
+        buffer.extend(10u32.to_bytes_le().unwrap());
+        buffer.extend(entries[0].0.to_bytes_le().unwrap());
+        fs::write(&path, buffer).unwrap();
+        assert_eq!(read_mempool_snapshot::<CurrentNetwork>(&path).unwrap(), entries[..1]);
+
+        // Ensure a corrupted snapshot is rejected.
+        fs::write(&path, [0xff; 6]).unwrap();
+        assert!(read_mempool_snapshot::<CurrentNetwork>(&path).is_err());
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -19,6 +19,16 @@ pub use names::*;
 // Re-export the snarkVM metrics.
 pub use snarkvm::metrics::*;
 
+/// Increments a counter with the given name and label by one.
+pub fn increment_counter_with_label(name: &'static str, label_key: &'static str, label_value: String) {
+    ::metrics::increment_counter!(name, label_key => label_value);
+}
+
+/// Updates a gauge with the given name and label to the given value.
+pub fn gauge_with_label<V: Into<f64>>(name: &'static str, label_key: &'static str, label_value: String, value: V) {
+    ::metrics::gauge!(name, value.into(), label_key => label_value);
+}
+
 /// Initialises the metrics and returns a handle to the task running the metrics exporter.
 pub fn initialize_metrics() -> tokio::task::JoinHandle<()> {
     use metrics_exporter_prometheus::PrometheusBuilder;
@@ -12,13 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-pub(super) const COUNTER_NAMES: [&str; 1] = [bft::LEADERS_ELECTED];
+pub(super) const COUNTER_NAMES: [&str; 4] =
+    [bft::CERTIFICATES_PRODUCED, bft::LEADERS_ELECTED, ledger::CACHE_HITS, ledger::CACHE_MISSES];
 
-pub(super) const GAUGE_NAMES: [&str; 12] = [
+pub(super) const GAUGE_NAMES: [&str; 15] = [
+    bft::CLOCK_OFFSET,
     bft::CONNECTED,
     bft::CONNECTING,
     bft::LAST_STORED_ROUND,
     bft::PROPOSAL_ROUND,
+    bft::RETAINED_ROUNDS,
+    bft::ROUNDS_PER_SECOND,
     blocks::HEIGHT,
     blocks::TRANSACTIONS,
     consensus::COMMITTED_CERTIFICATES,
@@ -29,8 +33,9 @@ pub(super) const GAUGE_NAMES: [&str; 12] = [
     tcp::TCP_TASKS,
 ];
 
-pub(super) const HISTOGRAM_NAMES: [&str; 6] = [
+pub(super) const HISTOGRAM_NAMES: [&str; 7] = [
     bft::COMMIT_ROUNDS_LATENCY,
+    bft::ROUND_LATENCY,
     consensus::CERTIFICATE_COMMIT_LATENCY,
     tcp::NOISE_CODEC_ENCRYPTION_TIME,
     tcp::NOISE_CODEC_DECRYPTION_TIME,
@@ -39,12 +44,19 @@ pub(super) const HISTOGRAM_NAMES: [&str; 6] = [
 ];
 
 pub mod bft {
+    pub const CERTIFICATES_PRODUCED: &str = "snarkos_bft_certificates_produced_total";
+    pub const CERTIFICATES_RECEIVED: &str = "snarkos_bft_certificates_received_total";
+    pub const CLOCK_OFFSET: &str = "snarkos_bft_clock_offset_secs";
     pub const COMMIT_ROUNDS_LATENCY: &str = "snarkos_bft_commit_rounds_latency_secs"; // <-- This one doesn't even make sense.
     pub const CONNECTED: &str = "snarkos_bft_connected_total";
     pub const CONNECTING: &str = "snarkos_bft_connecting_total";
     pub const LAST_STORED_ROUND: &str = "snarkos_bft_last_stored_round";
     pub const LEADERS_ELECTED: &str = "snarkos_bft_leaders_elected_total";
     pub const PROPOSAL_ROUND: &str = "snarkos_bft_primary_proposal_round";
+    pub const RETAINED_ROUNDS: &str = "snarkos_bft_retained_rounds";
+    pub const ROUND_LATENCY: &str = "snarkos_bft_round_latency_secs";
+    pub const ROUNDS_PER_SECOND: &str = "snarkos_bft_rounds_per_sec";
+    pub const VALIDATOR_PARTICIPATION: &str = "snarkos_bft_validator_participation_ratio";
 }
 
 pub mod blocks {
@@ -58,6 +70,11 @@ pub mod consensus {
     pub const LAST_COMMITTED_ROUND: &str = "snarkos_consensus_last_committed_round";
 }
 
+pub mod ledger {
+    pub const CACHE_HITS: &str = "snarkos_ledger_cache_hits_total";
+    pub const CACHE_MISSES: &str = "snarkos_ledger_cache_misses_total";
+}
+
 pub mod router {
     pub const CONNECTED: &str = "snarkos_router_connected_total";
     pub const CANDIDATE: &str = "snarkos_router_candidate_total";
@@ -0,0 +1,221 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{admin_auth_middleware, log_middleware, rpc::serve_rpc, HttpConfig, RpcError, RpcRequest, RpcResponse};
+use snarkos_node_router::Routing;
+use snarkvm::prelude::Network;
+
+use anyhow::{bail, Context, Result};
+use axum::{
+    body::Bytes,
+    extract::{DefaultBodyLimit, State},
+    middleware,
+    response::Response,
+    routing::post,
+};
+use serde_json::{json, Value};
+use std::{
+    marker::PhantomData,
+    net::SocketAddr,
+    path::PathBuf,
+    sync::Arc,
+};
+use tokio::task::JoinHandle;
+
+/// The default address of the admin server, which only accepts local connections.
+pub const DEFAULT_ADMIN_IP: &str = "127.0.0.1:3130";
+/// The maximum verbosity of the logs.
+const MAX_LOG_VERBOSITY: u8 = 6;
+
+/// The function that sets the verbosity of the logs, which is called by `admin_setLogLevel`.
+pub type LogVerbosityHandler = Arc<dyn Fn(u8) -> Result<()> + Send + Sync>;
+
+/// The configuration of the admin server.
+#[derive(Clone)]
+pub struct AdminConfig {
+    /// The address of the admin server.
+    pub ip: SocketAddr,
+    /// The function that sets the verbosity of the logs, if the logger supports it.
+    pub log_verbosity: Option<LogVerbosityHandler>,
+}
+
+/// The operations of a node that may be performed through the admin namespace.
+pub trait NodeAdmin<N: Network>: Routing<N> {
+    /// Starts or stops the coinbase puzzle of the node.
+    fn set_mining(&self, _enabled: bool) -> Result<()> {
+        bail!("Mining is only available on a prover")
+    }
+
+    /// Creates a backup of the ledger, and returns its path.
+    fn backup_ledger(&self) -> Result<PathBuf> {
+        bail!("Ledger backups are not available on this node")
+    }
+}
+
+/// The methods of the admin namespace.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AdminMethod {
+    /// Connects to the given peer.
+    AddPeer,
+    /// Disconnects from the given peer, and refuses its connections for a while.
+    BanPeer,
+    /// Sets the verbosity of the logs.
+    SetLogLevel,
+    /// Starts the coinbase puzzle.
+    StartMiner,
+    /// Stops the coinbase puzzle.
+    StopMiner,
+    /// Creates a backup of the ledger.
+    Backup,
+}
+
+impl AdminMethod {
+    /// The methods of the admin namespace.
+    pub const ALL: [Self; 6] =
+        [Self::AddPeer, Self::BanPeer, Self::SetLogLevel, Self::StartMiner, Self::StopMiner, Self::Backup];
+
+    /// Returns the name of the method.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::AddPeer => "admin_addPeer",
+            Self::BanPeer => "admin_banPeer",
+            Self::SetLogLevel => "admin_setLogLevel",
+            Self::StartMiner => "admin_startMiner",
+            Self::StopMiner => "admin_stopMiner",
+            Self::Backup => "admin_backup",
+        }
+    }
+
+    /// Returns the method with the given name, if it exists.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|method| method.name() == name)
+    }
+}
+
+/// An admin server, which serves the admin namespace as JSON-RPC 2.0 requests, authenticated with the admin JWT
+/// of the node, which is signed with a separate secret from the JWT of the REST server.
+pub struct AdminServer<N: Network, R: NodeAdmin<N>> {
+    /// The node.
+    node: Arc<R>,
+    /// The function that sets the verbosity of the logs, if the logger supports it.
+    log_verbosity: Option<LogVerbosityHandler>,
+    /// PhantomData.
+    _phantom: PhantomData<N>,
+}
+
+impl<N: Network, R: NodeAdmin<N>> Clone for AdminServer<N, R> {
+    fn clone(&self) -> Self {
+        Self { node: self.node.clone(), log_verbosity: self.log_verbosity.clone(), _phantom: PhantomData }
+    }
+}
+
+impl<N: Network, R: NodeAdmin<N>> AdminServer<N, R> {
+    /// Starts the admin server with the given configuration, and returns its handle.
+    pub fn start(config: AdminConfig, node: Arc<R>) -> Result<JoinHandle<()>> {
+        let admin_ip = config.ip;
+        if !admin_ip.ip().is_loopback() {
+            warn!("The admin server at '{admin_ip}' accepts remote connections");
+        }
+        let router = axum::Router::new()
+            .route("/", post(Self::rpc))
+            .route_layer(middleware::from_fn(admin_auth_middleware))
+            .with_state(Self { node, log_verbosity: config.log_verbosity, _phantom: PhantomData })
+            // Note: The admin server is not served behind the reverse proxies of the REST server.
+            .layer(middleware::from_fn_with_state(Arc::new(HttpConfig::default()), log_middleware))
+            .layer(DefaultBodyLimit::max(1024 * 1024));
+
+        // Bind the address before spawning the server, so that a failure is returned to the node.
+        let server = axum::Server::try_bind(&admin_ip)
+            .with_context(|| format!("Failed to start the admin server at '{admin_ip}'"))?;
+
+        Ok(tokio::spawn(async move {
+            if let Err(error) = server.serve(router.into_make_service_with_connect_info::<SocketAddr>()).await {
+                error!("The admin server at '{admin_ip}' failed - {error}");
+            }
+        }))
+    }
+
+    // POST /
+    async fn rpc(State(admin): State<Self>, body: Bytes) -> Response {
+        serve_rpc(&body, |request| admin.call(request)).await
+    }
+
+    /// Processes the given admin request, and returns its response, or `None` if the request is a notification.
+    async fn call(&self, request: Value) -> Option<RpcResponse> {
+        // Parse the request.
+        let request = match RpcRequest::parse(request) {
+            Ok(request) => request,
+            Err(error) => return Some(RpcResponse::new(Value::Null, Err(error))),
+        };
+        // Call the method.
+        let outcome = match AdminMethod::from_name(&request.method) {
+            Some(method) => self.dispatch(method, &request).await,
+            None => Err(RpcError::method_not_found(&request.method)),
+        };
+        // Note: The outcome of a notification is discarded.
+        request.id.map(|id| RpcResponse::new(id, outcome))
+    }
+
+    /// Calls the given method with the parameters of the given request.
+    async fn dispatch(&self, method: AdminMethod, request: &RpcRequest) -> Result<Value, RpcError> {
+        let router = self.node.router();
+        match method {
+            AdminMethod::AddPeer => {
+                let peer_ip: SocketAddr = request.param(0, "ip")?;
+                let Some(handle) = router.connect(peer_ip) else {
+                    return Err(RpcError::unavailable(format!("Cannot connect to '{peer_ip}'")));
+                };
+                let connected = handle.await.map_err(RpcError::internal_error)?;
+                Ok(json!({ "ip": peer_ip, "connected": connected }))
+            }
+            AdminMethod::BanPeer => {
+                let peer_ip: SocketAddr = request.param(0, "ip")?;
+                // Note: Whitelisted peers are exempt from bans.
+                router.insert_restricted_peer(peer_ip);
+                if !router.is_restricted(&peer_ip) {
+                    return Err(RpcError::unavailable(format!("Cannot ban '{peer_ip}', as it is whitelisted")));
+                }
+                if router.is_connected(&peer_ip) {
+                    router.disconnect(peer_ip);
+                }
+                Ok(json!({ "ip": peer_ip, "banned": true }))
+            }
+            AdminMethod::SetLogLevel => {
+                let verbosity: u8 = request.param(0, "verbosity")?;
+                if verbosity > MAX_LOG_VERBOSITY {
+                    return Err(RpcError::invalid_params(format!("The verbosity must be at most {MAX_LOG_VERBOSITY}")));
+                }
+                let Some(handler) = &self.log_verbosity else {
+                    return Err(RpcError::unavailable("The log verbosity cannot be changed on this node"));
+                };
+                handler(verbosity).map_err(RpcError::internal_error)?;
+                info!("Set the log verbosity to {verbosity}");
+                Ok(json!({ "verbosity": verbosity }))
+            }
+            AdminMethod::StartMiner | AdminMethod::StopMiner => {
+                let enabled = method == AdminMethod::StartMiner;
+                self.node.set_mining(enabled).map_err(RpcError::unavailable)?;
+                Ok(json!({ "mining": enabled }))
+            }
+            AdminMethod::Backup => {
+                let node = self.node.clone();
+                let path = match tokio::task::spawn_blocking(move || node.backup_ledger()).await {
+                    Ok(path) => path.map_err(RpcError::unavailable)?,
+                    Err(error) => return Err(RpcError::internal_error(error)),
+                };
+                Ok(json!({ "path": path.display().to_string() }))
+            }
+        }
+    }
+}
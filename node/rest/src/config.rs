@@ -0,0 +1,39 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+use crate::{ApiAccessConfig, HealthConfig, HttpConfig};
+
+#[cfg(unix)]
+use crate::UnixSocketConfig;
+
+#[cfg(feature = "grpc")]
+use std::net::SocketAddr;
+
+/// The configuration of the REST server, which is passed to the server on initialization.
+#[derive(Clone, Debug, Default)]
+pub struct RestConfig {
+    /// The HTTP configuration of the REST server.
+    pub http: HttpConfig,
+    /// The readiness thresholds of the node, which are checked by `/health/ready`.
+    pub health: HealthConfig,
+    /// The access configuration of the REST, JSON-RPC, and gRPC servers, if it is configured.
+    pub api_access: Option<ApiAccessConfig>,
+    /// The IP of the gRPC server, if the gRPC server is enabled.
+    #[cfg(feature = "grpc")]
+    pub grpc_ip: Option<SocketAddr>,
+    /// The Unix domain socket of the REST server, if it is enabled.
+    #[cfg(unix)]
+    pub unix_socket: Option<UnixSocketConfig>,
+}
@@ -0,0 +1,234 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{bearer_token, ApiAccess, ApiCaller, ApiDenial, HttpConfig, Rest};
+use snarkos_node_router::Routing;
+use snarkvm::prelude::{
+    block::{Block, Transaction},
+    store::ConsensusStorage,
+    FromBytes,
+    Network,
+    ToBytes,
+};
+
+use anyhow::{anyhow, Result};
+use std::{net::SocketAddr, pin::Pin, str::FromStr};
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
+use tonic::{
+    transport::{server::TcpIncoming, Server},
+    Request,
+    Response,
+    Status,
+};
+
+/// The types and service of the gRPC definitions in `proto/node.proto`.
+pub mod proto {
+    tonic::include_proto!("snarkos.node.v1");
+}
+
+use proto::{get_block_request::Id, node_server::NodeServer, send_transaction_response::Outcome};
+
+/// The path prefix of the gRPC methods, which name them in the API access.
+const GRPC_METHOD_PREFIX: &str = "/snarkos.node.v1.Node/";
+
+/// Returns the gRPC representation of the given block.
+fn to_proto_block<N: Network>(block: &Block<N>) -> Result<proto::Block, Status> {
+    Ok(proto::Block {
+        height: block.height(),
+        hash: block.hash().to_string(),
+        previous_hash: block.previous_hash().to_string(),
+        round: block.round(),
+        timestamp: block.timestamp(),
+        transaction_ids: block.transaction_ids().map(ToString::to_string).collect(),
+        block: block.to_bytes_le().map_err(|error| Status::internal(error.to_string()))?,
+    })
+}
+
+/// Parses the given ID of a block or transaction.
+fn parse_id<T: FromStr>(id: &str) -> Result<T, Status> {
+    T::from_str(id).map_err(|_| Status::invalid_argument(format!("Invalid ID '{id}'")))
+}
+
+/// Identifies the caller of a gRPC request, if the API access is configured, and rejects an unknown API key.
+fn access_interceptor(
+    access: Option<&ApiAccess>,
+    http: &HttpConfig,
+    mut request: Request<()>,
+) -> Result<Request<()>, Status> {
+    if let Some(access) = access {
+        let Some(peer_addr) = request.remote_addr() else {
+            return Err(Status::unauthenticated("Unknown client address"));
+        };
+        let headers = request.metadata().clone().into_headers();
+        let client_ip = http.client_ip(peer_addr, &headers);
+        let Some(caller) = access.caller(client_ip, bearer_token(&headers)) else {
+            return Err(Status::unauthenticated("Unknown API key"));
+        };
+        request.extensions_mut().insert(caller);
+    }
+    Ok(request)
+}
+
+impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
+    /// Spawns the gRPC server at the given address.
+    pub(crate) fn spawn_grpc_server(&self, grpc_ip: SocketAddr) -> Result<()> {
+        let (access, http) = (self.api_access.clone(), self.http.clone());
+        let service = NodeServer::with_interceptor(self.clone(), move |request| {
+            access_interceptor(access.as_deref(), &http, request)
+        });
+        // Bind the address before spawning the server, so that a failure is returned to the node.
+        let incoming = TcpIncoming::new(grpc_ip, true, None)
+            .map_err(|error| anyhow!("Failed to start the gRPC server at '{grpc_ip}' - {error}"))?;
+        self.handles.lock().push(tokio::spawn(async move {
+            info!("Starting the gRPC server at {grpc_ip}");
+            if let Err(error) = Server::builder().add_service(service).serve_with_incoming(incoming).await {
+                error!("The gRPC server failed - {error}");
+            }
+        }));
+        Ok(())
+    }
+
+    /// Ensures the caller of the given gRPC request may call the given method, if the API access is configured.
+    ///
+    /// Note: The method is checked here, as the interceptor does not see the path of the request.
+    fn permit<T>(&self, request: &Request<T>, method: &str) -> Result<(), Status> {
+        let Some(access) = &self.api_access else {
+            return Ok(());
+        };
+        let Some(caller) = request.extensions().get::<ApiCaller>() else {
+            return Err(Status::unauthenticated("Unknown caller"));
+        };
+        match access.permit(caller, &format!("{GRPC_METHOD_PREFIX}{method}")) {
+            Ok(()) => Ok(()),
+            Err(ApiDenial::Unauthorized) => {
+                Err(Status::permission_denied(format!("The method '{method}' is not permitted")))
+            }
+            Err(ApiDenial::RateLimited) => Err(Status::resource_exhausted("Rate limit exceeded")),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> proto::node_server::Node for Rest<N, C, R> {
+    type SubscribeBlocksStream = Pin<Box<dyn Stream<Item = Result<proto::Block, Status>> + Send>>;
+
+    async fn get_node_info(
+        &self,
+        request: Request<proto::GetNodeInfoRequest>,
+    ) -> Result<Response<proto::NodeInfo>, Status> {
+        self.permit(&request, "GetNodeInfo")?;
+        let router = self.routing.router();
+        Ok(Response::new(proto::NodeInfo {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            network: N::ID.into(),
+            node_type: router.node_type().to_string(),
+            address: router.address().to_string(),
+            listener_ip: router.local_ip().to_string(),
+            latest_height: self.ledger.latest_height(),
+            latest_hash: self.ledger.latest_hash().to_string(),
+            is_synced: self.sync.is_block_synced(),
+            num_peers: router.number_of_connected_peers() as u32,
+        }))
+    }
+
+    async fn get_block(&self, request: Request<proto::GetBlockRequest>) -> Result<Response<proto::Block>, Status> {
+        self.permit(&request, "GetBlock")?;
+        let block = match request.into_inner().id {
+            Some(Id::Height(height)) => {
+                if height > self.ledger.latest_height() {
+                    return Err(Status::not_found(format!("Block {height} does not exist")));
+                }
+                self.ledger_cache.get_block(&self.ledger, height)
+            }
+            Some(Id::Hash(hash)) => {
+                let hash: N::BlockHash = parse_id(&hash)?;
+                if !self.ledger.contains_block_hash(&hash).map_err(|error| Status::internal(error.to_string()))? {
+                    return Err(Status::not_found(format!("Block '{hash}' does not exist")));
+                }
+                self.ledger.get_block_by_hash(&hash)
+            }
+            None => return Err(Status::invalid_argument("Missing the block height or hash")),
+        };
+        let block = block.map_err(|error| Status::internal(error.to_string()))?;
+        Ok(Response::new(to_proto_block(&block)?))
+    }
+
+    async fn get_transaction(
+        &self,
+        request: Request<proto::GetTransactionRequest>,
+    ) -> Result<Response<proto::Transaction>, Status> {
+        self.permit(&request, "GetTransaction")?;
+        let internal = |error: anyhow::Error| Status::internal(error.to_string());
+        let transaction_id: N::TransactionID = parse_id(&request.into_inner().id)?;
+        let Some(block_hash) = self.ledger.find_block_hash(&transaction_id).map_err(internal)? else {
+            return Err(Status::not_found(format!("Transaction '{transaction_id}' is not confirmed")));
+        };
+        let height = self.ledger.get_height(&block_hash).map_err(internal)?;
+        let transaction = self.ledger_cache.get_transaction(&self.ledger, transaction_id).map_err(internal)?;
+        Ok(Response::new(proto::Transaction {
+            id: transaction_id.to_string(),
+            height,
+            block_hash: block_hash.to_string(),
+            transaction: transaction.to_bytes_le().map_err(internal)?,
+        }))
+    }
+
+    async fn get_peers(
+        &self,
+        request: Request<proto::GetPeersRequest>,
+    ) -> Result<Response<proto::GetPeersResponse>, Status> {
+        self.permit(&request, "GetPeers")?;
+        let peers = self.routing.router().connected_peers().iter().map(ToString::to_string).collect();
+        Ok(Response::new(proto::GetPeersResponse { peers }))
+    }
+
+    async fn send_transaction(
+        &self,
+        request: Request<proto::SendTransactionRequest>,
+    ) -> Result<Response<proto::SendTransactionResponse>, Status> {
+        self.permit(&request, "SendTransaction")?;
+        let request = request.into_inner();
+        let transaction = Transaction::<N>::read_le(&request.transaction[..])
+            .map_err(|error| Status::invalid_argument(format!("Invalid transaction - {error}")))?;
+        // Fully validate the transaction, to report the reason it is rejected, before broadcasting it.
+        let outcome = match self.validate_transaction(&transaction, request.expiration_height).await {
+            Ok(()) => match self.broadcast_transaction(transaction, request.expiration_height).await {
+                Ok(transaction_id) => Ok(transaction_id),
+                Err(error) => Err(crate::TransactionRejection::MempoolRejected(error.to_string())),
+            },
+            Err(rejection) => Err(rejection),
+        };
+        let outcome = match outcome {
+            Ok(transaction_id) => Outcome::TransactionId(transaction_id.to_string()),
+            Err(rejection) => Outcome::Rejection(proto::TransactionRejection {
+                reason: rejection.reason().to_string(),
+                message: rejection.to_string(),
+            }),
+        };
+        Ok(Response::new(proto::SendTransactionResponse { outcome: Some(outcome) }))
+    }
+
+    async fn subscribe_blocks(
+        &self,
+        request: Request<proto::SubscribeBlocksRequest>,
+    ) -> Result<Response<Self::SubscribeBlocksStream>, Status> {
+        self.permit(&request, "SubscribeBlocks")?;
+        let stream = BroadcastStream::new(self.block_events.subscribe()).filter_map(|block| match block {
+            Ok(block) => Some(to_proto_block(&block)),
+            // Note: A lagging subscriber skips the blocks it missed.
+            Err(_) => None,
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
@@ -0,0 +1,79 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use snarkos_node_bft_ledger_service::is_storage_degraded;
+
+use axum::response::IntoResponse;
+use serde_json::json;
+
+/// The default maximum number of blocks a ready node may be behind its peers.
+pub const DEFAULT_HEALTH_MAX_BLOCKS_BEHIND: u32 = 10; // blocks
+/// The default minimum number of connected peers of a ready node.
+pub const DEFAULT_HEALTH_MIN_PEERS: usize = 1;
+
+/// The readiness thresholds of the node, which are checked by `/health/ready`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct HealthConfig {
+    /// The maximum number of blocks the node may be behind its peers.
+    pub max_blocks_behind: u32,
+    /// The minimum number of connected peers.
+    pub min_peers: usize,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self { max_blocks_behind: DEFAULT_HEALTH_MAX_BLOCKS_BEHIND, min_peers: DEFAULT_HEALTH_MIN_PEERS }
+    }
+}
+
+impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
+    // GET /health/live
+    pub(crate) async fn get_health_live() -> Response {
+        // Note: The node is live as long as the REST server is able to respond.
+        Json(json!({ "status": "live" })).into_response()
+    }
+
+    // GET /health/ready
+    pub(crate) async fn get_health_ready(State(rest): State<Self>) -> Response {
+        let config = rest.health;
+        let router = rest.routing.router();
+
+        let num_blocks_behind = rest.sync.num_blocks_behind();
+        let num_peers = router.number_of_connected_peers();
+        let is_storage_degraded = is_storage_degraded();
+        let is_stalled = rest.sync.is_stalled();
+
+        let is_synced = num_blocks_behind <= config.max_blocks_behind && !is_stalled;
+        let has_peers = num_peers >= config.min_peers;
+        let is_ready = is_synced && has_peers && !is_storage_degraded;
+
+        let status = match is_ready {
+            true => StatusCode::OK,
+            false => StatusCode::SERVICE_UNAVAILABLE,
+        };
+        let body = json!({
+            "status": if is_ready { "ready" } else { "not_ready" },
+            "version": env!("CARGO_PKG_VERSION"),
+            "node_type": router.node_type(),
+            "latest_height": rest.ledger.latest_height(),
+            "checks": {
+                "sync": { "ok": is_synced, "blocks_behind": num_blocks_behind, "is_stalled": is_stalled },
+                "peers": { "ok": has_peers, "connected": num_peers, "minimum": config.min_peers },
+                "storage": { "ok": !is_storage_degraded },
+            },
+        });
+        (status, Json(body)).into_response()
+    }
+}
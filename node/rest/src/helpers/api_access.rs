@@ -0,0 +1,236 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{is_valid_jwt, Rest, RpcMethod};
+use snarkos_node_router::Routing;
+use snarkvm::prelude::{store::ConsensusStorage, Network};
+
+use anyhow::{ensure, Result};
+use axum::{
+    extract::{ConnectInfo, MatchedPath, State},
+    http::{header::AUTHORIZATION, HeaderMap, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use indexmap::IndexMap;
+use parking_lot::Mutex;
+use serde::Deserialize;
+use std::{
+    collections::HashSet,
+    net::{IpAddr, SocketAddr},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+/// The duration of a rate limit window.
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60); // 1 minute
+/// The number of tracked callers above which the callers with an expired window are removed.
+const MAX_TRACKED_CALLERS: usize = 10_000;
+/// The route of the JSON-RPC server, whose methods are permitted separately.
+const RPC_ROUTE: &str = "/testnet3/rpc";
+
+/// The access configuration of the REST, JSON-RPC, and gRPC servers, which is loaded from a JSON file.
+///
+/// A method is a JSON-RPC method (e.g. `getBlock`), a REST route (e.g. `/testnet3/block/:height_or_hash`),
+/// or a gRPC method (e.g. `/snarkos.node.v1.Node/GetBlock`).
+///
+/// Without an access configuration, every method is public and unlimited.
+/// With an access configuration, only the public methods may be called without authentication,
+/// and the other methods require an API key, or the JWT of the node, as a bearer token.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApiAccessConfig {
+    /// The methods that may be called without authentication.
+    #[serde(default)]
+    pub public_methods: Vec<String>,
+    /// The maximum number of unauthenticated requests per minute from each IP, if any.
+    pub public_rate_limit: Option<u32>,
+    /// The API keys.
+    #[serde(default)]
+    pub keys: Vec<ApiKeyConfig>,
+}
+
+/// The configuration of an API key.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApiKeyConfig {
+    /// The name of the API key, for logging purposes.
+    pub name: String,
+    /// The API key.
+    pub key: String,
+    /// The maximum number of requests per minute with the API key, if any.
+    pub rate_limit: Option<u32>,
+    /// The methods that may be called with the API key, or every method if not given.
+    pub methods: Option<Vec<String>>,
+}
+
+impl ApiAccessConfig {
+    /// Loads the access configuration from the given JSON file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let config: Self = serde_json::from_slice(&std::fs::read(path)?)?;
+        config.check()?;
+        Ok(config)
+    }
+
+    /// Ensures the methods are known, the rate limits are positive, and the API keys are unique.
+    pub fn check(&self) -> Result<()> {
+        let check_methods = |methods: &[String]| -> Result<()> {
+            for method in methods {
+                // Note: A REST route or gRPC method starts with a slash, and a JSON-RPC method must be known.
+                let is_known = method.starts_with('/') || RpcMethod::from_name(method).is_some();
+                ensure!(is_known, "Unknown method '{method}' in the API access");
+            }
+            Ok(())
+        };
+        check_methods(&self.public_methods)?;
+        ensure!(self.public_rate_limit != Some(0), "The public rate limit must be positive");
+        let mut keys = HashSet::new();
+        for key in &self.keys {
+            ensure!(key.key.len() >= 16, "The API key '{}' must be at least 16 characters", key.name);
+            ensure!(keys.insert(&key.key), "The API key '{}' is not unique", key.name);
+            ensure!(key.rate_limit != Some(0), "The rate limit of the API key '{}' must be positive", key.name);
+            check_methods(key.methods.as_deref().unwrap_or_default())?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the bearer token of the given request headers, if any.
+pub(crate) fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers.get(AUTHORIZATION)?.to_str().ok()?.strip_prefix("Bearer ")
+}
+
+/// Ensures the caller of a REST route may call it, if the access is configured.
+///
+/// Note: The JSON-RPC route is exempt, as each JSON-RPC method in its body is permitted separately.
+pub(crate) async fn access_middleware<N: Network, C: ConsensusStorage<N>, R: Routing<N>, B>(
+    State(rest): State<Rest<N, C, R>>,
+    ConnectInfo(peer_addr): ConnectInfo<SocketAddr>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Result<Response, Response>
+where
+    B: Send,
+{
+    let (Some(access), Some(route)) = (&rest.api_access, request.extensions().get::<MatchedPath>().cloned()) else {
+        return Ok(next.run(request).await);
+    };
+    if route.as_str() == RPC_ROUTE {
+        return Ok(next.run(request).await);
+    }
+    let client_ip = rest.http.client_ip(peer_addr, request.headers());
+    let denial = match access.caller(client_ip, bearer_token(request.headers())) {
+        None => Some((StatusCode::UNAUTHORIZED, "Unknown API key".to_string())),
+        Some(caller) => match access.permit(&caller, route.as_str()) {
+            Ok(()) => None,
+            Err(ApiDenial::Unauthorized) => {
+                Some((StatusCode::UNAUTHORIZED, format!("The route '{}' is not permitted", route.as_str())))
+            }
+            Err(ApiDenial::RateLimited) => Some((StatusCode::TOO_MANY_REQUESTS, "Rate limit exceeded".to_string())),
+        },
+    };
+    match denial {
+        Some(denial) => Err(denial.into_response()),
+        None => Ok(next.run(request).await),
+    }
+}
+
+/// The caller of a request.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum ApiCaller {
+    /// An unauthenticated caller from the given IP.
+    Public(IpAddr),
+    /// A caller with the API key at the given index.
+    Key(usize),
+    /// A caller with the JWT of the node.
+    Node,
+}
+
+/// The reason a request is not permitted.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum ApiDenial {
+    /// The method requires authentication, or is not permitted for the API key.
+    Unauthorized,
+    /// The rate limit of the caller was exceeded.
+    RateLimited,
+}
+
+/// The access to the REST, JSON-RPC, and gRPC servers, with the rate limit windows of the callers.
+pub(crate) struct ApiAccess {
+    /// The access configuration.
+    config: ApiAccessConfig,
+    /// The map of callers to the start of their rate limit window, and their number of requests in the window.
+    windows: Mutex<IndexMap<ApiCaller, (Instant, u32)>>,
+}
+
+impl ApiAccess {
+    /// Initializes the access with the given configuration, after ensuring it is valid.
+    pub(crate) fn new(config: ApiAccessConfig) -> Result<Self> {
+        config.check()?;
+        Ok(Self { config, windows: Default::default() })
+    }
+
+    /// Returns the caller from the given IP with the given bearer token, if any,
+    /// or `None` if the token is neither the JWT of the node nor a known API key.
+    pub(crate) fn caller(&self, client_ip: IpAddr, token: Option<&str>) -> Option<ApiCaller> {
+        match token {
+            None => Some(ApiCaller::Public(client_ip)),
+            Some(token) if is_valid_jwt(token) => Some(ApiCaller::Node),
+            Some(token) => self.config.keys.iter().position(|key| key.key == token).map(ApiCaller::Key),
+        }
+    }
+
+    /// Ensures the given caller may call the given method, and counts the request towards its rate limit.
+    pub(crate) fn permit(&self, caller: &ApiCaller, method: &str) -> Result<(), ApiDenial> {
+        let (is_allowed, rate_limit) = match caller {
+            ApiCaller::Public(_) => {
+                (self.config.public_methods.iter().any(|name| name == method), self.config.public_rate_limit)
+            }
+            ApiCaller::Key(index) => {
+                let key = &self.config.keys[*index];
+                let is_allowed = key.methods.as_ref().map_or(true, |methods| methods.iter().any(|name| name == method));
+                (is_allowed, key.rate_limit)
+            }
+            ApiCaller::Node => (true, None),
+        };
+        if !is_allowed {
+            return Err(ApiDenial::Unauthorized);
+        }
+        match rate_limit {
+            Some(rate_limit) if !self.count_request(caller, rate_limit) => Err(ApiDenial::RateLimited),
+            _ => Ok(()),
+        }
+    }
+
+    /// Counts a request of the given caller, and returns `false` if the caller exceeded the given rate limit.
+    fn count_request(&self, caller: &ApiCaller, rate_limit: u32) -> bool {
+        let now = Instant::now();
+        let mut windows = self.windows.lock();
+        // Remove the callers with an expired window, if too many callers are tracked.
+        if windows.len() >= MAX_TRACKED_CALLERS {
+            windows.retain(|_, (start, _)| now.duration_since(*start) < RATE_LIMIT_WINDOW);
+        }
+        let (start, num_requests) = windows.entry(caller.clone()).or_insert((now, 0));
+        // Start a new window, if the current window expired.
+        if now.duration_since(*start) >= RATE_LIMIT_WINDOW {
+            *start = now;
+            *num_requests = 0;
+        }
+        if *num_requests >= rate_limit {
+            return false;
+        }
+        *num_requests += 1;
+        true
+    }
+}
@@ -40,6 +40,16 @@ fn jwt_secret() -> &'static Vec<u8> {
     })
 }
 
+/// Returns the JWT secret for the admin server of the node instance, which is distinct from the JWT secret
+/// of the REST server, so that the JWT of the REST server does not grant access to the admin namespace.
+fn admin_jwt_secret() -> &'static Vec<u8> {
+    static SECRET: OnceCell<Vec<u8>> = OnceCell::new();
+    SECRET.get_or_init(|| {
+        let seed: [u8; 16] = ::rand::thread_rng().gen();
+        seed.to_vec()
+    })
+}
+
 /// The Json web token claims.
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Claims {
@@ -68,9 +78,36 @@ impl Claims {
     pub fn to_jwt_string(&self) -> Result<String> {
         encode(&Header::default(), &self, &EncodingKey::from_secret(jwt_secret())).map_err(|e| anyhow!(e))
     }
+
+    /// Returns the json web token string for the admin server.
+    pub fn to_admin_jwt_string(&self) -> Result<String> {
+        encode(&Header::default(), &self, &EncodingKey::from_secret(admin_jwt_secret())).map_err(|e| anyhow!(e))
+    }
+}
+
+/// Returns `true` if the given token is an unexpired JWT of the node.
+pub(crate) fn is_valid_jwt(token: &str) -> bool {
+    decode::<Claims>(token, &DecodingKey::from_secret(jwt_secret()), &Validation::new(Algorithm::HS256))
+        .map_or(false, |decoded| !decoded.claims.is_expired())
 }
 
 pub async fn auth_middleware<B>(request: Request<B>, next: Next<B>) -> Result<Response, Response>
+where
+    B: Send,
+{
+    authenticate(request, next, jwt_secret()).await
+}
+
+/// Authenticates the requests to the admin server with the admin JWT of the node.
+pub async fn admin_auth_middleware<B>(request: Request<B>, next: Next<B>) -> Result<Response, Response>
+where
+    B: Send,
+{
+    authenticate(request, next, admin_jwt_secret()).await
+}
+
+/// Runs the given request if it carries an unexpired JWT that is signed with the given secret.
+async fn authenticate<B>(request: Request<B>, next: Next<B>, secret: &[u8]) -> Result<Response, Response>
 where
     B: Send,
 {
@@ -79,7 +116,7 @@ where
     let auth: TypedHeader<Authorization<Bearer>> =
         parts.extract().await.map_err(|_| StatusCode::UNAUTHORIZED.into_response())?;
 
-    match decode::<Claims>(auth.token(), &DecodingKey::from_secret(jwt_secret()), &Validation::new(Algorithm::HS256)) {
+    match decode::<Claims>(auth.token(), &DecodingKey::from_secret(secret), &Validation::new(Algorithm::HS256)) {
         Ok(decoded) => {
             let claims = decoded.claims;
             if claims.is_expired() {
@@ -97,3 +134,27 @@ where
 
     Ok(next.run(request).await)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_admin_jwt_is_separate() {
+        let rng = &mut TestRng::default();
+        let address = Address::<CurrentNetwork>::try_from(PrivateKey::new(rng).unwrap()).unwrap();
+        let claims = Claims::new(address);
+        let is_signed_with = |token: &str, secret: &[u8]| {
+            decode::<Claims>(token, &DecodingKey::from_secret(secret), &Validation::new(Algorithm::HS256)).is_ok()
+        };
+
+        // Ensure the JWT of the REST server is not accepted by the admin server, and vice versa.
+        let jwt = claims.to_jwt_string().unwrap();
+        let admin_jwt = claims.to_admin_jwt_string().unwrap();
+        assert!(is_valid_jwt(&jwt) && is_signed_with(&jwt, jwt_secret()));
+        assert!(!is_valid_jwt(&admin_jwt) && !is_signed_with(&jwt, admin_jwt_secret()));
+        assert!(is_signed_with(&admin_jwt, admin_jwt_secret()));
+    }
+}
@@ -0,0 +1,63 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use axum::{
+    http::{
+        header::{CACHE_CONTROL, ETAG, IF_NONE_MATCH},
+        HeaderMap,
+        HeaderValue,
+        StatusCode,
+    },
+    response::{IntoResponse, Response},
+};
+use axum_extra::response::ErasedJson;
+use serde::Serialize;
+use std::fmt::Display;
+
+/// The `Cache-Control` header of a response that does not change once it exists, such as a block or transaction.
+pub const CACHE_CONTROL_IMMUTABLE: &str = "public, max-age=3600";
+/// The `Cache-Control` header of a response that changes with the chain tip, which must be revalidated.
+pub const CACHE_CONTROL_REVALIDATE: &str = "no-cache";
+
+/// Returns `true` if the `If-None-Match` header of the request matches the given entity tag.
+pub fn etag_matches(headers: &HeaderMap, etag: &str) -> bool {
+    let mut candidates = headers
+        .get_all(IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim);
+    // Note: A weak comparison is used, as the responses are equivalent if their entity tags match.
+    candidates.any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag)
+}
+
+/// Returns the given value as a JSON response, with the given entity tag and `Cache-Control` header.
+/// If the `If-None-Match` header of the request matches the entity tag, an empty `304 Not Modified` is returned.
+pub fn cached_json(
+    headers: &HeaderMap,
+    tag: impl Display,
+    cache_control: &'static str,
+    value: impl Serialize,
+) -> Response {
+    let etag = format!("\"{tag}\"");
+    let mut response = match etag_matches(headers, &etag) {
+        true => StatusCode::NOT_MODIFIED.into_response(),
+        false => ErasedJson::pretty(value).into_response(),
+    };
+    if let Ok(etag) = HeaderValue::from_str(&etag) {
+        response.headers_mut().insert(ETAG, etag);
+    }
+    response.headers_mut().insert(CACHE_CONTROL, HeaderValue::from_static(cache_control));
+    response
+}
@@ -0,0 +1,86 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{ensure, Result};
+use axum::http::{HeaderMap, HeaderValue};
+use std::net::{IpAddr, SocketAddr};
+
+/// The default maximum size of a request body, in bytes.
+pub const DEFAULT_MAX_BODY_SIZE: usize = 10 * 1024 * 1024; // 10 MiB
+/// The header in which reverse proxies record the IPs of the clients.
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
+
+/// The HTTP configuration of the REST server.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HttpConfig {
+    /// The origins that are allowed by CORS, or every origin if empty.
+    pub cors_origins: Vec<String>,
+    /// The IPs of the reverse proxies whose `X-Forwarded-For` header is trusted.
+    pub trusted_proxies: Vec<IpAddr>,
+    /// The maximum size of a request body, in bytes.
+    pub max_body_size: usize,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self { cors_origins: vec![], trusted_proxies: vec![], max_body_size: DEFAULT_MAX_BODY_SIZE }
+    }
+}
+
+impl HttpConfig {
+    /// Ensures the CORS origins are well-formed, and the maximum request body size is positive.
+    pub fn check(&self) -> Result<()> {
+        for origin in &self.cors_origins {
+            ensure!(HeaderValue::from_str(origin).is_ok(), "The CORS origin '{origin}' is malformed");
+        }
+        ensure!(self.max_body_size > 0, "The maximum request body size must be positive");
+        Ok(())
+    }
+
+    /// Returns the CORS origins as header values.
+    pub(crate) fn cors_origin_values(&self) -> Vec<HeaderValue> {
+        // Note: The origins are validated when the REST server is started.
+        self.cors_origins.iter().filter_map(|origin| HeaderValue::from_str(origin).ok()).collect()
+    }
+
+    /// Returns the IP of the client of the request from the given peer, which is taken from
+    /// the `X-Forwarded-For` header if the peer is a trusted reverse proxy.
+    pub(crate) fn client_ip(&self, peer_addr: SocketAddr, headers: &HeaderMap) -> IpAddr {
+        let mut client_ip = peer_addr.ip();
+        if !self.trusted_proxies.contains(&client_ip) {
+            return client_ip;
+        }
+        // Walk the forwarded IPs from the closest to the farthest, and return the first IP that is not
+        // a trusted proxy, as the farther IPs may be set by the client.
+        let forwarded_ips = headers
+            .get_all(X_FORWARDED_FOR)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .collect::<Vec<_>>();
+        for ip in forwarded_ips.into_iter().rev() {
+            match ip.trim().parse::<IpAddr>() {
+                Ok(ip) => {
+                    client_ip = ip;
+                    if !self.trusted_proxies.contains(&ip) {
+                        break;
+                    }
+                }
+                // Note: A malformed IP is not trusted, so the last valid IP is the client.
+                Err(_) => break,
+            }
+        }
+        client_ip
+    }
+}
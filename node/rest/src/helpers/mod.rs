@@ -12,8 +12,32 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod api_access;
+pub use api_access::*;
+
 mod auth;
 pub use auth::*;
 
+mod caching;
+pub use caching::*;
+
 mod error;
 pub use error::*;
+
+mod http_config;
+pub use http_config::*;
+
+mod openapi;
+pub use openapi::*;
+
+mod pagination;
+pub use pagination::*;
+
+mod rejection;
+pub use rejection::*;
+
+mod submissions;
+pub use submissions::*;
+
+mod rpc;
+pub use rpc::*;
@@ -0,0 +1,277 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::Network;
+
+use schemars::{
+    gen::{SchemaGenerator, SchemaSettings},
+    schema::{InstanceType, Metadata, ObjectValidation, Schema, SchemaObject},
+    JsonSchema,
+};
+use serde_json::{json, Map, Value};
+
+/// The version of the OpenAPI specification the document follows.
+const OPENAPI_VERSION: &str = "3.0.3";
+
+/// Defines the schema of a snarkVM type, which is described by its JSON encoding, as the snarkVM types do not
+/// implement `JsonSchema`.
+macro_rules! snarkvm_schema {
+    ($(#[doc = $doc:literal])* $schema:ident, $name:literal, $instance_type:ident) => {
+        $(#[doc = $doc])*
+        pub struct $schema;
+
+        impl JsonSchema for $schema {
+            fn schema_name() -> String {
+                $name.to_string()
+            }
+
+            fn json_schema(_: &mut SchemaGenerator) -> Schema {
+                let description = [$($doc.trim()),*].join(" ");
+                let metadata = Metadata { description: Some(description), ..Default::default() };
+                let instance_type = Some(InstanceType::$instance_type.into());
+                SchemaObject { metadata: Some(Box::new(metadata)), instance_type, ..Default::default() }.into()
+            }
+        }
+    };
+}
+
+snarkvm_schema!(
+    /// A block, in its snarkVM JSON encoding.
+    BlockSchema, "Block", Object
+);
+snarkvm_schema!(
+    /// A block header, in its snarkVM JSON encoding.
+    HeaderSchema, "Header", Object
+);
+snarkvm_schema!(
+    /// A transaction, in its snarkVM JSON encoding.
+    TransactionSchema, "Transaction", Object
+);
+snarkvm_schema!(
+    /// A block hash, e.g. `ab1...`.
+    BlockHashSchema, "BlockHash", String
+);
+snarkvm_schema!(
+    /// A state root, e.g. `sr1...`.
+    StateRootSchema, "StateRoot", String
+);
+
+/// A route of the REST server, as described in the OpenAPI document.
+#[derive(Clone, Debug)]
+struct ApiRoute {
+    /// The HTTP method of the route, in lowercase.
+    method: &'static str,
+    /// The path of the route, with the path parameters in the `:name` form.
+    path: &'static str,
+    /// The summary of the route.
+    summary: &'static str,
+    /// Whether the route is authenticated with the JWT of the node.
+    is_authenticated: bool,
+    /// The query parameters of the route, if its query object is described.
+    parameters: Vec<Value>,
+    /// The schema of the response of the route, if it is described.
+    response: Option<Value>,
+}
+
+/// The routes of the REST server, which are recorded as they are registered, to generate the OpenAPI document.
+#[derive(Clone, Debug)]
+pub struct ApiRoutes {
+    routes: Vec<ApiRoute>,
+    /// The generator of the schemas of the routes, which collects the definitions they reference.
+    generator: SchemaGenerator,
+}
+
+impl Default for ApiRoutes {
+    fn default() -> Self {
+        // Note: The OpenAPI 3 settings reference the definitions from the components of the document.
+        Self { routes: Vec::new(), generator: SchemaSettings::openapi3().into_generator() }
+    }
+}
+
+impl ApiRoutes {
+    /// Records a `GET` route, and returns its path.
+    pub fn get(&mut self, path: &'static str, summary: &'static str) -> &'static str {
+        self.record("get", path, summary, false)
+    }
+
+    /// Records a `GET` route with the given query object and response, and returns its path.
+    /// The query object is `()` if the route has none.
+    pub fn get_with<Q: JsonSchema, T: JsonSchema>(
+        &mut self,
+        path: &'static str,
+        summary: &'static str,
+    ) -> &'static str {
+        self.record("get", path, summary, false);
+        let parameters = self.query_parameters::<Q>();
+        let response = serde_json::to_value(self.generator.subschema_for::<T>()).ok();
+        if let Some(route) = self.routes.last_mut() {
+            route.parameters = parameters;
+            route.response = response;
+        }
+        path
+    }
+
+    /// Records a `POST` route, and returns its path.
+    pub fn post(&mut self, path: &'static str, summary: &'static str) -> &'static str {
+        self.record("post", path, summary, false)
+    }
+
+    /// Records a `GET` route that is authenticated with the JWT of the node, and returns its path.
+    pub fn get_with_jwt(&mut self, path: &'static str, summary: &'static str) -> &'static str {
+        self.record("get", path, summary, true)
+    }
+
+    /// Records a `POST` route that is authenticated with the JWT of the node, and returns its path.
+    pub fn post_with_jwt(&mut self, path: &'static str, summary: &'static str) -> &'static str {
+        self.record("post", path, summary, true)
+    }
+
+    /// Records the given route, and returns its path.
+    fn record(
+        &mut self,
+        method: &'static str,
+        path: &'static str,
+        summary: &'static str,
+        is_authenticated: bool,
+    ) -> &'static str {
+        let (parameters, response) = (Vec::new(), None);
+        self.routes.push(ApiRoute { method, path, summary, is_authenticated, parameters, response });
+        path
+    }
+
+    /// Returns the query parameters of the given query object, which are the properties of its schema.
+    fn query_parameters<Q: JsonSchema>(&mut self) -> Vec<Value> {
+        let root = self.generator.root_schema_for::<Q>();
+        // Note: The properties may reference the definitions of their types, so they are collected as well.
+        self.generator.definitions_mut().extend(root.definitions);
+        let Some(object) = root.schema.object else {
+            return Vec::new();
+        };
+        let ObjectValidation { properties, required, .. } = *object;
+        properties
+            .into_iter()
+            .map(|(name, schema)| {
+                let required = required.contains(&name);
+                json!({ "name": name, "in": "query", "required": required, "schema": schema })
+            })
+            .collect()
+    }
+
+    /// Returns the OpenAPI document of the recorded routes, for the given network.
+    pub fn to_openapi<N: Network>(&self) -> Value {
+        // The routes of the network are nested under its lowercase name, e.g. `/testnet3` for "Aleo Testnet3".
+        let network = N::NAME.rsplit(' ').next().unwrap_or(N::NAME).to_lowercase();
+
+        let mut paths = Map::new();
+        for route in &self.routes {
+            // Convert the path parameters from the `:name` form to the `{name}` form.
+            let path = route
+                .path
+                .split('/')
+                .map(|segment| match segment.strip_prefix(':') {
+                    Some(name) => format!("{{{name}}}"),
+                    None => segment.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join("/");
+            let parameters = route
+                .path
+                .split('/')
+                .filter_map(|segment| segment.strip_prefix(':'))
+                .map(|name| json!({ "name": name, "in": "path", "required": true, "schema": { "type": "string" } }))
+                .chain(route.parameters.iter().cloned())
+                .collect::<Vec<_>>();
+            // Group the routes by their first segment after the network, e.g. `block` or `transaction`.
+            let tag = route.path.split('/').find(|segment| !segment.is_empty() && *segment != network);
+            let tag = tag.unwrap_or_default();
+            let content = match &route.response {
+                Some(schema) => json!({ "application/json": { "schema": schema } }),
+                None => json!({ "application/json": {} }),
+            };
+
+            let mut operation = json!({
+                "summary": route.summary,
+                "tags": [tag],
+                "parameters": parameters,
+                "responses": {
+                    "200": { "description": "Success", "content": content },
+                    "500": { "description": "The request failed", "content": { "text/plain": {} } },
+                },
+            });
+            if route.method == "post" {
+                operation["requestBody"] = json!({ "required": true, "content": { "application/json": {} } });
+            }
+            if route.is_authenticated {
+                operation["security"] = json!([{ "jwt": [] }]);
+                operation["responses"]["401"] = json!({ "description": "Missing or invalid JWT" });
+            }
+            let item = paths.entry(path).or_insert_with(|| json!({}));
+            item[route.method] = operation;
+        }
+
+        json!({
+            "openapi": OPENAPI_VERSION,
+            "info": {
+                "title": format!("snarkOS REST API ({})", N::NAME),
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+            "paths": paths,
+            "components": {
+                "schemas": self.generator.definitions(),
+                "securitySchemes": {
+                    "jwt": { "type": "http", "scheme": "bearer", "bearerFormat": "JWT" },
+                },
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Page, Paged};
+    use snarkvm::prelude::Testnet3;
+
+    /// A query object of a test route.
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Options {
+        /// The block height to return the blocks below.
+        before: Option<u32>,
+    }
+
+    #[test]
+    fn test_openapi() {
+        let mut api = ApiRoutes::default();
+        api.get_with::<Paged<Options>, Page<Vec<BlockSchema>>>("/testnet3/blocks/:height", "Returns the blocks");
+        let openapi = api.to_openapi::<Testnet3>();
+
+        // Ensure the route is grouped after the network, and its path and query parameters are described.
+        let operation = &openapi["paths"]["/testnet3/blocks/{height}"]["get"];
+        assert_eq!(operation["tags"], json!(["blocks"]));
+        let parameters = operation["parameters"].as_array().unwrap();
+        let names = parameters.iter().map(|parameter| parameter["name"].as_str().unwrap()).collect::<Vec<_>>();
+        assert_eq!(names, ["height", "before", "cursor", "limit"]);
+        for parameter in &parameters[1..] {
+            assert_eq!(parameter["in"], "query");
+            assert_eq!(parameter["required"], false);
+        }
+
+        // Ensure the response references a definition of the components, which references the block.
+        let schema = &operation["responses"]["200"]["content"]["application/json"]["schema"];
+        let name = schema["$ref"].as_str().unwrap().strip_prefix("#/components/schemas/").unwrap();
+        assert!(openapi["components"]["schemas"][name].is_object());
+        assert_eq!(openapi["components"]["schemas"]["Block"]["type"], "object");
+    }
+}
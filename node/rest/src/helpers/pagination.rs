@@ -0,0 +1,255 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::RestError;
+
+use axum::{
+    http::HeaderValue,
+    response::{IntoResponse, Response},
+};
+use axum_extra::response::ErasedJson;
+use indexmap::IndexMap;
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{Schema, SchemaObject, SubschemaValidation},
+    JsonSchema,
+};
+use serde::{Deserialize, Serialize};
+use std::{hash::Hash, marker::PhantomData, ops::Range};
+
+/// The maximum number of items in a page of a list endpoint.
+pub const MAX_PAGE_SIZE: usize = 100;
+/// The header of the cursor of the next page, in a response without a cursor.
+pub const NEXT_CURSOR_HEADER: &str = "x-next-cursor";
+
+/// The query object of a list endpoint.
+///
+/// Note: A list endpoint returns a `Page` only if the query has a cursor, which is empty for the first page.
+/// Otherwise, it returns the shape that it had before it was paginated (see `Page::respond`).
+#[derive(Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+pub struct PageQuery {
+    /// The cursor returned with the previous page, or an empty cursor for the first page.
+    pub cursor: Option<String>,
+    /// The maximum number of items to return, up to the maximum page size of the endpoint.
+    pub limit: Option<usize>,
+}
+
+impl PageQuery {
+    /// Returns `true` if the query has a cursor, in which case the response is a `Page`.
+    pub fn is_paged(&self) -> bool {
+        self.cursor.is_some()
+    }
+
+    /// Returns the cursor returned with the previous page, or `None` for the first page.
+    pub fn cursor(&self) -> Option<&str> {
+        self.cursor.as_deref().filter(|cursor| !cursor.is_empty())
+    }
+
+    /// Returns the maximum number of items to return, up to the given maximum page size.
+    pub fn limit(&self, max_limit: usize) -> usize {
+        self.limit.unwrap_or(max_limit).clamp(1, max_limit)
+    }
+
+    /// Returns the block heights of the page within the given range, in ascending order, and the cursor of the next
+    /// page. The cursor of a page is its last block height.
+    pub fn heights(&self, range: Range<u32>, max_limit: usize) -> Result<(Range<u32>, Option<String>), RestError> {
+        let start = match self.cursor() {
+            Some(cursor) => parse_height_cursor(cursor)?.saturating_add(1).max(range.start),
+            None => range.start,
+        };
+        let end = start.saturating_add(u32::try_from(self.limit(max_limit)).unwrap_or(u32::MAX)).min(range.end);
+        // Note: There is a next page only if there are heights in the range beyond this page.
+        let next_cursor = match end < range.end && start < end {
+            true => Some((end - 1).to_string()),
+            false => None,
+        };
+        Ok((start..end.max(start), next_cursor))
+    }
+
+    /// Returns the block heights of the page below the given height (exclusive), in descending order, and the cursor
+    /// of the next page. The cursor of a page is its last (lowest) block height.
+    pub fn heights_before(&self, end: u32, max_limit: usize) -> Result<(Range<u32>, Option<String>), RestError> {
+        let end = match self.cursor() {
+            Some(cursor) => parse_height_cursor(cursor)?.min(end),
+            None => end,
+        };
+        let start = end.saturating_sub(u32::try_from(self.limit(max_limit)).unwrap_or(u32::MAX));
+        let next_cursor = match start > 0 {
+            true => Some(start.to_string()),
+            false => None,
+        };
+        Ok((start..end, next_cursor))
+    }
+}
+
+/// Returns the block height of the given cursor.
+fn parse_height_cursor(cursor: &str) -> Result<u32, RestError> {
+    cursor.parse().map_err(|_| RestError(format!("Invalid cursor '{cursor}', expected a block height")))
+}
+
+/// The query object of a list endpoint with its own query object `Q`, as described in the OpenAPI document.
+///
+/// Note: The endpoint extracts `Q` and the `PageQuery` separately, so this type only describes their parameters.
+#[derive(JsonSchema)]
+pub struct Paged<Q> {
+    #[serde(flatten)]
+    pub query: Q,
+    #[serde(flatten)]
+    pub page: PageQuery,
+}
+
+/// The response of a list endpoint with the items `T`, as described in the OpenAPI document, which is a `Page` if
+/// the query has a cursor, and otherwise the shape `U` of the endpoint before it was paginated.
+pub struct PageResponse<T, U = T>(PhantomData<(T, U)>);
+
+impl<T: JsonSchema, U: JsonSchema> JsonSchema for PageResponse<T, U> {
+    fn schema_name() -> String {
+        format!("PageResponse_of_{}_or_{}", T::schema_name(), U::schema_name())
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        let one_of = vec![generator.subschema_for::<Page<T>>(), generator.subschema_for::<U>()];
+        let subschemas = SubschemaValidation { one_of: Some(one_of), ..Default::default() };
+        SchemaObject { subschemas: Some(Box::new(subschemas)), ..Default::default() }.into()
+    }
+}
+
+/// A page of a list endpoint.
+#[derive(Clone, Debug, Serialize, JsonSchema)]
+pub struct Page<T> {
+    /// The items of the page.
+    pub items: T,
+    /// The cursor of the next page, or `None` if this is the last page.
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<Vec<T>> {
+    /// Returns the page of the given items for the given query, ordered by the given key, which must be unique.
+    /// The cursor of a page is the key of its last item, so the pages remain stable as items are added or removed.
+    pub fn new(items: impl IntoIterator<Item = T>, key: impl Fn(&T) -> String, query: &PageQuery) -> Self {
+        // Retrieve the items after the cursor, in order of their key.
+        let mut items = items
+            .into_iter()
+            .map(|item| (key(&item), item))
+            .filter(|(key, _)| query.cursor().map_or(true, |cursor| key.as_str() > cursor))
+            .collect::<Vec<_>>();
+        items.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+        Self::from_keyed(items, query.limit(MAX_PAGE_SIZE))
+    }
+
+    /// Returns the page of the given items for the given query, in the given order, with the given maximum page size.
+    /// The key of each item must be unique, and the cursor of a page is the key of its last item, which must still be
+    /// in the items when the next page is requested.
+    pub fn ordered(
+        items: impl IntoIterator<Item = T>,
+        key: impl Fn(&T) -> String,
+        query: &PageQuery,
+        max_limit: usize,
+    ) -> Result<Self, RestError> {
+        let mut items = items.into_iter().map(|item| (key(&item), item)).collect::<Vec<_>>();
+        // Retrieve the items after the cursor.
+        if let Some(cursor) = query.cursor() {
+            match items.iter().position(|(key, _)| key == cursor) {
+                Some(position) => {
+                    items.drain(..=position);
+                }
+                None => return Err(RestError(format!("The cursor '{cursor}' is no longer in the list"))),
+            }
+        }
+        Ok(Self::from_keyed(items, query.limit(max_limit)))
+    }
+
+    /// Returns the page of the first items of the given keyed items, up to the given limit.
+    fn from_keyed(mut items: Vec<(String, T)>, limit: usize) -> Self {
+        // Note: There is a next page only if there are items beyond the limit.
+        let next_cursor = match items.len() > limit {
+            true => Some(items[limit - 1].0.clone()),
+            false => None,
+        };
+        items.truncate(limit);
+        Self { items: items.into_iter().map(|(_, item)| item).collect(), next_cursor }
+    }
+}
+
+impl<T: Serialize> Page<T> {
+    /// Returns the response of the page to the given query, which is the page if the query has a cursor.
+    ///
+    /// Otherwise, the items are returned in the given shape of the endpoint before it was paginated, so its clients
+    /// are not broken, and the cursor of the next page is returned in the `X-Next-Cursor` header.
+    pub fn respond(self, query: &PageQuery, unpaginated: impl FnOnce(T) -> ErasedJson) -> Response {
+        if query.is_paged() {
+            return ErasedJson::pretty(self).into_response();
+        }
+        let mut response = unpaginated(self.items).into_response();
+        if let Some(next_cursor) = self.next_cursor.and_then(|cursor| HeaderValue::from_str(&cursor).ok()) {
+            response.headers_mut().insert(NEXT_CURSOR_HEADER, next_cursor);
+        }
+        response
+    }
+}
+
+impl<K: Hash + Eq, V> Page<Vec<(K, V)>> {
+    /// Returns the page with its items as a map, preserving their order.
+    pub fn into_map(self) -> Page<IndexMap<K, V>> {
+        Page { items: self.items.into_iter().collect(), next_cursor: self.next_cursor }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(cursor: Option<&str>, limit: Option<usize>) -> PageQuery {
+        PageQuery { cursor: cursor.map(ToString::to_string), limit }
+    }
+
+    #[test]
+    fn test_heights() {
+        let heights = |cursor, limit| query(cursor, limit).heights(10..25, 10).map_err(|error| error.0);
+
+        // Ensure the pages cover the range, and the last page has no next cursor.
+        assert_eq!(heights(Some(""), None).unwrap(), (10..20, Some("19".to_string())));
+        assert_eq!(heights(Some("19"), None).unwrap(), (20..25, None));
+        assert_eq!(heights(Some("9"), Some(5)).unwrap(), (10..15, Some("14".to_string())));
+        // Ensure the limit is bounded, and a cursor beyond the range returns an empty page.
+        assert_eq!(heights(Some(""), Some(100)).unwrap(), (10..20, Some("19".to_string())));
+        assert_eq!(heights(Some("30"), None).unwrap(), (31..31, None));
+        assert!(heights(Some("x"), None).is_err());
+    }
+
+    #[test]
+    fn test_heights_before() {
+        let heights = |cursor, limit| query(cursor, limit).heights_before(25, 10).map_err(|error| error.0);
+
+        // Ensure the pages cover the heights in descending order, and the last page has no next cursor.
+        assert_eq!(heights(Some(""), None).unwrap(), (15..25, Some("15".to_string())));
+        assert_eq!(heights(Some("15"), None).unwrap(), (5..15, Some("5".to_string())));
+        assert_eq!(heights(Some("5"), None).unwrap(), (0..5, None));
+        assert!(heights(Some("x"), None).is_err());
+    }
+
+    #[test]
+    fn test_ordered_page() {
+        let items = ["c", "a", "b", "d"];
+        let page = |cursor| Page::ordered(items, ToString::to_string, &query(cursor, Some(2)), MAX_PAGE_SIZE);
+
+        // Ensure the items keep their order, and the pages continue after the cursor.
+        let first = page(Some("")).unwrap();
+        assert_eq!((first.items, first.next_cursor), (vec!["c", "a"], Some("a".to_string())));
+        let second = page(Some("a")).unwrap();
+        assert_eq!((second.items, second.next_cursor), (vec!["b", "d"], None));
+        // Ensure a cursor that is no longer in the items is rejected.
+        assert!(page(Some("e")).is_err());
+    }
+}
@@ -0,0 +1,69 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::Serialize;
+use std::fmt;
+
+/// The reason a submitted transaction was rejected by the node.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "reason", content = "details", rename_all = "snake_case")]
+pub enum TransactionRejection {
+    /// The transaction is a fee transaction, which may not be submitted on its own.
+    FeeTransaction,
+    /// The transaction already exists in the ledger.
+    AlreadyConfirmed,
+    /// The transaction already exists in the memory pool.
+    AlreadyInMempool,
+    /// The transaction spends the given serial number, which was already spent in the ledger.
+    SpentSerialNumber(String),
+    /// The transaction produces the given commitment, which already exists in the ledger.
+    ExistingCommitment(String),
+    /// The expiration height of the transaction is invalid.
+    InvalidExpiration(String),
+    /// The transaction is malformed, or its proofs are invalid.
+    InvalidTransaction(String),
+    /// The memory pool refused the transaction, e.g. it conflicts with a transaction with a higher fee.
+    MempoolRejected(String),
+}
+
+impl TransactionRejection {
+    /// Returns the name of the reason of the rejection.
+    pub const fn reason(&self) -> &'static str {
+        match self {
+            Self::FeeTransaction => "fee_transaction",
+            Self::AlreadyConfirmed => "already_confirmed",
+            Self::AlreadyInMempool => "already_in_mempool",
+            Self::SpentSerialNumber(..) => "spent_serial_number",
+            Self::ExistingCommitment(..) => "existing_commitment",
+            Self::InvalidExpiration(..) => "invalid_expiration",
+            Self::InvalidTransaction(..) => "invalid_transaction",
+            Self::MempoolRejected(..) => "mempool_rejected",
+        }
+    }
+}
+
+impl fmt::Display for TransactionRejection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::FeeTransaction => write!(f, "Fee transactions may not be submitted on their own"),
+            Self::AlreadyConfirmed => write!(f, "The transaction already exists in the ledger"),
+            Self::AlreadyInMempool => write!(f, "The transaction already exists in the memory pool"),
+            Self::SpentSerialNumber(serial_number) => write!(f, "The serial number '{serial_number}' is already spent"),
+            Self::ExistingCommitment(commitment) => write!(f, "The commitment '{commitment}' already exists"),
+            Self::InvalidExpiration(error) => write!(f, "Invalid expiration height - {error}"),
+            Self::InvalidTransaction(error) => write!(f, "Invalid transaction - {error}"),
+            Self::MempoolRejected(error) => write!(f, "Rejected by the memory pool - {error}"),
+        }
+    }
+}
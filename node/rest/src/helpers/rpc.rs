@@ -0,0 +1,260 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::TransactionRejection;
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+/// The version of the JSON-RPC protocol.
+pub const JSON_RPC_VERSION: &str = "2.0";
+/// The maximum number of requests in a JSON-RPC batch.
+pub const MAX_RPC_BATCH_SIZE: usize = 100;
+
+/// The error code for a request that is not valid JSON.
+pub const RPC_PARSE_ERROR: i64 = -32700;
+/// The error code for a request that is not a valid JSON-RPC request.
+pub const RPC_INVALID_REQUEST: i64 = -32600;
+/// The error code for a request of an unknown method.
+pub const RPC_METHOD_NOT_FOUND: i64 = -32601;
+/// The error code for a request with invalid parameters.
+pub const RPC_INVALID_PARAMS: i64 = -32602;
+/// The error code for a request that failed on the node.
+pub const RPC_INTERNAL_ERROR: i64 = -32603;
+/// The error code for a request of a block or transaction that is not in the ledger.
+pub const RPC_NOT_FOUND: i64 = -32001;
+/// The error code for a transaction that was rejected by the node.
+pub const RPC_TRANSACTION_REJECTED: i64 = -32002;
+/// The error code for an operation that is not available on the node.
+pub const RPC_UNAVAILABLE: i64 = -32003;
+/// The error code for a request that is not authorized.
+pub const RPC_UNAUTHORIZED: i64 = -32004;
+/// The error code for a request that exceeds the rate limit of the caller.
+pub const RPC_RATE_LIMITED: i64 = -32005;
+
+/// The methods of the JSON-RPC server.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RpcMethod {
+    /// Returns the block with the given hash.
+    GetBlock,
+    /// Returns the block at the given height.
+    GetBlockByHeight,
+    /// Returns the confirmed transaction with the given ID.
+    GetTransaction,
+    /// Returns the IPs of the connected peers.
+    GetPeers,
+    /// Returns the address, type, and chain state of the node.
+    GetNodeInfo,
+    /// Returns the latest committee, with the recent participation of each validator, and the latest committed round.
+    GetCommittee,
+    /// Fully validates the given transaction, and broadcasts it, or returns the reason it is rejected.
+    SendTransaction,
+}
+
+impl RpcMethod {
+    /// The methods of the JSON-RPC server.
+    pub const ALL: [Self; 7] = [
+        Self::GetBlock,
+        Self::GetBlockByHeight,
+        Self::GetTransaction,
+        Self::GetPeers,
+        Self::GetNodeInfo,
+        Self::GetCommittee,
+        Self::SendTransaction,
+    ];
+
+    /// Returns the name of the method.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::GetBlock => "getblock",
+            Self::GetBlockByHeight => "getblockbyheight",
+            Self::GetTransaction => "gettransaction",
+            Self::GetPeers => "getpeers",
+            Self::GetNodeInfo => "getnodeinfo",
+            Self::GetCommittee => "getcommittee",
+            Self::SendTransaction => "sendtransaction",
+        }
+    }
+
+    /// Returns the method with the given name, if it exists.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|method| method.name() == name)
+    }
+}
+
+/// An error of a JSON-RPC request.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RpcError {
+    /// The error code.
+    pub code: i64,
+    /// The error message.
+    pub message: String,
+    /// The additional information about the error, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl RpcError {
+    /// Initializes a new error with the given code and message.
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self { code, message: message.into(), data: None }
+    }
+
+    /// Returns an error for a request that is not valid JSON.
+    pub fn parse_error(error: impl ToString) -> Self {
+        Self::new(RPC_PARSE_ERROR, "Parse error").with_data(error.to_string())
+    }
+
+    /// Returns an error for a request that is not a valid JSON-RPC request.
+    pub fn invalid_request(reason: impl ToString) -> Self {
+        Self::new(RPC_INVALID_REQUEST, "Invalid request").with_data(reason.to_string())
+    }
+
+    /// Returns an error for a request of an unknown method.
+    pub fn method_not_found(method: &str) -> Self {
+        Self::new(RPC_METHOD_NOT_FOUND, "Method not found").with_data(format!("Unknown method '{method}'"))
+    }
+
+    /// Returns an error for a request with invalid parameters.
+    pub fn invalid_params(reason: impl ToString) -> Self {
+        Self::new(RPC_INVALID_PARAMS, "Invalid params").with_data(reason.to_string())
+    }
+
+    /// Returns an error for a request that failed on the node.
+    pub fn internal_error(error: impl ToString) -> Self {
+        Self::new(RPC_INTERNAL_ERROR, "Internal error").with_data(error.to_string())
+    }
+
+    /// Returns an error for a submitted transaction that was rejected, with the typed reason as its data.
+    pub fn transaction_rejected(rejection: &TransactionRejection) -> Self {
+        let data = serde_json::to_value(rejection).ok();
+        Self { code: RPC_TRANSACTION_REJECTED, message: rejection.to_string(), data }
+    }
+
+    /// Returns an error for a request that is not authorized.
+    pub fn unauthorized(reason: impl ToString) -> Self {
+        Self::new(RPC_UNAUTHORIZED, "Unauthorized").with_data(reason.to_string())
+    }
+
+    /// Returns an error for a request that exceeds the rate limit of the caller.
+    pub fn rate_limited() -> Self {
+        Self::new(RPC_RATE_LIMITED, "Rate limit exceeded")
+    }
+
+    /// Returns an error for an operation that is not available on the node.
+    pub fn unavailable(reason: impl ToString) -> Self {
+        Self::new(RPC_UNAVAILABLE, "Unavailable").with_data(reason.to_string())
+    }
+
+    /// Sets the additional information about the error.
+    pub fn with_data(mut self, data: String) -> Self {
+        self.data = Some(Value::String(data));
+        self
+    }
+}
+
+impl From<anyhow::Error> for RpcError {
+    fn from(error: anyhow::Error) -> Self {
+        Self::internal_error(error)
+    }
+}
+
+/// A JSON-RPC request.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RpcRequest {
+    /// The ID of the request, or `None` if the request is a notification, which is not answered.
+    pub id: Option<Value>,
+    /// The name of the method.
+    pub method: String,
+    /// The parameters of the method, by position or by name.
+    pub params: Value,
+}
+
+impl RpcRequest {
+    /// Parses a JSON-RPC request from the given JSON value.
+    pub fn parse(value: Value) -> Result<Self, RpcError> {
+        let Value::Object(mut object) = value else {
+            return Err(RpcError::invalid_request("The request is not an object"));
+        };
+        // Ensure the protocol version is correct.
+        if object.get("jsonrpc").and_then(Value::as_str) != Some(JSON_RPC_VERSION) {
+            return Err(RpcError::invalid_request(format!("The 'jsonrpc' member must be \"{JSON_RPC_VERSION}\"")));
+        }
+        // Ensure the ID is a string, a number, or null.
+        let id = object.remove("id");
+        if let Some(id) = &id {
+            if !(id.is_string() || id.is_number() || id.is_null()) {
+                return Err(RpcError::invalid_request("The 'id' member must be a string, a number, or null"));
+            }
+        }
+        // Ensure the method is a string.
+        let Some(Value::String(method)) = object.remove("method") else {
+            return Err(RpcError::invalid_request("The 'method' member must be a string"));
+        };
+        // Ensure the parameters are an array or an object, if they are given.
+        let params = object.remove("params").unwrap_or(Value::Null);
+        if !(params.is_array() || params.is_object() || params.is_null()) {
+            return Err(RpcError::invalid_request("The 'params' member must be an array or an object"));
+        }
+        Ok(Self { id, method, params })
+    }
+
+    /// Returns the parameter at the given position or with the given name.
+    pub fn param<T: DeserializeOwned>(&self, index: usize, name: &str) -> Result<T, RpcError> {
+        match self.optional_param(index, name)? {
+            Some(param) => Ok(param),
+            None => Err(RpcError::invalid_params(format!("Missing the '{name}' parameter"))),
+        }
+    }
+
+    /// Returns the parameter at the given position or with the given name, if it is given.
+    pub fn optional_param<T: DeserializeOwned>(&self, index: usize, name: &str) -> Result<Option<T>, RpcError> {
+        let param = match &self.params {
+            Value::Array(params) => params.get(index),
+            Value::Object(params) => params.get(name),
+            _ => None,
+        };
+        match param {
+            None | Some(Value::Null) => Ok(None),
+            Some(param) => T::deserialize(param)
+                .map(Some)
+                .map_err(|error| RpcError::invalid_params(format!("Invalid '{name}' parameter - {error}"))),
+        }
+    }
+}
+
+/// A JSON-RPC response.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RpcResponse {
+    /// The version of the JSON-RPC protocol.
+    pub jsonrpc: &'static str,
+    /// The result of the request, if it succeeded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    /// The error of the request, if it failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+    /// The ID of the request, or null if the ID could not be determined.
+    pub id: Value,
+}
+
+impl RpcResponse {
+    /// Initializes a new response with the given ID and outcome.
+    pub fn new(id: Value, outcome: Result<Value, RpcError>) -> Self {
+        match outcome {
+            Ok(result) => Self { jsonrpc: JSON_RPC_VERSION, result: Some(result), error: None, id },
+            Err(error) => Self { jsonrpc: JSON_RPC_VERSION, result: None, error: Some(error), id },
+        }
+    }
+}
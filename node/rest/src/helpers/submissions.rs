@@ -0,0 +1,294 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_router::{messages::UnconfirmedTransaction, MemoryProfile};
+use snarkvm::prelude::Network;
+
+use indexmap::IndexMap;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+/// The number of blocks during which a submitted transaction without an expiration height is re-broadcast.
+pub const DEFAULT_SUBMISSION_VALIDITY_IN_BLOCKS: u32 = 360; // blocks
+/// The interval in seconds at which pending submitted transactions are re-broadcast.
+pub const REBROADCAST_INTERVAL_IN_SECS: u64 = 30; // 30 seconds
+/// The maximum number of pending submitted transactions that are re-broadcast per interval.
+pub const MAX_REBROADCASTS_PER_INTERVAL: usize = 64;
+/// The maximum number of times a submitted transaction is broadcast, after which it is no longer re-broadcast.
+pub const MAX_BROADCASTS_PER_SUBMISSION: u32 = 20;
+/// The maximum number of submission events that are buffered for each subscriber.
+const SUBMISSION_EVENT_CAPACITY: usize = 256;
+
+/// The status of a transaction submitted through the REST API.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum SubmissionStatus {
+    /// The transaction is waiting to be confirmed, and is periodically re-broadcast.
+    Pending { num_broadcasts: u32, expiration_height: u32 },
+    /// The transaction was confirmed in the block at the given height.
+    Confirmed { height: u32 },
+    /// The transaction was not confirmed by its expiration height.
+    Expired { expiration_height: u32 },
+}
+
+impl SubmissionStatus {
+    /// Returns `true` if the transaction is still pending.
+    pub const fn is_pending(&self) -> bool {
+        matches!(self, Self::Pending { .. })
+    }
+}
+
+/// A change in the status of a transaction submitted through the REST API.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubmissionEvent<N: Network> {
+    /// The ID of the transaction.
+    pub transaction_id: N::TransactionID,
+    /// The new status of the transaction.
+    pub status: SubmissionStatus,
+}
+
+/// A transaction submitted through the REST API, and its status.
+struct Submission<N: Network> {
+    /// The announced transaction, along with its expiration height, if the submitter set one.
+    message: UnconfirmedTransaction<N>,
+    /// The status of the transaction.
+    status: SubmissionStatus,
+}
+
+/// The tracker of transactions submitted through the REST API.
+///
+/// A submitted transaction is re-broadcast periodically, until it is confirmed or its expiration height is reached,
+/// so that submitters do not need to implement their own retry loops. To avoid gossip storms, the number of
+/// re-broadcasts per interval is capped, favoring the transactions that were broadcast the fewest times,
+/// and each transaction is broadcast at most `MAX_BROADCASTS_PER_SUBMISSION` times.
+pub struct Submissions<N: Network> {
+    /// The map of transaction IDs to their submission.
+    submissions: RwLock<IndexMap<N::TransactionID, Submission<N>>>,
+    /// The sender of the submission events.
+    events: broadcast::Sender<SubmissionEvent<N>>,
+    /// The maximum number of submitted transactions to track.
+    max_submissions: usize,
+}
+
+impl<N: Network> Default for Submissions<N> {
+    /// Initializes a new tracker of submitted transactions, sized for the standard memory profile.
+    fn default() -> Self {
+        Self::new(MemoryProfile::default())
+    }
+}
+
+impl<N: Network> Submissions<N> {
+    /// Initializes a new tracker of submitted transactions, sized for the given memory profile.
+    pub fn new(profile: MemoryProfile) -> Self {
+        Self {
+            submissions: Default::default(),
+            events: broadcast::channel(SUBMISSION_EVENT_CAPACITY).0,
+            max_submissions: profile.max_stored_transactions(),
+        }
+    }
+
+    /// Returns a receiver of the changes in the status of the submitted transactions.
+    pub fn subscribe(&self) -> broadcast::Receiver<SubmissionEvent<N>> {
+        self.events.subscribe()
+    }
+
+    /// Returns the status of the given submitted transaction, if it is tracked.
+    pub fn status(&self, transaction_id: &N::TransactionID) -> Option<SubmissionStatus> {
+        self.submissions.read().get(transaction_id).map(|submission| submission.status)
+    }
+
+    /// Starts tracking the given transaction, which was broadcast once, until the given expiration height.
+    pub fn insert(&self, message: UnconfirmedTransaction<N>, expiration_height: u32) {
+        let mut submissions = self.submissions.write();
+        // If the tracker is full, evict the oldest finished submission, or otherwise the oldest submission.
+        if submissions.len() >= self.max_submissions {
+            let index = submissions.values().position(|submission| !submission.status.is_pending()).unwrap_or(0);
+            submissions.shift_remove_index(index);
+        }
+        let transaction_id = message.transaction_id;
+        let status = SubmissionStatus::Pending { num_broadcasts: 1, expiration_height };
+        submissions.insert(transaction_id, Submission { message, status });
+        drop(submissions);
+        self.publish(transaction_id, status);
+    }
+
+    /// Updates the status of the pending transactions, given the latest block height and a function
+    /// that returns the block height of a confirmed transaction. Returns up to the given number of transactions
+    /// that remain pending, which the caller is expected to re-broadcast.
+    ///
+    /// Note: The confirmed transactions are looked up without holding the lock, as the lookups read the ledger.
+    pub fn update(
+        &self,
+        latest_height: u32,
+        max_rebroadcasts: usize,
+        find_confirmed_height: impl Fn(&N::TransactionID) -> Option<u32>,
+    ) -> Vec<UnconfirmedTransaction<N>> {
+        // Retrieve the IDs of the pending transactions.
+        let pending_ids = self
+            .submissions
+            .read()
+            .iter()
+            .filter(|(_, submission)| submission.status.is_pending())
+            .map(|(transaction_id, _)| *transaction_id)
+            .collect::<Vec<_>>();
+        // Find the block heights of the confirmed transactions.
+        let confirmed = pending_ids
+            .into_iter()
+            .filter_map(|transaction_id| Some((transaction_id, find_confirmed_height(&transaction_id)?)))
+            .collect::<IndexMap<_, _>>();
+
+        let mut events = Vec::new();
+        let mut pending = Vec::new();
+        let mut submissions = self.submissions.write();
+        // Iterate over the pending transactions.
+        for (transaction_id, submission) in submissions.iter_mut() {
+            let SubmissionStatus::Pending { num_broadcasts, expiration_height } = submission.status else {
+                continue;
+            };
+            // Check if the transaction was confirmed.
+            if let Some(height) = confirmed.get(transaction_id) {
+                debug!("Submitted transaction '{transaction_id}' was confirmed at block {height}");
+                submission.status = SubmissionStatus::Confirmed { height: *height };
+                events.push((*transaction_id, submission.status));
+            }
+            // Check if the transaction has expired.
+            else if latest_height >= expiration_height {
+                warn!("Submitted transaction '{transaction_id}' expired at block {expiration_height}");
+                submission.status = SubmissionStatus::Expired { expiration_height };
+                events.push((*transaction_id, submission.status));
+            }
+            // Otherwise, select the transaction to be re-broadcast, if it has not reached the broadcast limit.
+            else if num_broadcasts < MAX_BROADCASTS_PER_SUBMISSION {
+                pending.push((*transaction_id, num_broadcasts, expiration_height));
+            }
+        }
+        // Re-broadcast the transactions that were broadcast the fewest times, up to the given limit.
+        pending.sort_by_key(|(_, num_broadcasts, _)| *num_broadcasts);
+        pending.truncate(max_rebroadcasts);
+        let messages = pending
+            .into_iter()
+            .filter_map(|(transaction_id, num_broadcasts, expiration_height)| {
+                let submission = submissions.get_mut(&transaction_id)?;
+                submission.status = SubmissionStatus::Pending { num_broadcasts: num_broadcasts + 1, expiration_height };
+                events.push((transaction_id, submission.status));
+                Some(submission.message.clone())
+            })
+            .collect();
+        drop(submissions);
+
+        // Publish the changes in the status of the submitted transactions.
+        for (transaction_id, status) in events {
+            self.publish(transaction_id, status);
+        }
+        messages
+    }
+
+    /// Publishes the given status of the given transaction to the subscribers, if there are any.
+    fn publish(&self, transaction_id: N::TransactionID, status: SubmissionStatus) {
+        // Note: Sending fails only if there are no subscribers, in which case the event is dropped.
+        let _ = self.events.send(SubmissionEvent { transaction_id, status });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{
+        ledger::narwhal::Data,
+        prelude::{Field, TestRng, Uniform},
+    };
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    /// Returns a sample unconfirmed transaction message, with a random transaction ID.
+    fn sample_message(rng: &mut TestRng) -> UnconfirmedTransaction<CurrentNetwork> {
+        let transaction_id = <CurrentNetwork as Network>::TransactionID::from(Field::rand(rng));
+        let transaction = Data::Buffer(Default::default());
+        UnconfirmedTransaction { transaction_id, transaction, expiration_height: None }
+    }
+
+    #[test]
+    fn test_submissions() {
+        let rng = &mut TestRng::default();
+        let submissions = Submissions::<CurrentNetwork>::default();
+        let mut events = submissions.subscribe();
+        let (message_1, message_2, message_3) = (sample_message(rng), sample_message(rng), sample_message(rng));
+        let (id_1, id_2, id_3) = (message_1.transaction_id, message_2.transaction_id, message_3.transaction_id);
+
+        // Ensure a new submission is pending, and published.
+        submissions.insert(message_1, 10);
+        submissions.insert(message_2, 20);
+        submissions.insert(message_3, 30);
+        let pending = SubmissionStatus::Pending { num_broadcasts: 1, expiration_height: 10 };
+        assert_eq!(submissions.status(&id_1), Some(pending));
+        assert_eq!(events.try_recv().unwrap(), SubmissionEvent { transaction_id: id_1, status: pending });
+        assert_eq!(events.try_recv().unwrap().transaction_id, id_2);
+        assert_eq!(events.try_recv().unwrap().transaction_id, id_3);
+
+        // Ensure the pending transactions are re-broadcast, up to the given limit.
+        let rebroadcast = submissions.update(5, 2, |_| None);
+        assert_eq!(rebroadcast.len(), 2);
+        let pending = SubmissionStatus::Pending { num_broadcasts: 2, expiration_height: 10 };
+        assert_eq!(submissions.status(&id_1), Some(pending));
+        assert_eq!(events.try_recv().unwrap().transaction_id, id_1);
+        assert_eq!(events.try_recv().unwrap().transaction_id, id_2);
+
+        // Ensure the transactions broadcast the fewest times are re-broadcast first.
+        let rebroadcast = submissions.update(5, 1, |_| None);
+        assert_eq!(rebroadcast[0].transaction_id, id_3);
+        assert_eq!(events.try_recv().unwrap().transaction_id, id_3);
+
+        // Ensure a confirmed transaction and an expired transaction are no longer re-broadcast.
+        let rebroadcast = submissions.update(10, 10, |transaction_id| (*transaction_id == id_2).then_some(8));
+        assert_eq!(rebroadcast.len(), 1);
+        assert_eq!(rebroadcast[0].transaction_id, id_3);
+        assert_eq!(submissions.status(&id_1), Some(SubmissionStatus::Expired { expiration_height: 10 }));
+        assert_eq!(submissions.status(&id_2), Some(SubmissionStatus::Confirmed { height: 8 }));
+        let expired = SubmissionStatus::Expired { expiration_height: 10 };
+        assert_eq!(events.try_recv().unwrap(), SubmissionEvent { transaction_id: id_1, status: expired });
+        let confirmed = SubmissionStatus::Confirmed { height: 8 };
+        assert_eq!(events.try_recv().unwrap(), SubmissionEvent { transaction_id: id_2, status: confirmed });
+        assert_eq!(events.try_recv().unwrap().transaction_id, id_3);
+        assert!(events.try_recv().is_err());
+
+        // Ensure the finished transactions are not looked up again.
+        submissions.update(10, 10, |transaction_id| {
+            assert_eq!(*transaction_id, id_3);
+            None
+        });
+    }
+
+    #[test]
+    fn test_submissions_broadcast_limit() {
+        let rng = &mut TestRng::default();
+        let submissions = Submissions::<CurrentNetwork>::default();
+        let message = sample_message(rng);
+        let transaction_id = message.transaction_id;
+        submissions.insert(message, 100);
+
+        // Ensure the transaction is re-broadcast until it reaches the broadcast limit.
+        for _ in 1..MAX_BROADCASTS_PER_SUBMISSION {
+            assert_eq!(submissions.update(1, 1, |_| None).len(), 1);
+        }
+        assert!(submissions.update(1, 1, |_| None).is_empty());
+        let num_broadcasts = MAX_BROADCASTS_PER_SUBMISSION;
+        let status = SubmissionStatus::Pending { num_broadcasts, expiration_height: 100 };
+        assert_eq!(submissions.status(&transaction_id), Some(status));
+
+        // Ensure the transaction still expires once it is no longer re-broadcast.
+        assert!(submissions.update(100, 1, |_| None).is_empty());
+        assert_eq!(submissions.status(&transaction_id), Some(SubmissionStatus::Expired { expiration_height: 100 }));
+    }
+}
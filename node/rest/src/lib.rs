@@ -17,26 +17,72 @@
 #[macro_use]
 extern crate tracing;
 
+mod admin;
+pub use admin::*;
+
+mod config;
+pub use config::*;
+
+#[cfg(feature = "grpc")]
+mod grpc;
+#[cfg(feature = "grpc")]
+pub use grpc::proto;
+
+mod health;
+pub use health::*;
+
 mod helpers;
 pub use helpers::*;
 
 mod routes;
 
+mod rpc;
+
+mod subscriptions;
+pub use subscriptions::MAX_SUBSCRIPTIONS_PER_CONNECTION;
+
+#[cfg(unix)]
+mod unix_socket;
+#[cfg(unix)]
+pub use unix_socket::UnixSocketConfig;
+
+use snarkos_node_bft_ledger_service::BlockEventStream;
 use snarkos_node_consensus::Consensus;
 use snarkos_node_router::{
-    messages::{Message, UnconfirmedTransaction},
+    messages::UnconfirmedTransaction,
     Routing,
 };
+use snarkos_node_storage::{AddressIndex, BackupConfig, LedgerCache};
+use snarkos_node_sync::BlockSync;
 use snarkvm::{
     console::{program::ProgramID, types::Field},
     ledger::narwhal::Data,
     prelude::{cfg_into_iter, store::ConsensusStorage, Ledger, Network},
 };
 
+use routes::{
+    AddressActivityResponse,
+    AddressHistoryPage,
+    BlockPage,
+    BlockRange,
+    ChainTip,
+    FeeEstimateOptions,
+    FeeEstimateResponse,
+    HeaderRange,
+    MempoolEntryResponse,
+    MempoolPage,
+};
+use subscriptions::BlockSubscription;
+
 use anyhow::Result;
 use axum::{
     extract::{ConnectInfo, DefaultBodyLimit, Path, Query, State},
-    http::{header::CONTENT_TYPE, Method, Request, StatusCode},
+    http::{
+        header::{CONTENT_TYPE, ETAG, IF_NONE_MATCH},
+        Method,
+        Request,
+        StatusCode,
+    },
     middleware,
     middleware::Next,
     response::Response,
@@ -45,22 +91,41 @@ use axum::{
 };
 use axum_extra::response::ErasedJson;
 use parking_lot::Mutex;
-use std::{net::SocketAddr, sync::Arc};
+use serde_json::Value;
+use std::{collections::BTreeMap, net::SocketAddr, sync::Arc, time::Duration};
 use tokio::task::JoinHandle;
 use tower_http::{
-    cors::{Any, CorsLayer},
+    cors::{AllowOrigin, CorsLayer},
     trace::TraceLayer,
 };
 
-/// A REST API server for the ledger.
+/// A REST API server for the ledger, which also serves JSON-RPC 2.0 requests at `POST /testnet3/rpc`.
 #[derive(Clone)]
 pub struct Rest<N: Network, C: ConsensusStorage<N>, R: Routing<N>> {
     /// The consensus module.
     consensus: Option<Consensus<N>>,
     /// The ledger.
     ledger: Ledger<N, C>,
+    /// The cache of the recent blocks, block headers, and transactions of the ledger.
+    ledger_cache: Arc<LedgerCache<N>>,
+    /// The configuration of the ledger backups, if backups are enabled.
+    backups: Option<BackupConfig>,
+    /// The address index, if it is enabled.
+    address_index: Option<Arc<AddressIndex>>,
+    /// The block sync module.
+    sync: BlockSync<N>,
+    /// The stream of the blocks added to the ledger.
+    block_events: BlockEventStream<N>,
     /// The node (routing).
     routing: Arc<R>,
+    /// The transactions submitted through the REST API.
+    submissions: Arc<Submissions<N>>,
+    /// The HTTP configuration of the REST server.
+    http: Arc<HttpConfig>,
+    /// The readiness thresholds of the node.
+    health: HealthConfig,
+    /// The access to the REST, JSON-RPC, and gRPC servers, if it is configured.
+    api_access: Option<Arc<ApiAccess>>,
     /// The server handles.
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
 }
@@ -69,14 +134,50 @@ impl<N: Network, C: 'static + ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R>
     /// Initializes a new instance of the server.
     pub fn start(
         rest_ip: SocketAddr,
+        config: RestConfig,
         consensus: Option<Consensus<N>>,
         ledger: Ledger<N, C>,
+        ledger_cache: Arc<LedgerCache<N>>,
+        backups: Option<BackupConfig>,
+        address_index: Option<Arc<AddressIndex>>,
+        sync: BlockSync<N>,
+        block_events: BlockEventStream<N>,
         routing: Arc<R>,
     ) -> Result<Self> {
+        // Initialize the tracker of submitted transactions, sized for the memory profile of the node.
+        let submissions = Arc::new(Submissions::new(routing.router().memory_profile()));
+        // Ensure the HTTP configuration is valid.
+        config.http.check()?;
+        // Initialize the access to the servers, if it is configured.
+        let api_access = config.api_access.map(ApiAccess::new).transpose()?.map(Arc::new);
         // Initialize the server.
-        let mut server = Self { consensus, ledger, routing, handles: Default::default() };
+        let mut server = Self {
+            consensus,
+            ledger,
+            ledger_cache,
+            backups,
+            address_index,
+            sync,
+            block_events,
+            routing,
+            submissions,
+            http: Arc::new(config.http),
+            health: config.health,
+            api_access,
+            handles: Default::default(),
+        };
         // Spawn the server.
-        server.spawn_server(rest_ip);
+        #[cfg(unix)]
+        server.spawn_server(rest_ip, config.unix_socket)?;
+        #[cfg(not(unix))]
+        server.spawn_server(rest_ip)?;
+        // Spawn the gRPC server, if it is enabled.
+        #[cfg(feature = "grpc")]
+        if let Some(grpc_ip) = config.grpc_ip {
+            server.spawn_grpc_server(grpc_ip)?;
+        }
+        // Spawn the re-broadcast of the submitted transactions.
+        server.spawn_rebroadcast();
         // Return the server.
         Ok(server)
     }
@@ -95,17 +196,53 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
 }
 
 impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
-    fn spawn_server(&mut self, rest_ip: SocketAddr) {
+    fn spawn_server(
+        &mut self,
+        rest_ip: SocketAddr,
+        #[cfg(unix)] unix_socket: Option<UnixSocketConfig>,
+    ) -> Result<()> {
+        let http_config = self.http.clone();
+        // Allow the configured CORS origins, or every origin if none are configured.
+        let cors_origins = http_config.cors_origin_values();
+        let allow_origin = match cors_origins.is_empty() {
+            true => AllowOrigin::any(),
+            false => AllowOrigin::list(cors_origins),
+        };
         let cors = CorsLayer::new()
-            .allow_origin(Any)
+            .allow_origin(allow_origin)
             .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
-            .allow_headers([CONTENT_TYPE]);
+            .allow_headers([CONTENT_TYPE, IF_NONE_MATCH])
+            .expose_headers([ETAG]);
+
+        // Record the routes as they are registered, to describe them in the OpenAPI document.
+        let mut api = ApiRoutes::default();
+
+        // GET /health/..
+        let health_router = axum::Router::new()
+            .route(api.get("/health/live", "Returns 200 if the node is live"), get(Self::get_health_live))
+            .route(
+                api.get("/health/ready", "Returns 200 if the node is synced, connected, and writable"),
+                get(Self::get_health_ready),
+            )
+            .with_state(self.clone());
 
         let router = {
             axum::Router::new()
 
             // All the endpoints before the call to `route_layer` are protected with JWT auth.
-            .route("/testnet3/node/address", get(Self::get_node_address))
+            .route(
+                api.get_with_jwt("/testnet3/node/address", "Returns the address of the node"),
+                get(Self::get_node_address),
+            )
+            .route(
+                api.get_with_jwt("/testnet3/debug/consensusState", "Returns the internal state of the consensus"),
+                get(Self::get_debug_consensus_state),
+            )
+            .route(api.get_with_jwt("/testnet3/snapshot", "Streams a snapshot of the ledger"), get(Self::get_snapshot))
+            .route(
+                api.post_with_jwt("/testnet3/node/backup", "Creates a backup of the ledger"),
+                post(Self::create_backup),
+            )
             .route_layer(middleware::from_fn(auth_middleware))
 
             // ----------------- DEPRECATED ROUTES -----------------
@@ -113,79 +250,354 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
             // Please refer to the recommended routes for each endpoint:
 
             // Deprecated: use `/testnet3/block/height/latest` instead.
-            .route("/testnet3/latest/height", get(Self::latest_height))
+            .route(
+                api.get("/testnet3/latest/height", "Returns the latest block height (deprecated)"),
+                get(Self::latest_height),
+            )
             // Deprecated: use `/testnet3/block/hash/latest` instead.
-            .route("/testnet3/latest/hash", get(Self::latest_hash))
+            .route(
+                api.get("/testnet3/latest/hash", "Returns the latest block hash (deprecated)"),
+                get(Self::latest_hash),
+            )
             // Deprecated: use `/testnet3/latest/block/height` instead.
-            .route("/testnet3/latest/block", get(Self::latest_block))
+            .route(api.get("/testnet3/latest/block", "Returns the latest block (deprecated)"), get(Self::latest_block))
             // Deprecated: use `/testnet3/stateRoot/latest` instead.
-            .route("/testnet3/latest/stateRoot", get(Self::latest_state_root))
+            .route(
+                api.get("/testnet3/latest/stateRoot", "Returns the latest state root (deprecated)"),
+                get(Self::latest_state_root),
+            )
             // Deprecated: use `/testnet3/committee/latest` instead.
-            .route("/testnet3/latest/committee", get(Self::latest_committee))
+            .route(
+                api.get("/testnet3/latest/committee", "Returns the latest committee (deprecated)"),
+                get(Self::latest_committee),
+            )
             // ------------------------------------------------------
 
             // GET ../block/..
-            .route("/testnet3/block/height/latest", get(Self::get_block_height_latest))
-            .route("/testnet3/block/hash/latest", get(Self::get_block_hash_latest))
-            .route("/testnet3/block/latest", get(Self::get_block_latest))
-            .route("/testnet3/block/:height_or_hash", get(Self::get_block))
+            .route(
+                api.get_with::<(), u32>("/testnet3/block/height/latest", "Returns the latest block height"),
+                get(Self::get_block_height_latest),
+            )
+            .route(
+                api.get_with::<(), BlockHashSchema>("/testnet3/block/hash/latest", "Returns the latest block hash"),
+                get(Self::get_block_hash_latest),
+            )
+            .route(
+                api.get_with::<(), BlockSchema>("/testnet3/block/latest", "Returns the latest block"),
+                get(Self::get_block_latest),
+            )
+            .route(
+                api.get_with::<(), BlockSchema>(
+                    "/testnet3/block/:height_or_hash",
+                    "Returns the block with the given height or hash",
+                ),
+                get(Self::get_block),
+            )
             // The path param here is actually only the height, but the name must match the route
             // above, otherwise there'll be a conflict at runtime.
-            .route("/testnet3/block/:height_or_hash/transactions", get(Self::get_block_transactions))
+            .route(
+                api.get("/testnet3/block/:height_or_hash/transactions", "Returns the transactions of a block"),
+                get(Self::get_block_transactions),
+            )
 
             // GET and POST ../transaction/..
-            .route("/testnet3/transaction/:id", get(Self::get_transaction))
-            .route("/testnet3/transaction/confirmed/:id", get(Self::get_confirmed_transaction))
-            .route("/testnet3/transaction/broadcast", post(Self::transaction_broadcast))
+            .route(
+                api.get_with::<(), TransactionSchema>(
+                    "/testnet3/transaction/:id",
+                    "Returns the transaction with the given ID",
+                ),
+                get(Self::get_transaction),
+            )
+            .route(
+                api.get("/testnet3/transaction/confirmed/:id", "Returns the confirmed transaction with the given ID"),
+                get(Self::get_confirmed_transaction),
+            )
+            .route(
+                api.get("/testnet3/transaction/status/:id", "Returns the status of a submitted transaction"),
+                get(Self::get_transaction_status),
+            )
+            .route(
+                api.post("/testnet3/transaction/broadcast", "Broadcasts the given transaction"),
+                post(Self::transaction_broadcast),
+            )
+
+            // POST ../rpc
+            .route(api.post("/testnet3/rpc", "Processes a JSON-RPC 2.0 request or batch of requests"), post(Self::rpc))
+
+            // GET ../address/..
+            .route(
+                api.get_with::<Paged<AddressHistoryPage>, PageResponse<Vec<AddressActivityResponse>, Value>>(
+                    "/testnet3/address/:address/history",
+                    "Returns the transitions involving the given address",
+                ),
+                get(Self::get_address_history),
+            )
+
+            // GET ../search/..
+            .route(
+                api.get("/testnet3/search/:query", "Returns the block, transaction, or address matching the query"),
+                get(Self::search),
+            )
 
             // GET ../find/..
-            .route("/testnet3/find/blockHash/:tx_id", get(Self::find_block_hash))
-            .route("/testnet3/find/transactionID/deployment/:program_id", get(Self::find_transaction_id_from_program_id))
-            .route("/testnet3/find/transactionID/:transition_id", get(Self::find_transaction_id_from_transition_id))
-            .route("/testnet3/find/transitionID/:input_or_output_id", get(Self::find_transition_id))
+            .route(
+                api.get("/testnet3/find/blockHash/:tx_id", "Returns the block hash of a transaction"),
+                get(Self::find_block_hash),
+            )
+            .route(
+                api.get("/testnet3/find/transactionLocation/:tx_id", "Returns the location of a transaction"),
+                get(Self::find_transaction_location),
+            )
+            .route(
+                api.get("/testnet3/find/transactionID/deployment/:program_id", "Returns the deployment of a program"),
+                get(Self::find_transaction_id_from_program_id),
+            )
+            .route(
+                api.get("/testnet3/find/transactionID/record/:id", "Returns the transaction of a record"),
+                get(Self::find_transaction_id_from_record),
+            )
+            .route(
+                api.get("/testnet3/find/transactionID/:transition_id", "Returns the transaction of a transition"),
+                get(Self::find_transaction_id_from_transition_id),
+            )
+            .route(
+                api.get("/testnet3/find/transitionID/:input_or_output_id", "Returns the transition of an input/output"),
+                get(Self::find_transition_id),
+            )
 
             // GET ../peers/..
-            .route("/testnet3/peers/count", get(Self::get_peers_count))
-            .route("/testnet3/peers/all", get(Self::get_peers_all))
-            .route("/testnet3/peers/all/metrics", get(Self::get_peers_all_metrics))
+            .route(
+                api.get_with::<(), usize>("/testnet3/peers/count", "Returns the number of connected peers"),
+                get(Self::get_peers_count),
+            )
+            .route(
+                api.get_with::<PageQuery, PageResponse<Vec<SocketAddr>>>(
+                    "/testnet3/peers/all",
+                    "Returns the IPs of the connected peers",
+                ),
+                get(Self::get_peers_all),
+            )
+            .route(
+                api.get_with::<PageQuery, PageResponse<Vec<Value>>>(
+                    "/testnet3/peers/all/metrics",
+                    "Returns the IPs and node types of the connected peers",
+                ),
+                get(Self::get_peers_all_metrics),
+            )
+            .route(
+                api.get_with::<PageQuery, PageResponse<Vec<Value>>>(
+                    "/testnet3/peers/all/latency",
+                    "Returns the latencies of the connected peers",
+                ),
+                get(Self::get_peers_all_latency),
+            )
+            .route(
+                api.get_with::<PageQuery, PageResponse<Vec<Value>>>(
+                    "/testnet3/peers/all/info",
+                    "Returns the information about the connected peers",
+                ),
+                get(Self::get_peers_all_info),
+            )
+
+            // GET ../subscribe (WebSocket)
+            .route(
+                api.get("/testnet3/subscribe", "Subscribes to node events (WebSocket)"),
+                get(Self::subscribe),
+            )
+
+            // GET ../sync/..
+            .route(api.get("/testnet3/sync/status", "Returns the sync status of the node"), get(Self::get_sync_status))
 
             // GET ../program/..
-            .route("/testnet3/program/:id", get(Self::get_program))
-            .route("/testnet3/program/:id/mappings", get(Self::get_mapping_names))
-            .route("/testnet3/program/:id/mapping/:name/:key", get(Self::get_mapping_value))
+            .route(api.get("/testnet3/program/:id", "Returns the program with the given ID"), get(Self::get_program))
+            .route(
+                api.get("/testnet3/program/:id/mappings", "Returns the mapping names of the given program"),
+                get(Self::get_mapping_names),
+            )
+            .route(
+                api.get("/testnet3/program/:id/mapping/:name/:key", "Returns a mapping value"),
+                get(Self::get_mapping_value),
+            )
+
+            // GET ../blocks/recent and ../chain/..
+            .route(
+                api.get_with::<Paged<BlockPage>, PageResponse<Vec<BlockSchema>, Value>>(
+                    "/testnet3/blocks/recent",
+                    "Returns a page of the most recent blocks",
+                ),
+                get(Self::get_blocks_recent),
+            )
+            .route(
+                api.get_with::<(), ChainTip>(
+                    "/testnet3/chain/tip",
+                    "Returns the height, hash, and state root of the latest block",
+                ),
+                get(Self::get_chain_tip),
+            )
 
             // GET misc endpoints.
-            .route("/testnet3/blocks", get(Self::get_blocks))
-            .route("/testnet3/height/:hash", get(Self::get_height))
-            .route("/testnet3/memoryPool/transmissions", get(Self::get_memory_pool_transmissions))
-            .route("/testnet3/memoryPool/solutions", get(Self::get_memory_pool_solutions))
-            .route("/testnet3/memoryPool/transactions", get(Self::get_memory_pool_transactions))
-            .route("/testnet3/statePath/:commitment", get(Self::get_state_path_for_commitment))
-            .route("/testnet3/stateRoot/latest", get(Self::get_state_root_latest))
-            .route("/testnet3/committee/latest", get(Self::get_committee_latest))
+            .route(
+                api.get_with::<Paged<BlockRange>, PageResponse<Vec<BlockSchema>>>(
+                    "/testnet3/blocks",
+                    "Returns the blocks in the given range",
+                ),
+                get(Self::get_blocks),
+            )
+            .route(
+                api.get_with::<HeaderRange, Vec<HeaderSchema>>(
+                    "/testnet3/blocks/headers",
+                    "Returns the block headers in the given `range={start}-{end}`",
+                ),
+                get(Self::get_block_headers),
+            )
+            .route(
+                api.get_with::<Paged<BlockRange>, PageResponse<Vec<Value>>>(
+                    "/testnet3/blocks/transactions",
+                    "Returns the transactions of the blocks in the given range",
+                ),
+                get(Self::get_blocks_transactions),
+            )
+            .route(
+                api.get_with::<BlockSubscription, Value>(
+                    "/testnet3/blocks/subscribe",
+                    "Streams the blocks as they are added to the ledger (WebSocket)",
+                ),
+                get(Self::subscribe_blocks),
+            )
+            .route(
+                api.get_with::<(), u32>(
+                    "/testnet3/height/:hash",
+                    "Returns the height of the block with the given hash",
+                ),
+                get(Self::get_height),
+            )
+            .route(
+                api.get_with::<PageQuery, PageResponse<BTreeMap<String, Value>>>(
+                    "/testnet3/memoryPool/transmissions",
+                    "Returns the unconfirmed transmissions",
+                ),
+                get(Self::get_memory_pool_transmissions),
+            )
+            .route(
+                api.get_with::<PageQuery, PageResponse<BTreeMap<String, Value>>>(
+                    "/testnet3/memoryPool/solutions",
+                    "Returns the unconfirmed solutions",
+                ),
+                get(Self::get_memory_pool_solutions),
+            )
+            .route(
+                api.get_with::<PageQuery, PageResponse<BTreeMap<String, TransactionSchema>>>(
+                    "/testnet3/memoryPool/transactions",
+                    "Returns the unconfirmed transactions",
+                ),
+                get(Self::get_memory_pool_transactions),
+            )
+            .route(
+                api.get_with::<Paged<MempoolPage>, PageResponse<Vec<MempoolEntryResponse>, Value>>(
+                    "/testnet3/memoryPool/entries",
+                    "Returns the mempool entries and their fees",
+                ),
+                get(Self::get_memory_pool_entries),
+            )
+            .route(
+                api.get("/testnet3/memoryPool/subscribe", "Streams the mempool events (WebSocket)"),
+                get(Self::subscribe_memory_pool),
+            )
+            .route(
+                api.get_with::<FeeEstimateOptions, FeeEstimateResponse>(
+                    "/testnet3/fee/estimate",
+                    "Returns the estimated priority fee",
+                ),
+                get(Self::get_fee_estimate),
+            )
+            .route(
+                api.get("/testnet3/statePath/:commitment", "Returns the state path of the given commitment"),
+                get(Self::get_state_path_for_commitment),
+            )
+            .route(
+                api.get_with::<(), StateRootSchema>("/testnet3/stateRoot/latest", "Returns the latest state root"),
+                get(Self::get_state_root_latest),
+            )
+            .route(
+                api.get("/testnet3/committee/latest", "Returns the latest committee"),
+                get(Self::get_committee_latest),
+            )
+            .route(api.get("/testnet3/evidence", "Returns the recorded misbehavior evidence"), get(Self::get_evidence))
+            .route(
+                api.get("/testnet3/evidence/:address", "Returns the evidence against a validator"),
+                get(Self::get_evidence_for_address),
+            )
+            .route(
+                api.get("/testnet3/censorship", "Returns the transactions suspected of being censored"),
+                get(Self::get_censorship),
+            )
+
+            // GET ../openapi.json, which describes all the routes above.
+            .route(api.get("/testnet3/openapi.json", "Returns the OpenAPI document of the REST API"), {
+                let openapi = api.to_openapi::<N>();
+                get(move || std::future::ready(Json(openapi.clone())))
+            })
 
             // Pass in `Rest` to make things convenient.
             .with_state(self.clone())
+            // Ensure the callers may call the routes, if the API access is configured.
+            .layer(middleware::from_fn_with_state(self.clone(), access_middleware))
             // Enable tower-http tracing.
             .layer(TraceLayer::new_for_http())
             // Custom logging.
-            .layer(middleware::from_fn(log_middleware))
+            .layer(middleware::from_fn_with_state(self.http.clone(), log_middleware))
             // Enable CORS.
             .layer(cors)
-            // Cap body size at 10MB.
-            .layer(DefaultBodyLimit::max(10 * 1024 * 1024))
+            // Cap the body size, at 10MB by default.
+            .layer(DefaultBodyLimit::max(http_config.max_body_size))
+
+            // Add the health routes, without the layers above, as they are polled frequently by load balancers.
+            .merge(health_router)
         };
 
+        // Serve the routes on the Unix domain socket, if it is enabled.
+        #[cfg(unix)]
+        if let Some(config) = unix_socket {
+            self.spawn_unix_server(config.path, router.clone())?;
+            // If the socket is exclusive, do not listen on the TCP address.
+            if config.exclusive {
+                return Ok(());
+            }
+        }
+
         self.handles.lock().push(tokio::spawn(async move {
             axum::Server::bind(&rest_ip)
                 .serve(router.into_make_service_with_connect_info::<SocketAddr>())
                 .await
                 .expect("couldn't start rest server");
+        }));
+        Ok(())
+    }
+
+    /// Spawns a task that periodically re-broadcasts the submitted transactions, until they are confirmed or expire.
+    fn spawn_rebroadcast(&self) {
+        let rest = self.clone();
+        self.handles.lock().push(tokio::spawn(async move {
+            loop {
+                // Sleep briefly before re-broadcasting the pending transactions.
+                tokio::time::sleep(Duration::from_secs(REBROADCAST_INTERVAL_IN_SECS)).await;
+                // Update the status of the submitted transactions, and retrieve the pending transactions.
+                let latest_height = rest.ledger.latest_height();
+                let pending = rest.submissions.update(latest_height, MAX_REBROADCASTS_PER_INTERVAL, |transaction_id| {
+                    let block_hash = rest.ledger.find_block_hash(transaction_id).ok()??;
+                    rest.ledger.get_height(&block_hash).ok()
+                });
+                // Re-announce the pending transactions.
+                for message in pending {
+                    rest.routing.announce_transaction(message, &[]);
+                }
+            }
         }))
     }
 }
 
 async fn log_middleware<B>(
+    State(http): State<Arc<HttpConfig>>,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
     request: Request<B>,
     next: Next<B>,
@@ -193,7 +605,8 @@ async fn log_middleware<B>(
 where
     B: Send,
 {
-    info!("Received '{} {}' from '{addr}'", request.method(), request.uri());
+    let client_ip = http.client_ip(addr, request.headers());
+    info!("Received '{} {}' from '{client_ip}'", request.method(), request.uri());
 
     Ok(next.run(request).await)
 }
@@ -13,28 +13,195 @@
 // limitations under the License.
 
 use super::*;
-use snarkvm::prelude::{block::Transaction, Identifier, Plaintext};
+use snarkos_node_consensus::{check_transaction_expiration, MAX_TRANSACTION_VALIDITY_IN_BLOCKS};
+use snarkos_node_storage::{AddressActivity, LedgerIterators, MAX_ADDRESS_HISTORY_PAGE_SIZE};
+use snarkvm::prelude::{block::Transaction, Address, Identifier, Plaintext, ToBytes};
 
-use indexmap::IndexMap;
+use axum::{body::StreamBody, http::HeaderMap, response::IntoResponse};
 use rayon::prelude::*;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::ops::Range;
+
+/// The maximum number of unconfirmed transactions that are returned per page of the mempool.
+const MAX_MEMPOOL_PAGE_SIZE: usize = 100;
+/// The maximum number of blocks that are returned per page of blocks.
+const MAX_BLOCK_PAGE_SIZE: u32 = 50;
+/// The maximum number of blocks that a fee estimate may target.
+const MAX_FEE_ESTIMATE_TARGET_BLOCKS: u32 = 100; // blocks
 
 /// The `get_blocks` query object.
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
 pub(crate) struct BlockRange {
-    /// The starting block height (inclusive).
-    start: u32,
-    /// The ending block height (exclusive).
-    end: u32,
+    /// The starting block height (inclusive), which is required unless the range is paged.
+    start: Option<u32>,
+    /// The ending block height (exclusive), which is required unless the range is paged.
+    end: Option<u32>,
+}
+
+impl BlockRange {
+    /// Returns the block heights in the range, up to the given latest block height.
+    ///
+    /// If the range is paged, it defaults to every block height, as each page is bounded. Otherwise,
+    /// the start and the end height are required, and the range is bounded by `MAX_BLOCK_PAGE_SIZE`.
+    fn heights(&self, latest_height: u32, paged: bool) -> Result<Range<u32>, RestError> {
+        if paged {
+            let start_height = self.start.unwrap_or(0);
+            let end_height = self.end.unwrap_or(u32::MAX).min(latest_height.saturating_add(1));
+            return Ok(start_height..end_height.max(start_height));
+        }
+        let (Some(start_height), Some(end_height)) = (self.start, self.end) else {
+            return Err(RestError("The block range requires a start and an end height".to_string()));
+        };
+
+        // Ensure the end height is greater than the start height.
+        if start_height > end_height {
+            return Err(RestError("Invalid block range".to_string()));
+        }
+
+        // Ensure the block range is bounded.
+        if end_height - start_height > MAX_BLOCK_PAGE_SIZE {
+            return Err(RestError(format!(
+                "Cannot request more than {MAX_BLOCK_PAGE_SIZE} blocks per call (requested {})",
+                end_height - start_height
+            )));
+        }
+        Ok(start_height..end_height)
+    }
+}
+
+/// The `get_block_headers` query object.
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub(crate) struct HeaderRange {
+    /// The block range, as `{start_height}-{end_height}`, where the start is inclusive and the end is exclusive.
+    range: String,
+}
+
+impl HeaderRange {
+    /// Returns the block heights in the range.
+    fn heights(&self) -> Result<Range<u32>, RestError> {
+        let invalid = || RestError(format!("Invalid block range '{}', expected '{{start}}-{{end}}'", self.range));
+        let (start, end) = self.range.split_once('-').ok_or_else(invalid)?;
+        let start_height = start.trim().parse::<u32>().map_err(|_| invalid())?;
+        let end_height = end.trim().parse::<u32>().map_err(|_| invalid())?;
+        Ok(start_height..end_height)
+    }
+}
+
+/// The `get_blocks_recent` query object.
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub(crate) struct BlockPage {
+    /// The block height to return the blocks below (exclusive), or the latest block height + 1 if not given.
+    before: Option<u32>,
+}
+
+/// The `get_memory_pool_entries` query object.
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub(crate) struct MempoolPage {
+    /// The number of unconfirmed transactions to skip, unless the entries are paged.
+    offset: Option<usize>,
+}
+
+/// The `get_address_history` query object.
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub(crate) struct AddressHistoryPage {
+    /// The number of transitions to skip, from the most recent, unless the history is paged.
+    offset: Option<usize>,
+}
+
+/// The `get_fee_estimate` query object.
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub(crate) struct FeeEstimateOptions {
+    /// The number of blocks within which the transaction should be included.
+    target_blocks: Option<u32>,
+}
+
+/// The `get_snapshot` query object.
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub(crate) struct SnapshotOptions {
+    /// The height of the trusted checkpoint of the snapshot.
+    height: u32,
+}
+
+/// The `transaction_broadcast` query object.
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub(crate) struct BroadcastOptions {
+    /// The block height after which the transaction may no longer be included in a block, if any.
+    expiration_height: Option<u32>,
 }
 
 /// The `get_mapping_value` query object.
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
 pub(crate) struct Metadata {
     metadata: bool,
 }
 
+/// The `get_chain_tip` response object.
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct ChainTip {
+    /// The height of the latest block.
+    height: u32,
+    /// The hash of the latest block.
+    #[schemars(with = "BlockHashSchema")]
+    hash: String,
+    /// The hash of the block before the latest block.
+    #[schemars(with = "BlockHashSchema")]
+    previous_hash: String,
+    /// The round of the latest block.
+    round: u64,
+    /// The UNIX timestamp of the latest block.
+    timestamp: i64,
+    /// The state root after the latest block.
+    #[schemars(with = "StateRootSchema")]
+    state_root: String,
+}
+
+/// The `get_fee_estimate` response object.
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct FeeEstimateResponse {
+    /// The target number of blocks.
+    target_blocks: u32,
+    /// The estimated priority fee in microcredits.
+    priority_fee: u64,
+    /// The median priority fee of the accepted transactions in the recent blocks.
+    median_priority_fee: u64,
+    /// The number of unconfirmed transactions waiting to be included.
+    backlog: usize,
+    /// The number of recent blocks that were analyzed.
+    num_blocks: usize,
+}
+
+/// An entry of the `get_memory_pool_entries` response.
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct MempoolEntryResponse {
+    /// The ID of the transaction.
+    id: String,
+    /// The status of the transaction, which is `queued` in the mempool, or `ready` to be included in a batch.
+    status: &'static str,
+    /// The fee of the transaction in microcredits, if it is known.
+    fee: Option<u64>,
+    /// The priority fee of the transaction in microcredits, if it is queued.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    priority_fee: Option<u64>,
+    /// The size of the transaction in bytes.
+    size: usize,
+    /// The number of seconds since the transaction was queued, if it is queued.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    age_in_secs: Option<u64>,
+}
+
+/// An entry of the `get_address_history` response.
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct AddressActivityResponse {
+    /// The height of the block of the transition.
+    height: u32,
+    /// The ID of the transition that references the address.
+    transition_id: String,
+    /// The ID of the transaction of the transition.
+    transaction_id: String,
+}
+
 impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     // ----------------- DEPRECATED FUNCTIONS -----------------
     // The functions below are associated with deprecated routes.
@@ -83,8 +250,10 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     }
 
     // GET /testnet3/block/latest
-    pub(crate) async fn get_block_latest(State(rest): State<Self>) -> ErasedJson {
-        ErasedJson::pretty(rest.ledger.latest_block())
+    pub(crate) async fn get_block_latest(State(rest): State<Self>, headers: HeaderMap) -> Response {
+        // Note: The latest block changes with the chain tip, so it must be revalidated.
+        let block = rest.ledger.latest_block();
+        cached_json(&headers, block.hash(), CACHE_CONTROL_REVALIDATE, block)
     }
 
     // GET /testnet3/block/{height}
@@ -92,11 +261,12 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     pub(crate) async fn get_block(
         State(rest): State<Self>,
         Path(height_or_hash): Path<String>,
-    ) -> Result<ErasedJson, RestError> {
+        headers: HeaderMap,
+    ) -> Result<Response, RestError> {
         // Manually parse the height or the height or the hash, axum doesn't support different types
         // for the same path param.
         let block = if let Ok(height) = height_or_hash.parse::<u32>() {
-            rest.ledger.get_block(height)?
+            rest.ledger_cache.get_block(&rest.ledger, height)?
         } else {
             let hash = height_or_hash
                 .parse::<N::BlockHash>()
@@ -105,37 +275,96 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
             rest.ledger.get_block_by_hash(&hash)?
         };
 
-        Ok(ErasedJson::pretty(block))
+        Ok(cached_json(&headers, block.hash(), CACHE_CONTROL_IMMUTABLE, block))
     }
 
     // GET /testnet3/blocks?start={start_height}&end={end_height}
+    // GET /testnet3/blocks?start={start_height}&end={end_height}&cursor={cursor}&limit={limit}
     pub(crate) async fn get_blocks(
         State(rest): State<Self>,
         Query(block_range): Query<BlockRange>,
+        Query(page): Query<PageQuery>,
+    ) -> Result<Response, RestError> {
+        let range = block_range.heights(rest.ledger.latest_height(), page.is_paged())?;
+        let (heights, next_cursor) = page.heights(range, MAX_BLOCK_PAGE_SIZE as usize)?;
+
+        let blocks = cfg_into_iter!(heights)
+            .map(|height| rest.ledger_cache.get_block(&rest.ledger, height))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Page { items: blocks, next_cursor }.respond(&page, ErasedJson::pretty))
+    }
+
+    // GET /testnet3/blocks/recent
+    // GET /testnet3/blocks/recent?before={height}&limit={limit}
+    // GET /testnet3/blocks/recent?cursor={cursor}&limit={limit}
+    pub(crate) async fn get_blocks_recent(
+        State(rest): State<Self>,
+        Query(block_page): Query<BlockPage>,
+        Query(page): Query<PageQuery>,
+    ) -> Result<Response, RestError> {
+        let end = block_page.before.unwrap_or(u32::MAX).min(rest.ledger.latest_height().saturating_add(1));
+        let (heights, next_cursor) = page.heights_before(end, MAX_BLOCK_PAGE_SIZE as usize)?;
+        // Note: The `before` height of the next page is the height of the oldest block of this page.
+        let next = if heights.start > 0 { Some(heights.start) } else { None };
+        // Retrieve the blocks, from the latest to the oldest.
+        let blocks = heights
+            .rev()
+            .map(|height| rest.ledger_cache.get_block(&rest.ledger, height))
+            .collect::<Result<Vec<_>, _>>()?;
+        let page_of_blocks = Page { items: blocks, next_cursor };
+        Ok(page_of_blocks.respond(&page, |blocks| ErasedJson::pretty(json!({ "blocks": blocks, "next": next }))))
+    }
+
+    // GET /testnet3/blocks/headers?range={start_height}-{end_height}
+    pub(crate) async fn get_block_headers(
+        State(rest): State<Self>,
+        Query(header_range): Query<HeaderRange>,
     ) -> Result<ErasedJson, RestError> {
-        let start_height = block_range.start;
-        let end_height = block_range.end;
+        let Range { start: start_height, end: end_height } = header_range.heights()?;
 
-        const MAX_BLOCK_RANGE: u32 = 50;
+        const MAX_HEADER_RANGE: u32 = 1000;
 
         // Ensure the end height is greater than the start height.
         if start_height > end_height {
             return Err(RestError("Invalid block range".to_string()));
         }
 
-        // Ensure the block range is bounded.
-        if end_height - start_height > MAX_BLOCK_RANGE {
+        // Ensure the header range is bounded.
+        if end_height - start_height > MAX_HEADER_RANGE {
             return Err(RestError(format!(
-                "Cannot request more than {MAX_BLOCK_RANGE} blocks per call (requested {})",
+                "Cannot request more than {MAX_HEADER_RANGE} block headers per call (requested {})",
                 end_height - start_height
             )));
         }
 
-        let blocks = cfg_into_iter!((start_height..end_height))
-            .map(|height| rest.ledger.get_block(height))
+        // Retrieve the headers from the header cache, without loading the block bodies.
+        let headers = cfg_into_iter!((start_height..end_height))
+            .map(|height| rest.ledger_cache.get_header(&rest.ledger, height))
             .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(ErasedJson::pretty(blocks))
+        Ok(ErasedJson::pretty(headers))
+    }
+
+    // GET /testnet3/blocks/transactions?start={start_height}&end={end_height}
+    // GET /testnet3/blocks/transactions?start={start_height}&end={end_height}&cursor={cursor}&limit={limit}
+    pub(crate) async fn get_blocks_transactions(
+        State(rest): State<Self>,
+        Query(block_range): Query<BlockRange>,
+        Query(page): Query<PageQuery>,
+    ) -> Result<Response, RestError> {
+        let range = block_range.heights(rest.ledger.latest_height(), page.is_paged())?;
+        // Note: The transactions are paged by block, so the cursor of a page is the height of its last block.
+        let (heights, next_cursor) = page.heights(range, MAX_BLOCK_PAGE_SIZE as usize)?;
+
+        // Retrieve the transactions one block at a time, without loading the blocks.
+        let transactions = rest
+            .ledger
+            .transactions(heights)
+            .map(|result| result.map(|(height, transaction)| json!({ "height": height, "transaction": transaction })))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Page { items: transactions, next_cursor }.respond(&page, ErasedJson::pretty))
     }
 
     // GET /testnet3/height/{blockHash}
@@ -158,8 +387,10 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     pub(crate) async fn get_transaction(
         State(rest): State<Self>,
         Path(tx_id): Path<N::TransactionID>,
-    ) -> Result<ErasedJson, RestError> {
-        Ok(ErasedJson::pretty(rest.ledger.get_transaction(tx_id)?))
+        headers: HeaderMap,
+    ) -> Result<Response, RestError> {
+        let transaction = rest.ledger_cache.get_transaction(&rest.ledger, tx_id)?;
+        Ok(cached_json(&headers, tx_id, CACHE_CONTROL_IMMUTABLE, transaction))
     }
 
     // GET /testnet3/transaction/confirmed/{transactionID}
@@ -171,31 +402,141 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     }
 
     // GET /testnet3/memoryPool/transmissions
-    pub(crate) async fn get_memory_pool_transmissions(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+    // GET /testnet3/memoryPool/transmissions?cursor={cursor}&limit={limit}
+    pub(crate) async fn get_memory_pool_transmissions(
+        State(rest): State<Self>,
+        Query(page): Query<PageQuery>,
+    ) -> Result<Response, RestError> {
         match rest.consensus {
             Some(consensus) => {
-                Ok(ErasedJson::pretty(consensus.unconfirmed_transmissions().collect::<IndexMap<_, _>>()))
+                let transmissions = consensus.unconfirmed_transmissions();
+                let transmissions = Page::new(transmissions, |(id, _)| id.to_string(), &page).into_map();
+                Ok(transmissions.respond(&page, ErasedJson::pretty))
             }
             None => Err(RestError("Route isn't available for this node type".to_string())),
         }
     }
 
     // GET /testnet3/memoryPool/solutions
-    pub(crate) async fn get_memory_pool_solutions(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+    // GET /testnet3/memoryPool/solutions?cursor={cursor}&limit={limit}
+    pub(crate) async fn get_memory_pool_solutions(
+        State(rest): State<Self>,
+        Query(page): Query<PageQuery>,
+    ) -> Result<Response, RestError> {
         match rest.consensus {
-            Some(consensus) => Ok(ErasedJson::pretty(consensus.unconfirmed_solutions().collect::<IndexMap<_, _>>())),
+            Some(consensus) => {
+                let solutions = consensus.unconfirmed_solutions();
+                let solutions = Page::new(solutions, |(id, _)| id.to_string(), &page).into_map();
+                Ok(solutions.respond(&page, ErasedJson::pretty))
+            }
             None => Err(RestError("Route isn't available for this node type".to_string())),
         }
     }
 
     // GET /testnet3/memoryPool/transactions
-    pub(crate) async fn get_memory_pool_transactions(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+    // GET /testnet3/memoryPool/transactions?cursor={cursor}&limit={limit}
+    pub(crate) async fn get_memory_pool_transactions(
+        State(rest): State<Self>,
+        Query(page): Query<PageQuery>,
+    ) -> Result<Response, RestError> {
         match rest.consensus {
-            Some(consensus) => Ok(ErasedJson::pretty(consensus.unconfirmed_transactions().collect::<IndexMap<_, _>>())),
+            Some(consensus) => {
+                let transactions = consensus.unconfirmed_transactions();
+                let transactions = Page::new(transactions, |(id, _)| id.to_string(), &page).into_map();
+                Ok(transactions.respond(&page, ErasedJson::pretty))
+            }
             None => Err(RestError("Route isn't available for this node type".to_string())),
         }
     }
 
+    // GET /testnet3/memoryPool/entries
+    // GET /testnet3/memoryPool/entries?offset={offset}&limit={limit}
+    // GET /testnet3/memoryPool/entries?cursor={cursor}&limit={limit}
+    pub(crate) async fn get_memory_pool_entries(
+        State(rest): State<Self>,
+        Query(mempool_page): Query<MempoolPage>,
+        Query(page): Query<PageQuery>,
+    ) -> Result<Response, RestError> {
+        let Some(consensus) = rest.consensus else {
+            return Err(RestError("Route isn't available for this node type".to_string()));
+        };
+        // Retrieve the transactions queued in the mempool, from the highest priority to the lowest priority.
+        let queued = consensus.mempool().entries().into_iter().map(|entry| MempoolEntryResponse {
+            id: entry.transaction_id.to_string(),
+            status: "queued",
+            fee: Some(entry.fee),
+            priority_fee: Some(entry.priority_fee),
+            size: entry.size_in_bytes,
+            age_in_secs: Some(entry.age.as_secs()),
+        });
+        // Retrieve the transactions that were drained from the mempool, and are ready to be included in a batch.
+        // Note: The fee of a transaction is only available if it was received by this node.
+        let ready = consensus.unconfirmed_transactions().map(|(transaction_id, transaction)| {
+            let (fee, size) = match &transaction {
+                Data::Object(transaction) => {
+                    let size = transaction.to_bytes_le().map_or(0, |bytes| bytes.len());
+                    (transaction.fee_amount().ok().map(|fee| *fee), size)
+                }
+                Data::Buffer(bytes) => (None, bytes.len()),
+            };
+            let id = transaction_id.to_string();
+            MempoolEntryResponse { id, status: "ready", fee, priority_fee: None, size, age_in_secs: None }
+        });
+        // Note: The cursor of a page is the ID of its last transaction, so the pages follow the priority order.
+        if page.is_paged() {
+            let id = |entry: &MempoolEntryResponse| entry.id.clone();
+            let entries = Page::ordered(queued.chain(ready), id, &page, MAX_MEMPOOL_PAGE_SIZE)?;
+            return Ok(ErasedJson::pretty(entries).into_response());
+        }
+        let total = consensus.mempool().len() + consensus.num_unconfirmed_transactions();
+        let offset = mempool_page.offset.unwrap_or(0);
+        let transactions = queued.chain(ready).skip(offset).take(page.limit(MAX_MEMPOOL_PAGE_SIZE)).collect::<Vec<_>>();
+        let entries = json!({ "total": total, "offset": offset, "transactions": transactions });
+        Ok(ErasedJson::pretty(entries).into_response())
+    }
+
+    // GET /testnet3/fee/estimate
+    // GET /testnet3/fee/estimate?target_blocks={blocks}
+    pub(crate) async fn get_fee_estimate(
+        State(rest): State<Self>,
+        Query(options): Query<FeeEstimateOptions>,
+    ) -> Result<ErasedJson, RestError> {
+        let Some(consensus) = rest.consensus else {
+            return Err(RestError("Route isn't available for this node type".to_string()));
+        };
+        // Ensure the target number of blocks is valid.
+        let target_blocks = options.target_blocks.unwrap_or(1);
+        if target_blocks == 0 || target_blocks > MAX_FEE_ESTIMATE_TARGET_BLOCKS {
+            return Err(RestError(format!(
+                "The target number of blocks must be between 1 and {MAX_FEE_ESTIMATE_TARGET_BLOCKS}"
+            )));
+        }
+        // Estimate the priority fee.
+        let estimate = consensus.estimate_fee(target_blocks);
+        Ok(ErasedJson::pretty(FeeEstimateResponse {
+            target_blocks: estimate.target_blocks,
+            priority_fee: estimate.priority_fee,
+            median_priority_fee: estimate.median_priority_fee,
+            backlog: estimate.backlog,
+            num_blocks: estimate.num_blocks,
+        }))
+    }
+
+    // GET /testnet3/chain/tip
+    pub(crate) async fn get_chain_tip(State(rest): State<Self>, headers: HeaderMap) -> Response {
+        // Note: The tip is read from a single block, so its fields are consistent with each other.
+        let block = rest.ledger.latest_block();
+        let tip = ChainTip {
+            height: block.height(),
+            hash: block.hash().to_string(),
+            previous_hash: block.previous_hash().to_string(),
+            round: block.round(),
+            timestamp: block.timestamp(),
+            state_root: rest.ledger.latest_state_root().to_string(),
+        };
+        cached_json(&headers, block.hash(), CACHE_CONTROL_REVALIDATE, tip)
+    }
+
     // GET /testnet3/program/{programID}
     pub(crate) async fn get_program(
         State(rest): State<Self>,
@@ -252,19 +593,77 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         Ok(ErasedJson::pretty(rest.ledger.latest_committee()?))
     }
 
+    // GET /testnet3/evidence
+    pub(crate) async fn get_evidence(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        match rest.consensus {
+            Some(consensus) => Ok(ErasedJson::pretty(consensus.bft().primary().evidence().evidence())),
+            None => Err(RestError("Route isn't available for this node type".to_string())),
+        }
+    }
+
+    // GET /testnet3/evidence/{address}
+    pub(crate) async fn get_evidence_for_address(
+        State(rest): State<Self>,
+        Path(address): Path<Address<N>>,
+    ) -> Result<ErasedJson, RestError> {
+        match rest.consensus {
+            Some(consensus) => {
+                Ok(ErasedJson::pretty(consensus.bft().primary().evidence().get_evidence_for_author(address)))
+            }
+            None => Err(RestError("Route isn't available for this node type".to_string())),
+        }
+    }
+
+    // GET /testnet3/censorship
+    pub(crate) async fn get_censorship(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        match rest.consensus {
+            Some(consensus) => Ok(ErasedJson::pretty(consensus.bft().primary().inclusion().censorship_stats())),
+            None => Err(RestError("Route isn't available for this node type".to_string())),
+        }
+    }
+
     // GET /testnet3/peers/count
     pub(crate) async fn get_peers_count(State(rest): State<Self>) -> ErasedJson {
         ErasedJson::pretty(rest.routing.router().number_of_connected_peers())
     }
 
     // GET /testnet3/peers/all
-    pub(crate) async fn get_peers_all(State(rest): State<Self>) -> ErasedJson {
-        ErasedJson::pretty(rest.routing.router().connected_peers())
+    // GET /testnet3/peers/all?cursor={cursor}&limit={limit}
+    pub(crate) async fn get_peers_all(State(rest): State<Self>, Query(page): Query<PageQuery>) -> Response {
+        let peers = rest.routing.router().connected_peers();
+        Page::new(peers, ToString::to_string, &page).respond(&page, ErasedJson::pretty)
     }
 
     // GET /testnet3/peers/all/metrics
-    pub(crate) async fn get_peers_all_metrics(State(rest): State<Self>) -> ErasedJson {
-        ErasedJson::pretty(rest.routing.router().connected_metrics())
+    // GET /testnet3/peers/all/metrics?cursor={cursor}&limit={limit}
+    pub(crate) async fn get_peers_all_metrics(State(rest): State<Self>, Query(page): Query<PageQuery>) -> Response {
+        let metrics = rest.routing.router().connected_metrics();
+        Page::new(metrics, |(peer_ip, _)| peer_ip.to_string(), &page).respond(&page, ErasedJson::pretty)
+    }
+
+    // GET /testnet3/peers/all/latency
+    // GET /testnet3/peers/all/latency?cursor={cursor}&limit={limit}
+    pub(crate) async fn get_peers_all_latency(State(rest): State<Self>, Query(page): Query<PageQuery>) -> Response {
+        // Retrieve the round-trip time to each connected peer, in milliseconds.
+        let latencies = rest
+            .routing
+            .router()
+            .connected_latencies()
+            .into_iter()
+            .map(|(peer_ip, rtt)| (peer_ip, rtt.map(|rtt| rtt.as_millis() as u64)));
+        Page::new(latencies, |(peer_ip, _)| peer_ip.to_string(), &page).respond(&page, ErasedJson::pretty)
+    }
+
+    // GET /testnet3/peers/all/info
+    // GET /testnet3/peers/all/info?cursor={cursor}&limit={limit}
+    pub(crate) async fn get_peers_all_info(State(rest): State<Self>, Query(page): Query<PageQuery>) -> Response {
+        let info = rest.routing.router().connected_node_info();
+        Page::new(info, |(peer_ip, ..)| peer_ip.to_string(), &page).respond(&page, ErasedJson::pretty)
+    }
+
+    // GET /testnet3/sync/status
+    pub(crate) async fn get_sync_status(State(rest): State<Self>) -> ErasedJson {
+        ErasedJson::pretty(rest.sync.status())
     }
 
     // GET /testnet3/node/address
@@ -272,6 +671,53 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         ErasedJson::pretty(rest.routing.router().address())
     }
 
+    // GET /testnet3/debug/consensusState
+    pub(crate) async fn get_debug_consensus_state(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        match rest.consensus {
+            Some(consensus) => Ok(ErasedJson::pretty(consensus.bft().consensus_snapshot()?)),
+            None => Err(RestError("Route isn't available for this node type".to_string())),
+        }
+    }
+
+    // GET /testnet3/snapshot?height={height}
+    pub(crate) async fn get_snapshot(
+        State(rest): State<Self>,
+        Query(options): Query<SnapshotOptions>,
+    ) -> Result<Response, RestError> {
+        // Export the snapshot archive, without blocking the server.
+        let ledger = rest.ledger.clone();
+        let export =
+            tokio::task::spawn_blocking(move || snarkos_node_storage::export_snapshot(&ledger, options.height));
+        let (_, archive_path) = match export.await {
+            Ok(result) => result?,
+            Err(error) => return Err(RestError(format!("Failed to export the ledger snapshot - {error}"))),
+        };
+        // Open the archive, and remove its path, so that the archive is removed once the stream is dropped.
+        let file = std::fs::File::open(&archive_path).map_err(|error| RestError(error.to_string()))?;
+        if let Err(error) = std::fs::remove_file(&archive_path) {
+            warn!("Failed to remove the ledger snapshot archive - {error}");
+        }
+        // Stream the archive.
+        let stream = tokio_util::io::ReaderStream::new(tokio::fs::File::from_std(file));
+        Ok(([(CONTENT_TYPE, "application/octet-stream")], StreamBody::new(stream)).into_response())
+    }
+
+    // POST /testnet3/node/backup
+    pub(crate) async fn create_backup(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        let Some(backups) = rest.backups.clone() else {
+            return Err(RestError("Ledger backups are not enabled on this node".to_string()));
+        };
+        // Create the backup, without blocking the server.
+        let ledger = rest.ledger.clone();
+        let backup = tokio::task::spawn_blocking(move || {
+            snarkos_node_storage::create_backup(&ledger, backups.backup_dir(), backups.max_backups())
+        });
+        match backup.await {
+            Ok(path) => Ok(ErasedJson::pretty(json!({ "path": path?.display().to_string() }))),
+            Err(error) => Err(RestError(format!("Failed to create the ledger backup - {error}"))),
+        }
+    }
+
     // GET /testnet3/find/blockHash/{transactionID}
     pub(crate) async fn find_block_hash(
         State(rest): State<Self>,
@@ -280,6 +726,126 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         Ok(ErasedJson::pretty(rest.ledger.find_block_hash(&tx_id)?))
     }
 
+    // GET /testnet3/address/{address}/history
+    // GET /testnet3/address/{address}/history?offset={offset}&limit={limit}
+    // GET /testnet3/address/{address}/history?cursor={cursor}&limit={limit}
+    pub(crate) async fn get_address_history(
+        State(rest): State<Self>,
+        Path(address): Path<Address<N>>,
+        Query(history_page): Query<AddressHistoryPage>,
+        Query(page): Query<PageQuery>,
+    ) -> Result<Response, RestError> {
+        let Some(index) = &rest.address_index else {
+            return Err(RestError("The address index is not enabled on this node".to_string()));
+        };
+        let to_response = |activity: &AddressActivity<N>| AddressActivityResponse {
+            height: activity.height,
+            transition_id: activity.transition_id.to_string(),
+            transaction_id: activity.transaction_id.to_string(),
+        };
+        let cursor = |activity: &AddressActivity<N>| format!("{}:{}", activity.height, activity.transition_id);
+
+        // Retrieve the transitions that reference the address after the cursor, from the most recent to the oldest.
+        // Note: The cursor of a page is the height and the transition ID of its last transition.
+        if page.is_paged() {
+            let before = page.cursor().map(Self::parse_history_cursor).transpose()?;
+            let limit = page.limit(MAX_ADDRESS_HISTORY_PAGE_SIZE);
+            // Retrieve one more transition than the limit, to determine whether there is a next page.
+            let mut history = index.history_before(&address, before, limit + 1)?;
+            let next_cursor = match history.len() > limit {
+                true => history.get(limit - 1).map(cursor),
+                false => None,
+            };
+            history.truncate(limit);
+            let items = history.iter().map(to_response).collect::<Vec<_>>();
+            return Ok(ErasedJson::pretty(Page { items, next_cursor }).into_response());
+        }
+
+        // Retrieve the transitions that reference the address after the offset, from the most recent to the oldest.
+        let offset = history_page.offset.unwrap_or(0);
+        let history = index.history(&address, offset, page.limit(MAX_ADDRESS_HISTORY_PAGE_SIZE))?;
+        let history = history.iter().map(to_response).collect::<Vec<_>>();
+        let indexed_height = index.indexed_height()?;
+        let history = json!({ "indexed_height": indexed_height, "offset": offset, "history": history });
+        Ok(ErasedJson::pretty(history).into_response())
+    }
+
+    /// Parses the cursor of a page of the history of an address, as '<height>:<transition ID>'.
+    fn parse_history_cursor(cursor: &str) -> Result<(u32, N::TransitionID), RestError> {
+        let invalid = || RestError(format!("Invalid cursor '{cursor}', expected '<height>:<transition ID>'"));
+        let (height, transition_id) = cursor.split_once(':').ok_or_else(invalid)?;
+        Ok((height.parse().map_err(|_| invalid())?, transition_id.parse().map_err(|_| invalid())?))
+    }
+
+    // GET /testnet3/search/{query}
+    pub(crate) async fn search(State(rest): State<Self>, Path(query): Path<String>) -> Result<Response, RestError> {
+        let query = query.trim();
+        // Detect whether the query is a block height, a block hash, a transaction ID, or an address.
+        // Note: The IDs and addresses are distinguished by their Bech32 prefix.
+        if let Ok(height) = query.parse::<u32>() {
+            if height <= rest.ledger.latest_height() {
+                let block = rest.ledger_cache.get_block(&rest.ledger, height)?;
+                return Ok(ErasedJson::pretty(json!({ "type": "block", "block": block })).into_response());
+            }
+        } else if let Ok(hash) = query.parse::<N::BlockHash>() {
+            if rest.ledger.contains_block_hash(&hash)? {
+                let block = rest.ledger.get_block_by_hash(&hash)?;
+                return Ok(ErasedJson::pretty(json!({ "type": "block", "block": block })).into_response());
+            }
+        } else if let Ok(transaction_id) = query.parse::<N::TransactionID>() {
+            if let Some(block_hash) = rest.ledger.find_block_hash(&transaction_id)? {
+                let height = rest.ledger.get_height(&block_hash)?;
+                let transaction = rest.ledger_cache.get_transaction(&rest.ledger, transaction_id)?;
+                let transaction = json!({ "transaction": transaction, "height": height, "block_hash": block_hash });
+                let result = json!({ "type": "transaction", "transaction": transaction });
+                return Ok(ErasedJson::pretty(result).into_response());
+            }
+        } else if let Ok(address) = query.parse::<Address<N>>() {
+            // Retrieve the most recent activity of the address, if the address index is enabled.
+            let history = match &rest.address_index {
+                Some(index) => Some(
+                    index
+                        .history(&address, 0, MAX_ADDRESS_HISTORY_PAGE_SIZE)?
+                        .into_iter()
+                        .map(|activity| {
+                            json!({
+                                "height": activity.height,
+                                "transition_id": activity.transition_id,
+                                "transaction_id": activity.transaction_id,
+                            })
+                        })
+                        .collect::<Vec<_>>(),
+                ),
+                None => None,
+            };
+            let address = json!({ "address": address, "history": history });
+            return Ok(ErasedJson::pretty(json!({ "type": "address", "address": address })).into_response());
+        }
+        Ok((StatusCode::NOT_FOUND, ErasedJson::pretty(json!({ "type": null, "query": query }))).into_response())
+    }
+
+    // GET /testnet3/find/transactionLocation/{transactionID}
+    pub(crate) async fn find_transaction_location(
+        State(rest): State<Self>,
+        Path(tx_id): Path<N::TransactionID>,
+    ) -> Result<ErasedJson, RestError> {
+        // Look up the location in the address index, if it is enabled and has indexed the transaction.
+        let address_index = rest.address_index.as_ref();
+        let location = address_index.map(|index| index.transaction_location::<N>(&tx_id)).transpose()?.flatten();
+        if let Some(location) = location {
+            let block_hash = rest.ledger.get_hash(location.height)?;
+            let (height, index) = (location.height, location.index);
+            return Ok(ErasedJson::pretty(json!({ "height": height, "block_hash": block_hash, "index": index })));
+        }
+        // Otherwise, look up the block hash of the transaction, and its index in the block, in the ledger.
+        let Some(block_hash) = rest.ledger.find_block_hash(&tx_id)? else {
+            return Ok(ErasedJson::pretty(Option::<()>::None));
+        };
+        let height = rest.ledger.get_height(&block_hash)?;
+        let index = rest.ledger.get_confirmed_transaction(tx_id)?.index();
+        Ok(ErasedJson::pretty(json!({ "height": height, "block_hash": block_hash, "index": index })))
+    }
+
     // GET /testnet3/find/transactionID/deployment/{programID}
     pub(crate) async fn find_transaction_id_from_program_id(
         State(rest): State<Self>,
@@ -296,6 +862,22 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         Ok(ErasedJson::pretty(rest.ledger.find_transaction_id_from_transition_id(&transition_id)?))
     }
 
+    // GET /testnet3/find/transactionID/record/{commitmentOrSerialNumber}
+    pub(crate) async fn find_transaction_id_from_record(
+        State(rest): State<Self>,
+        Path(commitment_or_serial_number): Path<Field<N>>,
+    ) -> Result<ErasedJson, RestError> {
+        // Look up the transaction in the address index, if it is enabled and has indexed the record.
+        if let Some(index) = &rest.address_index {
+            if let Some(transaction_id) = index.find_transaction_id_from_record::<N>(&commitment_or_serial_number)? {
+                return Ok(ErasedJson::pretty(Some(transaction_id)));
+            }
+        }
+        // Note: A record commitment is an output ID, and a serial number is an input ID, of the transition.
+        let transition_id = rest.ledger.find_transition_id(&commitment_or_serial_number)?;
+        Ok(ErasedJson::pretty(rest.ledger.find_transaction_id_from_transition_id(&transition_id)?))
+    }
+
     // GET /testnet3/find/transitionID/{inputOrOutputID}
     pub(crate) async fn find_transition_id(
         State(rest): State<Self>,
@@ -305,26 +887,131 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     }
 
     // POST /testnet3/transaction/broadcast
+    // POST /testnet3/transaction/broadcast?expiration_height={height}
     pub(crate) async fn transaction_broadcast(
         State(rest): State<Self>,
+        Query(options): Query<BroadcastOptions>,
         Json(tx): Json<Transaction<N>>,
     ) -> Result<ErasedJson, RestError> {
-        // If the consensus module is enabled, add the unconfirmed transaction to the memory pool.
-        if let Some(consensus) = rest.consensus {
-            // Add the unconfirmed transaction to the memory pool.
-            consensus.add_unconfirmed_transaction(tx.clone()).await?;
+        Ok(ErasedJson::pretty(rest.broadcast_transaction(tx, options.expiration_height).await?))
+    }
+
+    /// Adds the given transaction to the memory pool, if the consensus module is enabled, and broadcasts it.
+    /// The transaction is tracked, and re-broadcast until it is confirmed or expires.
+    /// Validates the given transaction against the ledger and the memory pool, and returns the reason it is rejected,
+    /// if it is invalid. This includes the verification of its proofs.
+    pub(crate) async fn validate_transaction(
+        &self,
+        tx: &Transaction<N>,
+        expiration_height: Option<u32>,
+    ) -> Result<(), TransactionRejection> {
+        let invalid = |error: anyhow::Error| TransactionRejection::InvalidTransaction(error.to_string());
+        // Ensure the transaction is not a fee transaction.
+        if tx.is_fee() {
+            return Err(TransactionRejection::FeeTransaction);
+        }
+        // Ensure the transaction does not exist in the ledger or the memory pool.
+        if self.ledger.contains_transaction_id(&tx.id()).map_err(invalid)? {
+            return Err(TransactionRejection::AlreadyConfirmed);
+        }
+        if self.consensus.as_ref().map_or(false, |consensus| consensus.mempool().contains(&tx.id())) {
+            return Err(TransactionRejection::AlreadyInMempool);
+        }
+        // Ensure the serial numbers are not spent, and the commitments do not exist, in the ledger.
+        for serial_number in tx.serial_numbers() {
+            if self.ledger.contains_serial_number(serial_number).map_err(invalid)? {
+                return Err(TransactionRejection::SpentSerialNumber(serial_number.to_string()));
+            }
+        }
+        for commitment in tx.commitments() {
+            if self.ledger.contains_commitment(commitment).map_err(invalid)? {
+                return Err(TransactionRejection::ExistingCommitment(commitment.to_string()));
+            }
+        }
+        // Ensure the expiration height is valid, if it is given.
+        if let Some(expiration_height) = expiration_height {
+            let max_validity = self
+                .consensus
+                .as_ref()
+                .map_or(MAX_TRANSACTION_VALIDITY_IN_BLOCKS, |consensus| consensus.max_transaction_validity());
+            check_transaction_expiration(self.ledger.latest_height(), expiration_height, max_validity)
+                .map_err(|error| TransactionRejection::InvalidExpiration(error.to_string()))?;
+        }
+        // Ensure the transaction is well-formed, and its proofs are valid.
+        let ledger = self.ledger.clone();
+        let transaction = tx.clone();
+        tokio::task::spawn_blocking(move || ledger.check_transaction_basic(&transaction, None, &mut rand::thread_rng()))
+            .await
+            .map_err(|error| invalid(error.into()))?
+            .map_err(invalid)
+    }
+
+    pub(crate) async fn broadcast_transaction(
+        &self,
+        tx: Transaction<N>,
+        expiration_height: Option<u32>,
+    ) -> Result<N::TransactionID> {
+        match (&self.consensus, expiration_height) {
+            // If the consensus module is enabled, add the unconfirmed transaction to the memory pool.
+            (Some(consensus), Some(expiration_height)) => {
+                // Add the unconfirmed transaction to the memory pool, with its expiration height.
+                consensus.add_unconfirmed_transaction_with_expiration(tx.clone(), expiration_height).await?;
+            }
+            (Some(consensus), None) => {
+                // Add the unconfirmed transaction to the memory pool.
+                consensus.add_unconfirmed_transaction(tx.clone()).await?;
+            }
+            // Otherwise, ensure the transaction has not expired before broadcasting it.
+            (None, Some(expiration_height)) => {
+                let latest_height = self.ledger.latest_height();
+                check_transaction_expiration(latest_height, expiration_height, MAX_TRANSACTION_VALIDITY_IN_BLOCKS)?;
+            }
+            (None, None) => (),
         }
 
-        // Prepare the unconfirmed transaction message.
+        // Prepare the unconfirmed transaction message, carrying the user-supplied expiration height.
         let tx_id = tx.id();
-        let message = Message::UnconfirmedTransaction(UnconfirmedTransaction {
-            transaction_id: tx_id,
-            transaction: Data::Object(tx),
-        });
+        let message = UnconfirmedTransaction { transaction_id: tx_id, transaction: Data::Object(tx), expiration_height };
+
+        // Track the transaction, to re-broadcast it until it is confirmed or expires.
+        let tracked_height = match expiration_height {
+            Some(expiration_height) => expiration_height,
+            None => self.ledger.latest_height().saturating_add(DEFAULT_SUBMISSION_VALIDITY_IN_BLOCKS),
+        };
+        self.submissions.insert(message.clone(), tracked_height);
+
+        // Announce the transaction.
+        self.routing.announce_transaction(message, &[]);
 
-        // Broadcast the transaction.
-        rest.routing.propagate(message, &[]);
+        Ok(tx_id)
+    }
 
-        Ok(ErasedJson::pretty(tx_id))
+    // GET /testnet3/transaction/status/{transactionID}
+    pub(crate) async fn get_transaction_status(
+        State(rest): State<Self>,
+        Path(tx_id): Path<N::TransactionID>,
+    ) -> Result<ErasedJson, RestError> {
+        match rest.submissions.status(&tx_id) {
+            Some(status) => Ok(ErasedJson::pretty(status)),
+            None => Err(RestError(format!("Transaction '{tx_id}' was not submitted to this node"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_range() {
+        let heights = |range: &str| HeaderRange { range: range.to_string() }.heights().map_err(|error| error.0);
+
+        // Ensure a valid range is parsed, with an exclusive end.
+        assert_eq!(heights("0-1000").unwrap(), 0..1000);
+        assert_eq!(heights("5-5").unwrap(), 5..5);
+        // Ensure a malformed range is rejected.
+        for range in ["", "5", "5-", "-5", "a-b", "5..10", "-1-5"] {
+            assert!(heights(range).is_err(), "The range '{range}' should be rejected");
+        }
     }
 }
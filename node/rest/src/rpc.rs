@@ -0,0 +1,207 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use snarkvm::prelude::block::Transaction;
+
+use axum::{
+    body::Bytes,
+    headers::authorization::{Authorization, Bearer},
+    http::HeaderMap,
+    response::IntoResponse,
+    TypedHeader,
+};
+use serde_json::{json, Value};
+use std::future::Future;
+
+/// Processes the given body of a JSON-RPC request, which is a single request or a batch of requests,
+/// by calling the given function on each request, and returns the response.
+pub(crate) async fn serve_rpc<F, Fut>(body: &[u8], call: F) -> Response
+where
+    F: Fn(Value) -> Fut,
+    Fut: Future<Output = Option<RpcResponse>>,
+{
+    // Parse the body.
+    let requests = match serde_json::from_slice::<Value>(body) {
+        Ok(requests) => requests,
+        Err(error) => return Json(RpcResponse::new(Value::Null, Err(RpcError::parse_error(error)))).into_response(),
+    };
+    match requests {
+        Value::Array(requests) => {
+            // Ensure the batch is not empty, and is bounded.
+            if requests.is_empty() || requests.len() > MAX_RPC_BATCH_SIZE {
+                let error = RpcError::invalid_request(format!(
+                    "A batch must contain between 1 and {MAX_RPC_BATCH_SIZE} requests (found {})",
+                    requests.len()
+                ));
+                return Json(RpcResponse::new(Value::Null, Err(error))).into_response();
+            }
+            // Process the requests in order.
+            let mut responses = Vec::with_capacity(requests.len());
+            for request in requests {
+                responses.extend(call(request).await);
+            }
+            // Note: A batch of notifications is not answered.
+            match responses.is_empty() {
+                true => StatusCode::NO_CONTENT.into_response(),
+                false => Json(responses).into_response(),
+            }
+        }
+        request => match call(request).await {
+            Some(response) => Json(response).into_response(),
+            None => StatusCode::NO_CONTENT.into_response(),
+        },
+    }
+}
+
+impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
+    // POST /testnet3/rpc
+    pub(crate) async fn rpc(
+        State(rest): State<Self>,
+        ConnectInfo(peer_addr): ConnectInfo<SocketAddr>,
+        auth: Option<TypedHeader<Authorization<Bearer>>>,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> Response {
+        // Identify the caller, if the access to the methods is configured.
+        let caller = match &rest.api_access {
+            None => None,
+            Some(access) => {
+                let client_ip = rest.http.client_ip(peer_addr, &headers);
+                match access.caller(client_ip, auth.as_ref().map(|auth| auth.token())) {
+                    Some(caller) => Some(caller),
+                    None => {
+                        let error = RpcError::unauthorized("Unknown API key");
+                        let response = Json(RpcResponse::new(Value::Null, Err(error)));
+                        return (StatusCode::UNAUTHORIZED, response).into_response();
+                    }
+                }
+            }
+        };
+        serve_rpc(&body, |request| rest.rpc_call(request, caller.as_ref())).await
+    }
+
+    /// Processes the given JSON-RPC request of the given caller, and returns its response,
+    /// or `None` if the request is a notification.
+    async fn rpc_call(&self, request: Value, caller: Option<&ApiCaller>) -> Option<RpcResponse> {
+        // Parse the request.
+        let request = match RpcRequest::parse(request) {
+            Ok(request) => request,
+            Err(error) => return Some(RpcResponse::new(Value::Null, Err(error))),
+        };
+        // Call the method.
+        let outcome = match RpcMethod::from_name(&request.method) {
+            Some(method) => match (&self.api_access, caller) {
+                // Ensure the caller may call the method.
+                (Some(access), Some(caller)) => match access.permit(caller, method.name()) {
+                    Ok(()) => self.rpc_dispatch(method, &request).await,
+                    Err(ApiDenial::Unauthorized) => {
+                        Err(RpcError::unauthorized(format!("The method '{}' is not permitted", method.name())))
+                    }
+                    Err(ApiDenial::RateLimited) => Err(RpcError::rate_limited()),
+                },
+                _ => self.rpc_dispatch(method, &request).await,
+            },
+            None => Err(RpcError::method_not_found(&request.method)),
+        };
+        // Note: The outcome of a notification is discarded.
+        request.id.map(|id| RpcResponse::new(id, outcome))
+    }
+
+    /// Calls the given method with the parameters of the given request.
+    async fn rpc_dispatch(&self, method: RpcMethod, request: &RpcRequest) -> Result<Value, RpcError> {
+        let result = match method {
+            RpcMethod::GetBlock => {
+                let hash: N::BlockHash = request.param(0, "hash")?;
+                if !self.ledger.contains_block_hash(&hash)? {
+                    return Err(RpcError::new(RPC_NOT_FOUND, format!("Block '{hash}' does not exist")));
+                }
+                serde_json::to_value(self.ledger.get_block_by_hash(&hash)?)
+            }
+            RpcMethod::GetBlockByHeight => {
+                let height: u32 = request.param(0, "height")?;
+                if height > self.ledger.latest_height() {
+                    return Err(RpcError::new(RPC_NOT_FOUND, format!("Block {height} does not exist")));
+                }
+                serde_json::to_value(self.ledger_cache.get_block(&self.ledger, height)?)
+            }
+            RpcMethod::GetTransaction => {
+                let transaction_id: N::TransactionID = request.param(0, "id")?;
+                if !self.ledger.contains_transaction_id(&transaction_id)? {
+                    return Err(RpcError::new(RPC_NOT_FOUND, format!("Transaction '{transaction_id}' does not exist")));
+                }
+                serde_json::to_value(self.ledger_cache.get_transaction(&self.ledger, transaction_id)?)
+            }
+            RpcMethod::GetPeers => serde_json::to_value(self.routing.router().connected_peers()),
+            RpcMethod::GetNodeInfo => {
+                let router = self.routing.router();
+                Ok(json!({
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "network": N::ID,
+                    "node_type": router.node_type(),
+                    "address": router.address(),
+                    "listener_ip": router.local_ip(),
+                    "latest_height": self.ledger.latest_height(),
+                    "latest_hash": self.ledger.latest_hash(),
+                    "is_synced": self.sync.is_block_synced(),
+                    "num_peers": router.number_of_connected_peers(),
+                }))
+            }
+            RpcMethod::GetCommittee => {
+                let committee = self.ledger.latest_committee()?;
+                // Note: The participation and the rounds are only tracked by the BFT of a validator.
+                let bft = self.consensus.as_ref().map(|consensus| consensus.bft());
+                let members = committee
+                    .members()
+                    .iter()
+                    .map(|(address, (stake, is_open))| {
+                        let participation = bft.map(|bft| bft.primary().participation().rate(*address));
+                        json!({
+                            "address": address,
+                            "stake": stake,
+                            "is_open": is_open,
+                            "participation": participation,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                Ok(json!({
+                    "starting_round": committee.starting_round(),
+                    "total_stake": committee.total_stake(),
+                    "quorum_threshold": committee.quorum_threshold(),
+                    "availability_threshold": committee.availability_threshold(),
+                    "members": members,
+                    "participation_rounds": bft.map(|bft| bft.primary().participation().num_rounds()),
+                    "current_round": bft.map(|bft| bft.storage().current_round()),
+                    "latest_committed_round": bft.map(|bft| bft.last_committed_round()),
+                }))
+            }
+            RpcMethod::SendTransaction => {
+                let transaction: Transaction<N> = request.param(0, "transaction")?;
+                let expiration_height: Option<u32> = request.optional_param(1, "expiration_height")?;
+                // Fully validate the transaction, to report the reason it is rejected, before broadcasting it.
+                if let Err(rejection) = self.validate_transaction(&transaction, expiration_height).await {
+                    return Err(RpcError::transaction_rejected(&rejection));
+                }
+                match self.broadcast_transaction(transaction, expiration_height).await {
+                    Ok(transaction_id) => serde_json::to_value(transaction_id),
+                    Err(error) => {
+                        let rejection = TransactionRejection::MempoolRejected(error.to_string());
+                        return Err(RpcError::transaction_rejected(&rejection));
+                    }
+                }
+            }
+        };
+        result.map_err(RpcError::internal_error)
+    }
+}
@@ -0,0 +1,507 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use snarkos_node_consensus::MempoolEvent;
+use snarkos_node_sync::SyncHealthEvent;
+use snarkvm::prelude::block::Block;
+
+use axum::extract::ws::{Message as WsMessage, WebSocket, WebSocketUpgrade};
+use indexmap::IndexMap;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::str::FromStr;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+/// The maximum number of subscriptions of a single connection.
+pub const MAX_SUBSCRIPTIONS_PER_CONNECTION: usize = 16;
+/// The maximum number of blocks that are replayed to a blocks subscription from storage.
+const MAX_BLOCK_REPLAY: u32 = 500;
+/// The maximum time in seconds to send a message to a subscriber, after which the subscriber is disconnected.
+const SUBSCRIPTION_SEND_TIMEOUT_IN_SECS: u64 = 10; // 10 seconds
+
+/// The topics that a connection may subscribe to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Topic {
+    /// The blocks added to the ledger.
+    Blocks,
+    /// The transactions confirmed in the blocks added to the ledger.
+    Transactions,
+    /// The transactions added to, and removed from, the mempool.
+    Mempool,
+    /// The changes in the status of the transactions submitted through this node.
+    Submissions,
+    /// The changes in the sync status of the node.
+    Status,
+}
+
+/// The topics that are driven by the blocks added to the ledger.
+const BLOCK_TOPICS: [Topic; 3] = [Topic::Blocks, Topic::Transactions, Topic::Status];
+
+/// The `subscribe_blocks` query object.
+#[derive(Deserialize, Serialize, JsonSchema)]
+pub(crate) struct BlockSubscription {
+    /// The block height to start streaming from (inclusive), if the subscriber is resuming.
+    from_height: Option<u32>,
+}
+
+/// A request from a subscriber.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SubscriptionRequest {
+    /// Subscribes to the given topic, with the transactions filtered by the given program ID, if any,
+    /// and the blocks replayed from the given block height, if any.
+    Subscribe { subscribe: Topic, program_id: Option<String>, from_height: Option<u32> },
+    /// Removes the subscription with the given ID.
+    Unsubscribe { unsubscribe: u32 },
+}
+
+/// A subscription of a subscriber connection.
+struct Subscription<N: Network> {
+    /// The topic of the subscription.
+    topic: Topic,
+    /// The program ID that filters the transactions, if any.
+    program_id: Option<ProgramID<N>>,
+    /// The height of the next block to send, if the topic is the blocks.
+    next_height: u32,
+}
+
+/// The shape of the messages that are sent to a subscriber connection.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Framing {
+    /// Each message is wrapped with its subscription ID and topic, and the requests are answered.
+    Envelope,
+    /// Only the data of the messages is sent, and the requests are ignored, as by the single-topic endpoints.
+    Bare,
+}
+
+/// The state of a subscriber connection.
+struct Subscriber<N: Network> {
+    /// The map of subscription IDs to their subscription.
+    subscriptions: IndexMap<u32, Subscription<N>>,
+    /// The ID of the next subscription.
+    next_id: u32,
+    /// The receiver of the blocks, if the connection is subscribed to the blocks, transactions, or status.
+    blocks: Option<broadcast::Receiver<Arc<Block<N>>>>,
+    /// The receiver of the mempool events, if the connection is subscribed to the mempool.
+    mempool: Option<broadcast::Receiver<MempoolEvent<N>>>,
+    /// The receiver of the submission events, if the connection is subscribed to the submissions.
+    submissions: Option<broadcast::Receiver<SubmissionEvent<N>>>,
+    /// The receiver of the sync health events, if the connection is subscribed to the status.
+    health: Option<broadcast::Receiver<SyncHealthEvent>>,
+    /// The last sync status that was sent to the status subscriptions.
+    is_synced: bool,
+}
+
+impl<N: Network> Subscriber<N> {
+    /// Returns the subscriptions to the given topic.
+    fn subscriptions(&self, topic: Topic) -> impl '_ + Iterator<Item = (u32, Option<&ProgramID<N>>)> {
+        self.subscriptions
+            .iter()
+            .filter(move |(_, subscription)| subscription.topic == topic)
+            .map(|(id, subscription)| (*id, subscription.program_id.as_ref()))
+    }
+
+    /// Returns `true` if the connection is subscribed to the given topic.
+    fn is_subscribed(&self, topic: Topic) -> bool {
+        self.subscriptions(topic).next().is_some()
+    }
+}
+
+impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
+    // GET /testnet3/subscribe
+    pub(crate) async fn subscribe(State(rest): State<Self>, ws: WebSocketUpgrade) -> Response {
+        let subscriber = rest.new_subscriber();
+        ws.on_upgrade(move |socket| rest.serve_subscriber(socket, subscriber, Framing::Envelope))
+    }
+
+    // GET /testnet3/blocks/subscribe
+    // GET /testnet3/blocks/subscribe?from_height={height}
+    pub(crate) async fn subscribe_blocks(
+        State(rest): State<Self>,
+        Query(subscription): Query<BlockSubscription>,
+        ws: WebSocketUpgrade,
+    ) -> Result<Response, RestError> {
+        // Subscribe to the blocks topic before the upgrade, so that an invalid starting height is rejected.
+        let mut subscriber = rest.new_subscriber();
+        rest.subscribe_topic(&mut subscriber, Topic::Blocks, None, subscription.from_height).map_err(RestError)?;
+        Ok(ws.on_upgrade(move |socket| rest.serve_subscriber(socket, subscriber, Framing::Bare)))
+    }
+
+    // GET /testnet3/memoryPool/subscribe
+    pub(crate) async fn subscribe_memory_pool(
+        State(rest): State<Self>,
+        ws: WebSocketUpgrade,
+    ) -> Result<Response, RestError> {
+        // Subscribe to the mempool topic before the upgrade, so that no events are missed.
+        let mut subscriber = rest.new_subscriber();
+        rest.subscribe_topic(&mut subscriber, Topic::Mempool, None, None).map_err(RestError)?;
+        Ok(ws.on_upgrade(move |socket| rest.serve_subscriber(socket, subscriber, Framing::Bare)))
+    }
+
+    /// Returns the state of a new subscriber connection, without any subscriptions.
+    fn new_subscriber(&self) -> Subscriber<N> {
+        Subscriber {
+            subscriptions: Default::default(),
+            next_id: 0,
+            blocks: None,
+            mempool: None,
+            submissions: None,
+            health: None,
+            is_synced: self.sync.is_block_synced(),
+        }
+    }
+
+    /// Serves the subscriptions of the given connection, until it disconnects.
+    ///
+    /// Each event bus buffers a bounded number of events per subscriber. While a slow subscriber is sent a message,
+    /// its events are buffered, and once the buffer is full, the oldest events are skipped and the subscriber
+    /// is notified of the number of skipped events. A subscriber that does not receive a message in time is dropped.
+    ///
+    /// The blocks subscriptions are never notified of skipped blocks, as they are sent the missing blocks from
+    /// storage, one block at a time, before any new event.
+    async fn serve_subscriber(self, mut socket: WebSocket, mut subscriber: Subscriber<N>, framing: Framing) {
+        loop {
+            let messages = match self.replay_messages(&mut subscriber) {
+                Some(messages) => messages,
+                None => tokio::select! {
+                    request = socket.recv() => match request {
+                        Some(Ok(WsMessage::Text(request))) if framing == Framing::Envelope => {
+                            vec![self.process_subscription_request(&mut subscriber, &request)]
+                        }
+                        Some(Ok(WsMessage::Close(_))) | Some(Err(_)) | None => return,
+                        // Note: The pings are answered by the WebSocket implementation.
+                        Some(Ok(_)) => continue,
+                    },
+                    block = recv_event(&mut subscriber.blocks) => match block {
+                        Ok(block) => self.block_messages(&mut subscriber, &block),
+                        Err(error) => lagged_messages(&subscriber, &[Topic::Transactions, Topic::Status], error),
+                    },
+                    event = recv_event(&mut subscriber.mempool) => match event {
+                        Ok(event) => mempool_messages(&subscriber, event),
+                        Err(error) => lagged_messages(&subscriber, &[Topic::Mempool], error),
+                    },
+                    event = recv_event(&mut subscriber.submissions) => match event {
+                        Ok(event) => submission_messages(&subscriber, event),
+                        Err(error) => lagged_messages(&subscriber, &[Topic::Submissions], error),
+                    },
+                    event = recv_event(&mut subscriber.health) => match event {
+                        Ok(event) => status_messages(&subscriber, health_status(event)),
+                        Err(error) => lagged_messages(&subscriber, &[Topic::Status], error),
+                    },
+                },
+            };
+            // Send the messages, and drop the subscriber if it has disconnected or is too slow.
+            let messages = messages.into_iter().filter_map(|message| match framing {
+                Framing::Envelope => Some(message),
+                Framing::Bare => bare_message(message),
+            });
+            for message in messages {
+                let send = socket.send(WsMessage::Text(message.to_string()));
+                match tokio::time::timeout(Duration::from_secs(SUBSCRIPTION_SEND_TIMEOUT_IN_SECS), send).await {
+                    Ok(Ok(())) => (),
+                    Ok(Err(_)) => return,
+                    Err(_) => {
+                        debug!("Dropping a subscriber that did not receive a message in time");
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Processes the given request of the subscriber, and returns the reply.
+    fn process_subscription_request(&self, subscriber: &mut Subscriber<N>, request: &str) -> Value {
+        match serde_json::from_str::<SubscriptionRequest>(request) {
+            Ok(SubscriptionRequest::Subscribe { subscribe: topic, program_id, from_height }) => {
+                // Parse the program ID filter, which only applies to the transactions.
+                let program_id = match (topic, program_id) {
+                    (Topic::Transactions, Some(program_id)) => match ProgramID::from_str(&program_id) {
+                        Ok(program_id) => Some(program_id),
+                        Err(error) => return json!({ "error": format!("Invalid program ID - {error}") }),
+                    },
+                    (_, Some(_)) => return json!({ "error": "Only the transactions can be filtered by program ID" }),
+                    (_, None) => None,
+                };
+                match self.subscribe_topic(subscriber, topic, program_id, from_height) {
+                    Ok(id) => json!({ "subscribed": id, "topic": topic }),
+                    Err(error) => json!({ "error": error }),
+                }
+            }
+            Ok(SubscriptionRequest::Unsubscribe { unsubscribe: id }) => {
+                if subscriber.subscriptions.shift_remove(&id).is_none() {
+                    return json!({ "error": format!("Subscription {id} does not exist") });
+                }
+                // Unsubscribe from the event buses that are no longer needed.
+                if !BLOCK_TOPICS.iter().any(|topic| subscriber.is_subscribed(*topic)) {
+                    subscriber.blocks = None;
+                }
+                if !subscriber.is_subscribed(Topic::Mempool) {
+                    subscriber.mempool = None;
+                }
+                if !subscriber.is_subscribed(Topic::Submissions) {
+                    subscriber.submissions = None;
+                }
+                if !subscriber.is_subscribed(Topic::Status) {
+                    subscriber.health = None;
+                }
+                json!({ "unsubscribed": id })
+            }
+            Err(_) => json!({
+                "error": "Expected '{\"subscribe\": <topic>}' or '{\"unsubscribe\": <id>}', \
+                          where the topic is 'blocks', 'transactions', 'mempool', 'submissions', or 'status'"
+            }),
+        }
+    }
+
+    /// Subscribes the subscriber to the given topic, and returns the ID of the subscription.
+    ///
+    /// The transactions are filtered by the given program ID, if any. The blocks are replayed from storage from the
+    /// given block height, if any, or otherwise start from the next block.
+    fn subscribe_topic(
+        &self,
+        subscriber: &mut Subscriber<N>,
+        topic: Topic,
+        program_id: Option<ProgramID<N>>,
+        from_height: Option<u32>,
+    ) -> Result<u32, String> {
+        // Ensure the number of subscriptions is bounded.
+        if subscriber.subscriptions.len() >= MAX_SUBSCRIPTIONS_PER_CONNECTION {
+            return Err(format!("Cannot exceed {MAX_SUBSCRIPTIONS_PER_CONNECTION} subscriptions"));
+        }
+        // Subscribe to the event bus of the topic, if the connection is not subscribed to it yet.
+        // Note: The event bus is subscribed to before the latest block height is read, so that no blocks are missed.
+        match topic {
+            Topic::Blocks | Topic::Transactions | Topic::Status => {
+                if subscriber.blocks.is_none() {
+                    subscriber.blocks = Some(self.block_events.subscribe());
+                }
+                if topic == Topic::Status && subscriber.health.is_none() {
+                    subscriber.health = Some(self.sync.subscribe_health());
+                }
+            }
+            Topic::Mempool => {
+                if subscriber.mempool.is_none() {
+                    let Some(consensus) = &self.consensus else {
+                        return Err("The mempool is not available on this node".to_string());
+                    };
+                    subscriber.mempool = Some(consensus.subscribe_mempool());
+                }
+            }
+            Topic::Submissions => {
+                if subscriber.submissions.is_none() {
+                    subscriber.submissions = Some(self.submissions.subscribe());
+                }
+            }
+        }
+        // Determine the block height to start streaming the blocks from.
+        let latest_height = self.ledger.latest_height();
+        let next_height = match (topic, from_height) {
+            (Topic::Blocks, Some(from_height)) => {
+                // Ensure the starting height is not beyond the next block.
+                if from_height > latest_height.saturating_add(1) {
+                    return Err(format!(
+                        "Cannot subscribe from block {from_height} (the latest block is {latest_height})"
+                    ));
+                }
+                // Ensure the missed blocks are within the replay window.
+                if latest_height.saturating_sub(from_height) > MAX_BLOCK_REPLAY {
+                    return Err(format!(
+                        "Cannot replay more than {MAX_BLOCK_REPLAY} blocks (use '/testnet3/blocks' to catch up)"
+                    ));
+                }
+                from_height
+            }
+            (_, Some(_)) => return Err("Only the blocks can be replayed from a block height".to_string()),
+            // By default, only stream new blocks.
+            (_, None) => latest_height.saturating_add(1),
+        };
+        // Register the subscription.
+        let id = subscriber.next_id;
+        subscriber.next_id = subscriber.next_id.wrapping_add(1);
+        subscriber.subscriptions.insert(id, Subscription { topic, program_id, next_height });
+        Ok(id)
+    }
+
+    /// Returns the messages of the next block from storage that a blocks subscription has not been sent yet, or
+    /// `None` if every blocks subscription is up to date with the ledger.
+    ///
+    /// A blocks subscription misses the blocks before the block height it subscribed from, and the blocks that were
+    /// skipped while it lagged behind the block events.
+    fn replay_messages(&self, subscriber: &mut Subscriber<N>) -> Option<Vec<Value>> {
+        let latest_height = self.ledger.latest_height();
+        let height = subscriber
+            .subscriptions
+            .values()
+            .filter(|subscription| subscription.topic == Topic::Blocks)
+            .map(|subscription| subscription.next_height)
+            .min()
+            .filter(|height| *height <= latest_height)?;
+        // Note: If the block cannot be retrieved, it is skipped, so that the subscriptions are not stalled.
+        let block = match self.ledger_cache.get_block(&self.ledger, height) {
+            Ok(block) => Some(block),
+            Err(error) => {
+                warn!("Failed to retrieve block {height} for a blocks subscription - {error}");
+                None
+            }
+        };
+        let mut messages = Vec::new();
+        for (id, subscription) in subscriber.subscriptions.iter_mut() {
+            if subscription.topic == Topic::Blocks && subscription.next_height == height {
+                subscription.next_height = height.saturating_add(1);
+                messages.push(match &block {
+                    Some(block) => json!({ "subscription": id, "topic": Topic::Blocks, "data": block }),
+                    None => json!({ "subscription": id, "topic": Topic::Blocks, "missed": height }),
+                });
+            }
+        }
+        Some(messages)
+    }
+
+    /// Returns the messages of the given block, for the blocks, transactions, and status subscriptions.
+    fn block_messages(&self, subscriber: &mut Subscriber<N>, block: &Block<N>) -> Vec<Value> {
+        let mut messages = Vec::new();
+        // Send the block to the block subscriptions that are up to date with the ledger.
+        // Note: The other blocks subscriptions were sent this block from storage, or will be once they catch up.
+        for (id, subscription) in subscriber.subscriptions.iter_mut() {
+            if subscription.topic == Topic::Blocks && subscription.next_height == block.height() {
+                subscription.next_height = block.height().saturating_add(1);
+                messages.push(json!({ "subscription": id, "topic": Topic::Blocks, "data": block }));
+            }
+        }
+        // Send the confirmed transactions to the transaction subscriptions, that match their program ID filter.
+        for (id, program_id) in subscriber.subscriptions(Topic::Transactions) {
+            for confirmed in block.transactions().iter() {
+                let transaction = confirmed.transaction();
+                if program_id.map_or(true, |program_id| transaction.transitions().any(|t| t.program_id() == program_id))
+                {
+                    let data = json!({
+                        "id": confirmed.to_unconfirmed_transaction_id().ok(),
+                        "status": confirmed.variant(),
+                        "height": block.height(),
+                        "block_hash": block.hash(),
+                    });
+                    messages.push(json!({ "subscription": id, "topic": Topic::Transactions, "data": data }));
+                }
+            }
+        }
+        // Send the sync status to the status subscriptions, if it changed.
+        let is_synced = self.sync.is_block_synced();
+        if is_synced != subscriber.is_synced {
+            subscriber.is_synced = is_synced;
+            let status = json!({ "event": if is_synced { "synced" } else { "syncing" }, "height": block.height() });
+            messages.extend(status_messages(subscriber, status));
+        }
+        messages
+    }
+}
+
+/// Receives the next event from the given event bus, or waits forever if the connection is not subscribed to it.
+/// If the event bus is closed, the connection is unsubscribed from it.
+async fn recv_event<T: Clone>(receiver: &mut Option<broadcast::Receiver<T>>) -> Result<T, RecvError> {
+    if let Some(bus) = receiver {
+        match bus.recv().await {
+            Err(RecvError::Closed) => *receiver = None,
+            result => return result,
+        }
+    }
+    std::future::pending().await
+}
+
+/// Returns the messages of the given mempool event, for the mempool subscriptions.
+fn mempool_messages<N: Network>(subscriber: &Subscriber<N>, event: MempoolEvent<N>) -> Vec<Value> {
+    let data = match event {
+        MempoolEvent::Added { transaction_id } => json!({ "event": "added", "id": transaction_id }),
+        MempoolEvent::Removed { transaction_id, reason } => {
+            json!({ "event": "removed", "id": transaction_id, "reason": reason })
+        }
+        MempoolEvent::Confirmed { transaction_id, height } => {
+            json!({ "event": "confirmed", "id": transaction_id, "height": height })
+        }
+    };
+    subscriber
+        .subscriptions(Topic::Mempool)
+        .map(|(id, _)| json!({ "subscription": id, "topic": Topic::Mempool, "data": data }))
+        .collect()
+}
+
+/// Returns the given message as sent by the single-topic endpoints, which send the data of their only subscription
+/// without its envelope, or `None` if the message has no data.
+///
+/// Note: The notification of skipped events keeps the shape of the `/testnet3/memoryPool/subscribe` endpoint.
+fn bare_message(mut message: Value) -> Option<Value> {
+    if let Some(num_skipped) = message.get("lagged") {
+        return Some(json!({ "event": "lagged", "skipped": num_skipped }));
+    }
+    message.get_mut("data").map(Value::take)
+}
+
+/// Returns the messages of the given submission event, for the submissions subscriptions.
+fn submission_messages<N: Network>(subscriber: &Subscriber<N>, event: SubmissionEvent<N>) -> Vec<Value> {
+    let data = json!({ "id": event.transaction_id, "status": event.status });
+    subscriber
+        .subscriptions(Topic::Submissions)
+        .map(|(id, _)| json!({ "subscription": id, "topic": Topic::Submissions, "data": data }))
+        .collect()
+}
+
+/// Returns the given status of the node, for the status subscriptions.
+fn status_messages<N: Network>(subscriber: &Subscriber<N>, status: Value) -> Vec<Value> {
+    subscriber
+        .subscriptions(Topic::Status)
+        .map(|(id, _)| json!({ "subscription": id, "topic": Topic::Status, "data": status }))
+        .collect()
+}
+
+/// Returns the status of the node for the given sync health event.
+fn health_status(event: SyncHealthEvent) -> Value {
+    match event {
+        SyncHealthEvent::Stalled { height, stalled_for_secs, rotated_peers } => json!({
+            "event": "stalled",
+            "height": height,
+            "stalled_for_secs": stalled_for_secs,
+            "rotated_peers": rotated_peers,
+        }),
+        SyncHealthEvent::Recovered { height } => json!({ "event": "recovered", "height": height }),
+    }
+}
+
+/// Returns the messages that notify the subscriptions to the given topics that they skipped events.
+fn lagged_messages<N: Network>(subscriber: &Subscriber<N>, topics: &[Topic], error: RecvError) -> Vec<Value> {
+    // Note: A closed event bus is never returned by `recv_event`.
+    let RecvError::Lagged(num_skipped) = error else { return vec![] };
+    topics
+        .iter()
+        .flat_map(|topic| subscriber.subscriptions(*topic).map(move |(id, _)| (id, topic)))
+        .map(|(id, topic)| json!({ "subscription": id, "topic": topic, "lagged": num_skipped }))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bare_message() {
+        // Ensure the data is sent without its envelope, and the replies are not sent.
+        let message = json!({ "subscription": 0, "topic": Topic::Mempool, "data": { "event": "added", "id": "at1" } });
+        assert_eq!(bare_message(message), Some(json!({ "event": "added", "id": "at1" })));
+        assert_eq!(bare_message(json!({ "subscribed": 0, "topic": Topic::Blocks })), None);
+        // Ensure the skipped events are notified in the shape of the mempool endpoint.
+        let message = json!({ "subscription": 0, "topic": Topic::Mempool, "lagged": 3 });
+        assert_eq!(bare_message(message), Some(json!({ "event": "lagged", "skipped": 3 })));
+    }
+}
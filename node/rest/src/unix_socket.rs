@@ -0,0 +1,69 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use anyhow::Context;
+use axum::Extension;
+use std::{
+    net::Ipv4Addr,
+    os::unix::fs::{FileTypeExt, PermissionsExt},
+    path::PathBuf,
+};
+use tokio::net::UnixListener;
+
+/// The permissions of the Unix domain socket, which only allow the user running the node to connect.
+const UNIX_SOCKET_MODE: u32 = 0o600;
+
+/// The Unix domain socket configuration of the REST server.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnixSocketConfig {
+    /// The path of the socket.
+    pub path: PathBuf,
+    /// Whether the REST server only listens on the socket, and not on its TCP address.
+    pub exclusive: bool,
+}
+
+/// Binds the Unix domain socket at the given path, replacing a stale socket, and restricts its permissions.
+fn bind_unix_socket(path: &PathBuf) -> Result<UnixListener> {
+    // Remove the socket of a previous run, if it exists.
+    if let Ok(metadata) = std::fs::symlink_metadata(path) {
+        anyhow::ensure!(metadata.file_type().is_socket(), "'{}' exists and is not a socket", path.display());
+        std::fs::remove_file(path)?;
+    }
+    let listener = UnixListener::bind(path)?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(UNIX_SOCKET_MODE))?;
+    Ok(listener)
+}
+
+impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
+    /// Serves the given routes on the Unix domain socket at the given path.
+    pub(crate) fn spawn_unix_server(&self, path: PathBuf, router: axum::Router) -> Result<()> {
+        // Note: The clients of the socket are local, so they are treated as connecting from the loopback address.
+        let router = router.layer(Extension(ConnectInfo(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)))));
+        // Bind the socket before spawning the server, so that a failure is returned to the node.
+        let listener = bind_unix_socket(&path)
+            .with_context(|| format!("Failed to bind the REST server to '{}'", path.display()))?;
+        self.handles.lock().push(tokio::spawn(async move {
+            info!("Serving the REST API on the Unix domain socket at '{}'", path.display());
+            let accept = hyper::server::accept::poll_fn(move |cx| {
+                listener.poll_accept(cx).map(|result| Some(result.map(|(stream, _)| stream)))
+            });
+            if let Err(error) = axum::Server::builder(accept).serve(router.into_make_service()).await {
+                error!("The REST server on '{}' failed - {error}", path.display());
+            }
+        }));
+        Ok(())
+    }
+}
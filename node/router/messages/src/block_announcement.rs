@@ -0,0 +1,85 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use snarkvm::prelude::{FromBytes, ToBytes};
+
+use std::borrow::Cow;
+
+/// The announcement of a new block, sent by a node once it has advanced its ledger to the block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockAnnouncement<N: Network> {
+    /// The height of the block.
+    pub height: u32,
+    /// The hash of the block.
+    pub block_hash: N::BlockHash,
+}
+
+impl<N: Network> MessageTrait for BlockAnnouncement<N> {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        format!("BlockAnnouncement {}", self.height).into()
+    }
+}
+
+impl<N: Network> ToBytes for BlockAnnouncement<N> {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        self.height.write_le(&mut writer)?;
+        self.block_hash.write_le(&mut writer)?;
+        Ok(())
+    }
+}
+
+impl<N: Network> FromBytes for BlockAnnouncement<N> {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let height = u32::read_le(&mut reader)?;
+        let block_hash = N::BlockHash::read_le(&mut reader)?;
+        Ok(Self { height, block_hash })
+    }
+}
+
+#[cfg(test)]
+pub mod prop_tests {
+    use crate::BlockAnnouncement;
+    use snarkvm::{
+        prelude::{Field, Network, Uniform},
+        utilities::{FromBytes, ToBytes},
+    };
+
+    use bytes::{Buf, BufMut, BytesMut};
+    use proptest::prelude::{any, BoxedStrategy, Just, Strategy};
+    use test_strategy::proptest;
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    pub fn any_block_hash() -> BoxedStrategy<<CurrentNetwork as Network>::BlockHash> {
+        Just(0).prop_perturb(|_, mut rng| <CurrentNetwork as Network>::BlockHash::from(Field::rand(&mut rng))).boxed()
+    }
+
+    pub fn any_block_announcement() -> BoxedStrategy<BlockAnnouncement<CurrentNetwork>> {
+        (any::<u32>(), any_block_hash())
+            .prop_map(|(height, block_hash)| BlockAnnouncement { height, block_hash })
+            .boxed()
+    }
+
+    #[proptest]
+    fn block_announcement_roundtrip(#[strategy(any_block_announcement())] original: BlockAnnouncement<CurrentNetwork>) {
+        let mut buf = BytesMut::default().writer();
+        original.write_le(&mut buf).unwrap();
+        let deserialized = BlockAnnouncement::read_le(buf.into_inner().reader()).unwrap();
+        assert_eq!(original, deserialized);
+    }
+}
@@ -0,0 +1,113 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use snarkvm::prelude::{FromBytes, ToBytes};
+
+use std::borrow::Cow;
+
+/// The response to a `BlockHeadersRequest`, with the previous block hash and the header of each requested block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockHeadersResponse<N: Network> {
+    /// The original block headers request.
+    pub request: BlockHeadersRequest,
+    /// The previous block hash and the header of each block.
+    pub headers: Vec<(N::BlockHash, Header<N>)>,
+}
+
+impl<N: Network> BlockHeadersResponse<N> {
+    /// The maximum number of headers that can be sent in a single message.
+    pub const MAXIMUM_NUMBER_OF_HEADERS: usize = 1000;
+}
+
+impl<N: Network> MessageTrait for BlockHeadersResponse<N> {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        format!("BlockHeadersResponse {}..{}", self.request.start_height, self.request.end_height).into()
+    }
+}
+
+impl<N: Network> ToBytes for BlockHeadersResponse<N> {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        // Ensure the number of headers is within bounds.
+        if self.headers.len() > Self::MAXIMUM_NUMBER_OF_HEADERS {
+            return Err(error("Block headers response exceeds maximum number of headers"));
+        }
+        self.request.write_le(&mut writer)?;
+        (self.headers.len() as u16).write_le(&mut writer)?;
+        for (previous_hash, header) in &self.headers {
+            previous_hash.write_le(&mut writer)?;
+            header.write_le(&mut writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<N: Network> FromBytes for BlockHeadersResponse<N> {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let request = BlockHeadersRequest::read_le(&mut reader)?;
+        let num_headers = u16::read_le(&mut reader)? as usize;
+        // Ensure the number of headers is within bounds.
+        if num_headers > Self::MAXIMUM_NUMBER_OF_HEADERS {
+            return Err(error("Block headers response exceeds maximum number of headers"));
+        }
+        let mut headers = Vec::with_capacity(num_headers);
+        for _ in 0..num_headers {
+            let previous_hash = N::BlockHash::read_le(&mut reader)?;
+            let header = Header::read_le(&mut reader)?;
+            headers.push((previous_hash, header));
+        }
+        Ok(Self { request, headers })
+    }
+}
+
+#[cfg(test)]
+pub mod prop_tests {
+    use crate::{
+        block_headers_request::prop_tests::any_block_headers_request,
+        block_response::prop_tests::any_block,
+        BlockHeadersResponse,
+    };
+    use snarkvm::utilities::{FromBytes, ToBytes};
+
+    use bytes::{Buf, BufMut, BytesMut};
+    use proptest::{
+        collection::vec,
+        prelude::{BoxedStrategy, Strategy},
+    };
+    use test_strategy::proptest;
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    pub fn any_block_headers_response() -> BoxedStrategy<BlockHeadersResponse<CurrentNetwork>> {
+        (any_block_headers_request(), vec(any_block(), 0..=2))
+            .prop_map(|(request, blocks)| BlockHeadersResponse {
+                request,
+                headers: blocks.iter().map(|block| (block.previous_hash(), *block.header())).collect(),
+            })
+            .boxed()
+    }
+
+    #[proptest]
+    fn block_headers_response_roundtrip(
+        #[strategy(any_block_headers_response())] original: BlockHeadersResponse<CurrentNetwork>,
+    ) {
+        let mut buf = BytesMut::default().writer();
+        original.write_le(&mut buf).unwrap();
+        let deserialized = BlockHeadersResponse::read_le(buf.into_inner().reader()).unwrap();
+        assert_eq!(original, deserialized);
+    }
+}
@@ -0,0 +1,77 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use snarkvm::prelude::{FromBytes, ToBytes};
+
+use std::borrow::Cow;
+
+/// The response to a block request for blocks that the node does not serve,
+/// e.g. as they were pruned or are above its latest block height.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BlocksUnavailable {
+    /// The original block request.
+    pub request: BlockRequest,
+    /// The oldest block height that the node serves.
+    pub oldest_block_height: u32,
+}
+
+impl MessageTrait for BlocksUnavailable {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        format!("BlocksUnavailable {}", self.request).into()
+    }
+}
+
+impl ToBytes for BlocksUnavailable {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        self.request.write_le(&mut writer)?;
+        self.oldest_block_height.write_le(&mut writer)?;
+        Ok(())
+    }
+}
+
+impl FromBytes for BlocksUnavailable {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let request = BlockRequest::read_le(&mut reader)?;
+        let oldest_block_height = u32::read_le(&mut reader)?;
+        Ok(Self { request, oldest_block_height })
+    }
+}
+
+#[cfg(test)]
+pub mod prop_tests {
+    use crate::{block_request::prop_tests::any_block_request, BlocksUnavailable};
+    use snarkvm::utilities::{FromBytes, ToBytes};
+
+    use bytes::{Buf, BufMut, BytesMut};
+    use proptest::prelude::{any, BoxedStrategy, Strategy};
+    use test_strategy::proptest;
+
+    pub fn any_blocks_unavailable() -> BoxedStrategy<BlocksUnavailable> {
+        (any_block_request(), any::<u32>())
+            .prop_map(|(request, oldest_block_height)| BlocksUnavailable { request, oldest_block_height })
+            .boxed()
+    }
+
+    #[proptest]
+    fn blocks_unavailable_roundtrip(#[strategy(any_blocks_unavailable())] message: BlocksUnavailable) {
+        let mut bytes = BytesMut::default().writer();
+        message.write_le(&mut bytes).unwrap();
+        let decoded = BlocksUnavailable::read_le(&mut bytes.into_inner().reader()).unwrap();
+        assert_eq!(decoded, message);
+    }
+}
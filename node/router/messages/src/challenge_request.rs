@@ -25,6 +25,7 @@ pub struct ChallengeRequest<N: Network> {
     pub node_type: NodeType,
     pub address: Address<N>,
     pub nonce: u64,
+    pub node_info: NodeInfo,
 }
 
 impl<N: Network> MessageTrait for ChallengeRequest<N> {
@@ -42,6 +43,7 @@ impl<N: Network> ToBytes for ChallengeRequest<N> {
         self.node_type.write_le(&mut writer)?;
         self.address.write_le(&mut writer)?;
         self.nonce.write_le(&mut writer)?;
+        self.node_info.write_le(&mut writer)?;
         Ok(())
     }
 }
@@ -53,20 +55,28 @@ impl<N: Network> FromBytes for ChallengeRequest<N> {
         let node_type = NodeType::read_le(&mut reader)?;
         let address = Address::<N>::read_le(&mut reader)?;
         let nonce = u64::read_le(&mut reader)?;
+        let node_info = NodeInfo::read_le(&mut reader)?;
 
-        Ok(Self { version, listener_port, node_type, address, nonce })
+        Ok(Self { version, listener_port, node_type, address, nonce, node_info })
     }
 }
 
 impl<N: Network> ChallengeRequest<N> {
     pub fn new(listener_port: u16, node_type: NodeType, address: Address<N>, nonce: u64) -> Self {
-        Self { version: Message::<N>::VERSION, listener_port, node_type, address, nonce }
+        Self {
+            version: Message::<N>::VERSION,
+            listener_port,
+            node_type,
+            address,
+            nonce,
+            node_info: NodeInfo::new(node_type),
+        }
     }
 }
 
 #[cfg(test)]
 pub mod prop_tests {
-    use crate::{ChallengeRequest, NodeType};
+    use crate::{ChallengeRequest, NodeInfo, NodeType};
     use snarkvm::{
         console::prelude::{FromBytes, ToBytes},
         prelude::{Address, TestRng, Uniform},
@@ -93,14 +103,21 @@ pub mod prop_tests {
             .boxed()
     }
 
+    pub fn any_node_info() -> BoxedStrategy<NodeInfo> {
+        ("[0-9.]{0,16}", "[a-z]{0,16}", proptest::collection::vec("[a-z]{0,16}", 0..=8))
+            .prop_map(|(version, os, services)| NodeInfo { version, os, services })
+            .boxed()
+    }
+
     pub fn any_challenge_request() -> BoxedStrategy<ChallengeRequest<CurrentNetwork>> {
-        (any_valid_address(), any::<u64>(), any::<u32>(), any::<u16>(), any_node_type())
-            .prop_map(|(address, nonce, version, listener_port, node_type)| ChallengeRequest {
+        (any_valid_address(), any::<u64>(), any::<u32>(), any::<u16>(), any_node_type(), any_node_info())
+            .prop_map(|(address, nonce, version, listener_port, node_type, node_info)| ChallengeRequest {
                 address,
                 nonce,
                 version,
                 listener_port,
                 node_type,
+                node_info,
             })
             .boxed()
     }
@@ -0,0 +1,330 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use snarkvm::{
+    ledger::{
+        authority::Authority,
+        block::{Block, ConfirmedTransaction, Ratifications, Transactions},
+        coinbase::CoinbaseSolution,
+    },
+    prelude::{FromBytes, ToBytes},
+    synthesizer::program::FinalizeOperation,
+};
+
+use anyhow::{bail, ensure, Result};
+use std::borrow::Cow;
+
+/// A transaction of a compact block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CompactTransaction<N: Network> {
+    /// A confirmed transaction, sent in full.
+    Full(ConfirmedTransaction<N>),
+    /// An accepted deploy transaction, sent by ID, with its index and finalize operations.
+    AcceptedDeploy(u32, N::TransactionID, Vec<FinalizeOperation<N>>),
+    /// An accepted execute transaction, sent by ID, with its index and finalize operations.
+    AcceptedExecute(u32, N::TransactionID, Vec<FinalizeOperation<N>>),
+}
+
+/// A block in compact form, in which the accepted transactions are sent by ID, as the recipient
+/// is expected to have received them already. The missing transactions are requested from the sender.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompactBlock<N: Network> {
+    /// The hash of the block.
+    pub block_hash: N::BlockHash,
+    /// The hash of the previous block.
+    pub previous_hash: N::BlockHash,
+    /// The header of the block.
+    pub header: Header<N>,
+    /// The authority of the block.
+    pub authority: Authority<N>,
+    /// The ratifications of the block.
+    pub ratifications: Ratifications<N>,
+    /// The solutions of the block.
+    pub solutions: Option<CoinbaseSolution<N>>,
+    /// The transactions of the block.
+    pub transactions: Vec<CompactTransaction<N>>,
+    /// The aborted transaction IDs of the block.
+    pub aborted_transaction_ids: Vec<N::TransactionID>,
+}
+
+impl<N: Network> CompactBlock<N> {
+    /// Initializes a new compact block from the given block, in which the accepted transactions are sent by ID,
+    /// except for the given transaction IDs, which are sent in full.
+    pub fn new(block: &Block<N>, full_transaction_ids: &[N::TransactionID]) -> Self {
+        let transactions = block
+            .transactions()
+            .iter()
+            .map(|confirmed| match confirmed {
+                ConfirmedTransaction::AcceptedDeploy(index, transaction, finalize)
+                    if !full_transaction_ids.contains(&transaction.id()) =>
+                {
+                    CompactTransaction::AcceptedDeploy(*index, transaction.id(), finalize.clone())
+                }
+                ConfirmedTransaction::AcceptedExecute(index, transaction, finalize)
+                    if !full_transaction_ids.contains(&transaction.id()) =>
+                {
+                    CompactTransaction::AcceptedExecute(*index, transaction.id(), finalize.clone())
+                }
+                confirmed => CompactTransaction::Full(confirmed.clone()),
+            })
+            .collect();
+
+        Self {
+            block_hash: block.hash(),
+            previous_hash: block.previous_hash(),
+            header: *block.header(),
+            authority: block.authority().clone(),
+            ratifications: block.ratifications().clone(),
+            solutions: block.solutions().cloned(),
+            transactions,
+            aborted_transaction_ids: block.aborted_transaction_ids().clone(),
+        }
+    }
+
+    /// Returns the height of the block.
+    pub const fn height(&self) -> u32 {
+        self.header.height()
+    }
+
+    /// Returns the IDs of the transactions that are sent by ID, and are not known to the recipient.
+    pub fn missing_transaction_ids(&self, is_known: impl Fn(&N::TransactionID) -> bool) -> Vec<N::TransactionID> {
+        self.transactions
+            .iter()
+            .filter_map(|transaction| match transaction {
+                CompactTransaction::Full(..) => None,
+                CompactTransaction::AcceptedDeploy(_, transaction_id, _)
+                | CompactTransaction::AcceptedExecute(_, transaction_id, _) => Some(*transaction_id),
+            })
+            .filter(|transaction_id| !is_known(transaction_id))
+            .collect()
+    }
+
+    /// Reconstructs the block, retrieving the transactions that are sent by ID from the given lookup.
+    pub fn into_block(self, lookup: impl Fn(&N::TransactionID) -> Option<Transaction<N>>) -> Result<Block<N>> {
+        // Retrieve the transaction for the given ID, ensuring it matches.
+        let retrieve = |transaction_id: N::TransactionID| match lookup(&transaction_id) {
+            Some(transaction) if transaction.id() == transaction_id => Ok(transaction),
+            Some(_) => bail!("Transaction '{transaction_id}' does not match its ID"),
+            None => bail!("Transaction '{transaction_id}' is missing from the compact block"),
+        };
+        // Reconstruct the confirmed transactions.
+        let transactions = self
+            .transactions
+            .into_iter()
+            .map(|transaction| match transaction {
+                CompactTransaction::Full(confirmed) => Ok(confirmed),
+                CompactTransaction::AcceptedDeploy(index, transaction_id, finalize) => {
+                    ConfirmedTransaction::accepted_deploy(index, retrieve(transaction_id)?, finalize)
+                }
+                CompactTransaction::AcceptedExecute(index, transaction_id, finalize) => {
+                    ConfirmedTransaction::accepted_execute(index, retrieve(transaction_id)?, finalize)
+                }
+            })
+            .collect::<Result<Transactions<N>>>()?;
+
+        // Reconstruct the block.
+        let block = Block::from(
+            self.previous_hash,
+            self.header,
+            self.authority,
+            self.ratifications,
+            self.solutions,
+            transactions,
+            self.aborted_transaction_ids,
+        )?;
+        // Ensure the block hash matches.
+        ensure!(block.hash() == self.block_hash, "The reconstructed block {} has an incorrect hash", block.height());
+        Ok(block)
+    }
+}
+
+impl<N: Network> MessageTrait for CompactBlock<N> {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        format!("CompactBlock {}", self.height()).into()
+    }
+}
+
+impl<N: Network> ToBytes for CompactTransaction<N> {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        // Write the finalize operations, with their (bounded) number.
+        let write_finalize = |finalize: &Vec<FinalizeOperation<N>>, writer: &mut W| -> io::Result<()> {
+            u16::try_from(finalize.len()).map_err(error)?.write_le(&mut *writer)?;
+            finalize.iter().try_for_each(|operation| operation.write_le(&mut *writer))
+        };
+        match self {
+            Self::Full(confirmed) => {
+                0u8.write_le(&mut writer)?;
+                confirmed.write_le(&mut writer)
+            }
+            Self::AcceptedDeploy(index, transaction_id, finalize) => {
+                1u8.write_le(&mut writer)?;
+                index.write_le(&mut writer)?;
+                transaction_id.write_le(&mut writer)?;
+                write_finalize(finalize, &mut writer)
+            }
+            Self::AcceptedExecute(index, transaction_id, finalize) => {
+                2u8.write_le(&mut writer)?;
+                index.write_le(&mut writer)?;
+                transaction_id.write_le(&mut writer)?;
+                write_finalize(finalize, &mut writer)
+            }
+        }
+    }
+}
+
+impl<N: Network> FromBytes for CompactTransaction<N> {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        // Read the finalize operations.
+        let read_finalize = |reader: &mut R| -> io::Result<Vec<FinalizeOperation<N>>> {
+            let num_finalize = u16::read_le(&mut *reader)?;
+            (0..num_finalize).map(|_| FinalizeOperation::read_le(&mut *reader)).collect()
+        };
+        match u8::read_le(&mut reader)? {
+            0 => Ok(Self::Full(ConfirmedTransaction::read_le(&mut reader)?)),
+            1 => {
+                let index = u32::read_le(&mut reader)?;
+                let transaction_id = N::TransactionID::read_le(&mut reader)?;
+                Ok(Self::AcceptedDeploy(index, transaction_id, read_finalize(&mut reader)?))
+            }
+            2 => {
+                let index = u32::read_le(&mut reader)?;
+                let transaction_id = N::TransactionID::read_le(&mut reader)?;
+                Ok(Self::AcceptedExecute(index, transaction_id, read_finalize(&mut reader)?))
+            }
+            variant => Err(error(format!("Invalid compact transaction variant {variant}"))),
+        }
+    }
+}
+
+impl<N: Network> ToBytes for CompactBlock<N> {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        // Ensure the number of transactions is within bounds.
+        if self.transactions.len() > Transactions::<N>::MAX_TRANSACTIONS
+            || self.aborted_transaction_ids.len() > Transactions::<N>::MAX_TRANSACTIONS
+        {
+            return Err(error("Compact block exceeds maximum number of transactions"));
+        }
+        self.block_hash.write_le(&mut writer)?;
+        self.previous_hash.write_le(&mut writer)?;
+        self.header.write_le(&mut writer)?;
+        self.authority.write_le(&mut writer)?;
+        self.ratifications.write_le(&mut writer)?;
+        match &self.solutions {
+            None => 0u8.write_le(&mut writer)?,
+            Some(solutions) => {
+                1u8.write_le(&mut writer)?;
+                solutions.write_le(&mut writer)?;
+            }
+        }
+        (self.transactions.len() as u32).write_le(&mut writer)?;
+        for transaction in &self.transactions {
+            transaction.write_le(&mut writer)?;
+        }
+        (self.aborted_transaction_ids.len() as u32).write_le(&mut writer)?;
+        for transaction_id in &self.aborted_transaction_ids {
+            transaction_id.write_le(&mut writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<N: Network> FromBytes for CompactBlock<N> {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let block_hash = N::BlockHash::read_le(&mut reader)?;
+        let previous_hash = N::BlockHash::read_le(&mut reader)?;
+        let header = Header::read_le(&mut reader)?;
+        let authority = Authority::read_le(&mut reader)?;
+        let ratifications = Ratifications::read_le(&mut reader)?;
+        let solutions = match u8::read_le(&mut reader)? {
+            0 => None,
+            1 => Some(CoinbaseSolution::read_le(&mut reader)?),
+            _ => return Err(error("Invalid solutions variant in the compact block")),
+        };
+        // Read the transactions.
+        let num_transactions = u32::read_le(&mut reader)? as usize;
+        if num_transactions > Transactions::<N>::MAX_TRANSACTIONS {
+            return Err(error("Compact block exceeds maximum number of transactions"));
+        }
+        let mut transactions = Vec::with_capacity(num_transactions);
+        for _ in 0..num_transactions {
+            transactions.push(CompactTransaction::read_le(&mut reader)?);
+        }
+        // Read the aborted transaction IDs.
+        let num_aborted = u32::read_le(&mut reader)? as usize;
+        if num_aborted > Transactions::<N>::MAX_TRANSACTIONS {
+            return Err(error("Compact block exceeds maximum number of aborted transactions"));
+        }
+        let mut aborted_transaction_ids = Vec::with_capacity(num_aborted);
+        for _ in 0..num_aborted {
+            aborted_transaction_ids.push(N::TransactionID::read_le(&mut reader)?);
+        }
+        Ok(Self {
+            block_hash,
+            previous_hash,
+            header,
+            authority,
+            ratifications,
+            solutions,
+            transactions,
+            aborted_transaction_ids,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{ledger::ledger_test_helpers::sample_genesis_block, prelude::TestRng};
+
+    use bytes::{Buf, BufMut, BytesMut};
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_compact_block() {
+        let block = sample_genesis_block(&mut TestRng::default());
+        // Retrieve the accepted transactions of the block.
+        let transactions = block
+            .transactions()
+            .iter()
+            .filter(|confirmed| confirmed.is_accepted())
+            .map(|confirmed| (confirmed.transaction().id(), confirmed.transaction().clone()))
+            .collect::<Vec<_>>();
+        assert!(!transactions.is_empty());
+        let lookup = |transaction_id: &<CurrentNetwork as Network>::TransactionID| {
+            transactions.iter().find(|(id, _)| id == transaction_id).map(|(_, transaction)| transaction.clone())
+        };
+
+        // Ensure the compact block round-trips.
+        let compact = CompactBlock::new(&block, &[]);
+        let mut buf = BytesMut::default().writer();
+        compact.write_le(&mut buf).unwrap();
+        let deserialized = CompactBlock::<CurrentNetwork>::read_le(buf.into_inner().reader()).unwrap();
+        assert_eq!(compact, deserialized);
+
+        // Ensure the accepted transactions are missing, unless they are known or sent in full.
+        assert_eq!(compact.missing_transaction_ids(|_| false).len(), transactions.len());
+        assert!(compact.missing_transaction_ids(|_| true).is_empty());
+        let full_ids = transactions.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+        assert!(CompactBlock::new(&block, &full_ids).missing_transaction_ids(|_| false).is_empty());
+
+        // Ensure the block is reconstructed, only if every transaction is available.
+        assert!(compact.clone().into_block(|_| None).is_err());
+        assert_eq!(compact.into_block(lookup).unwrap(), block);
+    }
+}
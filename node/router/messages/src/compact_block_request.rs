@@ -0,0 +1,102 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use snarkvm::prelude::{FromBytes, ToBytes};
+
+use std::borrow::Cow;
+
+/// The request for a block in compact form, in response to a `BlockAnnouncement` or a `CompactBlock`.
+///
+/// The given transaction IDs are the transactions of the block that are missing from the requester,
+/// which are to be sent in full in the `CompactBlock`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompactBlockRequest<N: Network> {
+    /// The hash of the block.
+    pub block_hash: N::BlockHash,
+    /// The IDs of the missing transactions.
+    pub transaction_ids: Vec<N::TransactionID>,
+}
+
+impl<N: Network> MessageTrait for CompactBlockRequest<N> {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        "CompactBlockRequest".into()
+    }
+}
+
+impl<N: Network> ToBytes for CompactBlockRequest<N> {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        // Ensure the number of transaction IDs is within bounds.
+        if self.transaction_ids.len() > TransactionInventory::<N>::MAXIMUM_NUMBER_OF_TRANSACTIONS {
+            return Err(error("Compact block request exceeds maximum number of transactions"));
+        }
+        self.block_hash.write_le(&mut writer)?;
+        (self.transaction_ids.len() as u16).write_le(&mut writer)?;
+        for transaction_id in &self.transaction_ids {
+            transaction_id.write_le(&mut writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<N: Network> FromBytes for CompactBlockRequest<N> {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let block_hash = N::BlockHash::read_le(&mut reader)?;
+        let num_transactions = u16::read_le(&mut reader)? as usize;
+        // Ensure the number of transaction IDs is within bounds.
+        if num_transactions > TransactionInventory::<N>::MAXIMUM_NUMBER_OF_TRANSACTIONS {
+            return Err(error("Compact block request exceeds maximum number of transactions"));
+        }
+        let mut transaction_ids = Vec::with_capacity(num_transactions);
+        for _ in 0..num_transactions {
+            transaction_ids.push(N::TransactionID::read_le(&mut reader)?);
+        }
+        Ok(Self { block_hash, transaction_ids })
+    }
+}
+
+#[cfg(test)]
+pub mod prop_tests {
+    use crate::{
+        block_announcement::prop_tests::any_block_hash,
+        transaction_inventory::prop_tests::any_transaction_ids,
+        CompactBlockRequest,
+    };
+    use snarkvm::utilities::{FromBytes, ToBytes};
+
+    use bytes::{Buf, BufMut, BytesMut};
+    use proptest::prelude::{BoxedStrategy, Strategy};
+    use test_strategy::proptest;
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    pub fn any_compact_block_request() -> BoxedStrategy<CompactBlockRequest<CurrentNetwork>> {
+        (any_block_hash(), any_transaction_ids())
+            .prop_map(|(block_hash, transaction_ids)| CompactBlockRequest { block_hash, transaction_ids })
+            .boxed()
+    }
+
+    #[proptest]
+    fn compact_block_request_roundtrip(
+        #[strategy(any_compact_block_request())] original: CompactBlockRequest<CurrentNetwork>,
+    ) {
+        let mut buf = BytesMut::default().writer();
+        original.write_le(&mut buf).unwrap();
+        let deserialized = CompactBlockRequest::read_le(buf.into_inner().reader()).unwrap();
+        assert_eq!(original, deserialized);
+    }
+}
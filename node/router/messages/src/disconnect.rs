@@ -78,6 +78,8 @@ mod tests {
             DisconnectReason::TooManyPeers,
             DisconnectReason::YouNeedToSyncFirst,
             DisconnectReason::YourPortIsClosed(TestRng::default().gen()),
+            DisconnectReason::Banned,
+            DisconnectReason::Stale,
         ];
 
         for reason in all_reasons.iter() {
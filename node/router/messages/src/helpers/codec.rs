@@ -13,21 +13,24 @@
 // limitations under the License.
 
 use crate::Message;
-use snarkvm::prelude::{FromBytes, Network, ToBytes};
+use snarkos_node_bft_events::FrameCodec;
+use snarkvm::prelude::{FromBytes, Network};
 
-use ::bytes::{Buf, BufMut, BytesMut};
+use ::bytes::{Buf, BytesMut};
 use core::marker::PhantomData;
-use tokio_util::codec::{Decoder, Encoder, LengthDelimitedCodec};
+use tokio_util::codec::{Decoder, Encoder};
 
 /// The maximum size of a message that can be transmitted during the handshake.
 const MAXIMUM_HANDSHAKE_MESSAGE_SIZE: usize = 1024 * 1024; // 1 MiB
 
 /// The maximum size of a message that can be transmitted in the network.
 pub(crate) const MAXIMUM_MESSAGE_SIZE: usize = 128 * 1024 * 1024; // 128 MiB
+/// The magic that prefixes every message frame.
+const MESSAGE_FRAME_MAGIC: u32 = u32::from_le_bytes(*b"AMSG");
 
 /// The codec used to decode and encode network `Message`s.
 pub struct MessageCodec<N: Network> {
-    codec: LengthDelimitedCodec,
+    codec: FrameCodec,
     _phantom: PhantomData<N>,
 }
 
@@ -42,7 +45,7 @@ impl<N: Network> MessageCodec<N> {
 impl<N: Network> Default for MessageCodec<N> {
     fn default() -> Self {
         Self {
-            codec: LengthDelimitedCodec::builder().max_frame_length(MAXIMUM_MESSAGE_SIZE).little_endian().new_codec(),
+            codec: FrameCodec::new(MESSAGE_FRAME_MAGIC, MAXIMUM_MESSAGE_SIZE, Message::<N>::max_size),
             _phantom: Default::default(),
         }
     }
@@ -52,15 +55,8 @@ impl<N: Network> Encoder<Message<N>> for MessageCodec<N> {
     type Error = std::io::Error;
 
     fn encode(&mut self, message: Message<N>, dst: &mut BytesMut) -> Result<(), Self::Error> {
-        // Serialize the payload directly into dst.
-        message
-            .write_le(&mut dst.writer())
-            // This error should never happen, the conversion is for greater compatibility.
-            .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "serialization error"))?;
-
-        let serialized_message = dst.split_to(dst.len()).freeze();
-
-        self.codec.encode(serialized_message, dst)
+        // Serialize the message into a frame.
+        self.codec.encode(&message, dst)
     }
 }
 
@@ -49,6 +49,10 @@ pub enum DisconnectReason {
     YouNeedToSyncFirst,
     /// The peer's listening port is closed.
     YourPortIsClosed(u16),
+    /// The peer is restricted from connecting to the node.
+    Banned,
+    /// The peer has not communicated within the allowed time.
+    Stale,
 }
 
 impl ToBytes for DisconnectReason {
@@ -72,6 +76,8 @@ impl ToBytes for DisconnectReason {
                 14u8.write_le(&mut writer)?;
                 port.write_le(writer)
             }
+            Self::Banned => 15u8.write_le(writer),
+            Self::Stale => 16u8.write_le(writer),
         }
     }
 }
@@ -97,6 +103,8 @@ impl FromBytes for DisconnectReason {
                 let port = u16::read_le(reader)?;
                 Ok(Self::YourPortIsClosed(port))
             }
+            15 => Ok(Self::Banned),
+            16 => Ok(Self::Stale),
             _ => Err(error("Invalid disconnect reason")),
         }
     }
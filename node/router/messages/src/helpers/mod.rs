@@ -14,9 +14,13 @@
 
 mod codec;
 pub use codec::MessageCodec;
+pub(crate) use codec::MAXIMUM_MESSAGE_SIZE;
 
 mod disconnect;
 pub use disconnect::DisconnectReason;
 
+mod node_info;
+pub use node_info::*;
+
 mod node_type;
 pub use node_type::*;
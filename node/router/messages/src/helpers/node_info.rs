@@ -0,0 +1,135 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::NodeType;
+use snarkvm::prelude::{error, FromBytes, ToBytes};
+
+use serde::{Deserialize, Serialize};
+use std::io;
+
+/// The maximum length of a string in the node info.
+const MAX_NODE_INFO_STRING_LENGTH: usize = 64; // bytes
+/// The maximum number of services advertised in the node info.
+const MAX_NODE_INFO_SERVICES: usize = 8;
+
+/// The information that a node advertises about itself during the handshake.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeInfo {
+    /// The semantic version of the node software.
+    pub version: String,
+    /// The operating system of the node.
+    pub os: String,
+    /// The services advertised by the node (e.g. "archival" or "prover").
+    pub services: Vec<String>,
+}
+
+impl NodeInfo {
+    /// Initializes the node info of this node, for the given node type.
+    pub fn new(node_type: NodeType) -> Self {
+        // Determine the services of the node type.
+        let services: &[&str] = match node_type {
+            NodeType::Client => &["archival"],
+            NodeType::Prover => &["prover"],
+            NodeType::Validator => &["archival", "validator"],
+        };
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            services: services.iter().map(|service| service.to_string()).collect(),
+        }
+    }
+
+    /// Returns `true` if the node advertises the given service.
+    pub fn has_service(&self, service: &str) -> bool {
+        self.services.iter().any(|s| s == service)
+    }
+}
+
+/// Writes the given string, prefixed by its length.
+fn write_string<W: io::Write>(string: &str, mut writer: W) -> io::Result<()> {
+    if string.len() > MAX_NODE_INFO_STRING_LENGTH {
+        return Err(error(format!("Node info string exceeds {MAX_NODE_INFO_STRING_LENGTH} bytes")));
+    }
+    (string.len() as u8).write_le(&mut writer)?;
+    writer.write_all(string.as_bytes())
+}
+
+/// Reads a string, prefixed by its length.
+fn read_string<R: io::Read>(mut reader: R) -> io::Result<String> {
+    let length = u8::read_le(&mut reader)? as usize;
+    if length > MAX_NODE_INFO_STRING_LENGTH {
+        return Err(error(format!("Node info string exceeds {MAX_NODE_INFO_STRING_LENGTH} bytes")));
+    }
+    let mut bytes = vec![0u8; length];
+    reader.read_exact(&mut bytes)?;
+    String::from_utf8(bytes).map_err(|_| error("Node info string is not valid UTF-8"))
+}
+
+impl ToBytes for NodeInfo {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        if self.services.len() > MAX_NODE_INFO_SERVICES {
+            return Err(error(format!("Node info exceeds {MAX_NODE_INFO_SERVICES} services")));
+        }
+        write_string(&self.version, &mut writer)?;
+        write_string(&self.os, &mut writer)?;
+        (self.services.len() as u8).write_le(&mut writer)?;
+        for service in &self.services {
+            write_string(service, &mut writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromBytes for NodeInfo {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let version = read_string(&mut reader)?;
+        let os = read_string(&mut reader)?;
+        let num_services = u8::read_le(&mut reader)? as usize;
+        if num_services > MAX_NODE_INFO_SERVICES {
+            return Err(error(format!("Node info exceeds {MAX_NODE_INFO_SERVICES} services")));
+        }
+        let services = (0..num_services).map(|_| read_string(&mut reader)).collect::<io::Result<Vec<_>>>()?;
+
+        Ok(Self { version, os, services })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_info_roundtrip() {
+        for node_type in [NodeType::Client, NodeType::Prover, NodeType::Validator] {
+            let node_info = NodeInfo::new(node_type);
+            let bytes = node_info.to_bytes_le().unwrap();
+            assert_eq!(NodeInfo::read_le(&bytes[..]).unwrap(), node_info);
+        }
+        assert!(NodeInfo::new(NodeType::Prover).has_service("prover"));
+        assert!(!NodeInfo::new(NodeType::Client).has_service("prover"));
+    }
+
+    #[test]
+    fn test_node_info_limits() {
+        // Ensure oversized strings and service lists are rejected.
+        let node_info = NodeInfo { version: "a".repeat(MAX_NODE_INFO_STRING_LENGTH + 1), ..Default::default() };
+        assert!(node_info.to_bytes_le().is_err());
+        let node_info = NodeInfo { services: vec![String::new(); MAX_NODE_INFO_SERVICES + 1], ..Default::default() };
+        assert!(node_info.to_bytes_le().is_err());
+
+        // Ensure an oversized string is rejected on deserialization.
+        let bytes = [MAX_NODE_INFO_STRING_LENGTH as u8 + 1; 128];
+        assert!(NodeInfo::read_le(&bytes[..]).is_err());
+    }
+}
@@ -20,18 +20,36 @@ extern crate tracing;
 pub mod helpers;
 pub use helpers::*;
 
+mod block_announcement;
+pub use block_announcement::BlockAnnouncement;
+
+mod block_headers_request;
+pub use block_headers_request::BlockHeadersRequest;
+
+mod block_headers_response;
+pub use block_headers_response::BlockHeadersResponse;
+
 mod block_request;
 pub use block_request::BlockRequest;
 
 mod block_response;
 pub use block_response::BlockResponse;
 
+mod blocks_unavailable;
+pub use blocks_unavailable::BlocksUnavailable;
+
 mod challenge_request;
 pub use challenge_request::ChallengeRequest;
 
 mod challenge_response;
 pub use challenge_response::ChallengeResponse;
 
+mod compact_block;
+pub use compact_block::{CompactBlock, CompactTransaction};
+
+mod compact_block_request;
+pub use compact_block_request::CompactBlockRequest;
+
 mod disconnect;
 pub use disconnect::Disconnect;
 
@@ -53,6 +71,12 @@ pub use puzzle_request::PuzzleRequest;
 mod puzzle_response;
 pub use puzzle_response::PuzzleResponse;
 
+mod transaction_inventory;
+pub use transaction_inventory::TransactionInventory;
+
+mod transaction_request;
+pub use transaction_request::TransactionRequest;
+
 mod unconfirmed_solution;
 pub use unconfirmed_solution::UnconfirmedSolution;
 
@@ -61,6 +85,7 @@ pub use unconfirmed_transaction::UnconfirmedTransaction;
 
 pub use snarkos_node_bft_events::DataBlocks;
 
+use snarkos_node_bft_events::{MAX_MEDIUM_PAYLOAD_SIZE, MAX_SMALL_PAYLOAD_SIZE};
 use snarkos_node_sync_locators::BlockLocators;
 use snarkvm::prelude::{
     block::{Header, Transaction},
@@ -101,6 +126,14 @@ pub enum Message<N: Network> {
     PuzzleResponse(PuzzleResponse<N>),
     UnconfirmedSolution(UnconfirmedSolution<N>),
     UnconfirmedTransaction(UnconfirmedTransaction<N>),
+    TransactionInventory(TransactionInventory<N>),
+    TransactionRequest(TransactionRequest<N>),
+    BlockAnnouncement(BlockAnnouncement<N>),
+    CompactBlock(CompactBlock<N>),
+    CompactBlockRequest(CompactBlockRequest<N>),
+    BlockHeadersRequest(BlockHeadersRequest),
+    BlockHeadersResponse(BlockHeadersResponse<N>),
+    BlocksUnavailable(BlocksUnavailable),
 }
 
 impl<N: Network> From<DisconnectReason> for Message<N> {
@@ -111,7 +144,7 @@ impl<N: Network> From<DisconnectReason> for Message<N> {
 
 impl<N: Network> Message<N> {
     /// The version of the network protocol; it can be incremented in order to force users to update.
-    pub const VERSION: u32 = 13;
+    pub const VERSION: u32 = 21;
 
     /// Returns the message name.
     #[inline]
@@ -130,6 +163,14 @@ impl<N: Network> Message<N> {
             Self::PuzzleResponse(message) => message.name(),
             Self::UnconfirmedSolution(message) => message.name(),
             Self::UnconfirmedTransaction(message) => message.name(),
+            Self::TransactionInventory(message) => message.name(),
+            Self::TransactionRequest(message) => message.name(),
+            Self::BlockAnnouncement(message) => message.name(),
+            Self::CompactBlock(message) => message.name(),
+            Self::CompactBlockRequest(message) => message.name(),
+            Self::BlockHeadersRequest(message) => message.name(),
+            Self::BlockHeadersResponse(message) => message.name(),
+            Self::BlocksUnavailable(message) => message.name(),
         }
     }
 
@@ -150,6 +191,29 @@ impl<N: Network> Message<N> {
             Self::PuzzleResponse(..) => 10,
             Self::UnconfirmedSolution(..) => 11,
             Self::UnconfirmedTransaction(..) => 12,
+            Self::TransactionInventory(..) => 13,
+            Self::TransactionRequest(..) => 14,
+            Self::BlockAnnouncement(..) => 15,
+            Self::CompactBlock(..) => 16,
+            Self::CompactBlockRequest(..) => 17,
+            Self::BlockHeadersRequest(..) => 18,
+            Self::BlockHeadersResponse(..) => 19,
+            Self::BlocksUnavailable(..) => 20,
+        }
+    }
+
+    /// Returns the maximum size in bytes of a message with the given ID, or `None` if the ID is unknown.
+    pub const fn max_size(id: u16) -> Option<usize> {
+        match id {
+            // BlockRequest, ChallengeRequest, ChallengeResponse, Disconnect, PeerRequest, PeerResponse,
+            // Pong, PuzzleRequest, TransactionInventory, TransactionRequest, BlockAnnouncement, CompactBlockRequest,
+            // BlockHeadersRequest, BlocksUnavailable
+            0 | 2 | 3 | 4 | 5 | 6 | 8 | 9 | 13 | 14 | 15 | 17 | 18 | 20 => Some(MAX_SMALL_PAYLOAD_SIZE),
+            // Ping, PuzzleResponse, UnconfirmedSolution, UnconfirmedTransaction, BlockHeadersResponse
+            7 | 10 | 11 | 12 | 19 => Some(MAX_MEDIUM_PAYLOAD_SIZE),
+            // BlockResponse, CompactBlock
+            1 | 16 => Some(MAXIMUM_MESSAGE_SIZE),
+            21.. => None,
         }
     }
 }
@@ -172,6 +236,14 @@ impl<N: Network> ToBytes for Message<N> {
             Self::PuzzleResponse(message) => message.write_le(writer),
             Self::UnconfirmedSolution(message) => message.write_le(writer),
             Self::UnconfirmedTransaction(message) => message.write_le(writer),
+            Self::TransactionInventory(message) => message.write_le(writer),
+            Self::TransactionRequest(message) => message.write_le(writer),
+            Self::BlockAnnouncement(message) => message.write_le(writer),
+            Self::CompactBlock(message) => message.write_le(writer),
+            Self::CompactBlockRequest(message) => message.write_le(writer),
+            Self::BlockHeadersRequest(message) => message.write_le(writer),
+            Self::BlockHeadersResponse(message) => message.write_le(writer),
+            Self::BlocksUnavailable(message) => message.write_le(writer),
         }
     }
 }
@@ -198,7 +270,15 @@ impl<N: Network> FromBytes for Message<N> {
             10 => Self::PuzzleResponse(PuzzleResponse::read_le(reader)?),
             11 => Self::UnconfirmedSolution(UnconfirmedSolution::read_le(reader)?),
             12 => Self::UnconfirmedTransaction(UnconfirmedTransaction::read_le(reader)?),
-            13.. => return Err(error("Unknown message ID {id}")),
+            13 => Self::TransactionInventory(TransactionInventory::read_le(reader)?),
+            14 => Self::TransactionRequest(TransactionRequest::read_le(reader)?),
+            15 => Self::BlockAnnouncement(BlockAnnouncement::read_le(reader)?),
+            16 => Self::CompactBlock(CompactBlock::read_le(reader)?),
+            17 => Self::CompactBlockRequest(CompactBlockRequest::read_le(reader)?),
+            18 => Self::BlockHeadersRequest(BlockHeadersRequest::read_le(reader)?),
+            19 => Self::BlockHeadersResponse(BlockHeadersResponse::read_le(reader)?),
+            20 => Self::BlocksUnavailable(BlocksUnavailable::read_le(reader)?),
+            21.. => return Err(error("Unknown message ID {id}")),
         };
 
         Ok(message)
@@ -23,6 +23,8 @@ use std::borrow::Cow;
 pub struct Ping<N: Network> {
     pub version: u32,
     pub node_type: NodeType,
+    /// The oldest block height that the node serves, which is above genesis if the node is pruned.
+    pub oldest_block_height: u32,
     pub block_locators: Option<BlockLocators<N>>,
 }
 
@@ -38,6 +40,7 @@ impl<N: Network> ToBytes for Ping<N> {
     fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
         self.version.write_le(&mut writer)?;
         self.node_type.write_le(&mut writer)?;
+        self.oldest_block_height.write_le(&mut writer)?;
         if let Some(locators) = &self.block_locators {
             1u8.write_le(&mut writer)?;
 
@@ -64,9 +67,10 @@ impl<N: Network> FromBytes for Ping<N> {
     fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
         let version = u32::read_le(&mut reader)?;
         let node_type = NodeType::read_le(&mut reader)?;
+        let oldest_block_height = u32::read_le(&mut reader)?;
 
         if u8::read_le(&mut reader)? == 0 {
-            return Ok(Self { version, node_type, block_locators: None });
+            return Ok(Self { version, node_type, oldest_block_height, block_locators: None });
         }
 
         let mut recents = IndexMap::new();
@@ -87,13 +91,13 @@ impl<N: Network> FromBytes for Ping<N> {
 
         let block_locators = Some(BlockLocators { recents, checkpoints });
 
-        Ok(Self { version, node_type, block_locators })
+        Ok(Self { version, node_type, oldest_block_height, block_locators })
     }
 }
 
 impl<N: Network> Ping<N> {
-    pub fn new(node_type: NodeType, block_locators: Option<BlockLocators<N>>) -> Self {
-        Self { version: <Message<N>>::VERSION, node_type, block_locators }
+    pub fn new(node_type: NodeType, oldest_block_height: u32, block_locators: Option<BlockLocators<N>>) -> Self {
+        Self { version: <Message<N>>::VERSION, node_type, oldest_block_height, block_locators }
     }
 }
 
@@ -114,8 +118,13 @@ pub mod prop_tests {
     }
 
     pub fn any_ping() -> BoxedStrategy<Ping<CurrentNetwork>> {
-        (any::<u32>(), any_block_locators(), any_node_type())
-            .prop_map(|(version, bls, node_type)| Ping { version, block_locators: Some(bls), node_type })
+        (any::<u32>(), any_block_locators(), any_node_type(), any::<u32>())
+            .prop_map(|(version, bls, node_type, oldest_block_height)| Ping {
+                version,
+                block_locators: Some(bls),
+                node_type,
+                oldest_block_height,
+            })
             .boxed()
     }
 
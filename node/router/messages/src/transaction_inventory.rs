@@ -0,0 +1,109 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use snarkvm::prelude::{FromBytes, ToBytes};
+
+use std::borrow::Cow;
+
+/// The announcement of the IDs of unconfirmed transactions that the sender is able to serve.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionInventory<N: Network> {
+    pub transaction_ids: Vec<N::TransactionID>,
+}
+
+impl<N: Network> TransactionInventory<N> {
+    /// The maximum number of transaction IDs in an inventory (or request) message.
+    pub const MAXIMUM_NUMBER_OF_TRANSACTIONS: usize = 1024;
+}
+
+impl<N: Network> MessageTrait for TransactionInventory<N> {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        "TransactionInventory".into()
+    }
+}
+
+impl<N: Network> ToBytes for TransactionInventory<N> {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        // Ensure the number of transaction IDs is within bounds.
+        if self.transaction_ids.len() > Self::MAXIMUM_NUMBER_OF_TRANSACTIONS {
+            return Err(error("Transaction inventory exceeds maximum number of transactions"));
+        }
+        (self.transaction_ids.len() as u16).write_le(&mut writer)?;
+        for transaction_id in &self.transaction_ids {
+            transaction_id.write_le(&mut writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<N: Network> FromBytes for TransactionInventory<N> {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let num_transactions = u16::read_le(&mut reader)? as usize;
+        // Ensure the number of transaction IDs is within bounds.
+        if num_transactions > Self::MAXIMUM_NUMBER_OF_TRANSACTIONS {
+            return Err(error("Transaction inventory exceeds maximum number of transactions"));
+        }
+        let mut transaction_ids = Vec::with_capacity(num_transactions);
+        for _ in 0..num_transactions {
+            transaction_ids.push(N::TransactionID::read_le(&mut reader)?);
+        }
+        Ok(Self { transaction_ids })
+    }
+}
+
+#[cfg(test)]
+pub mod prop_tests {
+    use crate::TransactionInventory;
+    use snarkvm::{
+        prelude::{Field, Network, Uniform},
+        utilities::{FromBytes, ToBytes},
+    };
+
+    use bytes::{Buf, BufMut, BytesMut};
+    use proptest::{
+        collection::vec,
+        prelude::{BoxedStrategy, Just, Strategy},
+    };
+    use test_strategy::proptest;
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    pub fn any_transaction_id() -> BoxedStrategy<<CurrentNetwork as Network>::TransactionID> {
+        Just(0)
+            .prop_perturb(|_, mut rng| <CurrentNetwork as Network>::TransactionID::from(Field::rand(&mut rng)))
+            .boxed()
+    }
+
+    pub fn any_transaction_ids() -> BoxedStrategy<Vec<<CurrentNetwork as Network>::TransactionID>> {
+        vec(any_transaction_id(), 0..=TransactionInventory::<CurrentNetwork>::MAXIMUM_NUMBER_OF_TRANSACTIONS).boxed()
+    }
+
+    pub fn any_transaction_inventory() -> BoxedStrategy<TransactionInventory<CurrentNetwork>> {
+        any_transaction_ids().prop_map(|transaction_ids| TransactionInventory { transaction_ids }).boxed()
+    }
+
+    #[proptest]
+    fn transaction_inventory_roundtrip(
+        #[strategy(any_transaction_inventory())] original: TransactionInventory<CurrentNetwork>,
+    ) {
+        let mut buf = BytesMut::default().writer();
+        original.write_le(&mut buf).unwrap();
+        let deserialized = TransactionInventory::read_le(buf.into_inner().reader()).unwrap();
+        assert_eq!(original, deserialized);
+    }
+}
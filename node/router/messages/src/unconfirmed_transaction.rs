@@ -16,7 +16,7 @@ use super::*;
 
 use snarkvm::{
     ledger::narwhal::Data,
-    prelude::{FromBytes, ToBytes},
+    prelude::{error, FromBytes, ToBytes},
 };
 
 use std::borrow::Cow;
@@ -25,12 +25,14 @@ use std::borrow::Cow;
 pub struct UnconfirmedTransaction<N: Network> {
     pub transaction_id: N::TransactionID,
     pub transaction: Data<Transaction<N>>,
+    /// The block height at which the transaction expires, if the submitter set one.
+    pub expiration_height: Option<u32>,
 }
 
 impl<N: Network> From<Transaction<N>> for UnconfirmedTransaction<N> {
     /// Initializes a new `UnconfirmedTransaction` message.
     fn from(transaction: Transaction<N>) -> Self {
-        Self { transaction_id: transaction.id(), transaction: Data::Object(transaction) }
+        Self { transaction_id: transaction.id(), transaction: Data::Object(transaction), expiration_height: None }
     }
 }
 
@@ -46,13 +48,26 @@ impl<N: Network> ToBytes for UnconfirmedTransaction<N> {
     fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
         self.transaction_id.write_le(&mut writer)?;
         self.transaction.write_le(&mut writer)?;
+        if let Some(expiration_height) = self.expiration_height {
+            1u8.write_le(&mut writer)?;
+            expiration_height.write_le(&mut writer)?;
+        } else {
+            0u8.write_le(&mut writer)?;
+        }
         Ok(())
     }
 }
 
 impl<N: Network> FromBytes for UnconfirmedTransaction<N> {
     fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
-        Ok(Self { transaction_id: N::TransactionID::read_le(&mut reader)?, transaction: Data::read_le(reader)? })
+        let transaction_id = N::TransactionID::read_le(&mut reader)?;
+        let transaction = Data::read_le(&mut reader)?;
+        let expiration_height = match u8::read_le(&mut reader)? {
+            0 => None,
+            1 => Some(u32::read_le(&mut reader)?),
+            _ => return Err(error("Invalid expiration height flag")),
+        };
+        Ok(Self { transaction_id, transaction, expiration_height })
     }
 }
 
@@ -65,7 +80,10 @@ pub mod prop_tests {
     };
 
     use bytes::{Buf, BufMut, BytesMut};
-    use proptest::prelude::{any, BoxedStrategy, Strategy};
+    use proptest::{
+        option,
+        prelude::{any, BoxedStrategy, Strategy},
+    };
     use test_strategy::proptest;
 
     type CurrentNetwork = snarkvm::prelude::Testnet3;
@@ -80,8 +98,12 @@ pub mod prop_tests {
     }
 
     pub fn any_unconfirmed_transaction() -> BoxedStrategy<UnconfirmedTransaction<CurrentNetwork>> {
-        any_transaction()
-            .prop_map(|tx| UnconfirmedTransaction { transaction_id: tx.id(), transaction: Data::Object(tx) })
+        (any_transaction(), option::of(any::<u32>()))
+            .prop_map(|(tx, expiration_height)| UnconfirmedTransaction {
+                transaction_id: tx.id(),
+                transaction: Data::Object(tx),
+                expiration_height,
+            })
             .boxed()
     }
 
@@ -95,6 +117,7 @@ pub mod prop_tests {
         let deserialized: UnconfirmedTransaction<CurrentNetwork> =
             UnconfirmedTransaction::read_le(buf.into_inner().reader()).unwrap();
         assert_eq!(original.transaction_id, deserialized.transaction_id);
+        assert_eq!(original.expiration_height, deserialized.expiration_height);
         assert_eq!(
             original.transaction.deserialize_blocking().unwrap(),
             deserialized.transaction.deserialize_blocking().unwrap(),
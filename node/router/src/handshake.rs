@@ -149,6 +149,10 @@ impl<N: Network> Router<N> {
         // Listen for the challenge request message.
         let peer_request = expect_message!(Message::ChallengeRequest, framed, peer_addr);
 
+        // Ensure the peer is whitelisted, if the node is in whitelist mode.
+        if let Err(forbidden_message) = self.ensure_peer_is_whitelisted(peer_ip, &peer_request.address) {
+            return Err(error(format!("{forbidden_message}")));
+        }
         // Verify the challenge response. If a disconnect reason was returned, send the disconnect message and abort.
         if let Some(reason) = self
             .verify_challenge_response(peer_addr, peer_request.address, peer_response, genesis_header, our_nonce)
@@ -173,7 +177,7 @@ impl<N: Network> Router<N> {
         send(&mut framed, peer_addr, Message::ChallengeResponse(our_response)).await?;
 
         // Add the peer to the router.
-        self.insert_connected_peer(Peer::new(peer_ip, &peer_request), peer_addr);
+        self.insert_connected_peer(Peer::new(peer_ip, &peer_request, ConnectionSide::Responder), peer_addr);
 
         Ok((peer_ip, framed))
     }
@@ -200,6 +204,14 @@ impl<N: Network> Router<N> {
 
         // Knowing the peer's listening address, ensure it is allowed to connect.
         if let Err(forbidden_message) = self.ensure_peer_is_allowed(peer_ip) {
+            // If the peer is restricted, send the disconnect message, so the peer refrains from reconnecting.
+            if self.is_restricted(&peer_ip) {
+                send(&mut framed, peer_addr, DisconnectReason::Banned.into()).await?;
+            }
+            return Err(error(format!("{forbidden_message}")));
+        }
+        // Ensure the peer is whitelisted, if the node is in whitelist mode.
+        if let Err(forbidden_message) = self.ensure_peer_is_whitelisted(peer_ip, &peer_request.address) {
             return Err(error(format!("{forbidden_message}")));
         }
         // Verify the challenge request. If a disconnect reason was returned, send the disconnect message and abort.
@@ -239,7 +251,7 @@ impl<N: Network> Router<N> {
             return Err(error(format!("Dropped '{peer_addr}' for reason: {reason:?}")));
         }
         // Add the peer to the router.
-        self.insert_connected_peer(Peer::new(peer_ip, &peer_request), peer_addr);
+        self.insert_connected_peer(Peer::new(peer_ip, &peer_request, ConnectionSide::Initiator), peer_addr);
 
         Ok((peer_ip, framed))
     }
@@ -262,8 +274,8 @@ impl<N: Network> Router<N> {
         if self.is_restricted(&peer_ip) {
             bail!("Dropping connection request from '{peer_ip}' (restricted)")
         }
-        // Ensure the peer is not spamming connection attempts.
-        if !peer_ip.ip().is_loopback() {
+        // Ensure the peer is not spamming connection attempts. Whitelisted peers are exempt from this limit.
+        if !peer_ip.ip().is_loopback() && !self.is_whitelisted_ip(&peer_ip) {
             // Add this connection attempt and retrieve the number of attempts.
             let num_attempts = self.cache.insert_inbound_connection(peer_ip.ip(), Self::RADIO_SILENCE_IN_SECS as i64);
             // Ensure the connecting peer has not surpassed the connection attempt limit.
@@ -276,6 +288,16 @@ impl<N: Network> Router<N> {
         Ok(())
     }
 
+    /// Ensure the peer is whitelisted, if the node is in whitelist mode.
+    fn ensure_peer_is_whitelisted(&self, peer_ip: SocketAddr, peer_address: &Address<N>) -> Result<()> {
+        if let Some(whitelist) = self.whitelist() {
+            if !whitelist.contains(&peer_ip, peer_address) {
+                bail!("Dropping connection with '{peer_ip}' (not whitelisted)")
+            }
+        }
+        Ok(())
+    }
+
     /// Verifies the given challenge request. Returns a disconnect reason if the request is invalid.
     fn verify_challenge_request(
         &self,
@@ -283,13 +305,18 @@ impl<N: Network> Router<N> {
         message: &ChallengeRequest<N>,
     ) -> Option<DisconnectReason> {
         // Retrieve the components of the challenge request.
-        let &ChallengeRequest { version, listener_port: _, node_type: _, address: _, nonce: _ } = message;
+        let &ChallengeRequest { version, listener_port, node_type: _, address: _, nonce: _, node_info: _ } = message;
 
         // Ensure the message protocol version is not outdated.
         if version < Message::<N>::VERSION {
             warn!("Dropping '{peer_addr}' on version {version} (outdated)");
             return Some(DisconnectReason::OutdatedClientVersion);
         }
+        // Ensure the listener IP of the peer is not in the peer deny list.
+        if self.is_denied_peer(&SocketAddr::new(peer_addr.ip(), listener_port)) {
+            debug!("Dropping '{peer_addr}' (denied)");
+            return Some(DisconnectReason::NoReasonGiven);
+        }
         None
     }
 
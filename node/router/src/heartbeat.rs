@@ -21,6 +21,7 @@ use snarkvm::prelude::Network;
 
 use colored::Colorize;
 use rand::{prelude::IteratorRandom, rngs::OsRng};
+use std::{cmp::Reverse, time::Duration};
 
 /// A helper function to compute the maximum of two numbers.
 /// See Rust issue 92391: https://github.com/rust-lang/rust/issues/92391.
@@ -48,8 +49,8 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
 
         // Remove any stale connected peers.
         self.remove_stale_connected_peers();
-        // Remove the oldest connected peer.
-        self.remove_oldest_connected_peer();
+        // Rotate out the lowest-scoring outbound peer.
+        self.rotate_lowest_scoring_peer();
         // Keep the number of connected peers within the allowed range.
         self.handle_connected_peers();
         // Keep the bootstrap peers within the allowed range.
@@ -82,7 +83,8 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
         }
     }
 
-    /// This function removes any connected peers that have not communicated within the predefined time.
+    /// This function removes any connected peers that have not communicated within the predefined time,
+    /// or have not responded to a `Ping` within the predefined time.
     fn remove_stale_connected_peers(&self) {
         // Check if any connected peer is stale.
         for peer in self.router().get_connected_peers() {
@@ -90,15 +92,29 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
             let elapsed = peer.last_seen().elapsed().as_secs();
             if elapsed > Router::<N>::RADIO_SILENCE_IN_SECS {
                 warn!("Peer {} has not communicated in {elapsed} seconds", peer.ip());
+                let _ = self.send(peer.ip(), Message::Disconnect(DisconnectReason::Stale.into()));
                 // Disconnect from this peer.
                 self.router().disconnect(peer.ip());
+                continue;
+            }
+            // Disconnect if the peer has not responded to the pending `Ping` within the predefined time.
+            if let Some(ping_sent) = peer.ping_sent() {
+                let elapsed = ping_sent.elapsed().as_secs();
+                if elapsed > Router::<N>::PONG_TIMEOUT_IN_SECS {
+                    warn!("Peer {} has not responded to a ping in {elapsed} seconds", peer.ip());
+                    let _ = self.send(peer.ip(), Message::Disconnect(DisconnectReason::Stale.into()));
+                    // Disconnect from this peer.
+                    self.router().disconnect(peer.ip());
+                }
             }
         }
     }
 
-    /// This function removes the oldest connected peer, to keep the connections fresh.
+    /// This function removes the lowest-scoring outbound peer, to probe for better peers.
+    /// A peer scores lower the higher its round-trip time (with unmeasured peers scoring lowest),
+    /// and ties are broken in favor of the peer that communicated least recently.
     /// This function only triggers if the router is above the minimum number of connected peers.
-    fn remove_oldest_connected_peer(&self) {
+    fn rotate_lowest_scoring_peer(&self) {
         // Skip if the router is at or below the minimum number of connected peers.
         if self.router().number_of_connected_peers() <= Self::MINIMUM_NUMBER_OF_PEERS {
             return;
@@ -109,21 +125,23 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
         // Retrieve the bootstrap peers.
         let bootstrap = self.router().bootstrap_peers();
 
-        // Find the oldest connected peer, that is neither trusted nor a bootstrap peer.
-        let oldest_peer = self
+        // Find the lowest-scoring outbound peer, that is neither trusted, whitelisted, nor a bootstrap peer.
+        let lowest_peer = self
             .router()
             .get_connected_peers()
             .iter()
+            .filter(|peer| peer.is_outbound())
             .filter(|peer| !trusted.contains(&peer.ip()) && !bootstrap.contains(&peer.ip()))
-            .min_by_key(|peer| peer.last_seen())
+            .filter(|peer| !self.router().is_whitelisted(&peer.ip()))
+            .max_by_key(|peer| (peer.rtt().unwrap_or(Duration::MAX), Reverse(peer.last_seen())))
             .map(|peer| peer.ip());
 
-        // Disconnect from the oldest connected peer, if one exists.
-        if let Some(oldest) = oldest_peer {
-            info!("Disconnecting from '{oldest}' (periodic refresh of peers)");
-            let _ = self.send(oldest, Message::Disconnect(DisconnectReason::PeerRefresh.into()));
+        // Disconnect from the lowest-scoring outbound peer, if one exists.
+        if let Some(lowest) = lowest_peer {
+            info!("Disconnecting from '{lowest}' (periodic refresh of peers)");
+            let _ = self.send(lowest, Message::Disconnect(DisconnectReason::PeerRefresh.into()));
             // Disconnect from this peer.
-            self.router().disconnect(oldest);
+            self.router().disconnect(lowest);
         }
     }
 
@@ -156,6 +174,7 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
                 .connected_peers()
                 .into_iter()
                 .filter(|peer_ip| !trusted.contains(peer_ip) && !bootstrap.contains(peer_ip))
+                .filter(|peer_ip| !self.router().is_whitelisted(peer_ip))
                 .choose_multiple(rng, num_surplus);
 
             // Proceed to send disconnect requests to these peers.
@@ -176,7 +195,8 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
             }
         }
 
-        if num_deficient > 0 {
+        // In whitelist mode, the node only connects to its whitelisted peers.
+        if num_deficient > 0 && !self.router().is_whitelist_mode() {
             // Initialize an RNG.
             let rng = &mut OsRng;
 
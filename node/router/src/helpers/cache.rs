@@ -12,7 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::messages::BlockRequest;
+use super::MemoryProfile;
+use crate::messages::{BlockHeadersRequest, BlockRequest};
 use snarkvm::prelude::{coinbase::PuzzleCommitment, Network};
 
 use core::hash::Hash;
@@ -25,9 +26,6 @@ use std::{
 };
 use time::{Duration, OffsetDateTime};
 
-/// The maximum number of items to store in a cache map.
-const MAX_CACHE_SIZE: usize = 1 << 17;
-
 /// A helper containing the peer IP and solution commitment.
 type SolutionKey<N> = (SocketAddr, PuzzleCommitment<N>);
 /// A helper containing the peer IP and transaction ID.
@@ -47,34 +45,42 @@ pub struct Cache<N: Network> {
     seen_inbound_transactions: RwLock<LinkedHashMap<TransactionKey<N>, OffsetDateTime>>,
     /// The map of peer IPs to their block requests.
     seen_outbound_block_requests: RwLock<IndexMap<SocketAddr, IndexSet<BlockRequest>>>,
+    /// The map of peer IPs to their block headers requests.
+    seen_outbound_block_headers_requests: RwLock<IndexMap<SocketAddr, IndexSet<BlockHeadersRequest>>>,
     /// The map of peer IPs to the number of puzzle requests.
     seen_outbound_puzzle_requests: RwLock<IndexMap<SocketAddr, u32>>,
     /// The map of solution commitments to their last seen timestamp.
     seen_outbound_solutions: RwLock<LinkedHashMap<SolutionKey<N>, OffsetDateTime>>,
     /// The map of transaction IDs to their last seen timestamp.
     seen_outbound_transactions: RwLock<LinkedHashMap<TransactionKey<N>, OffsetDateTime>>,
+    /// The maximum number of items to store in a cache map.
+    max_cache_size: usize,
 }
 
 impl<N: Network> Default for Cache<N> {
-    /// Initializes a new instance of the cache.
+    /// Initializes a new instance of the cache, sized for the standard memory profile.
     fn default() -> Self {
-        Self::new()
+        Self::new(MemoryProfile::default())
     }
 }
 
 impl<N: Network> Cache<N> {
-    /// Initializes a new instance of the cache.
-    pub fn new() -> Self {
+    /// Initializes a new instance of the cache, sized for the given memory profile.
+    pub fn new(profile: MemoryProfile) -> Self {
+        // Retrieve the maximum number of items to store in a cache map.
+        let max_cache_size = profile.max_cache_size();
         Self {
             seen_inbound_connections: Default::default(),
             seen_inbound_messages: Default::default(),
             seen_inbound_puzzle_requests: Default::default(),
-            seen_inbound_solutions: RwLock::new(LinkedHashMap::with_capacity(MAX_CACHE_SIZE)),
-            seen_inbound_transactions: RwLock::new(LinkedHashMap::with_capacity(MAX_CACHE_SIZE)),
+            seen_inbound_solutions: RwLock::new(LinkedHashMap::with_capacity(max_cache_size)),
+            seen_inbound_transactions: RwLock::new(LinkedHashMap::with_capacity(max_cache_size)),
             seen_outbound_block_requests: Default::default(),
+            seen_outbound_block_headers_requests: Default::default(),
             seen_outbound_puzzle_requests: Default::default(),
-            seen_outbound_solutions: RwLock::new(LinkedHashMap::with_capacity(MAX_CACHE_SIZE)),
-            seen_outbound_transactions: RwLock::new(LinkedHashMap::with_capacity(MAX_CACHE_SIZE)),
+            seen_outbound_solutions: RwLock::new(LinkedHashMap::with_capacity(max_cache_size)),
+            seen_outbound_transactions: RwLock::new(LinkedHashMap::with_capacity(max_cache_size)),
+            max_cache_size,
         }
     }
 }
@@ -101,7 +107,7 @@ impl<N: Network> Cache<N> {
         peer_ip: SocketAddr,
         solution: PuzzleCommitment<N>,
     ) -> Option<OffsetDateTime> {
-        Self::refresh_and_insert(&self.seen_inbound_solutions, (peer_ip, solution))
+        self.refresh_and_insert(&self.seen_inbound_solutions, (peer_ip, solution))
     }
 
     /// Inserts a transaction ID into the cache, returning the previously seen timestamp if it existed.
@@ -110,7 +116,7 @@ impl<N: Network> Cache<N> {
         peer_ip: SocketAddr,
         transaction: N::TransactionID,
     ) -> Option<OffsetDateTime> {
-        Self::refresh_and_insert(&self.seen_inbound_transactions, (peer_ip, transaction))
+        self.refresh_and_insert(&self.seen_inbound_transactions, (peer_ip, transaction))
     }
 }
 
@@ -134,6 +140,20 @@ impl<N: Network> Cache<N> {
         if let Some(requests) = map_write.get_mut(&peer_ip) { requests.remove(request) } else { false }
     }
 
+    /// Inserts the block headers request for the given peer IP, returning the number of recent requests.
+    pub fn insert_outbound_block_headers_request(&self, peer_ip: SocketAddr, request: BlockHeadersRequest) -> usize {
+        let mut map_write = self.seen_outbound_block_headers_requests.write();
+        let requests = map_write.entry(peer_ip).or_default();
+        requests.insert(request);
+        requests.len()
+    }
+
+    /// Removes the block headers request for the given peer IP, returning `true` if the request was present.
+    pub fn remove_outbound_block_headers_request(&self, peer_ip: SocketAddr, request: &BlockHeadersRequest) -> bool {
+        let mut map_write = self.seen_outbound_block_headers_requests.write();
+        if let Some(requests) = map_write.get_mut(&peer_ip) { requests.remove(request) } else { false }
+    }
+
     /// Returns `true` if the cache contains a puzzle request from the given peer.
     pub fn contains_outbound_puzzle_request(&self, peer_ip: &SocketAddr) -> bool {
         self.seen_outbound_puzzle_requests.read().get(peer_ip).map(|r| *r > 0).unwrap_or(false)
@@ -155,7 +175,7 @@ impl<N: Network> Cache<N> {
         peer_ip: SocketAddr,
         solution: PuzzleCommitment<N>,
     ) -> Option<OffsetDateTime> {
-        Self::refresh_and_insert(&self.seen_outbound_solutions, (peer_ip, solution))
+        self.refresh_and_insert(&self.seen_outbound_solutions, (peer_ip, solution))
     }
 
     /// Inserts a transaction ID into the cache, returning the previously seen timestamp if it existed.
@@ -164,7 +184,7 @@ impl<N: Network> Cache<N> {
         peer_ip: SocketAddr,
         transaction: N::TransactionID,
     ) -> Option<OffsetDateTime> {
-        Self::refresh_and_insert(&self.seen_outbound_transactions, (peer_ip, transaction))
+        self.refresh_and_insert(&self.seen_outbound_transactions, (peer_ip, transaction))
     }
 }
 
@@ -218,9 +238,9 @@ impl<N: Network> Cache<N> {
     }
 
     /// Updates the map by enforcing the maximum cache size.
-    fn refresh<K: Eq + Hash, V>(map: &RwLock<LinkedHashMap<K, V>>) {
+    fn refresh<K: Eq + Hash, V>(&self, map: &RwLock<LinkedHashMap<K, V>>) {
         let mut map_write = map.write();
-        while map_write.len() >= MAX_CACHE_SIZE {
+        while map_write.len() >= self.max_cache_size {
             map_write.pop_front();
         }
     }
@@ -228,13 +248,14 @@ impl<N: Network> Cache<N> {
     /// Updates the map by enforcing the maximum cache size, and inserts the given key.
     /// Returns the previously seen timestamp if it existed.
     fn refresh_and_insert<K: Eq + Hash>(
+        &self,
         map: &RwLock<LinkedHashMap<K, OffsetDateTime>>,
         key: K,
     ) -> Option<OffsetDateTime> {
         // Insert the key, and return the previous timestamp if it existed.
         let previous_timestamp = map.write().insert(key, OffsetDateTime::now_utc());
         // Refresh the cache.
-        Self::refresh(map);
+        self.refresh(map);
         // Return the previous timestamp.
         previous_timestamp
     }
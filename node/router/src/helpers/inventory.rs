@@ -0,0 +1,203 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::MemoryProfile;
+use crate::messages::UnconfirmedTransaction;
+use snarkvm::prelude::Network;
+
+use indexmap::IndexMap;
+use linked_hash_map::LinkedHashMap;
+use parking_lot::RwLock;
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+/// The duration in seconds after which an unanswered transaction request may be sent to another peer.
+const TRANSACTION_REQUEST_TIMEOUT_IN_SECS: u64 = 10; // seconds
+
+/// The transaction inventory, used to gossip unconfirmed transactions by announcing their IDs.
+///
+/// Peers announce the IDs of the transactions they are able to serve, and the node requests only the
+/// transactions it has not seen, from a single peer at a time. The IDs known by each peer are tracked,
+/// so that a transaction is never announced (or sent) to a peer that already has it.
+#[derive(Debug)]
+pub struct Inventory<N: Network> {
+    /// The map of transaction IDs to their (serialized) unconfirmed transactions, for serving requests.
+    transactions: RwLock<LinkedHashMap<N::TransactionID, UnconfirmedTransaction<N>>>,
+    /// The set of transaction IDs seen by this node.
+    seen_transactions: RwLock<LinkedHashMap<N::TransactionID, ()>>,
+    /// The map of peer IPs to the set of transaction IDs known by the peer.
+    known_transactions: RwLock<IndexMap<SocketAddr, LinkedHashMap<N::TransactionID, ()>>>,
+    /// The map of requested transaction IDs to the peer IP and time of the request.
+    requests: RwLock<IndexMap<N::TransactionID, (SocketAddr, Instant)>>,
+    /// The memory profile of the node, which bounds the size of the inventory.
+    profile: MemoryProfile,
+}
+
+impl<N: Network> Default for Inventory<N> {
+    /// Initializes a new instance of the inventory, sized for the standard memory profile.
+    fn default() -> Self {
+        Self::new(MemoryProfile::default())
+    }
+}
+
+impl<N: Network> Inventory<N> {
+    /// Initializes a new instance of the inventory, sized for the given memory profile.
+    pub fn new(profile: MemoryProfile) -> Self {
+        Self {
+            transactions: Default::default(),
+            seen_transactions: Default::default(),
+            known_transactions: Default::default(),
+            requests: Default::default(),
+            profile,
+        }
+    }
+
+    /// Returns the unconfirmed transaction for the given transaction ID, if it is stored.
+    pub fn get_transaction(&self, transaction_id: &N::TransactionID) -> Option<UnconfirmedTransaction<N>> {
+        self.transactions.read().get(transaction_id).cloned()
+    }
+
+    /// Returns `true` if the given transaction ID has been seen by this node.
+    pub fn is_seen(&self, transaction_id: &N::TransactionID) -> bool {
+        self.seen_transactions.read().contains_key(transaction_id)
+    }
+
+    /// Returns `true` if the given transaction ID is known by the given peer.
+    pub fn is_known(&self, peer_ip: &SocketAddr, transaction_id: &N::TransactionID) -> bool {
+        self.known_transactions.read().get(peer_ip).map_or(false, |known| known.contains_key(transaction_id))
+    }
+
+    /// Stores the given unconfirmed transaction for serving requests, and marks it as seen.
+    pub fn insert_transaction(&self, transaction: UnconfirmedTransaction<N>) {
+        let transaction_id = transaction.transaction_id;
+        // Store the transaction, evicting the oldest one if the store is full.
+        {
+            let mut transactions = self.transactions.write();
+            transactions.insert(transaction_id, transaction);
+            while transactions.len() > self.profile.max_stored_transactions() {
+                transactions.pop_front();
+            }
+        }
+        self.insert_seen(transaction_id);
+    }
+
+    /// Marks the given transaction ID as seen by this node, and removes any pending request for it.
+    pub fn insert_seen(&self, transaction_id: N::TransactionID) {
+        // Mark the transaction as seen, evicting the oldest ID if the set is full.
+        {
+            let mut seen_transactions = self.seen_transactions.write();
+            seen_transactions.insert(transaction_id, ());
+            while seen_transactions.len() > self.profile.max_cache_size() {
+                seen_transactions.pop_front();
+            }
+        }
+        self.requests.write().remove(&transaction_id);
+    }
+
+    /// Marks the given transaction IDs as known by the given peer.
+    pub fn insert_known(&self, peer_ip: SocketAddr, transaction_ids: impl IntoIterator<Item = N::TransactionID>) {
+        let mut known_transactions = self.known_transactions.write();
+        let known = known_transactions.entry(peer_ip).or_default();
+        for transaction_id in transaction_ids {
+            known.insert(transaction_id, ());
+        }
+        // Evict the oldest IDs if the set is full.
+        while known.len() > self.profile.max_known_transactions_per_peer() {
+            known.pop_front();
+        }
+    }
+
+    /// Returns the transaction IDs that should be requested from the given peer, marking them as requested.
+    ///
+    /// A transaction ID is requested if it has not been seen, and has no pending request to another peer.
+    pub fn insert_requests(
+        &self,
+        peer_ip: SocketAddr,
+        transaction_ids: impl IntoIterator<Item = N::TransactionID>,
+    ) -> Vec<N::TransactionID> {
+        let timeout = Duration::from_secs(TRANSACTION_REQUEST_TIMEOUT_IN_SECS);
+        let seen_transactions = self.seen_transactions.read();
+        let mut requests = self.requests.write();
+        // Remove the expired requests.
+        requests.retain(|_, (_, timestamp)| timestamp.elapsed() < timeout);
+
+        let mut requested = Vec::new();
+        for transaction_id in transaction_ids {
+            // Skip transactions that are seen or already requested.
+            if seen_transactions.contains_key(&transaction_id) || requests.contains_key(&transaction_id) {
+                continue;
+            }
+            requests.insert(transaction_id, (peer_ip, Instant::now()));
+            requested.push(transaction_id);
+        }
+        requested
+    }
+
+    /// Removes the known transactions and pending requests of the given peer.
+    pub fn remove_peer(&self, peer_ip: &SocketAddr) {
+        self.known_transactions.write().remove(peer_ip);
+        self.requests.write().retain(|_, (ip, _)| ip != peer_ip);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{
+        ledger::narwhal::Data,
+        prelude::{Field, TestRng, Uniform},
+    };
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_inventory() {
+        let rng = &mut TestRng::default();
+        let inventory = Inventory::<CurrentNetwork>::default();
+        let peer_a = SocketAddr::from(([1, 2, 3, 4], 4133));
+        let peer_b = SocketAddr::from(([5, 6, 7, 8], 4133));
+        let id_1 = <CurrentNetwork as Network>::TransactionID::from(Field::rand(rng));
+        let id_2 = <CurrentNetwork as Network>::TransactionID::from(Field::rand(rng));
+
+        // Ensure unseen transactions are requested from a single peer at a time.
+        assert_eq!(inventory.insert_requests(peer_a, [id_1, id_2]), vec![id_1, id_2]);
+        assert!(inventory.insert_requests(peer_b, [id_1, id_2]).is_empty());
+
+        // Ensure the requests of a departed peer may be sent to another peer.
+        inventory.remove_peer(&peer_a);
+        assert_eq!(inventory.insert_requests(peer_b, [id_1]), vec![id_1]);
+
+        // Ensure seen transactions are not requested.
+        inventory.insert_seen(id_1);
+        inventory.remove_peer(&peer_b);
+        assert!(inventory.insert_requests(peer_a, [id_1]).is_empty());
+
+        // Ensure stored transactions are served, and marked as seen.
+        let transaction_id = <CurrentNetwork as Network>::TransactionID::from(Field::rand(rng));
+        let transaction = Data::Buffer(Default::default());
+        inventory.insert_transaction(UnconfirmedTransaction { transaction_id, transaction, expiration_height: None });
+        assert!(inventory.get_transaction(&transaction_id).is_some());
+        assert!(inventory.is_seen(&transaction_id));
+        assert!(inventory.get_transaction(&id_2).is_none());
+
+        // Ensure the known transactions are tracked per peer.
+        inventory.insert_known(peer_a, [id_2]);
+        assert!(inventory.is_known(&peer_a, &id_2));
+        assert!(!inventory.is_known(&peer_b, &id_2));
+        inventory.remove_peer(&peer_a);
+        assert!(!inventory.is_known(&peer_a, &id_2));
+    }
+}
@@ -15,8 +15,20 @@
 mod cache;
 pub use cache::Cache;
 
+mod inventory;
+pub use inventory::*;
+
 mod peer;
 pub use peer::*;
 
+mod profile;
+pub use profile::*;
+
+mod relay;
+pub use relay::*;
+
 mod resolver;
 pub use resolver::*;
+
+mod whitelist;
+pub use whitelist::*;
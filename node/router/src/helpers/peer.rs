@@ -12,10 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::messages::{ChallengeRequest, NodeType};
+use crate::messages::{ChallengeRequest, NodeInfo, NodeType};
+use snarkos_node_tcp::ConnectionSide;
 use snarkvm::prelude::{Address, Network};
 
-use std::{net::SocketAddr, time::Instant};
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
 
 /// The state for each connected peer.
 #[derive(Clone, Debug)]
@@ -28,22 +32,34 @@ pub struct Peer<N: Network> {
     node_type: NodeType,
     /// The message version of the peer.
     version: u32,
+    /// The node info advertised by the peer.
+    node_info: NodeInfo,
+    /// The side of the connection that the peer is on.
+    peer_side: ConnectionSide,
     /// The timestamp of the first message received from the peer.
     first_seen: Instant,
     /// The timestamp of the last message received from this peer.
     last_seen: Instant,
+    /// The timestamp of the last `Ping` sent to this peer, if a `Pong` is still pending.
+    ping_sent: Option<Instant>,
+    /// The smoothed round-trip time to this peer, if it has been measured.
+    rtt: Option<Duration>,
 }
 
 impl<N: Network> Peer<N> {
     /// Initializes a new instance of `Peer`.
-    pub fn new(listening_ip: SocketAddr, challenge_request: &ChallengeRequest<N>) -> Self {
+    pub fn new(listening_ip: SocketAddr, challenge_request: &ChallengeRequest<N>, peer_side: ConnectionSide) -> Self {
         Self {
             peer_ip: listening_ip,
             address: challenge_request.address,
             node_type: challenge_request.node_type,
             version: challenge_request.version,
+            node_info: challenge_request.node_info.clone(),
+            peer_side,
             first_seen: Instant::now(),
             last_seen: Instant::now(),
+            ping_sent: None,
+            rtt: None,
         }
     }
 
@@ -82,6 +98,16 @@ impl<N: Network> Peer<N> {
         self.version
     }
 
+    /// Returns the node info advertised by the peer.
+    pub const fn node_info(&self) -> &NodeInfo {
+        &self.node_info
+    }
+
+    /// Returns `true` if the connection to the peer was initiated by this node.
+    pub fn is_outbound(&self) -> bool {
+        self.peer_side == ConnectionSide::Responder
+    }
+
     /// Returns the first seen timestamp of the peer.
     pub fn first_seen(&self) -> Instant {
         self.first_seen
@@ -91,6 +117,16 @@ impl<N: Network> Peer<N> {
     pub fn last_seen(&self) -> Instant {
         self.last_seen
     }
+
+    /// Returns the timestamp of the last `Ping` sent to the peer, if a `Pong` is still pending.
+    pub const fn ping_sent(&self) -> Option<Instant> {
+        self.ping_sent
+    }
+
+    /// Returns the smoothed round-trip time to the peer, if it has been measured.
+    pub const fn rtt(&self) -> Option<Duration> {
+        self.rtt
+    }
 }
 
 impl<N: Network> Peer<N> {
@@ -108,4 +144,57 @@ impl<N: Network> Peer<N> {
     pub fn set_last_seen(&mut self, last_seen: Instant) {
         self.last_seen = last_seen;
     }
+
+    /// Updates the timestamp of the last `Ping` sent to the peer.
+    pub fn set_ping_sent(&mut self, ping_sent: Instant) {
+        self.ping_sent = Some(ping_sent);
+    }
+
+    /// Updates the round-trip time to the peer, given the timestamp at which a `Pong` was received,
+    /// and returns the smoothed round-trip time. Returns `None` if no `Ping` was pending.
+    pub fn set_pong_received(&mut self, pong_received: Instant) -> Option<Duration> {
+        // Compute the round-trip time of the pending `Ping`.
+        let sample = pong_received.saturating_duration_since(self.ping_sent.take()?);
+        // Smooth the round-trip time with an exponentially-weighted moving average.
+        let rtt = match self.rtt {
+            Some(rtt) => (rtt * 7 + sample) / 8,
+            None => sample,
+        };
+        self.rtt = Some(rtt);
+        Some(rtt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{prelude::Rng, utilities::TestRng};
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_peer_rtt() {
+        let mut rng = TestRng::default();
+        let address = Address::<CurrentNetwork>::new(rng.gen());
+        let challenge_request = ChallengeRequest::new(4133, NodeType::Client, address, rng.gen());
+        let mut peer = Peer::new(SocketAddr::from(([1, 2, 3, 4], 4133)), &challenge_request, ConnectionSide::Responder);
+        assert!(peer.is_outbound());
+
+        // Ensure a `Pong` without a pending `Ping` is ignored.
+        let now = Instant::now();
+        assert_eq!(peer.set_pong_received(now), None);
+        assert_eq!(peer.rtt(), None);
+
+        // Ensure the first round-trip time is recorded as is.
+        peer.set_ping_sent(now);
+        assert_eq!(peer.ping_sent(), Some(now));
+        assert_eq!(peer.set_pong_received(now + Duration::from_millis(80)), Some(Duration::from_millis(80)));
+        assert_eq!(peer.ping_sent(), None);
+        // Ensure subsequent round-trip times are smoothed.
+        peer.set_ping_sent(now);
+        assert_eq!(peer.set_pong_received(now + Duration::from_millis(160)), Some(Duration::from_millis(90)));
+        assert_eq!(peer.rtt(), Some(Duration::from_millis(90)));
+        // Ensure the pending `Ping` is consumed.
+        assert_eq!(peer.set_pong_received(now + Duration::from_millis(500)), None);
+    }
 }
@@ -0,0 +1,106 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{bail, Result};
+use std::str::FromStr;
+
+/// The memory profile of the node, which determines the footprint of its caches.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum MemoryProfile {
+    /// The standard profile, sized for dedicated machines.
+    #[default]
+    Standard,
+    /// The low-memory profile, sized for small machines (e.g. Raspberry Pi or small cloud instances).
+    /// It shrinks the caches, compresses the ledger cache, limits the verification parallelism,
+    /// and disables the coinbase puzzle.
+    LowMemory,
+}
+
+impl MemoryProfile {
+    /// Returns the maximum number of items to store in a cache map.
+    pub const fn max_cache_size(&self) -> usize {
+        match self {
+            Self::Standard => 1 << 17,
+            Self::LowMemory => 1 << 12,
+        }
+    }
+
+    /// Returns the maximum number of unconfirmed transactions to store for serving requests.
+    pub const fn max_stored_transactions(&self) -> usize {
+        match self {
+            Self::Standard => 1 << 12,
+            Self::LowMemory => 1 << 8,
+        }
+    }
+
+    /// Returns the maximum number of transaction IDs to remember as known by each peer.
+    pub const fn max_known_transactions_per_peer(&self) -> usize {
+        match self {
+            Self::Standard => 1 << 14,
+            Self::LowMemory => 1 << 10,
+        }
+    }
+
+    /// Returns the maximum number of candidate peers to store.
+    pub const fn max_candidate_peers(&self) -> usize {
+        match self {
+            Self::Standard => 10_000,
+            Self::LowMemory => 1_000,
+        }
+    }
+
+    /// Returns `true` if the coinbase puzzle of a prover starts running.
+    /// In the low-memory profile, the coinbase puzzle is disabled until it is started through the admin API.
+    pub const fn is_mining_enabled(&self) -> bool {
+        match self {
+            Self::Standard => true,
+            Self::LowMemory => false,
+        }
+    }
+}
+
+impl FromStr for MemoryProfile {
+    type Err = anyhow::Error;
+
+    /// Parses the memory profile from its name.
+    fn from_str(profile: &str) -> Result<Self> {
+        match profile {
+            "standard" => Ok(Self::Standard),
+            "low-memory" => Ok(Self::LowMemory),
+            _ => bail!("Unknown profile '{profile}' (expected 'standard' or 'low-memory')"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_profile() {
+        assert_eq!(MemoryProfile::from_str("standard").unwrap(), MemoryProfile::Standard);
+        assert_eq!(MemoryProfile::from_str("low-memory").unwrap(), MemoryProfile::LowMemory);
+        assert!(MemoryProfile::from_str("tiny").is_err());
+
+        // Ensure the low-memory profile shrinks every cache.
+        let (standard, low_memory) = (MemoryProfile::Standard, MemoryProfile::LowMemory);
+        assert!(low_memory.max_cache_size() < standard.max_cache_size());
+        assert!(low_memory.max_stored_transactions() < standard.max_stored_transactions());
+        assert!(low_memory.max_known_transactions_per_peer() < standard.max_known_transactions_per_peer());
+        assert!(low_memory.max_candidate_peers() < standard.max_candidate_peers());
+        // Ensure the low-memory profile disables the coinbase puzzle.
+        assert!(standard.is_mining_enabled());
+        assert!(!low_memory.is_mining_enabled());
+    }
+}
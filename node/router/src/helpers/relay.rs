@@ -0,0 +1,127 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::Network;
+
+use indexmap::IndexMap;
+use parking_lot::RwLock;
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+/// The duration in seconds after which an unanswered compact block request may be sent to another peer.
+const COMPACT_BLOCK_REQUEST_TIMEOUT_IN_SECS: u64 = 10; // seconds
+
+/// A pending compact block request.
+#[derive(Copy, Clone, Debug)]
+struct CompactBlockRequest {
+    /// The peer IP the compact block was requested from.
+    peer_ip: SocketAddr,
+    /// The time of the request.
+    timestamp: Instant,
+    /// The boolean flag for whether the missing transactions of the block have been requested.
+    has_requested_transactions: bool,
+}
+
+/// The block relay, which tracks the compact blocks requested from peers upon a block announcement,
+/// so that each announced block is requested from a single peer at a time.
+#[derive(Debug)]
+pub struct BlockRelay<N: Network> {
+    /// The map of requested block hashes to their pending request.
+    requests: RwLock<IndexMap<N::BlockHash, CompactBlockRequest>>,
+}
+
+impl<N: Network> Default for BlockRelay<N> {
+    /// Initializes a new instance of the block relay.
+    fn default() -> Self {
+        Self { requests: Default::default() }
+    }
+}
+
+impl<N: Network> BlockRelay<N> {
+    /// Records a compact block request for the given block hash to the given peer,
+    /// returning `false` if the block has already been requested from a peer.
+    pub fn insert_block_request(&self, peer_ip: SocketAddr, block_hash: N::BlockHash) -> bool {
+        let mut requests = self.requests.write();
+        // Remove the expired requests.
+        let timeout = Duration::from_secs(COMPACT_BLOCK_REQUEST_TIMEOUT_IN_SECS);
+        requests.retain(|_, request| request.timestamp.elapsed() < timeout);
+        // Ensure the block has not been requested.
+        if requests.contains_key(&block_hash) {
+            return false;
+        }
+        let request = CompactBlockRequest { peer_ip, timestamp: Instant::now(), has_requested_transactions: false };
+        requests.insert(block_hash, request);
+        true
+    }
+
+    /// Records a request for the missing transactions of the given block to the given peer,
+    /// returning `false` if the missing transactions of the block have already been requested.
+    pub fn insert_transactions_request(&self, peer_ip: SocketAddr, block_hash: N::BlockHash) -> bool {
+        let mut requests = self.requests.write();
+        let request = requests.entry(block_hash).or_insert(CompactBlockRequest {
+            peer_ip,
+            timestamp: Instant::now(),
+            has_requested_transactions: false,
+        });
+        // Ensure the missing transactions have not been requested.
+        if request.has_requested_transactions {
+            return false;
+        }
+        *request = CompactBlockRequest { peer_ip, timestamp: Instant::now(), has_requested_transactions: true };
+        true
+    }
+
+    /// Removes the pending request for the given block hash.
+    pub fn remove_request(&self, block_hash: &N::BlockHash) {
+        self.requests.write().remove(block_hash);
+    }
+
+    /// Removes the pending requests to the given peer.
+    pub fn remove_peer(&self, peer_ip: &SocketAddr) {
+        self.requests.write().retain(|_, request| request.peer_ip != *peer_ip);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{Field, TestRng, Uniform};
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_block_relay() {
+        let rng = &mut TestRng::default();
+        let relay = BlockRelay::<CurrentNetwork>::default();
+        let peer_a = SocketAddr::from(([1, 2, 3, 4], 4133));
+        let peer_b = SocketAddr::from(([5, 6, 7, 8], 4133));
+        let block_hash = <CurrentNetwork as Network>::BlockHash::from(Field::rand(rng));
+
+        // Ensure a block is requested from a single peer at a time.
+        assert!(relay.insert_block_request(peer_a, block_hash));
+        assert!(!relay.insert_block_request(peer_b, block_hash));
+
+        // Ensure the missing transactions of a block are requested once.
+        assert!(relay.insert_transactions_request(peer_a, block_hash));
+        assert!(!relay.insert_transactions_request(peer_b, block_hash));
+
+        // Ensure the block may be requested again once the peer departs.
+        relay.remove_peer(&peer_a);
+        assert!(relay.insert_block_request(peer_b, block_hash));
+        relay.remove_request(&block_hash);
+        assert!(relay.insert_transactions_request(peer_a, block_hash));
+    }
+}
@@ -0,0 +1,124 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{Address, Network};
+
+use anyhow::{bail, Result};
+use indexmap::IndexSet;
+use std::{net::SocketAddr, str::FromStr};
+
+/// The whitelist of peers that a node in whitelist mode is permitted to connect to.
+///
+/// In whitelist mode, the node only accepts and dials connections to peers whose listener IP
+/// or Aleo address is in the whitelist, never gossips their IPs, and exempts them from rate limits and bans.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Whitelist<N: Network> {
+    /// The set of whitelisted peer IPs.
+    ips: IndexSet<SocketAddr>,
+    /// The set of whitelisted Aleo addresses.
+    addresses: IndexSet<Address<N>>,
+}
+
+impl<N: Network> Default for Whitelist<N> {
+    /// Initializes an empty whitelist.
+    fn default() -> Self {
+        Self { ips: Default::default(), addresses: Default::default() }
+    }
+}
+
+impl<N: Network> Whitelist<N> {
+    /// Initializes a new whitelist from the given peer IPs and Aleo addresses.
+    pub fn new(ips: impl IntoIterator<Item = SocketAddr>, addresses: impl IntoIterator<Item = Address<N>>) -> Self {
+        Self { ips: ips.into_iter().collect(), addresses: addresses.into_iter().collect() }
+    }
+
+    /// Returns the set of whitelisted peer IPs.
+    pub const fn ips(&self) -> &IndexSet<SocketAddr> {
+        &self.ips
+    }
+
+    /// Returns the set of whitelisted Aleo addresses.
+    pub const fn addresses(&self) -> &IndexSet<Address<N>> {
+        &self.addresses
+    }
+
+    /// Returns `true` if the whitelist is empty.
+    pub fn is_empty(&self) -> bool {
+        self.ips.is_empty() && self.addresses.is_empty()
+    }
+
+    /// Returns `true` if the given peer IP is whitelisted.
+    pub fn contains_ip(&self, peer_ip: &SocketAddr) -> bool {
+        self.ips.contains(peer_ip)
+    }
+
+    /// Returns `true` if the given Aleo address is whitelisted.
+    pub fn contains_address(&self, address: &Address<N>) -> bool {
+        self.addresses.contains(address)
+    }
+
+    /// Returns `true` if the given peer IP or Aleo address is whitelisted.
+    pub fn contains(&self, peer_ip: &SocketAddr, address: &Address<N>) -> bool {
+        self.contains_ip(peer_ip) || self.contains_address(address)
+    }
+}
+
+impl<N: Network> FromStr for Whitelist<N> {
+    type Err = anyhow::Error;
+
+    /// Parses a comma-separated list of peer IPs and Aleo addresses into a whitelist.
+    fn from_str(whitelist: &str) -> Result<Self> {
+        let mut ips = IndexSet::new();
+        let mut addresses = IndexSet::new();
+        for entry in whitelist.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            // Parse the entry as a peer IP.
+            if let Ok(ip) = entry.parse::<SocketAddr>() {
+                ips.insert(ip);
+            }
+            // Parse the entry as an Aleo address.
+            else if let Ok(address) = Address::<N>::from_str(entry) {
+                addresses.insert(address);
+            } else {
+                bail!("The whitelist entry '{entry}' is neither a peer IP nor an Aleo address")
+            }
+        }
+        Ok(Self { ips, addresses })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{prelude::Rng, utilities::TestRng};
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_whitelist_from_str() {
+        let mut rng = TestRng::default();
+        let address = Address::<CurrentNetwork>::new(rng.gen());
+        let ip = SocketAddr::from(([1, 2, 3, 4], 4133));
+
+        let whitelist = Whitelist::<CurrentNetwork>::from_str("").unwrap();
+        assert!(whitelist.is_empty());
+
+        let whitelist = Whitelist::<CurrentNetwork>::from_str(&format!("{ip}, {address}")).unwrap();
+        assert_eq!(whitelist, Whitelist::new([ip], [address]));
+        assert!(whitelist.contains_ip(&ip));
+        assert!(whitelist.contains_address(&address));
+        assert!(whitelist.contains(&SocketAddr::from(([5, 6, 7, 8], 4133)), &address));
+
+        assert!(Whitelist::<CurrentNetwork>::from_str("not-a-peer").is_err());
+    }
+}
@@ -14,13 +14,22 @@
 
 use crate::{
     messages::{
+        BlockAnnouncement,
+        BlockHeadersRequest,
+        BlockHeadersResponse,
         BlockRequest,
         BlockResponse,
+        BlocksUnavailable,
+        CompactBlock,
+        CompactBlockRequest,
         DataBlocks,
+        DisconnectReason,
         Message,
         PeerResponse,
         Ping,
         Pong,
+        TransactionInventory,
+        TransactionRequest,
         UnconfirmedSolution,
         UnconfirmedTransaction,
     },
@@ -58,10 +67,13 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
         };
 
         // Drop the peer, if they have sent more than `MESSAGE_LIMIT` messages
-        // in the last `MESSAGE_LIMIT_TIME_FRAME_IN_SECS` seconds.
-        let num_messages = self.router().cache.insert_inbound_message(peer_ip, Self::MESSAGE_LIMIT_TIME_FRAME_IN_SECS);
-        if num_messages > Self::MESSAGE_LIMIT {
-            bail!("Dropping '{peer_ip}' for spamming messages (num_messages = {num_messages})")
+        // in the last `MESSAGE_LIMIT_TIME_FRAME_IN_SECS` seconds. Whitelisted peers are exempt from this limit.
+        if !self.router().is_whitelisted(&peer_ip) {
+            let num_messages =
+                self.router().cache.insert_inbound_message(peer_ip, Self::MESSAGE_LIMIT_TIME_FRAME_IN_SECS);
+            if num_messages > Self::MESSAGE_LIMIT {
+                bail!("Dropping '{peer_ip}' for spamming messages (num_messages = {num_messages})")
+            }
         }
 
         trace!("Received '{}' from '{peer_ip}'", message.name());
@@ -100,17 +112,72 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                 blocks.ensure_response_is_well_formed(peer_ip, request.start_height, request.end_height)?;
 
                 // Process the block response.
-                let node = self.clone();
-                match spawn_blocking(move || node.block_response(peer_ip, blocks.0)).await? {
+                // Note: The blocks are committed in a dedicated task, so that this peer's messages are not stalled.
+                match self.block_response(peer_ip, blocks.0) {
                     true => Ok(()),
                     false => bail!("Peer '{peer_ip}' sent an invalid block response"),
                 }
             }
+            Message::BlocksUnavailable(message) => {
+                // Remove the block request, checking if this node previously sent a block request to this peer.
+                if !self.router().cache.remove_outbound_block_request(peer_ip, &message.request) {
+                    bail!("Peer '{peer_ip}' is not following the protocol (unexpected blocks unavailable)")
+                }
+                // Process the blocks unavailable message.
+                match self.blocks_unavailable(peer_ip, message) {
+                    true => Ok(()),
+                    false => bail!("Peer '{peer_ip}' sent an invalid blocks unavailable message"),
+                }
+            }
+            Message::BlockHeadersRequest(message) => {
+                let BlockHeadersRequest { start_height, end_height } = &message;
+
+                // Ensure the block headers request is well-formed.
+                if start_height >= end_height {
+                    bail!("Block headers request from '{peer_ip}' has an invalid range ({start_height}..{end_height})")
+                }
+                // Ensure that the block headers request is within the allowed bounds.
+                if end_height - start_height > BlockHeadersResponse::<N>::MAXIMUM_NUMBER_OF_HEADERS as u32 {
+                    bail!("Block headers request from '{peer_ip}' is too large ({start_height}..{end_height})")
+                }
+
+                let node = self.clone();
+                match spawn_blocking(move || node.block_headers_request(peer_ip, message)).await? {
+                    true => Ok(()),
+                    false => bail!("Peer '{peer_ip}' sent an invalid block headers request"),
+                }
+            }
+            Message::BlockHeadersResponse(message) => {
+                let BlockHeadersResponse { request, headers } = message;
+
+                // Remove the block headers request, checking if this node previously sent it to this peer.
+                if !self.router().cache.remove_outbound_block_headers_request(peer_ip, &request) {
+                    bail!("Peer '{peer_ip}' is not following the protocol (unexpected block headers response)")
+                }
+                // Ensure the block headers response is well-formed.
+                if headers.len() != (request.start_height..request.end_height).len() {
+                    bail!("Peer '{peer_ip}' sent a block headers response with an incorrect number of headers")
+                }
+
+                // Process the block headers response.
+                let node = self.clone();
+                match spawn_blocking(move || node.block_headers_response(peer_ip, headers)).await? {
+                    true => Ok(()),
+                    false => bail!("Peer '{peer_ip}' sent an invalid block headers response"),
+                }
+            }
             Message::ChallengeRequest(..) | Message::ChallengeResponse(..) => {
                 // Disconnect as the peer is not following the protocol.
                 bail!("Peer '{peer_ip}' is not following the protocol")
             }
             Message::Disconnect(message) => {
+                match message.reason {
+                    // If the peer has restricted this node, refrain from reconnecting for a while.
+                    DisconnectReason::Banned => self.router().insert_restricted_peer(peer_ip),
+                    // If the peer is shutting down, back off before reconnecting, without restricting it.
+                    DisconnectReason::ShuttingDown => self.router().insert_backoff_peer(peer_ip),
+                    _ => (),
+                }
                 bail!("{:?}", message.reason)
             }
             Message::PeerRequest(..) => match self.peer_request(peer_ip) {
@@ -157,10 +224,16 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                     false => bail!("Peer '{peer_ip}' sent an invalid ping"),
                 }
             }
-            Message::Pong(message) => match self.pong(peer_ip, message) {
-                true => Ok(()),
-                false => bail!("Peer '{peer_ip}' sent an invalid pong"),
-            },
+            Message::Pong(message) => {
+                // Update the round-trip time to the peer.
+                self.router().update_pong_received(peer_ip);
+
+                // Process the pong message.
+                match self.pong(peer_ip, message) {
+                    true => Ok(()),
+                    false => bail!("Peer '{peer_ip}' sent an invalid pong"),
+                }
+            }
             Message::PuzzleRequest(..) => {
                 // Insert the puzzle request for the peer, and fetch the recent frequency.
                 let frequency = self.router().cache.insert_inbound_puzzle_request(peer_ip);
@@ -223,6 +296,9 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                 // Update the timestamp for the unconfirmed transaction.
                 let seen_before =
                     self.router().cache.insert_inbound_transaction(peer_ip, message.transaction_id).is_some();
+                // Mark the transaction as known by the peer, and as seen by this node.
+                self.router().inventory.insert_known(peer_ip, [message.transaction_id]);
+                self.router().inventory.insert_seen(message.transaction_id);
                 // Determine whether to propagate the transaction.
                 if seen_before {
                     bail!("Skipping 'UnconfirmedTransaction' from '{peer_ip}'")
@@ -242,6 +318,50 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                     false => bail!("Peer '{peer_ip}' sent an invalid unconfirmed transaction"),
                 }
             }
+            Message::TransactionInventory(message) => {
+                // Ensure the transaction inventory is within bounds.
+                if message.transaction_ids.len() > TransactionInventory::<N>::MAXIMUM_NUMBER_OF_TRANSACTIONS {
+                    bail!("Peer '{peer_ip}' sent an excessive transaction inventory")
+                }
+                match self.transaction_inventory(peer_ip, message.transaction_ids) {
+                    true => Ok(()),
+                    false => bail!("Peer '{peer_ip}' sent an invalid transaction inventory"),
+                }
+            }
+            Message::TransactionRequest(message) => {
+                // Ensure the transaction request is within bounds.
+                if message.transaction_ids.len() > TransactionInventory::<N>::MAXIMUM_NUMBER_OF_TRANSACTIONS {
+                    bail!("Peer '{peer_ip}' sent an excessive transaction request")
+                }
+                match self.transaction_request(peer_ip, message.transaction_ids) {
+                    true => Ok(()),
+                    false => bail!("Peer '{peer_ip}' sent an invalid transaction request"),
+                }
+            }
+            Message::BlockAnnouncement(message) => match self.block_announcement(peer_ip, message) {
+                true => Ok(()),
+                false => bail!("Peer '{peer_ip}' sent an invalid block announcement"),
+            },
+            Message::CompactBlock(message) => {
+                // Process the compact block.
+                let node = self.clone();
+                match spawn_blocking(move || node.compact_block(peer_ip, message)).await? {
+                    true => Ok(()),
+                    false => bail!("Peer '{peer_ip}' sent an invalid compact block"),
+                }
+            }
+            Message::CompactBlockRequest(message) => {
+                // Ensure the compact block request is within bounds.
+                if message.transaction_ids.len() > TransactionInventory::<N>::MAXIMUM_NUMBER_OF_TRANSACTIONS {
+                    bail!("Peer '{peer_ip}' sent an excessive compact block request")
+                }
+                // Process the compact block request.
+                let node = self.clone();
+                match spawn_blocking(move || node.compact_block_request(peer_ip, message)).await? {
+                    true => Ok(()),
+                    false => bail!("Peer '{peer_ip}' sent an invalid compact block request"),
+                }
+            }
         }
     }
 
@@ -251,12 +371,33 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
     /// Handles a `BlockResponse` message.
     fn block_response(&self, peer_ip: SocketAddr, _blocks: Vec<Block<N>>) -> bool;
 
+    /// Handles a `BlocksUnavailable` message.
+    fn blocks_unavailable(&self, peer_ip: SocketAddr, message: BlocksUnavailable) -> bool;
+
+    /// Handles a `BlockHeadersRequest` message.
+    fn block_headers_request(&self, peer_ip: SocketAddr, message: BlockHeadersRequest) -> bool;
+
+    /// Handles a `BlockHeadersResponse` message.
+    fn block_headers_response(&self, peer_ip: SocketAddr, headers: Vec<(N::BlockHash, Header<N>)>) -> bool;
+
+    /// Handles a `BlockAnnouncement` message.
+    fn block_announcement(&self, peer_ip: SocketAddr, message: BlockAnnouncement<N>) -> bool;
+
+    /// Handles a `CompactBlock` message.
+    fn compact_block(&self, peer_ip: SocketAddr, message: CompactBlock<N>) -> bool;
+
+    /// Handles a `CompactBlockRequest` message.
+    fn compact_block_request(&self, peer_ip: SocketAddr, message: CompactBlockRequest<N>) -> bool;
+
     /// Handles a `PeerRequest` message.
     fn peer_request(&self, peer_ip: SocketAddr) -> bool {
         // Retrieve the connected peers.
         let peers = self.router().connected_peers();
-        // Filter out invalid addresses.
-        let peers = peers.into_iter().filter(|ip| self.router().is_valid_peer_ip(ip)).collect();
+        // Filter out invalid addresses, and never gossip the addresses of whitelisted peers.
+        let peers = peers
+            .into_iter()
+            .filter(|ip| self.router().is_valid_peer_ip(ip) && !self.router().is_whitelisted(ip))
+            .collect();
         // Send a `PeerResponse` message to the peer.
         self.send(peer_ip, Message::PeerResponse(PeerResponse { peers }));
         true
@@ -291,6 +432,33 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
         solution: ProverSolution<N>,
     ) -> bool;
 
+    /// Handles a `TransactionInventory` message.
+    fn transaction_inventory(&self, peer_ip: SocketAddr, transaction_ids: Vec<N::TransactionID>) -> bool {
+        // Mark the announced transactions as known by the peer.
+        self.router().inventory.insert_known(peer_ip, transaction_ids.iter().copied());
+        // Provers do not process unconfirmed transactions, so they never request them.
+        if self.router().node_type().is_prover() {
+            return true;
+        }
+        // Request the transactions that have not been seen, and are not already requested from another peer.
+        let transaction_ids = self.router().inventory.insert_requests(peer_ip, transaction_ids);
+        if !transaction_ids.is_empty() {
+            self.send(peer_ip, Message::TransactionRequest(TransactionRequest { transaction_ids }));
+        }
+        true
+    }
+
+    /// Handles a `TransactionRequest` message.
+    fn transaction_request(&self, peer_ip: SocketAddr, transaction_ids: Vec<N::TransactionID>) -> bool {
+        // Send the requested transactions that are stored in the inventory.
+        for transaction_id in transaction_ids {
+            if let Some(transaction) = self.router().inventory.get_transaction(&transaction_id) {
+                self.send(peer_ip, Message::UnconfirmedTransaction(transaction));
+            }
+        }
+        true
+    }
+
     /// Handles an `UnconfirmedTransaction` message.
     async fn unconfirmed_transaction(
         &self,
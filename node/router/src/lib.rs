@@ -39,15 +39,26 @@ pub use outbound::*;
 mod routing;
 pub use routing::*;
 
-use crate::messages::NodeType;
+use crate::messages::{NodeInfo, NodeType};
 use snarkos_account::Account;
-use snarkos_node_tcp::{is_bogon_ip, is_unspecified_or_broadcast_ip, Config, Tcp};
+use snarkos_node_tcp::{is_bogon_ip, is_unspecified_or_broadcast_ip, Config, PeerDenyList, Tcp};
 use snarkvm::prelude::{Address, Network, PrivateKey, ViewKey};
 
 use anyhow::{bail, Result};
 use indexmap::{IndexMap, IndexSet};
 use parking_lot::{Mutex, RwLock};
-use std::{collections::HashSet, future::Future, net::SocketAddr, ops::Deref, str::FromStr, sync::Arc, time::Instant};
+use std::{
+    collections::HashSet,
+    future::Future,
+    net::SocketAddr,
+    ops::Deref,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 use tokio::task::JoinHandle;
 
 #[derive(Clone)]
@@ -72,8 +83,22 @@ pub struct InnerRouter<N: Network> {
     cache: Cache<N>,
     /// The resolver.
     resolver: Resolver,
+    /// The transaction inventory.
+    inventory: Inventory<N>,
+    /// The block relay.
+    block_relay: BlockRelay<N>,
+    /// The oldest block height that the node serves, which is above genesis if the node is pruned.
+    oldest_block_height: AtomicU32,
     /// The set of trusted peers.
     trusted_peers: IndexSet<SocketAddr>,
+    /// The whitelist of peers, if the node is in whitelist mode.
+    whitelist: Option<Whitelist<N>>,
+    /// The peer deny list, if it is enabled.
+    peer_deny_list: Option<Arc<PeerDenyList>>,
+    /// The memory profile of the node, which determines the footprint of its caches.
+    profile: MemoryProfile,
+    /// The boolean flag for whether new blocks are relayed to peers in compact form, instead of announced.
+    is_compact_relay_enabled: bool,
     /// The map of connected peer IPs to their peer handlers.
     connected_peers: RwLock<IndexMap<SocketAddr, Peer<N>>>,
     /// The set of handshaking peers. While `Tcp` already recognizes the connecting IP addresses
@@ -85,6 +110,8 @@ pub struct InnerRouter<N: Network> {
     candidate_peers: RwLock<IndexSet<SocketAddr>>,
     /// The set of restricted peer IPs.
     restricted_peers: RwLock<IndexMap<SocketAddr, Instant>>,
+    /// The map of peer IPs that announced a shutdown, to the time of the announcement.
+    backoff_peers: RwLock<IndexMap<SocketAddr, Instant>>,
     /// The spawned handles.
     handles: Mutex<Vec<JoinHandle<()>>>,
     /// The boolean flag for the development mode.
@@ -92,13 +119,16 @@ pub struct InnerRouter<N: Network> {
 }
 
 impl<N: Network> Router<N> {
-    /// The maximum number of candidate peers permitted to be stored in the node.
-    const MAXIMUM_CANDIDATE_PEERS: usize = 10_000;
     /// The maximum number of connection failures permitted by an inbound connecting peer.
     const MAXIMUM_CONNECTION_FAILURES: usize = 5;
     /// The duration in seconds after which a connected peer is considered inactive or
     /// disconnected if no message has been received in the meantime.
     const RADIO_SILENCE_IN_SECS: u64 = 150; // 2.5 minutes
+    /// The duration in seconds after which a connected peer is considered unresponsive
+    /// if it has not replied to a `Ping` in the meantime.
+    const PONG_TIMEOUT_IN_SECS: u64 = 60; // 1 minute
+    /// The duration in seconds during which the node refrains from reconnecting to a peer that shut down.
+    const RECONNECT_BACKOFF_IN_SECS: u64 = 30; // 30 seconds
 }
 
 impl<N: Network> Router<N> {
@@ -108,6 +138,10 @@ impl<N: Network> Router<N> {
         node_type: NodeType,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
+        whitelist: Option<Whitelist<N>>,
+        peer_deny_list: Option<Arc<PeerDenyList>>,
+        profile: MemoryProfile,
+        is_compact_relay_enabled: bool,
         max_peers: u16,
         is_dev: bool,
     ) -> Result<Self> {
@@ -118,13 +152,21 @@ impl<N: Network> Router<N> {
             tcp,
             node_type,
             account,
-            cache: Default::default(),
+            cache: Cache::new(profile),
             resolver: Default::default(),
+            inventory: Inventory::new(profile),
+            block_relay: Default::default(),
+            oldest_block_height: Default::default(),
             trusted_peers: trusted_peers.iter().copied().collect(),
+            whitelist,
+            peer_deny_list,
+            profile,
+            is_compact_relay_enabled,
             connected_peers: Default::default(),
             connecting_peers: Default::default(),
             candidate_peers: Default::default(),
             restricted_peers: Default::default(),
+            backoff_peers: Default::default(),
             handles: Default::default(),
             is_dev,
         })))
@@ -173,10 +215,22 @@ impl<N: Network> Router<N> {
         if self.is_connected(&peer_ip) {
             bail!("Dropping connection attempt to '{peer_ip}' (already connected)")
         }
+        // Ensure the peer is whitelisted, if the node is in whitelist mode.
+        if self.is_whitelist_mode() && !self.is_whitelisted_ip(&peer_ip) {
+            bail!("Dropping connection attempt to '{peer_ip}' (not whitelisted)")
+        }
         // Ensure the peer is not restricted.
         if self.is_restricted(&peer_ip) {
             bail!("Dropping connection attempt to '{peer_ip}' (restricted)")
         }
+        // Ensure the peer is not in the peer deny list.
+        if self.is_denied_peer(&peer_ip) {
+            bail!("Dropping connection attempt to '{peer_ip}' (denied)")
+        }
+        // Ensure the peer has not recently announced that it is shutting down.
+        if self.is_backing_off(&peer_ip) {
+            bail!("Dropping connection attempt to '{peer_ip}' (shutting down)")
+        }
         // Ensure the node is not already connecting to this peer.
         if !self.connecting_peers.lock().insert(peer_ip) {
             bail!("Dropping connection attempt to '{peer_ip}' (already shaking hands as the initiator)")
@@ -184,6 +238,14 @@ impl<N: Network> Router<N> {
         Ok(())
     }
 
+    /// Disconnects from the connected peers that are in the peer deny list.
+    pub fn disconnect_denied_peers(&self) {
+        for peer_ip in self.connected_peers().into_iter().filter(|peer_ip| self.is_denied_peer(peer_ip)) {
+            debug!("Disconnecting from '{peer_ip}' (denied)");
+            self.disconnect(peer_ip);
+        }
+    }
+
     /// Disconnects from the given peer IP, if the peer is connected.
     pub fn disconnect(&self, peer_ip: SocketAddr) -> JoinHandle<bool> {
         let router = self.clone();
@@ -245,6 +307,36 @@ impl<N: Network> Router<N> {
         self.is_dev
     }
 
+    /// Returns the memory profile of the node.
+    pub fn memory_profile(&self) -> MemoryProfile {
+        self.profile
+    }
+
+    /// Returns `true` if new blocks are relayed to peers in compact form.
+    pub fn is_compact_relay_enabled(&self) -> bool {
+        self.is_compact_relay_enabled
+    }
+
+    /// Returns the transaction inventory.
+    pub fn inventory(&self) -> &Inventory<N> {
+        &self.inventory
+    }
+
+    /// Returns the block relay.
+    pub fn block_relay(&self) -> &BlockRelay<N> {
+        &self.block_relay
+    }
+
+    /// Returns the oldest block height that the node serves, which is above genesis if the node is pruned.
+    pub fn oldest_block_height(&self) -> u32 {
+        self.oldest_block_height.load(Ordering::SeqCst)
+    }
+
+    /// Sets the oldest block height that the node serves, which is advertised to the peers in each `Ping`.
+    pub fn set_oldest_block_height(&self, height: u32) {
+        self.oldest_block_height.store(height, Ordering::SeqCst);
+    }
+
     /// Returns the listener IP address from the (ambiguous) peer address.
     pub fn resolve_to_listener(&self, peer_addr: &SocketAddr) -> Option<SocketAddr> {
         self.resolver.get_listener(peer_addr)
@@ -280,8 +372,39 @@ impl<N: Network> Router<N> {
         self.connecting_peers.lock().contains(ip)
     }
 
+    /// Returns `true` if the node is in whitelist mode.
+    pub fn is_whitelist_mode(&self) -> bool {
+        self.whitelist.is_some()
+    }
+
+    /// Returns `true` if the given peer IP is whitelisted.
+    pub fn is_whitelisted_ip(&self, ip: &SocketAddr) -> bool {
+        self.whitelist.as_ref().map_or(false, |whitelist| whitelist.contains_ip(ip))
+    }
+
+    /// Returns `true` if the given listener IP of a peer is in the peer deny list.
+    pub fn is_denied_peer(&self, peer_ip: &SocketAddr) -> bool {
+        self.peer_deny_list.as_ref().map_or(false, |deny_list| deny_list.contains(peer_ip))
+    }
+
+    /// Returns `true` if the given connected peer IP is whitelisted, either by its IP or by its Aleo address.
+    pub fn is_whitelisted(&self, ip: &SocketAddr) -> bool {
+        match &self.whitelist {
+            Some(whitelist) => {
+                // Retrieve the Aleo address of the connected peer, if it exists.
+                let address = self.connected_peers.read().get(ip).map(|peer| peer.address());
+                whitelist.contains_ip(ip) || address.map_or(false, |address| whitelist.contains_address(&address))
+            }
+            None => false,
+        }
+    }
+
     /// Returns `true` if the given IP is restricted.
     pub fn is_restricted(&self, ip: &SocketAddr) -> bool {
+        // Whitelisted peers are exempt from bans.
+        if self.is_whitelisted(ip) {
+            return false;
+        }
         self.restricted_peers
             .read()
             .get(ip)
@@ -289,6 +412,15 @@ impl<N: Network> Router<N> {
             .unwrap_or(false)
     }
 
+    /// Returns `true` if the given IP recently announced that it is shutting down.
+    pub fn is_backing_off(&self, ip: &SocketAddr) -> bool {
+        self.backoff_peers
+            .read()
+            .get(ip)
+            .map(|time| time.elapsed().as_secs() < Self::RECONNECT_BACKOFF_IN_SECS)
+            .unwrap_or(false)
+    }
+
     /// Returns the maximum number of connected peers.
     pub fn max_connected_peers(&self) -> usize {
         self.tcp.config().max_connections as usize
@@ -369,9 +501,14 @@ impl<N: Network> Router<N> {
         &self.trusted_peers
     }
 
+    /// Returns the whitelist, if the node is in whitelist mode.
+    pub fn whitelist(&self) -> Option<&Whitelist<N>> {
+        self.whitelist.as_ref()
+    }
+
     /// Returns the list of bootstrap peers.
     pub fn bootstrap_peers(&self) -> Vec<SocketAddr> {
-        if cfg!(feature = "test") || self.is_dev {
+        if cfg!(feature = "test") || self.is_dev || self.is_whitelist_mode() {
             vec![]
         } else {
             vec![
@@ -388,6 +525,16 @@ impl<N: Network> Router<N> {
         self.connected_peers.read().iter().map(|(ip, peer)| (*ip, peer.node_type())).collect()
     }
 
+    /// Returns the list of connected peers, their node type, and their advertised node info.
+    pub fn connected_node_info(&self) -> Vec<(SocketAddr, NodeType, NodeInfo)> {
+        self.connected_peers.read().iter().map(|(ip, peer)| (*ip, peer.node_type(), peer.node_info().clone())).collect()
+    }
+
+    /// Returns the list of connected peers and their round-trip time, if it has been measured.
+    pub fn connected_latencies(&self) -> Vec<(SocketAddr, Option<Duration>)> {
+        self.connected_peers.read().iter().map(|(ip, peer)| (*ip, peer.rtt())).collect()
+    }
+
     #[cfg(feature = "metrics")]
     fn update_metrics(&self) {
         metrics::gauge(metrics::router::CONNECTED, self.connected_peers.read().len() as f64);
@@ -406,6 +553,8 @@ impl<N: Network> Router<N> {
         self.candidate_peers.write().remove(&peer_ip);
         // Remove this peer from the restricted peers, if it exists.
         self.restricted_peers.write().remove(&peer_ip);
+        // Remove this peer from the backoff peers, if it exists, as it is evidently back online.
+        self.backoff_peers.write().remove(&peer_ip);
         #[cfg(feature = "metrics")]
         self.update_metrics();
     }
@@ -415,8 +564,13 @@ impl<N: Network> Router<N> {
     /// This method skips adding any given peers if the combined size exceeds the threshold,
     /// as the peer providing this list could be subverting the protocol.
     pub fn insert_candidate_peers(&self, peers: &[SocketAddr]) {
+        // In whitelist mode, the node only connects to whitelisted peers, so there is no need for candidates.
+        if self.is_whitelist_mode() {
+            return;
+        }
         // Compute the maximum number of candidate peers.
-        let max_candidate_peers = Self::MAXIMUM_CANDIDATE_PEERS.saturating_sub(self.number_of_candidate_peers());
+        let max_candidate_peers =
+            self.profile.max_candidate_peers().saturating_sub(self.number_of_candidate_peers());
         // Ensure the combined number of peers does not surpass the threshold.
         let eligible_peers = peers
             .iter()
@@ -434,6 +588,10 @@ impl<N: Network> Router<N> {
 
     /// Inserts the given peer into the restricted peers.
     pub fn insert_restricted_peer(&self, peer_ip: SocketAddr) {
+        // Whitelisted peers are exempt from bans.
+        if self.is_whitelisted(&peer_ip) {
+            return;
+        }
         // Remove this peer from the candidate peers, if it exists.
         self.candidate_peers.write().remove(&peer_ip);
         // Add the peer to the restricted peers.
@@ -442,6 +600,16 @@ impl<N: Network> Router<N> {
         self.update_metrics();
     }
 
+    /// Inserts the given peer into the backoff peers, delaying any reconnection attempt to it.
+    /// Unlike a restriction, this does not prevent the peer from connecting to the node once it is back online.
+    pub fn insert_backoff_peer(&self, peer_ip: SocketAddr) {
+        let mut backoff_peers = self.backoff_peers.write();
+        // Remove the expired entries.
+        backoff_peers.retain(|_, time| time.elapsed().as_secs() < Self::RECONNECT_BACKOFF_IN_SECS);
+        // Add the peer to the backoff peers.
+        backoff_peers.insert(peer_ip, Instant::now());
+    }
+
     /// Updates the connected peer with the given function.
     pub fn update_connected_peer<Fn: FnMut(&mut Peer<N>)>(
         &self,
@@ -461,14 +629,33 @@ impl<N: Network> Router<N> {
         Ok(())
     }
 
+    /// Updates the timestamp of the last `Ping` sent to the given peer.
+    pub fn update_ping_sent(&self, peer_ip: SocketAddr) {
+        if let Some(peer) = self.connected_peers.write().get_mut(&peer_ip) {
+            peer.set_ping_sent(Instant::now());
+        }
+    }
+
+    /// Updates the round-trip time to the given peer upon receiving a `Pong`,
+    /// and returns the smoothed round-trip time, if it has been measured.
+    pub fn update_pong_received(&self, peer_ip: SocketAddr) -> Option<Duration> {
+        self.connected_peers.write().get_mut(&peer_ip).and_then(|peer| peer.set_pong_received(Instant::now()))
+    }
+
     /// Removes the connected peer and adds them to the candidate peers.
     pub fn remove_connected_peer(&self, peer_ip: SocketAddr) {
         // Removes the bidirectional map between the listener address and (ambiguous) peer address.
         self.resolver.remove_peer(&peer_ip);
+        // Remove the known transactions and pending transaction requests of this peer.
+        self.inventory.remove_peer(&peer_ip);
+        // Remove the pending compact block requests to this peer.
+        self.block_relay.remove_peer(&peer_ip);
         // Remove this peer from the connected peers, if it exists.
         self.connected_peers.write().remove(&peer_ip);
-        // Add the peer to the candidate peers.
-        self.candidate_peers.write().insert(peer_ip);
+        // Add the peer to the candidate peers, unless the node is in whitelist mode.
+        if !self.is_whitelist_mode() {
+            self.candidate_peers.write().insert(peer_ip);
+        }
         #[cfg(feature = "metrics")]
         self.update_metrics();
     }
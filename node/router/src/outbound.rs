@@ -13,12 +13,12 @@
 // limitations under the License.
 
 use crate::{
-    messages::{Message, Ping},
+    messages::{BlockAnnouncement, CompactBlock, Message, Ping, TransactionInventory, UnconfirmedTransaction},
     Router,
 };
 use snarkos_node_sync_locators::BlockLocators;
-use snarkos_node_tcp::protocols::Writing;
-use snarkvm::prelude::Network;
+use snarkos_node_tcp::protocols::{Priority, Writing};
+use snarkvm::prelude::{block::Block, Network};
 use std::io;
 
 use std::net::SocketAddr;
@@ -30,7 +30,11 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
 
     /// Sends a "Ping" message to the given peer.
     fn send_ping(&self, peer_ip: SocketAddr, block_locators: Option<BlockLocators<N>>) {
-        self.send(peer_ip, Message::Ping(Ping::new(self.router().node_type(), block_locators)));
+        // Record the time the ping was sent, to measure the round-trip time to the peer.
+        self.router().update_ping_sent(peer_ip);
+        // Retrieve the oldest block height that this node serves.
+        let oldest_block_height = self.router().oldest_block_height();
+        self.send(peer_ip, Message::Ping(Ping::new(self.router().node_type(), oldest_block_height, block_locators)));
     }
 
     /// Sends the given message to specified peer.
@@ -55,6 +59,10 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
         if let Message::BlockRequest(request) = message {
             self.router().cache.insert_outbound_block_request(peer_ip, request);
         }
+        // If the message type is a block headers request, add it to the cache.
+        if let Message::BlockHeadersRequest(request) = message {
+            self.router().cache.insert_outbound_block_headers_request(peer_ip, request);
+        }
         // If the message type is a puzzle request, increment the cache.
         if matches!(message, Message::PuzzleRequest(_)) {
             self.router().cache.increment_outbound_puzzle_requests(peer_ip);
@@ -129,6 +137,48 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
         }
     }
 
+    /// Announces the given unconfirmed transaction to every connected peer that does not know it,
+    /// excluding the sender and any specified peer IPs, and stores it to serve their requests.
+    fn announce_transaction(&self, transaction: UnconfirmedTransaction<N>, excluded_peers: &[SocketAddr]) {
+        let transaction_id = transaction.transaction_id;
+        // Store the transaction, to serve the requests for it.
+        self.router().inventory.insert_transaction(transaction);
+
+        // Prepare the peers to announce to.
+        let connected_peers = self.router().connected_peers();
+        let peers = connected_peers.iter().filter(|peer_ip| {
+            !excluded_peers.contains(peer_ip) && !self.router().inventory.is_known(peer_ip, &transaction_id)
+        });
+
+        // Iterate through all peers that are not the sender, excluded peers, or already aware of the transaction.
+        for peer_ip in peers {
+            // Mark the transaction as known by the peer, to avoid announcing it again.
+            self.router().inventory.insert_known(*peer_ip, [transaction_id]);
+            let message = TransactionInventory { transaction_ids: vec![transaction_id] };
+            self.send(*peer_ip, Message::TransactionInventory(message));
+        }
+    }
+
+    /// Announces the given block to every connected client, excluding the sender and any specified peer IPs.
+    ///
+    /// If compact relay is enabled, the block is sent in compact form, instead of announcing its hash and height.
+    fn announce_block(&self, block: &Block<N>, excluded_peers: &[SocketAddr]) {
+        // Prepare the message.
+        let message = match self.router().is_compact_relay_enabled() {
+            true => Message::CompactBlock(CompactBlock::new(block, &[])),
+            false => Message::BlockAnnouncement(BlockAnnouncement { height: block.height(), block_hash: block.hash() }),
+        };
+
+        // Prepare the peers to send to.
+        let connected_clients = self.router().connected_clients();
+        let peers = connected_clients.iter().filter(|peer_ip| !excluded_peers.contains(peer_ip));
+
+        // Iterate through all clients that are not the sender and excluded peers.
+        for peer_ip in peers {
+            self.send(*peer_ip, message.clone());
+        }
+    }
+
     /// Returns `true` if the message can be sent.
     fn can_send(&self, peer_ip: SocketAddr, message: &Message<N>) -> bool {
         // Ensure the peer is connected before sending.
@@ -148,6 +198,8 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
                 // Update the timestamp for the unconfirmed transaction.
                 let seen_before =
                     self.router().cache.insert_outbound_transaction(peer_ip, message.transaction_id).is_some();
+                // Mark the transaction as known by the peer.
+                self.router().inventory.insert_known(peer_ip, [message.transaction_id]);
                 // Determine whether to send the transaction.
                 !seen_before
             }
@@ -156,3 +208,33 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
         }
     }
 }
+
+/// Returns the priority lane for the given outbound message.
+///
+/// Block announcements and disconnects are sent ahead of sync traffic, while gossip (peer lists,
+/// unconfirmed solutions and transactions, and transaction inventories) is dropped when its lane is full.
+pub fn message_priority<N: Network>(message: &Message<N>) -> Priority {
+    match message {
+        Message::ChallengeRequest(..)
+        | Message::ChallengeResponse(..)
+        | Message::Disconnect(..)
+        | Message::Ping(..)
+        | Message::Pong(..)
+        | Message::BlockAnnouncement(..)
+        | Message::CompactBlock(..) => Priority::High,
+        Message::BlockRequest(..)
+        | Message::BlockResponse(..)
+        | Message::BlocksUnavailable(..)
+        | Message::BlockHeadersRequest(..)
+        | Message::BlockHeadersResponse(..)
+        | Message::CompactBlockRequest(..)
+        | Message::PuzzleRequest(..)
+        | Message::PuzzleResponse(..) => Priority::Normal,
+        Message::PeerRequest(..)
+        | Message::PeerResponse(..)
+        | Message::UnconfirmedSolution(..)
+        | Message::UnconfirmedTransaction(..)
+        | Message::TransactionInventory(..)
+        | Message::TransactionRequest(..) => Priority::Low,
+    }
+}
@@ -12,7 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{messages::Message, Heartbeat, Inbound, Outbound};
+use crate::{
+    messages::{DisconnectReason, Message},
+    Heartbeat,
+    Inbound,
+    Outbound,
+};
 use snarkos_node_tcp::{
     protocols::{Disconnect, Handshake, OnConnect},
     P2P,
@@ -21,6 +26,9 @@ use snarkvm::prelude::Network;
 
 use core::time::Duration;
 
+/// The maximum time in milliseconds to wait for the shutdown notice to be delivered to the connected peers.
+const SHUTDOWN_NOTICE_TIMEOUT_IN_MS: u64 = 1000; // ms
+
 #[async_trait]
 pub trait Routing<N: Network>:
     P2P + Disconnect + OnConnect + Handshake + Inbound<N> + Outbound<N> + Heartbeat<N>
@@ -42,6 +50,26 @@ pub trait Routing<N: Network>:
         self.initialize_report();
     }
 
+    /// Sends a `Disconnect` message to every connected peer, to inform them that the node is shutting down.
+    /// This waits for the messages to be delivered, up to `SHUTDOWN_NOTICE_TIMEOUT_IN_MS`.
+    async fn notify_shutdown(&self) {
+        // Send the disconnect message to every connected peer.
+        let message = Message::Disconnect(DisconnectReason::ShuttingDown.into());
+        let receivers = self
+            .router()
+            .connected_peers()
+            .into_iter()
+            .filter_map(|peer_ip| self.send(peer_ip, message.clone()))
+            .collect::<Vec<_>>();
+        // Wait for the messages to be delivered.
+        let delivery = async move {
+            for receiver in receivers {
+                let _ = receiver.await;
+            }
+        };
+        let _ = tokio::time::timeout(Duration::from_millis(SHUTDOWN_NOTICE_TIMEOUT_IN_MS), delivery).await;
+    }
+
     // Start listening for inbound connections.
     async fn enable_listener(&self) {
         self.tcp().enable_listener().await.expect("Failed to enable the TCP listener");
@@ -23,7 +23,7 @@ use std::{
 };
 
 use snarkos_account::Account;
-use snarkos_node_router::{messages::NodeType, Router};
+use snarkos_node_router::{messages::NodeType, MemoryProfile, Router};
 use snarkvm::prelude::{block::Block, FromBytes, Network, Testnet3 as CurrentNetwork};
 
 /// A helper macro to print the TCP listening address, along with the connected and connecting peers.
@@ -76,6 +76,10 @@ pub async fn client(listening_port: u16, max_peers: u16) -> TestRouter<CurrentNe
         NodeType::Client,
         sample_account(),
         &[],
+        None,
+        None,
+        MemoryProfile::default(),
+        false,
         max_peers,
         true,
     )
@@ -92,6 +96,10 @@ pub async fn prover(listening_port: u16, max_peers: u16) -> TestRouter<CurrentNe
         NodeType::Prover,
         sample_account(),
         &[],
+        None,
+        None,
+        MemoryProfile::default(),
+        false,
         max_peers,
         true,
     )
@@ -108,6 +116,10 @@ pub async fn validator(listening_port: u16, max_peers: u16) -> TestRouter<Curren
         NodeType::Validator,
         sample_account(),
         &[],
+        None,
+        None,
+        MemoryProfile::default(),
+        false,
         max_peers,
         true,
     )
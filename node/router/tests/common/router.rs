@@ -15,7 +15,12 @@
 use crate::common::sample_genesis_block;
 use snarkos_node_router::{
     messages::{
+        BlockAnnouncement,
+        BlockHeadersRequest,
         BlockRequest,
+        BlocksUnavailable,
+        CompactBlock,
+        CompactBlockRequest,
         DisconnectReason,
         Message,
         MessageCodec,
@@ -163,6 +168,36 @@ impl<N: Network> Inbound<N> for TestRouter<N> {
         true
     }
 
+    /// Handles a `BlocksUnavailable` message.
+    fn blocks_unavailable(&self, _peer_ip: SocketAddr, _message: BlocksUnavailable) -> bool {
+        true
+    }
+
+    /// Handles a `BlockHeadersRequest` message.
+    fn block_headers_request(&self, _peer_ip: SocketAddr, _message: BlockHeadersRequest) -> bool {
+        true
+    }
+
+    /// Handles a `BlockHeadersResponse` message.
+    fn block_headers_response(&self, _peer_ip: SocketAddr, _headers: Vec<(N::BlockHash, Header<N>)>) -> bool {
+        true
+    }
+
+    /// Handles a `BlockAnnouncement` message.
+    fn block_announcement(&self, _peer_ip: SocketAddr, _message: BlockAnnouncement<N>) -> bool {
+        true
+    }
+
+    /// Handles a `CompactBlock` message.
+    fn compact_block(&self, _peer_ip: SocketAddr, _message: CompactBlock<N>) -> bool {
+        true
+    }
+
+    /// Handles a `CompactBlockRequest` message.
+    fn compact_block_request(&self, _peer_ip: SocketAddr, _message: CompactBlockRequest<N>) -> bool {
+        true
+    }
+
     /// Handles an `Ping` message.
     fn ping(&self, _peer_ip: SocketAddr, _message: Ping<N>) -> bool {
         true
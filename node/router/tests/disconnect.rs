@@ -108,3 +108,26 @@ async fn test_disconnect_with_handshake() {
     assert_eq!(node1.tcp().num_connected(), 1); // Router 1 has no way of knowing that Router 0 disconnected.
     assert_eq!(node1.tcp().num_connecting(), 0);
 }
+
+#[tokio::test]
+async fn test_shutdown_backoff_is_not_a_restriction() {
+    // Create 2 routers.
+    let node0 = validator(0, 1).await;
+    let node1 = client(0, 1).await;
+
+    // Start listening.
+    node0.tcp().enable_listener().await.unwrap();
+    node1.tcp().enable_listener().await.unwrap();
+
+    // Mark node1 as shutting down, from the point of view of node0.
+    node0.insert_backoff_peer(node1.local_ip());
+    assert!(node0.is_backing_off(&node1.local_ip()));
+    assert!(!node0.is_restricted(&node1.local_ip()));
+
+    // Ensure node0 refrains from reconnecting to node1.
+    assert!(node0.connect(node1.local_ip()).is_none());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(node0.tcp().num_connected(), 0);
+    assert_eq!(node1.tcp().num_connected(), 0);
+}
@@ -12,12 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod pruner;
+pub use pruner::*;
+
 mod router;
 
-use crate::traits::NodeInterface;
+use crate::{traits::NodeInterface, NodeConfig};
 use snarkos_account::Account;
 use snarkos_node_bft::ledger_service::CoreLedgerService;
-use snarkos_node_rest::Rest;
+use snarkos_node_rest::{AdminServer, NodeAdmin, Rest};
 use snarkos_node_router::{
     messages::{Message, NodeType, UnconfirmedSolution},
     Heartbeat,
@@ -26,7 +29,8 @@ use snarkos_node_router::{
     Router,
     Routing,
 };
-use snarkos_node_sync::{BlockSync, BlockSyncMode};
+use snarkos_node_storage::{AddressIndex, BackupConfig, LedgerCache};
+use snarkos_node_sync::{BlockSync, BlockSyncMode, SyncStatus};
 use snarkos_node_tcp::{
     protocols::{Disconnect, Handshake, OnConnect, Reading, Writing},
     P2P,
@@ -41,11 +45,12 @@ use snarkvm::{
     },
 };
 
-use anyhow::Result;
+use anyhow::{bail, ensure, Result};
 use core::future::Future;
 use parking_lot::Mutex;
 use std::{
     net::SocketAddr,
+    path::PathBuf,
     sync::{atomic::AtomicBool, Arc},
 };
 use tokio::task::JoinHandle;
@@ -55,12 +60,20 @@ use tokio::task::JoinHandle;
 pub struct Client<N: Network, C: ConsensusStorage<N>> {
     /// The ledger of the node.
     ledger: Ledger<N, C>,
+    /// The cache of the recent blocks, block headers, and transactions of the ledger.
+    ledger_cache: Arc<LedgerCache<N>>,
+    /// The configuration of the ledger backups, if backups are enabled.
+    backups: Option<BackupConfig>,
+    /// The address index, if it is enabled.
+    address_index: Option<Arc<AddressIndex>>,
     /// The router of the node.
     router: Router<N>,
     /// The REST server of the node.
     rest: Option<Rest<N, C, Self>>,
     /// The sync module.
     sync: Arc<BlockSync<N>>,
+    /// The pruner of the ledger, if the node is in pruned mode.
+    pruner: Option<Arc<Pruner<N, C>>>,
     /// The genesis block.
     genesis: Block<N>,
     /// The coinbase puzzle.
@@ -73,24 +86,28 @@ pub struct Client<N: Network, C: ConsensusStorage<N>> {
 
 impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
     /// Initializes a new client node.
-    pub async fn new(
-        node_ip: SocketAddr,
-        rest_ip: Option<SocketAddr>,
-        account: Account<N>,
-        trusted_peers: &[SocketAddr],
-        genesis: Block<N>,
-        cdn: Option<String>,
-        dev: Option<u16>,
-    ) -> Result<Self> {
+    pub async fn new(account: Account<N>, genesis: Block<N>, config: NodeConfig<N>) -> Result<Self> {
+        let dev = config.dev;
         // Initialize the signal handler.
         let signal_node = Self::handle_signals();
 
         // Initialize the ledger.
-        let ledger = Ledger::<N, C>::load(genesis.clone(), dev)?;
+        let ledger = match config.prune_depth {
+            // A pruned ledger is loaded without the spot check of random blocks, as their bodies may be pruned.
+            Some(_) => {
+                let ledger = Ledger::<N, C>::load_unchecked(genesis.clone(), dev)?;
+                // Ensure the ledger contains the correct genesis block.
+                ensure!(ledger.contains_block_hash(&genesis.hash())?, "Incorrect genesis block (run 'snarkos clean')");
+                ledger
+            }
+            None => Ledger::<N, C>::load(genesis.clone(), dev)?,
+        };
         // TODO: Remove me after Phase 3.
         let ledger = crate::phase_3_reset(ledger, dev)?;
+        // Ensure the block tree of the ledger matches its persisted state root, or rebuild it.
+        let ledger = crate::load_block_tree(ledger, dev)?;
         // Initialize the CDN.
-        if let Some(base_url) = cdn {
+        if let Some(base_url) = config.cdn {
             // Sync the ledger with the CDN.
             if let Err((_, error)) = snarkos_node_cdn::sync_ledger_with_cdn(&base_url, ledger.clone()).await {
                 crate::log_clean_error(dev);
@@ -98,29 +115,51 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
             }
         }
 
-        // Initialize the ledger service.
-        let ledger_service = Arc::new(CoreLedgerService::<N, C>::new(ledger.clone()));
+        // Initialize the pruner, if the node is in pruned mode.
+        let pruner = match config.prune_depth {
+            Some(depth) => Some(Arc::new(Pruner::new(ledger.clone(), depth)?)),
+            None => None,
+        };
+
+        // Catch up the address index with the ledger, if it is enabled.
+        let address_index = crate::load_address_index(&ledger, config.address_index.clone()).await?;
+        // Initialize the ledger service, which updates the address index as each block is added.
+        let ledger_service = Arc::new(CoreLedgerService::<N, C>::new(ledger.clone(), address_index));
         // Initialize the sync module.
         let sync = BlockSync::new(BlockSyncMode::Router, ledger_service.clone());
+        // Download the header chain ahead of the blocks, to learn the target height and chain shape early.
+        sync.enable_header_first();
 
         // Initialize the node router.
         let router = Router::new(
-            node_ip,
+            config.node_ip,
             NodeType::Client,
             account,
-            trusted_peers,
+            &config.trusted_peers,
+            config.whitelist,
+            config.peer_deny_list.clone(),
+            config.memory_profile,
+            config.compact_relay,
             Self::MAXIMUM_NUMBER_OF_PEERS as u16,
             dev.is_some(),
         )
         .await?;
+        // Advertise the oldest block that this node serves, if the ledger was pruned.
+        if let Some(pruner) = &pruner {
+            router.set_oldest_block_height(pruner.oldest_block_height());
+        }
         // Load the coinbase puzzle.
         let coinbase_puzzle = CoinbasePuzzle::<N>::load()?;
         // Initialize the node.
         let mut node = Self {
             ledger: ledger.clone(),
+            ledger_cache: Arc::new(LedgerCache::new(config.ledger_cache)),
+            backups: config.backups,
+            address_index: config.address_index,
             router,
             rest: None,
             sync: Arc::new(sync),
+            pruner,
             genesis,
             coinbase_puzzle,
             handles: Default::default(),
@@ -128,8 +167,24 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
         };
 
         // Initialize the REST server.
-        if let Some(rest_ip) = rest_ip {
-            node.rest = Some(Rest::start(rest_ip, None, ledger.clone(), Arc::new(node.clone()))?);
+        if let Some(rest_ip) = config.rest_ip {
+            let sync = (*node.sync).clone();
+            let (ledger, ledger_cache) = (ledger.clone(), node.ledger_cache.clone());
+            let (backups, address_index) = (node.backups.clone(), node.address_index.clone());
+            let block_events = ledger_service.block_events().clone();
+            let routing = Arc::new(node.clone());
+            node.rest = Some(Rest::start(
+                rest_ip,
+                config.rest,
+                None,
+                ledger,
+                ledger_cache,
+                backups,
+                address_index,
+                sync,
+                block_events,
+                routing,
+            )?);
         }
         // Initialize the routing.
         node.initialize_routing().await;
@@ -137,6 +192,22 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
         node.initialize_sync();
         // Initialize the notification message loop.
         node.handles.lock().push(crate::start_notification_message_loop());
+        // Initialize the peer deny list loop, if the peer deny list is enabled.
+        let router = node.router.clone();
+        node.handles
+            .lock()
+            .extend(crate::start_peer_deny_list_loop(config.peer_deny_list, move || router.disconnect_denied_peers()));
+        // Initialize the storage maintenance loop, if the storage maintenance is enabled.
+        let sync = node.sync.clone();
+        node.handles.lock().extend(crate::start_storage_maintenance_loop::<N>(
+            dev,
+            config.min_free_disk_space,
+            move || sync.is_block_synced(),
+        ));
+        // Initialize the admin server, if it is enabled.
+        if let Some(admin) = config.admin {
+            node.handles.lock().push(AdminServer::start(admin, Arc::new(node.clone()))?);
+        }
         // Pass the node to the signal handler.
         let _ = signal_node.set(node.clone());
         // Return the node.
@@ -152,6 +223,19 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
     pub fn rest(&self) -> &Option<Rest<N, C, Self>> {
         &self.rest
     }
+
+    /// Returns the status of the block sync.
+    pub fn sync_status(&self) -> SyncStatus {
+        self.sync.status()
+    }
+}
+
+impl<N: Network, C: ConsensusStorage<N>> NodeAdmin<N> for Client<N, C> {
+    /// Creates a backup of the ledger, and returns its path.
+    fn backup_ledger(&self) -> Result<PathBuf> {
+        let Some(backups) = &self.backups else { bail!("Ledger backups are not enabled on this node") };
+        snarkos_node_storage::create_backup(&self.ledger, backups.backup_dir(), backups.max_backups())
+    }
 }
 
 impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
@@ -171,10 +255,39 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
                 tokio::time::sleep(std::time::Duration::from_secs(5)).await;
                 // Perform the sync routine.
                 node.sync.try_block_sync(&node).await;
+                // Log a summary of the sync status, if the node is behind.
+                if let Some(status) = node.sync.status_summary() {
+                    info!("{status}");
+                }
+                // Prune the bodies of the old blocks, if the node is in pruned mode.
+                // Note: The router stops serving the pruned blocks before their bodies are deleted.
+                if let Some(pruner) = &node.pruner {
+                    let (pruner, router) = (pruner.clone(), node.router.clone());
+                    let publish = move |height| router.set_oldest_block_height(height);
+                    if let Ok(Err(error)) = tokio::task::spawn_blocking(move || pruner.prune(publish)).await {
+                        warn!("Failed to prune the ledger - {error}");
+                    }
+                }
             }
         }));
     }
 
+    /// Announces the latest block to the connected clients, excluding the given peer IPs,
+    /// if the ledger has advanced past the given height and the node is synced.
+    fn announce_latest_block(&self, previous_height: u32, excluded_peers: &[SocketAddr]) {
+        // Retrieve the latest height.
+        let latest_height = self.ledger.latest_height();
+        // Ensure the ledger has advanced, and the node is not catching up with the network.
+        if latest_height <= previous_height || !self.sync.is_block_synced() {
+            return;
+        }
+        // Announce the latest block.
+        match self.ledger.get_block(latest_height) {
+            Ok(block) => self.announce_block(&block, excluded_peers),
+            Err(error) => warn!("Failed to retrieve block {latest_height} to announce - {error}"),
+        }
+    }
+
     /// Spawns a task with the given future; it should only be used for long-running tasks.
     pub fn spawn<T: Future<Output = ()> + Send + 'static>(&self, future: T) {
         self.handles.lock().push(tokio::spawn(future));
@@ -195,6 +308,10 @@ impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Client<N, C> {
         trace!("Shutting down the validator...");
         self.handles.lock().iter().for_each(|handle| handle.abort());
 
+        // Inform the connected peers that the node is shutting down.
+        trace!("Notifying the peers...");
+        self.notify_shutdown().await;
+
         // Shut down the router.
         self.router.shut_down().await;
 
@@ -0,0 +1,158 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_sync::locators::CHECKPOINT_INTERVAL;
+use snarkvm::{
+    console::network::Network,
+    ledger::{
+        store::{
+            helpers::{Map, MapRead},
+            BlockStorage,
+            ConsensusStorage,
+        },
+        Ledger,
+    },
+};
+
+use anyhow::{ensure, Result};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// The number of blocks below the latest block height at which a checkpoint is treated as final.
+pub const FINALITY_DEPTH: u32 = 10; // 10 blocks
+/// The minimum number of recent blocks that a pruned client retains in full.
+pub const MIN_PRUNE_DEPTH: u32 = 1_000; // 1000 blocks
+/// The maximum number of blocks that are pruned at a time, to bound the time spent pruning.
+const MAX_BLOCKS_PER_PRUNE: u32 = 1_000; // 1000 blocks
+
+/// The pruner of a client ledger, which deletes the bodies of the blocks beyond the prune depth.
+///
+/// A pruned block retains its hash, header, and state root, which are needed to serve block locators
+/// and block headers, and to verify state paths. Its transactions are also retained, as their transitions
+/// hold the commitments and serial numbers that are needed to validate new blocks. Its authority,
+/// ratifications, and solutions are deleted, so the block itself can no longer be served to peers.
+pub struct Pruner<N: Network, C: ConsensusStorage<N>> {
+    /// The ledger of the node.
+    ledger: Ledger<N, C>,
+    /// The handle to the block storage of the ledger, which is used to delete the block bodies.
+    storage: C::BlockStorage,
+    /// The number of recent blocks that are retained in full.
+    depth: u32,
+    /// The oldest block height that is retained in full.
+    oldest_block_height: AtomicU32,
+}
+
+impl<N: Network, C: ConsensusStorage<N>> Pruner<N, C> {
+    /// Initializes a new pruner for the given ledger, retaining the given number of recent blocks in full.
+    pub fn new(ledger: Ledger<N, C>, depth: u32) -> Result<Self> {
+        // Ensure the prune depth retains enough blocks to validate new blocks and serve recent blocks to peers.
+        ensure!(depth >= MIN_PRUNE_DEPTH, "The prune depth must be at least {MIN_PRUNE_DEPTH} blocks");
+        // Open a handle to the block storage of the ledger.
+        let storage = C::BlockStorage::open(ledger.vm().block_store().dev())?;
+        let pruner = Self { ledger, storage, depth, oldest_block_height: Default::default() };
+        // Find the oldest block that is retained in full, if the ledger was pruned before.
+        pruner.oldest_block_height.store(pruner.find_oldest_block_height()?, Ordering::SeqCst);
+        Ok(pruner)
+    }
+
+    /// Returns the oldest block height that is retained in full, which is `0` if the ledger has not been pruned.
+    pub fn oldest_block_height(&self) -> u32 {
+        self.oldest_block_height.load(Ordering::SeqCst)
+    }
+
+    /// Returns the height below which the block bodies may be pruned, for the given latest height and prune depth.
+    ///
+    /// The boundary is at least `depth` blocks below the latest height, and never crosses the latest checkpoint
+    /// that is final, i.e. that is at least `FINALITY_DEPTH` blocks below the latest height.
+    pub fn prune_boundary(latest_height: u32, depth: u32) -> u32 {
+        // Retain the blocks within the prune depth.
+        let depth_boundary = latest_height.saturating_sub(depth);
+        // Retain the blocks above the latest final checkpoint.
+        let final_height = latest_height.saturating_sub(FINALITY_DEPTH);
+        let checkpoint_boundary = final_height / CHECKPOINT_INTERVAL * CHECKPOINT_INTERVAL;
+        depth_boundary.min(checkpoint_boundary)
+    }
+
+    /// Prunes the bodies of the blocks below the prune boundary, and returns the number of pruned blocks.
+    /// The new oldest block height is published with the given closure before any block is deleted,
+    /// so that the node stops serving the blocks before they are pruned.
+    /// Note: The genesis block is never pruned.
+    pub fn prune(&self, publish_oldest_block_height: impl FnOnce(u32)) -> Result<u32> {
+        // Determine the range of blocks to prune.
+        let boundary = Self::prune_boundary(self.ledger.latest_height(), self.depth);
+        let start = self.oldest_block_height().max(1);
+        let end = boundary.min(start.saturating_add(MAX_BLOCKS_PER_PRUNE));
+        if start >= end {
+            return Ok(0);
+        }
+        // Update and publish the oldest block height first, so the blocks are no longer served while they are pruned.
+        self.oldest_block_height.store(end, Ordering::SeqCst);
+        publish_oldest_block_height(end);
+        // Delete the bodies of the blocks.
+        for height in start..end {
+            let hash = self.ledger.get_hash(height)?;
+            self.storage.authority_map().remove(&hash)?;
+            self.storage.ratifications_map().remove(&hash)?;
+            self.storage.solutions_map().remove(&hash)?;
+        }
+        debug!("Pruned blocks {start} to {}", end - 1);
+        Ok(end - start)
+    }
+
+    /// Returns `true` if the block at the given height is retained in full.
+    fn is_retained(&self, height: u32) -> Result<bool> {
+        self.storage.ratifications_map().contains_key_confirmed(&self.ledger.get_hash(height)?)
+    }
+
+    /// Returns the oldest block height that is retained in full, which is `0` if the ledger has not been pruned.
+    fn find_oldest_block_height(&self) -> Result<u32> {
+        // If the first block is retained, the ledger has not been pruned.
+        let latest_height = self.ledger.latest_height();
+        if latest_height == 0 || self.is_retained(1)? {
+            return Ok(0);
+        }
+        // Otherwise, search for the oldest retained block, as the blocks are pruned in ascending order.
+        // Note: The block at `low` is pruned, and the block at `high` is retained, as the latest block is never pruned.
+        let (mut low, mut high) = (1, latest_height);
+        while high - low > 1 {
+            let middle = low + (high - low) / 2;
+            match self.is_retained(middle)? {
+                true => high = middle,
+                false => low = middle,
+            }
+        }
+        Ok(high)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+    type CurrentConsensusStore = snarkvm::ledger::store::helpers::memory::ConsensusMemory<CurrentNetwork>;
+
+    #[test]
+    fn test_prune_boundary() {
+        let prune_boundary = Pruner::<CurrentNetwork, CurrentConsensusStore>::prune_boundary;
+        // Ensure nothing is pruned before the first checkpoint is final.
+        assert_eq!(prune_boundary(5_000, MIN_PRUNE_DEPTH), 0);
+        assert_eq!(prune_boundary(CHECKPOINT_INTERVAL + FINALITY_DEPTH - 1, MIN_PRUNE_DEPTH), 0);
+        // Ensure pruning never crosses the latest final checkpoint.
+        assert_eq!(prune_boundary(3 * CHECKPOINT_INTERVAL - 1, MIN_PRUNE_DEPTH), 2 * CHECKPOINT_INTERVAL);
+        // Ensure pruning never crosses the prune depth.
+        let latest_height = CHECKPOINT_INTERVAL + FINALITY_DEPTH;
+        assert_eq!(prune_boundary(latest_height, MIN_PRUNE_DEPTH), latest_height - MIN_PRUNE_DEPTH);
+        assert_eq!(prune_boundary(3 * CHECKPOINT_INTERVAL, 5 * CHECKPOINT_INTERVAL), 0);
+    }
+}
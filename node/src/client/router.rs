@@ -14,26 +14,35 @@
 
 use super::*;
 use snarkos_node_router::{
+    message_priority,
     messages::{
+        BlockAnnouncement,
+        BlockHeadersRequest,
+        BlockHeadersResponse,
         BlockRequest,
         BlockResponse,
+        BlocksUnavailable,
+        CompactBlock,
+        CompactBlockRequest,
         DataBlocks,
         DisconnectReason,
         MessageCodec,
         Ping,
         Pong,
         PuzzleResponse,
+        TransactionInventory,
         UnconfirmedTransaction,
     },
     Routing,
 };
-use snarkos_node_tcp::{Connection, ConnectionSide, Tcp};
+use snarkos_node_consensus::{check_transaction_expiration, MAX_TRANSACTION_VALIDITY_IN_BLOCKS};
+use snarkos_node_tcp::{protocols::Priority, Connection, ConnectionSide, Tcp};
 use snarkvm::{
     ledger::narwhal::Data,
     prelude::{block::Transaction, Network},
 };
 
-use snarkos_node_sync::communication_service::CommunicationService;
+use snarkos_node_sync::{communication_service::CommunicationService, MAX_ORPHAN_BLOCKS};
 use std::{io, net::SocketAddr, time::Duration};
 
 impl<N: Network, C: ConsensusStorage<N>> P2P for Client<N, C> {
@@ -100,6 +109,11 @@ impl<N: Network, C: ConsensusStorage<N>> Writing for Client<N, C> {
     fn codec(&self, _addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
         Default::default()
     }
+
+    /// Returns the priority lane that the given outbound message is queued in.
+    fn priority(&self, message: &Self::Message) -> Priority {
+        message_priority(message)
+    }
 }
 
 #[async_trait]
@@ -139,6 +153,12 @@ impl<N: Network, C: ConsensusStorage<N>> CommunicationService for Client<N, C> {
         Message::BlockRequest(BlockRequest { start_height, end_height })
     }
 
+    /// Prepares a block headers request to be sent.
+    fn prepare_block_headers_request(start_height: u32, end_height: u32) -> Option<Self::Message> {
+        debug_assert!(start_height < end_height, "Invalid block headers request format");
+        Some(Message::BlockHeadersRequest(BlockHeadersRequest { start_height, end_height }))
+    }
+
     /// Sends the given message to specified peer.
     ///
     /// This function returns as soon as the message is queued to be sent,
@@ -171,8 +191,15 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Client<N, C> {
     fn block_request(&self, peer_ip: SocketAddr, message: BlockRequest) -> bool {
         let BlockRequest { start_height, end_height } = &message;
 
+        // If this node does not serve the requested blocks, respond that they are unavailable.
+        let oldest_block_height = self.router().oldest_block_height();
+        if *start_height < oldest_block_height || *end_height > self.ledger.latest_height() + 1 {
+            let response = BlocksUnavailable { request: message, oldest_block_height };
+            Outbound::send(self, peer_ip, Message::BlocksUnavailable(response));
+            return true;
+        }
         // Retrieve the blocks within the requested range.
-        let blocks = match self.ledger.get_blocks(*start_height..*end_height) {
+        let blocks = match self.ledger_cache.get_blocks(&self.ledger, *start_height..*end_height) {
             Ok(blocks) => Data::Object(DataBlocks(blocks)),
             Err(error) => {
                 error!("Failed to retrieve blocks {start_height} to {end_height} from the ledger - {error}");
@@ -186,8 +213,67 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Client<N, C> {
 
     /// Handles a `BlockResponse` message.
     fn block_response(&self, peer_ip: SocketAddr, blocks: Vec<Block<N>>) -> bool {
-        // Tries to advance with blocks from the sync module.
-        match self.sync.advance_with_sync_blocks(peer_ip, blocks) {
+        // Insert the blocks into the sync pool.
+        if let Err(error) = self.sync.process_block_response(peer_ip, blocks) {
+            warn!("{error}");
+            return false;
+        }
+        // Advance with the sync blocks in a dedicated task, so that the messages of the peer continue to be processed.
+        let self_ = self.clone();
+        tokio::spawn(async move {
+            // Retrieve the latest height, to announce the new blocks.
+            let previous_height = self_.ledger.latest_height();
+            // Tries to advance with blocks from the sync module.
+            if let Err(error) = self_.sync.advance_with_sync_blocks().await {
+                warn!("{error}");
+            }
+            // If the peer served an invalid block, restrict and disconnect the peer.
+            if self_.sync.is_peer_banned(&peer_ip) {
+                self_.router().insert_restricted_peer(peer_ip);
+                self_.router().disconnect(peer_ip);
+                return;
+            }
+            // Announce the latest block, if the ledger advanced.
+            self_.announce_latest_block(previous_height, &[peer_ip]);
+        });
+        true
+    }
+
+    /// Handles a `BlocksUnavailable` message.
+    fn blocks_unavailable(&self, peer_ip: SocketAddr, message: BlocksUnavailable) -> bool {
+        let BlocksUnavailable { request, oldest_block_height } = message;
+        // Request the blocks from other peers.
+        self.sync.process_blocks_unavailable(peer_ip, request.start_height..request.end_height, oldest_block_height);
+        true
+    }
+
+    /// Retrieves the block headers within the block headers request range, and returns them to the peer.
+    fn block_headers_request(&self, peer_ip: SocketAddr, message: BlockHeadersRequest) -> bool {
+        let BlockHeadersRequest { start_height, end_height } = message;
+
+        // Retrieve the previous block hash and the header of each block within the requested range.
+        let headers = match (start_height..end_height)
+            .map(|height| {
+                Ok((self.ledger.get_previous_hash(height)?, self.ledger_cache.get_header(&self.ledger, height)?))
+            })
+            .collect::<Result<Vec<_>>>()
+        {
+            Ok(headers) => headers,
+            Err(error) => {
+                error!("Failed to retrieve block headers {start_height} to {end_height} from the ledger - {error}");
+                return false;
+            }
+        };
+        // Send the `BlockHeadersResponse` message to the peer.
+        let response = BlockHeadersResponse { request: message, headers };
+        Outbound::send(self, peer_ip, Message::BlockHeadersResponse(response));
+        true
+    }
+
+    /// Handles a `BlockHeadersResponse` message.
+    fn block_headers_response(&self, peer_ip: SocketAddr, headers: Vec<(N::BlockHash, Header<N>)>) -> bool {
+        // Extends the header chain of the sync module.
+        match self.sync.process_block_headers_response(peer_ip, headers) {
             Ok(()) => true,
             Err(error) => {
                 warn!("{error}");
@@ -196,15 +282,122 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Client<N, C> {
         }
     }
 
+    /// Handles a `BlockAnnouncement` message.
+    fn block_announcement(&self, peer_ip: SocketAddr, message: BlockAnnouncement<N>) -> bool {
+        let BlockAnnouncement { height, block_hash } = message;
+        // If the announced block is the next block, or within the orphan window, request it in compact form
+        // from the announcer. The parents of an orphan block are then requested from the announcer in turn.
+        let latest_height = self.ledger.latest_height();
+        let is_relayable = height > latest_height && height <= latest_height + MAX_ORPHAN_BLOCKS;
+        if is_relayable
+            && !self.sync.contains_orphan_block(&block_hash)
+            && self.router().block_relay().insert_block_request(peer_ip, block_hash)
+        {
+            let request = CompactBlockRequest { block_hash, transaction_ids: vec![] };
+            Outbound::send(self, peer_ip, Message::CompactBlockRequest(request));
+        }
+        true
+    }
+
+    /// Handles a `CompactBlock` message.
+    fn compact_block(&self, peer_ip: SocketAddr, compact_block: CompactBlock<N>) -> bool {
+        // If the block is not the next block, or within the orphan window, leave it to the sync module.
+        let latest_height = self.ledger.latest_height();
+        if compact_block.height() <= latest_height || compact_block.height() > latest_height + MAX_ORPHAN_BLOCKS {
+            return true;
+        }
+        let block_hash = compact_block.block_hash;
+        let inventory = self.router().inventory();
+
+        // If transactions are missing from the inventory, request them from the sender (once per block).
+        let missing = compact_block.missing_transaction_ids(|id| inventory.get_transaction(id).is_some());
+        if !missing.is_empty() {
+            if missing.len() <= TransactionInventory::<N>::MAXIMUM_NUMBER_OF_TRANSACTIONS
+                && self.router().block_relay().insert_transactions_request(peer_ip, block_hash)
+            {
+                let request = CompactBlockRequest { block_hash, transaction_ids: missing };
+                Outbound::send(self, peer_ip, Message::CompactBlockRequest(request));
+            }
+            return true;
+        }
+
+        // Reconstruct the block, with the transactions from the inventory.
+        let lookup = |id: &N::TransactionID| inventory.get_transaction(id)?.transaction.deserialize_blocking().ok();
+        let block = match compact_block.into_block(lookup) {
+            Ok(block) => block,
+            Err(error) => {
+                warn!("Peer '{peer_ip}' sent an invalid compact block - {error}");
+                return false;
+            }
+        };
+        self.router().block_relay().remove_request(&block_hash);
+
+        // If the parent block is unknown, hold the block in the orphan pool, and request the parent from the peer.
+        if block.height() > self.ledger.latest_height() + 1 {
+            let previous_hash = block.previous_hash();
+            if self.sync.insert_orphan_block(peer_ip, block)
+                && !self.sync.contains_orphan_block(&previous_hash)
+                && self.router().block_relay().insert_block_request(peer_ip, previous_hash)
+            {
+                let request = CompactBlockRequest { block_hash: previous_hash, transaction_ids: vec![] };
+                Outbound::send(self, peer_ip, Message::CompactBlockRequest(request));
+            }
+            return true;
+        }
+
+        // Retrieve the latest height, to announce the new block.
+        let previous_height = self.ledger.latest_height();
+        // Tries to advance with the relayed block.
+        match self.sync.advance_with_relayed_block(peer_ip, &block) {
+            Ok(_) => {
+                // Announce the latest block, if the ledger advanced.
+                self.announce_latest_block(previous_height, &[peer_ip]);
+                true
+            }
+            Err(error) => {
+                warn!("{error}");
+                // If the peer relayed an invalid block, restrict the peer.
+                if self.sync.is_peer_banned(&peer_ip) {
+                    self.router().insert_restricted_peer(peer_ip);
+                }
+                false
+            }
+        }
+    }
+
+    /// Handles a `CompactBlockRequest` message.
+    fn compact_block_request(&self, peer_ip: SocketAddr, message: CompactBlockRequest<N>) -> bool {
+        let CompactBlockRequest { block_hash, transaction_ids } = message;
+        // Retrieve the requested block.
+        let block =
+            self.ledger.get_height(&block_hash).and_then(|height| self.ledger_cache.get_block(&self.ledger, height));
+        let block = match block {
+            Ok(block) => block,
+            Err(error) => {
+                debug!("Unable to serve the compact block request from '{peer_ip}' - {error}");
+                return true;
+            }
+        };
+        // Send the `CompactBlock` message to the peer, with the missing transactions in full.
+        Outbound::send(self, peer_ip, Message::CompactBlock(CompactBlock::new(&block, &transaction_ids)));
+        true
+    }
+
     /// Processes the block locators and sends back a `Pong` message.
     fn ping(&self, peer_ip: SocketAddr, message: Ping<N>) -> bool {
         // Check if the sync module is in router mode.
         if self.sync.mode().is_router() {
+            // Update the oldest block height that the peer serves.
+            self.sync.update_peer_oldest_height(peer_ip, message.oldest_block_height);
             // If block locators were provided, then update the peer in the sync pool.
             if let Some(block_locators) = message.block_locators {
                 // Check the block locators are valid, and update the peer in the sync pool.
                 if let Err(error) = self.sync.update_peer_locators(peer_ip, block_locators) {
                     warn!("Peer '{peer_ip}' sent invalid block locators: {error}");
+                    // If the peer served an invalid block, restrict the peer.
+                    if self.sync.is_peer_banned(&peer_ip) {
+                        self.router().insert_restricted_peer(peer_ip);
+                    }
                     return false;
                 }
             }
@@ -217,6 +410,11 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Client<N, C> {
 
     /// Sleeps for a period and then sends a `Ping` message to the peer.
     fn pong(&self, peer_ip: SocketAddr, _message: Pong) -> bool {
+        // Update the round-trip time to the peer in the sync pool.
+        if let Some(rtt) = self.router().get_connected_peer(&peer_ip).and_then(|peer| peer.rtt()) {
+            self.sync.update_peer_latency(peer_ip, rtt);
+        }
+
         // Spawn an asynchronous task for the `Ping` request.
         let self_ = self.clone();
         tokio::spawn(async move {
@@ -303,10 +501,20 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Client<N, C> {
         if transaction.is_fee() {
             return true; // Maintain the connection.
         }
+        // Check that the transaction has not expired, and that its expiration height is within bounds.
+        if let Some(expiration_height) = serialized.expiration_height {
+            let latest_height = self.ledger.latest_height();
+            if let Err(error) =
+                check_transaction_expiration(latest_height, expiration_height, MAX_TRANSACTION_VALIDITY_IN_BLOCKS)
+            {
+                trace!("[UnconfirmedTransaction] {error}");
+                return true; // Maintain the connection.
+            }
+        }
         // Check that the transaction is well-formed and unique.
         if self.ledger.check_transaction_basic(&transaction, None, &mut rand::thread_rng()).is_ok() {
-            // Propagate the `UnconfirmedTransaction`.
-            self.propagate(Message::UnconfirmedTransaction(serialized), &[peer_ip]);
+            // Announce the `UnconfirmedTransaction` to the peers that do not know it.
+            self.announce_transaction(serialized, &[peer_ip]);
         }
         true
     }
@@ -0,0 +1,113 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::StorageMode;
+use snarkos_node_bft::PrimaryConfig;
+use snarkos_node_consensus::{MAX_MEMPOOL_SIZE_IN_BYTES, MAX_TRANSACTION_VALIDITY_IN_BLOCKS};
+use snarkos_node_rest::{AdminConfig, RestConfig};
+use snarkos_node_router::{MemoryProfile, Whitelist};
+use snarkos_node_storage::{AddressIndex, BackupConfig, LedgerCacheConfig};
+use snarkos_node_tcp::PeerDenyList;
+use snarkvm::prelude::Network;
+
+use std::{net::SocketAddr, path::PathBuf, sync::Arc};
+
+/// The configuration of a node, which is passed to the node on initialization.
+///
+/// The options that do not apply to the node type are ignored, e.g. the trusted validators of a client.
+pub struct NodeConfig<N: Network> {
+    /// The IP of the node server.
+    pub node_ip: SocketAddr,
+    /// The IP of the REST server, if the REST server is enabled.
+    pub rest_ip: Option<SocketAddr>,
+    /// The configuration of the REST server.
+    pub rest: RestConfig,
+    /// The configuration of the admin server, if the admin server is enabled.
+    pub admin: Option<AdminConfig>,
+    /// The IP of the BFT server, if it is overridden.
+    pub bft_ip: Option<SocketAddr>,
+    /// The IPs of the trusted peers.
+    pub trusted_peers: Vec<SocketAddr>,
+    /// The whitelist of the peers, if the node only connects to whitelisted peers.
+    pub whitelist: Option<Whitelist<N>>,
+    /// The IPs of the trusted validators.
+    pub trusted_validators: Vec<SocketAddr>,
+    /// The peer deny list, if it is enabled.
+    pub peer_deny_list: Option<Arc<PeerDenyList>>,
+    /// The memory profile of the node, which determines the footprint of its caches.
+    pub memory_profile: MemoryProfile,
+    /// The boolean flag for whether new blocks are relayed to peers in compact form, instead of announced.
+    pub compact_relay: bool,
+    /// The maximum number of blocks for which an unconfirmed transaction is valid.
+    pub max_transaction_validity: u32,
+    /// The maximum size of the mempool in bytes.
+    pub max_mempool_size: usize,
+    /// The path to persist the mempool of a validator to, if the mempool persistence is enabled.
+    pub mempool_snapshot_path: Option<PathBuf>,
+    /// The configuration of the primary of a validator.
+    pub primary: PrimaryConfig,
+    /// The IP on which the active validator serves heartbeats, if the validator is a standby.
+    pub standby: Option<SocketAddr>,
+    /// The IP on which the validator serves heartbeats to its standby, if heartbeats are enabled.
+    pub heartbeat_ip: Option<SocketAddr>,
+    /// The base URL of the CDN to sync the ledger from, if enabled.
+    pub cdn: Option<String>,
+    /// The number of recent blocks that a pruned client retains, if pruning is enabled.
+    pub prune_depth: Option<u32>,
+    /// The storage of the ledger.
+    pub storage_mode: StorageMode,
+    /// The sizes of the ledger cache.
+    pub ledger_cache: LedgerCacheConfig,
+    /// The configuration of the ledger backups, if backups are enabled.
+    pub backups: Option<BackupConfig>,
+    /// The address index, which is updated as each block is added, if it is enabled.
+    pub address_index: Option<Arc<AddressIndex>>,
+    /// The free disk space below which the storage is degraded (in bytes), if the storage maintenance is enabled.
+    pub min_free_disk_space: Option<u64>,
+    /// The ID of the node in development mode, if enabled.
+    pub dev: Option<u16>,
+}
+
+impl<N: Network> NodeConfig<N> {
+    /// Initializes a new configuration for a node listening at the given IP, with the default options.
+    pub fn new(node_ip: SocketAddr) -> Self {
+        Self {
+            node_ip,
+            rest_ip: None,
+            rest: RestConfig::default(),
+            admin: None,
+            bft_ip: None,
+            trusted_peers: Default::default(),
+            whitelist: None,
+            trusted_validators: Default::default(),
+            peer_deny_list: None,
+            memory_profile: MemoryProfile::default(),
+            compact_relay: false,
+            max_transaction_validity: MAX_TRANSACTION_VALIDITY_IN_BLOCKS,
+            max_mempool_size: MAX_MEMPOOL_SIZE_IN_BYTES,
+            mempool_snapshot_path: None,
+            primary: PrimaryConfig::default(),
+            standby: None,
+            heartbeat_ip: None,
+            cdn: None,
+            prune_depth: None,
+            storage_mode: StorageMode::default(),
+            ledger_cache: LedgerCacheConfig::default(),
+            backups: None,
+            address_index: None,
+            min_free_disk_space: None,
+            dev: None,
+        }
+    }
+}
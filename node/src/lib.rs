@@ -26,6 +26,7 @@ pub use snarkos_node_cdn as cdn;
 pub use snarkos_node_consensus as consensus;
 pub use snarkos_node_rest as rest;
 pub use snarkos_node_router as router;
+pub use snarkos_node_storage as storage;
 pub use snarkos_node_sync as sync;
 pub use snarkos_node_tcp as tcp;
 pub use snarkvm;
@@ -33,6 +34,15 @@ pub use snarkvm;
 mod client;
 pub use client::*;
 
+mod config;
+pub use config::*;
+
+mod maintenance;
+pub use maintenance::*;
+
+mod migrations;
+pub use migrations::*;
+
 mod prover;
 pub use prover::*;
 
@@ -53,12 +63,15 @@ pub fn log_clean_error(dev: Option<u16>) {
     }
 }
 
+use snarkos_node_bft::ledger_service::BlockIndex;
+use snarkos_node_storage::AddressIndex;
 use snarkvm::{
     ledger::store::ConsensusStorage,
     prelude::{const_assert, hrp2, AleoID, Field, Ledger, Network},
 };
 
 use anyhow::{bail, Result};
+use std::sync::Arc;
 
 // TODO: Remove me after Phase 3.
 pub fn phase_3_reset<N: Network, C: ConsensusStorage<N>>(
@@ -170,6 +183,77 @@ pub fn phase_3_reset<N: Network, C: ConsensusStorage<N>>(
     Ok(ledger)
 }
 
+/// Ensures the block tree of the given ledger matches its persisted state root, and returns the ledger.
+///
+/// If it does not, the block tree is rebuilt from the stored block hashes, repairing them, and the ledger is reloaded,
+/// so the block tree is loaded from the repaired hashes.
+pub fn load_block_tree<N: Network, C: ConsensusStorage<N>>(
+    ledger: Ledger<N, C>,
+    dev: Option<u16>,
+) -> Result<Ledger<N, C>> {
+    let Err(error) = snarkos_node_storage::verify_block_tree(&ledger) else { return Ok(ledger) };
+    warn!("{error} - rebuilding the block tree from the stored block hashes");
+
+    // Repair the stored block hashes.
+    let issues = snarkos_node_storage::rebuild_block_tree(&ledger, true)?;
+    for issue in &issues {
+        warn!("Block {}: {}{}", issue.height, issue.description, if issue.repaired { " (repaired)" } else { "" });
+    }
+    if !issues.iter().all(|issue| issue.repaired) {
+        bail!("Failed to rebuild the block tree (run 'snarkos ledger rebuild --replay')")
+    }
+
+    // Reload the ledger, which rebuilds the block tree from the repaired hashes.
+    let genesis = ledger.get_block(0)?;
+    drop(ledger);
+    let ledger = Ledger::<N, C>::load_unchecked(genesis, dev)?;
+    if let Err(error) = snarkos_node_storage::verify_block_tree(&ledger) {
+        bail!("{error} (run 'snarkos ledger rebuild --replay')");
+    }
+    info!("Rebuilt the block tree from the stored block hashes");
+    Ok(ledger)
+}
+
+/// Indexes the blocks of the ledger that the given address index is missing, and returns the address index,
+/// which the ledger service then updates as each block is added, if the address index is enabled.
+pub async fn load_address_index<N: Network, C: ConsensusStorage<N>>(
+    ledger: &Ledger<N, C>,
+    address_index: Option<Arc<AddressIndex>>,
+) -> Result<Option<Arc<dyn BlockIndex<N>>>> {
+    let Some(index) = address_index else {
+        return Ok(None);
+    };
+    // Index the missing blocks on the blocking pool, as reading the blocks is expensive.
+    let (ledger, address_index) = (ledger.clone(), index.clone());
+    let num_blocks = tokio::task::spawn_blocking(move || address_index.sync(&ledger)).await??;
+    if num_blocks > 0 {
+        info!("Indexed {num_blocks} blocks that were missing from the address index");
+    }
+    Ok(Some(index))
+}
+
+/// The interval at which the peer deny list is reloaded.
+const PEER_DENY_LIST_INTERVAL_IN_MS: u64 = 500; // ms
+
+/// Starts the peer deny list loop, which reloads the given peer deny list, and disconnects the denied peers
+/// with the given closure, if the peer deny list is enabled.
+pub fn start_peer_deny_list_loop(
+    peer_deny_list: Option<Arc<snarkos_node_tcp::PeerDenyList>>,
+    disconnect_denied_peers: impl Fn() + Send + 'static,
+) -> Option<tokio::task::JoinHandle<()>> {
+    let deny_list = peer_deny_list?;
+    let mut interval = tokio::time::interval(std::time::Duration::from_millis(PEER_DENY_LIST_INTERVAL_IN_MS));
+    Some(tokio::spawn(async move {
+        loop {
+            interval.tick().await;
+            match deny_list.reload().await {
+                Ok(()) => disconnect_denied_peers(),
+                Err(error) => warn!("Failed to reload the peer deny list - {error}"),
+            }
+        }
+    }))
+}
+
 /// Starts the notification message loop.
 pub fn start_notification_message_loop() -> tokio::task::JoinHandle<()> {
     let mut interval = tokio::time::interval(std::time::Duration::from_secs(180));
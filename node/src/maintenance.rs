@@ -0,0 +1,117 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_bft::ledger_service::{is_storage_degraded, set_storage_degraded};
+use snarkvm::prelude::Network;
+
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+/// The number of bytes in a mebibyte.
+const MIB: u64 = 1024 * 1024;
+/// The default free disk space below which the storage is degraded (in bytes).
+pub const DEFAULT_MIN_FREE_DISK_SPACE: u64 = 2_048 * MIB;
+/// The interval at which the storage maintenance runs.
+const STORAGE_MAINTENANCE_INTERVAL_IN_SECS: u64 = 60; // seconds
+/// The minimum interval between two compactions of the ledger.
+const COMPACTION_INTERVAL_IN_SECS: u64 = 6 * 60 * 60; // seconds
+
+/// Starts the storage maintenance loop, if the storage maintenance is enabled, i.e. if the free disk space
+/// below which the storage is degraded (in bytes) is given.
+///
+/// The loop checks the free disk space of the ledger, and degrades the storage before the disk fills up,
+/// as RocksDB may corrupt the ledger if a write fails. While the node is synced and RocksDB is idle,
+/// the loop also compacts the ledger, at most once per `COMPACTION_INTERVAL_IN_SECS`.
+pub fn start_storage_maintenance_loop<N: Network>(
+    dev: Option<u16>,
+    min_free_disk_space: Option<u64>,
+    is_synced: impl Fn() -> bool + Send + 'static,
+) -> Option<JoinHandle<()>> {
+    let min_free_disk_space = min_free_disk_space?;
+    let ledger_dir = aleo_std::aleo_ledger_dir(N::ID, dev);
+    let mut interval = tokio::time::interval(Duration::from_secs(STORAGE_MAINTENANCE_INTERVAL_IN_SECS));
+    Some(tokio::spawn(async move {
+        // Note: The first compaction waits for a full interval, so that it does not slow down the startup.
+        let mut last_compaction = Instant::now();
+        loop {
+            interval.tick().await;
+            // Update the degraded state of the storage from the free disk space.
+            match snarkos_node_storage::free_disk_space(&ledger_dir) {
+                Ok(Some(free_disk_space)) => update_degraded_state(free_disk_space, min_free_disk_space),
+                Ok(None) => (),
+                Err(error) => warn!("Failed to check the free disk space - {error}"),
+            }
+
+            // Compact the ledger, if the node is idle and the last compaction is old enough.
+            if is_storage_degraded()
+                || !is_synced()
+                || last_compaction.elapsed() < Duration::from_secs(COMPACTION_INTERVAL_IN_SECS)
+            {
+                continue;
+            }
+            match tokio::task::spawn_blocking(move || snarkos_node_storage::compact_ledger::<N>(dev)).await {
+                Ok(Ok(true)) => {
+                    debug!("Compacted the ledger storage");
+                    last_compaction = Instant::now();
+                }
+                Ok(Ok(false)) => trace!("Deferred the compaction of the ledger storage, as RocksDB is busy"),
+                Ok(Err(error)) => warn!("Failed to compact the ledger storage - {error}"),
+                Err(error) => warn!("Failed to compact the ledger storage - {error}"),
+            }
+        }
+    }))
+}
+
+/// Updates the degraded state of the storage from the free disk space (in bytes).
+fn update_degraded_state(free_disk_space: u64, min_free_disk_space: u64) {
+    let (degraded, free_mib) = (is_storage_degraded(), free_disk_space / MIB);
+    match (degraded, next_degraded_state(degraded, free_disk_space, min_free_disk_space)) {
+        (false, true) => {
+            error!("Only {free_mib} MiB of disk space is free - the ledger stops advancing until more space is freed");
+            set_storage_degraded(true);
+        }
+        (true, false) => {
+            info!("{free_mib} MiB of disk space is free - the ledger resumes advancing");
+            set_storage_degraded(false);
+        }
+        _ => (),
+    }
+}
+
+/// Returns `true` if the storage is degraded, from its current state and the free disk space (in bytes).
+///
+/// Note: A degraded storage recovers at twice the minimum free disk space, so that it does not flap.
+fn next_degraded_state(degraded: bool, free_disk_space: u64, min_free_disk_space: u64) -> bool {
+    match degraded {
+        false => free_disk_space < min_free_disk_space,
+        true => free_disk_space < min_free_disk_space.saturating_mul(2),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_degraded_state() {
+        let min = DEFAULT_MIN_FREE_DISK_SPACE;
+        // Ensure the storage degrades below the minimum free disk space.
+        assert!(!next_degraded_state(false, min, min));
+        assert!(next_degraded_state(false, min - 1, min));
+        // Ensure a degraded storage only recovers at twice the minimum free disk space.
+        assert!(next_degraded_state(true, min, min));
+        assert!(next_degraded_state(true, 2 * min - 1, min));
+        assert!(!next_degraded_state(true, 2 * min, min));
+    }
+}
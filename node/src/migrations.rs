@@ -0,0 +1,224 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_bft::storage_service::{RecordKind, LATEST_RECORD_VERSION};
+use snarkvm::{
+    ledger::store::helpers::{
+        rocksdb::internal::{self, Database},
+        Map,
+        MapRead,
+    },
+    prelude::Network,
+};
+
+use anyhow::{bail, ensure, Result};
+use std::cmp::Ordering;
+
+/// The schema version of the ledger storage that this node reads and writes.
+pub const LEDGER_SCHEMA_VERSION: u32 = 2;
+
+/// The key of the schema version in the metadata map.
+const SCHEMA_VERSION_KEY: u8 = 0;
+
+/// The RocksDB map IDs of the metadata of the ledger storage.
+///
+/// Note: These IDs are reserved far above the map IDs of the ledger, so that the two never collide.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u16)]
+enum MetadataMap {
+    SchemaVersion = 0xE000,
+    RecordVersions = 0xE001,
+}
+
+impl From<MetadataMap> for u16 {
+    fn from(map_id: MetadataMap) -> Self {
+        map_id as u16
+    }
+}
+
+/// A migration of the ledger storage to the next schema version.
+pub struct Migration {
+    /// The schema version of the ledger storage after the migration.
+    pub version: u32,
+    /// The description of the migration.
+    pub description: &'static str,
+    /// Migrates the ledger storage of the given network and (optional) development ID.
+    pub migrate: fn(u16, Option<u16>) -> Result<()>,
+}
+
+/// The migrations of the ledger storage, in the order of their schema versions.
+///
+/// Note: A ledger storage without a schema version predates the versioning, and is at schema version `0`.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, description: "Record the schema version of the ledger storage", migrate: |_, _| Ok(()) },
+    Migration {
+        version: 2,
+        description: "Record the encoding versions of the blocks and transactions in the ledger storage",
+        // Note: The ledger storage predating the record versions is in the first encoding version.
+        migrate: |network, dev| record_encoding_versions(network, dev, 1),
+    },
+];
+
+/// The options of the migrations of the ledger storage.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct MigrationOptions {
+    /// Whether the pending migrations are only listed, and not applied.
+    pub dry_run: bool,
+    /// Whether a checkpoint of the ledger storage is skipped before the migrations are applied.
+    pub skip_backup: bool,
+}
+
+/// Applies the pending migrations of the ledger storage of the given (optional) development ID,
+/// and returns the pending migrations, in order.
+///
+/// A new ledger storage is initialized at the current schema version. Unless the backup is skipped,
+/// a checkpoint of the ledger storage is written next to it before the first migration is applied,
+/// so that the ledger can be restored if a migration fails. This must be called before the ledger is loaded.
+pub fn migrate_ledger<N: Network>(dev: Option<u16>, options: MigrationOptions) -> Result<Vec<&'static Migration>> {
+    let ledger_dir = aleo_std::aleo_ledger_dir(N::ID, dev);
+    // Initialize a new ledger storage at the current schema version.
+    // Note: In a dry run, the storage is not opened, as opening it creates the ledger directory.
+    if !ledger_dir.exists() {
+        if !options.dry_run {
+            record_schema_version::<N>(dev)?;
+        }
+        return Ok(Vec::new());
+    }
+    // Open the schema version of the ledger storage.
+    let metadata = internal::RocksDB::open_map::<u8, u32, _>(N::ID, dev, MetadataMap::SchemaVersion)?;
+
+    // Retrieve the pending migrations.
+    let version = metadata.get_confirmed(&SCHEMA_VERSION_KEY)?.map(|version| *version).unwrap_or(0);
+    let pending = pending_migrations(MIGRATIONS, version)?;
+    if options.dry_run {
+        return Ok(pending);
+    }
+
+    // Write a checkpoint of the ledger storage, before the first migration is applied.
+    if !pending.is_empty() && !options.skip_backup {
+        let backup_dir = ledger_dir.with_extension(format!("pre-migration-v{version}"));
+        ensure!(!backup_dir.exists(), "The pre-migration backup '{}' already exists", backup_dir.display());
+        snarkos_node_storage::checkpoint_storage(N::ID, dev, &backup_dir)?;
+        info!("Created a backup of the ledger at schema version {version} (in '{}')", backup_dir.display());
+    }
+    // Apply the migrations in order, recording the schema version after each migration.
+    for migration in &pending {
+        info!("Migrating the ledger to schema version {} - {}", migration.version, migration.description);
+        if let Err(error) = (migration.migrate)(N::ID, dev) {
+            bail!("Failed to migrate the ledger to schema version {} - {error}", migration.version)
+        }
+        metadata.insert(SCHEMA_VERSION_KEY, migration.version)?;
+    }
+    // Ensure the blocks and transactions of the ledger storage are in the latest encoding version.
+    check_encoding_versions(N::ID, dev)?;
+    Ok(pending)
+}
+
+/// Records the current schema version in the ledger storage of the given (optional) development ID,
+/// which must have been written by this node, such as a new or rebuilt ledger.
+pub fn record_schema_version<N: Network>(dev: Option<u16>) -> Result<()> {
+    record_encoding_versions(N::ID, dev, LATEST_RECORD_VERSION)?;
+    let metadata = internal::RocksDB::open_map::<u8, u32, _>(N::ID, dev, MetadataMap::SchemaVersion)?;
+    metadata.insert(SCHEMA_VERSION_KEY, LEDGER_SCHEMA_VERSION)
+}
+
+/// Records the given encoding version of every kind of record in the ledger storage of the given network
+/// and (optional) development ID.
+fn record_encoding_versions(network: u16, dev: Option<u16>, version: u16) -> Result<()> {
+    let record_versions = internal::RocksDB::open_map::<u8, u16, _>(network, dev, MetadataMap::RecordVersions)?;
+    for kind in RecordKind::ALL {
+        record_versions.insert(kind as u8, version)?;
+    }
+    Ok(())
+}
+
+/// Ensures every kind of record in the ledger storage of the given network and (optional) development ID
+/// is in the latest encoding version, which is the version this node reads and writes.
+fn check_encoding_versions(network: u16, dev: Option<u16>) -> Result<()> {
+    let record_versions = internal::RocksDB::open_map::<u8, u16, _>(network, dev, MetadataMap::RecordVersions)?;
+    for kind in RecordKind::ALL {
+        let Some(version) = record_versions.get_confirmed(&(kind as u8))?.map(|version| *version) else {
+            bail!("The encoding version of the {kind:?} records in the ledger is missing")
+        };
+        check_encoding_version(kind, version)?;
+    }
+    Ok(())
+}
+
+/// Ensures the given encoding version of the given kind of record in the ledger storage is the latest version.
+fn check_encoding_version(kind: RecordKind, version: u16) -> Result<()> {
+    match version.cmp(&LATEST_RECORD_VERSION) {
+        Ordering::Equal => Ok(()),
+        Ordering::Less => bail!("The {kind:?} records in the ledger are in version {version}, which was not migrated"),
+        Ordering::Greater => bail!(
+            "The {kind:?} records in the ledger are in version {version}, which is newer than this node supports \
+             ({LATEST_RECORD_VERSION})"
+        ),
+    }
+}
+
+/// Returns the migrations that are pending for a ledger storage at the given schema version, in order.
+fn pending_migrations(migrations: &'static [Migration], version: u32) -> Result<Vec<&'static Migration>> {
+    // Ensure the migrations are contiguous, starting at schema version 1.
+    for (index, migration) in migrations.iter().enumerate() {
+        let version = migration.version;
+        ensure!(version as usize == index + 1, "The migration to schema version {version} is out of order");
+    }
+    // Ensure the ledger storage is not newer than this node.
+    let latest_version = migrations.last().map_or(0, |migration| migration.version);
+    if version > latest_version {
+        bail!("The ledger is at schema version {version}, which is newer than this node supports ({latest_version})")
+    }
+    Ok(migrations.iter().filter(|migration| migration.version > version).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_MIGRATIONS: &[Migration] = &[
+        Migration { version: 1, description: "first", migrate: |_, _| Ok(()) },
+        Migration { version: 2, description: "second", migrate: |_, _| Ok(()) },
+    ];
+
+    #[test]
+    fn test_pending_migrations() {
+        let versions = |version| -> Vec<u32> {
+            pending_migrations(TEST_MIGRATIONS, version).unwrap().iter().map(|migration| migration.version).collect()
+        };
+        assert_eq!(versions(0), [1, 2]);
+        assert_eq!(versions(1), [2]);
+        assert!(versions(2).is_empty());
+
+        // Ensure a ledger from a newer node is rejected.
+        assert!(pending_migrations(TEST_MIGRATIONS, 3).is_err());
+
+        // Ensure the migrations must be in order.
+        const UNORDERED_MIGRATIONS: &[Migration] = &[Migration { version: 2, description: "", migrate: |_, _| Ok(()) }];
+        assert!(pending_migrations(UNORDERED_MIGRATIONS, 0).is_err());
+
+        // Ensure the current schema version is the version of the last migration.
+        assert_eq!(MIGRATIONS.last().unwrap().version, LEDGER_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_check_encoding_version() {
+        for kind in RecordKind::ALL {
+            // Ensure only the latest encoding version is accepted.
+            check_encoding_version(kind, LATEST_RECORD_VERSION).unwrap();
+            assert!(check_encoding_version(kind, LATEST_RECORD_VERSION - 1).is_err());
+            assert!(check_encoding_version(kind, LATEST_RECORD_VERSION + 1).is_err());
+        }
+    }
+}
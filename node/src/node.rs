@@ -12,9 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{traits::NodeInterface, Client, Prover, Validator};
+use crate::{traits::NodeInterface, Client, NodeConfig, Prover, Validator};
 use snarkos_account::Account;
 use snarkos_node_router::messages::NodeType;
+use snarkos_node_sync::SyncStatus;
 use snarkvm::prelude::{
     block::Block,
     store::helpers::{memory::ConsensusMemory, rocksdb::ConsensusDB},
@@ -24,8 +25,18 @@ use snarkvm::prelude::{
     ViewKey,
 };
 
-use anyhow::Result;
-use std::{net::SocketAddr, sync::Arc};
+use anyhow::{ensure, Result};
+use std::sync::Arc;
+
+/// The storage of the ledger of a node.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum StorageMode {
+    /// The ledger is persisted to disk, and restored after a restart.
+    #[default]
+    Persistent,
+    /// The ledger is held in memory, and nothing is written to disk, for tests and ephemeral devnets.
+    InMemory,
+}
 
 pub enum Node<N: Network> {
     /// A validator is a full node, capable of validating blocks.
@@ -34,49 +45,37 @@ pub enum Node<N: Network> {
     Prover(Arc<Prover<N, ConsensusMemory<N>>>),
     /// A client node is a full node, capable of querying with the network.
     Client(Arc<Client<N, ConsensusDB<N>>>),
+    /// A validator whose ledger is held in memory.
+    InMemoryValidator(Arc<Validator<N, ConsensusMemory<N>>>),
+    /// A client node whose ledger is held in memory.
+    InMemoryClient(Arc<Client<N, ConsensusMemory<N>>>),
 }
 
 impl<N: Network> Node<N> {
     /// Initializes a new validator node.
-    pub async fn new_validator(
-        node_ip: SocketAddr,
-        rest_ip: Option<SocketAddr>,
-        bft_ip: Option<SocketAddr>,
-        account: Account<N>,
-        trusted_peers: &[SocketAddr],
-        trusted_validators: &[SocketAddr],
-        genesis: Block<N>,
-        cdn: Option<String>,
-        dev: Option<u16>,
-    ) -> Result<Self> {
-        Ok(Self::Validator(Arc::new(
-            Validator::new(node_ip, rest_ip, bft_ip, account, trusted_peers, trusted_validators, genesis, cdn, dev)
-                .await?,
-        )))
+    pub async fn new_validator(account: Account<N>, genesis: Block<N>, config: NodeConfig<N>) -> Result<Self> {
+        match config.storage_mode {
+            StorageMode::Persistent => Ok(Self::Validator(Arc::new(Validator::new(account, genesis, config).await?))),
+            StorageMode::InMemory => {
+                Ok(Self::InMemoryValidator(Arc::new(Validator::new(account, genesis, config).await?)))
+            }
+        }
     }
 
     /// Initializes a new prover node.
-    pub async fn new_prover(
-        node_ip: SocketAddr,
-        account: Account<N>,
-        trusted_peers: &[SocketAddr],
-        genesis: Block<N>,
-        dev: Option<u16>,
-    ) -> Result<Self> {
-        Ok(Self::Prover(Arc::new(Prover::new(node_ip, account, trusted_peers, genesis, dev).await?)))
+    pub async fn new_prover(account: Account<N>, genesis: Block<N>, config: NodeConfig<N>) -> Result<Self> {
+        Ok(Self::Prover(Arc::new(Prover::new(account, genesis, config).await?)))
     }
 
     /// Initializes a new client node.
-    pub async fn new_client(
-        node_ip: SocketAddr,
-        rest_ip: Option<SocketAddr>,
-        account: Account<N>,
-        trusted_peers: &[SocketAddr],
-        genesis: Block<N>,
-        cdn: Option<String>,
-        dev: Option<u16>,
-    ) -> Result<Self> {
-        Ok(Self::Client(Arc::new(Client::new(node_ip, rest_ip, account, trusted_peers, genesis, cdn, dev).await?)))
+    pub async fn new_client(account: Account<N>, genesis: Block<N>, config: NodeConfig<N>) -> Result<Self> {
+        match config.storage_mode {
+            StorageMode::Persistent => Ok(Self::Client(Arc::new(Client::new(account, genesis, config).await?))),
+            StorageMode::InMemory => {
+                ensure!(config.prune_depth.is_none(), "A client with an in-memory ledger cannot be pruned");
+                Ok(Self::InMemoryClient(Arc::new(Client::new(account, genesis, config).await?)))
+            }
+        }
     }
 
     /// Returns the node type.
@@ -85,6 +84,8 @@ impl<N: Network> Node<N> {
             Self::Validator(validator) => validator.node_type(),
             Self::Prover(prover) => prover.node_type(),
             Self::Client(client) => client.node_type(),
+            Self::InMemoryValidator(validator) => validator.node_type(),
+            Self::InMemoryClient(client) => client.node_type(),
         }
     }
 
@@ -94,6 +95,8 @@ impl<N: Network> Node<N> {
             Self::Validator(node) => node.private_key(),
             Self::Prover(node) => node.private_key(),
             Self::Client(node) => node.private_key(),
+            Self::InMemoryValidator(node) => node.private_key(),
+            Self::InMemoryClient(node) => node.private_key(),
         }
     }
 
@@ -103,6 +106,8 @@ impl<N: Network> Node<N> {
             Self::Validator(node) => node.view_key(),
             Self::Prover(node) => node.view_key(),
             Self::Client(node) => node.view_key(),
+            Self::InMemoryValidator(node) => node.view_key(),
+            Self::InMemoryClient(node) => node.view_key(),
         }
     }
 
@@ -112,6 +117,8 @@ impl<N: Network> Node<N> {
             Self::Validator(node) => node.address(),
             Self::Prover(node) => node.address(),
             Self::Client(node) => node.address(),
+            Self::InMemoryValidator(node) => node.address(),
+            Self::InMemoryClient(node) => node.address(),
         }
     }
 
@@ -121,6 +128,19 @@ impl<N: Network> Node<N> {
             Self::Validator(node) => node.is_dev(),
             Self::Prover(node) => node.is_dev(),
             Self::Client(node) => node.is_dev(),
+            Self::InMemoryValidator(node) => node.is_dev(),
+            Self::InMemoryClient(node) => node.is_dev(),
+        }
+    }
+
+    /// Returns the status of the block sync.
+    pub fn sync_status(&self) -> SyncStatus {
+        match self {
+            Self::Validator(node) => node.sync_status(),
+            Self::Prover(node) => node.sync_status(),
+            Self::Client(node) => node.sync_status(),
+            Self::InMemoryValidator(node) => node.sync_status(),
+            Self::InMemoryClient(node) => node.sync_status(),
         }
     }
 }
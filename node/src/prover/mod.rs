@@ -14,9 +14,10 @@
 
 mod router;
 
-use crate::traits::NodeInterface;
+use crate::{traits::NodeInterface, NodeConfig};
 use snarkos_account::Account;
 use snarkos_node_bft::ledger_service::ProverLedgerService;
+use snarkos_node_rest::{AdminServer, NodeAdmin};
 use snarkos_node_router::{
     messages::{Message, NodeType, UnconfirmedSolution},
     Heartbeat,
@@ -25,7 +26,7 @@ use snarkos_node_router::{
     Router,
     Routing,
 };
-use snarkos_node_sync::{BlockSync, BlockSyncMode};
+use snarkos_node_sync::{BlockSync, BlockSyncMode, SyncStatus};
 use snarkos_node_tcp::{
     protocols::{Disconnect, Handshake, OnConnect, Reading, Writing},
     P2P,
@@ -45,12 +46,9 @@ use colored::Colorize;
 use core::{marker::PhantomData, time::Duration};
 use parking_lot::{Mutex, RwLock};
 use rand::{rngs::OsRng, CryptoRng, Rng};
-use std::{
-    net::SocketAddr,
-    sync::{
-        atomic::{AtomicBool, AtomicU8, Ordering},
-        Arc,
-    },
+use std::sync::{
+    atomic::{AtomicBool, AtomicU8, Ordering},
+    Arc,
 };
 use tokio::task::JoinHandle;
 
@@ -73,6 +71,8 @@ pub struct Prover<N: Network, C: ConsensusStorage<N>> {
     puzzle_instances: Arc<AtomicU8>,
     /// The maximum number of puzzle instances.
     max_puzzle_instances: u8,
+    /// The boolean flag for whether the coinbase puzzle is running.
+    is_mining: Arc<AtomicBool>,
     /// The spawned handles.
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
     /// The shutdown signal.
@@ -83,13 +83,7 @@ pub struct Prover<N: Network, C: ConsensusStorage<N>> {
 
 impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
     /// Initializes a new prover node.
-    pub async fn new(
-        node_ip: SocketAddr,
-        account: Account<N>,
-        trusted_peers: &[SocketAddr],
-        genesis: Block<N>,
-        dev: Option<u16>,
-    ) -> Result<Self> {
+    pub async fn new(account: Account<N>, genesis: Block<N>, config: NodeConfig<N>) -> Result<Self> {
         // Initialize the signal handler.
         let signal_node = Self::handle_signals();
 
@@ -100,18 +94,27 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
 
         // Initialize the node router.
         let router = Router::new(
-            node_ip,
+            config.node_ip,
             NodeType::Prover,
             account,
-            trusted_peers,
+            &config.trusted_peers,
+            config.whitelist,
+            config.peer_deny_list.clone(),
+            config.memory_profile,
+            config.compact_relay,
             Self::MAXIMUM_NUMBER_OF_PEERS as u16,
-            dev.is_some(),
+            config.dev.is_some(),
         )
         .await?;
         // Load the coinbase puzzle.
         let coinbase_puzzle = CoinbasePuzzle::<N>::load()?;
         // Compute the maximum number of puzzle instances.
         let max_puzzle_instances = num_cpus::get().saturating_sub(2).clamp(1, 6);
+        // Determine if the coinbase puzzle starts running, which is disabled in the low-memory profile.
+        let is_mining = config.memory_profile.is_mining_enabled();
+        if !is_mining {
+            info!("The coinbase puzzle is disabled in the low-memory profile, and can be started via the admin API");
+        }
         // Initialize the node.
         let node = Self {
             router,
@@ -122,6 +125,7 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
             latest_block_header: Default::default(),
             puzzle_instances: Default::default(),
             max_puzzle_instances: u8::try_from(max_puzzle_instances)?,
+            is_mining: Arc::new(AtomicBool::new(is_mining)),
             handles: Default::default(),
             shutdown: Default::default(),
             _phantom: Default::default(),
@@ -130,13 +134,42 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
         node.initialize_routing().await;
         // Initialize the coinbase puzzle.
         node.initialize_coinbase_puzzle().await;
+        // Initialize the admin server, if it is enabled.
+        if let Some(admin) = config.admin {
+            node.handles.lock().push(AdminServer::start(admin, Arc::new(node.clone()))?);
+        }
         // Initialize the notification message loop.
         node.handles.lock().push(crate::start_notification_message_loop());
+        // Initialize the peer deny list loop, if the peer deny list is enabled.
+        let router = node.router.clone();
+        node.handles
+            .lock()
+            .extend(crate::start_peer_deny_list_loop(config.peer_deny_list, move || router.disconnect_denied_peers()));
         // Pass the node to the signal handler.
         let _ = signal_node.set(node.clone());
         // Return the node.
         Ok(node)
     }
+
+    /// Returns the status of the block sync.
+    pub fn sync_status(&self) -> SyncStatus {
+        self.sync.status()
+    }
+
+    /// Returns `true` if the coinbase puzzle is running.
+    pub fn is_mining(&self) -> bool {
+        self.is_mining.load(Ordering::Relaxed)
+    }
+}
+
+impl<N: Network, C: ConsensusStorage<N>> NodeAdmin<N> for Prover<N, C> {
+    /// Starts or stops the coinbase puzzle of the node.
+    fn set_mining(&self, enabled: bool) -> Result<()> {
+        if self.is_mining.swap(enabled, Ordering::Relaxed) != enabled {
+            info!("{} the coinbase puzzle", if enabled { "Started" } else { "Stopped" });
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -153,6 +186,10 @@ impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Prover<N, C> {
         trace!("Shutting down the prover...");
         self.handles.lock().iter().for_each(|handle| handle.abort());
 
+        // Inform the connected peers that the node is shutting down.
+        trace!("Notifying the peers...");
+        self.notify_shutdown().await;
+
         // Shut down the router.
         self.router.shut_down().await;
 
@@ -181,6 +218,12 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
                 continue;
             }
 
+            // If the coinbase puzzle was stopped, then skip this iteration.
+            if !self.is_mining() {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                continue;
+            }
+
             // If the number of instances of the coinbase puzzle exceeds the maximum, then skip this iteration.
             if self.num_puzzle_instances() > self.max_puzzle_instances {
                 // Sleep for a brief period of time.
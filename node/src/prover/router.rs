@@ -14,17 +14,25 @@
 
 use super::*;
 
-use snarkos_node_router::messages::{
-    BlockRequest,
-    DisconnectReason,
-    Message,
-    MessageCodec,
-    Ping,
-    Pong,
-    PuzzleRequest,
-    UnconfirmedTransaction,
+use snarkos_node_router::{
+    message_priority,
+    messages::{
+        BlockAnnouncement,
+        BlockHeadersRequest,
+        BlockRequest,
+        BlocksUnavailable,
+        CompactBlock,
+        CompactBlockRequest,
+        DisconnectReason,
+        Message,
+        MessageCodec,
+        Ping,
+        Pong,
+        PuzzleRequest,
+        UnconfirmedTransaction,
+    },
 };
-use snarkos_node_tcp::{Connection, ConnectionSide, Tcp};
+use snarkos_node_tcp::{protocols::Priority, Connection, ConnectionSide, Tcp};
 use snarkvm::prelude::{block::Transaction, Network};
 
 use std::{io, net::SocketAddr};
@@ -85,6 +93,11 @@ impl<N: Network, C: ConsensusStorage<N>> Writing for Prover<N, C> {
     fn codec(&self, _addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
         Default::default()
     }
+
+    /// Returns the priority lane that the given outbound message is queued in.
+    fn priority(&self, message: &Self::Message) -> Priority {
+        message_priority(message)
+    }
 }
 
 #[async_trait]
@@ -151,6 +164,40 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Prover<N, C> {
         false
     }
 
+    /// Handles a `BlocksUnavailable` message.
+    fn blocks_unavailable(&self, peer_ip: SocketAddr, _message: BlocksUnavailable) -> bool {
+        debug!("Disconnecting '{peer_ip}' for the following reason - {:?}", DisconnectReason::ProtocolViolation);
+        false
+    }
+
+    /// Handles a `BlockHeadersRequest` message.
+    fn block_headers_request(&self, peer_ip: SocketAddr, _message: BlockHeadersRequest) -> bool {
+        debug!("Disconnecting '{peer_ip}' for the following reason - {:?}", DisconnectReason::ProtocolViolation);
+        false
+    }
+
+    /// Handles a `BlockHeadersResponse` message.
+    fn block_headers_response(&self, peer_ip: SocketAddr, _headers: Vec<(N::BlockHash, Header<N>)>) -> bool {
+        debug!("Disconnecting '{peer_ip}' for the following reason - {:?}", DisconnectReason::ProtocolViolation);
+        false
+    }
+
+    /// Handles a `BlockAnnouncement` message.
+    fn block_announcement(&self, _peer_ip: SocketAddr, _message: BlockAnnouncement<N>) -> bool {
+        true
+    }
+
+    /// Handles a `CompactBlock` message.
+    fn compact_block(&self, _peer_ip: SocketAddr, _message: CompactBlock<N>) -> bool {
+        true
+    }
+
+    /// Handles a `CompactBlockRequest` message.
+    fn compact_block_request(&self, peer_ip: SocketAddr, _message: CompactBlockRequest<N>) -> bool {
+        debug!("Disconnecting '{peer_ip}' for the following reason - {:?}", DisconnectReason::ProtocolViolation);
+        false
+    }
+
     /// Processes the block locators and sends back a `Pong` message.
     fn ping(&self, peer_ip: SocketAddr, message: Ping<N>) -> bool {
         // Check if the sync module is in router mode.
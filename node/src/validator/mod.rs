@@ -14,11 +14,15 @@
 
 mod router;
 
-use crate::traits::NodeInterface;
+use crate::{traits::NodeInterface, NodeConfig, StorageMode};
 use snarkos_account::Account;
-use snarkos_node_bft::{helpers::init_primary_channels, ledger_service::CoreLedgerService};
+use snarkos_node_bft::{
+    helpers::{await_failover, init_primary_channels, serve_heartbeats, SigningLease},
+    ledger_service::CoreLedgerService,
+    storage_service::{BFTMemoryService, BFTPersistentStorage, StorageService},
+};
 use snarkos_node_consensus::Consensus;
-use snarkos_node_rest::Rest;
+use snarkos_node_rest::{AdminServer, NodeAdmin, Rest};
 use snarkos_node_router::{
     messages::{NodeType, PuzzleResponse, UnconfirmedSolution, UnconfirmedTransaction},
     Heartbeat,
@@ -27,7 +31,8 @@ use snarkos_node_router::{
     Router,
     Routing,
 };
-use snarkos_node_sync::{BlockSync, BlockSyncMode};
+use snarkos_node_storage::{AddressIndex, BackupConfig, LedgerCache};
+use snarkos_node_sync::{BlockSync, BlockSyncMode, SyncStatus};
 use snarkos_node_tcp::{
     protocols::{Disconnect, Handshake, OnConnect, Reading, Writing},
     P2P,
@@ -40,11 +45,11 @@ use snarkvm::prelude::{
     Network,
 };
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use core::future::Future;
 use parking_lot::Mutex;
 use std::{
-    net::SocketAddr,
+    path::PathBuf,
     sync::{atomic::AtomicBool, Arc},
     time::Duration,
 };
@@ -55,6 +60,12 @@ use tokio::task::JoinHandle;
 pub struct Validator<N: Network, C: ConsensusStorage<N>> {
     /// The ledger of the node.
     ledger: Ledger<N, C>,
+    /// The cache of the recent blocks, block headers, and transactions of the ledger.
+    ledger_cache: Arc<LedgerCache<N>>,
+    /// The configuration of the ledger backups, if backups are enabled.
+    backups: Option<BackupConfig>,
+    /// The address index, if it is enabled.
+    address_index: Option<Arc<AddressIndex>>,
     /// The consensus module of the node.
     consensus: Consensus<N>,
     /// The router of the node.
@@ -71,17 +82,8 @@ pub struct Validator<N: Network, C: ConsensusStorage<N>> {
 
 impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
     /// Initializes a new validator node.
-    pub async fn new(
-        node_ip: SocketAddr,
-        rest_ip: Option<SocketAddr>,
-        bft_ip: Option<SocketAddr>,
-        account: Account<N>,
-        trusted_peers: &[SocketAddr],
-        trusted_validators: &[SocketAddr],
-        genesis: Block<N>,
-        cdn: Option<String>,
-        dev: Option<u16>,
-    ) -> Result<Self> {
+    pub async fn new(account: Account<N>, genesis: Block<N>, mut config: NodeConfig<N>) -> Result<Self> {
+        let dev = config.dev;
         // Initialize the signal handler.
         let signal_node = Self::handle_signals();
 
@@ -89,8 +91,10 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
         let ledger = Ledger::load(genesis, dev)?;
         // TODO: Remove me after Phase 3.
         let ledger = crate::phase_3_reset(ledger, dev)?;
+        // Ensure the block tree of the ledger matches its persisted state root, or rebuild it.
+        let ledger = crate::load_block_tree(ledger, dev)?;
         // Initialize the CDN.
-        if let Some(base_url) = cdn {
+        if let Some(base_url) = config.cdn {
             // Sync the ledger with the CDN.
             if let Err((_, error)) = snarkos_node_cdn::sync_ledger_with_cdn(&base_url, ledger.clone()).await {
                 crate::log_clean_error(dev);
@@ -98,13 +102,53 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
             }
         }
 
-        // Initialize the ledger service.
-        let ledger_service = Arc::new(CoreLedgerService::new(ledger.clone()));
+        // Catch up the address index with the ledger, if it is enabled.
+        let address_index = crate::load_address_index(&ledger, config.address_index.clone()).await?;
+        // Initialize the ledger service, which updates the address index as each block is added.
+        let ledger_service = Arc::new(CoreLedgerService::new(ledger.clone(), address_index));
         // Initialize the sync module.
         let sync = BlockSync::new(BlockSyncMode::Gateway, ledger_service.clone());
+        // Retrieve the stream of the blocks added to the ledger.
+        let block_events = ledger_service.block_events().clone();
+
+        // If the validator is a standby, keep the ledger in sync with the active validator until it fails.
+        if let Some(validator_ip) = config.standby {
+            let Some(journal_path) = &config.primary.signing_journal else {
+                bail!("The standby requires the signing journal")
+            };
+            await_failover(validator_ip, &account, journal_path, ledger_service.clone()).await?;
+        }
+        // Serve heartbeats to the standby, if enabled, and only sign while the standby renews the signing lease.
+        let mut heartbeat_handle = None;
+        if let Some(heartbeat_ip) = config.heartbeat_ip {
+            let Some(journal_path) = config.primary.signing_journal.clone() else {
+                bail!("The heartbeats require the signing journal")
+            };
+            let lease = Arc::new(SigningLease::default());
+            config.primary.signing_lease = Some(lease.clone());
+            let (account, ledger) = (account.clone(), ledger_service.clone());
+            heartbeat_handle = Some(serve_heartbeats(heartbeat_ip, account, journal_path, lease, ledger).await?);
+        }
 
+        // Initialize the storage of the Narwhal transmissions, which is held in memory alongside an in-memory ledger.
+        let transmissions: Arc<dyn StorageService<N>> = match config.storage_mode {
+            StorageMode::Persistent => Arc::new(BFTPersistentStorage::open(dev)?),
+            StorageMode::InMemory => Arc::new(BFTMemoryService::new()),
+        };
         // Initialize the consensus.
-        let mut consensus = Consensus::new(account.clone(), ledger_service, bft_ip, trusted_validators, dev)?;
+        let mut consensus = Consensus::new(
+            account.clone(),
+            ledger_service,
+            transmissions,
+            config.bft_ip,
+            &config.trusted_validators,
+            config.peer_deny_list.clone(),
+            config.max_transaction_validity,
+            config.max_mempool_size,
+            config.mempool_snapshot_path,
+            config.primary,
+            dev,
+        )?;
         // Initialize the primary channels.
         let (primary_sender, primary_receiver) = init_primary_channels::<N>();
         // Start the consensus.
@@ -112,10 +156,14 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
 
         // Initialize the node router.
         let router = Router::new(
-            node_ip,
+            config.node_ip,
             NodeType::Validator,
             account,
-            trusted_peers,
+            &config.trusted_peers,
+            config.whitelist,
+            config.peer_deny_list.clone(),
+            config.memory_profile,
+            config.compact_relay,
             Self::MAXIMUM_NUMBER_OF_PEERS as u16,
             dev.is_some(),
         )
@@ -124,6 +172,9 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
         // Initialize the node.
         let mut node = Self {
             ledger: ledger.clone(),
+            ledger_cache: Arc::new(LedgerCache::new(config.ledger_cache)),
+            backups: config.backups,
+            address_index: config.address_index,
             consensus: consensus.clone(),
             router,
             rest: None,
@@ -135,13 +186,46 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
         node.initialize_transaction_pool(dev)?;
 
         // Initialize the REST server.
-        if let Some(rest_ip) = rest_ip {
-            node.rest = Some(Rest::start(rest_ip, Some(consensus), ledger.clone(), Arc::new(node.clone()))?);
+        if let Some(rest_ip) = config.rest_ip {
+            // Serve the status of the BFT block sync, through which the validator syncs.
+            let sync = consensus.bft().primary().sync().block_sync().clone();
+            let (ledger, ledger_cache, routing) = (ledger.clone(), node.ledger_cache.clone(), Arc::new(node.clone()));
+            node.rest = Some(Rest::start(
+                rest_ip,
+                config.rest,
+                Some(consensus),
+                ledger,
+                ledger_cache,
+                node.backups.clone(),
+                node.address_index.clone(),
+                sync,
+                block_events,
+                routing,
+            )?);
         }
         // Initialize the routing.
         node.initialize_routing().await;
         // Initialize the notification message loop.
         node.handles.lock().push(crate::start_notification_message_loop());
+        // Track the heartbeat server, if heartbeats are enabled.
+        node.handles.lock().extend(heartbeat_handle);
+        // Initialize the peer deny list loop, if the peer deny list is enabled.
+        let (router, gateway) = (node.router.clone(), node.consensus.bft().primary().gateway().clone());
+        node.handles.lock().extend(crate::start_peer_deny_list_loop(config.peer_deny_list, move || {
+            router.disconnect_denied_peers();
+            gateway.disconnect_denied_peers();
+        }));
+        // Initialize the storage maintenance loop, if the storage maintenance is enabled.
+        let sync = node.consensus.bft().primary().sync().block_sync().clone();
+        node.handles.lock().extend(crate::start_storage_maintenance_loop::<N>(
+            dev,
+            config.min_free_disk_space,
+            move || sync.is_block_synced(),
+        ));
+        // Initialize the admin server, if it is enabled.
+        if let Some(admin) = config.admin {
+            node.handles.lock().push(AdminServer::start(admin, Arc::new(node.clone()))?);
+        }
         // Pass the node to the signal handler.
         let _ = signal_node.set(node.clone());
         // Return the node.
@@ -157,6 +241,19 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
     pub fn rest(&self) -> &Option<Rest<N, C, Self>> {
         &self.rest
     }
+
+    /// Returns the status of the block sync.
+    pub fn sync_status(&self) -> SyncStatus {
+        self.consensus.bft().primary().sync().block_sync().status()
+    }
+}
+
+impl<N: Network, C: ConsensusStorage<N>> NodeAdmin<N> for Validator<N, C> {
+    /// Creates a backup of the ledger, and returns its path.
+    fn backup_ledger(&self) -> Result<PathBuf> {
+        let Some(backups) = &self.backups else { bail!("Ledger backups are not enabled on this node") };
+        snarkos_node_storage::create_backup(&self.ledger, backups.backup_dir(), backups.max_backups())
+    }
 }
 
 impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
@@ -429,6 +526,10 @@ impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Validator<N, C> {
         trace!("Shutting down the validator...");
         self.handles.lock().iter().for_each(|handle| handle.abort());
 
+        // Inform the connected peers that the node is shutting down.
+        trace!("Notifying the peers...");
+        self.notify_shutdown().await;
+
         // Shut down the router.
         self.router.shut_down().await;
 
@@ -452,7 +553,7 @@ mod tests {
     use anyhow::bail;
     use rand::SeedableRng;
     use rand_chacha::ChaChaRng;
-    use std::str::FromStr;
+    use std::{net::SocketAddr, str::FromStr};
 
     type CurrentNetwork = Testnet3;
 
@@ -476,19 +577,9 @@ mod tests {
 
         println!("Initializing validator node...");
 
-        let validator = Validator::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::new(
-            node,
-            Some(rest),
-            None,
-            account,
-            &[],
-            &[],
-            genesis,
-            None,
-            dev,
-        )
-        .await
-        .unwrap();
+        let config = NodeConfig { rest_ip: Some(rest), dev, ..NodeConfig::new(node) };
+        let validator =
+            Validator::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::new(account, genesis, config).await.unwrap();
 
         println!("Loaded validator node with {} blocks", validator.ledger.latest_height(),);
 
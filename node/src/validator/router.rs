@@ -13,18 +13,27 @@
 // limitations under the License.
 
 use super::*;
-use snarkos_node_router::messages::{
-    BlockRequest,
-    BlockResponse,
-    DataBlocks,
-    DisconnectReason,
-    Message,
-    MessageCodec,
-    Ping,
-    Pong,
-    UnconfirmedTransaction,
+use snarkos_node_router::{
+    message_priority,
+    messages::{
+        BlockAnnouncement,
+        BlockHeadersRequest,
+        BlockHeadersResponse,
+        BlockRequest,
+        BlockResponse,
+        BlocksUnavailable,
+        CompactBlock,
+        CompactBlockRequest,
+        DataBlocks,
+        DisconnectReason,
+        Message,
+        MessageCodec,
+        Ping,
+        Pong,
+        UnconfirmedTransaction,
+    },
 };
-use snarkos_node_tcp::{Connection, ConnectionSide, Tcp};
+use snarkos_node_tcp::{protocols::Priority, Connection, ConnectionSide, Tcp};
 use snarkvm::{
     ledger::narwhal::Data,
     prelude::{block::Transaction, coinbase::EpochChallenge, error, Network},
@@ -96,6 +105,11 @@ impl<N: Network, C: ConsensusStorage<N>> Writing for Validator<N, C> {
     fn codec(&self, _addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
         Default::default()
     }
+
+    /// Returns the priority lane that the given outbound message is queued in.
+    fn priority(&self, message: &Self::Message) -> Priority {
+        message_priority(message)
+    }
 }
 
 #[async_trait]
@@ -145,8 +159,15 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Validator<N, C> {
     fn block_request(&self, peer_ip: SocketAddr, message: BlockRequest) -> bool {
         let BlockRequest { start_height, end_height } = &message;
 
+        // If this node does not serve the requested blocks, respond that they are unavailable.
+        let oldest_block_height = self.router().oldest_block_height();
+        if *start_height < oldest_block_height || *end_height > self.ledger.latest_height() + 1 {
+            let response = BlocksUnavailable { request: message, oldest_block_height };
+            Outbound::send(self, peer_ip, Message::BlocksUnavailable(response));
+            return true;
+        }
         // Retrieve the blocks within the requested range.
-        let blocks = match self.ledger.get_blocks(*start_height..*end_height) {
+        let blocks = match self.ledger_cache.get_blocks(&self.ledger, *start_height..*end_height) {
             Ok(blocks) => Data::Object(DataBlocks(blocks)),
             Err(error) => {
                 error!("Failed to retrieve blocks {start_height} to {end_height} from the ledger - {error}");
@@ -160,8 +181,62 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Validator<N, C> {
 
     /// Handles a `BlockResponse` message.
     fn block_response(&self, peer_ip: SocketAddr, blocks: Vec<Block<N>>) -> bool {
-        // Tries to advance with blocks from the sync module.
-        match self.sync.advance_with_sync_blocks(peer_ip, blocks) {
+        // Insert the blocks into the sync pool.
+        if let Err(error) = self.sync.process_block_response(peer_ip, blocks) {
+            warn!("{error}");
+            return false;
+        }
+        // Advance with the sync blocks in a dedicated task, so that the messages of the peer continue to be processed.
+        let self_ = self.clone();
+        tokio::spawn(async move {
+            // Tries to advance with blocks from the sync module.
+            if let Err(error) = self_.sync.advance_with_sync_blocks().await {
+                warn!("{error}");
+            }
+            // If the peer served an invalid block, restrict and disconnect the peer.
+            if self_.sync.is_peer_banned(&peer_ip) {
+                self_.router().insert_restricted_peer(peer_ip);
+                self_.router().disconnect(peer_ip);
+            }
+        });
+        true
+    }
+
+    /// Handles a `BlocksUnavailable` message.
+    fn blocks_unavailable(&self, peer_ip: SocketAddr, message: BlocksUnavailable) -> bool {
+        let BlocksUnavailable { request, oldest_block_height } = message;
+        // Request the blocks from other peers.
+        self.sync.process_blocks_unavailable(peer_ip, request.start_height..request.end_height, oldest_block_height);
+        true
+    }
+
+    /// Retrieves the block headers within the block headers request range, and returns them to the peer.
+    fn block_headers_request(&self, peer_ip: SocketAddr, message: BlockHeadersRequest) -> bool {
+        let BlockHeadersRequest { start_height, end_height } = message;
+
+        // Retrieve the previous block hash and the header of each block within the requested range.
+        let headers = match (start_height..end_height)
+            .map(|height| {
+                Ok((self.ledger.get_previous_hash(height)?, self.ledger_cache.get_header(&self.ledger, height)?))
+            })
+            .collect::<Result<Vec<_>>>()
+        {
+            Ok(headers) => headers,
+            Err(error) => {
+                error!("Failed to retrieve block headers {start_height} to {end_height} from the ledger - {error}");
+                return false;
+            }
+        };
+        // Send the `BlockHeadersResponse` message to the peer.
+        let response = BlockHeadersResponse { request: message, headers };
+        Outbound::send(self, peer_ip, Message::BlockHeadersResponse(response));
+        true
+    }
+
+    /// Handles a `BlockHeadersResponse` message.
+    fn block_headers_response(&self, peer_ip: SocketAddr, headers: Vec<(N::BlockHash, Header<N>)>) -> bool {
+        // Extends the header chain of the sync module.
+        match self.sync.process_block_headers_response(peer_ip, headers) {
             Ok(()) => true,
             Err(error) => {
                 warn!("{error}");
@@ -170,15 +245,51 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Validator<N, C> {
         }
     }
 
+    /// Handles a `BlockAnnouncement` message.
+    fn block_announcement(&self, _peer_ip: SocketAddr, _message: BlockAnnouncement<N>) -> bool {
+        // Validators advance their ledger through consensus, so block announcements are ignored.
+        true
+    }
+
+    /// Handles a `CompactBlock` message.
+    fn compact_block(&self, _peer_ip: SocketAddr, _message: CompactBlock<N>) -> bool {
+        // Validators advance their ledger through consensus, so compact blocks are ignored.
+        true
+    }
+
+    /// Handles a `CompactBlockRequest` message.
+    fn compact_block_request(&self, peer_ip: SocketAddr, message: CompactBlockRequest<N>) -> bool {
+        let CompactBlockRequest { block_hash, transaction_ids } = message;
+        // Retrieve the requested block.
+        let block =
+            self.ledger.get_height(&block_hash).and_then(|height| self.ledger_cache.get_block(&self.ledger, height));
+        let block = match block {
+            Ok(block) => block,
+            Err(error) => {
+                debug!("Unable to serve the compact block request from '{peer_ip}' - {error}");
+                return true;
+            }
+        };
+        // Send the `CompactBlock` message to the peer, with the missing transactions in full.
+        Outbound::send(self, peer_ip, Message::CompactBlock(CompactBlock::new(&block, &transaction_ids)));
+        true
+    }
+
     /// Processes the block locators and sends back a `Pong` message.
     fn ping(&self, peer_ip: SocketAddr, message: Ping<N>) -> bool {
         // Check if the sync module is in router mode.
         if self.sync.mode().is_router() {
+            // Update the oldest block height that the peer serves.
+            self.sync.update_peer_oldest_height(peer_ip, message.oldest_block_height);
             // If block locators were provided, then update the peer in the sync pool.
             if let Some(block_locators) = message.block_locators {
                 // Check the block locators are valid, and update the peer in the sync pool.
                 if let Err(error) = self.sync.update_peer_locators(peer_ip, block_locators) {
                     warn!("Peer '{peer_ip}' sent invalid block locators: {error}");
+                    // If the peer served an invalid block, restrict the peer.
+                    if self.sync.is_peer_banned(&peer_ip) {
+                        self.router().insert_restricted_peer(peer_ip);
+                    }
                     return false;
                 }
             }
@@ -257,8 +368,14 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Validator<N, C> {
         serialized: UnconfirmedTransaction<N>,
         transaction: Transaction<N>,
     ) -> bool {
-        // Add the unconfirmed transaction to the memory pool.
-        if let Err(error) = self.consensus.add_unconfirmed_transaction(transaction).await {
+        // Add the unconfirmed transaction to the memory pool, along with its expiration height, if it has one.
+        let result = match serialized.expiration_height {
+            Some(expiration_height) => {
+                self.consensus.add_unconfirmed_transaction_with_expiration(transaction, expiration_height).await
+            }
+            None => self.consensus.add_unconfirmed_transaction(transaction).await,
+        };
+        if let Err(error) = result {
             trace!("[UnconfirmedTransaction] {error}");
             return true; // Maintain the connection.
         }
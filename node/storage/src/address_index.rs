@@ -0,0 +1,376 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::LedgerIterators;
+use snarkos_node_bft_ledger_service::BlockIndex;
+use snarkos_node_bft_storage_service::{BatchOperation, IndexStorage, Keyspace};
+use snarkvm::prelude::{
+    block::{Block, Input, Output, Transition},
+    store::ConsensusStorage,
+    Address,
+    Argument,
+    Field,
+    FromBytes,
+    Future,
+    Ledger,
+    Literal,
+    Network,
+    Owner,
+    Plaintext,
+    ToBytes,
+};
+
+use anyhow::{ensure, Result};
+use std::collections::HashSet;
+
+/// The maximum number of entries in a page of the history of an address.
+pub const MAX_ADDRESS_HISTORY_PAGE_SIZE: usize = 100;
+
+/// The key of the latest indexed block height, in the metadata keyspace.
+const HEIGHT_KEY: &[u8] = &[Keyspace::Metadata.prefix()];
+/// The prefix of the keys of the address activity, which are `(prefix, address, height, transition ID)`.
+const ACTIVITY_PREFIX: u8 = Keyspace::Addresses.prefix();
+/// The prefix of the keys of the transaction locations, which are `(prefix, transaction ID)`.
+const TRANSACTION_PREFIX: u8 = Keyspace::Transactions.prefix();
+/// The prefix of the keys of the record commitments, which are `(prefix, commitment)`.
+const COMMITMENT_PREFIX: u8 = Keyspace::Commitments.prefix();
+/// The prefix of the keys of the record serial numbers, which are `(prefix, serial number)`.
+const SERIAL_NUMBER_PREFIX: u8 = Keyspace::SerialNumbers.prefix();
+
+/// A transition that references an address.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AddressActivity<N: Network> {
+    /// The height of the block of the transition.
+    pub height: u32,
+    /// The ID of the transition.
+    pub transition_id: N::TransitionID,
+    /// The ID of the transaction of the transition.
+    pub transaction_id: N::TransactionID,
+}
+
+/// The location of a confirmed transaction in the ledger.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TransactionLocation {
+    /// The height of the block of the transaction.
+    pub height: u32,
+    /// The index of the transaction in the block.
+    pub index: u32,
+}
+
+/// An index of the transitions that reference each address, in order of block height, and of the location
+/// of each transaction, and the transaction of each record commitment and serial number.
+///
+/// Note: An address is referenced by a transition if it is in a public input or output, in the arguments
+/// of a future output, or is the public owner of an output record. Private values are not indexed.
+#[derive(Debug)]
+pub struct AddressIndex {
+    /// The storage of the index.
+    storage: Box<dyn IndexStorage>,
+}
+
+impl AddressIndex {
+    /// Initializes a new address index on the given storage.
+    pub fn new(storage: Box<dyn IndexStorage>) -> Self {
+        Self { storage }
+    }
+
+    /// Returns the latest indexed block height, if any block is indexed.
+    pub fn indexed_height(&self) -> Result<Option<u32>> {
+        match self.storage.get(HEIGHT_KEY)? {
+            Some(bytes) => Ok(Some(u32::from_bytes_le(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Indexes the given block, which must be the next block after the latest indexed block.
+    ///
+    /// Note: The entries of the block and the indexed height are written in one atomic batch.
+    pub fn index_block<N: Network>(&self, block: &Block<N>) -> Result<()> {
+        let (height, expected_height) = (block.height(), self.indexed_height()?.map_or(0, |height| height + 1));
+        ensure!(height == expected_height, "Expected to index block {expected_height}, found {height}");
+
+        let mut operations = Vec::new();
+        for confirmed in block.transactions().iter() {
+            let transaction_id = confirmed.transaction().id();
+            let transaction_id_bytes = transaction_id.to_bytes_le()?;
+            // Index the location of the transaction.
+            let mut location = height.to_bytes_le()?;
+            location.extend(confirmed.index().to_bytes_le()?);
+            operations.push(BatchOperation::Put(prefixed_key(TRANSACTION_PREFIX, &transaction_id)?, location));
+            // Index the transaction of each record commitment and serial number.
+            for commitment in confirmed.transaction().commitments() {
+                let key = prefixed_key(COMMITMENT_PREFIX, commitment)?;
+                operations.push(BatchOperation::Put(key, transaction_id_bytes.clone()));
+            }
+            for serial_number in confirmed.transaction().serial_numbers() {
+                let key = prefixed_key(SERIAL_NUMBER_PREFIX, serial_number)?;
+                operations.push(BatchOperation::Put(key, transaction_id_bytes.clone()));
+            }
+            // Index the activity of the referenced addresses.
+            for transition in confirmed.transaction().transitions() {
+                for address in referenced_addresses(transition) {
+                    let key = activity_key(&address, height, transition.id())?;
+                    operations.push(BatchOperation::Put(key, transaction_id_bytes.clone()));
+                }
+            }
+        }
+        operations.push(BatchOperation::Put(HEIGHT_KEY.to_vec(), height.to_bytes_le()?));
+        self.storage.batch(operations)
+    }
+
+    /// Indexes the blocks of the given ledger after the latest indexed block, and returns the number of indexed blocks.
+    pub fn sync<N: Network, C: ConsensusStorage<N>>(&self, ledger: &Ledger<N, C>) -> Result<u32> {
+        let start_height = self.indexed_height()?.map_or(0, |height| height + 1);
+        let end_height = ledger.latest_height().saturating_add(1);
+        for block in ledger.blocks(start_height..end_height) {
+            self.index_block(&block?)?;
+        }
+        Ok(end_height.saturating_sub(start_height))
+    }
+
+    /// Returns the location of the given transaction, if it is indexed.
+    pub fn transaction_location<N: Network>(
+        &self,
+        transaction_id: &N::TransactionID,
+    ) -> Result<Option<TransactionLocation>> {
+        match self.storage.get(&prefixed_key(TRANSACTION_PREFIX, transaction_id)?)? {
+            Some(bytes) => {
+                ensure!(bytes.len() == 8, "The address index contains a malformed transaction location");
+                let height = u32::from_bytes_le(&bytes[..4])?;
+                let index = u32::from_bytes_le(&bytes[4..])?;
+                Ok(Some(TransactionLocation { height, index }))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the ID of the transaction that contains the given record commitment or serial number, if it is indexed.
+    pub fn find_transaction_id_from_record<N: Network>(
+        &self,
+        commitment_or_serial_number: &Field<N>,
+    ) -> Result<Option<N::TransactionID>> {
+        for prefix in [COMMITMENT_PREFIX, SERIAL_NUMBER_PREFIX] {
+            if let Some(bytes) = self.storage.get(&prefixed_key(prefix, commitment_or_serial_number)?)? {
+                return Ok(Some(N::TransactionID::from_bytes_le(&bytes)?));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns a page of the transitions that reference the given address, from the most recent to the oldest,
+    /// skipping the given number of transitions, and returning at most `MAX_ADDRESS_HISTORY_PAGE_SIZE` transitions.
+    pub fn history<N: Network>(
+        &self,
+        address: &Address<N>,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<AddressActivity<N>>> {
+        let mut prefix = vec![ACTIVITY_PREFIX];
+        prefix.extend(address.to_bytes_le()?);
+        let limit = limit.min(MAX_ADDRESS_HISTORY_PAGE_SIZE);
+        // Note: The keys are in order of block height, as the heights are encoded in big-endian.
+        let entries = self.storage.iterate(&prefix)?;
+        entries
+            .iter()
+            .rev()
+            .skip(offset)
+            .take(limit)
+            .map(|(key, value)| parse_activity(&key[prefix.len()..], value))
+            .collect()
+    }
+
+    /// Returns a page of the transitions that reference the given address, from the most recent to the oldest,
+    /// starting after the given activity if one is given, and returning at most the given number of transitions.
+    /// Unlike an offset, the activity remains a stable position as new blocks are indexed.
+    pub fn history_before<N: Network>(
+        &self,
+        address: &Address<N>,
+        before: Option<(u32, N::TransitionID)>,
+        limit: usize,
+    ) -> Result<Vec<AddressActivity<N>>> {
+        let mut prefix = vec![ACTIVITY_PREFIX];
+        prefix.extend(address.to_bytes_le()?);
+        let end = before.map(|(height, transition_id)| activity_key(address, height, &transition_id)).transpose()?;
+        let entries = self.storage.iterate(&prefix)?;
+        entries
+            .iter()
+            .rev()
+            .filter(|(key, _)| end.as_ref().map_or(true, |end| key < end))
+            .take(limit)
+            .map(|(key, value)| parse_activity(&key[prefix.len()..], value))
+            .collect()
+    }
+}
+
+/// Returns the activity of the given key suffix, after the address, and the given value.
+fn parse_activity<N: Network>(suffix: &[u8], value: &[u8]) -> Result<AddressActivity<N>> {
+    ensure!(suffix.len() > 4, "The address index contains a malformed key");
+    let height = u32::from_be_bytes([suffix[0], suffix[1], suffix[2], suffix[3]]);
+    let transition_id = N::TransitionID::from_bytes_le(&suffix[4..])?;
+    let transaction_id = N::TransactionID::from_bytes_le(value)?;
+    Ok(AddressActivity { height, transition_id, transaction_id })
+}
+
+impl<N: Network> BlockIndex<N> for AddressIndex {
+    /// Returns the latest indexed block height, if any block is indexed.
+    fn indexed_height(&self) -> Result<Option<u32>> {
+        AddressIndex::indexed_height(self)
+    }
+
+    /// Indexes the given block, which must be the next block after the latest indexed block.
+    fn index_block(&self, block: &Block<N>) -> Result<()> {
+        AddressIndex::index_block(self, block)
+    }
+}
+
+/// Returns the key of the given value under the given prefix.
+fn prefixed_key(prefix: u8, value: &impl ToBytes) -> Result<Vec<u8>> {
+    let mut key = vec![prefix];
+    key.extend(value.to_bytes_le()?);
+    Ok(key)
+}
+
+/// Returns the key of the activity of the given address in the given transition.
+fn activity_key<N: Network>(address: &Address<N>, height: u32, transition_id: &N::TransitionID) -> Result<Vec<u8>> {
+    let mut key = vec![ACTIVITY_PREFIX];
+    key.extend(address.to_bytes_le()?);
+    key.extend(height.to_be_bytes());
+    key.extend(transition_id.to_bytes_le()?);
+    Ok(key)
+}
+
+/// Returns the addresses that are referenced by the public inputs and outputs of the given transition.
+fn referenced_addresses<N: Network>(transition: &Transition<N>) -> HashSet<Address<N>> {
+    let mut addresses = HashSet::new();
+    for input in transition.inputs() {
+        if let Input::Constant(_, Some(plaintext)) | Input::Public(_, Some(plaintext)) = input {
+            collect_plaintext_addresses(plaintext, &mut addresses);
+        }
+    }
+    for output in transition.outputs() {
+        match output {
+            Output::Constant(_, Some(plaintext)) | Output::Public(_, Some(plaintext)) => {
+                collect_plaintext_addresses(plaintext, &mut addresses)
+            }
+            Output::Record(_, _, Some(record)) => {
+                if let Owner::Public(owner) = record.owner() {
+                    addresses.insert(*owner);
+                }
+            }
+            Output::Future(_, Some(future)) => collect_future_addresses(future, &mut addresses),
+            _ => (),
+        }
+    }
+    addresses
+}
+
+/// Collects the addresses in the given plaintext.
+fn collect_plaintext_addresses<N: Network>(plaintext: &Plaintext<N>, addresses: &mut HashSet<Address<N>>) {
+    match plaintext {
+        Plaintext::Literal(Literal::Address(address), _) => {
+            addresses.insert(*address);
+        }
+        Plaintext::Literal(..) => (),
+        Plaintext::Struct(members, _) => {
+            members.values().for_each(|member| collect_plaintext_addresses(member, addresses))
+        }
+        Plaintext::Array(elements, _) => {
+            elements.iter().for_each(|element| collect_plaintext_addresses(element, addresses))
+        }
+    }
+}
+
+/// Collects the addresses in the arguments of the given future.
+fn collect_future_addresses<N: Network>(future: &Future<N>, addresses: &mut HashSet<Address<N>>) {
+    for argument in future.arguments() {
+        match argument {
+            Argument::Plaintext(plaintext) => collect_plaintext_addresses(plaintext, addresses),
+            Argument::Future(future) => collect_future_addresses(future, addresses),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_node_bft_storage_service::MemoryStorage;
+    use snarkvm::{
+        ledger::{ledger_test_helpers::sample_genesis_block, store::helpers::memory::ConsensusMemory},
+        prelude::{PrivateKey, TestRng, Uniform},
+    };
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_address_index() {
+        let rng = &mut TestRng::default();
+        let genesis = sample_genesis_block(rng);
+        let ledger = Ledger::<CurrentNetwork, ConsensusMemory<_>>::load(genesis.clone(), None).unwrap();
+
+        // Index the ledger, and ensure a block is only indexed once.
+        let index = AddressIndex::new(Box::new(MemoryStorage::new()));
+        assert_eq!(index.indexed_height().unwrap(), None);
+        assert_eq!(index.sync(&ledger).unwrap(), 1);
+        assert_eq!(index.indexed_height().unwrap(), Some(0));
+        assert_eq!(index.sync(&ledger).unwrap(), 0);
+        assert!(index.index_block(&genesis).is_err());
+
+        // Ensure the history of each referenced address contains the transitions that reference it.
+        for confirmed in genesis.transactions().iter() {
+            for transition in confirmed.transaction().transitions() {
+                for address in referenced_addresses(transition) {
+                    let history = index.history(&address, 0, MAX_ADDRESS_HISTORY_PAGE_SIZE).unwrap();
+                    let activity = AddressActivity {
+                        height: 0,
+                        transition_id: *transition.id(),
+                        transaction_id: confirmed.transaction().id(),
+                    };
+                    assert!(history.contains(&activity));
+                    // Ensure the history after the activity continues from it.
+                    let position = history.iter().position(|entry| *entry == activity).unwrap();
+                    let before = Some((activity.height, activity.transition_id));
+                    let rest = index.history_before(&address, before, MAX_ADDRESS_HISTORY_PAGE_SIZE).unwrap();
+                    assert_eq!(rest, history[position + 1..]);
+                }
+            }
+        }
+
+        // Ensure the location of each transaction, and the transaction of each record, are indexed.
+        for confirmed in genesis.transactions().iter() {
+            let transaction_id = confirmed.transaction().id();
+            let location = TransactionLocation { height: 0, index: confirmed.index() };
+            assert_eq!(index.transaction_location::<CurrentNetwork>(&transaction_id).unwrap(), Some(location));
+            let transaction = confirmed.transaction();
+            for record in transaction.commitments().chain(transaction.serial_numbers()) {
+                let found = index.find_transaction_id_from_record::<CurrentNetwork>(record).unwrap();
+                assert_eq!(found, Some(transaction_id));
+            }
+        }
+        // Ensure a transaction or record that is not in the ledger is not indexed.
+        let transaction_id = <CurrentNetwork as Network>::TransactionID::from(Field::rand(rng));
+        assert_eq!(index.transaction_location::<CurrentNetwork>(&transaction_id).unwrap(), None);
+        let record = Field::<CurrentNetwork>::rand(rng);
+        assert_eq!(index.find_transaction_id_from_record::<CurrentNetwork>(&record).unwrap(), None);
+
+        // Ensure an address without activity has no history.
+        let address = Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+        assert!(index.history(&address, 0, MAX_ADDRESS_HISTORY_PAGE_SIZE).unwrap().is_empty());
+
+        // Ensure the keys of an address are in order of block height.
+        let transition_id = genesis.transitions().next().unwrap().id();
+        let key_1 = activity_key(&address, 1, transition_id).unwrap();
+        let key_256 = activity_key(&address, 256, transition_id).unwrap();
+        assert!(key_1 < key_256);
+    }
+}
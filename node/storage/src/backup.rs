@@ -0,0 +1,185 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::snapshot::checkpoint_ledger;
+use snarkvm::prelude::{store::ConsensusStorage, Ledger, Network};
+
+use anyhow::{bail, ensure, Result};
+use parking_lot::Mutex;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The prefix of the name of a ledger backup directory.
+const BACKUP_PREFIX: &str = "backup-";
+/// The default number of ledger backups that are retained.
+pub const DEFAULT_MAX_BACKUPS: usize = 5;
+
+/// The directory of the ledger backups, and the number of most recent backups to retain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BackupConfig {
+    /// The directory of the ledger backups.
+    backup_dir: PathBuf,
+    /// The number of most recent backups to retain.
+    max_backups: usize,
+}
+
+impl BackupConfig {
+    /// Initializes the ledger backups into the given directory, retaining the given number of most recent backups.
+    pub fn new(backup_dir: PathBuf, max_backups: usize) -> Result<Self> {
+        ensure!(max_backups > 0, "At least one ledger backup must be retained");
+        Ok(Self { backup_dir, max_backups })
+    }
+
+    /// Returns the directory of the ledger backups.
+    pub fn backup_dir(&self) -> &Path {
+        &self.backup_dir
+    }
+
+    /// Returns the number of most recent backups to retain.
+    pub const fn max_backups(&self) -> usize {
+        self.max_backups
+    }
+}
+
+/// Creates a consistent point-in-time backup of the given running ledger in the given directory,
+/// removes all but the given number of most recent backups, and returns the path of the new backup.
+///
+/// Each backup is a RocksDB checkpoint, which is restored by copying it into place as the ledger directory.
+/// Note: Every directory in the given directory whose name starts with `backup-` is subject to the retention.
+pub fn create_backup<N: Network, C: ConsensusStorage<N>>(
+    ledger: &Ledger<N, C>,
+    backup_dir: &Path,
+    max_backups: usize,
+) -> Result<PathBuf> {
+    // Ensure only one backup is created at a time.
+    static BACKUP_LOCK: Mutex<()> = Mutex::new(());
+    let Some(_lock) = BACKUP_LOCK.try_lock() else { bail!("A ledger backup is already being created") };
+
+    // Name the backup by its creation time and height, so that the backups sort from oldest to newest.
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let path = backup_dir.join(format!("{BACKUP_PREFIX}{timestamp:020}-{}", ledger.latest_height()));
+    ensure!(!path.exists(), "The ledger backup '{}' already exists", path.display());
+
+    // Write the checkpoint of the ledger.
+    fs::create_dir_all(backup_dir)?;
+    checkpoint_ledger(ledger, &path)?;
+    info!("Created the ledger backup at '{}'", path.display());
+
+    // Remove the oldest backups.
+    prune_backups(backup_dir, max_backups)?;
+    Ok(path)
+}
+
+/// Returns the path and the height of the backup with the highest height at or below the given height
+/// in the given directory, if there is one.
+pub fn latest_backup(backup_dir: &Path, max_height: u32) -> Result<Option<(PathBuf, u32)>> {
+    let mut latest = None;
+    for backup in list_backups(backup_dir)? {
+        // Parse the height of the backup, which ends its name.
+        let name = backup.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+        let Some(Ok(height)) = name.rsplit_once('-').map(|(_, height)| height.parse::<u32>()) else { continue };
+        if height <= max_height && latest.as_ref().map_or(true, |(_, latest_height)| height >= *latest_height) {
+            latest = Some((backup, height));
+        }
+    }
+    Ok(latest)
+}
+
+/// Copies the ledger checkpoint in the given directory into the given (new) directory, hard-linking the table files,
+/// which RocksDB never modifies, and copying the other files.
+pub(crate) fn copy_checkpoint(checkpoint_dir: &Path, destination: &Path) -> Result<()> {
+    ensure!(!destination.exists(), "'{}' already exists", destination.display());
+    fs::create_dir_all(destination)?;
+    for entry in fs::read_dir(checkpoint_dir)? {
+        let entry = entry?;
+        ensure!(entry.file_type()?.is_file(), "The ledger checkpoint contains a subdirectory");
+        let path = destination.join(entry.file_name());
+        // Copy the table files that cannot be hard-linked, such as across file systems.
+        let is_table = entry.path().extension().map_or(false, |extension| extension == "sst");
+        if !is_table || fs::hard_link(entry.path(), &path).is_err() {
+            fs::copy(entry.path(), &path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the backups in the given directory, from oldest to newest.
+fn list_backups(backup_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(backup_dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() && entry.file_name().to_string_lossy().starts_with(BACKUP_PREFIX) {
+            backups.push(entry.path());
+        }
+    }
+    backups.sort();
+    Ok(backups)
+}
+
+/// Removes all but the given number of most recent backups in the given directory.
+fn prune_backups(backup_dir: &Path, max_backups: usize) -> Result<()> {
+    let backups = list_backups(backup_dir)?;
+    // Remove the oldest backups.
+    for backup in backups.iter().take(backups.len().saturating_sub(max_backups)) {
+        fs::remove_dir_all(backup)?;
+        debug!("Removed the ledger backup at '{}'", backup.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prune_backups() {
+        let backup_dir = std::env::temp_dir().join(format!("ledger-backups-{}", std::process::id()));
+        for name in ["backup-3-30", "backup-1-10", "backup-2-20"] {
+            fs::create_dir_all(backup_dir.join(name)).unwrap();
+        }
+        // Ensure unrelated entries are never removed.
+        fs::create_dir_all(backup_dir.join("other")).unwrap();
+        fs::write(backup_dir.join("backup-notes"), b"").unwrap();
+
+        // Ensure the most recent backups are retained.
+        prune_backups(&backup_dir, 2).unwrap();
+        let mut names = fs::read_dir(&backup_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, ["backup-2-20", "backup-3-30", "backup-notes", "other"]);
+
+        fs::remove_dir_all(backup_dir).unwrap();
+    }
+
+    #[test]
+    fn test_latest_backup() {
+        let backup_dir = std::env::temp_dir().join(format!("ledger-latest-backup-{}", std::process::id()));
+        for name in ["backup-1-10", "backup-2-30", "backup-3-20", "backup-4-x", "other-5-15"] {
+            fs::create_dir_all(backup_dir.join(name)).unwrap();
+        }
+
+        // Ensure the backup with the highest height at or below the given height is returned.
+        assert_eq!(latest_backup(&backup_dir, 25).unwrap(), Some((backup_dir.join("backup-3-20"), 20)));
+        assert_eq!(latest_backup(&backup_dir, 30).unwrap(), Some((backup_dir.join("backup-2-30"), 30)));
+        assert_eq!(latest_backup(&backup_dir, 10).unwrap(), Some((backup_dir.join("backup-1-10"), 10)));
+        assert_eq!(latest_backup(&backup_dir, 9).unwrap(), None);
+
+        fs::remove_dir_all(backup_dir).unwrap();
+    }
+}
@@ -0,0 +1,460 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{
+    authority::Authority,
+    block::{Block, Header, Ratifications, Transaction, Transactions},
+    coinbase::CoinbaseSolution,
+    error,
+    store::ConsensusStorage,
+    FromBytes,
+    Ledger,
+    Network,
+    ToBytes,
+};
+
+use anyhow::Result;
+use flate2::{read::DeflateDecoder, write::DeflateEncoder};
+use lru::LruCache;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::{
+    hash::Hash,
+    io::{Read, Write},
+    num::NonZeroUsize,
+    ops::Range,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+/// The default number of block bodies in the ledger cache.
+pub const DEFAULT_CACHED_BLOCKS: usize = 128; // blocks
+/// The default number of block headers in the ledger cache.
+pub const DEFAULT_CACHED_HEADERS: usize = 4_096; // headers
+/// The default number of transactions in the ledger cache.
+pub const DEFAULT_CACHED_TRANSACTIONS: usize = 4_096; // transactions
+/// The number of block bodies in the ledger cache of the low-memory profile.
+pub const LOW_MEMORY_CACHED_BLOCKS: usize = 16; // blocks
+/// The number of block headers in the ledger cache of the low-memory profile.
+pub const LOW_MEMORY_CACHED_HEADERS: usize = 512; // headers
+/// The number of transactions in the ledger cache of the low-memory profile.
+pub const LOW_MEMORY_CACHED_TRANSACTIONS: usize = 512; // transactions
+
+/// The sizes of the ledger cache, as numbers of entries, where a size of 0 disables the cache.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LedgerCacheConfig {
+    /// The number of block bodies, which are combined with the cached block headers into the blocks.
+    pub blocks: usize,
+    /// The number of block headers.
+    pub headers: usize,
+    /// The number of transactions.
+    pub transactions: usize,
+    /// If `true`, the entries are stored as compressed bytes, and decoded on each lookup.
+    pub compressed: bool,
+}
+
+impl LedgerCacheConfig {
+    /// Returns the sizes of the ledger cache for the low-memory profile, which stores compressed entries.
+    pub const fn low_memory() -> Self {
+        Self {
+            blocks: LOW_MEMORY_CACHED_BLOCKS,
+            headers: LOW_MEMORY_CACHED_HEADERS,
+            transactions: LOW_MEMORY_CACHED_TRANSACTIONS,
+            compressed: true,
+        }
+    }
+}
+
+impl Default for LedgerCacheConfig {
+    fn default() -> Self {
+        Self {
+            blocks: DEFAULT_CACHED_BLOCKS,
+            headers: DEFAULT_CACHED_HEADERS,
+            transactions: DEFAULT_CACHED_TRANSACTIONS,
+            compressed: false,
+        }
+    }
+}
+
+/// The numbers of hits and misses of a cache.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    /// The number of lookups that were served from the cache.
+    pub hits: u64,
+    /// The number of lookups that were read from storage.
+    pub misses: u64,
+}
+
+/// The numbers of hits and misses of the ledger cache.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct LedgerCacheStats {
+    /// The numbers of hits and misses of the block bodies.
+    pub bodies: CacheStats,
+    /// The numbers of hits and misses of the block headers.
+    pub headers: CacheStats,
+    /// The numbers of hits and misses of the transactions.
+    pub transactions: CacheStats,
+}
+
+/// An entry of a cache, which holds either the value, or its compressed bytes.
+#[derive(Clone)]
+enum Entry<V> {
+    /// The value.
+    Value(V),
+    /// The compressed bytes of the value.
+    Compressed(Arc<[u8]>),
+}
+
+impl<V: Clone + ToBytes + FromBytes> Entry<V> {
+    /// Initializes the entry for the given value, which is compressed if requested.
+    fn new(value: &V, compressed: bool) -> Result<Self> {
+        match compressed {
+            true => {
+                let mut encoder = DeflateEncoder::new(Vec::new(), flate2::Compression::fast());
+                encoder.write_all(&value.to_bytes_le()?)?;
+                Ok(Self::Compressed(encoder.finish()?.into()))
+            }
+            false => Ok(Self::Value(value.clone())),
+        }
+    }
+
+    /// Returns the value of the entry, decompressing it if needed.
+    fn value(&self) -> Result<V> {
+        match self {
+            Self::Value(value) => Ok(value.clone()),
+            Self::Compressed(bytes) => {
+                let mut decompressed = Vec::new();
+                DeflateDecoder::new(&bytes[..]).read_to_end(&mut decompressed)?;
+                V::from_bytes_le(&decompressed)
+            }
+        }
+    }
+}
+
+/// The body of a block, which is the block without its header.
+#[derive(Clone)]
+struct BlockBody<N: Network> {
+    /// The hash of the block.
+    block_hash: N::BlockHash,
+    /// The hash of the previous block.
+    previous_hash: N::BlockHash,
+    /// The authority for the block.
+    authority: Authority<N>,
+    /// The ratifications in the block.
+    ratifications: Ratifications<N>,
+    /// The solutions in the block.
+    solutions: Option<CoinbaseSolution<N>>,
+    /// The transactions in the block.
+    transactions: Transactions<N>,
+    /// The aborted transaction IDs in the block.
+    aborted_transaction_ids: Vec<N::TransactionID>,
+}
+
+impl<N: Network> BlockBody<N> {
+    /// Returns the body of the given block.
+    fn new(block: &Block<N>) -> Self {
+        Self {
+            block_hash: block.hash(),
+            previous_hash: block.previous_hash(),
+            authority: block.authority().clone(),
+            ratifications: block.ratifications().clone(),
+            solutions: block.solutions().cloned(),
+            transactions: block.transactions().clone(),
+            aborted_transaction_ids: block.aborted_transaction_ids().clone(),
+        }
+    }
+
+    /// Returns the block with the given header and this body.
+    ///
+    /// Note: The block is not verified again, as both parts were read from the ledger.
+    fn into_block(self, header: Header<N>) -> Result<Block<N>> {
+        Block::from_unchecked(
+            self.block_hash,
+            self.previous_hash,
+            header,
+            self.authority,
+            self.ratifications,
+            self.solutions,
+            self.transactions,
+            self.aborted_transaction_ids,
+        )
+    }
+}
+
+impl<N: Network> ToBytes for BlockBody<N> {
+    fn write_le<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        self.block_hash.write_le(&mut writer)?;
+        self.previous_hash.write_le(&mut writer)?;
+        self.authority.write_le(&mut writer)?;
+        self.ratifications.write_le(&mut writer)?;
+        match &self.solutions {
+            None => 0u8.write_le(&mut writer)?,
+            Some(solutions) => {
+                1u8.write_le(&mut writer)?;
+                solutions.write_le(&mut writer)?;
+            }
+        }
+        self.transactions.write_le(&mut writer)?;
+        u32::try_from(self.aborted_transaction_ids.len()).map_err(error)?.write_le(&mut writer)?;
+        self.aborted_transaction_ids.write_le(&mut writer)
+    }
+}
+
+impl<N: Network> FromBytes for BlockBody<N> {
+    fn read_le<R: Read>(mut reader: R) -> std::io::Result<Self> {
+        let block_hash = N::BlockHash::read_le(&mut reader)?;
+        let previous_hash = N::BlockHash::read_le(&mut reader)?;
+        let authority = Authority::read_le(&mut reader)?;
+        let ratifications = Ratifications::read_le(&mut reader)?;
+        let solutions = match u8::read_le(&mut reader)? {
+            0 => None,
+            1 => Some(CoinbaseSolution::read_le(&mut reader)?),
+            _ => return Err(error("Invalid solutions variant in the block body")),
+        };
+        let transactions = Transactions::read_le(&mut reader)?;
+        // Read the aborted transaction IDs.
+        let num_aborted = u32::read_le(&mut reader)? as usize;
+        if num_aborted > Transactions::<N>::MAX_TRANSACTIONS {
+            return Err(error("Invalid number of aborted transaction IDs in the block body"));
+        }
+        let mut aborted_transaction_ids = Vec::with_capacity(num_aborted);
+        for _ in 0..num_aborted {
+            aborted_transaction_ids.push(N::TransactionID::read_le(&mut reader)?);
+        }
+        Ok(Self {
+            block_hash,
+            previous_hash,
+            authority,
+            ratifications,
+            solutions,
+            transactions,
+            aborted_transaction_ids,
+        })
+    }
+}
+
+/// A least-recently-used cache, which counts its hits and misses.
+struct Cache<K: Hash + Eq, V> {
+    /// The name of the cache, as the label of its metrics.
+    #[cfg_attr(not(feature = "metrics"), allow(dead_code))]
+    name: &'static str,
+    /// The cached entries, if the cache is enabled.
+    entries: Option<Mutex<LruCache<K, Entry<V>>>>,
+    /// If `true`, the entries are stored as compressed bytes.
+    compressed: bool,
+    /// The number of hits.
+    hits: AtomicU64,
+    /// The number of misses.
+    misses: AtomicU64,
+}
+
+impl<K: Hash + Eq + Copy, V: Clone + ToBytes + FromBytes> Cache<K, V> {
+    /// Initializes a new cache with the given name and capacity, which is disabled if the capacity is 0,
+    /// and which stores its entries as compressed bytes if requested.
+    fn new(name: &'static str, capacity: usize, compressed: bool) -> Self {
+        let entries = NonZeroUsize::new(capacity).map(|capacity| Mutex::new(LruCache::new(capacity)));
+        Self { name, entries, compressed, hits: Default::default(), misses: Default::default() }
+    }
+
+    /// Returns the cached value for the given key, or loads it with the given closure and caches it.
+    ///
+    /// Note: The lock is not held while the value is loaded, so concurrent misses on the same key
+    /// may load it more than once.
+    fn get_or_load(&self, key: K, load: impl FnOnce() -> Result<V>) -> Result<V> {
+        // If the cache is disabled, load the value.
+        let Some(entries) = &self.entries else {
+            return load();
+        };
+        if let Some(entry) = entries.lock().get(&key).cloned() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            #[cfg(feature = "metrics")]
+            metrics::increment_counter_with_label(metrics::ledger::CACHE_HITS, "cache", self.name.to_string());
+            return entry.value();
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        #[cfg(feature = "metrics")]
+        metrics::increment_counter_with_label(metrics::ledger::CACHE_MISSES, "cache", self.name.to_string());
+        // Load the value, and cache it if it exists.
+        let value = load()?;
+        entries.lock().put(key, Entry::new(&value, self.compressed)?);
+        Ok(value)
+    }
+
+    /// Returns the numbers of hits and misses of the cache.
+    fn stats(&self) -> CacheStats {
+        CacheStats { hits: self.hits.load(Ordering::Relaxed), misses: self.misses.load(Ordering::Relaxed) }
+    }
+}
+
+/// A cache of the recent blocks and transactions of the ledger, which are read repeatedly
+/// by the peers that sync from the node and by the REST API.
+///
+/// The block headers and the block bodies are cached apart, so the header lookups, which are far more
+/// frequent than the block lookups, keep many more headers in memory than the cached blocks, and do not
+/// load the transactions of the blocks.
+///
+/// Note: The blocks are cached by height, as the blocks of the canonical chain are never disconnected
+/// while the node is running; the ledger is only reverted while the node is stopped.
+///
+/// In the low-memory profile, the entries are stored as compressed bytes, which are far smaller than
+/// the decoded blocks and transactions, at the cost of decoding them on each lookup.
+pub struct LedgerCache<N: Network> {
+    /// The block bodies, by height.
+    bodies: Cache<u32, BlockBody<N>>,
+    /// The block headers, by height.
+    headers: Cache<u32, Header<N>>,
+    /// The transactions, by ID.
+    transactions: Cache<N::TransactionID, Transaction<N>>,
+}
+
+impl<N: Network> LedgerCache<N> {
+    /// Initializes a new ledger cache with the given sizes.
+    pub fn new(config: LedgerCacheConfig) -> Self {
+        Self {
+            bodies: Cache::new("bodies", config.blocks, config.compressed),
+            headers: Cache::new("headers", config.headers, config.compressed),
+            transactions: Cache::new("transactions", config.transactions, config.compressed),
+        }
+    }
+
+    /// Returns the block for the given block height.
+    pub fn get_block<C: ConsensusStorage<N>>(&self, ledger: &Ledger<N, C>, height: u32) -> Result<Block<N>> {
+        let header = self.get_header(ledger, height)?;
+        let body = self.bodies.get_or_load(height, || ledger.get_block(height).map(|block| BlockBody::new(&block)))?;
+        body.into_block(header)
+    }
+
+    /// Returns the blocks in the given block range.
+    /// The range is inclusive of the start and exclusive of the end.
+    pub fn get_blocks<C: ConsensusStorage<N>>(
+        &self,
+        ledger: &Ledger<N, C>,
+        heights: Range<u32>,
+    ) -> Result<Vec<Block<N>>> {
+        heights.map(|height| self.get_block(ledger, height)).collect()
+    }
+
+    /// Returns the block header for the given block height.
+    pub fn get_header<C: ConsensusStorage<N>>(&self, ledger: &Ledger<N, C>, height: u32) -> Result<Header<N>> {
+        self.headers.get_or_load(height, || ledger.get_header(height))
+    }
+
+    /// Returns the transaction for the given transaction ID.
+    pub fn get_transaction<C: ConsensusStorage<N>>(
+        &self,
+        ledger: &Ledger<N, C>,
+        transaction_id: N::TransactionID,
+    ) -> Result<Transaction<N>> {
+        self.transactions.get_or_load(transaction_id, || ledger.get_transaction(transaction_id))
+    }
+
+    /// Returns the numbers of hits and misses of the cache.
+    pub fn stats(&self) -> LedgerCacheStats {
+        LedgerCacheStats {
+            bodies: self.bodies.stats(),
+            headers: self.headers.stats(),
+            transactions: self.transactions.stats(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{store::helpers::memory::ConsensusMemory, FromBytes};
+
+    use anyhow::bail;
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_ledger_cache() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let ledger = Ledger::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::load(genesis.clone(), None).unwrap();
+        let transaction_id = *genesis.transaction_ids().next().unwrap();
+
+        // Ensure the first lookups miss, and the repeated lookups hit.
+        let cache = LedgerCache::new(LedgerCacheConfig::default());
+        for _ in 0..3 {
+            assert_eq!(cache.get_block(&ledger, 0).unwrap(), genesis);
+            assert_eq!(cache.get_header(&ledger, 0).unwrap(), *genesis.header());
+            assert_eq!(cache.get_transaction(&ledger, transaction_id).unwrap().id(), transaction_id);
+        }
+        assert_eq!(cache.get_blocks(&ledger, 0..1).unwrap(), vec![genesis.clone()]);
+        let stats = cache.stats();
+        assert_eq!(stats.bodies, CacheStats { hits: 3, misses: 1 });
+        assert_eq!(stats.headers, CacheStats { hits: 6, misses: 1 });
+        assert_eq!(stats.transactions, CacheStats { hits: 2, misses: 1 });
+
+        // Ensure a missing block is not cached.
+        assert!(cache.get_block(&ledger, 1).is_err());
+        assert!(cache.get_block(&ledger, 1).is_err());
+        assert_eq!(cache.stats().headers, CacheStats { hits: 6, misses: 3 });
+
+        // Ensure a disabled cache reads from storage, and does not count the lookups.
+        let cache = LedgerCache::new(LedgerCacheConfig { blocks: 0, headers: 0, transactions: 0, compressed: false });
+        assert_eq!(cache.get_block(&ledger, 0).unwrap(), genesis);
+        assert_eq!(cache.get_block(&ledger, 0).unwrap(), genesis);
+        assert_eq!(cache.stats(), LedgerCacheStats::default());
+
+        // Ensure a compressed cache decodes its entries on the repeated lookups.
+        let cache = LedgerCache::new(LedgerCacheConfig::low_memory());
+        for _ in 0..2 {
+            assert_eq!(cache.get_block(&ledger, 0).unwrap(), genesis);
+            assert_eq!(cache.get_header(&ledger, 0).unwrap(), *genesis.header());
+            assert_eq!(cache.get_transaction(&ledger, transaction_id).unwrap().id(), transaction_id);
+        }
+        assert_eq!(cache.stats().bodies, CacheStats { hits: 1, misses: 1 });
+    }
+
+    #[test]
+    fn test_header_lookups_skip_bodies() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let ledger = Ledger::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::load(genesis.clone(), None).unwrap();
+
+        // Ensure the header lookups do not load the block bodies.
+        let config = LedgerCacheConfig { blocks: 1, ..Default::default() };
+        let cache = LedgerCache::new(config);
+        for _ in 0..2 {
+            assert_eq!(cache.get_header(&ledger, 0).unwrap(), *genesis.header());
+        }
+        assert_eq!(cache.stats().bodies, CacheStats::default());
+        assert_eq!(cache.stats().headers, CacheStats { hits: 1, misses: 1 });
+
+        // Ensure a block lookup reuses the cached header.
+        assert_eq!(cache.get_block(&ledger, 0).unwrap(), genesis);
+        assert_eq!(cache.stats().bodies, CacheStats { hits: 0, misses: 1 });
+        assert_eq!(cache.stats().headers, CacheStats { hits: 2, misses: 1 });
+
+        // Ensure the block body round-trips through its bytes.
+        let body = BlockBody::new(&genesis);
+        let body = BlockBody::<CurrentNetwork>::from_bytes_le(&body.to_bytes_le().unwrap()).unwrap();
+        assert_eq!(body.into_block(*genesis.header()).unwrap(), genesis);
+    }
+
+    #[test]
+    fn test_cache_eviction() {
+        // Ensure the least recently used entry is evicted.
+        let cache = Cache::<u32, u32>::new("test", 2, false);
+        for key in [1, 2, 1, 3] {
+            cache.get_or_load(key, || Ok(key * 10)).unwrap();
+        }
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 3 });
+        assert_eq!(cache.get_or_load(1, || bail!("The entry should be cached")).unwrap(), 10);
+        assert_eq!(cache.get_or_load(2, || Ok(20)).unwrap(), 20);
+        assert_eq!(cache.stats(), CacheStats { hits: 2, misses: 4 });
+    }
+}
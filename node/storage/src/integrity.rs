@@ -0,0 +1,292 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{
+    store::{
+        helpers::Map,
+        BlockStorage,
+        ConsensusStorage,
+    },
+    BlockTree,
+    Ledger,
+    Network,
+    ToBits,
+};
+
+use anyhow::{bail, ensure, Result};
+use std::{fmt, ops::Range};
+
+/// An issue found by an integrity check of the ledger.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IntegrityIssue {
+    /// The height of the block with the issue.
+    pub height: u32,
+    /// The description of the issue.
+    pub description: String,
+    /// Whether the issue was repaired.
+    pub repaired: bool,
+}
+
+/// The report of an integrity check of the ledger.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IntegrityReport {
+    /// The range of block heights that were checked.
+    pub range: Range<u32>,
+    /// The issues that were found.
+    pub issues: Vec<IntegrityIssue>,
+}
+
+impl IntegrityReport {
+    /// Returns `true` if every issue that was found was repaired.
+    pub fn is_ok(&self) -> bool {
+        self.issues.iter().all(|issue| issue.repaired)
+    }
+}
+
+impl fmt::Display for IntegrityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (start, end) = (self.range.start, self.range.end);
+        match self.issues.is_empty() {
+            true => write!(f, "Checked blocks {start} to {end} (exclusive) and found no issues"),
+            false => {
+                write!(f, "Checked blocks {start} to {end} (exclusive) and found {} issue(s):", self.issues.len())?;
+                for issue in &self.issues {
+                    let status = if issue.repaired { " (repaired)" } else { "" };
+                    write!(f, "\n  - Block {}: {}{status}", issue.height, issue.description)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Ensures the block tree of the given ledger matches the state root that was persisted with its latest block.
+///
+/// Note: snarkVM rebuilds the block tree from the stored block hashes when the ledger is loaded, so the stored hashes
+/// and the state roots persisted with each block are the persisted block tree. A ledger that fails this check is
+/// repaired with `rebuild_block_tree`.
+pub fn verify_block_tree<N: Network, C: ConsensusStorage<N>>(ledger: &Ledger<N, C>) -> Result<()> {
+    let latest_height = ledger.latest_height();
+    let Some(state_root) = ledger.get_state_root(latest_height)? else {
+        bail!("The state root of the latest block {latest_height} is missing")
+    };
+    let tree_root = ledger.latest_state_root();
+    ensure!(
+        tree_root == state_root,
+        "The block tree root '{tree_root}' does not match the state root '{state_root}' of block {latest_height}"
+    );
+    Ok(())
+}
+
+/// Rebuilds the block tree of the given ledger from its stored block hashes, ensures it matches the state root that
+/// was persisted with each block, and returns the issues that were found.
+///
+/// If `repair` is `true`, a stored block hash that differs from the previous block hash of the next block is rewritten,
+/// along with its entry in the index of block heights by block hash. The repaired hashes are loaded into the block tree
+/// when the ledger is loaded again. If the block tree does not match, the first block whose state root differs is
+/// reported, and the ledger must be rebuilt from its blocks.
+pub fn rebuild_block_tree<N: Network, C: ConsensusStorage<N>>(
+    ledger: &Ledger<N, C>,
+    repair: bool,
+) -> Result<Vec<IntegrityIssue>> {
+    let latest_height = ledger.latest_height();
+    // Open a handle to the block storage of the ledger, to repair the stored block hashes.
+    let storage = match repair {
+        true => Some(C::BlockStorage::open(ledger.vm().block_store().dev())?),
+        false => None,
+    };
+
+    let mut issues = Vec::new();
+    // Retrieve the stored block hashes, which are the leaves of the block tree.
+    let mut hashes = (0..=latest_height).map(|height| ledger.get_hash(height)).collect::<Result<Vec<_>>>()?;
+    // Ensure each stored block hash matches the previous block hash of the next block, or repair it.
+    for height in 0..latest_height {
+        let Some(expected) = ledger.vm().block_store().get_previous_block_hash(height + 1)? else { continue };
+        let hash = &mut hashes[height as usize];
+        if *hash != expected {
+            let repaired = match &storage {
+                Some(storage) => storage
+                    .id_map()
+                    .insert(height, expected)
+                    .and_then(|_| storage.reverse_id_map().insert(expected, height))
+                    .is_ok(),
+                None => false,
+            };
+            let description = format!("The stored block hash is {hash}, but block {} follows {expected}", height + 1);
+            issues.push(IntegrityIssue { height, description, repaired });
+            if repaired {
+                *hash = expected;
+            }
+        }
+    }
+
+    // Rebuild the block tree up to each given height, and compare it to the state root of the block at the height.
+    let leaves = hashes.iter().map(|hash| hash.to_bits_le()).collect::<Vec<_>>();
+    let matches = |height: u32| -> Result<bool> {
+        let tree: BlockTree<N> = N::merkle_tree_bhp(&leaves[..=height as usize])?;
+        Ok(ledger.get_state_root(height)? == Some((*tree.root()).into()))
+    };
+    if !matches(latest_height)? {
+        // Find the first block whose state root does not match the block tree, as every later root differs as well.
+        let (mut low, mut high) = (0, latest_height);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            match matches(middle)? {
+                true => low = middle + 1,
+                false => high = middle,
+            }
+        }
+        let description = "The block tree does not match the state root of the block".to_string();
+        issues.push(IntegrityIssue { height: low, description, repaired: false });
+    }
+    Ok(issues)
+}
+
+/// Re-validates the block linkage, the transactions roots, and the block and transaction indexes of the given ledger
+/// over the given range of block heights, and returns the issues that were found.
+/// If the range ends at the latest block, the block tree is rebuilt from the stored block hashes as well.
+///
+/// If `repair` is `true`, the recoverable issues are repaired: a missing or incorrect entry in the index of block
+/// heights by block hash is rewritten from the canonical block hash at the height, and a stored block hash that
+/// differs from the previous block hash of the next block is rewritten (see `rebuild_block_tree`).
+/// Note: The blocks below the oldest block height of a pruned ledger are reported as missing.
+pub fn verify_integrity<N: Network, C: ConsensusStorage<N>>(
+    ledger: &Ledger<N, C>,
+    range: Range<u32>,
+    repair: bool,
+) -> Result<IntegrityReport> {
+    // Ensure the range is within the ledger.
+    let latest_height = ledger.latest_height();
+    ensure!(range.start < range.end, "The range of block heights to check is empty");
+    ensure!(range.end <= latest_height + 1, "The range of block heights ends beyond the latest block {latest_height}");
+
+    // Open a handle to the block storage of the ledger, to repair the indexes.
+    let storage = match repair {
+        true => Some(C::BlockStorage::open(ledger.vm().block_store().dev())?),
+        false => None,
+    };
+
+    let mut issues = Vec::new();
+    // Retrieve the hash of the block preceding the range, to check the linkage of the first block.
+    let mut previous_hash = match range.start {
+        0 => None,
+        start => ledger.get_hash(start - 1).ok(),
+    };
+    for height in range.clone() {
+        // Records an issue at the current height.
+        let mut report = |description: String, repaired: bool| {
+            issues.push(IntegrityIssue { height, description, repaired });
+        };
+
+        // Retrieve the block hash from the index of block hashes by height.
+        let hash = match ledger.get_hash(height) {
+            Ok(hash) => hash,
+            Err(error) => {
+                report(format!("The block hash is missing - {error}"), false);
+                previous_hash = None;
+                continue;
+            }
+        };
+        // Retrieve the block.
+        let block = match ledger.get_block(height) {
+            Ok(block) => block,
+            Err(error) => {
+                report(format!("The block is missing or incomplete - {error}"), false);
+                previous_hash = Some(hash);
+                continue;
+            }
+        };
+
+        // Ensure the block is at the height.
+        if block.height() != height {
+            report(format!("The block header is at height {}", block.height()), false);
+        }
+        // Ensure the block hash matches the contents of the block.
+        let preimage = [block.previous_hash().to_bits_le(), block.header().to_root()?.to_bits_le()].concat();
+        if N::hash_bhp1024(&preimage)? != *hash {
+            report("The block hash does not match the block header".to_string(), false);
+        }
+        // Ensure the block links to the previous block.
+        if let Some(previous_hash) = previous_hash {
+            if block.previous_hash() != previous_hash {
+                report(format!("The previous block hash is {}, not {previous_hash}", block.previous_hash()), false);
+            }
+        }
+        // Ensure the transactions root matches the transactions.
+        if block.transactions().to_transactions_root()? != block.header().transactions_root() {
+            report("The transactions root does not match the transactions".to_string(), false);
+        }
+
+        // Ensure the index of block heights by block hash is consistent, or repair it.
+        if ledger.get_height(&hash).ok() != Some(height) {
+            let repaired = match &storage {
+                Some(storage) => storage.reverse_id_map().insert(hash, height).is_ok(),
+                None => false,
+            };
+            report("The block height index is missing or incorrect".to_string(), repaired);
+        }
+        // Ensure the index of block hashes by transaction ID is consistent.
+        for transaction_id in block.transaction_ids() {
+            if ledger.find_block_hash(transaction_id)? != Some(hash) {
+                report(format!("The index of transaction {transaction_id} is missing or incorrect"), false);
+            }
+        }
+        // Ensure the index of transaction IDs by transition ID is consistent.
+        for transaction in block.transactions().iter() {
+            for transition_id in transaction.transition_ids() {
+                if ledger.find_transaction_id_from_transition_id(transition_id)? != Some(transaction.id()) {
+                    report(format!("The index of transition {transition_id} is missing or incorrect"), false);
+                }
+            }
+        }
+
+        previous_hash = Some(hash);
+    }
+
+    // Ensure the block tree rebuilt from the stored block hashes matches the state roots, or repair the hashes.
+    if range.end == latest_height + 1 {
+        issues.extend(rebuild_block_tree(ledger, repair)?);
+    }
+    Ok(IntegrityReport { range, issues })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{
+        ledger::{ledger_test_helpers::sample_genesis_block, store::helpers::memory::ConsensusMemory},
+        prelude::TestRng,
+    };
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_verify_integrity() {
+        let rng = &mut TestRng::default();
+        let ledger = Ledger::<CurrentNetwork, ConsensusMemory<_>>::load(sample_genesis_block(rng), None).unwrap();
+
+        // Ensure the genesis block passes the integrity check.
+        let report = verify_integrity(&ledger, 0..1, false).unwrap();
+        assert!(report.issues.is_empty());
+        assert!(report.is_ok());
+
+        // Ensure the block tree matches the state root of the genesis block.
+        verify_block_tree(&ledger).unwrap();
+        assert!(rebuild_block_tree(&ledger, false).unwrap().is_empty());
+
+        // Ensure the range must be non-empty and within the ledger.
+        assert!(verify_integrity(&ledger, 0..0, false).is_err());
+        assert!(verify_integrity(&ledger, 0..2, false).is_err());
+    }
+}
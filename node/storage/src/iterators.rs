@@ -0,0 +1,143 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{
+    block::{Block, ConfirmedTransaction},
+    store::ConsensusStorage,
+    Ledger,
+    Network,
+};
+
+use anyhow::Result;
+use std::{ops::Range, vec};
+
+/// Range iterators over the history of the ledger, which read from storage one block at a time.
+pub trait LedgerIterators<N: Network, C: ConsensusStorage<N>> {
+    /// Returns an iterator over the blocks in the given range of heights.
+    fn blocks(&self, range: Range<u32>) -> BlockIter<N, C>;
+
+    /// Returns an iterator over the confirmed transactions of the blocks in the given range of heights,
+    /// with the height of their block.
+    fn transactions(&self, range: Range<u32>) -> TransactionIter<N, C>;
+}
+
+impl<N: Network, C: ConsensusStorage<N>> LedgerIterators<N, C> for Ledger<N, C> {
+    fn blocks(&self, range: Range<u32>) -> BlockIter<N, C> {
+        BlockIter { ledger: self.clone(), range }
+    }
+
+    fn transactions(&self, range: Range<u32>) -> TransactionIter<N, C> {
+        TransactionIter { ledger: self.clone(), range, height: 0, transactions: Vec::new().into_iter() }
+    }
+}
+
+/// An iterator over the blocks in a range of heights.
+///
+/// Note: The iterator ends after the first error, such as a height that is not in the ledger.
+pub struct BlockIter<N: Network, C: ConsensusStorage<N>> {
+    /// The ledger.
+    ledger: Ledger<N, C>,
+    /// The remaining range of heights.
+    range: Range<u32>,
+}
+
+impl<N: Network, C: ConsensusStorage<N>> Iterator for BlockIter<N, C> {
+    type Item = Result<Block<N>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let height = self.range.next()?;
+        let block = self.ledger.get_block(height);
+        // End the iterator after an error.
+        if block.is_err() {
+            self.range = Range::default();
+        }
+        Some(block)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.range.len()))
+    }
+}
+
+/// An iterator over the confirmed transactions in a range of heights, with the height of their block.
+///
+/// Note: The iterator holds the transactions of at most one block, and ends after the first error.
+pub struct TransactionIter<N: Network, C: ConsensusStorage<N>> {
+    /// The ledger.
+    ledger: Ledger<N, C>,
+    /// The remaining range of heights.
+    range: Range<u32>,
+    /// The height of the current block.
+    height: u32,
+    /// The remaining transactions of the current block.
+    transactions: vec::IntoIter<ConfirmedTransaction<N>>,
+}
+
+impl<N: Network, C: ConsensusStorage<N>> Iterator for TransactionIter<N, C> {
+    type Item = Result<(u32, ConfirmedTransaction<N>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // Return the next transaction of the current block.
+            if let Some(transaction) = self.transactions.next() {
+                return Some(Ok((self.height, transaction)));
+            }
+            // Read the transactions of the next block.
+            self.height = self.range.next()?;
+            match self.ledger.get_transactions(self.height) {
+                Ok(transactions) => self.transactions = transactions.into_iter().collect::<Vec<_>>().into_iter(),
+                Err(error) => {
+                    // End the iterator after an error.
+                    self.range = Range::default();
+                    return Some(Err(error));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{
+        ledger::{ledger_test_helpers::sample_genesis_block, store::helpers::memory::ConsensusMemory},
+        prelude::TestRng,
+    };
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_ledger_iterators() {
+        let rng = &mut TestRng::default();
+        let genesis = sample_genesis_block(rng);
+        let ledger = Ledger::<CurrentNetwork, ConsensusMemory<_>>::load(genesis.clone(), None).unwrap();
+
+        // Ensure the blocks are iterated in order.
+        let blocks = ledger.blocks(0..1).collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(blocks, vec![genesis.clone()]);
+
+        // Ensure the transactions are iterated in order, with the height of their block.
+        let transactions = ledger.transactions(0..1).collect::<Result<Vec<_>>>().unwrap();
+        let expected = genesis.transactions().iter().map(|transaction| (0, transaction.clone())).collect::<Vec<_>>();
+        assert_eq!(transactions, expected);
+
+        // Ensure the iterators end after a height that is not in the ledger.
+        let mut blocks = ledger.blocks(1..5);
+        assert!(blocks.next().unwrap().is_err());
+        assert!(blocks.next().is_none());
+        let mut transactions = ledger.transactions(0..5);
+        assert_eq!(transactions.by_ref().take_while(|transaction| transaction.is_ok()).count(), expected.len());
+        assert!(transactions.next().is_none());
+    }
+}
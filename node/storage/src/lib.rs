@@ -0,0 +1,70 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![forbid(unsafe_code)]
+
+#[macro_use]
+extern crate tracing;
+
+mod address_index;
+pub use address_index::{AddressActivity, AddressIndex, MAX_ADDRESS_HISTORY_PAGE_SIZE};
+
+mod backup;
+pub use backup::{create_backup, latest_backup, BackupConfig, DEFAULT_MAX_BACKUPS};
+
+mod cache;
+pub use cache::{
+    CacheStats,
+    LedgerCache,
+    LedgerCacheConfig,
+    LedgerCacheStats,
+    DEFAULT_CACHED_BLOCKS,
+    DEFAULT_CACHED_HEADERS,
+    DEFAULT_CACHED_TRANSACTIONS,
+    LOW_MEMORY_CACHED_BLOCKS,
+    LOW_MEMORY_CACHED_HEADERS,
+    LOW_MEMORY_CACHED_TRANSACTIONS,
+};
+
+mod integrity;
+pub use integrity::{rebuild_block_tree, verify_block_tree, verify_integrity, IntegrityIssue, IntegrityReport};
+
+mod iterators;
+pub use iterators::{BlockIter, LedgerIterators, TransactionIter};
+
+mod maintenance;
+pub use maintenance::{compact_ledger, free_disk_space};
+
+mod revert;
+pub use revert::{
+    export_replay,
+    read_replay_plan,
+    replay_staged_ledger,
+    stage_checkpoint,
+    staged_ledger_dir,
+    swap_staged_ledger,
+    ReplayPlan,
+    STAGED_LEDGER_DEV_ID,
+};
+
+mod snapshot;
+pub use snapshot::{
+    checkpoint_storage,
+    export_snapshot,
+    import_snapshot,
+    load_snapshot,
+    verify_snapshot,
+    SnapshotFile,
+    SnapshotManifest,
+};
@@ -0,0 +1,69 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{
+    store::helpers::rocksdb::internal::{self, Database},
+    Network,
+};
+
+use anyhow::Result;
+use std::path::Path;
+
+/// Compacts the whole ledger storage, unless RocksDB is already running a compaction or a flush,
+/// and returns `true` if the ledger was compacted.
+///
+/// Note: The compaction is not exclusive, so the automatic compactions proceed while it runs.
+/// This function is blocking, and may run for minutes on a large ledger.
+pub fn compact_ledger<N: Network>(dev: Option<u16>) -> Result<bool> {
+    let database = internal::RocksDB::open(N::ID, dev)?;
+    // Defer the compaction if RocksDB is busy.
+    for property in ["rocksdb.num-running-compactions", "rocksdb.num-running-flushes"] {
+        if database.property_int_value(property)?.unwrap_or(0) > 0 {
+            return Ok(false);
+        }
+    }
+    let mut options = rocksdb::CompactOptions::default();
+    options.set_exclusive_manual_compaction(false);
+    database.compact_range_opt::<&[u8], &[u8]>(None, None, &options);
+    Ok(true)
+}
+
+/// Returns the disk space that is available to the node on the filesystem of the given path (in bytes),
+/// or `None` if it cannot be measured on this platform.
+pub fn free_disk_space(path: &Path) -> Result<Option<u64>> {
+    #[cfg(target_family = "unix")]
+    {
+        let stat = nix::sys::statvfs::statvfs(path)?;
+        Ok(Some((stat.blocks_available() as u64).saturating_mul(stat.fragment_size() as u64)))
+    }
+    #[cfg(not(target_family = "unix"))]
+    {
+        let _ = path;
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(target_family = "unix")]
+    fn test_free_disk_space() {
+        // Ensure the free disk space of an existing path is measured.
+        assert!(free_disk_space(&std::env::temp_dir()).unwrap().unwrap() > 0);
+        // Ensure a missing path is rejected.
+        assert!(free_disk_space(&std::env::temp_dir().join("missing-free-disk-space")).is_err());
+    }
+}
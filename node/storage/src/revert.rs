@@ -0,0 +1,244 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{backup::copy_checkpoint, verify_block_tree, LedgerIterators};
+use snarkos_node_bft_storage_service::{read_record, write_record};
+use snarkvm::prelude::{block::Block, store::helpers::rocksdb::ConsensusDB, Deserialize, Ledger, Network, Serialize};
+
+use anyhow::{bail, ensure, Result};
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+/// The development ID of the staged ledger, into which the blocks of a revert or a rebuild are replayed.
+pub const STAGED_LEDGER_DEV_ID: u16 = u16::MAX;
+/// The maximum size of the plan in a replay file.
+const MAX_REPLAY_PLAN_SIZE: u32 = 64 * 1024 * 1024; // 64 MiB
+
+/// The plan of a staged replay of the ledger, which is written ahead of the blocks in a replay file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayPlan {
+    /// The network ID of the ledger.
+    pub network: u16,
+    /// The height of the latest block in the ledger, before the replay.
+    pub latest_height: u32,
+    /// The height of the first block to replay.
+    pub start_height: u32,
+    /// The height of the latest block after the replay.
+    pub height: u32,
+    /// The hash of the latest block after the replay.
+    pub block_hash: String,
+    /// The heights and hashes of the removed blocks, from the latest to the oldest.
+    pub removed: Vec<(u32, String)>,
+}
+
+/// Writes the plan and the blocks of a staged replay of the RocksDB ledger of the given (optional) development ID
+/// into a replay file at the given path, from the given start height up to the given height, or up to the latest
+/// block height if no height is given, and returns the plan.
+///
+/// The ledger is only read, so it is left as it is if the replay fails. The replay file is removed on failure.
+pub fn export_replay<N: Network>(
+    genesis: Block<N>,
+    dev: Option<u16>,
+    start_height: u32,
+    height: Option<u32>,
+    path: &Path,
+) -> Result<ReplayPlan> {
+    let ledger_dir = aleo_std::aleo_ledger_dir(N::ID, dev);
+    ensure!(ledger_dir.exists(), "No ledger was found (in \"{}\")", ledger_dir.display());
+    ensure!(!path.exists(), "The replay file '{}' already exists", path.display());
+    let ledger = Ledger::<N, ConsensusDB<N>>::load_unchecked(genesis, dev)?;
+    let latest_height = ledger.latest_height();
+    if let Some(height) = height {
+        ensure!(height < latest_height, "The ledger is at block {latest_height}, which is not above block {height}");
+    }
+    let height = height.unwrap_or(latest_height);
+    ensure!((1..=height + 1).contains(&start_height), "Cannot replay block {height} from block {start_height}");
+
+    // Record the blocks to remove, from the latest to the oldest.
+    let removed = (height + 1..=latest_height)
+        .rev()
+        .map(|removed_height| Ok((removed_height, ledger.get_hash(removed_height)?.to_string())))
+        .collect::<Result<Vec<_>>>()?;
+    let block_hash = ledger.get_hash(height)?.to_string();
+    let plan = ReplayPlan { network: N::ID, latest_height, start_height, height, block_hash, removed };
+
+    // Write the plan and the blocks to replay, one block at a time.
+    let write = || -> Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        write_replay_plan(&plan, &mut writer)?;
+        for block in ledger.blocks(start_height..height + 1) {
+            write_record(&block?, &mut writer)?;
+        }
+        Ok(writer.flush()?)
+    };
+    if let Err(error) = write() {
+        let _ = fs::remove_file(path);
+        return Err(error);
+    }
+    Ok(plan)
+}
+
+/// Writes the given plan of a staged replay, prefixed with its length.
+fn write_replay_plan<W: Write>(plan: &ReplayPlan, writer: &mut W) -> Result<()> {
+    let plan_bytes = serde_json::to_vec(plan)?;
+    writer.write_all(&u32::try_from(plan_bytes.len())?.to_le_bytes())?;
+    Ok(writer.write_all(&plan_bytes)?)
+}
+
+/// Reads the plan of a staged replay from the given reader, which is left at the first block to replay.
+pub fn read_replay_plan<R: Read>(reader: &mut R) -> Result<ReplayPlan> {
+    let mut length = [0u8; 4];
+    reader.read_exact(&mut length)?;
+    let length = u32::from_le_bytes(length);
+    ensure!(length <= MAX_REPLAY_PLAN_SIZE, "The replay plan is too large ({length} bytes)");
+    let mut plan_bytes = vec![0u8; length as usize];
+    reader.read_exact(&mut plan_bytes)?;
+    match serde_json::from_slice::<ReplayPlan>(&plan_bytes) {
+        Ok(plan) => Ok(plan),
+        Err(error) => bail!("Failed to deserialize the replay plan: {error}"),
+    }
+}
+
+/// Returns the directory of the staged ledger of the given network in the given staging directory.
+pub fn staged_ledger_dir(network: u16, staging_dir: &Path) -> Result<PathBuf> {
+    match aleo_std::aleo_ledger_dir(network, Some(STAGED_LEDGER_DEV_ID)).file_name() {
+        Some(name) => Ok(staging_dir.join(name)),
+        None => bail!("The staged ledger of network {network} has no directory name"),
+    }
+}
+
+/// Stages the given ledger checkpoint as the staged ledger of the given network in the given staging directory,
+/// so the replay starts after the block of the checkpoint rather than after the genesis block.
+pub fn stage_checkpoint(network: u16, checkpoint_dir: &Path, staging_dir: &Path) -> Result<()> {
+    copy_checkpoint(checkpoint_dir, &staged_ledger_dir(network, staging_dir)?)
+}
+
+/// Replays the blocks of the replay file at the given path into the staged ledger in the given staging directory,
+/// which starts from its genesis block or from a staged checkpoint, and returns the staged ledger and the plan.
+///
+/// The staged ledger must end at the planned block, and its block tree must match the state root of that block.
+/// Note: snarkVM opens one ledger storage per process, in a directory relative to the current directory in
+/// development mode, so this changes the current directory, and must run in a process of its own.
+pub fn replay_staged_ledger<N: Network>(
+    genesis: Block<N>,
+    staging_dir: &Path,
+    path: &Path,
+) -> Result<(Ledger<N, ConsensusDB<N>>, ReplayPlan)> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let plan = read_replay_plan(&mut reader)?;
+    ensure!(plan.network == N::ID, "The replay file is for network {}, not {}", plan.network, N::ID);
+
+    // Load the staged ledger, and ensure the replay continues it.
+    std::env::set_current_dir(staging_dir)?;
+    let ledger = Ledger::<N, ConsensusDB<N>>::load(genesis, Some(STAGED_LEDGER_DEV_ID))?;
+    let staged_height = ledger.latest_height();
+    ensure!(
+        staged_height + 1 == plan.start_height,
+        "The staged ledger is at block {staged_height}, but the replay starts at block {}",
+        plan.start_height
+    );
+
+    // Replay the blocks, ensuring each block follows the latest block of the staged ledger.
+    for height in plan.start_height..=plan.height {
+        let block = read_record::<Block<N>, _>(&mut reader)?;
+        ensure!(block.height() == height, "Expected block {height} in the replay file, found block {}", block.height());
+        ensure!(block.previous_hash() == ledger.latest_hash(), "Block {height} does not follow the staged ledger");
+        ledger.advance_to_next_block(&block)?;
+    }
+
+    // Ensure the staged ledger ends at the planned block, with a block tree that matches its state root.
+    let block_hash = ledger.latest_hash().to_string();
+    ensure!(block_hash == plan.block_hash, "The staged ledger ends at '{block_hash}', not '{}'", plan.block_hash);
+    verify_block_tree(&ledger)?;
+    Ok((ledger, plan))
+}
+
+/// Swaps the staged ledger in the given directory in as the ledger in the given directory,
+/// and moves the previous ledger to the given directory, which must not exist.
+///
+/// If the staged ledger cannot be moved into place, the previous ledger is moved back.
+/// Note: If the process stops between the two renames, the previous ledger is left in the given directory.
+pub fn swap_staged_ledger(staged_dir: &Path, ledger_dir: &Path, previous_dir: &Path) -> Result<()> {
+    ensure!(staged_dir.exists(), "No staged ledger was found (in \"{}\")", staged_dir.display());
+    ensure!(!previous_dir.exists(), "'{}' already exists", previous_dir.display());
+    fs::rename(ledger_dir, previous_dir)?;
+    if let Err(error) = fs::rename(staged_dir, ledger_dir) {
+        fs::rename(previous_dir, ledger_dir)?;
+        bail!("Failed to move the staged ledger into place - {error}")
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_plan() -> ReplayPlan {
+        ReplayPlan {
+            network: 3,
+            latest_height: 12,
+            start_height: 1,
+            height: 10,
+            block_hash: "ab1hash10".to_string(),
+            removed: vec![(12, "ab1hash12".to_string()), (11, "ab1hash11".to_string())],
+        }
+    }
+
+    #[test]
+    fn test_replay_plan() {
+        let plan = sample_plan();
+        let mut bytes = Vec::new();
+        write_replay_plan(&plan, &mut bytes).unwrap();
+        bytes.extend_from_slice(b"blocks");
+
+        // Ensure the plan is read back, and the reader is left at the blocks.
+        let mut reader = bytes.as_slice();
+        assert_eq!(read_replay_plan(&mut reader).unwrap(), plan);
+        assert_eq!(reader, b"blocks");
+
+        // Ensure an oversized or truncated plan is rejected.
+        assert!(read_replay_plan(&mut (MAX_REPLAY_PLAN_SIZE + 1).to_le_bytes().as_slice()).is_err());
+        assert!(read_replay_plan(&mut &bytes[..bytes.len() - 10]).is_err());
+    }
+
+    #[test]
+    fn test_swap_staged_ledger() {
+        let root = std::env::temp_dir().join(format!("ledger-swap-{}", std::process::id()));
+        let (staged_dir, ledger_dir, previous_dir) = (root.join("staged"), root.join("ledger"), root.join("previous"));
+        fs::create_dir_all(&staged_dir).unwrap();
+        fs::create_dir_all(&ledger_dir).unwrap();
+        fs::write(staged_dir.join("CURRENT"), b"staged").unwrap();
+        fs::write(ledger_dir.join("CURRENT"), b"ledger").unwrap();
+
+        // Ensure an existing previous directory is never overwritten, and the ledger is left as it is.
+        fs::create_dir_all(&previous_dir).unwrap();
+        assert!(swap_staged_ledger(&staged_dir, &ledger_dir, &previous_dir).is_err());
+        assert_eq!(fs::read(ledger_dir.join("CURRENT")).unwrap(), b"ledger");
+        fs::remove_dir(&previous_dir).unwrap();
+
+        // Ensure the staged ledger is swapped in, and the previous ledger is moved aside.
+        swap_staged_ledger(&staged_dir, &ledger_dir, &previous_dir).unwrap();
+        assert_eq!(fs::read(ledger_dir.join("CURRENT")).unwrap(), b"staged");
+        assert_eq!(fs::read(previous_dir.join("CURRENT")).unwrap(), b"ledger");
+        assert!(!staged_dir.exists());
+
+        // Ensure a missing staged ledger is rejected.
+        assert!(swap_staged_ledger(&staged_dir, &ledger_dir, &root.join("other")).is_err());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,572 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::verify_block_tree;
+use snarkos_node_cdn::handle_dispatch_error;
+use snarkvm::prelude::{
+    store::{
+        helpers::rocksdb::{
+            internal::{self, Database},
+            ConsensusDB,
+        },
+        ConsensusStorage,
+    },
+    Deserialize,
+    Ledger,
+    Network,
+    Serialize,
+};
+
+use anyhow::{anyhow, bail, ensure, Result};
+use parking_lot::Mutex;
+use reqwest::Client;
+use rocksdb::checkpoint::Checkpoint;
+use sha2::{Digest, Sha256};
+use std::{
+    any::TypeId,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Component, Path, PathBuf},
+};
+use tokio::io::AsyncWriteExt;
+
+/// The name of the snapshot manifest file.
+const SNAPSHOT_MANIFEST: &str = "snapshot.json";
+/// The magic bytes at the start of a snapshot archive.
+const SNAPSHOT_ARCHIVE_MAGIC: &[u8; 8] = b"SNAPSHOT";
+/// The maximum size of the manifest in a snapshot archive.
+const MAX_SNAPSHOT_MANIFEST_SIZE: u32 = 16 * 1024 * 1024; // 16 MiB
+/// The size of the buffer with which the files of a snapshot archive are imported.
+const SNAPSHOT_IMPORT_BUFFER_SIZE: usize = 64 * 1024; // 64 KiB
+/// The maximum number of attempts to export a snapshot, while blocks are being added to the ledger.
+const MAX_SNAPSHOT_EXPORT_ATTEMPTS: usize = 3;
+
+/// The manifest of a ledger snapshot, which lists the files of the ledger storage at the snapshot height.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    /// The network ID of the ledger.
+    pub network: u16,
+    /// The height of the latest block in the snapshot.
+    pub height: u32,
+    /// The hash of the latest block in the snapshot.
+    pub block_hash: String,
+    /// The height of the latest block in the ledger storage, which may be above the snapshot height.
+    pub latest_height: u32,
+    /// The checksum of the finalize state of the ledger storage, at the latest height.
+    pub finalize_checksum: String,
+    /// The files of the ledger storage.
+    pub files: Vec<SnapshotFile>,
+}
+
+impl SnapshotManifest {
+    /// Checks that the snapshot is at the given trusted checkpoint.
+    fn check_checkpoint<N: Network>(&self, (checkpoint_height, checkpoint_hash): (u32, N::BlockHash)) -> Result<()> {
+        if self.height != checkpoint_height || self.block_hash != checkpoint_hash.to_string() {
+            bail!(
+                "The snapshot at block {} ({}) does not match the checkpoint at block {checkpoint_height} ({checkpoint_hash})",
+                self.height,
+                self.block_hash
+            )
+        }
+        Ok(())
+    }
+}
+
+/// A file of the ledger storage in a snapshot.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotFile {
+    /// The path of the file, relative to the ledger directory.
+    pub path: String,
+    /// The size of the file in bytes.
+    pub size: u64,
+    /// The hex-encoded SHA-256 checksum of the file.
+    pub checksum: String,
+}
+
+impl SnapshotFile {
+    /// Checks that the path is a plain relative path, so that it does not escape the ledger directory.
+    fn check_path(&self) -> Result<()> {
+        let path = Path::new(&self.path);
+        ensure!(
+            path.components().next().is_some() && path.components().all(|c| matches!(c, Component::Normal(_))),
+            "The snapshot file '{}' has an invalid path",
+            self.path
+        );
+        Ok(())
+    }
+
+    /// Checks that the given size and hex-encoded checksum match the file.
+    fn check_contents(&self, size: u64, checksum: &str) -> Result<()> {
+        // Ensure the size matches.
+        ensure!(size == self.size, "The snapshot file '{}' has an incorrect size", self.path);
+        // Ensure the checksum matches.
+        ensure!(
+            checksum == self.checksum.to_lowercase(),
+            "The snapshot file '{}' has an incorrect checksum",
+            self.path
+        );
+        Ok(())
+    }
+}
+
+/// Downloads the ledger snapshot at the given base URL into the given (empty) ledger directory,
+/// after checking that the snapshot is at the given trusted checkpoint.
+///
+/// The ledger must then be loaded and checked with [`verify_snapshot`], before it is used.
+/// On success, this function returns the manifest of the snapshot.
+pub async fn load_snapshot<N: Network>(
+    base_url: &str,
+    ledger_dir: &Path,
+    (checkpoint_height, checkpoint_hash): (u32, N::BlockHash),
+) -> Result<SnapshotManifest> {
+    // Ensure the ledger directory does not exist.
+    ensure!(!ledger_dir.exists(), "Cannot load a snapshot into the existing ledger at '{}'", ledger_dir.display());
+
+    // Create a Client to maintain a connection pool throughout the download.
+    let client = match Client::builder().build() {
+        Ok(client) => client,
+        Err(error) => bail!("Failed to create a snapshot request client: {error}"),
+    };
+
+    // Fetch the snapshot manifest.
+    let manifest_url = format!("{base_url}/{SNAPSHOT_MANIFEST}");
+    let bytes = handle_dispatch_error(|| cdn_get_bytes(client.clone(), &manifest_url)).await?;
+    let manifest = match serde_json::from_slice::<SnapshotManifest>(&bytes) {
+        Ok(manifest) => manifest,
+        Err(error) => bail!("Failed to deserialize the snapshot manifest: {error}"),
+    };
+
+    // Ensure the snapshot is for this network.
+    ensure!(manifest.network == N::ID, "The snapshot is for network {}, not {}", manifest.network, N::ID);
+    // Ensure the snapshot is at the trusted checkpoint.
+    manifest.check_checkpoint::<N>((checkpoint_height, checkpoint_hash))?;
+
+    // Download the files into a staging directory, so that a failed download never leaves a partial ledger behind.
+    let staging_dir = ledger_dir.with_extension("snapshot");
+    if tokio::fs::try_exists(&staging_dir).await? {
+        tokio::fs::remove_dir_all(&staging_dir).await?;
+    }
+    if let Err(error) = download_snapshot_files(&client, base_url, &manifest, &staging_dir, ledger_dir).await {
+        remove_staging_dir(&staging_dir);
+        return Err(error);
+    }
+    info!("Downloaded the ledger snapshot at block {}", manifest.height);
+    Ok(manifest)
+}
+
+/// Downloads the files of the given manifest into the given staging directory, after checking the checksum
+/// of every file, and moves the staging directory to the given ledger directory.
+async fn download_snapshot_files(
+    client: &Client,
+    base_url: &str,
+    manifest: &SnapshotManifest,
+    staging_dir: &Path,
+    ledger_dir: &Path,
+) -> Result<()> {
+    let num_files = manifest.files.len();
+    for (index, file) in manifest.files.iter().enumerate() {
+        // Ensure the path of the file does not escape the staging directory.
+        file.check_path()?;
+        let path = staging_dir.join(&file.path);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        // Download the file, streaming it to disk.
+        let file_url = format!("{base_url}/{}", file.path);
+        let (size, checksum) =
+            handle_dispatch_error(|| cdn_download_file(client.clone(), &file_url, &path, file.size)).await?;
+        // Ensure the file is well-formed.
+        file.check_contents(size, &checksum)?;
+        info!("Downloaded snapshot file {} of {num_files} ({})", index + 1, file.path);
+    }
+
+    // Move the staging directory into place.
+    if let Some(parent) = ledger_dir.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::rename(staging_dir, ledger_dir).await?;
+    Ok(())
+}
+
+/// Removes the staging directory of a failed snapshot download or import, if it exists.
+fn remove_staging_dir(staging_dir: &Path) {
+    if staging_dir.exists() {
+        if let Err(error) = fs::remove_dir_all(staging_dir) {
+            warn!("Failed to remove the snapshot staging directory '{}' - {error}", staging_dir.display());
+        }
+    }
+}
+
+/// Checks that the given ledger, loaded from the snapshot with the given manifest, contains the given trusted
+/// checkpoint, that its block tree matches the state root of its latest block, and that its finalize state
+/// matches the checksum of the snapshot.
+pub fn verify_snapshot<N: Network, C: ConsensusStorage<N>>(
+    ledger: &Ledger<N, C>,
+    manifest: &SnapshotManifest,
+    (checkpoint_height, checkpoint_hash): (u32, N::BlockHash),
+) -> Result<()> {
+    // Ensure the ledger reaches the checkpoint.
+    let latest_height = ledger.latest_height();
+    ensure!(latest_height >= checkpoint_height, "The snapshot ends at block {latest_height}, before the checkpoint");
+    // Ensure the ledger contains the checkpoint.
+    let hash = ledger.get_hash(checkpoint_height)?;
+    ensure!(hash == checkpoint_hash, "The snapshot block {checkpoint_height} ({hash}) does not match the checkpoint");
+    // Ensure the block tree, rebuilt from the block hashes, matches the state root of the latest block.
+    verify_block_tree(ledger)?;
+    // Ensure the finalize state is the one the snapshot was exported with, at the same height.
+    ensure!(
+        latest_height == manifest.latest_height,
+        "The snapshot ends at block {latest_height}, but its manifest ends at block {}",
+        manifest.latest_height
+    );
+    let finalize_checksum = ledger.vm().finalize_store().get_checksum_confirmed()?.to_string();
+    ensure!(
+        finalize_checksum == manifest.finalize_checksum,
+        "The finalize state of the snapshot ({finalize_checksum}) does not match its manifest ({})",
+        manifest.finalize_checksum
+    );
+    Ok(())
+}
+
+/// Exports a snapshot archive of the given running ledger, with the trusted checkpoint at the given height,
+/// and returns the manifest of the snapshot and the path to the archive.
+///
+/// The snapshot is a consistent RocksDB checkpoint of the ledger storage, so the node does not need to stop.
+/// The archive starts with the magic bytes, followed by the length-prefixed JSON manifest, which holds the
+/// checksum of every file, followed by the files in manifest order. The caller is responsible for removing the archive.
+pub fn export_snapshot<N: Network, C: ConsensusStorage<N>>(
+    ledger: &Ledger<N, C>,
+    height: u32,
+) -> Result<(SnapshotManifest, PathBuf)> {
+    // Ensure only one snapshot is exported at a time, as the exports share their paths.
+    static EXPORT_LOCK: Mutex<()> = Mutex::new(());
+    let Some(_lock) = EXPORT_LOCK.try_lock() else { bail!("A ledger snapshot is already being exported") };
+
+    // Retrieve the hash of the block at the snapshot height, which is the trusted checkpoint of the snapshot.
+    let block_hash = ledger.get_hash(height)?;
+
+    // Write a checkpoint of the ledger storage next to the ledger, so that its files are hard links.
+    let ledger_dir = ledger_dir(ledger)?;
+    let checkpoint_dir = ledger_dir.with_extension("checkpoint");
+    let archive_path = ledger_dir.with_extension("export");
+    // Compute the checksum of the finalize state, and checkpoint the storage at the same latest height,
+    // retrying if a block was added in between.
+    let mut attempts = 0;
+    let (latest_height, finalize_checksum) = loop {
+        if checkpoint_dir.exists() {
+            fs::remove_dir_all(&checkpoint_dir)?;
+        }
+        let latest_height = ledger.latest_height();
+        let finalize_checksum = ledger.vm().finalize_store().get_checksum_confirmed()?;
+        checkpoint_ledger(ledger, &checkpoint_dir)?;
+        if ledger.latest_height() == latest_height {
+            break (latest_height, finalize_checksum.to_string());
+        }
+        attempts += 1;
+        if attempts == MAX_SNAPSHOT_EXPORT_ATTEMPTS {
+            fs::remove_dir_all(&checkpoint_dir)?;
+            bail!("Failed to export the ledger snapshot, as blocks were added during each of {attempts} attempts")
+        }
+    };
+
+    // Write the archive, and remove the checkpoint.
+    let manifest = SnapshotManifest {
+        network: N::ID,
+        height,
+        block_hash: block_hash.to_string(),
+        latest_height,
+        finalize_checksum,
+        files: vec![],
+    };
+    let result = write_snapshot_archive(&checkpoint_dir, manifest, &archive_path);
+    fs::remove_dir_all(&checkpoint_dir)?;
+    let manifest = result?;
+    info!("Exported the ledger snapshot at block {height} ({} files)", manifest.files.len());
+    Ok((manifest, archive_path))
+}
+
+/// Returns the RocksDB storage of the given ledger.
+fn ledger_database<N: Network, C: ConsensusStorage<N>>(ledger: &Ledger<N, C>) -> Result<internal::RocksDB> {
+    // Ensure the ledger is stored in RocksDB, as only a RocksDB ledger can be checkpointed.
+    ensure!(TypeId::of::<C>() == TypeId::of::<ConsensusDB<N>>(), "The ledger is not stored on disk");
+    internal::RocksDB::open(N::ID, ledger.vm().block_store().dev())
+}
+
+/// Returns the directory of the RocksDB storage of the given ledger.
+pub(crate) fn ledger_dir<N: Network, C: ConsensusStorage<N>>(ledger: &Ledger<N, C>) -> Result<PathBuf> {
+    Ok(ledger_database(ledger)?.path().to_path_buf())
+}
+
+/// Writes a consistent point-in-time checkpoint of the storage of the given ledger into the given (new) directory.
+///
+/// Note: If the directory is on the same filesystem as the ledger, the files of the checkpoint are hard links.
+pub(crate) fn checkpoint_ledger<N: Network, C: ConsensusStorage<N>>(
+    ledger: &Ledger<N, C>,
+    checkpoint_dir: &Path,
+) -> Result<()> {
+    Ok(Checkpoint::new(&**ledger_database(ledger)?)?.create_checkpoint(checkpoint_dir)?)
+}
+
+/// Writes a consistent point-in-time checkpoint of the RocksDB ledger storage of the given network and
+/// (optional) development ID into the given (new) directory, before the ledger is loaded.
+pub fn checkpoint_storage(network: u16, dev: Option<u16>, checkpoint_dir: &Path) -> Result<()> {
+    Ok(Checkpoint::new(&**internal::RocksDB::open(network, dev)?)?.create_checkpoint(checkpoint_dir)?)
+}
+
+/// Writes the files of the given RocksDB checkpoint into a snapshot archive at the given path,
+/// and returns the given manifest of the snapshot, with the files of the checkpoint.
+fn write_snapshot_archive(
+    checkpoint_dir: &Path,
+    mut manifest: SnapshotManifest,
+    archive_path: &Path,
+) -> Result<SnapshotManifest> {
+    // Compute the size and checksum of each file. Note that a RocksDB checkpoint has no subdirectories.
+    let mut files = Vec::new();
+    for entry in fs::read_dir(checkpoint_dir)? {
+        let entry = entry?;
+        ensure!(entry.file_type()?.is_file(), "The ledger checkpoint contains a subdirectory");
+        let path = entry.file_name().into_string().map_err(|_| anyhow!("The ledger checkpoint has an invalid file"))?;
+        let mut hasher = Sha256::new();
+        let size = io::copy(&mut File::open(entry.path())?, &mut hasher)?;
+        files.push(SnapshotFile { path, size, checksum: hex::encode(hasher.finalize()) });
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    manifest.files = files;
+
+    // Write the magic bytes and the manifest.
+    let mut writer = BufWriter::new(File::create(archive_path)?);
+    let manifest_bytes = serde_json::to_vec(&manifest)?;
+    writer.write_all(SNAPSHOT_ARCHIVE_MAGIC)?;
+    writer.write_all(&u32::try_from(manifest_bytes.len())?.to_le_bytes())?;
+    writer.write_all(&manifest_bytes)?;
+    // Write the files, in manifest order.
+    for file in &manifest.files {
+        let size = io::copy(&mut File::open(checkpoint_dir.join(&file.path))?, &mut writer)?;
+        ensure!(size == file.size, "The ledger checkpoint file '{}' changed during the export", file.path);
+    }
+    writer.flush()?;
+    Ok(manifest)
+}
+
+/// Imports the snapshot archive at the given path into the given (empty) ledger directory,
+/// after checking the checksum of every file, and returns the manifest of the snapshot.
+///
+/// If a trusted checkpoint is given, the snapshot must be at the checkpoint.
+pub fn import_snapshot<N: Network>(
+    archive_path: &Path,
+    ledger_dir: &Path,
+    checkpoint: Option<(u32, N::BlockHash)>,
+) -> Result<SnapshotManifest> {
+    // Ensure the ledger directory does not exist.
+    ensure!(!ledger_dir.exists(), "Cannot import a snapshot into the existing ledger at '{}'", ledger_dir.display());
+
+    // Read the magic bytes and the manifest.
+    let mut reader = BufReader::new(File::open(archive_path)?);
+    let mut magic = [0u8; 8];
+    reader.read_exact(&mut magic)?;
+    ensure!(&magic == SNAPSHOT_ARCHIVE_MAGIC, "'{}' is not a ledger snapshot archive", archive_path.display());
+    let mut length = [0u8; 4];
+    reader.read_exact(&mut length)?;
+    let length = u32::from_le_bytes(length);
+    ensure!(length <= MAX_SNAPSHOT_MANIFEST_SIZE, "The snapshot manifest is too large ({length} bytes)");
+    let mut manifest_bytes = vec![0u8; length as usize];
+    reader.read_exact(&mut manifest_bytes)?;
+    let manifest = match serde_json::from_slice::<SnapshotManifest>(&manifest_bytes) {
+        Ok(manifest) => manifest,
+        Err(error) => bail!("Failed to deserialize the snapshot manifest: {error}"),
+    };
+
+    // Ensure the snapshot is for this network.
+    ensure!(manifest.network == N::ID, "The snapshot is for network {}, not {}", manifest.network, N::ID);
+    // Ensure the snapshot is at the trusted checkpoint, if one is given.
+    if let Some(checkpoint) = checkpoint {
+        manifest.check_checkpoint::<N>(checkpoint)?;
+    }
+
+    // Extract the files into a staging directory, so that a failed import never leaves a partial ledger behind.
+    let staging_dir = ledger_dir.with_extension("snapshot");
+    if staging_dir.exists() {
+        fs::remove_dir_all(&staging_dir)?;
+    }
+    if let Err(error) = extract_snapshot_files(&mut reader, &manifest, &staging_dir, ledger_dir) {
+        remove_staging_dir(&staging_dir);
+        return Err(error);
+    }
+    info!("Imported the ledger snapshot at block {}", manifest.height);
+    Ok(manifest)
+}
+
+/// Extracts the files of the given manifest from the given archive into the given staging directory, after checking
+/// the checksum of every file, and moves the staging directory to the given ledger directory.
+fn extract_snapshot_files(
+    reader: &mut impl Read,
+    manifest: &SnapshotManifest,
+    staging_dir: &Path,
+    ledger_dir: &Path,
+) -> Result<()> {
+    fs::create_dir_all(staging_dir)?;
+    let mut buffer = vec![0u8; SNAPSHOT_IMPORT_BUFFER_SIZE];
+    for file in &manifest.files {
+        file.check_path()?;
+        let path = staging_dir.join(&file.path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Copy the file out of the archive, while computing its checksum.
+        let mut writer = BufWriter::new(File::create(&path)?);
+        let mut hasher = Sha256::new();
+        let mut remaining = file.size;
+        while remaining > 0 {
+            let chunk = &mut buffer[..remaining.min(buffer.len() as u64) as usize];
+            reader.read_exact(chunk)?;
+            hasher.update(&*chunk);
+            writer.write_all(chunk)?;
+            remaining -= chunk.len() as u64;
+        }
+        writer.flush()?;
+        // Ensure the file is well-formed.
+        file.check_contents(file.size, &hex::encode(hasher.finalize()))?;
+    }
+
+    // Move the staging directory into place.
+    if let Some(parent) = ledger_dir.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::rename(staging_dir, ledger_dir)?;
+    Ok(())
+}
+
+/// Retrieves the bytes from the CDN with the given URL.
+async fn cdn_get_bytes(client: Client, url: &str) -> Result<Vec<u8>> {
+    // Fetch the bytes from the given URL.
+    let response = match client.get(url).send().await.and_then(|response| response.error_for_status()) {
+        Ok(response) => response,
+        Err(error) => bail!("Failed to fetch '{url}': {error}"),
+    };
+    // Parse the response.
+    match response.bytes().await {
+        Ok(bytes) => Ok(bytes.to_vec()),
+        Err(error) => bail!("Failed to parse '{url}': {error}"),
+    }
+}
+
+/// Downloads the file from the CDN with the given URL into the given path, streaming it to disk while computing its
+/// checksum, and returns the size and the hex-encoded SHA-256 checksum of the file. The download is aborted once it
+/// exceeds the given maximum size.
+async fn cdn_download_file(client: Client, url: &str, path: &Path, max_size: u64) -> Result<(u64, String)> {
+    // Fetch the file from the given URL.
+    let mut response = match client.get(url).send().await.and_then(|response| response.error_for_status()) {
+        Ok(response) => response,
+        Err(error) => bail!("Failed to fetch '{url}': {error}"),
+    };
+    // Stream the file to disk, truncating any partial file from a previous attempt.
+    let mut file = tokio::fs::File::create(path).await?;
+    let mut hasher = Sha256::new();
+    let mut size = 0u64;
+    loop {
+        match response.chunk().await {
+            Ok(Some(chunk)) => {
+                size += chunk.len() as u64;
+                ensure!(size <= max_size, "The snapshot file at '{url}' exceeds its size of {max_size} bytes");
+                hasher.update(&chunk);
+                file.write_all(&chunk).await?;
+            }
+            Ok(None) => break,
+            Err(error) => bail!("Failed to download '{url}': {error}"),
+        }
+    }
+    file.flush().await?;
+    Ok((size, hex::encode(hasher.finalize())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks the path of the given file, and that the given bytes match its size and checksum.
+    fn check(file: &SnapshotFile, bytes: &[u8]) -> Result<()> {
+        file.check_path()?;
+        file.check_contents(bytes.len() as u64, &hex::encode(Sha256::digest(bytes)))
+    }
+
+    #[test]
+    fn test_snapshot_file_check() {
+        let bytes = b"ledger";
+        let file = SnapshotFile {
+            path: "ledger-3/000001.sst".to_string(),
+            size: 6,
+            checksum: hex::encode(Sha256::digest(bytes)),
+        };
+        assert!(check(&file, bytes).is_ok());
+
+        // Ensure the size and checksum are checked.
+        assert!(check(&file, b"ledge").is_err());
+        assert!(check(&file, b"ledgeR").is_err());
+
+        // Ensure the path may not escape the ledger directory.
+        for path in ["", "/etc/passwd", "../ledger", "ledger-3/../../ledger"] {
+            let file = SnapshotFile { path: path.to_string(), ..file.clone() };
+            assert!(check(&file, bytes).is_err());
+        }
+    }
+
+    #[test]
+    fn test_snapshot_archive() {
+        type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+        let directory = std::env::temp_dir().join(format!("snapshot-archive-{}", std::process::id()));
+        let checkpoint_dir = directory.join("checkpoint");
+        let archive_path = directory.join("snapshot.export");
+        fs::create_dir_all(&checkpoint_dir).unwrap();
+        fs::write(checkpoint_dir.join("000001.sst"), b"ledger").unwrap();
+        fs::write(checkpoint_dir.join("CURRENT"), vec![7u8; 100_000]).unwrap();
+
+        // Write the archive, and ensure the manifest lists the files in order.
+        let manifest = SnapshotManifest {
+            network: 3,
+            height: 10,
+            block_hash: "hash".to_string(),
+            latest_height: 12,
+            finalize_checksum: "checksum".to_string(),
+            files: vec![],
+        };
+        let manifest = write_snapshot_archive(&checkpoint_dir, manifest, &archive_path).unwrap();
+        assert_eq!(manifest.files.iter().map(|file| file.path.as_str()).collect::<Vec<_>>(), ["000001.sst", "CURRENT"]);
+
+        // Ensure the archive is imported, and the files are restored.
+        let ledger_dir = directory.join("ledger");
+        assert_eq!(import_snapshot::<CurrentNetwork>(&archive_path, &ledger_dir, None).unwrap(), manifest);
+        assert_eq!(fs::read(ledger_dir.join("000001.sst")).unwrap(), b"ledger");
+        assert_eq!(fs::read(ledger_dir.join("CURRENT")).unwrap(), vec![7u8; 100_000]);
+        // Ensure an existing ledger is never overwritten.
+        assert!(import_snapshot::<CurrentNetwork>(&archive_path, &ledger_dir, None).is_err());
+        fs::remove_dir_all(&ledger_dir).unwrap();
+
+        // Ensure a corrupted archive is rejected, and leaves no ledger or staging directory behind.
+        let mut bytes = fs::read(&archive_path).unwrap();
+        *bytes.last_mut().unwrap() ^= 1;
+        fs::write(&archive_path, &bytes).unwrap();
+        assert!(import_snapshot::<CurrentNetwork>(&archive_path, &ledger_dir, None).is_err());
+        assert!(!ledger_dir.exists());
+        assert!(!ledger_dir.with_extension("snapshot").exists());
+
+        // Ensure a truncated archive is rejected, and leaves no ledger or staging directory behind.
+        fs::write(&archive_path, &bytes[..bytes.len() - 1000]).unwrap();
+        assert!(import_snapshot::<CurrentNetwork>(&archive_path, &ledger_dir, None).is_err());
+        assert!(!ledger_dir.exists());
+        assert!(!ledger_dir.with_extension("snapshot").exists());
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -28,6 +28,11 @@ pub trait CommunicationService: Send + Sync {
     /// Prepares a block request to be sent.
     fn prepare_block_request(start: u32, end: u32) -> Self::Message;
 
+    /// Prepares a block headers request to be sent, or returns `None` if header-first sync is unsupported.
+    fn prepare_block_headers_request(_start: u32, _end: u32) -> Option<Self::Message> {
+        None
+    }
+
     /// Sends the given message to specified peer.
     ///
     /// This function returns as soon as the message is queued to be sent,
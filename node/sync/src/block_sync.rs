@@ -13,39 +13,85 @@
 // limitations under the License.
 
 use crate::{
-    helpers::{PeerPair, SyncRequest},
+    helpers::{PeerPair, SyncRequest, SyncResponse},
     locators::BlockLocators,
 };
 use snarkos_node_bft_ledger_service::LedgerService;
 use snarkos_node_sync_communication_service::CommunicationService;
 use snarkos_node_sync_locators::{CHECKPOINT_INTERVAL, NUM_RECENT_BLOCKS};
-use snarkvm::prelude::{block::Block, Network};
+use snarkvm::prelude::{
+    block::{Block, Header},
+    Network,
+    ToBits,
+    ToBytes,
+};
 
 use anyhow::{bail, ensure, Result};
 use indexmap::{IndexMap, IndexSet};
 use itertools::Itertools;
 use parking_lot::{Mutex, RwLock};
-use rand::{prelude::IteratorRandom, CryptoRng, Rng};
+use rand::{prelude::SliceRandom, CryptoRng, Rng};
+use serde::Serialize;
 use std::{
-    collections::BTreeMap,
+    cmp::Reverse,
+    collections::{BTreeMap, VecDeque},
+    fmt,
     net::{IpAddr, Ipv4Addr, SocketAddr},
+    ops::Range,
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicU32, Ordering},
         Arc,
     },
-    time::Instant,
+    time::{Duration, Instant},
 };
+use tokio::sync::broadcast;
 
 pub const REDUNDANCY_FACTOR: usize = 3;
 const EXTRA_REDUNDANCY_FACTOR: usize = REDUNDANCY_FACTOR * 2;
 const NUM_SYNC_CANDIDATE_PEERS: usize = REDUNDANCY_FACTOR * 5;
 
 const BLOCK_REQUEST_TIMEOUT_IN_SECS: u64 = 15; // 15 seconds
-const MAX_BLOCK_REQUESTS: usize = 50; // 50 requests
 const MAX_BLOCK_REQUEST_TIMEOUTS: usize = 5; // 5 timeouts
+const BANNED_PEER_TIMEOUT_IN_SECS: u64 = 3600; // 1 hour
+
+/// The number of consecutive blocks that are requested from the same sync peers.
+const BLOCKS_PER_CHUNK: u32 = 50; // 50 blocks
+/// The maximum number of chunks that are requested concurrently.
+const MAX_BLOCK_CHUNKS: usize = 4; // 4 chunks
+/// The maximum number of blocks that are requested concurrently.
+const MAX_BLOCK_REQUESTS: usize = BLOCKS_PER_CHUNK as usize * MAX_BLOCK_CHUNKS; // 200 requests
+
+/// The maximum number of consecutive synced blocks that are committed per task on the blocking pool.
+const MAX_BLOCKS_PER_COMMIT: usize = BLOCKS_PER_CHUNK as usize; // 50 blocks
+
+/// The maximum number of block headers that are requested at once.
+const MAX_BLOCK_HEADERS_PER_REQUEST: u32 = 1000; // 1000 headers
+
+/// The duration over which the throughput of the block sync is measured.
+const SYNC_THROUGHPUT_WINDOW_IN_SECS: u64 = 60; // 1 minute
+/// The interval at which a summary of the sync status is logged.
+const SYNC_SUMMARY_INTERVAL_IN_SECS: u64 = 30; // 30 seconds
+
+/// The duration without any progress after which the block sync is considered stalled.
+const SYNC_STALL_TIMEOUT_IN_SECS: u64 = 60; // 1 minute
+/// The duration for which the peers are excluded from the sync peer set, once rotated out after a stall.
+const ROTATED_PEER_TIMEOUT_IN_SECS: u64 = 300; // 5 minutes
+/// The maximum number of sync health events that are buffered for each subscriber.
+const SYNC_HEALTH_EVENT_CAPACITY: usize = 16;
+
+/// The score that a peer gains for each block it serves.
+const BLOCK_RESPONSE_REWARD: i32 = 1;
+/// The maximum score of a peer.
+const MAX_PEER_SCORE: i32 = 100;
+/// The score that a peer loses when its block requests time out.
+const TIMEOUT_PENALTY: i32 = 10;
+/// The score that a peer loses when it serves a block that does not match the block request.
+const MALFORMED_RESPONSE_PENALTY: i32 = 50;
 
 /// The maximum number of blocks tolerated before the primary is considered behind its peers.
 pub const MAX_BLOCKS_BEHIND: u32 = 2; // blocks
+/// The maximum number of orphan blocks, whose parent block is unknown, that are held ahead of the canonical tip.
+pub const MAX_ORPHAN_BLOCKS: u32 = 10; // blocks
 
 /// This is a dummy IP address that is used to represent the local node.
 /// Note: This here does not need to be a real IP address, but it must be unique/distinct from all other connections.
@@ -69,6 +115,76 @@ impl BlockSyncMode {
     }
 }
 
+/// The status of the block sync.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize)]
+pub struct SyncStatus {
+    /// The latest block height of the canonical ledger.
+    pub canon_height: u32,
+    /// The latest block height of the header chain.
+    pub header_height: u32,
+    /// The greatest block height of the peers.
+    pub target_height: u32,
+    /// The number of blocks synced per second, over the recent throughput window.
+    pub blocks_per_sec: f64,
+    /// The number of bytes synced per second, over the recent throughput window.
+    pub bytes_per_sec: f64,
+    /// The estimated time remaining to sync up to the target height, if the node is syncing.
+    pub eta_in_secs: Option<u64>,
+    /// The number of peers with outstanding block requests.
+    pub num_sync_peers: usize,
+    /// The boolean flag for whether the block sync is stalled.
+    pub is_stalled: bool,
+}
+
+impl SyncStatus {
+    /// Returns the percentage of the blocks that have been synced.
+    pub fn percentage(&self) -> u32 {
+        match self.target_height {
+            0 => 100,
+            target_height => (self.canon_height as u64 * 100 / target_height as u64).min(100) as u32,
+        }
+    }
+}
+
+impl fmt::Display for SyncStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Synced up to block {} of {} - {}% complete (headers up to block {}, {:.1} blocks/s, {:.1} KiB/s, {} peers",
+            self.canon_height,
+            self.target_height,
+            self.percentage(),
+            self.header_height,
+            self.blocks_per_sec,
+            self.bytes_per_sec / 1024.0,
+            self.num_sync_peers,
+        )?;
+        match self.eta_in_secs {
+            Some(eta_in_secs) => write!(f, ", ETA {})", fmt_duration(eta_in_secs)),
+            None => write!(f, ")"),
+        }
+    }
+}
+
+/// Formats the given number of seconds as hours, minutes, and seconds.
+fn fmt_duration(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("{secs}s"),
+        60..=3599 => format!("{}m {}s", secs / 60, secs % 60),
+        _ => format!("{}h {}m", secs / 3600, (secs % 3600) / 60),
+    }
+}
+
+/// A change in the health of the block sync, which is published to the sync health subscribers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SyncHealthEvent {
+    /// The block sync has not advanced from the given height for the given duration, despite having sync peers,
+    /// and the given peers were rotated out of the sync peer set.
+    Stalled { height: u32, stalled_for_secs: u64, rotated_peers: Vec<SocketAddr> },
+    /// The block sync advanced to the given height, after having stalled.
+    Recovered { height: u32 },
+}
+
 /// A struct that keeps track of the current block sync state.
 ///
 /// # State
@@ -95,19 +211,60 @@ pub struct BlockSync<N: Network> {
     /// The map of block height to the expected block hash and peer IPs.
     /// Each entry is removed when its corresponding entry in the responses map is removed.
     requests: Arc<RwLock<BTreeMap<u32, SyncRequest<N>>>>,
-    /// The map of block height to the received blocks.
+    /// The map of block height to the received blocks, and the peer IPs that served them.
     /// Removing an entry from this map must remove the corresponding entry from the requests map.
-    responses: Arc<RwLock<BTreeMap<u32, Block<N>>>>,
+    responses: Arc<RwLock<BTreeMap<u32, SyncResponse<N>>>>,
     /// The map of block height to the timestamp of the last time the block was requested.
     /// This map is used to determine which requests to remove if they have been pending for too long.
     request_timestamps: Arc<RwLock<BTreeMap<u32, Instant>>>,
     /// The map of (timed out) peer IPs to their request timestamps.
     /// This map is used to determine which peers to remove if they have timed out too many times.
     request_timeouts: Arc<RwLock<IndexMap<SocketAddr, Vec<Instant>>>>,
+    /// The map of peer IPs to their round-trip time.
+    /// This map is used as a tie-breaker to determine which peers to request blocks from.
+    latencies: Arc<RwLock<IndexMap<SocketAddr, Duration>>>,
+    /// The map of peer IPs to their sync score.
+    /// Peers gain score for each block they serve, and lose score when their requests time out or
+    /// their blocks do not match the request. Peers with a negative score are only assigned chunks as a last resort.
+    scores: Arc<RwLock<IndexMap<SocketAddr, i32>>>,
+    /// The map of peer IPs to the oldest block height that each peer serves, for the peers that are pruned.
+    /// Peers are only asked for blocks at or above their oldest block height.
+    oldest_heights: Arc<RwLock<IndexMap<SocketAddr, u32>>>,
+    /// The header chain above the canonical ledger, as a map of block height to the block hash and previous block hash.
+    /// The header chain is downloaded ahead of the blocks, and the blocks are checked against it.
+    headers: Arc<RwLock<BTreeMap<u32, (N::BlockHash, N::BlockHash)>>>,
+    /// The greatest height of the header chain that matches the block locators of the sync peers.
+    /// As the header chain is linked by hashes, every block header up to this height is authenticated.
+    anchored_header_height: Arc<AtomicU32>,
+    /// The outstanding block headers request, as the peer IP, the requested heights, and the request timestamp.
+    headers_request: Arc<Mutex<Option<(SocketAddr, Range<u32>, Instant)>>>,
+    /// The boolean indicator of whether the header chain is downloaded before the blocks are requested.
+    is_header_first: Arc<AtomicBool>,
+    /// The map of block hashes to the relayed orphan blocks (whose parent block is unknown), and the relaying peer IPs.
+    orphans: Arc<RwLock<IndexMap<N::BlockHash, (Block<N>, SocketAddr)>>>,
+    /// The timestamps and sizes (in bytes) of the blocks synced within the throughput window.
+    synced_blocks: Arc<Mutex<VecDeque<(Instant, usize)>>>,
+    /// The timestamp of the last summary of the sync status.
+    last_summary: Arc<Mutex<Option<Instant>>>,
+    /// The canonical height at the last check of the stall watchdog, and the time since which it has not advanced.
+    last_progress: Arc<Mutex<(u32, Instant)>>,
+    /// The map of peer IPs that were rotated out of the sync peer set after a stall, to the time of rotation.
+    rotated_peers: Arc<RwLock<IndexMap<SocketAddr, Instant>>>,
+    /// The boolean flag for whether the block sync is stalled.
+    is_stalled: Arc<AtomicBool>,
+    /// The sender of the sync health events.
+    health_events: broadcast::Sender<SyncHealthEvent>,
+    /// The map of peer IPs that served an invalid block to the time they were banned.
+    /// Banned peers are excluded from the sync pool until their ban expires.
+    banned_peers: Arc<RwLock<IndexMap<SocketAddr, Instant>>>,
     /// The boolean indicator of whether the node is synced up to the latest block (within the given tolerance).
     is_block_synced: Arc<AtomicBool>,
-    /// The lock to guarantee advance_with_sync_blocks() is called only once at a time.
+    /// The number of blocks that the node is behind the greatest block height of its peers.
+    num_blocks_behind: Arc<AtomicU32>,
+    /// The lock to guarantee the ledger is advanced by only one caller at a time.
     advance_with_sync_blocks_lock: Arc<Mutex<()>>,
+    /// The boolean indicator of whether a caller is committing the blocks of the sync pool.
+    is_committing: Arc<AtomicBool>,
 }
 
 impl<N: Network> BlockSync<N> {
@@ -122,8 +279,25 @@ impl<N: Network> BlockSync<N> {
             responses: Default::default(),
             request_timestamps: Default::default(),
             request_timeouts: Default::default(),
+            latencies: Default::default(),
+            scores: Default::default(),
+            oldest_heights: Default::default(),
+            headers: Default::default(),
+            anchored_header_height: Default::default(),
+            headers_request: Default::default(),
+            is_header_first: Default::default(),
+            orphans: Default::default(),
+            synced_blocks: Default::default(),
+            last_summary: Default::default(),
+            last_progress: Arc::new(Mutex::new((0, Instant::now()))),
+            rotated_peers: Default::default(),
+            is_stalled: Default::default(),
+            health_events: broadcast::channel(SYNC_HEALTH_EVENT_CAPACITY).0,
+            banned_peers: Default::default(),
             is_block_synced: Default::default(),
+            num_blocks_behind: Default::default(),
             advance_with_sync_blocks_lock: Default::default(),
+            is_committing: Default::default(),
         }
     }
 
@@ -138,6 +312,84 @@ impl<N: Network> BlockSync<N> {
     pub fn is_block_synced(&self) -> bool {
         self.is_block_synced.load(Ordering::SeqCst)
     }
+
+    /// Returns the number of blocks that the node is behind the greatest block height of its peers.
+    #[inline]
+    pub fn num_blocks_behind(&self) -> u32 {
+        self.num_blocks_behind.load(Ordering::SeqCst)
+    }
+
+    /// Enables the header-first sync, where the header chain is downloaded from the peers before the blocks,
+    /// and the blocks are then requested from the peers in parallel, and checked against the header chain.
+    /// Note: The communication service must support block headers requests.
+    pub fn enable_header_first(&self) {
+        self.is_header_first.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns `true` if the header-first sync is enabled.
+    #[inline]
+    pub fn is_header_first(&self) -> bool {
+        self.is_header_first.load(Ordering::SeqCst)
+    }
+
+    /// Returns the latest block height of the header chain.
+    pub fn header_height(&self) -> u32 {
+        let canon_height = self.canon.latest_block_height();
+        self.headers.read().last_key_value().map(|(height, _)| *height).unwrap_or(0).max(canon_height)
+    }
+
+    /// Returns the status of the block sync.
+    pub fn status(&self) -> SyncStatus {
+        let canon_height = self.canon.latest_block_height();
+        let header_height = self.header_height();
+        let target_height = (canon_height + self.num_blocks_behind()).max(header_height);
+        // Compute the throughput of the recently synced blocks.
+        let (blocks_per_sec, bytes_per_sec) = self.sync_throughput();
+        // Estimate the time remaining to sync, at the current throughput.
+        let num_remaining_blocks = target_height.saturating_sub(canon_height);
+        let eta_in_secs = match num_remaining_blocks {
+            0 => Some(0),
+            _ if blocks_per_sec > 0.0 => Some((num_remaining_blocks as f64 / blocks_per_sec).ceil() as u64),
+            _ => None,
+        };
+        // Retrieve the number of peers with outstanding block requests.
+        let num_sync_peers = self.requests.read().values().flat_map(|(_, _, sync_ips)| sync_ips).unique().count();
+        SyncStatus {
+            canon_height,
+            header_height,
+            target_height,
+            blocks_per_sec,
+            bytes_per_sec,
+            eta_in_secs,
+            num_sync_peers,
+            is_stalled: self.is_stalled(),
+        }
+    }
+
+    /// Returns `true` if the block sync is stalled.
+    pub fn is_stalled(&self) -> bool {
+        self.is_stalled.load(Ordering::SeqCst)
+    }
+
+    /// Returns a receiver of the sync health events, such as stalls and recoveries of the block sync.
+    pub fn subscribe_health(&self) -> broadcast::Receiver<SyncHealthEvent> {
+        self.health_events.subscribe()
+    }
+
+    /// Returns the status of the block sync, if the node is behind and a summary is due to be logged.
+    pub fn status_summary(&self) -> Option<SyncStatus> {
+        // Ensure the node is behind.
+        if self.is_block_synced() {
+            return None;
+        }
+        // Ensure the previous summary was logged at least `SYNC_SUMMARY_INTERVAL_IN_SECS` seconds ago.
+        let mut last_summary = self.last_summary.lock();
+        if last_summary.map_or(false, |timestamp| timestamp.elapsed().as_secs() < SYNC_SUMMARY_INTERVAL_IN_SECS) {
+            return None;
+        }
+        *last_summary = Some(Instant::now());
+        Some(self.status())
+    }
 }
 
 #[allow(dead_code)]
@@ -213,6 +465,26 @@ impl<N: Network> BlockSync<N> {
     /// Performs one iteration of the block sync.
     #[inline]
     pub async fn try_block_sync<C: CommunicationService>(&self, communication: &C) {
+        // Check if the block sync is stalled, and if so, rotate the sync peers.
+        self.check_for_stall();
+
+        // If the header-first sync is enabled, request the next block headers, if any.
+        if self.is_header_first() {
+            if let Some((peer_ip, start_height, end_height)) = self.prepare_block_headers_request() {
+                // Construct the message.
+                let Some(message) = C::prepare_block_headers_request(start_height, end_height) else {
+                    warn!("Block sync failed - the communication service does not support block headers requests");
+                    self.headers_request.lock().take();
+                    return;
+                };
+                // Send the message to the peer.
+                if communication.send(peer_ip, message).await.is_none() {
+                    warn!("Failed to send block headers request to peer '{peer_ip}'");
+                    self.headers_request.lock().take();
+                }
+            }
+        }
+
         // Prepare the block requests, if any.
         // In the process, we update the state of `is_block_synced` for the sync module.
         let block_requests = self.prepare_block_requests();
@@ -260,49 +532,216 @@ impl<N: Network> BlockSync<N> {
         Ok(())
     }
 
-    /// Returns the next block to process, if one is ready.
+    /// Processes the block headers response from the given peer IP,
+    /// where each entry is the previous block hash and the block header.
+    pub fn process_block_headers_response(
+        &self,
+        peer_ip: SocketAddr,
+        headers: Vec<(N::BlockHash, Header<N>)>,
+    ) -> Result<()> {
+        // Ensure the sync pool requested the block headers from the given peer, and retrieve the requested heights.
+        let heights = {
+            let mut headers_request = self.headers_request.lock();
+            match headers_request.take() {
+                Some((request_ip, heights, _)) if request_ip == peer_ip => heights,
+                request => {
+                    *headers_request = request;
+                    bail!("The sync pool did not request block headers from '{peer_ip}'")
+                }
+            }
+        };
+        // Insert the block headers into the header chain.
+        match self.insert_block_headers(peer_ip, heights, headers) {
+            // Reward the peer.
+            Ok(true) => self.update_peer_score(peer_ip, BLOCK_RESPONSE_REWARD),
+            // The unanchored tip of the header chain was discarded, as it may be from a dishonest peer.
+            Ok(false) => debug!("Discarded the unanchored header chain, as it conflicts with '{peer_ip}'"),
+            Err(error) => {
+                // Discard the unanchored tip of the header chain, and penalize the peer.
+                self.truncate_headers();
+                self.update_peer_score(peer_ip, -MALFORMED_RESPONSE_PENALTY);
+                bail!("{error}")
+            }
+        }
+        Ok(())
+    }
+
+    /// Processes the response from the given peer IP that it does not serve the blocks in the given range,
+    /// by updating the oldest block height of the peer, and removing the block requests to the peer,
+    /// so that the blocks are requested from other peers on the next sync iteration.
+    pub fn process_blocks_unavailable(&self, peer_ip: SocketAddr, heights: Range<u32>, oldest_block_height: u32) {
+        debug!("Peer '{peer_ip}' does not serve blocks {}..{}", heights.start, heights.end);
+        // Update the oldest block height of the peer.
+        self.update_peer_oldest_height(peer_ip, oldest_block_height);
+        // Remove the block requests to the peer.
+        for height in heights {
+            self.remove_block_request_to_peer(&peer_ip, height);
+        }
+    }
+
+    /// Returns the next block to process, and the peer IPs that served it, if one is ready.
     #[inline]
-    pub fn process_next_block(&self, next_height: u32) -> Option<Block<N>> {
+    pub fn process_next_block(&self, next_height: u32) -> Option<SyncResponse<N>> {
         // Try to advance the ledger with a block from the sync pool.
-        self.remove_block_response(next_height)
+        let response = self.remove_block_response(next_height);
+        // Record the synced block.
+        if let Some((block, _)) = &response {
+            self.record_synced_block(block);
+        }
+        response
     }
 
     /// Attempts to advance with blocks from the sync pool.
-    #[inline]
-    pub fn advance_with_sync_blocks(&self, peer_ip: SocketAddr, blocks: Vec<Block<N>>) -> Result<()> {
-        // Process the block response from the given peer IP.
-        self.process_block_response(peer_ip, blocks)?;
-
-        // Acquire the lock to ensure this function is called only once at a time.
-        // If the lock is already acquired, return early.
-        let Some(_lock) = self.advance_with_sync_blocks_lock.try_lock() else {
-            trace!("Skipping a call to advance_with_sync_blocks() as it is already in progress");
-            return Ok(());
-        };
+    ///
+    /// The blocks are committed through a pipeline: the caller that finds no commit in progress drains the sync pool,
+    /// handing each run of consecutive blocks to the blocking pool, where they are checked and committed in order.
+    /// Meanwhile, the block responses of other peers are inserted into the sync pool with `process_block_response`,
+    /// and are picked up by the next run.
+    ///
+    /// Note: This call waits for the sync pool to be drained, so it must be made from a dedicated task,
+    /// and never from the task reading the messages of a peer, which would stall its other messages (e.g. `Pong`).
+    /// Note: The ledger commits each block as a single atomic batch of its keys.
+    pub async fn advance_with_sync_blocks(&self) -> Result<()> {
+        // Drain the sync pool, unless another caller is already draining it.
+        while self.is_committing.compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire).is_ok() {
+            let result = self.commit_sync_blocks().await;
+            self.is_committing.store(false, Ordering::Release);
+            result?;
+            // Stop, unless the next block became ready as the previous caller was finishing.
+            if !self.is_block_response_ready(self.canon.latest_block_height() + 1) {
+                break;
+            }
+        }
+        Ok(())
+    }
 
-        // Retrieve the latest block height.
-        let mut current_height = self.canon.latest_block_height();
-        // Try to advance the ledger with the sync pool.
-        while let Some(block) = self.remove_block_response(current_height + 1) {
+    /// Commits the consecutive blocks of the sync pool, in runs of up to `MAX_BLOCKS_PER_COMMIT` blocks,
+    /// until the sync pool has no block for the next height, or a block could not be committed.
+    async fn commit_sync_blocks(&self) -> Result<()> {
+        loop {
+            // Take the next run of consecutive blocks from the sync pool.
+            let start_height = self.canon.latest_block_height() + 1;
+            let blocks = (start_height..)
+                .map_while(|height| self.remove_block_response(height))
+                .take(MAX_BLOCKS_PER_COMMIT)
+                .collect::<Vec<_>>();
+            if blocks.is_empty() {
+                return Ok(());
+            }
+            // Check and commit the blocks on the blocking pool, as they are verified and written to the ledger.
+            let self_ = self.clone();
+            if !tokio::task::spawn_blocking(move || self_.commit_blocks(blocks)).await? {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Checks and commits the given consecutive blocks in order, followed by the orphan blocks they connect,
+    /// returning `false` if a block could not be committed.
+    ///
+    /// Note: This is a blocking call, and must never be made from the async executor.
+    fn commit_blocks(&self, blocks: Vec<SyncResponse<N>>) -> bool {
+        // Acquire the lock, to ensure the ledger is not advanced concurrently with a relayed block.
+        let _lock = self.advance_with_sync_blocks_lock.lock();
+
+        let mut is_committed = true;
+        for (block, sync_ips) in blocks {
+            // Skip the block if a relayed block already advanced the ledger past it.
+            let next_height = self.canon.latest_block_height() + 1;
+            if block.height() < next_height {
+                continue;
+            }
             // Ensure the block height matches.
-            if block.height() != current_height + 1 {
-                warn!("Block height mismatch: expected {}, found {}", current_height + 1, block.height());
+            if block.height() != next_height {
+                warn!("Block height mismatch: expected {next_height}, found {}", block.height());
+                self.handle_invalid_block(next_height, &sync_ips);
+                is_committed = false;
                 break;
             }
             // Check the next block.
             if let Err(error) = self.canon.check_next_block(&block) {
                 warn!("The next block ({}) is invalid - {error}", block.height());
+                self.handle_invalid_block(block.height(), &sync_ips);
+                is_committed = false;
                 break;
             }
             // Attempt to advance to the next block.
             if let Err(error) = self.canon.advance_to_next_block(&block) {
                 warn!("{error}");
+                is_committed = false;
                 break;
             }
-            // Update the latest height.
-            current_height = self.canon.latest_block_height();
+            trace!("Synced block {} from {}", block.height(), sync_ips.iter().join(", "));
+            // Record the synced block.
+            self.record_synced_block(&block);
         }
-        Ok(())
+        // Advance with the orphan blocks that the sync blocks connect.
+        self.advance_with_orphan_blocks();
+        is_committed
+    }
+
+    /// Attempts to advance the ledger with the given block, relayed by the given peer IP,
+    /// returning `false` if the block is not the next block (e.g. it was already synced).
+    ///
+    /// Note: If the block is invalid, the peer is banned, as it served an invalid block.
+    pub fn advance_with_relayed_block(&self, peer_ip: SocketAddr, block: &Block<N>) -> Result<bool> {
+        // Acquire the lock, to ensure the ledger is not advanced concurrently with the sync pool.
+        let _lock = self.advance_with_sync_blocks_lock.lock();
+
+        // Ensure the block is the next block.
+        let height = block.height();
+        if height != self.canon.latest_block_height() + 1 {
+            return Ok(false);
+        }
+        // Check the next block.
+        if let Err(error) = self.canon.check_next_block(block) {
+            self.handle_invalid_block(height, &IndexSet::from([peer_ip]));
+            bail!("Peer '{peer_ip}' relayed an invalid block ({height}) - {error}");
+        }
+        // Advance to the next block.
+        self.canon.advance_to_next_block(block)?;
+        // Remove the block request for the block, as it is no longer needed.
+        self.remove_block_request(height);
+        // Advance with the orphan blocks that the block connects.
+        self.advance_with_orphan_blocks();
+        Ok(true)
+    }
+
+    /// Inserts the given relayed block, whose parent block is unknown, into the orphan pool,
+    /// returning `false` if the block is not within `MAX_ORPHAN_BLOCKS` blocks ahead of the canonical tip.
+    ///
+    /// Note: If the orphan pool is full, the orphan block that is furthest ahead of the canonical tip is evicted.
+    pub fn insert_orphan_block(&self, peer_ip: SocketAddr, block: Block<N>) -> bool {
+        let height = block.height();
+        let latest_height = self.canon.latest_block_height();
+        // Ensure the block is ahead of the next block, and within the orphan window.
+        if height <= latest_height + 1 || height > latest_height + MAX_ORPHAN_BLOCKS {
+            return false;
+        }
+        let mut orphans = self.orphans.write();
+        // Remove the orphan blocks that are already canonized.
+        orphans.retain(|_, (block, _)| block.height() > latest_height);
+        // If the orphan pool is full, evict the orphan block that is furthest ahead, unless it is this block.
+        if orphans.len() >= MAX_ORPHAN_BLOCKS as usize {
+            match orphans.values().position_max_by_key(|(block, _)| block.height()) {
+                Some(index) if orphans[index].0.height() > height => {
+                    orphans.shift_remove_index(index);
+                }
+                _ => return false,
+            }
+        }
+        orphans.insert(block.hash(), (block, peer_ip));
+        true
+    }
+
+    /// Returns `true` if the orphan pool contains the given block hash.
+    pub fn contains_orphan_block(&self, hash: &N::BlockHash) -> bool {
+        self.orphans.read().contains_key(hash)
+    }
+
+    /// Returns the number of orphan blocks in the orphan pool.
+    pub fn num_orphan_blocks(&self) -> usize {
+        self.orphans.read().len()
     }
 }
 
@@ -331,6 +770,10 @@ impl<N: Network> BlockSync<N> {
             return Ok(());
         }
 
+        // Ensure the peer is not banned.
+        if self.is_peer_banned(&peer_ip) {
+            bail!("Peer '{peer_ip}' is banned from the sync pool")
+        }
         // Ensure the given block locators are well-formed.
         locators.ensure_is_valid()?;
         // Update the locators entry for the given peer IP.
@@ -372,6 +815,19 @@ impl<N: Network> BlockSync<N> {
         Ok(())
     }
 
+    /// Updates the round-trip time for the given peer IP.
+    pub fn update_peer_latency(&self, peer_ip: SocketAddr, rtt: Duration) {
+        self.latencies.write().insert(peer_ip, rtt);
+    }
+
+    /// Updates the oldest block height that the given peer IP serves, as advertised by the peer.
+    pub fn update_peer_oldest_height(&self, peer_ip: SocketAddr, oldest_block_height: u32) {
+        match oldest_block_height {
+            0 => self.oldest_heights.write().remove(&peer_ip),
+            height => self.oldest_heights.write().insert(peer_ip, height),
+        };
+    }
+
     /// TODO (howardwu): Remove the `common_ancestor` entry. But check that this is safe
     ///  (that we don't rely upon it for safety when we re-connect with the same peer).
     /// Removes the peer from the sync pool, if they exist.
@@ -382,6 +838,70 @@ impl<N: Network> BlockSync<N> {
         self.remove_block_requests_to_peer(peer_ip);
         // Remove the timeouts for the peer.
         self.request_timeouts.write().remove(peer_ip);
+        // Remove the round-trip time for the peer.
+        self.latencies.write().remove(peer_ip);
+        // Remove the score for the peer.
+        self.scores.write().remove(peer_ip);
+        // Remove the rotation of the peer.
+        self.rotated_peers.write().remove(peer_ip);
+        // Remove the oldest block height of the peer.
+        self.oldest_heights.write().remove(peer_ip);
+    }
+
+    /// Returns the sync score of the given peer IP.
+    pub fn get_peer_score(&self, peer_ip: &SocketAddr) -> i32 {
+        self.scores.read().get(peer_ip).copied().unwrap_or(0)
+    }
+
+    /// Returns `true` if the given peer IP is banned from the sync pool.
+    pub fn is_peer_banned(&self, peer_ip: &SocketAddr) -> bool {
+        self.banned_peers
+            .read()
+            .get(peer_ip)
+            .map(|timestamp| timestamp.elapsed().as_secs() < BANNED_PEER_TIMEOUT_IN_SECS)
+            .unwrap_or(false)
+    }
+
+    /// Handles an invalid block at the given height, by banning the peers that served it,
+    /// and removing the block request for the given height, and every response from the banned peers.
+    /// The block request is then re-issued to the remaining peers on the next sync iteration.
+    pub fn handle_invalid_block(&self, height: u32, peer_ips: &IndexSet<SocketAddr>) {
+        // Ban the peers that served the invalid block.
+        {
+            let mut banned_peers = self.banned_peers.write();
+            // Remove the expired bans.
+            banned_peers.retain(|_, timestamp| timestamp.elapsed().as_secs() < BANNED_PEER_TIMEOUT_IN_SECS);
+            for peer_ip in peer_ips {
+                warn!("Banning '{peer_ip}' from the sync pool for serving invalid block {height}");
+                banned_peers.insert(*peer_ip, Instant::now());
+            }
+        }
+        // Remove the banned peers from the sync pool.
+        for peer_ip in peer_ips {
+            self.remove_peer(peer_ip);
+        }
+
+        // Acquire the write lock on the requests map.
+        let mut requests = self.requests.write();
+        // Acquire the write lock on the responses map.
+        let mut responses = self.responses.write();
+        // Acquire the write lock on the request timestamps map.
+        let mut request_timestamps = self.request_timestamps.write();
+
+        // Remove the block request for the given height, and every response served by the banned peers,
+        // as the blocks after an invalid block from a peer cannot be trusted either.
+        let mut heights = vec![height];
+        heights.extend(
+            responses
+                .iter()
+                .filter(|(_, (_, sync_ips))| sync_ips.iter().any(|ip| peer_ips.contains(ip)))
+                .map(|(height, _)| *height),
+        );
+        for height in heights {
+            requests.remove(&height);
+            responses.remove(&height);
+            request_timestamps.remove(&height);
+        }
     }
 }
 
@@ -390,6 +910,9 @@ impl<N: Network> BlockSync<N> {
     fn prepare_block_requests(&self) -> Vec<(u32, SyncRequest<N>)> {
         // Remove timed out block requests.
         self.remove_timed_out_block_requests();
+        // Remove the block headers that are already canonized.
+        let canon_height = self.canon.latest_block_height();
+        self.headers.write().retain(|height, _| *height > canon_height);
         // Prepare the block requests.
         if let Some((sync_peers, min_common_ancestor)) = self.find_sync_peers_inner() {
             // Retrieve the highest block height.
@@ -419,6 +942,255 @@ impl<N: Network> BlockSync<N> {
         let is_synced = num_blocks_behind <= max_blocks_behind;
         // Update the sync status.
         self.is_block_synced.store(is_synced, Ordering::SeqCst);
+        self.num_blocks_behind.store(num_blocks_behind, Ordering::SeqCst);
+    }
+
+    /// Returns the peer IP and the heights of the next block headers request, if the header chain is behind the peers.
+    fn prepare_block_headers_request(&self) -> Option<(SocketAddr, u32, u32)> {
+        // Acquire the lock on the block headers request.
+        let mut headers_request = self.headers_request.lock();
+        // If a block headers request is outstanding, ensure it has timed out.
+        if let Some((peer_ip, _, timestamp)) = &*headers_request {
+            if timestamp.elapsed().as_secs() <= BLOCK_REQUEST_TIMEOUT_IN_SECS {
+                return None;
+            }
+            // Add the peer IP to the request timeouts map, and penalize its score.
+            self.request_timeouts.write().entry(*peer_ip).or_default().push(Instant::now());
+            self.update_peer_score(*peer_ip, -TIMEOUT_PENALTY);
+            *headers_request = None;
+        }
+
+        // Retrieve the sync peers.
+        let (sync_peers, min_common_ancestor) = self.find_sync_peers_inner()?;
+        // Compute the heights of the block headers request, up to the minimum common ancestor of the sync peers.
+        let start_height = self.header_height() + 1;
+        let end_height = (min_common_ancestor + 1).min(start_height + MAX_BLOCK_HEADERS_PER_REQUEST);
+        if start_height >= end_height {
+            return None;
+        }
+        // Pick the sync peer with the highest score.
+        let peer_ip = *sync_peers.keys().rev().max_by_key(|peer_ip| self.get_peer_score(peer_ip))?;
+
+        // Insert the block headers request.
+        *headers_request = Some((peer_ip, start_height..end_height, Instant::now()));
+        Some((peer_ip, start_height, end_height))
+    }
+
+    /// Inserts the given block headers from the peer into the header chain, after checking that they extend it.
+    /// Each block header at a height in the block locators of the peer anchors the header chain up to that height.
+    ///
+    /// Returns `false` if the block headers conflict with the unanchored tip of the header chain,
+    /// in which case the unanchored tip is discarded, as it may have been served by a dishonest peer.
+    fn insert_block_headers(
+        &self,
+        peer_ip: SocketAddr,
+        heights: Range<u32>,
+        headers: Vec<(N::BlockHash, Header<N>)>,
+    ) -> Result<bool> {
+        // Ensure the number of block headers matches the request.
+        ensure!(headers.len() == heights.len(), "Expected {} block headers from '{peer_ip}'", heights.len());
+
+        // Retrieve the hash of the block preceding the block headers.
+        let previous_height = heights.start.saturating_sub(1);
+        let mut expected_previous_hash = match self.headers.read().get(&previous_height) {
+            Some((hash, _)) => *hash,
+            None => self.canon.get_block_hash(previous_height)?,
+        };
+        // Ensure the block headers extend the header chain, or discard its unanchored tip.
+        if let Some((previous_hash, _)) = headers.first() {
+            if *previous_hash != expected_previous_hash && previous_height > self.anchored_header_height() {
+                self.truncate_headers();
+                return Ok(false);
+            }
+        }
+
+        // Retrieve the block locators of the peer.
+        let Some(locators) = self.locators.read().get(&peer_ip).cloned() else {
+            bail!("The sync pool has no block locators for '{peer_ip}'")
+        };
+
+        let mut chain = Vec::with_capacity(headers.len());
+        let mut anchored_height = None;
+        for (height, (previous_hash, header)) in heights.zip_eq(headers) {
+            // Ensure the block header is at the expected height.
+            ensure!(header.height() == height, "The block header {height} from '{peer_ip}' has an incorrect height");
+            // Ensure the block header extends the header chain.
+            ensure!(
+                previous_hash == expected_previous_hash,
+                "The block header {height} from '{peer_ip}' does not extend the header chain"
+            );
+            // Compute the block hash.
+            let hash = compute_block_hash::<N>(previous_hash, &header)?;
+            // If the block locators of the peer contain the height, ensure the block hash matches, and anchor it.
+            if let Some(locator_hash) = locators.get_hash(height) {
+                ensure!(hash == locator_hash, "The block header {height} from '{peer_ip}' does not match its locators");
+                anchored_height = Some(height);
+            }
+            chain.push((height, (hash, previous_hash)));
+            expected_previous_hash = hash;
+        }
+
+        // Insert the block headers into the header chain.
+        self.headers.write().extend(chain);
+        // Update the anchored height of the header chain.
+        if let Some(anchored_height) = anchored_height {
+            self.anchored_header_height.fetch_max(anchored_height, Ordering::SeqCst);
+        }
+        Ok(true)
+    }
+
+    /// Returns the greatest height of the header chain that matches the block locators of the sync peers.
+    fn anchored_header_height(&self) -> u32 {
+        self.anchored_header_height.load(Ordering::SeqCst).max(self.canon.latest_block_height())
+    }
+
+    /// Removes the unanchored tip of the header chain.
+    fn truncate_headers(&self) {
+        let anchored_height = self.anchored_header_height();
+        self.headers.write().retain(|height, _| *height <= anchored_height);
+    }
+
+    /// Advances the ledger with the orphan blocks that extend the canonical tip, in order of height.
+    /// Note: The caller must hold the `advance_with_sync_blocks_lock`.
+    fn advance_with_orphan_blocks(&self) {
+        loop {
+            // Remove the orphan blocks that are already canonized.
+            let latest_height = self.canon.latest_block_height();
+            self.orphans.write().retain(|_, (block, _)| block.height() > latest_height);
+            // Retrieve the orphan block that extends the canonical tip.
+            let Ok(latest_hash) = self.canon.get_block_hash(latest_height) else {
+                break;
+            };
+            let orphan = {
+                let mut orphans = self.orphans.write();
+                let index = orphans.values().position(|(block, _)| block.previous_hash() == latest_hash);
+                index.and_then(|index| orphans.shift_remove_index(index)).map(|(_, orphan)| orphan)
+            };
+            let Some((block, peer_ip)) = orphan else {
+                break;
+            };
+            // Check the next block.
+            let height = block.height();
+            if let Err(error) = self.canon.check_next_block(&block) {
+                warn!("The orphan block ({height}) relayed by '{peer_ip}' is invalid - {error}");
+                self.handle_invalid_block(height, &IndexSet::from([peer_ip]));
+                break;
+            }
+            // Advance to the next block.
+            if let Err(error) = self.canon.advance_to_next_block(&block) {
+                warn!("{error}");
+                break;
+            }
+            // Remove the block request for the block, as it is no longer needed.
+            self.remove_block_request(height);
+            debug!("Advanced to the orphan block {height} relayed by '{peer_ip}'");
+        }
+    }
+
+    /// Checks if the canonical height has not advanced for `SYNC_STALL_TIMEOUT_IN_SECS` seconds while the node
+    /// is behind its peers, in which case the peers with outstanding requests are rotated out of the sync peer set,
+    /// so that their requests are retried from different peers, and a sync health event is published.
+    fn check_for_stall(&self) {
+        let canon_height = self.canon.latest_block_height();
+        let mut last_progress = self.last_progress.lock();
+        // If the canonical height advanced, restart the watchdog.
+        if canon_height != last_progress.0 {
+            *last_progress = (canon_height, Instant::now());
+            if self.is_stalled.swap(false, Ordering::SeqCst) {
+                info!("Block sync recovered at block {canon_height}");
+                let _ = self.health_events.send(SyncHealthEvent::Recovered { height: canon_height });
+            }
+            return;
+        }
+        // If the node is synced, or has no peers to sync from, the block sync is not stalled.
+        if self.is_block_synced() || self.locators.read().is_empty() {
+            last_progress.1 = Instant::now();
+            return;
+        }
+        // Ensure the canonical height has not advanced for the stall timeout.
+        let stalled_for_secs = last_progress.1.elapsed().as_secs();
+        if stalled_for_secs < SYNC_STALL_TIMEOUT_IN_SECS {
+            return;
+        }
+        // Restart the watchdog, so that the rotated peers are given a full timeout to make progress.
+        last_progress.1 = Instant::now();
+        drop(last_progress);
+
+        // Retrieve the peers with outstanding block requests or block headers requests.
+        let mut stalled_peers: IndexSet<SocketAddr> =
+            self.requests.read().values().flat_map(|(_, _, sync_ips)| sync_ips.iter().copied()).collect();
+        stalled_peers.extend(self.headers_request.lock().as_ref().map(|(peer_ip, ..)| *peer_ip));
+        // Rotate the stalled peers out of the sync peer set, if there are other peers to retry from.
+        let rotated_peers = self.rotated_peers();
+        let has_other_peers = self
+            .locators
+            .read()
+            .keys()
+            .any(|peer_ip| !stalled_peers.contains(peer_ip) && !rotated_peers.contains(peer_ip));
+        let rotated_peers = match has_other_peers {
+            true => stalled_peers.into_iter().collect::<Vec<_>>(),
+            false => vec![],
+        };
+        for peer_ip in &rotated_peers {
+            self.rotated_peers.write().insert(*peer_ip, Instant::now());
+            self.remove_block_requests_to_peer(peer_ip);
+        }
+        // Remove the block headers request, if it was sent to a rotated peer.
+        {
+            let mut headers_request = self.headers_request.lock();
+            if headers_request.as_ref().map_or(false, |(peer_ip, ..)| rotated_peers.contains(peer_ip)) {
+                *headers_request = None;
+            }
+        }
+
+        warn!(
+            "Block sync stalled at block {canon_height} for {stalled_for_secs} seconds - rotated {} sync peers",
+            rotated_peers.len()
+        );
+        self.is_stalled.store(true, Ordering::SeqCst);
+        // Publish the sync health event.
+        let event = SyncHealthEvent::Stalled { height: canon_height, stalled_for_secs, rotated_peers };
+        let _ = self.health_events.send(event);
+    }
+
+    /// Returns the peers that are rotated out of the sync peer set.
+    fn rotated_peers(&self) -> IndexSet<SocketAddr> {
+        let mut rotated_peers = self.rotated_peers.write();
+        // Remove the expired rotations.
+        rotated_peers.retain(|_, timestamp| timestamp.elapsed().as_secs() < ROTATED_PEER_TIMEOUT_IN_SECS);
+        rotated_peers.keys().copied().collect()
+    }
+
+    /// Records the given block as synced, for the throughput of the block sync.
+    fn record_synced_block(&self, block: &Block<N>) {
+        // Retrieve the size of the block.
+        let num_bytes = block.to_bytes_le().map(|bytes| bytes.len()).unwrap_or_default();
+        let mut synced_blocks = self.synced_blocks.lock();
+        synced_blocks.push_back((Instant::now(), num_bytes));
+        Self::remove_expired_synced_blocks(&mut synced_blocks);
+    }
+
+    /// Returns the number of blocks and bytes synced per second, over the throughput window.
+    fn sync_throughput(&self) -> (f64, f64) {
+        let mut synced_blocks = self.synced_blocks.lock();
+        Self::remove_expired_synced_blocks(&mut synced_blocks);
+        // Measure the throughput since the oldest block in the window, for at least one second.
+        let Some((oldest, _)) = synced_blocks.front() else {
+            return (0.0, 0.0);
+        };
+        let elapsed = oldest.elapsed().as_secs_f64().max(1.0);
+        let num_bytes = synced_blocks.iter().map(|(_, num_bytes)| *num_bytes).sum::<usize>();
+        (synced_blocks.len() as f64 / elapsed, num_bytes as f64 / elapsed)
+    }
+
+    /// Removes the synced blocks that are older than the throughput window.
+    fn remove_expired_synced_blocks(synced_blocks: &mut VecDeque<(Instant, usize)>) {
+        while let Some((timestamp, _)) = synced_blocks.front() {
+            if timestamp.elapsed().as_secs() < SYNC_THROUGHPUT_WINDOW_IN_SECS {
+                break;
+            }
+            synced_blocks.pop_front();
+        }
     }
 
     /// Inserts a block request for the given height.
@@ -436,15 +1208,24 @@ impl<N: Network> BlockSync<N> {
 
     /// Inserts the given block response, after checking that the request exists and the response is well-formed.
     /// On success, this function removes the peer IP from the requests map.
-    /// On failure, this function removes all block requests from the given peer IP.
+    /// On failure, this function removes all block requests from the given peer IP, and if the block does not match
+    /// the requested block, bans the peer, as it served a mismatched block.
     fn insert_block_response(&self, peer_ip: SocketAddr, block: Block<N>) -> Result<()> {
         // Retrieve the block height.
         let height = block.height();
 
-        // Ensure the block (response) from the peer is well-formed. On failure, remove all block requests to the peer.
-        if let Err(error) = self.check_block_response(&peer_ip, &block) {
+        // Ensure the sync pool requested the block from the peer. On failure, remove all block requests to the peer.
+        // Note: The peer is not banned, as the response may have arrived after the request timed out.
+        if let Err(error) = self.check_block_request_to_peer(&peer_ip, height) {
             // Remove all block requests to the peer.
             self.remove_block_requests_to_peer(&peer_ip);
+            // Penalize the peer.
+            self.update_peer_score(peer_ip, -MALFORMED_RESPONSE_PENALTY);
+            return Err(error);
+        }
+        // Ensure the block (response) from the peer matches the requested block. On failure, ban the peer.
+        if let Err(error) = self.check_block_response(&peer_ip, &block) {
+            self.handle_invalid_block(height, &IndexSet::from([peer_ip]));
             return Err(error);
         }
 
@@ -455,23 +1236,44 @@ impl<N: Network> BlockSync<N> {
 
         // Acquire the write lock on the responses map.
         let mut responses = self.responses.write();
-        // Insert the candidate block into the responses map.
-        if let Some(existing_block) = responses.insert(height, block.clone()) {
+        // Insert the candidate block into the responses map, or add the peer IP to the existing response.
+        match responses.get_mut(&height) {
             // If the candidate block was already present, ensure it is the same block.
-            if block != existing_block {
-                // Remove the candidate block.
-                responses.remove(&height);
-                // Drop the write lock on the responses map.
-                drop(responses);
-                // Remove all block requests to the peer.
-                self.remove_block_requests_to_peer(&peer_ip);
-                bail!("Candidate block {height} from '{peer_ip}' is malformed");
+            Some((existing_block, sync_ips)) => {
+                if block == *existing_block {
+                    sync_ips.insert(peer_ip);
+                } else {
+                    // Remove the candidate block.
+                    responses.remove(&height);
+                    // Drop the write lock on the responses map.
+                    drop(responses);
+                    // Remove all block requests to the peer.
+                    self.remove_block_requests_to_peer(&peer_ip);
+                    // Penalize the peer.
+                    self.update_peer_score(peer_ip, -MALFORMED_RESPONSE_PENALTY);
+                    bail!("Candidate block {height} from '{peer_ip}' is malformed");
+                }
+            }
+            // Otherwise, insert the candidate block.
+            None => {
+                responses.insert(height, (block, IndexSet::from([peer_ip])));
             }
         }
+        // Drop the write lock on the responses map.
+        drop(responses);
 
+        // Reward the peer.
+        self.update_peer_score(peer_ip, BLOCK_RESPONSE_REWARD);
         Ok(())
     }
 
+    /// Adds the given amount to the sync score of the given peer IP, up to the maximum score.
+    fn update_peer_score(&self, peer_ip: SocketAddr, amount: i32) {
+        let mut scores = self.scores.write();
+        let score = scores.entry(peer_ip).or_default();
+        *score = score.saturating_add(amount).min(MAX_PEER_SCORE);
+    }
+
     /// Checks that a block request for the given height does not already exist.
     fn check_block_request(&self, height: u32) -> Result<()> {
         // Ensure the block height is not already canon.
@@ -493,13 +1295,26 @@ impl<N: Network> BlockSync<N> {
         Ok(())
     }
 
+    /// Checks that the sync pool has an outstanding request for the given height to the given peer.
+    fn check_block_request_to_peer(&self, peer_ip: &SocketAddr, height: u32) -> Result<()> {
+        // Retrieve the request entry for the candidate block.
+        match self.requests.read().get(&height) {
+            // Ensure the sync pool requested this block from the given peer.
+            Some((_, _, sync_ips)) if !sync_ips.contains(peer_ip) => {
+                bail!("The sync pool did not request block {height} from '{peer_ip}'")
+            }
+            Some(_) => Ok(()),
+            None => bail!("The sync pool did not request block {height}"),
+        }
+    }
+
     /// Checks the given block (response) from a peer against the expected block hash and previous block hash.
     fn check_block_response(&self, peer_ip: &SocketAddr, block: &Block<N>) -> Result<()> {
         // Retrieve the block height.
         let height = block.height();
 
         // Retrieve the request entry for the candidate block.
-        if let Some((expected_hash, expected_previous_hash, sync_ips)) = self.requests.read().get(&height) {
+        if let Some((expected_hash, expected_previous_hash, _)) = self.requests.read().get(&height) {
             // Ensure the candidate block hash matches the expected hash.
             if let Some(expected_hash) = expected_hash {
                 if block.hash() != *expected_hash {
@@ -512,10 +1327,6 @@ impl<N: Network> BlockSync<N> {
                     bail!("The previous block hash in candidate block {height} from '{peer_ip}' is incorrect")
                 }
             }
-            // Ensure the sync pool requested this block from the given peer.
-            if !sync_ips.contains(peer_ip) {
-                bail!("The sync pool did not request block {height} from '{peer_ip}'")
-            }
             Ok(())
         } else {
             bail!("The sync pool did not request block {height}")
@@ -532,8 +1343,15 @@ impl<N: Network> BlockSync<N> {
         self.request_timestamps.write().remove(&height);
     }
 
+    /// Returns `true` if the block response for the given height is in the sync pool, and its request is complete.
+    fn is_block_response_ready(&self, height: u32) -> bool {
+        let is_request_complete =
+            self.requests.read().get(&height).map(|(_, _, peer_ips)| peer_ips.is_empty()).unwrap_or(false);
+        is_request_complete && self.responses.read().contains_key(&height)
+    }
+
     /// Removes and returns the block response for the given height, if the request is complete.
-    fn remove_block_response(&self, height: u32) -> Option<Block<N>> {
+    fn remove_block_response(&self, height: u32) -> Option<SyncResponse<N>> {
         // Acquire the requests write lock.
         // Note: This lock must be held across the entire scope, due to asynchronous block responses
         // from multiple peers that may be received concurrently.
@@ -553,7 +1371,6 @@ impl<N: Network> BlockSync<N> {
     }
 
     /// Removes the block request for the given peer IP, if it exists.
-    #[allow(dead_code)]
     fn remove_block_request_to_peer(&self, peer_ip: &SocketAddr, height: u32) {
         let mut can_revoke = self.responses.read().get(&height).is_none();
 
@@ -591,7 +1408,9 @@ impl<N: Network> BlockSync<N> {
     }
 
     /// Removes block requests that have timed out. This also removes the corresponding block responses,
-    /// and adds the timed out sync IPs to a map for tracking. Returns the number of timed out block requests.
+    /// and adds the timed out sync IPs to a map for tracking, and penalizes their score.
+    /// The removed block requests are reassigned to other sync peers on the next sync iteration.
+    /// Returns the number of timed out block requests.
     fn remove_timed_out_block_requests(&self) -> usize {
         // Acquire the write lock on the requests map.
         let mut requests = self.requests.write();
@@ -638,9 +1457,10 @@ impl<N: Network> BlockSync<N> {
         if !timeout_ips.is_empty() {
             // Acquire the write lock on the request timeouts map.
             let mut request_timeouts = self.request_timeouts.write();
-            // Add each timeout IP to the request timeouts map.
+            // Add each timeout IP to the request timeouts map, and penalize its score.
             for timeout_ip in timeout_ips {
                 request_timeouts.entry(timeout_ip).or_default().push(now);
+                self.update_peer_score(timeout_ip, -TIMEOUT_PENALTY);
             }
         }
 
@@ -660,14 +1480,33 @@ impl<N: Network> BlockSync<N> {
             .map(|(peer_ip, timestamps)| (*peer_ip, timestamps.len()))
             .collect::<IndexMap<_, _>>();
 
-        // Pick a set of peers above the latest canon height, and include their locators.
+        // Retrieve the round-trip time of each peer, where peers without a measurement are ranked last.
+        let latencies = self.latencies.read().clone();
+        let latency = |peer_ip: &SocketAddr| latencies.get(peer_ip).copied().unwrap_or(Duration::MAX);
+        // Retrieve the score of each peer.
+        let scores = self.scores.read().clone();
+        let score = |peer_ip: &SocketAddr| scores.get(peer_ip).copied().unwrap_or(0);
+        // Retrieve the peers that were rotated out of the sync peer set.
+        let rotated_peers = self.rotated_peers();
+        // Retrieve the oldest block height that each peer serves.
+        let oldest_heights = self.oldest_heights.read().clone();
+
+        // Pick a set of peers above the latest canon height that serve the next block, and include their locators.
+        // Peers with the same height are ordered by their score, and then by their round-trip time.
         let candidate_locators: IndexMap<_, _> = self
             .locators
             .read()
             .iter()
             .filter(|(_, locators)| locators.latest_locator_height() > latest_canon_height)
             .filter(|(ip, _)| timeouts.get(*ip).map(|count| *count < MAX_BLOCK_REQUEST_TIMEOUTS).unwrap_or(true))
-            .sorted_by(|(_, a), (_, b)| b.latest_locator_height().cmp(&a.latest_locator_height()))
+            .filter(|(ip, _)| !rotated_peers.contains(*ip))
+            .filter(|(ip, _)| oldest_heights.get(*ip).map_or(true, |height| *height <= latest_canon_height + 1))
+            .sorted_by(|(a_ip, a), (b_ip, b)| {
+                b.latest_locator_height()
+                    .cmp(&a.latest_locator_height())
+                    .then_with(|| score(b_ip).cmp(&score(a_ip)))
+                    .then_with(|| latency(a_ip).cmp(&latency(b_ip)))
+            })
             .take(NUM_SYNC_CANDIDATE_PEERS)
             .map(|(peer_ip, locators)| (*peer_ip, locators.clone()))
             .collect();
@@ -731,6 +1570,10 @@ impl<N: Network> BlockSync<N> {
     }
 
     /// Given the sync peers and their minimum common ancestor, return a list of block requests.
+    ///
+    /// The block requests are grouped into chunks of consecutive heights, and each chunk is assigned to
+    /// the sync peers with the fewest blocks in flight, so that idle peers pick up the next chunk.
+    /// Peers with a negative score are only assigned a chunk if there are not enough other sync peers.
     fn construct_requests<R: Rng + CryptoRng>(
         &self,
         sync_peers: IndexMap<SocketAddr, BlockLocators<N>>,
@@ -748,40 +1591,105 @@ impl<N: Network> BlockSync<N> {
         // Compute the start height for the block request.
         let start_height = latest_canon_height + 1;
         // Compute the end height for the block request.
-        let end_height = (min_common_ancestor + 1).min(start_height + MAX_BLOCK_REQUESTS as u32);
+        let mut end_height = (min_common_ancestor + 1).min(start_height + MAX_BLOCK_REQUESTS as u32);
+        // If the header-first sync is enabled, only request the blocks in the anchored header chain.
+        let anchored_height = self.anchored_header_height();
+        if self.is_header_first() {
+            end_height = end_height.min(anchored_height + 1);
+        }
+        // Retrieve the header chain within the range of the block request.
+        let headers: BTreeMap<_, _> =
+            self.headers.read().range(start_height..end_height).map(|(height, hashes)| (*height, *hashes)).collect();
+
+        // Retrieve the number of blocks in flight to each sync peer.
+        let mut loads = IndexMap::<SocketAddr, usize>::new();
+        for (_, _, sync_ips) in self.requests.read().values() {
+            for sync_ip in sync_ips {
+                *loads.entry(*sync_ip).or_default() += 1;
+            }
+        }
+        // Retrieve the score and round-trip time of each sync peer.
+        let scores = self.scores.read().clone();
+        let score = |peer_ip: &SocketAddr| scores.get(peer_ip).copied().unwrap_or(0);
+        let latencies = self.latencies.read().clone();
+        let latency = |peer_ip: &SocketAddr| latencies.get(peer_ip).copied().unwrap_or(Duration::MAX);
+
+        // Shuffle the sync peers, so that ties between equally-ranked peers are broken at random.
+        let mut peer_ips = sync_peers.keys().copied().collect::<Vec<_>>();
+        peer_ips.shuffle(rng);
 
         let mut requests = Vec::with_capacity((start_height..end_height).len());
 
-        for height in start_height..end_height {
-            // Ensure the current height is not canonized or already requested.
-            if self.check_block_request(height).is_err() {
-                continue;
+        // Assigns the given chunk of block requests to the highest-ranked sync peers.
+        let mut assign_chunk = |chunk: Vec<(u32, Option<N::BlockHash>, Option<N::BlockHash>, usize)>,
+                                requests: &mut Vec<(u32, SyncRequest<N>)>| {
+            // Determine the number of sync peers for the chunk.
+            let Some(num_sync_ips) = chunk.iter().map(|(.., num_sync_ips)| *num_sync_ips).max() else {
+                return;
+            };
+            // Pick the sync peers, preferring peers in good standing with the fewest blocks in flight.
+            let load = |peer_ip: &SocketAddr| loads.get(peer_ip).copied().unwrap_or(0);
+            let sync_ips: IndexSet<SocketAddr> = peer_ips
+                .iter()
+                .copied()
+                .sorted_by_key(|ip| (score(ip) < 0, load(ip), Reverse(score(ip)), latency(ip)))
+                .take(num_sync_ips)
+                .collect();
+            // Update the number of blocks in flight to each sync peer.
+            for sync_ip in &sync_ips {
+                *loads.entry(*sync_ip).or_default() += chunk.len();
             }
+            // Append the requests.
+            for (height, hash, previous_hash, _) in chunk {
+                requests.push((height, (hash, previous_hash, sync_ips.clone())));
+            }
+        };
 
-            // Construct the block request.
-            let (hash, previous_hash, num_sync_ips, is_honest) = construct_request(height, &sync_peers);
-
-            // Handle the dishonest case.
-            if !is_honest {
-                // TODO (howardwu): Consider performing an integrity check on peers (to disconnect).
-                warn!("Detected dishonest peer(s) when preparing block request");
-                // If there are not enough peers in the dishonest case, then return early.
-                if sync_peers.len() < num_sync_ips {
-                    break;
+        let mut chunk = Vec::with_capacity(BLOCKS_PER_CHUNK as usize);
+        for height in start_height..end_height {
+            // Ensure the current height is not canonized or already requested.
+            if self.check_block_request(height).is_ok() {
+                // Construct the block request, from the header chain if it is anchored at this height.
+                let (hash, previous_hash, num_sync_ips, is_honest) = match headers.get(&height) {
+                    Some((hash, previous_hash)) if height <= anchored_height => {
+                        (Some(*hash), Some(*previous_hash), 1, true)
+                    }
+                    _ => construct_request(height, &sync_peers),
+                };
+
+                // Handle the dishonest case.
+                if !is_honest {
+                    // TODO (howardwu): Consider performing an integrity check on peers (to disconnect).
+                    warn!("Detected dishonest peer(s) when preparing block request");
+                    // If there are not enough peers in the dishonest case, then return early.
+                    if sync_peers.len() < num_sync_ips {
+                        break;
+                    }
                 }
-            }
 
-            // Pick the sync peers.
-            let sync_ips = sync_peers.keys().copied().choose_multiple(rng, num_sync_ips);
+                // Append the request to the chunk.
+                chunk.push((height, hash, previous_hash, num_sync_ips));
+            }
 
-            // Append the request.
-            requests.push((height, (hash, previous_hash, sync_ips.into_iter().collect())));
+            // If this is the last height of the chunk, then assign the chunk to the sync peers.
+            if (height + 1) % BLOCKS_PER_CHUNK == 0 {
+                assign_chunk(std::mem::take(&mut chunk), &mut requests);
+            }
         }
+        // Assign the last chunk to the sync peers.
+        assign_chunk(chunk, &mut requests);
 
         requests
     }
 }
 
+/// Returns the hash of the block with the given previous block hash and block header.
+fn compute_block_hash<N: Network>(previous_hash: N::BlockHash, header: &Header<N>) -> Result<N::BlockHash> {
+    let mut preimage = previous_hash.to_bits_le();
+    preimage.extend(header.to_root()?.to_bits_le());
+    Ok(N::hash_bhp1024(&preimage)?.into())
+}
+
 /// If any peer is detected to be dishonest in this function, it will not set the hash or previous hash,
 /// in order to allow the caller to determine what to do.
 fn construct_request<N: Network>(
@@ -863,7 +1771,10 @@ mod tests {
         NUM_RECENT_BLOCKS,
     };
     use snarkos_node_bft_ledger_service::MockLedgerService;
-    use snarkvm::prelude::{Field, TestRng};
+    use snarkvm::{
+        ledger::ledger_test_helpers::sample_genesis_block,
+        prelude::{Field, TestRng},
+    };
 
     use indexmap::indexset;
     use snarkvm::ledger::committee::Committee;
@@ -991,6 +1902,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_find_sync_peers_prefers_lower_latency() {
+        let sync = sample_sync_at_height(0);
+
+        // Add peers at the same height, where the last peer has the lowest round-trip time.
+        for peer_id in 1..=3 {
+            sync.update_peer_locators(sample_peer_ip(peer_id), sample_block_locators(10)).unwrap();
+        }
+        sync.update_peer_latency(sample_peer_ip(1), Duration::from_millis(300));
+        sync.update_peer_latency(sample_peer_ip(3), Duration::from_millis(20));
+
+        // Ensure the sync peers are ordered by their round-trip time, with unmeasured peers last.
+        let (sync_peers, _) = sync.find_sync_peers_inner().unwrap();
+        assert_eq!(sync_peers.keys().copied().collect::<Vec<_>>(), vec![
+            sample_peer_ip(3),
+            sample_peer_ip(1),
+            sample_peer_ip(2)
+        ]);
+
+        // Ensure the round-trip time is removed with the peer.
+        sync.remove_peer(&sample_peer_ip(3));
+        assert!(!sync.latencies.read().contains_key(&sample_peer_ip(3)));
+    }
+
     #[test]
     fn test_prepare_block_requests_with_leading_fork_at_11() {
         let sync = sample_sync_at_height(0);
@@ -1292,5 +2227,233 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_handle_invalid_block() {
+        let sync = sample_sync_at_height(0);
+
+        // Add the peers.
+        let peer_1 = sample_peer_ip(1);
+        let peer_2 = sample_peer_ip(2);
+        sync.update_peer_locators(peer_1, sample_block_locators(10)).unwrap();
+        sync.update_peer_locators(peer_2, sample_block_locators(10)).unwrap();
+
+        // Insert the block requests.
+        for (height, request) in sync.prepare_block_requests() {
+            sync.insert_block_request(height, request).unwrap();
+        }
+        assert!(sync.get_block_request(1).is_some());
+
+        // Handle an invalid block from the first peer.
+        sync.handle_invalid_block(1, &IndexSet::from([peer_1]));
+
+        // Ensure the first peer is banned, and removed from the sync pool.
+        assert!(sync.is_peer_banned(&peer_1));
+        assert!(!sync.is_peer_banned(&peer_2));
+        assert_eq!(sync.get_peer_height(&peer_1), None);
+        assert!(sync.update_peer_locators(peer_1, sample_block_locators(10)).is_err());
+        // Ensure the block request is removed, and no request remains to the first peer.
+        assert!(sync.get_block_request(1).is_none());
+        assert!(sync.requests.read().values().all(|(_, _, sync_ips)| !sync_ips.contains(&peer_1)));
+
+        // Ensure the block is requested again from the second peer.
+        let requests = sync.prepare_block_requests();
+        assert!(requests.iter().any(|(height, _)| *height == 1));
+        assert!(requests.iter().all(|(_, (_, _, sync_ips))| sync_ips.iter().all(|ip| *ip == peer_2)));
+    }
+
+    #[test]
+    fn test_mismatched_block_response() {
+        let rng = &mut TestRng::default();
+        let sync = sample_sync_at_height(0);
+        let block = sample_genesis_block(rng);
+
+        // Add the peers.
+        let peer_1 = sample_peer_ip(1);
+        let peer_2 = sample_peer_ip(2);
+        sync.update_peer_locators(peer_1, sample_block_locators(10)).unwrap();
+        sync.update_peer_locators(peer_2, sample_block_locators(10)).unwrap();
+
+        // Insert a request for the block to the first peer, with a different block hash.
+        // Note: The request is inserted directly, as the block height is already canonical.
+        let expected_hash = Some(Field::<CurrentNetwork>::from_u32(1).into());
+        sync.requests.write().insert(block.height(), (expected_hash, None, indexset![peer_1]));
+
+        // Ensure an unrequested block response from the second peer is rejected, without banning the peer.
+        assert!(sync.process_block_response(peer_2, vec![block.clone()]).is_err());
+        assert!(!sync.is_peer_banned(&peer_2));
+        assert!(sync.get_block_request(block.height()).is_some());
+
+        // Ensure the mismatched block response from the first peer is rejected, and the peer is banned.
+        assert!(sync.process_block_response(peer_1, vec![block.clone()]).is_err());
+        assert!(sync.is_peer_banned(&peer_1));
+        assert_eq!(sync.get_peer_height(&peer_1), None);
+        assert!(sync.get_block_request(block.height()).is_none());
+        assert!(sync.responses.read().is_empty());
+    }
+
+    #[test]
+    fn test_pruned_peers() {
+        let sync = sample_sync_at_height(0);
+
+        // Add the peers, where the first peer is pruned below block 5.
+        let peer_1 = sample_peer_ip(1);
+        let peer_2 = sample_peer_ip(2);
+        sync.update_peer_locators(peer_1, sample_block_locators(10)).unwrap();
+        sync.update_peer_locators(peer_2, sample_block_locators(10)).unwrap();
+        sync.update_peer_oldest_height(peer_1, 5);
+
+        // Ensure the blocks are only requested from the second peer.
+        let requests = sync.prepare_block_requests();
+        assert_eq!(requests.len(), 10);
+        assert!(requests.iter().all(|(_, (_, _, sync_ips))| sync_ips.iter().all(|ip| *ip == peer_2)));
+
+        // Ensure the blocks are requested again, once the second peer responds that they are unavailable.
+        for (height, request) in requests {
+            sync.insert_block_request(height, request).unwrap();
+        }
+        sync.process_blocks_unavailable(peer_2, 1..2, 3);
+        assert!(sync.get_block_request(1).is_none());
+        assert!(sync.get_block_request(2).is_some());
+        assert_eq!(sync.oldest_heights.read().get(&peer_2), Some(&3));
+
+        // Ensure the oldest block height is removed, once the peer serves every block.
+        sync.update_peer_oldest_height(peer_1, 0);
+        assert!(sync.oldest_heights.read().get(&peer_1).is_none());
+    }
+
+    #[test]
+    fn test_stalled_sync_rotates_peers() {
+        let sync = sample_sync_at_height(0);
+        let mut health_events = sync.subscribe_health();
+
+        // Add the peers.
+        let peer_1 = sample_peer_ip(1);
+        let peer_2 = sample_peer_ip(2);
+        sync.update_peer_locators(peer_1, sample_block_locators(10)).unwrap();
+        sync.update_peer_locators(peer_2, sample_block_locators(10)).unwrap();
+
+        // Insert a block request to the first peer.
+        sync.insert_block_request(1, (None, None, indexset![peer_1])).unwrap();
+
+        // Ensure the block sync is not stalled before the stall timeout.
+        sync.check_for_stall();
+        assert!(!sync.is_stalled());
+        assert!(sync.get_block_request(1).is_some());
+
+        // Simulate no progress for the stall timeout.
+        sync.last_progress.lock().1 = Instant::now() - Duration::from_secs(SYNC_STALL_TIMEOUT_IN_SECS);
+        sync.check_for_stall();
+
+        // Ensure the first peer is rotated out, and its block request is removed.
+        assert!(sync.is_stalled());
+        assert!(sync.status().is_stalled);
+        assert!(sync.get_block_request(1).is_none());
+        assert_eq!(sync.rotated_peers(), indexset![peer_1]);
+        let expected = SyncHealthEvent::Stalled {
+            height: 0,
+            stalled_for_secs: SYNC_STALL_TIMEOUT_IN_SECS,
+            rotated_peers: vec![peer_1],
+        };
+        assert_eq!(health_events.try_recv().unwrap(), expected);
+
+        // Ensure the block is requested again from the second peer.
+        let requests = sync.prepare_block_requests();
+        assert!(requests.iter().any(|(height, _)| *height == 1));
+        assert!(requests.iter().all(|(_, (_, _, sync_ips))| sync_ips.iter().all(|ip| *ip == peer_2)));
+
+        // Ensure the first peer is no longer rotated out, once removed from the sync pool.
+        sync.remove_peer(&peer_1);
+        assert!(sync.rotated_peers().is_empty());
+    }
+
+    #[test]
+    fn test_construct_requests_assigns_chunks() {
+        let sync = sample_sync_at_height(0);
+
+        // Add the peers, which are two chunks ahead.
+        let num_blocks = 2 * BLOCKS_PER_CHUNK - 1;
+        for peer_id in 1..=4 {
+            sync.update_peer_locators(sample_peer_ip(peer_id), sample_block_locators(num_blocks)).unwrap();
+        }
+        // Penalize the first peer.
+        sync.update_peer_score(sample_peer_ip(1), -TIMEOUT_PENALTY);
+
+        // Prepare the block requests.
+        let requests = sync.prepare_block_requests();
+        assert_eq!(requests.len(), num_blocks as usize);
+
+        // Ensure each chunk is assigned to a single peer in good standing, and that the chunks are spread across peers.
+        let chunks = requests
+            .iter()
+            .map(|(height, (_, _, sync_ips))| (height / BLOCKS_PER_CHUNK, sync_ips.clone()))
+            .dedup()
+            .collect::<Vec<_>>();
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks.iter().all(|(_, sync_ips)| sync_ips.len() == 1 && sync_ips[0] != sample_peer_ip(1)));
+        assert_ne!(chunks[0].1, chunks[1].1);
+
+        // Insert the block requests, and time them out.
+        for (height, request) in requests {
+            sync.insert_block_request(height, request).unwrap();
+        }
+        for timestamp in sync.request_timestamps.write().values_mut() {
+            *timestamp -= Duration::from_secs(BLOCK_REQUEST_TIMEOUT_IN_SECS + 1);
+        }
+        assert_eq!(sync.remove_timed_out_block_requests(), num_blocks as usize);
+
+        // Ensure the slow peers are penalized.
+        for (_, sync_ips) in &chunks {
+            assert_eq!(sync.get_peer_score(&sync_ips[0]), -TIMEOUT_PENALTY);
+        }
+
+        // Ensure the chunks are reassigned to the remaining peer in good standing.
+        let remaining_ip =
+            (2..=4).map(sample_peer_ip).find(|ip| chunks.iter().all(|(_, sync_ips)| !sync_ips.contains(ip))).unwrap();
+        let requests = sync.prepare_block_requests();
+        assert_eq!(requests.len(), num_blocks as usize);
+        assert!(requests.iter().all(|(_, (_, _, sync_ips))| *sync_ips == IndexSet::from([remaining_ip])));
+    }
+
+    #[test]
+    fn test_header_first_sync() {
+        let sync = sample_sync_at_height(0);
+        sync.enable_header_first();
+
+        // Add the peers.
+        let num_blocks = 10;
+        for peer_id in 1..=2 {
+            sync.update_peer_locators(sample_peer_ip(peer_id), sample_block_locators(num_blocks)).unwrap();
+        }
+        // Reward the second peer.
+        sync.update_peer_score(sample_peer_ip(2), BLOCK_RESPONSE_REWARD);
+
+        // Ensure no blocks are requested before the header chain is downloaded.
+        assert!(sync.prepare_block_requests().is_empty());
+        // Ensure the block headers are requested from the highest-scored peer, only once at a time.
+        assert_eq!(sync.prepare_block_headers_request(), Some((sample_peer_ip(2), 1, num_blocks + 1)));
+        assert_eq!(sync.prepare_block_headers_request(), None);
+        // Ensure the block headers response is only accepted from the requested peer.
+        assert!(sync.process_block_headers_response(sample_peer_ip(1), vec![]).is_err());
+
+        // Ensure a timed out block headers request is penalized.
+        if let Some((_, _, timestamp)) = sync.headers_request.lock().as_mut() {
+            *timestamp -= Duration::from_secs(BLOCK_REQUEST_TIMEOUT_IN_SECS + 1);
+        }
+        assert!(sync.prepare_block_headers_request().is_some());
+        assert_eq!(sync.get_peer_score(&sample_peer_ip(2)), BLOCK_RESPONSE_REWARD - TIMEOUT_PENALTY);
+
+        // Ensure the status reports the target height of the peers.
+        let status = sync.status();
+        assert_eq!((status.canon_height, status.header_height, status.target_height), (0, 0, num_blocks));
+        assert_eq!(status.percentage(), 0);
+        // Ensure the time remaining is unknown, as no blocks have been synced.
+        assert_eq!(status.eta_in_secs, None);
+
+        // Ensure the summary of the sync status is only logged periodically.
+        assert!(sync.status_summary().is_some());
+        assert!(sync.status_summary().is_none());
+        assert_eq!(fmt_duration(3725), "1h 2m");
+    }
+
     // TODO: duplicate responses, ensure fails.
 }
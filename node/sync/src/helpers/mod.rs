@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use snarkvm::prelude::Network;
+use snarkvm::prelude::{block::Block, Network};
 
 use core::hash::Hash;
 use indexmap::IndexSet;
@@ -21,6 +21,9 @@ use std::net::SocketAddr;
 /// A tuple of the block hash (optional), previous block hash (optional), and sync IPs.
 pub type SyncRequest<N> = (Option<<N as Network>::BlockHash>, Option<<N as Network>::BlockHash>, IndexSet<SocketAddr>);
 
+/// A tuple of the block and the IPs of the peers that served it.
+pub type SyncResponse<N> = (Block<N>, IndexSet<SocketAddr>);
+
 #[derive(Copy, Clone, Debug)]
 pub(crate) struct PeerPair(pub SocketAddr, pub SocketAddr);
 
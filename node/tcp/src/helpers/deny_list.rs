@@ -0,0 +1,96 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use parking_lot::RwLock;
+use std::{collections::HashSet, io, net::SocketAddr, path::PathBuf};
+
+/// The listener IPs of the peers the node refuses to connect with, which are reloaded from a file while
+/// the node runs. The file lists one IP per line, and a missing file denies no peers.
+///
+/// This is used to partition the nodes of a local network, by denying the peers across the partition.
+/// The deny list is passed to the router and the gateway of the node, which refuse the denied peers.
+#[derive(Debug)]
+pub struct PeerDenyList {
+    /// The path to the file of the denied peer IPs.
+    path: PathBuf,
+    /// The denied peer IPs.
+    peers: RwLock<HashSet<SocketAddr>>,
+}
+
+impl PeerDenyList {
+    /// Initializes a new peer deny list, which is loaded from the file at the given path on `reload`.
+    pub fn new(path: PathBuf) -> Self {
+        Self { path, peers: Default::default() }
+    }
+
+    /// Returns `true` if the given peer IP is denied.
+    pub fn contains(&self, peer_ip: &SocketAddr) -> bool {
+        self.peers.read().contains(peer_ip)
+    }
+
+    /// Reloads the denied peer IPs from the file.
+    pub async fn reload(&self) -> io::Result<()> {
+        let peers = match tokio::fs::read_to_string(&self.path).await {
+            Ok(contents) => parse_peer_ips(&contents)?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Default::default(),
+            Err(error) => return Err(error),
+        };
+        *self.peers.write() = peers;
+        Ok(())
+    }
+}
+
+/// Parses the peer IPs, one per line, skipping the empty lines.
+fn parse_peer_ips(contents: &str) -> io::Result<HashSet<SocketAddr>> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            line.parse().map_err(|error| io::Error::new(io::ErrorKind::InvalidData, format!("'{line}' - {error}")))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_peer_deny_list() {
+        let path = std::env::temp_dir().join(format!("peer-deny-list-{}", std::process::id()));
+        let deny_list = PeerDenyList::new(path.clone());
+        let peer_ip = "127.0.0.1:5001".parse().unwrap();
+
+        // Ensure a missing file denies no peers.
+        deny_list.reload().await.unwrap();
+        assert!(!deny_list.contains(&peer_ip));
+
+        // Ensure the listed peers are denied, until they are removed from the file.
+        std::fs::write(&path, "127.0.0.1:5001\n\n127.0.0.1:4131\n").unwrap();
+        deny_list.reload().await.unwrap();
+        assert!(deny_list.contains(&peer_ip));
+        assert!(!deny_list.contains(&"127.0.0.1:5002".parse().unwrap()));
+        std::fs::write(&path, "").unwrap();
+        deny_list.reload().await.unwrap();
+        assert!(!deny_list.contains(&peer_ip));
+
+        // Ensure an invalid file is rejected, and the denied peers are retained.
+        std::fs::write(&path, "127.0.0.1:5001\nlocalhost").unwrap();
+        assert!(deny_list.reload().await.is_err());
+        assert!(!deny_list.contains(&peer_ip));
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
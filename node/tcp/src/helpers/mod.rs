@@ -18,6 +18,9 @@ pub use config::Config;
 pub mod connections;
 pub use connections::{Connection, ConnectionSide};
 
+mod deny_list;
+pub use deny_list::*;
+
 mod known_peers;
 pub use known_peers::KnownPeers;
 
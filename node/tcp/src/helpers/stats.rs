@@ -27,6 +27,8 @@ pub struct Stats {
     bytes_received: AtomicU64,
     /// The number of failures.
     failures: AtomicU64,
+    /// The number of outbound messages dropped due to a full low-priority queue.
+    msgs_dropped: AtomicU64,
 }
 
 impl Stats {
@@ -51,6 +53,11 @@ impl Stats {
         self.failures.load(Relaxed)
     }
 
+    /// Returns the number of outbound messages dropped due to a full low-priority queue.
+    pub fn dropped(&self) -> u64 {
+        self.msgs_dropped.load(Relaxed)
+    }
+
     /// Registers a sent message of the provided `size` in bytes.
     pub fn register_sent_message(&self, size: usize) {
         self.msgs_sent.fetch_add(1, Relaxed);
@@ -67,4 +74,9 @@ impl Stats {
     pub fn register_failure(&self) {
         self.failures.fetch_add(1, Relaxed);
     }
+
+    /// Registers an outbound message dropped due to a full low-priority queue.
+    pub fn register_dropped_message(&self) {
+        self.msgs_dropped.fetch_add(1, Relaxed);
+    }
 }
@@ -33,7 +33,7 @@ pub use disconnect::Disconnect;
 pub use handshake::Handshake;
 pub use on_connect::OnConnect;
 pub use reading::Reading;
-pub use writing::Writing;
+pub use writing::{Priority, Writing};
 
 #[derive(Default)]
 pub(crate) struct Protocols {
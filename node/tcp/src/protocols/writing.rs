@@ -33,7 +33,25 @@ use crate::{
     P2P,
 };
 
-type WritingSenders = Arc<RwLock<HashMap<SocketAddr, mpsc::Sender<WrappedMessage>>>>;
+type WritingSenders = Arc<RwLock<HashMap<SocketAddr, Lanes>>>;
+
+/// The maximum number of consecutive messages that are sent ahead of a lower-priority lane; once it is reached,
+/// a queued message from the lower-priority lane is sent next, so that the lane is never starved.
+const MAX_CONSECUTIVE_PRIORITY_MESSAGES: usize = 16;
+
+/// The priority lane of an outbound message. Each connection has a dedicated queue per lane, and
+/// queued messages are sent from the highest-priority lane that is not empty, except that a lower-priority
+/// lane is served after it was passed over for a bounded number of messages, so that it is never starved.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Priority {
+    /// Time-critical messages (e.g. consensus messages), which must never wait behind other traffic.
+    High,
+    /// Regular messages (e.g. requests and responses).
+    #[default]
+    Normal,
+    /// Best-effort messages (e.g. gossip), which are dropped when their lane is full.
+    Low,
+}
 
 /// Can be used to specify and enable writing, i.e. sending outbound messages. If the [`Handshake`]
 /// protocol is enabled too, it goes into force only after the handshake has been concluded.
@@ -46,9 +64,17 @@ where
     /// messages the node can enqueue. Setting it to a large value is not recommended, as doing it might
     /// obscure potential issues with your implementation (like slow serialization) or network.
     ///
+    /// The depth applies to the high and normal priority lanes individually.
+    ///
     /// The default value is 1024.
     const MESSAGE_QUEUE_DEPTH: usize = 1024;
 
+    /// The depth of per-connection queues used for low-priority outbound messages; once it is reached,
+    /// further low-priority messages are dropped instead of being queued.
+    ///
+    /// The default value is 256.
+    const LOW_PRIORITY_QUEUE_DEPTH: usize = 256;
+
     /// The type of the outbound messages; unless their serialization is expensive and the message
     /// is broadcasted (in which case it would get serialized multiple times), serialization should
     /// be done in the implementation of [`Self::Codec`].
@@ -92,10 +118,20 @@ where
     /// The `side` param indicates the connection side **from the node's perspective**.
     fn codec(&self, addr: SocketAddr, side: ConnectionSide) -> Self::Codec;
 
+    /// Returns the priority lane that the given outbound message is queued in.
+    ///
+    /// The default implementation queues every message with [`Priority::Normal`].
+    fn priority(&self, _message: &Self::Message) -> Priority {
+        Priority::Normal
+    }
+
     /// Sends the provided message to the specified [`SocketAddr`]. Returns as soon as the message is queued to
     /// be sent, without waiting for the actual delivery; instead, the caller is provided with a [`oneshot::Receiver`]
     /// which can be used to determine when and whether the message has been delivered.
     ///
+    /// If the message has [`Priority::Low`] and its queue for this address is full, the message is dropped,
+    /// and the returned [`oneshot::Receiver`] yields an [`io::ErrorKind::WouldBlock`] error.
+    ///
     /// # Errors
     ///
     /// The following errors can be returned:
@@ -105,17 +141,25 @@ where
     fn unicast(&self, addr: SocketAddr, message: Self::Message) -> io::Result<oneshot::Receiver<io::Result<()>>> {
         // access the protocol handler
         if let Some(handler) = self.tcp().protocols.writing.get() {
-            // find the message sender for the given address
-            if let Some(sender) = handler.senders.read().get(&addr).cloned() {
+            // find the message senders for the given address
+            if let Some(lanes) = handler.senders.read().get(&addr).cloned() {
+                let priority = self.priority(&message);
                 let (msg, delivery) = WrappedMessage::new(Box::new(message));
-                sender
-                    .try_send(msg)
-                    .map_err(|e| {
+                match lanes.sender(priority).try_send(msg) {
+                    Ok(()) => Ok(delivery),
+                    // low-priority messages are dropped when their queue is full
+                    Err(mpsc::error::TrySendError::Full(msg)) if priority == Priority::Low => {
+                        debug!(parent: self.tcp().span(), "dropped a low-priority message to {}: queue full", addr);
+                        self.tcp().stats().register_dropped_message();
+                        let _ = msg.delivery_notification.send(Err(io::ErrorKind::WouldBlock.into()));
+                        Ok(delivery)
+                    }
+                    Err(e) => {
                         error!(parent: self.tcp().span(), "can't send a message to {}: {}", addr, e);
                         self.tcp().stats().register_failure();
-                        io::ErrorKind::Other.into()
-                    })
-                    .map(|_| delivery)
+                        Err(io::ErrorKind::Other.into())
+                    }
+                }
             } else {
                 Err(io::ErrorKind::NotConnected.into())
             }
@@ -138,13 +182,22 @@ where
     {
         // access the protocol handler
         if let Some(handler) = self.tcp().protocols.writing.get() {
+            let priority = self.priority(&message);
             let senders = handler.senders.read().clone();
-            for (addr, message_sender) in senders {
+            for (addr, lanes) in senders {
                 let (msg, _delivery) = WrappedMessage::new(Box::new(message.clone()));
-                let _ = message_sender.try_send(msg).map_err(|e| {
-                    error!(parent: self.tcp().span(), "can't send a message to {}: {}", addr, e);
-                    self.tcp().stats().register_failure();
-                });
+                match lanes.sender(priority).try_send(msg) {
+                    Ok(()) => {}
+                    // low-priority messages are dropped when their queue is full
+                    Err(mpsc::error::TrySendError::Full(_)) if priority == Priority::Low => {
+                        debug!(parent: self.tcp().span(), "dropped a low-priority message to {}: queue full", addr);
+                        self.tcp().stats().register_dropped_message();
+                    }
+                    Err(e) => {
+                        error!(parent: self.tcp().span(), "can't send a message to {}: {}", addr, e);
+                        self.tcp().stats().register_failure();
+                    }
+                }
             }
 
             Ok(())
@@ -192,10 +245,10 @@ impl<W: Writing> WritingInternal for W {
         let writer = conn.writer.take().expect("missing connection writer!");
         let mut framed = FramedWrite::new(writer, codec);
 
-        let (outbound_message_sender, mut outbound_message_receiver) = mpsc::channel(Self::MESSAGE_QUEUE_DEPTH);
+        let (lanes, mut receivers) = Lanes::new(Self::MESSAGE_QUEUE_DEPTH, Self::LOW_PRIORITY_QUEUE_DEPTH);
 
-        // register the connection's message sender with the Writing protocol handler
-        conn_senders.write().insert(addr, outbound_message_sender);
+        // register the connection's message senders with the Writing protocol handler
+        conn_senders.write().insert(addr, lanes);
 
         // this will automatically drop the sender upon a disconnect
         let auto_cleanup = SenderCleanup { addr, senders: Arc::clone(conn_senders) };
@@ -213,7 +266,8 @@ impl<W: Writing> WritingInternal for W {
             // move the cleanup into the task that gets aborted on disconnect
             let _auto_cleanup = auto_cleanup;
 
-            while let Some(wrapped_msg) = outbound_message_receiver.recv().await {
+            // drain the higher-priority lanes first, without starving the lower-priority ones
+            while let Some((_, wrapped_msg)) = receivers.recv().await {
                 let msg = wrapped_msg.msg.downcast().unwrap();
 
                 match self_clone.write_to_stream(*msg, &mut framed).await {
@@ -247,6 +301,100 @@ impl<W: Writing> WritingInternal for W {
     }
 }
 
+/// The per-connection senders of the outbound message queues, one for each priority lane.
+#[derive(Clone)]
+struct Lanes {
+    high: mpsc::Sender<WrappedMessage>,
+    normal: mpsc::Sender<WrappedMessage>,
+    low: mpsc::Sender<WrappedMessage>,
+}
+
+impl Lanes {
+    /// Creates the lanes of a connection, with the given depths of the high and normal, and the low priority lanes.
+    fn new(depth: usize, low_priority_depth: usize) -> (Self, LaneReceivers) {
+        let (high, high_receiver) = mpsc::channel(depth);
+        let (normal, normal_receiver) = mpsc::channel(depth);
+        let (low, low_receiver) = mpsc::channel(low_priority_depth);
+
+        let receivers = LaneReceivers {
+            high: high_receiver,
+            normal: normal_receiver,
+            low: low_receiver,
+            high_streak: 0,
+            streak: 0,
+        };
+        (Self { high, normal, low }, receivers)
+    }
+
+    /// Returns the sender for the lane of the given priority.
+    fn sender(&self, priority: Priority) -> &mpsc::Sender<WrappedMessage> {
+        match priority {
+            Priority::High => &self.high,
+            Priority::Normal => &self.normal,
+            Priority::Low => &self.low,
+        }
+    }
+}
+
+/// The per-connection receivers of the outbound message queues, one for each priority lane.
+struct LaneReceivers {
+    high: mpsc::Receiver<WrappedMessage>,
+    normal: mpsc::Receiver<WrappedMessage>,
+    low: mpsc::Receiver<WrappedMessage>,
+    /// The number of consecutive messages sent from the high priority lane.
+    high_streak: usize,
+    /// The number of consecutive messages sent from the high and normal priority lanes.
+    streak: usize,
+}
+
+impl LaneReceivers {
+    /// Returns the next message to send, and the lane it was queued in, or `None` if all the lanes are closed.
+    ///
+    /// The message is taken from the highest-priority lane that is not empty, unless a lower-priority lane was
+    /// passed over for `MAX_CONSECUTIVE_PRIORITY_MESSAGES` messages, in which case it is taken from that lane.
+    async fn recv(&mut self) -> Option<(Priority, WrappedMessage)> {
+        // send a waiting low-priority message after a streak of higher-priority ones
+        if self.streak >= MAX_CONSECUTIVE_PRIORITY_MESSAGES {
+            self.streak = 0;
+            if let Ok(msg) = self.low.try_recv() {
+                self.high_streak = 0;
+                return Some((Priority::Low, msg));
+            }
+        }
+        // send a waiting normal-priority message after a streak of high-priority ones
+        if self.high_streak >= MAX_CONSECUTIVE_PRIORITY_MESSAGES {
+            self.high_streak = 0;
+            if let Ok(msg) = self.normal.try_recv() {
+                self.streak += 1;
+                return Some((Priority::Normal, msg));
+            }
+        }
+
+        let (priority, msg) = tokio::select! {
+            biased;
+            Some(msg) = self.high.recv() => (Priority::High, msg),
+            Some(msg) = self.normal.recv() => (Priority::Normal, msg),
+            Some(msg) = self.low.recv() => (Priority::Low, msg),
+            else => return None,
+        };
+        match priority {
+            Priority::High => {
+                self.high_streak += 1;
+                self.streak += 1;
+            }
+            Priority::Normal => {
+                self.high_streak = 0;
+                self.streak += 1;
+            }
+            Priority::Low => {
+                self.high_streak = 0;
+                self.streak = 0;
+            }
+        }
+        Some((priority, msg))
+    }
+}
+
 /// Used to queue messages for delivery.
 struct WrappedMessage {
     msg: Box<dyn Any + Send>,
@@ -284,3 +432,103 @@ impl Drop for SenderCleanup {
         self.senders.write().remove(&self.addr);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Queues a message with the given ID in the lane of the given priority.
+    fn queue(lanes: &Lanes, priority: Priority, id: usize) {
+        let (msg, _delivery) = WrappedMessage::new(Box::new(id));
+        assert!(lanes.sender(priority).try_send(msg).is_ok());
+    }
+
+    // Returns the lane and the ID of the next message to send.
+    async fn next(receivers: &mut LaneReceivers) -> (Priority, usize) {
+        let (priority, msg) = receivers.recv().await.unwrap();
+        (priority, *msg.msg.downcast::<usize>().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_priority_ordering() {
+        let (lanes, mut receivers) = Lanes::new(8, 8);
+
+        // Queue the messages regardless of their priority.
+        queue(&lanes, Priority::Low, 0);
+        queue(&lanes, Priority::Normal, 1);
+        queue(&lanes, Priority::High, 2);
+        queue(&lanes, Priority::Low, 3);
+        queue(&lanes, Priority::Normal, 4);
+        queue(&lanes, Priority::High, 5);
+
+        // Ensure the higher-priority lanes are drained first, and each lane is drained in order.
+        assert_eq!(next(&mut receivers).await, (Priority::High, 2));
+        assert_eq!(next(&mut receivers).await, (Priority::High, 5));
+        assert_eq!(next(&mut receivers).await, (Priority::Normal, 1));
+        assert_eq!(next(&mut receivers).await, (Priority::Normal, 4));
+        assert_eq!(next(&mut receivers).await, (Priority::Low, 0));
+        assert_eq!(next(&mut receivers).await, (Priority::Low, 3));
+
+        // Ensure a message queued later in a higher-priority lane is still sent first.
+        queue(&lanes, Priority::Low, 6);
+        queue(&lanes, Priority::High, 7);
+        assert_eq!(next(&mut receivers).await, (Priority::High, 7));
+        assert_eq!(next(&mut receivers).await, (Priority::Low, 6));
+
+        // Ensure there are no more messages once the lanes are closed.
+        drop(lanes);
+        assert!(receivers.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_normal_priority_lane_is_not_starved() {
+        let (lanes, mut receivers) = Lanes::new(4 * MAX_CONSECUTIVE_PRIORITY_MESSAGES, 1);
+
+        // Queue a normal-priority message behind a saturated high-priority lane.
+        queue(&lanes, Priority::Normal, 0);
+        for id in 1..=4 * MAX_CONSECUTIVE_PRIORITY_MESSAGES {
+            queue(&lanes, Priority::High, id);
+        }
+
+        // Ensure the normal-priority message is sent right after the maximum streak of high-priority messages.
+        for id in 1..=MAX_CONSECUTIVE_PRIORITY_MESSAGES {
+            assert_eq!(next(&mut receivers).await, (Priority::High, id));
+        }
+        assert_eq!(next(&mut receivers).await, (Priority::Normal, 0));
+        // Ensure the high-priority lane takes precedence again afterwards.
+        assert_eq!(next(&mut receivers).await, (Priority::High, MAX_CONSECUTIVE_PRIORITY_MESSAGES + 1));
+    }
+
+    #[tokio::test]
+    async fn test_low_priority_lane_is_not_starved() {
+        let (lanes, mut receivers) = Lanes::new(4 * MAX_CONSECUTIVE_PRIORITY_MESSAGES, 1);
+        let half = MAX_CONSECUTIVE_PRIORITY_MESSAGES / 2;
+
+        // Queue a low-priority message behind the saturated high and normal priority lanes.
+        queue(&lanes, Priority::Low, 0);
+        for id in 1..=MAX_CONSECUTIVE_PRIORITY_MESSAGES {
+            queue(&lanes, Priority::Normal, id);
+        }
+        for id in 1..=half {
+            queue(&lanes, Priority::High, 100 + id);
+        }
+
+        // Ensure the low-priority message is sent right after the maximum streak of higher-priority messages.
+        for id in 1..=half {
+            assert_eq!(next(&mut receivers).await, (Priority::High, 100 + id));
+        }
+        for id in 1..=MAX_CONSECUTIVE_PRIORITY_MESSAGES - half {
+            assert_eq!(next(&mut receivers).await, (Priority::Normal, id));
+        }
+        assert_eq!(next(&mut receivers).await, (Priority::Low, 0));
+        // Ensure the remaining messages are sent in order of priority.
+        for id in MAX_CONSECUTIVE_PRIORITY_MESSAGES - half + 1..=MAX_CONSECUTIVE_PRIORITY_MESSAGES {
+            assert_eq!(next(&mut receivers).await, (Priority::Normal, id));
+        }
+
+        // Ensure a full low-priority lane rejects further messages, which are dropped by the writer.
+        queue(&lanes, Priority::Low, 1);
+        let (msg, _delivery) = WrappedMessage::new(Box::new(2usize));
+        assert!(matches!(lanes.sender(Priority::Low).try_send(msg), Err(mpsc::error::TrySendError::Full(_))));
+    }
+}
@@ -14,49 +14,36 @@
 
 use crate::common::test_peer::sample_genesis_block;
 use snarkos_account::Account;
-use snarkos_node::{Client, Prover, Validator};
+use snarkos_node::{Client, NodeConfig, Prover, Validator};
 use snarkvm::prelude::{store::helpers::memory::ConsensusMemory, Testnet3 as CurrentNetwork};
 
 use std::str::FromStr;
 
+/// Returns the account of the test nodes.
+fn sample_account() -> Account<CurrentNetwork> {
+    Account::<CurrentNetwork>::from_str("APrivateKey1zkp2oVPTci9kKcUprnbzMwq95Di1MQERpYBhEeqvkrDirK1").unwrap()
+}
+
+/// Returns the configuration of the test nodes, which listen on an ephemeral port.
+fn sample_config() -> NodeConfig<CurrentNetwork> {
+    NodeConfig::new("127.0.0.1:0".parse().unwrap())
+}
+
 pub async fn client() -> Client<CurrentNetwork, ConsensusMemory<CurrentNetwork>> {
-    Client::new(
-        "127.0.0.1:0".parse().unwrap(),
-        None,
-        Account::<CurrentNetwork>::from_str("APrivateKey1zkp2oVPTci9kKcUprnbzMwq95Di1MQERpYBhEeqvkrDirK1").unwrap(),
-        &[],
-        sample_genesis_block(),
-        None, // No CDN.
-        None,
-    )
-    .await
-    .expect("couldn't create client instance")
+    Client::new(sample_account(), sample_genesis_block(), sample_config())
+        .await
+        .expect("couldn't create client instance")
 }
 
 pub async fn prover() -> Prover<CurrentNetwork, ConsensusMemory<CurrentNetwork>> {
-    Prover::new(
-        "127.0.0.1:0".parse().unwrap(),
-        Account::<CurrentNetwork>::from_str("APrivateKey1zkp2oVPTci9kKcUprnbzMwq95Di1MQERpYBhEeqvkrDirK1").unwrap(),
-        &[],
-        sample_genesis_block(),
-        None,
-    )
-    .await
-    .expect("couldn't create prover instance")
+    Prover::new(sample_account(), sample_genesis_block(), sample_config())
+        .await
+        .expect("couldn't create prover instance")
 }
 
 pub async fn validator() -> Validator<CurrentNetwork, ConsensusMemory<CurrentNetwork>> {
-    Validator::new(
-        "127.0.0.1:0".parse().unwrap(),
-        None,
-        None,
-        Account::<CurrentNetwork>::from_str("APrivateKey1zkp2oVPTci9kKcUprnbzMwq95Di1MQERpYBhEeqvkrDirK1").unwrap(),
-        &[],
-        &[],
-        sample_genesis_block(), // Should load the current network's genesis block.
-        None,                   // No CDN.
-        None,
-    )
-    .await
-    .expect("couldn't create validator instance")
+    // Should load the current network's genesis block.
+    Validator::new(sample_account(), sample_genesis_block(), sample_config())
+        .await
+        .expect("couldn't create validator instance")
 }